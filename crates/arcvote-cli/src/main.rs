@@ -0,0 +1,3419 @@
+//! `arcvote` — run ArcVote elections from the command line.
+
+mod signer;
+
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use arcvote_client::{
+    accounts::{
+        decode_allowlist, decode_ballot, decode_category, decode_compliance_roll, decode_council_chamber,
+        decode_credit_account, decode_credential_issuance, decode_credential_registry, decode_dependency,
+        decode_history_gate, decode_mxe_public_key, decode_nullifier_set, decode_option_details, decode_proposal,
+        decode_proposal_translations, decode_sanction, decode_spend_histogram, decode_trustee_set, decode_voter_credits,
+        decode_write_in_tally, CredentialRegistry, GatingMode, ProposalState,
+    },
+    credential::{verify_credential, CredentialRequest, RegistrarKey},
+    export::{archive_message, fetch_archive, ArchiveSignature},
+    encryption::{
+        decrypt_identity, encrypt_ballot_choices, encrypt_credits, encrypt_identity, encrypt_jury_scores, encrypt_vote,
+        encrypt_write_in, write_in_hash, EncryptionKeypair, JuryScores, VoteAllocation, ENCRYPTION_KEY_MESSAGE,
+    },
+    instructions::{
+        self, BallotKind, ChamberRule, CreatePollParams, CreateProposalParams, DependencyCondition, OptionDetail,
+        ProposalKind, QuorumKind, SanctionAction, SlashReason, WeightCurve,
+    },
+    pda,
+    rpc::{
+        dry_run_vote, fetch_config, fetch_matching_pool, fetch_mxe_lookup_table, fetch_proposal, fetch_reward_pool,
+        fetch_sealed_identities, fetch_verified_commitment, fetch_verified_results, fetch_voter_records,
+    },
+    transaction::UnsignedTransaction,
+    validation::{effective_budget, validate_jury_scores, validate_vote_with_credits},
+    ArciumEnv, Instruction, Pubkey,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signer};
+
+#[derive(Parser)]
+#[command(name = "arcvote", version, about = "Private quadratic voting on Arcium")]
+struct Cli {
+    /// Solana JSON-RPC endpoint.
+    #[arg(long, short = 'u', env = "ARCVOTE_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair file, or `usb://ledger[?key=<path>]` for a Ledger.
+    #[arg(long, short = 'k', env = "ARCVOTE_KEYPAIR", default_value_t = default_keypair_path())]
+    keypair: String,
+    /// Offset of the Arcium cluster the MXE is assigned to.
+    #[arg(long, env = "ARCIUM_CLUSTER_OFFSET", default_value_t = 0)]
+    cluster_offset: u32,
+    /// Print the unsigned transaction message (base64) and its required
+    /// signers instead of signing and sending it.
+    #[arg(long, global = true)]
+    unsigned: bool,
+    /// Follow ballots cast by `vote`, `score` and `write-in` with an SPL
+    /// Memo "Voted on proposal #<id>", for custodial and accounting
+    /// systems.  Off by default: explorers then show the transaction as a
+    /// vote, and on which proposal.
+    #[arg(long, global = true, env = "ARCVOTE_MEMO")]
+    memo: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a proposal; the signer becomes its authority.
+    CreateProposal {
+        #[arg(long)]
+        id: u32,
+        #[arg(long)]
+        title: String,
+        /// Option label, repeat up to 4 times.
+        #[arg(long = "option", required_unless_present = "registration")]
+        options: Vec<String>,
+        /// Seconds from now during which candidates register as the
+        /// options, instead of passing `--option`; voting follows.
+        #[arg(long, conflicts_with = "options")]
+        registration: Option<i64>,
+        /// Voting period in seconds from now.
+        #[arg(long, conflicts_with = "deadline", required_unless_present = "deadline")]
+        duration: Option<i64>,
+        /// Voting deadline as a unix timestamp.
+        #[arg(long)]
+        deadline: Option<i64>,
+        #[arg(long, default_value_t = 100)]
+        voice_credits: u64,
+        /// Voter count, or basis points with a percentage `--quorum-kind`.
+        #[arg(long, default_value_t = 1)]
+        quorum: u32,
+        #[arg(long, value_enum, default_value_t = QuorumArg::Voters)]
+        quorum_kind: QuorumArg,
+        /// Registered voter count, for `--quorum-kind registered`.
+        #[arg(long, required_if_eq("quorum_kind", "registered"))]
+        electorate: Option<u64>,
+        /// Token whose supply is snapshotted, for `--quorum-kind supply`.
+        #[arg(long, required_if_eq("quorum_kind", "supply"))]
+        quorum_mint: Option<Pubkey>,
+        /// Let only holders of this token vote.
+        #[arg(long, requires = "gate_threshold")]
+        gate_mint: Option<Pubkey>,
+        /// Smallest `--gate-mint` balance, in base units, that may vote.
+        #[arg(long, requires = "gate_mint")]
+        gate_threshold: Option<u64>,
+        /// Proposal authority when it is not the signer, e.g. a multisig
+        /// vault; it must co-sign, so combine with `--unsigned`.
+        #[arg(long)]
+        authority: Option<Pubkey>,
+        /// Create a non-binding poll: no quorum or deposit, and anyone may
+        /// reveal it after the deadline.
+        #[arg(
+            long,
+            conflicts_with_all = ["registration", "quorum", "quorum_kind", "electorate", "quorum_mint", "gate_mint"]
+        )]
+        poll: bool,
+        /// Score each option -2..+2 (Likert) instead of spending quadratic
+        /// voice credits; the reveal reports net sentiment per option.
+        #[arg(long)]
+        likert: bool,
+        /// Ask For/Against/Abstain (give those three `--option`s, in that
+        /// order); Against above this many basis points of turnout vetoes
+        /// the proposal, and 0 disables the veto.
+        #[arg(long, conflicts_with_all = ["likert", "poll", "registration"])]
+        veto: Option<u16>,
+        /// Run a quadratic funding round with a pairwise coordination
+        /// discount of this strength: pairs of voters backing the same
+        /// options get less matching, and 0 disables the discount.  Holds
+        /// at most 8 ballots.
+        #[arg(long, conflicts_with_all = ["likert", "veto", "poll"])]
+        pairwise: Option<u32>,
+        /// Weight quadratic ballots by when they are cast: START,END
+        /// percentages at the opening and at the deadline, linear in
+        /// between, e.g. `200,100` to count early votes double.
+        #[arg(long, value_delimiter = ',', num_args = 2, conflicts_with_all = ["likert", "veto", "pairwise"])]
+        time_weight: Vec<u16>,
+        /// Review applications (the options) by a seated jury: CRITERIA,MAX
+        /// for up to 3 criteria each scored 0..=MAX, e.g. `3,10`.  Only the
+        /// summed score per application is revealed.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            num_args = 2,
+            conflicts_with_all = ["likert", "veto", "pairwise", "time_weight", "poll"]
+        )]
+        jury: Vec<u8>,
+        /// Shortlist by approval: each voter approves up to this many
+        /// options with one vote each.
+        #[arg(long, conflicts_with_all = ["likert", "veto", "pairwise", "time_weight", "jury"])]
+        choose: Option<u8>,
+        /// Participatory budgeting: BUDGET,COST... with one cost per
+        /// option, e.g. `100,60,30,40`.  Voters approve any options and the
+        /// reveal funds the most-approved set that fits the budget.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            num_args = 2..=5,
+            conflicts_with_all = ["likert", "veto", "pairwise", "time_weight", "jury", "choose", "poll", "registration"]
+        )]
+        budget_box: Vec<u64>,
+        /// Ranked-choice: voters rank the options and the reveal runs an
+        /// instant runoff.  Open the ranking tally with `arcvote
+        /// open-rankings` before the first vote.
+        #[arg(
+            long,
+            conflicts_with_all = ["likert", "veto", "pairwise", "time_weight", "jury", "choose", "budget_box", "poll"]
+        )]
+        ranked: bool,
+        /// Borda count: voters rank the options, each place scores one
+        /// point more than the next, and the most points win.
+        #[arg(
+            long,
+            conflicts_with_all = ["likert", "veto", "pairwise", "time_weight", "jury", "choose", "budget_box", "ranked"]
+        )]
+        borda: bool,
+        /// Condorcet: voters rank the options and the option winning the
+        /// most head-to-head contests wins.  Open the preference matrix
+        /// with `arcvote open-preferences` before the first vote.
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "likert", "veto", "pairwise", "time_weight", "jury", "choose", "budget_box", "poll", "ranked", "borda"
+            ]
+        )]
+        condorcet: bool,
+        /// STAR: voters score each option 0 to 5, and the reveal runs off
+        /// the two highest-scoring options head to head.  Open the
+        /// preference matrix with `arcvote open-preferences` before the
+        /// first vote.
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "likert", "veto", "pairwise", "time_weight", "jury", "choose", "budget_box", "poll", "ranked", "borda",
+                "condorcet"
+            ]
+        )]
+        star: bool,
+        /// Range voting: voters score each option 0 to 10, and the reveal
+        /// reports each option's average score.
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "likert", "veto", "pairwise", "time_weight", "jury", "choose", "budget_box", "ranked", "borda",
+                "condorcet", "star"
+            ]
+        )]
+        range: bool,
+        /// Key that shares the authority's right to extend the deadline and
+        /// queue the reveal; repeat up to 3 times.
+        #[arg(long = "co-author")]
+        co_authors: Vec<Pubkey>,
+        /// Key that may queue the reveal after the deadline and nothing
+        /// else, e.g. a keeper's.
+        #[arg(long)]
+        reveal_operator: Option<Pubkey>,
+    },
+    /// File a proposal under a registry category (authority only, before
+    /// the first vote).
+    AssignCategory {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        category: u16,
+    },
+    /// Group proposals sharing a deadline and registration deadline into
+    /// a ballot, such as the races of one election (authority only, before
+    /// their first vote).
+    CreateBallot {
+        /// Proposal authority; defaults to the signer.
+        #[arg(long)]
+        authority: Option<Pubkey>,
+        /// Ballot id, unique per authority.
+        #[arg(long)]
+        id: u32,
+        #[arg(long)]
+        title: String,
+        /// Ids of the proposals, e.g. `3,4,5`.
+        #[arg(long, value_delimiter = ',', required = true)]
+        proposals: Vec<u32>,
+    },
+    /// Record each voter's ballots on a ballot's proposals in one account
+    /// instead of one per proposal (authority only, before the first vote).
+    OpenRoundRecords {
+        /// Proposal authority; defaults to the signer.
+        #[arg(long)]
+        authority: Option<Pubkey>,
+        /// Ballot id.
+        #[arg(long)]
+        id: u32,
+    },
+    /// Mark each ballot on a quadratic proposal open to anyone with an
+    /// empty record instead of a full one (authority only, before the
+    /// first vote).
+    OpenDatalessRecords {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Budget each voter at the square root of their balance of a token
+    /// (authority only, before the first vote).
+    OpenSqrtCredits {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Register your square-root voice credits from your token balance,
+    /// which stays escrowed until the deadline.
+    RegisterCredits {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Take back the tokens your square-root credits escrowed, once the
+    /// deadline has passed.
+    ReleaseCredits {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Budget a voter at a number of voice credits; voters nobody funded
+    /// cannot vote (authority only, before the deadline).
+    FundCredits {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        voter: Pubkey,
+        #[arg(long)]
+        credits: u64,
+    },
+    /// Give a voter a budget nobody else can read, encrypted under the
+    /// authority's key; ballots then go through the MPC budget check
+    /// (authority only, once per voter, before the deadline).
+    FundEncryptedBudget {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        voter: Pubkey,
+        #[arg(long)]
+        credits: u64,
+    },
+    /// Make every ballot burn one of the voter's tokens (authority only,
+    /// before the first vote).
+    OpenBurnToVote {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Have every ballot ask an external program whether, and with what
+    /// weight, the voter may vote (authority only, before the first vote).
+    SetEligibilityOracle {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Program implementing ArcVote's `can_vote` interface.
+        #[arg(long)]
+        oracle: Pubkey,
+    },
+    /// Weigh every ballot by the voter's whole tokens of a mint, up to
+    /// `--max-weight` (authority only, before the first vote).
+    OpenTokenWeights {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Whole tokens a ballot counts with at most.
+        #[arg(long)]
+        max_weight: u64,
+    },
+    /// Bend the eligibility oracle's weights, or the token balances, so
+    /// large holders don't carry the vote (authority only, before the
+    /// first vote).
+    SetWeightCurve {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long, value_enum)]
+        curve: CurveArg,
+        /// Largest weight a ballot counts with, for `--curve cap`.
+        #[arg(long, required_if_eq("curve", "cap"))]
+        cap: Option<u64>,
+    },
+    /// Stand as a candidate while a proposal's registration is open.
+    RegisterCandidate {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Option label for the candidate, up to 32 bytes.
+        #[arg(long)]
+        name: String,
+    },
+    /// Reclaim your candidacy deposit once the proposal is settled.
+    CloseCandidate {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Open voting on this proposal only once another proposal picks a
+    /// given option (authority only, before the first vote).
+    RequireParentOutcome {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Address of the parent proposal.
+        #[arg(long)]
+        parent: Pubkey,
+        /// Option index the parent must reveal as its winner.
+        #[arg(long)]
+        winner: u8,
+    },
+    /// Open an amendment for voting once its parent has picked the required
+    /// option.
+    ConfirmParent {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Open voting on this proposal only if a For/Against/Abstain proposal
+    /// passes, or only if it fails (authority only, before the first vote).
+    DeclareDependency {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Address of the parent proposal.
+        #[arg(long)]
+        parent: Pubkey,
+        /// How the parent must be decided.
+        #[arg(long = "if", value_enum)]
+        condition: ConditionArg,
+    },
+    /// Say what an option pays or points to, for execution adapters
+    /// (authority only, before the first vote).
+    SetOptionDetails {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        option: u8,
+        /// Account the option pays or acts on.
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        /// Lamports or token base units the option moves.
+        #[arg(long, default_value_t = 0)]
+        amount: u64,
+        /// Off-chain description of the option.
+        #[arg(long, default_value = "")]
+        uri: String,
+    },
+    /// Add or replace the title and option labels in another language
+    /// (authority only, at any time).
+    Translate {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// BCP 47 tag, such as `pt-BR`.
+        #[arg(long)]
+        locale: String,
+        #[arg(long)]
+        title: String,
+        /// Label of each option in order; repeat for each.
+        #[arg(long = "option")]
+        options: Vec<String>,
+    },
+    /// Drop a translation (authority only).
+    RemoveTranslation {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        locale: String,
+    },
+    /// Open a chained proposal for voting once its parent was decided as
+    /// required.
+    ResolveDependency {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Have a For/Against/Abstain proposal vote on slashing another
+    /// proposal's deposit or removing a creator from the registry
+    /// (authority only, before the first vote).
+    DeclareSanction {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Address of the proposal whose deposit to slash.
+        #[arg(long, required_unless_present = "remove_creator", conflicts_with = "remove_creator")]
+        slash: Option<Pubkey>,
+        /// Why the deposit is slashed.
+        #[arg(long, value_enum, default_value_t = ReasonArg::Other, requires = "slash")]
+        reason: ReasonArg,
+        /// Creator to strip of its whitelisted and allowed flags.
+        #[arg(long)]
+        remove_creator: Option<Pubkey>,
+    },
+    /// Carry out the sanction a proposal passed.
+    ExecuteSanction {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Seat a juror on a jury proposal's panel (authority only).
+    SeatJuror {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        juror: Pubkey,
+    },
+    /// Reclaim a juror seat's rent once the proposal is settled (authority
+    /// only).
+    CloseJuror {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        juror: Pubkey,
+    },
+    /// Score a jury proposal's applications as a seated juror.
+    Score {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// One criterion's score per application, e.g. `8,5,9`; repeat
+        /// once per criterion, in order.
+        #[arg(long = "criterion", required = true)]
+        criteria: Vec<String>,
+    },
+    /// Cast an encrypted quadratic vote, or a Likert ballot with `--scores`.
+    Vote {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Effective votes per option, e.g. `7,3,1,0`.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            num_args = 1..=4,
+            required_unless_present_any = ["scores", "choice", "approve", "rank"]
+        )]
+        votes: Vec<u64>,
+        /// Likert score per option from -2 to 2, e.g. `2,-1,0`, STAR score
+        /// from 0 to 5, or range score from 0 to 10.
+        #[arg(long, value_delimiter = ',', num_args = 1..=4, allow_negative_numbers = true, conflicts_with = "votes")]
+        scores: Vec<i8>,
+        /// For/Against/Abstain proposals: the one choice.
+        #[arg(long, value_enum, conflicts_with_all = ["votes", "scores"])]
+        choice: Option<ChoiceArg>,
+        /// Approval proposals: the option indices approved, e.g. `0,2`.
+        #[arg(long, value_delimiter = ',', num_args = 1..=4, conflicts_with_all = ["votes", "scores", "choice"])]
+        approve: Vec<usize>,
+        /// Ranked-choice, Borda and Condorcet proposals: the options best
+        /// first, e.g. `2,0,1`; any left out follow in index order.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            num_args = 1..=4,
+            conflicts_with_all = ["votes", "scores", "choice", "approve"]
+        )]
+        rank: Vec<usize>,
+        /// Credential-gated proposals: the credential file presented from
+        /// this wallet with `arcvote present-credential` — or, with a
+        /// nullifier set, spent by this ballot from any fee-paying wallet.
+        #[arg(long)]
+        credential: Option<PathBuf>,
+        /// Quadratic proposals: cast this wallet's ballot, signing as the
+        /// session key it authorized with `arcvote open-session`.
+        #[arg(long, conflicts_with = "dry_run")]
+        session_for: Option<Pubkey>,
+        /// Proposals with an eligibility oracle: the accounts its `can_vote`
+        /// reads, e.g. the voter's membership record.
+        #[arg(long, value_delimiter = ',')]
+        oracle_accounts: Vec<Pubkey>,
+        /// NFT-gated proposals: the mint of this wallet's NFT of the
+        /// proposal's collection.
+        #[arg(long)]
+        nft: Option<Pubkey>,
+        /// Proposals with a commit phase: commit to this ballot now, then
+        /// cast the same ballot without `--commit` once the phase ends.
+        #[arg(long, conflicts_with_all = ["session_for", "dry_run"])]
+        commit: bool,
+        /// Validate and simulate the vote without sending it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Cast For/Against/Abstain choices on two proposals of one ballot in a
+    /// single transaction and MPC computation.
+    VoteBallot {
+        /// Proposal authority; defaults to the signer.
+        #[arg(long)]
+        authority: Option<Pubkey>,
+        /// Ids of the proposals, one to eight, e.g. `3,4`.
+        #[arg(long, value_delimiter = ',', num_args = 1..=instructions::MAX_BALLOT_PROPOSALS, required = true)]
+        ids: Vec<u32>,
+        /// The choice on each, in `--ids` order, e.g. `for,against`.
+        #[arg(long, value_enum, value_delimiter = ',', num_args = 1..=instructions::MAX_BALLOT_PROPOSALS, required = true)]
+        choices: Vec<ChoiceArg>,
+    },
+    /// Let a session key vote for this wallet on one proposal until it
+    /// expires, e.g. a throwaway browser key.
+    OpenSession {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        session_key: Pubkey,
+        /// Seconds until the session key stops working.
+        #[arg(long, default_value_t = 3600)]
+        expires_in: i64,
+    },
+    /// Kill a wallet's session, as the wallet or the session key.
+    CloseSession {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// The wallet that opened the session; defaults to the signer.
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    /// Hold ballots cast in the last `--window` seconds until voting closes
+    /// (authority only, before the first vote).
+    OpenMixWindow {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        window: i64,
+    },
+    /// Take only ballot commitments until `--commit-deadline`, and after it
+    /// only the ballots committed to (authority only, before the first
+    /// vote).
+    OpenCommitPhase {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// End of the commit phase as a unix timestamp.
+        #[arg(long)]
+        commit_deadline: i64,
+    },
+    /// Release every held ballot to the tally in a shuffled order, once
+    /// voting has closed.
+    ReleaseHeld {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Accept write-in candidates on a quadratic proposal (authority only).
+    OpenWriteIns {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Write a candidate in instead of voting the listed options.
+    WriteIn {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Candidate identifier; case and surrounding spaces are ignored.
+        #[arg(long)]
+        name: String,
+    },
+    /// Reveal the top write-in of a finalized proposal (authority only).
+    RevealWriteIn {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Collect a histogram of how many credits ballots spend on each option
+    /// (authority only, before the first vote).
+    OpenSpendHistogram {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Reveal the noised spend histogram of a finalized proposal (authority
+    /// only).
+    RevealHistogram {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Let voters carry unspent credits to your next banked proposal
+    /// (authority only, before the first vote).
+    OpenCreditBank {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Create your credit bank with a banked proposal's authority; one bank
+    /// serves all of the authority's proposals.
+    CreateCreditBank {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Set up the encrypted ranking tally of a ranked-choice proposal
+    /// (authority only, before the first vote).
+    OpenRankings {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Set up the encrypted preference matrix of a Condorcet or STAR
+    /// proposal (authority only, before the first vote).
+    OpenPreferences {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Give a For/Against/Abstain proposal a council that votes alongside
+    /// the community with its own encrypted tally (authority only, before
+    /// the first vote).
+    OpenCouncil {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Council member, repeat up to 16 times.
+        #[arg(long = "member", required = true)]
+        members: Vec<Pubkey>,
+        /// How the two chambers' decisions combine.
+        #[arg(long, value_enum, default_value_t = ChamberRuleArg::Both)]
+        rule: ChamberRuleArg,
+        /// Basis points of weight on the council, for `--rule weighted`.
+        #[arg(long, required_if_eq("rule", "weighted"))]
+        council_weight: Option<u16>,
+    },
+    /// Require a larger majority the lower a For/Against/Abstain
+    /// proposal's turnout (authority only, before the first vote).
+    SetTurnoutBias {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Basis points over a simple majority For needs when nobody in
+        /// the electorate votes, 1 to 5,000.
+        #[arg(long)]
+        bias_bps: u16,
+    },
+    /// Bring a For/Against/Abstain proposal's deadline forward for a
+    /// security-critical decision, under the config's emergency
+    /// supermajority (authority and council, before the first vote).
+    FastTrack {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// New voting deadline as a unix timestamp.
+        #[arg(long)]
+        deadline: i64,
+    },
+    /// Cast your ballot as a member of the proposal's council.
+    CouncilVote {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long, value_enum)]
+        choice: ChoiceArg,
+    },
+    /// Print the BLS public key of a registrar secret, creating a fresh
+    /// secret file if none exists.
+    RegistrarKey {
+        #[arg(long)]
+        secret: PathBuf,
+    },
+    /// Gate a quadratic proposal behind registrar-issued credentials
+    /// (authority only, before the first vote).
+    OpenCredentials {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        registrar: Pubkey,
+        /// The registrar's BLS public key (base64), from `arcvote registrar-key`.
+        #[arg(long)]
+        registrar_key: String,
+    },
+    /// Start a credential: write a secret serial and blinding factor to
+    /// `--out` and print the blinded message for the registrar.
+    RequestCredential {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Sign a registered voter's blinded message (registrar only).
+    IssueCredential {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        voter: Pubkey,
+        /// The voter's blinded message (base64).
+        #[arg(long)]
+        blinded: String,
+        /// The registrar's secret file.
+        #[arg(long)]
+        secret: PathBuf,
+    },
+    /// Unblind an issued credential and present it from this wallet, which
+    /// should be unconnected to the registered one.
+    PresentCredential {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        credential: PathBuf,
+    },
+    /// Revoke a credential by its serial (registrar only).
+    RevokeCredential {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// The credential's serial (base64).
+        #[arg(long)]
+        serial: String,
+    },
+    /// Gate a quadratic proposal behind voting history (authority only,
+    /// before the first vote).
+    OpenHistoryGate {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Counted ballots a wallet must have cast on other proposals.
+        #[arg(long, default_value_t = 0)]
+        min_prior_ballots: u32,
+        /// Seconds since a wallet's first transaction; needs `--attestor`.
+        #[arg(long, default_value_t = 0)]
+        min_wallet_age: i64,
+        /// Who vouches for wallet history with `arcvote attest-history`.
+        #[arg(long)]
+        attestor: Option<Pubkey>,
+    },
+    /// Vouch for a wallet's history on a history-gated proposal (attestor
+    /// only).
+    AttestHistory {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        voter: Pubkey,
+        /// Unix time of the wallet's first transaction.
+        #[arg(long)]
+        first_seen: i64,
+        #[arg(long)]
+        prior_ballots: u32,
+    },
+    /// Qualify this wallet for a history-gated proposal with its counted
+    /// ballots on other proposals.
+    ProveHistory {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Gate a proposal behind a list of up to `--capacity` wallets kept
+    /// with `arcvote add-voter` (authority only, before the first vote).
+    OpenAllowlist {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        capacity: u16,
+    },
+    /// List wallets on an allowlisted proposal (authority only).
+    AddVoter {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Wallets to list, e.g. `<A>,<B>`.
+        #[arg(long, value_delimiter = ',', required = true)]
+        voters: Vec<Pubkey>,
+    },
+    /// Strike wallets off an allowlisted proposal's list (authority only).
+    RemoveVoter {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long, value_delimiter = ',', required = true)]
+        voters: Vec<Pubkey>,
+    },
+    /// Gate a proposal behind NFTs of a Metaplex-verified `--collection`,
+    /// one ballot per NFT (authority only, before the first vote).
+    OpenCollectionGate {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        collection: Pubkey,
+    },
+    /// Let credentials vote anonymously, spent into one nullifier set for
+    /// up to `--max-voters` instead of presented (authority only, before the
+    /// first credential is used).  Grows the set to full size unless
+    /// `--unsigned`.
+    OpenNullifierSet {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        max_voters: u32,
+    },
+    /// Grow a nullifier set the rest of the way to full size; anyone may pay.
+    GrowNullifierSet {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Print the x25519 public key of a compliance secret, creating a fresh
+    /// secret file if none exists.
+    ComplianceKey {
+        #[arg(long)]
+        secret: PathBuf,
+    },
+    /// Make anonymous ballots seal their voter's registered wallet, for
+    /// release to the compliance key once the proposal is decided
+    /// (authority only, after `open-nullifier-set`, before the first vote).
+    OpenComplianceRoll {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// The compliance key (base64), from `arcvote compliance-key`.
+        #[arg(long)]
+        compliance_key: String,
+    },
+    /// Re-encrypt every sealed identity to the compliance key (authority
+    /// only, once the proposal is finalized or failed quorum).
+    ReleaseIdentities {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// List the registered wallets that voted anonymously, decrypting the
+    /// released identities with the compliance secret.
+    ComplianceList {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        secret: PathBuf,
+    },
+    /// Ask the cluster whether the encrypted vote total has reached
+    /// `--threshold`, revealing only yes or no (authority only, while
+    /// voting; the first check fixes the threshold).
+    CheckProgress {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        threshold: u64,
+    },
+    /// Show a proposal's state, turnout and quorum progress.
+    Status {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Show the title and options in this locale where translated.
+        #[arg(long)]
+        locale: Option<String>,
+    },
+    /// Queue the reveal of final results (authority or co-author only,
+    /// except for polls and auto-reveal proposals).
+    Reveal {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Move the voting deadline later (authority or co-author, before the
+    /// first vote).
+    ExtendDeadline {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// New deadline as a unix timestamp.
+        #[arg(long)]
+        deadline: i64,
+    },
+    /// Call off a reveal that was queued in error, before its callback
+    /// lands (authority only).
+    AbortReveal {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Require `--threshold` of the `--trustee` keys to approve before any
+    /// reveal, and let them force it `--grace` seconds after the deadline
+    /// (authority only, before the first vote).
+    AppointTrustees {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long = "trustee", required = true)]
+        trustees: Vec<Pubkey>,
+        #[arg(long)]
+        threshold: u8,
+        #[arg(long)]
+        grace: i64,
+    },
+    /// Approve the reveal as one of the proposal's trustees.
+    ApproveReveal {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Queue the reveal as a trustee once the authority's grace period has
+    /// passed.
+    ForceReveal {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Let anyone reveal the proposal once voting closes, with a computation
+    /// offset fixed in advance for automation services (authority only,
+    /// before the deadline).
+    EnableAutoReveal {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Check revealed results against the MPC cluster's signature, and
+    /// the tally they came from against its latest checkpoint, if any.
+    Verify {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Archive a finalized proposal — accounts, verified results,
+    /// attestation and event history — to `--out`, signed by the keypair
+    /// into `<out>.sig`.
+    Export {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Check an archive from `export` against its signature.
+    VerifyExport {
+        archive: PathBuf,
+        /// Signature file; defaults to `<archive>.sig`.
+        #[arg(long)]
+        signature: Option<PathBuf>,
+    },
+    /// Checkpoint a proposal's encrypted tally for auditors (anyone).
+    CommitTally {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Escrow SPL tokens as a participation reward, split evenly between
+    /// counted voters (authority only, before the first vote).
+    FundRewards {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Amount in the mint's base units.
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Pay voters' Arcium fees for one of your proposals (lamports).
+    Sponsor {
+        #[arg(long)]
+        id: u32,
+        #[arg(long)]
+        lamports: u64,
+    },
+    /// Fix reward shares once a proposal is settled (anyone may call).
+    SettleRewards {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Claim your participation reward.
+    ClaimReward {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Escrow SPL tokens to match a quadratic funding round, split between
+    /// options by their revealed funding (authority only, before the first
+    /// vote).
+    FundMatching {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Amount in the mint's base units.
+        #[arg(long)]
+        amount: u64,
+        /// Wallet paid each option's match, one per option in order.
+        #[arg(long = "recipient", required = true)]
+        recipients: Vec<Pubkey>,
+    },
+    /// Fix each option's match once a funding round is settled (anyone may
+    /// call).
+    SettleMatching {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Pay matched funds to the options' recipients (anyone may call).
+    PayMatch {
+        #[command(flatten)]
+        proposal: ProposalRef,
+        /// Only this option; every unpaid option by default.
+        #[arg(long)]
+        option: Option<u8>,
+    },
+    /// Claim your non-transferable "I voted" token for a finalized proposal.
+    ClaimReceipt {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+    /// Close a finalized proposal and reclaim its rent (authority only).
+    Close {
+        #[command(flatten)]
+        proposal: ProposalRef,
+    },
+}
+
+#[derive(Args)]
+struct ProposalRef {
+    /// Proposal authority; defaults to the signer.
+    #[arg(long)]
+    authority: Option<Pubkey>,
+    #[arg(long)]
+    id: u32,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum QuorumArg {
+    /// `--quorum` ballots.
+    Voters,
+    /// `--quorum` basis points of `--electorate` registered voters.
+    Registered,
+    /// `--quorum` basis points of `--quorum-mint`'s current supply.
+    Supply,
+    /// `--quorum` basis points of the voters the registrar issues
+    /// credentials to before the first ballot.
+    Registrar,
+}
+
+impl From<QuorumArg> for QuorumKind {
+    fn from(arg: QuorumArg) -> Self {
+        match arg {
+            QuorumArg::Voters => QuorumKind::Voters,
+            QuorumArg::Registered => QuorumKind::RegisteredBps,
+            QuorumArg::Supply => QuorumKind::SupplyBps,
+            QuorumArg::Registrar => QuorumKind::RegistrarBps,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Json,
+    /// One row per account field, then one per event.
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ChoiceArg {
+    For,
+    Against,
+    Abstain,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ChamberRuleArg {
+    /// Both chambers must pass the proposal.
+    Both,
+    /// Either chamber passing it is enough.
+    Either,
+    /// For's share, weighted `--council-weight` to the council, must be
+    /// over half.
+    Weighted,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ConditionArg {
+    Passes,
+    Fails,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReasonArg {
+    Spam,
+    Duplicate,
+    Abusive,
+    Misleading,
+    Other,
+}
+
+impl From<ReasonArg> for SlashReason {
+    fn from(arg: ReasonArg) -> Self {
+        match arg {
+            ReasonArg::Spam => SlashReason::Spam,
+            ReasonArg::Duplicate => SlashReason::Duplicate,
+            ReasonArg::Abusive => SlashReason::Abusive,
+            ReasonArg::Misleading => SlashReason::Misleading,
+            ReasonArg::Other => SlashReason::Other,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CurveArg {
+    /// The oracle's weight as is.
+    Linear,
+    /// The oracle's weight, up to `--cap`.
+    Cap,
+    /// The square root of the oracle's weight.
+    Sqrt,
+    /// One more per doubling of the oracle's weight.
+    Log,
+}
+
+impl From<ConditionArg> for DependencyCondition {
+    fn from(arg: ConditionArg) -> Self {
+        match arg {
+            ConditionArg::Passes => DependencyCondition::Passes,
+            ConditionArg::Fails => DependencyCondition::Fails,
+        }
+    }
+}
+
+fn default_keypair_path() -> String {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    format!("{home}/.config/solana/id.json")
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+    let env = ArciumEnv::new(cli.cluster_offset);
+
+    match cli.command {
+        Command::CreateProposal {
+            id,
+            title,
+            options,
+            registration,
+            duration,
+            deadline,
+            voice_credits,
+            quorum,
+            quorum_kind,
+            electorate,
+            quorum_mint,
+            gate_mint,
+            gate_threshold,
+            authority,
+            poll,
+            likert,
+            veto,
+            pairwise,
+            time_weight,
+            jury,
+            choose,
+            budget_box,
+            ranked,
+            borda,
+            condorcet,
+            star,
+            range,
+            co_authors,
+            reveal_operator,
+        } => {
+            if options.len() > 4 {
+                bail!("at most 4 options are supported, got {}", options.len());
+            }
+            let signer = signer::load_signer(&cli.keypair)?;
+            let deadline = match (deadline, duration) {
+                (Some(deadline), _) => deadline,
+                (None, Some(duration)) => unix_now() + duration,
+                (None, None) => unreachable!("clap requires one of --deadline/--duration"),
+            };
+            let registration_deadline = registration.map_or(0, |secs| unix_now() + secs);
+            let authority = cosigner(signer.as_ref(), authority, cli.unsigned)?;
+            let ballot = match (likert, veto, pairwise, &time_weight[..]) {
+                _ if !jury.is_empty() => BallotKind::Jury {
+                    criteria: jury[0],
+                    max_score: jury[1],
+                },
+                _ if !budget_box.is_empty() => {
+                    let mut costs = [0; 4];
+                    costs[..budget_box.len() - 1].copy_from_slice(&budget_box[1..]);
+                    BallotKind::BudgetBox {
+                        budget: budget_box[0],
+                        costs,
+                    }
+                }
+                _ if ranked => BallotKind::Ranked,
+                _ if borda => BallotKind::Borda,
+                _ if condorcet => BallotKind::Condorcet,
+                _ if star => BallotKind::Star,
+                _ if range => BallotKind::Range,
+                (true, ..) => BallotKind::Likert,
+                (false, Some(veto_bps), ..) => BallotKind::YesNoAbstain { veto_bps },
+                (false, None, Some(discount), _) => BallotKind::Pairwise { discount },
+                (false, None, None, &[start_pct, end_pct]) => BallotKind::TimeWeighted { start_pct, end_pct },
+                (false, None, None, _) => {
+                    choose.map_or(BallotKind::Quadratic, |max_choices| BallotKind::Approval { max_choices })
+                }
+            };
+            let computation_offset = rand::random();
+            let ix = if poll {
+                instructions::create_poll(
+                    &env,
+                    &signer.pubkey(),
+                    &authority,
+                    computation_offset,
+                    CreatePollParams {
+                        id,
+                        title,
+                        options,
+                        deadline,
+                        voice_credits,
+                        ballot,
+                        nonce: rand::random(),
+                    },
+                )
+            } else {
+                instructions::create_proposal(
+                    &env,
+                    &signer.pubkey(),
+                    &authority,
+                    computation_offset,
+                    CreateProposalParams {
+                        id,
+                        title,
+                        options,
+                        deadline,
+                        registration_deadline,
+                        voice_credits,
+                        quorum,
+                        quorum_kind: quorum_kind.into(),
+                        electorate: electorate.unwrap_or(0),
+                        quorum_mint,
+                        gate_mint,
+                        gate_threshold: gate_threshold.unwrap_or(0),
+                        ballot,
+                        nonce: rand::random(),
+                    },
+                )
+            };
+            let address = pda::proposal_pda(&authority, id).0;
+            println!("Proposal {address}");
+            let mut ixs = vec![ix];
+            if !co_authors.is_empty() {
+                ixs.push(instructions::set_co_authors(&authority, &address, co_authors));
+            }
+            if let Some(operator) = reveal_operator {
+                ixs.push(instructions::set_reveal_operator(&authority, &address, operator));
+            }
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Tally initialization queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::Vote {
+            proposal,
+            votes,
+            scores,
+            choice,
+            approve,
+            rank,
+            credential,
+            session_for,
+            oracle_accounts,
+            nft,
+            commit,
+            dry_run,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let voter = session_for.unwrap_or_else(|| signer.pubkey());
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let account = fetch_proposal(&rpc, &authority, proposal.id)?;
+            let (serial, anonymous) = match (account.gating, credential) {
+                (GatingMode::Credential, Some(path)) => {
+                    let address = pda::proposal_pda(&authority, proposal.id).0;
+                    let registry =
+                        decode_credential_registry(&rpc.get_account_data(&pda::credential_registry_pda(&address).0)?)?;
+                    if registry.anonymous {
+                        let (serial, signature, registered) = unblind_credential(&rpc, &path, &address, &registry)?;
+                        let identity = registry.compliance_roll.then_some(registered);
+                        (None, Some((serial, signature, identity)))
+                    } else {
+                        (Some(read_credential(&path, &address)?.0.serial()), None)
+                    }
+                }
+                (GatingMode::Credential, None) => bail!("this proposal is credential-gated; pass --credential"),
+                (GatingMode::History, _) => (Some(voter.to_bytes()), None),
+                (GatingMode::Allowlist, _) if session_for.is_some() => {
+                    bail!("session keys can't vote on an allowlisted proposal; vote from the wallet")
+                }
+                (GatingMode::Allowlist, _) => (None, None),
+                (GatingMode::Collection, _) if session_for.is_some() => {
+                    bail!("session keys can't vote with the wallet's NFT; vote from the wallet")
+                }
+                (GatingMode::Collection, _) if nft.is_none() => bail!("this proposal is NFT-gated; pass --nft"),
+                (GatingMode::Open | GatingMode::Token | GatingMode::Collection, _) => (None, None),
+            };
+            let allocation = match (account.ballot, choice) {
+                (BallotKind::Quadratic | BallotKind::Pairwise { .. } | BallotKind::TimeWeighted { .. }, None)
+                    if scores.is_empty() && approve.is_empty() && rank.is_empty() =>
+                {
+                    let mut padded = [0u64; 4];
+                    padded[..votes.len()].copy_from_slice(&votes);
+                    VoteAllocation::new(padded)
+                }
+                (BallotKind::Likert, None) if !scores.is_empty() => {
+                    VoteAllocation::likert(&scores).ok_or_else(|| anyhow!("scores must be from -2 to 2"))?
+                }
+                (BallotKind::Star, None) if !scores.is_empty() => scores
+                    .iter()
+                    .map(|s| u8::try_from(*s))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()
+                    .and_then(|scores| VoteAllocation::star(&scores))
+                    .ok_or_else(|| anyhow!("STAR scores must be from 0 to 5"))?,
+                (BallotKind::Range, None) if !scores.is_empty() => scores
+                    .iter()
+                    .map(|s| u8::try_from(*s))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()
+                    .and_then(|scores| VoteAllocation::range(&scores))
+                    .ok_or_else(|| anyhow!("range scores must be from 0 to 10"))?,
+                (BallotKind::YesNoAbstain { .. }, Some(choice)) => {
+                    VoteAllocation::choice(choice as usize).expect("choices are 0..=2")
+                }
+                (BallotKind::Approval { .. } | BallotKind::BudgetBox { .. }, None) if !approve.is_empty() => {
+                    VoteAllocation::approval(&approve).ok_or_else(|| anyhow!("approve distinct options from 0 to 3"))?
+                }
+                (BallotKind::Ranked | BallotKind::Borda | BallotKind::Condorcet, None) if !rank.is_empty() => {
+                    VoteAllocation::ranking(&rank).ok_or_else(|| anyhow!("rank distinct options from 0 to 3"))?
+                }
+                (BallotKind::Quadratic | BallotKind::Pairwise { .. } | BallotKind::TimeWeighted { .. }, _) => {
+                    bail!("this proposal takes --votes")
+                }
+                (BallotKind::Likert | BallotKind::Star | BallotKind::Range, _) => bail!("this proposal takes --scores"),
+                (BallotKind::YesNoAbstain { .. }, None) => bail!("this proposal takes --choice"),
+                (BallotKind::Jury { .. }, _) => bail!("jurors score this proposal with `arcvote score`"),
+                (BallotKind::Approval { .. } | BallotKind::BudgetBox { .. }, _) => {
+                    bail!("this proposal takes --approve")
+                }
+                (BallotKind::Ranked | BallotKind::Borda | BallotKind::Condorcet, _) => {
+                    bail!("this proposal takes --rank")
+                }
+            };
+            let budget = match account.credit_mint {
+                None if account.funded_credits => {
+                    let address = pda::credit_account_pda(&pda::proposal_pda(&authority, proposal.id).0, &voter).0;
+                    let data = rpc
+                        .get_account_data(&address)
+                        .context("the proposal authority has not funded your voice credits")?;
+                    decode_credit_account(&data)?.credits
+                }
+                Some(_) => {
+                    let scope = account
+                        .on_ballot
+                        .unwrap_or(pda::proposal_pda(&authority, proposal.id).0);
+                    let address = pda::voter_credits_pda(&scope, &voter).0;
+                    let data = rpc
+                        .get_account_data(&address)
+                        .context("no square-root credits registered; run `arcvote register-credits` first")?;
+                    decode_voter_credits(&data)?.credits
+                }
+                // The banked balance or encrypted budget is sealed; the
+                // circuit checks it.
+                None if account.credit_bank || account.encrypted_budgets => u64::MAX,
+                None => effective_budget(&account),
+            };
+            validate_vote_with_credits(&account, &allocation, unix_now(), budget)
+                .with_context(|| format!("ballot {:?} would be discarded", allocation.votes))?;
+
+            let mxe_data = rpc
+                .get_account_data(&pda::mxe_account())
+                .context("failed to fetch MXE account")?;
+            let mxe_public_key = decode_mxe_public_key(&mxe_data)?
+                .ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+            let signature = signer
+                .try_sign_message(ENCRYPTION_KEY_MESSAGE)
+                .context("failed to derive encryption key")?;
+            let keypair = EncryptionKeypair::from_signature(signature.as_ref());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let now = unix_now();
+            if account.commit_deadline == 0 {
+                if commit {
+                    bail!("proposal {address} has no commit phase");
+                }
+            } else if commit && now >= account.commit_deadline {
+                bail!("the commit phase ended at {}", account.commit_deadline);
+            } else if !commit && now < account.commit_deadline {
+                bail!("the commit phase runs until {}; commit with --commit first", account.commit_deadline);
+            }
+            // Both steps derive the nonce, so the ballot cast after the commit
+            // phase is the one committed to.
+            let nonce = match account.commit_deadline {
+                0 => rand::random(),
+                _ => keypair.commit_nonce(&address),
+            };
+            let vote = encrypt_vote(&keypair, &mxe_public_key, &allocation, nonce);
+            if commit {
+                let ix = instructions::commit_ballot(&signer.pubkey(), &address, &vote);
+                if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                    println!("Ballot committed; cast it after {}: {sig}", account.commit_deadline);
+                }
+                return Ok(());
+            }
+
+            // Supply quorums weigh, and token gates check, the voter's holding.
+            let token_account = match instructions::voter_token_mint(&account) {
+                Some(mint) => {
+                    let owner = rpc.get_account(&mint).context("failed to fetch quorum or gate mint")?.owner;
+                    Some(instructions::quorum_token_account(&voter, &mint, &owner))
+                }
+                None => None,
+            };
+            // Burn-to-vote proposals take a token from the voter's account.
+            let burn = match account.burn_mint {
+                Some(mint) => Some((mint, rpc.get_account(&mint).context("failed to fetch burn mint")?.owner)),
+                None => None,
+            };
+            // Token-weighted proposals read the voter's balance.
+            let weights = match account.weight_mint {
+                Some(mint) => Some((mint, rpc.get_account(&mint).context("failed to fetch weight mint")?.owner)),
+                None => None,
+            };
+
+            let computation_offset = rand::random();
+            let cast = match account.ballot {
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. } if account.spend_histogram => {
+                    instructions::cast_vote_histogram
+                }
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. } if account.credit_bank => {
+                    instructions::cast_vote_banked
+                }
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. } if account.encrypted_budgets => {
+                    instructions::cast_vote_budgeted
+                }
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. } if account.dataless_records => {
+                    instructions::cast_vote_dataless
+                }
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. } => instructions::cast_vote,
+                BallotKind::Likert => instructions::cast_likert,
+                BallotKind::YesNoAbstain { .. } => instructions::cast_choice,
+                BallotKind::Pairwise { .. } => instructions::cast_pairwise,
+                BallotKind::Approval { .. } | BallotKind::BudgetBox { .. } => instructions::cast_approval,
+                BallotKind::Ranked => instructions::cast_ranked_vote,
+                BallotKind::Borda => instructions::cast_borda,
+                BallotKind::Condorcet => instructions::cast_condorcet,
+                BallotKind::Star => instructions::cast_star,
+                BallotKind::Range => instructions::cast_range,
+                BallotKind::Jury { .. } => unreachable!("rejected above"),
+            };
+            let ix = match (session_for, serial, anonymous) {
+                (Some(_), _, Some(_)) => bail!("anonymous ballots are cast from a fresh wallet, not a session key"),
+                (None, _, Some((serial, signature, identity))) => {
+                    // Sealed under the ballot's key, for the compliance roll.
+                    let identity = identity.map(|wallet| encrypt_identity(&keypair, &mxe_public_key, &wallet, rand::random()));
+                    instructions::cast_vote_anonymous(
+                        &env,
+                        &signer.pubkey(),
+                        &authority,
+                        proposal.id,
+                        computation_offset,
+                        &vote,
+                        serial,
+                        signature,
+                        identity.as_ref(),
+                    )
+                }
+                (Some(wallet), serial, None) => {
+                    if !matches!(account.ballot, BallotKind::Quadratic | BallotKind::TimeWeighted { .. }) {
+                        bail!("session keys can only cast quadratic ballots");
+                    }
+                    if account.spend_histogram {
+                        bail!("session keys can't vote on a proposal with a spend histogram");
+                    }
+                    if account.credit_bank {
+                        bail!("session keys can't draw on the wallet's credit bank; vote from the wallet");
+                    }
+                    if account.encrypted_budgets {
+                        bail!("session keys can't draw on the wallet's encrypted budget; vote from the wallet");
+                    }
+                    if account.dataless_records {
+                        bail!("session keys can't leave the wallet's data-less record; vote from the wallet");
+                    }
+                    if account.commit_deadline > 0 {
+                        bail!("session keys can't vote on a proposal with a commit phase");
+                    }
+                    if burn.is_some() {
+                        bail!("session keys can't burn the wallet's token; vote from the wallet");
+                    }
+                    if account.eligibility_oracle.is_some() {
+                        bail!("the eligibility oracle only vouches for wallets; vote from the wallet");
+                    }
+                    if weights.is_some() {
+                        bail!("session keys can't show the wallet's token balance; vote from the wallet");
+                    }
+                    instructions::cast_vote_session(
+                        &env,
+                        &signer.pubkey(),
+                        &wallet,
+                        &authority,
+                        proposal.id,
+                        computation_offset,
+                        &vote,
+                        token_account.as_ref(),
+                        serial.as_ref(),
+                    )
+                }
+                (None, _, None) if burn.is_some() && account.eligibility_oracle.is_some() => {
+                    bail!("this proposal both burns a token and asks an eligibility oracle; the CLI casts neither")
+                }
+                (None, _, None) if weights.is_some() && (burn.is_some() || account.eligibility_oracle.is_some()) => {
+                    bail!("this proposal weighs balances and burns a token or asks an oracle; the CLI casts neither")
+                }
+                (None, _, None) if account.gating == GatingMode::Collection => {
+                    if burn.is_some() || weights.is_some() || account.eligibility_oracle.is_some() {
+                        bail!("this NFT-gated proposal also burns, weighs or asks an oracle; the CLI can't cast it");
+                    }
+                    let mint = nft.expect("checked above");
+                    let token_program = rpc.get_account(&mint).context("failed to fetch NFT mint")?.owner;
+                    instructions::cast_vote_with_nft(
+                        &env,
+                        &signer.pubkey(),
+                        &authority,
+                        proposal.id,
+                        computation_offset,
+                        &vote,
+                        &mint,
+                        &token_program,
+                    )
+                }
+                (None, serial, None) if weights.is_some() => {
+                    let (mint, token_program) = weights.expect("matched above");
+                    instructions::cast_vote_token_weighted(
+                        &env,
+                        &signer.pubkey(),
+                        &authority,
+                        proposal.id,
+                        computation_offset,
+                        &vote,
+                        token_account.as_ref(),
+                        serial.as_ref(),
+                        &mint,
+                        &token_program,
+                    )
+                }
+                (None, serial, None) if account.eligibility_oracle.is_some() => {
+                    let oracle = account.eligibility_oracle.expect("matched above");
+                    instructions::cast_vote_with_oracle(
+                        &env,
+                        &signer.pubkey(),
+                        &authority,
+                        proposal.id,
+                        computation_offset,
+                        &vote,
+                        token_account.as_ref(),
+                        serial.as_ref(),
+                        &oracle,
+                        &oracle_accounts,
+                    )
+                }
+                (None, serial, None) if burn.is_some() => {
+                    let (mint, token_program) = burn.expect("matched above");
+                    instructions::cast_vote_burning(
+                        &env,
+                        &signer.pubkey(),
+                        &authority,
+                        proposal.id,
+                        computation_offset,
+                        &vote,
+                        token_account.as_ref(),
+                        serial.as_ref(),
+                        &mint,
+                        &token_program,
+                    )
+                }
+                (None, Some(serial), None) => instructions::cast_vote_with_credential(
+                    &env,
+                    &signer.pubkey(),
+                    &authority,
+                    proposal.id,
+                    computation_offset,
+                    &vote,
+                    token_account.as_ref(),
+                    &serial,
+                ),
+                (None, None, None) if account.round_slot.is_some() => instructions::cast_round_choice(
+                    &env,
+                    &signer.pubkey(),
+                    &authority,
+                    proposal.id,
+                    &account.on_ballot.expect("round records are kept per ballot"),
+                    computation_offset,
+                    &vote,
+                    token_account.as_ref(),
+                ),
+                (None, None, None) => cast(
+                    &env,
+                    &signer.pubkey(),
+                    &authority,
+                    proposal.id,
+                    computation_offset,
+                    &vote,
+                    token_account.as_ref(),
+                ),
+            };
+            // Credits registered once for the whole ballot.
+            let ix = match account.on_ballot {
+                Some(ballot) if account.credit_mint.is_some() => instructions::with_ballot_credits(
+                    ix,
+                    &pda::proposal_pda(&authority, proposal.id).0,
+                    &ballot,
+                    &voter,
+                ),
+                _ => ix,
+            };
+            if dry_run {
+                let (cost, logs) =
+                    dry_run_vote(&rpc, &signer.pubkey(), &authority, proposal.id, &allocation, &ix)?;
+                for line in logs {
+                    println!("  {line}");
+                }
+                println!("Dry run OK: cost {cost}/{budget} voice credits");
+                return Ok(());
+            }
+            let ixs = with_memo(ix, proposal.id, cli.memo);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Vote queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::VoteBallot { authority, ids, choices } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = authority.unwrap_or_else(|| signer.pubkey());
+            if ids.len() != choices.len() {
+                bail!("{} proposals but {} choices; give one choice per proposal", ids.len(), choices.len());
+            }
+            let accounts = ids
+                .iter()
+                .map(|id| fetch_proposal(&rpc, &authority, *id))
+                .collect::<Result<Vec<_>, _>>()?;
+            let on_ballot = accounts[0].on_ballot;
+            let distinct = ids.iter().enumerate().all(|(i, id)| !ids[..i].contains(id));
+            if on_ballot.is_none() || !distinct || accounts.iter().any(|account| account.on_ballot != on_ballot) {
+                bail!("proposals {ids:?} are not distinct races of the same ballot");
+            }
+            for (i, account) in accounts.iter().enumerate() {
+                if !matches!(account.ballot, BallotKind::YesNoAbstain { .. }) {
+                    bail!("proposal {} is not For/Against/Abstain; vote on it with `arcvote vote`", ids[i]);
+                }
+                if instructions::voter_token_mint(account).is_some() {
+                    bail!("proposal {} reads token holdings; vote on it with `arcvote vote`", ids[i]);
+                }
+                if account.round_slot.is_some() {
+                    bail!("proposal {}'s ballot keeps round records; vote on it with `arcvote vote`", ids[i]);
+                }
+                let allocation = VoteAllocation::choice(choices[i] as usize).expect("choices are 0..=2");
+                validate_vote_with_credits(account, &allocation, unix_now(), effective_budget(account))
+                    .with_context(|| format!("ballot on proposal {} would be discarded", ids[i]))?;
+            }
+            let choices: Vec<u8> = choices.iter().map(|choice| *choice as u8).collect();
+
+            let mxe_data = rpc
+                .get_account_data(&pda::mxe_account())
+                .context("failed to fetch MXE account")?;
+            let mxe_public_key = decode_mxe_public_key(&mxe_data)?
+                .ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+            let signature = signer
+                .try_sign_message(ENCRYPTION_KEY_MESSAGE)
+                .context("failed to derive encryption key")?;
+            let keypair = EncryptionKeypair::from_signature(signature.as_ref());
+            let ballot = encrypt_ballot_choices(&keypair, &mxe_public_key, &choices, rand::random());
+
+            let computation_offset = rand::random();
+            let proposals: Vec<_> = ids.iter().map(|id| pda::proposal_pda(&authority, *id).0).collect();
+            let ix = instructions::cast_ballot_choices(&env, &signer.pubkey(), &proposals, computation_offset, &ballot);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Votes on {ids:?} queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::SeatJuror { proposal, juror } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::seat_juror(&signer.pubkey(), &authority, &address, &juror);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Seated juror {juror}: {sig}");
+            }
+        }
+        Command::CloseJuror { proposal, juror } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::close_juror(&authority, &address, &juror);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Closed juror seat {juror}: {sig}");
+            }
+        }
+        Command::Score { proposal, criteria } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let account = fetch_proposal(&rpc, &authority, proposal.id)?;
+            let rows = criteria
+                .iter()
+                .map(|row| row.split(',').map(|score| score.trim().parse()).collect())
+                .collect::<Result<Vec<Vec<u64>>, _>>()
+                .context("scores must be comma-separated whole numbers")?;
+            let scores = JuryScores::new(&rows)
+                .ok_or_else(|| anyhow!("at most 3 criteria of 4 applications are supported"))?;
+            validate_jury_scores(&account, &scores, unix_now())
+                .with_context(|| format!("scores {criteria:?} would be discarded"))?;
+
+            let mxe_data = rpc
+                .get_account_data(&pda::mxe_account())
+                .context("failed to fetch MXE account")?;
+            let mxe_public_key = decode_mxe_public_key(&mxe_data)?
+                .ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+            let signature = signer
+                .try_sign_message(ENCRYPTION_KEY_MESSAGE)
+                .context("failed to derive encryption key")?;
+            let keypair = EncryptionKeypair::from_signature(signature.as_ref());
+            let ballot = encrypt_jury_scores(&keypair, &mxe_public_key, &scores, rand::random());
+
+            let token_account = match instructions::voter_token_mint(&account) {
+                Some(mint) => {
+                    let owner = rpc.get_account(&mint).context("failed to fetch quorum or gate mint")?.owner;
+                    Some(instructions::quorum_token_account(&signer.pubkey(), &mint, &owner))
+                }
+                None => None,
+            };
+
+            let computation_offset = rand::random();
+            let ix = instructions::cast_jury_scores(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                &ballot,
+                token_account.as_ref(),
+            );
+            let ixs = with_memo(ix, proposal.id, cli.memo);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Scores queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::OpenWriteIns { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let computation_offset = rand::random();
+            let ix = instructions::open_write_ins(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                u128::from_le_bytes(rand::random()),
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Write-ins opening (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::WriteIn { proposal, name } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let account = fetch_proposal(&rpc, &authority, proposal.id)?;
+
+            let mxe_data = rpc
+                .get_account_data(&pda::mxe_account())
+                .context("failed to fetch MXE account")?;
+            let mxe_public_key = decode_mxe_public_key(&mxe_data)?
+                .ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+            let signature = signer
+                .try_sign_message(ENCRYPTION_KEY_MESSAGE)
+                .context("failed to derive encryption key")?;
+            let keypair = EncryptionKeypair::from_signature(signature.as_ref());
+            let write_in = encrypt_write_in(&keypair, &mxe_public_key, write_in_hash(&name), rand::random());
+
+            let token_account = match instructions::voter_token_mint(&account) {
+                Some(mint) => {
+                    let owner = rpc.get_account(&mint).context("failed to fetch quorum or gate mint")?.owner;
+                    Some(instructions::quorum_token_account(&signer.pubkey(), &mint, &owner))
+                }
+                None => None,
+            };
+
+            let computation_offset = rand::random();
+            let ix = instructions::cast_write_in(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                &write_in,
+                token_account.as_ref(),
+            );
+            let ixs = with_memo(ix, proposal.id, cli.memo);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Write-in queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::RevealWriteIn { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let computation_offset = rand::random();
+            let ix = instructions::reveal_write_in(&env, &signer.pubkey(), &authority, proposal.id, computation_offset);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Write-in reveal queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::OpenSpendHistogram { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let computation_offset = rand::random();
+            let ix = instructions::open_spend_histogram(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                u128::from_le_bytes(rand::random()),
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Spend histogram opening (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::RevealHistogram { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let computation_offset = rand::random();
+            let ix = instructions::reveal_spend_histogram(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Histogram reveal queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::OpenCreditBank { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_credit_bank(&authority, &address);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ballots draw on each voter's credit bank: {sig}");
+            }
+        }
+        Command::CreateCreditBank { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let computation_offset = rand::random();
+            let ix = instructions::create_credit_bank(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                u128::from_le_bytes(rand::random()),
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Credit bank opening (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::OpenRankings { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let computation_offset = rand::random();
+            let ix = instructions::open_rankings(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                u128::from_le_bytes(rand::random()),
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ranking tally opening (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::OpenPreferences { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let computation_offset = rand::random();
+            let ix = instructions::open_preferences(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                u128::from_le_bytes(rand::random()),
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Preference matrix opening (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::OpenCouncil {
+            proposal,
+            members,
+            rule,
+            council_weight,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let rule = match rule {
+                ChamberRuleArg::Both => ChamberRule::BothMustPass,
+                ChamberRuleArg::Either => ChamberRule::Either,
+                ChamberRuleArg::Weighted => ChamberRule::Weighted {
+                    council_bps: council_weight.expect("required by clap"),
+                },
+            };
+            let computation_offset = rand::random();
+            let ix = instructions::open_council(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                u128::from_le_bytes(rand::random()),
+                members,
+                rule,
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Council opening (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::SetTurnoutBias { proposal, bias_bps } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let ix = instructions::set_turnout_bias(&authority, proposal.id, bias_bps);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Outcome needs {bias_bps} bps over a majority at zero turnout: {sig}");
+            }
+        }
+        Command::FastTrack { proposal, deadline } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let council = fetch_config(&rpc)?.council;
+            if council != signer.pubkey() && !cli.unsigned {
+                bail!("council {council} must co-sign; rerun with --unsigned");
+            }
+            let ix = instructions::fast_track(&authority, &council, proposal.id, deadline);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Fast-tracked to close at {deadline}: {sig}");
+            }
+        }
+        Command::CouncilVote { proposal, choice } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let chamber = decode_council_chamber(
+                &rpc.get_account_data(&pda::council_chamber_pda(&address).0)
+                    .context("this proposal has no council")?,
+            )?;
+            if !chamber.members.contains(&signer.pubkey()) {
+                bail!("{} is not a member of this proposal's council", signer.pubkey());
+            }
+
+            let mxe_data = rpc
+                .get_account_data(&pda::mxe_account())
+                .context("failed to fetch MXE account")?;
+            let mxe_public_key = decode_mxe_public_key(&mxe_data)?
+                .ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+            let signature = signer
+                .try_sign_message(ENCRYPTION_KEY_MESSAGE)
+                .context("failed to derive encryption key")?;
+            let keypair = EncryptionKeypair::from_signature(signature.as_ref());
+            let allocation = VoteAllocation::choice(choice as usize).expect("choices are 0..=2");
+            let vote = encrypt_vote(&keypair, &mxe_public_key, &allocation, rand::random());
+
+            let computation_offset = rand::random();
+            let ix = instructions::cast_council_choice(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                &vote,
+            );
+            let ixs = with_memo(ix, proposal.id, cli.memo);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Council ballot queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::RegistrarKey { secret } => {
+            if !secret.exists() {
+                std::fs::write(&secret, BASE64.encode(rand::random::<[u8; 32]>()))
+                    .with_context(|| format!("failed to write {}", secret.display()))?;
+                println!("Wrote a new registrar secret to {}", secret.display());
+            }
+            println!("Registrar key: {}", BASE64.encode(read_registrar_key(&secret)?.public_key()));
+        }
+        Command::OpenCredentials {
+            proposal,
+            registrar,
+            registrar_key,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let registrar_key = decode_base64(&registrar_key, "registrar key")?;
+            let ix = instructions::open_credentials(&signer.pubkey(), &authority, &address, &registrar, registrar_key);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Credentials required, issued by {registrar}: {sig}");
+            }
+        }
+        Command::RequestCredential { proposal, out } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let (serial, blinding): ([u8; 32], [u8; 32]) = (rand::random(), rand::random());
+            let request = CredentialRequest::new(address, serial, blinding)
+                .ok_or_else(|| anyhow!("drew a zero blinding factor; try again"))?;
+            let file = [&serial[..], &blinding[..], signer.pubkey().as_ref()].concat();
+            std::fs::write(&out, BASE64.encode(file)).with_context(|| format!("failed to write {}", out.display()))?;
+            println!("Credential secret written to {}; keep it private", out.display());
+            println!("Wallet:    {}", signer.pubkey());
+            println!("Blinded:   {}", BASE64.encode(request.blinded_message()));
+            println!("Give both to the registrar, then present the credential from another wallet.");
+        }
+        Command::IssueCredential {
+            proposal,
+            voter,
+            blinded,
+            secret,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let blinded = decode_base64(&blinded, "blinded message")?;
+            let blind_signature = read_registrar_key(&secret)?
+                .sign_blinded(&blinded)
+                .ok_or_else(|| anyhow!("blinded message is not a curve point"))?;
+            let ix = instructions::issue_credential(&signer.pubkey(), &address, &voter, blinded, blind_signature);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Issued a credential to {voter}: {sig}");
+            }
+        }
+        Command::PresentCredential { proposal, credential } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal
+                .authority
+                .ok_or_else(|| anyhow!("pass --authority: the presenting wallet should not be the authority"))?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let registry = decode_credential_registry(
+                &rpc.get_account_data(&pda::credential_registry_pda(&address).0)
+                    .context("proposal is not credential-gated")?,
+            )?;
+            if registry.anonymous {
+                bail!("credentials on this proposal are spent by `arcvote vote --credential`, not presented");
+            }
+            let (serial, signature, _) = unblind_credential(&rpc, &credential, &address, &registry)?;
+            let ix = instructions::present_credential(&signer.pubkey(), &address, serial, signature);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Credential presented; {} may now vote: {sig}", signer.pubkey());
+            }
+        }
+        Command::OpenSession {
+            proposal,
+            session_key,
+            expires_in,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let expires_at = unix_now() + expires_in;
+            let ix = instructions::open_session(&signer.pubkey(), &address, &session_key, expires_at);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Session {session_key} may vote for {} until {expires_at}: {sig}", signer.pubkey());
+            }
+        }
+        Command::CloseSession { proposal, wallet } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let wallet = wallet.unwrap_or_else(|| signer.pubkey());
+            let ix = instructions::close_session(&signer.pubkey(), &address, &wallet);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Closed {wallet}'s session: {sig}");
+            }
+        }
+        Command::OpenMixWindow { proposal, window } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let ix = instructions::open_mix_window(&signer.pubkey(), &authority, proposal.id, window);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ballots cast in the last {window}s are held until voting closes: {sig}");
+            }
+        }
+        Command::OpenCommitPhase { proposal, commit_deadline } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let ix = instructions::open_commit_phase(&authority, proposal.id, commit_deadline);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ballots are committed until {commit_deadline} and cast after it: {sig}");
+            }
+        }
+        Command::ReleaseHeld { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let held = decode_proposal(&rpc.get_account_data(&address)?)?.mix_held;
+            if held == 0 {
+                bail!("proposal {address} holds no ballots");
+            }
+            for _ in 0..held {
+                let computation_offset = rand::random();
+                let ix = instructions::release_held_ballot(&env, &signer.pubkey(), &authority, proposal.id, computation_offset);
+                if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                    println!("Held ballot released (computation offset {computation_offset}): {sig}");
+                }
+            }
+        }
+        Command::RevokeCredential { proposal, serial } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::revoke_credential(&signer.pubkey(), &address, decode_base64(&serial, "serial")?);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Revoked credential {serial}: {sig}");
+            }
+        }
+        Command::OpenHistoryGate {
+            proposal,
+            min_prior_ballots,
+            min_wallet_age,
+            attestor,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_history_gate(
+                &signer.pubkey(),
+                &authority,
+                &address,
+                attestor,
+                min_wallet_age,
+                min_prior_ballots,
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Voters need {min_prior_ballots} prior ballots and a {min_wallet_age}s-old wallet: {sig}");
+            }
+        }
+        Command::OpenAllowlist { proposal, capacity } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_allowlist(&signer.pubkey(), &authority, &address, capacity);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Only listed wallets may vote, up to {capacity}: {sig}");
+            }
+        }
+        Command::AddVoter { proposal, voters } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ixs: Vec<_> = voters
+                .iter()
+                .map(|voter| instructions::add_voter(&authority, &address, voter))
+                .collect();
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Listed {} voters: {sig}", voters.len());
+            }
+        }
+        Command::RemoveVoter { proposal, voters } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ixs: Vec<_> = voters
+                .iter()
+                .map(|voter| instructions::remove_voter(&authority, &address, voter))
+                .collect();
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Removed {} voters: {sig}", voters.len());
+            }
+        }
+        Command::OpenCollectionGate { proposal, collection } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_collection_gate(&authority, &address, &collection);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Only NFTs of collection {collection} may vote, once each: {sig}");
+            }
+        }
+        Command::AttestHistory {
+            proposal,
+            voter,
+            first_seen,
+            prior_ballots,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::attest_history(&signer.pubkey(), &address, &voter, first_seen, prior_ballots);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Admitted {voter}: {sig}");
+            }
+        }
+        Command::ProveHistory { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let records: Vec<Pubkey> = fetch_voter_records(&rpc, &signer.pubkey())?
+                .into_iter()
+                .filter(|(_, record)| record.proposal != address)
+                .map(|(record, _)| record)
+                .collect();
+            let ix = instructions::prove_history(&signer.pubkey(), &address, &records);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Admitted on {} prior ballots: {sig}", records.len());
+            }
+        }
+        Command::OpenNullifierSet { proposal, max_voters } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_nullifier_set(&signer.pubkey(), &authority, &address, max_voters);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Credentials now vote anonymously, up to {max_voters}: {sig}");
+                grow_nullifier_set(&rpc, signer.as_ref(), &address)?;
+            } else {
+                println!("Then grow the set with `arcvote grow-nullifier-set`.");
+            }
+        }
+        Command::GrowNullifierSet { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            grow_nullifier_set(&rpc, signer.as_ref(), &pda::proposal_pda(&authority, proposal.id).0)?;
+        }
+        Command::ComplianceKey { secret } => {
+            if !secret.exists() {
+                std::fs::write(&secret, BASE64.encode(rand::random::<[u8; 32]>()))
+                    .with_context(|| format!("failed to write {}", secret.display()))?;
+                println!("Wrote a new compliance secret to {}", secret.display());
+            }
+            println!("Compliance key: {}", BASE64.encode(read_compliance_secret(&secret)?.public_key()));
+        }
+        Command::OpenComplianceRoll { proposal, compliance_key } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let compliance_key = decode_base64(&compliance_key, "compliance key")?;
+            let ix = instructions::open_compliance_roll(&signer.pubkey(), &authority, &address, compliance_key);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Anonymous ballots now seal their voter's identity: {sig}");
+            }
+        }
+        Command::ReleaseIdentities { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let sealed: Vec<Pubkey> = fetch_sealed_identities(&rpc, &address)?
+                .into_iter()
+                .filter(|(_, identity)| !identity.released)
+                .map(|(sealed, _)| sealed)
+                .collect();
+            if sealed.is_empty() {
+                bail!("proposal {address} has no identities left to release");
+            }
+            // One computation per identity.
+            for identity in sealed {
+                let computation_offset = rand::random();
+                let ix = instructions::release_identity(
+                    &env,
+                    &signer.pubkey(),
+                    &authority,
+                    proposal.id,
+                    computation_offset,
+                    &identity,
+                );
+                if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                    println!("Identity {identity} release queued (computation offset {computation_offset}): {sig}");
+                }
+            }
+        }
+        Command::ComplianceList { proposal, secret } => {
+            let authority = match proposal.authority {
+                Some(authority) => authority,
+                None => signer::load_signer(&cli.keypair)?.pubkey(),
+            };
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let compliance = read_compliance_secret(&secret)?;
+            let roll = decode_compliance_roll(
+                &rpc.get_account_data(&pda::compliance_roll_pda(&address).0)
+                    .context("proposal has no compliance roll")?,
+            )?;
+            if roll.compliance_key != compliance.public_key() {
+                bail!("{} is not the secret of this roll's compliance key", secret.display());
+            }
+            let mxe_public_key = decode_mxe_public_key(&rpc.get_account_data(&pda::mxe_account())?)?
+                .ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+            for (sealed, identity) in fetch_sealed_identities(&rpc, &address)? {
+                if !identity.released {
+                    println!("{sealed}: not released yet");
+                    continue;
+                }
+                match decrypt_identity(&compliance, &mxe_public_key, &identity.ciphertexts, identity.nonce) {
+                    Some(wallet) => println!("{wallet}"),
+                    None => println!("{sealed}: does not decrypt to a wallet"),
+                }
+            }
+            println!("{} of {} identities released", roll.released, roll.sealed);
+        }
+        Command::CheckProgress { proposal, threshold } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let computation_offset = rand::random();
+            let ix = instructions::check_quorum_progress(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                computation_offset,
+                threshold,
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Progress check queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::Status { proposal, locale } => {
+            let authority = match proposal.authority {
+                Some(authority) => authority,
+                None => signer::load_signer(&cli.keypair)?.pubkey(),
+            };
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let data = rpc
+                .get_account_data(&address)
+                .with_context(|| format!("failed to fetch proposal {address}"))?;
+            let account = decode_proposal(&data)?;
+            let now = rpc.get_block_time(rpc.get_slot()?)?;
+            let status = account.status(now);
+
+            let translation = match (&locale, rpc.get_account_data(&pda::translations_pda(&address).0)) {
+                (Some(locale), Ok(data)) => decode_proposal_translations(&data)?
+                    .translations
+                    .into_iter()
+                    .find(|t| &t.locale == locale),
+                _ => None,
+            };
+            println!("Proposal:  {address}");
+            match &translation {
+                Some(t) => println!("Title:     {} ({}: {})", t.title, t.locale, account.title),
+                None => println!("Title:     {}", account.title),
+            }
+            if account.kind == ProposalKind::Poll {
+                println!("Kind:      non-binding poll");
+            }
+            if let Some(category) = account.category {
+                let data = rpc.get_account_data(&pda::category_pda(category).0)?;
+                println!("Category:  {} (#{category})", decode_category(&data)?.name);
+            }
+            for co_author in &account.co_authors {
+                println!("Co-author: {co_author}");
+            }
+            if let Some(operator) = account.reveal_operator {
+                println!("Operator:  {operator} may queue the reveal");
+            }
+            let details = match rpc.get_account_data(&pda::option_details_pda(&address).0) {
+                Ok(data) => decode_option_details(&data)?.details,
+                Err(_) => Vec::new(),
+            };
+            for (i, option) in account.options.iter().enumerate() {
+                match translation.as_ref().and_then(|t| t.options.get(i)) {
+                    Some(label) => println!("  [{i}] {label} ({option})"),
+                    None => println!("  [{i}] {option}"),
+                }
+                let Some(detail) = details.get(i) else { continue };
+                if let Some(recipient) = detail.recipient {
+                    println!("      pays {} to {recipient}", detail.amount);
+                }
+                if !detail.metadata_uri.is_empty() {
+                    println!("      {}", detail.metadata_uri);
+                }
+            }
+            let state = match status.state {
+                ProposalState::Registration => "candidate registration",
+                ProposalState::Voting => "voting",
+                ProposalState::VotingClosed => "voting closed",
+                ProposalState::Finalized => "finalized",
+                ProposalState::QuorumFailed => "quorum failed",
+            };
+            println!("State:     {state}");
+            if let Some(required) = account.requires_parent_outcome {
+                let gate = if account.parent_outcome_met {
+                    "confirmed"
+                } else {
+                    "pending"
+                };
+                println!(
+                    "Amendment: opens once {} picks option {} ({gate})",
+                    required.parent, required.winner
+                );
+            }
+            if let Ok(data) = rpc.get_account_data(&pda::dependency_pda(&address).0) {
+                let dependency = decode_dependency(&data)?;
+                let condition = match dependency.condition {
+                    DependencyCondition::Passes => "passes",
+                    DependencyCondition::Fails => "fails",
+                };
+                let gate = if dependency.resolved { "resolved" } else { "pending" };
+                println!("Depends:   opens if {} {condition} ({gate})", dependency.parent);
+            }
+            if let Ok(data) = rpc.get_account_data(&pda::sanction_pda(&address).0) {
+                let sanction = decode_sanction(&data)?;
+                let action = match sanction.action {
+                    SanctionAction::SlashDeposit { proposal, reason } => {
+                        format!("slash {proposal}'s deposit ({reason:?})")
+                    }
+                    SanctionAction::RemoveCreator { creator } => format!("remove creator {creator}"),
+                };
+                let state = if sanction.executed { "executed" } else { "pending" };
+                println!("Sanction:  {action} if passed ({state})");
+            }
+            println!("Remaining: {}s", status.time_remaining);
+            if let Some(mint) = account.credit_mint {
+                println!("Credits:   square root of each voter's {mint} balance");
+            }
+            if let Some(mint) = account.burn_mint {
+                println!("Burns:     one {mint} token per ballot");
+            }
+            if let Some(oracle) = account.eligibility_oracle {
+                println!("Oracle:    {oracle} weighs each ballot");
+            }
+            if let Some(mint) = account.weight_mint {
+                println!("Weights:   each voter's whole {mint} tokens, up to {}", account.max_token_weight);
+            }
+            match account.weight_curve {
+                WeightCurve::Linear => {}
+                WeightCurve::Cap { max } => println!("Curve:     weights capped at {max}"),
+                WeightCurve::Sqrt => println!("Curve:     square root of weights"),
+                WeightCurve::Log => println!("Curve:     log of weights"),
+            }
+            if account.is_frozen() {
+                println!(
+                    "Frozen:    options, deadline and budget since slot {}",
+                    account.first_vote_slot
+                );
+            }
+            let unit = match status.quorum_kind {
+                QuorumKind::SupplyBps => "tokens",
+                QuorumKind::Voters | QuorumKind::RegisteredBps | QuorumKind::RegistrarBps => "voters",
+            };
+            println!(
+                "Turnout:   {}/{} {unit} ({}.{:02}% of quorum)",
+                status.turnout,
+                status.quorum_target,
+                status.quorum_progress_bps / 100,
+                status.quorum_progress_bps % 100
+            );
+            if account.quorum_kind == QuorumKind::RegistrarBps {
+                let fixed = if account.is_frozen() { "fixed" } else { "so far" };
+                println!("Electors:  {} registered with the registrar, {fixed}", account.electorate);
+            }
+            if account.progress_checked_at > 0 {
+                println!(
+                    "Progress:  {} {} votes as of {}",
+                    if account.vote_threshold_reached { "reached" } else { "short of" },
+                    account.vote_threshold,
+                    account.progress_checked_at
+                );
+            }
+            if account.supermajority_bps > 0 {
+                println!(
+                    "Emergency: fast-tracked, For needs over {} bps of For and Against",
+                    account.supermajority_bps
+                );
+            }
+            if account.turnout_bias_bps > 0 {
+                println!("Bias:      +{} bps to pass at zero turnout", account.turnout_bias_bps);
+            }
+            if let Some(slot) = account.round_slot {
+                println!("Round:     slot {slot} of each voter's round record on the ballot");
+            }
+            if account.dataless_records {
+                println!("Records:   data-less; each ballot leaves an empty voter record");
+            }
+            if let Some(outcome) = account.outcome {
+                println!("Outcome:   {outcome:?}");
+            }
+            if account.dual_chamber {
+                let chamber = decode_council_chamber(&rpc.get_account_data(&pda::council_chamber_pda(&address).0)?)?;
+                println!(
+                    "Council:   {} members, {} ballots, combined {:?}",
+                    chamber.members.len(),
+                    chamber.ballots,
+                    chamber.rule
+                );
+                if let (Some(community), Some(council)) = (chamber.community_outcome, chamber.council_outcome) {
+                    println!(
+                        "  community {community:?}; council {council:?} with {} for / {} against / {} abstain",
+                        chamber.results[0], chamber.results[1], chamber.results[2]
+                    );
+                }
+            }
+            if account.gating == GatingMode::Credential {
+                let registry = decode_credential_registry(
+                    &rpc.get_account_data(&pda::credential_registry_pda(&address).0)?,
+                )?;
+                println!(
+                    "Gating:    credentials from {}: {} issued, {} presented, {} revoked",
+                    registry.registrar, registry.issued, registry.presented, registry.revoked
+                );
+            }
+            if account.gating == GatingMode::History {
+                let gate = decode_history_gate(&rpc.get_account_data(&pda::history_gate_pda(&address).0)?)?;
+                println!(
+                    "Gating:    {} prior ballots, {}s wallet age: {} admitted",
+                    gate.min_prior_ballots, gate.min_wallet_age, gate.admitted
+                );
+            }
+            if account.gating == GatingMode::Allowlist {
+                let allowlist = decode_allowlist(&rpc.get_account_data(&pda::allowlist_pda(&address).0)?)?;
+                println!(
+                    "Gating:    allowlist, {} of {} slots filled",
+                    allowlist.voters.len(),
+                    allowlist.capacity
+                );
+            }
+            if account.gating == GatingMode::Token {
+                println!("Gating:    holders of {} or more of {}", account.gate_threshold, account.gate_mint);
+            }
+            if account.gating == GatingMode::Collection {
+                println!("Gating:    NFTs of collection {}, one ballot each", account.gate_mint);
+            }
+            if account.gating == GatingMode::Credential {
+                if let Ok(data) = rpc.get_account_data(&pda::nullifier_set_pda(&address).0) {
+                    let set = decode_nullifier_set(&data)?;
+                    let grows = instructions::nullifier_set_grows_left(&set, data.len());
+                    println!(
+                        "Anonymous: {} of {} credentials spent{}",
+                        set.count,
+                        set.max_voters,
+                        if grows > 0 { " (set still growing)" } else { "" }
+                    );
+                }
+            }
+            if let Ok(data) = rpc.get_account_data(&pda::compliance_roll_pda(&address).0) {
+                let roll = decode_compliance_roll(&data)?;
+                println!("Compliance: {} of {} identities released", roll.released, roll.sealed);
+            }
+            if let Ok(data) = rpc.get_account_data(&pda::trustee_set_pda(&address).0) {
+                let set = decode_trustee_set(&data)?;
+                println!(
+                    "Trustees:  {} of {} approvals from {} trustees; they may force the reveal {}s after the deadline",
+                    account.trustee_approvals,
+                    account.trustee_threshold,
+                    set.trustees.len(),
+                    set.grace_period
+                );
+            }
+            if account.auto_reveal {
+                println!(
+                    "Reveal:    automatic, next computation offset {}",
+                    pda::auto_reveal_offset(&address, account.auto_reveals)
+                );
+            }
+            if account.mix_window > 0 {
+                println!(
+                    "Mix:       last {}s of voting held, {} ballot(s) waiting",
+                    account.mix_window, account.mix_held
+                );
+            }
+            if account.commit_deadline > 0 {
+                println!("Commit:    ballots committed until {}, cast after it", account.commit_deadline);
+            }
+            if let BallotKind::BudgetBox { budget, costs } = account.ballot {
+                for (i, cost) in costs.iter().take(account.num_options as usize).enumerate() {
+                    println!("  [{i}] costs {cost} of {budget}");
+                }
+                if account.funded.is_some() {
+                    let (funded, spent) = account.funded_options();
+                    println!("Funded:    options {funded:?}, spending {spent} of {budget}");
+                }
+            }
+            if let (BallotKind::TimeWeighted { .. }, ProposalState::Voting) = (account.ballot, status.state) {
+                println!("Weight:    {}% for a ballot cast now", account.ballot_weight(now));
+            }
+            if account.ballot == BallotKind::Likert && account.is_finalized {
+                for (i, net) in account.net_sentiment().iter().take(account.num_options as usize).enumerate() {
+                    println!("  [{i}] net sentiment {net:+} over {} ballots", account.results[4]);
+                }
+            }
+            if let (BallotKind::Jury { .. }, true) = (account.ballot, account.is_finalized) {
+                for (rank, i) in account.jury_ranking().into_iter().enumerate() {
+                    println!(
+                        "  #{} [{i}] scored {} by {} jurors",
+                        rank + 1,
+                        account.results[i as usize],
+                        account.results[4]
+                    );
+                }
+            }
+            if let Ok(data) = rpc.get_account_data(&pda::write_in_tally_pda(&address).0) {
+                let write_ins = decode_write_in_tally(&data)?;
+                if write_ins.revealed {
+                    println!(
+                        "Write-in:  {:032x} with {} votes",
+                        write_ins.top_candidate, write_ins.top_votes
+                    );
+                } else {
+                    println!("Write-in:  accepted, top one revealed after finalization");
+                }
+            }
+            if let Ok(data) = rpc.get_account_data(&pda::spend_histogram_pda(&address).0) {
+                let histogram = decode_spend_histogram(&data)?;
+                if histogram.revealed {
+                    println!("Spend:     ballots spending 1-9 / 16-49 / 64-100 credits, noised");
+                    let options = histogram.counts.chunks(3).take(account.num_options as usize);
+                    for (i, counts) in options.enumerate() {
+                        println!("  [{i}] {} / {} / {}", counts[0], counts[1], counts[2]);
+                    }
+                } else {
+                    println!("Spend:     histogram collected, revealed after finalization");
+                }
+            }
+            if account.sponsor_balance > 0 || account.sponsored_votes > 0 {
+                println!(
+                    "Sponsored: {} votes, {} lamports left",
+                    account.sponsored_votes, account.sponsor_balance
+                );
+            }
+        }
+        Command::Reveal { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let poll_authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&poll_authority, proposal.id).0;
+            let account = fetch_proposal(&rpc, &poll_authority, proposal.id)?;
+            let computation_offset = if account.auto_reveal {
+                pda::auto_reveal_offset(&address, account.auto_reveals)
+            } else {
+                rand::random()
+            };
+            let ix = if account.auto_reveal {
+                // Permissionless; marks the proposal quorum-failed instead
+                // if it fell short.
+                instructions::auto_reveal(&env, &signer.pubkey(), &address, account.auto_reveals)
+            } else if account.kind == ProposalKind::Poll {
+                instructions::reveal_poll(&env, &signer.pubkey(), &address, computation_offset)
+            } else if account.co_authors.contains(&signer.pubkey()) {
+                instructions::co_author_reveal(&env, &signer.pubkey(), &signer.pubkey(), &address, computation_offset)
+            } else if account.reveal_operator == Some(signer.pubkey()) {
+                instructions::operator_reveal(&env, &signer.pubkey(), &signer.pubkey(), &address, computation_offset)
+            } else {
+                let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+                let reveal = match account.ballot {
+                    BallotKind::YesNoAbstain { .. } if account.dual_chamber => instructions::reveal_chambers,
+                    BallotKind::YesNoAbstain { .. } => instructions::reveal_outcome,
+                    BallotKind::Pairwise { .. } => instructions::reveal_pairwise,
+                    BallotKind::BudgetBox { .. } => instructions::reveal_budget_box,
+                    BallotKind::Ranked => instructions::reveal_ranked,
+                    BallotKind::Condorcet => instructions::reveal_condorcet,
+                    BallotKind::Star => instructions::reveal_star,
+                    BallotKind::Quadratic
+                    | BallotKind::Likert
+                    | BallotKind::TimeWeighted { .. }
+                    | BallotKind::Jury { .. }
+                    | BallotKind::Approval { .. }
+                    | BallotKind::Borda
+                    | BallotKind::Range => instructions::reveal_results,
+                };
+                reveal(&env, &signer.pubkey(), &authority, proposal.id, computation_offset)
+            };
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Reveal queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::ExtendDeadline { proposal, deadline } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let account = fetch_proposal(&rpc, &authority, proposal.id)?;
+            let ix = instructions::extend_deadline(&signer.pubkey(), &address, account.category, deadline);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Deadline moved to {deadline}: {sig}");
+            }
+        }
+        Command::AbortReveal { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::abort_reveal(&authority, &address);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Reveal aborted; its callback will be dropped: {sig}");
+            }
+        }
+        Command::AppointTrustees {
+            proposal,
+            trustees,
+            threshold,
+            grace,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let count = trustees.len();
+            let ix = instructions::appoint_trustees(&signer.pubkey(), &authority, &address, trustees, threshold, grace);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Reveal now needs {threshold} of {count} trustee approvals: {sig}");
+            }
+        }
+        Command::ApproveReveal { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::approve_reveal(&signer.pubkey(), &address);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Reveal approved: {sig}");
+            }
+        }
+        Command::ForceReveal { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let computation_offset = rand::random();
+            let ix = instructions::force_reveal(&env, &signer.pubkey(), &signer.pubkey(), &address, computation_offset);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Forced reveal queued (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::EnableAutoReveal { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::enable_auto_reveal(&authority, &address);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Anyone may now reveal at the deadline: {sig}");
+                println!("First auto-reveal computation offset: {}", pda::auto_reveal_offset(&address, 0));
+            }
+        }
+        Command::Verify { proposal } => {
+            let authority = match proposal.authority {
+                Some(authority) => authority,
+                None => signer::load_signer(&cli.keypair)?.pubkey(),
+            };
+            let results = fetch_verified_results(&rpc, &authority, proposal.id)?;
+            println!("Results verified against the cluster signature:");
+            for (i, votes) in results.options.iter().enumerate() {
+                println!("  option {i}: {votes}");
+            }
+            println!("  total:    {}", results.total_votes);
+            println!("  winner:   option {}", results.winner);
+            if let Some(outcome) = results.outcome {
+                println!("  outcome:  {outcome:?}");
+            }
+            if let Some(funded) = results.funded {
+                println!("  funded:   bitmask {funded:04b}");
+            }
+            if let Some(commitment) = fetch_verified_commitment(&rpc, &authority, proposal.id)? {
+                println!(
+                    "Tally matches the latest of {} checkpoints ({} ballots)",
+                    commitment.checkpoints, commitment.applied_ballots
+                );
+            }
+        }
+        Command::Export { proposal, format, out } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let archive = fetch_archive(&rpc, &address)?;
+            let rendered = match format {
+                ExportFormat::Json => archive.to_json(),
+                ExportFormat::Csv => archive.to_csv(),
+            };
+            let signature = signer
+                .try_sign_message(&archive_message(rendered.as_bytes()))
+                .context("failed to sign archive")?;
+            let signature = ArchiveSignature::new(rendered.as_bytes(), &signer.pubkey(), &signature);
+            std::fs::write(&out, &rendered).with_context(|| format!("failed to write {}", out.display()))?;
+            let signature_file = signature_path(&out);
+            std::fs::write(&signature_file, serde_json::to_string_pretty(&signature)? + "\n")
+                .with_context(|| format!("failed to write {}", signature_file.display()))?;
+            println!(
+                "Archived proposal {address} with {} events to {}, signed in {}",
+                archive.events.len(),
+                out.display(),
+                signature_file.display()
+            );
+        }
+        Command::VerifyExport { archive, signature } => {
+            let signature_file = signature.unwrap_or_else(|| signature_path(&archive));
+            let bytes = std::fs::read(&archive).with_context(|| format!("failed to read {}", archive.display()))?;
+            let signature: ArchiveSignature = serde_json::from_str(
+                &std::fs::read_to_string(&signature_file)
+                    .with_context(|| format!("failed to read {}", signature_file.display()))?,
+            )
+            .with_context(|| format!("{} is not an archive signature", signature_file.display()))?;
+            if !signature.verify(&bytes) {
+                bail!("{} is not the archive {} signed", archive.display(), signature.signer);
+            }
+            println!("Archive signed by {}", signature.signer);
+        }
+        Command::CommitTally { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::commit_tally(&signer.pubkey(), &address);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Tally checkpointed: {sig}");
+            }
+        }
+        Command::FundRewards { proposal, mint, amount } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let ix = instructions::create_reward_pool(&signer.pubkey(), &authority, proposal.id, &mint, amount);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Reward pool funded with {amount} of {mint}: {sig}");
+            }
+        }
+        Command::SettleRewards { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let pool = fetch_reward_pool(&rpc, &address)?;
+            let ix = instructions::settle_rewards(&signer.pubkey(), &address, &authority, &pool.mint);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Rewards settled: {sig}");
+            }
+        }
+        Command::ClaimReward { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let voter = signer.pubkey();
+            let authority = proposal.authority.unwrap_or(voter);
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let pool = fetch_reward_pool(&rpc, &address)?;
+            if !pool.settled {
+                bail!("rewards are not settled yet (run `arcvote settle-rewards`)");
+            }
+            let ixs = [
+                instructions::create_token_account(&voter, &voter, &pool.mint),
+                instructions::claim_reward(&voter, &address, &pool.mint),
+            ];
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Claimed {} of {}: {sig}", pool.share, pool.mint);
+            }
+        }
+        Command::FundMatching { proposal, mint, amount, recipients } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let ix = instructions::create_matching_pool(
+                &signer.pubkey(),
+                &authority,
+                proposal.id,
+                &mint,
+                amount,
+                recipients,
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Matching pool funded with {amount} of {mint}: {sig}");
+            }
+        }
+        Command::SettleMatching { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let pool = fetch_matching_pool(&rpc, &address)?;
+            let ix = instructions::settle_matching(&signer.pubkey(), &address, &authority, &pool.mint);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Matching settled: {sig}");
+            }
+        }
+        Command::PayMatch { proposal, option } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let payer = signer.pubkey();
+            let authority = proposal.authority.unwrap_or(payer);
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let pool = fetch_matching_pool(&rpc, &address)?;
+            if !pool.settled {
+                bail!("matching is not settled yet (run `arcvote settle-matching`)");
+            }
+            let options: Vec<u8> = match option {
+                Some(option) if option as usize >= pool.recipients.len() => bail!("no option {option}"),
+                Some(option) => vec![option],
+                None => (0..pool.recipients.len() as u8).filter(|i| pool.paid & (1 << i) == 0).collect(),
+            };
+            if options.is_empty() {
+                bail!("every option's match is already paid");
+            }
+            for option in options {
+                let recipient = pool.recipients[option as usize];
+                let ixs = [
+                    instructions::create_token_account(&payer, &recipient, &pool.mint),
+                    instructions::pay_match(&payer, &address, option, &recipient, &pool.mint),
+                ];
+                if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                    let amount = pool.matches[option as usize];
+                    println!("Paid {amount} of {} to {recipient} for option {option}: {sig}", pool.mint);
+                }
+            }
+        }
+        Command::Sponsor { id, lamports } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let ix = instructions::fund_sponsorship(&signer.pubkey(), id, lamports);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Sponsored {lamports} lamports of voting fees: {sig}");
+            }
+        }
+        Command::AssignCategory { proposal, category } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::assign_category(&authority, &address, category);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Filed under category {category}: {sig}");
+            }
+        }
+        Command::CreateBallot {
+            authority,
+            id,
+            title,
+            proposals,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), authority, cli.unsigned)?;
+            let addresses: Vec<_> = proposals
+                .iter()
+                .map(|proposal| pda::proposal_pda(&authority, *proposal).0)
+                .collect();
+            let ix = instructions::create_ballot(&signer.pubkey(), &authority, id, title, &addresses);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ballot {id} groups {} proposals: {sig}", proposals.len());
+            }
+        }
+        Command::OpenRoundRecords { authority, id } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), authority, cli.unsigned)?;
+            let address = pda::ballot_pda(&authority, id).0;
+            let data = rpc.get_account_data(&address).with_context(|| format!("ballot {id} not found"))?;
+            let ballot = decode_ballot(&data)?;
+            let ix = instructions::open_round_records(&authority, id, &ballot.proposals);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ballot {id} records each voter's {} races in one account: {sig}", ballot.proposals.len());
+            }
+        }
+        Command::OpenDatalessRecords { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_dataless_records(&authority, &address);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ballots on proposal {} leave data-less voter records: {sig}", proposal.id);
+            }
+        }
+        Command::OpenSqrtCredits { proposal, mint } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_sqrt_credits(&authority, &address, &mint);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Voters are budgeted at the square root of their {mint} balance: {sig}");
+            }
+        }
+        Command::RegisterCredits { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let account = fetch_proposal(&rpc, &authority, proposal.id)?;
+            let credit_mint = account
+                .credit_mint
+                .ok_or_else(|| anyhow!("proposal does not assign square-root credits"))?;
+            let mint = rpc.get_account(&credit_mint).context("failed to fetch credit mint")?;
+            let token_account = instructions::quorum_token_account(&signer.pubkey(), &credit_mint, &mint.owner);
+            let mut ix =
+                instructions::register_credits(&signer.pubkey(), &address, &token_account, &credit_mint, &mint.owner);
+            if let Some(ballot) = account.on_ballot {
+                ix = instructions::with_ballot_credits(ix, &address, &ballot, &signer.pubkey());
+            }
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                match account.on_ballot {
+                    Some(ballot) => println!("Credits registered for every proposal on ballot {ballot}: {sig}"),
+                    None => println!("Credits registered: {sig}"),
+                }
+            }
+        }
+        Command::ReleaseCredits { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            // Once the proposal is closed, only credits registered with it are found.
+            let scope = fetch_proposal(&rpc, &authority, proposal.id)
+                .ok()
+                .and_then(|account| account.on_ballot)
+                .unwrap_or(address);
+            let data = rpc
+                .get_account_data(&pda::voter_credits_pda(&scope, &signer.pubkey()).0)
+                .context("no square-root credits registered")?;
+            let credit_mint = decode_voter_credits(&data)?.credit_mint;
+            let mint = rpc.get_account(&credit_mint).context("failed to fetch credit mint")?;
+            let token_account = instructions::quorum_token_account(&signer.pubkey(), &credit_mint, &mint.owner);
+            let ix = instructions::release_credits(&signer.pubkey(), &scope, &token_account, &credit_mint, &mint.owner);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Escrowed credit tokens returned: {sig}");
+            }
+        }
+        Command::FundCredits { proposal, voter, credits } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::fund_credits(&signer.pubkey(), &authority, &address, &voter, credits);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("{voter} is budgeted at {credits} voice credits: {sig}");
+            }
+        }
+        Command::FundEncryptedBudget { proposal, voter, credits } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let mxe_data = rpc
+                .get_account_data(&pda::mxe_account())
+                .context("failed to fetch MXE account")?;
+            let mxe_public_key = decode_mxe_public_key(&mxe_data)?
+                .ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+            let signature = signer
+                .try_sign_message(ENCRYPTION_KEY_MESSAGE)
+                .context("failed to derive encryption key")?;
+            let keypair = EncryptionKeypair::from_signature(signature.as_ref());
+            let budget = encrypt_credits(&keypair, &mxe_public_key, credits, rand::random());
+            let computation_offset = rand::random();
+            let ix = instructions::fund_encrypted_budget(
+                &env,
+                &signer.pubkey(),
+                &authority,
+                &address,
+                computation_offset,
+                &voter,
+                &budget,
+                u128::from_le_bytes(rand::random()),
+            );
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("{voter}'s encrypted budget is being sealed (computation offset {computation_offset}): {sig}");
+            }
+        }
+        Command::OpenBurnToVote { proposal, mint } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_burn_to_vote(&authority, &address, &mint);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Each ballot burns one {mint} token: {sig}");
+            }
+        }
+        Command::SetEligibilityOracle { proposal, oracle } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::set_eligibility_oracle(&authority, &address, &oracle);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Ballots now ask {oracle} for their weight: {sig}");
+            }
+        }
+        Command::OpenTokenWeights { proposal, mint, max_weight } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::open_token_weights(&authority, &address, &mint, max_weight);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Each ballot now counts with the voter's {mint} balance, up to {max_weight} tokens: {sig}");
+            }
+        }
+        Command::SetWeightCurve { proposal, curve, cap } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let curve = match curve {
+                CurveArg::Linear => WeightCurve::Linear,
+                CurveArg::Cap => WeightCurve::Cap {
+                    max: cap.expect("required by clap"),
+                },
+                CurveArg::Sqrt => WeightCurve::Sqrt,
+                CurveArg::Log => WeightCurve::Log,
+            };
+            let ix = instructions::set_weight_curve(&authority, &address, curve);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Weights now count as {curve:?}: {sig}");
+            }
+        }
+        Command::RegisterCandidate { proposal, name } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::register_candidate(&signer.pubkey(), &address, name);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Registered as candidate: {sig}");
+            }
+        }
+        Command::CloseCandidate { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::close_candidate(&signer.pubkey(), &address);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Candidacy closed: {sig}");
+            }
+        }
+        Command::RequireParentOutcome {
+            proposal,
+            parent,
+            winner,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::require_parent_outcome(&authority, &address, &parent, winner);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Voting waits on {parent} picking option {winner}: {sig}");
+            }
+        }
+        Command::ConfirmParent { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let data = rpc
+                .get_account_data(&address)
+                .with_context(|| format!("failed to fetch proposal {address}"))?;
+            let Some(required) = decode_proposal(&data)?.requires_parent_outcome else {
+                bail!("proposal {address} does not wait on a parent outcome");
+            };
+            let ix = instructions::confirm_parent_outcome(&address, &required.parent);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Parent outcome confirmed: {sig}");
+            }
+        }
+        Command::DeclareDependency {
+            proposal,
+            parent,
+            condition,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix =
+                instructions::declare_dependency(&signer.pubkey(), &authority, &address, &parent, condition.into());
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Voting waits on {parent}: {sig}");
+            }
+        }
+        Command::SetOptionDetails {
+            proposal,
+            option,
+            recipient,
+            amount,
+            uri,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            // The instruction replaces every option's details, so keep the
+            // others as they are.
+            let mut details = match rpc.get_account_data(&pda::option_details_pda(&address).0) {
+                Ok(data) => decode_option_details(&data)?.details,
+                Err(_) => Vec::new(),
+            };
+            if details.len() <= option as usize {
+                details.resize(
+                    option as usize + 1,
+                    OptionDetail {
+                        recipient: None,
+                        amount: 0,
+                        metadata_uri: String::new(),
+                    },
+                );
+            }
+            details[option as usize] = OptionDetail {
+                recipient,
+                amount,
+                metadata_uri: uri,
+            };
+            let ix = instructions::set_option_details(&signer.pubkey(), &authority, &address, details);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Option {option} details set: {sig}");
+            }
+        }
+        Command::Translate {
+            proposal,
+            locale,
+            title,
+            options,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix =
+                instructions::set_translation(&signer.pubkey(), &authority, &address, locale.clone(), title, options);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Translated into {locale}: {sig}");
+            }
+        }
+        Command::RemoveTranslation { proposal, locale } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let ix = instructions::remove_translation(&authority, &address, locale.clone());
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Removed the {locale} translation: {sig}");
+            }
+        }
+        Command::ResolveDependency { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let data = rpc
+                .get_account_data(&pda::dependency_pda(&address).0)
+                .with_context(|| format!("proposal {address} has no dependency"))?;
+            let dependency = decode_dependency(&data)?;
+            let ix = instructions::resolve_dependency(&address, &dependency.parent);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Dependency resolved: {sig}");
+            }
+        }
+        Command::DeclareSanction {
+            proposal,
+            slash,
+            reason,
+            remove_creator,
+        } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let action = match (slash, remove_creator) {
+                (Some(target), _) => SanctionAction::SlashDeposit {
+                    proposal: target,
+                    reason: reason.into(),
+                },
+                (None, Some(creator)) => SanctionAction::RemoveCreator { creator },
+                (None, None) => bail!("pass --slash or --remove-creator"),
+            };
+            let ix = instructions::declare_sanction(&signer.pubkey(), &authority, &address, action);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Sanction declared: {sig}");
+            }
+        }
+        Command::ExecuteSanction { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = proposal.authority.unwrap_or_else(|| signer.pubkey());
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let data = rpc
+                .get_account_data(&pda::sanction_pda(&address).0)
+                .with_context(|| format!("proposal {address} declares no sanction"))?;
+            let sanction = decode_sanction(&data)?;
+            let treasury = fetch_config(&rpc)?.treasury;
+            let ix = instructions::execute_sanction(&address, &sanction.action, &treasury);
+            if let Some(sig) = send(&rpc, signer.as_ref(), &[ix], cli.unsigned)? {
+                println!("Sanction executed: {sig}");
+            }
+        }
+        Command::ClaimReceipt { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let voter = signer.pubkey();
+            let authority = proposal.authority.unwrap_or(voter);
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let mint = pda::receipt_mint_pda(&address).0;
+            let mut ixs = Vec::new();
+            // The first claimer creates the mint.
+            if rpc.get_account(&mint).is_err() {
+                ixs.push(instructions::create_receipt_mint(&voter, &address));
+            }
+            ixs.push(instructions::create_receipt_account(&voter, &voter, &address));
+            ixs.push(instructions::claim_receipt(&voter, &address));
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Receipt {mint} claimed: {sig}");
+            }
+        }
+        Command::Close { proposal } => {
+            let signer = signer::load_signer(&cli.keypair)?;
+            let authority = cosigner(signer.as_ref(), proposal.authority, cli.unsigned)?;
+            let address = pda::proposal_pda(&authority, proposal.id).0;
+            let mut ixs = vec![instructions::close_proposal(&signer.pubkey(), &authority, proposal.id)];
+            // A nullifier set can hold a lot of rent; reclaim it as well.
+            if rpc.get_account(&pda::nullifier_set_pda(&address).0).is_ok() {
+                ixs.insert(0, instructions::close_nullifier_set(&authority, &address));
+            }
+            if let Ok(data) = rpc.get_account_data(&pda::compliance_roll_pda(&address).0) {
+                let roll = decode_compliance_roll(&data)?;
+                if roll.released < roll.sealed {
+                    bail!("release the sealed identities first with `arcvote release-identities`");
+                }
+                ixs.insert(0, instructions::close_compliance_roll(&authority, &address));
+            }
+            if let Some(sig) = send(&rpc, signer.as_ref(), &ixs, cli.unsigned)? {
+                println!("Proposal closed: {sig}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Compile `instructions` into a v0 transaction against the MXE lookup table and sign
+/// it with `signer`, or print it unsigned.  Only the message bytes reach the
+/// signer, so a Ledger signs exactly what is sent.
+fn send(
+    rpc: &RpcClient,
+    signer: &dyn Signer,
+    instructions: &[Instruction],
+    unsigned: bool,
+) -> Result<Option<String>> {
+    let payer = signer.pubkey();
+    let lookup_table = fetch_mxe_lookup_table(rpc).context("failed to fetch MXE lookup table")?;
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = UnsignedTransaction::new(&payer, instructions, &[lookup_table], blockhash)?;
+    if unsigned {
+        println!("Message:   {}", BASE64.encode(tx.message_bytes()));
+        for key in tx.signers() {
+            println!("Signer:    {key}");
+        }
+        println!("Blockhash: {blockhash} (sign before it expires)");
+        return Ok(None);
+    }
+    let signature = signer
+        .try_sign_message(&tx.message_bytes())
+        .context("failed to sign transaction")?;
+    let tx = tx.into_signed(&[(payer, signature)])?;
+    Ok(Some(rpc.send_and_confirm_transaction(&tx)?.to_string()))
+}
+
+/// A ballot instruction, followed by its memo if `--memo` asked for one.
+fn with_memo(ix: Instruction, proposal_id: u32, memo: bool) -> Vec<Instruction> {
+    let mut ixs = vec![ix];
+    if memo {
+        ixs.push(instructions::vote_memo(proposal_id));
+    }
+    ixs
+}
+
+/// Where `export` puts the signature of the archive at `archive`.
+fn signature_path(archive: &Path) -> PathBuf {
+    let mut path = archive.as_os_str().to_owned();
+    path.push(".sig");
+    PathBuf::from(path)
+}
+
+/// The proposal authority for an instruction it must sign: the signer
+/// itself, or another key (a multisig vault, say) whose signature is
+/// collected from the `--unsigned` output.
+fn cosigner(signer: &dyn Signer, authority: Option<Pubkey>, unsigned: bool) -> Result<Pubkey> {
+    match authority {
+        Some(authority) if authority != signer.pubkey() && !unsigned => {
+            bail!("authority {authority} must co-sign; rerun with --unsigned")
+        }
+        Some(authority) => Ok(authority),
+        None => Ok(signer.pubkey()),
+    }
+}
+
+/// A credential file from `request-credential`: the request for
+/// `proposal` and the registered wallet it was issued to.
+fn read_credential(path: &Path, proposal: &Pubkey) -> Result<(CredentialRequest, Pubkey)> {
+    let file: [u8; 96] = decode_base64(
+        &std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?,
+        "credential file",
+    )?;
+    let serial = file[..32].try_into().unwrap();
+    let blinding = file[32..64].try_into().unwrap();
+    let voter = Pubkey::new_from_array(file[64..].try_into().unwrap());
+    let request = CredentialRequest::new(*proposal, serial, blinding)
+        .ok_or_else(|| anyhow!("credential file has a zero blinding factor"))?;
+    Ok((request, voter))
+}
+
+/// Send `grow_nullifier_set` until the proposal's set is full size, a few
+/// per transaction.
+fn grow_nullifier_set(rpc: &RpcClient, signer: &dyn Signer, proposal: &Pubkey) -> Result<()> {
+    const GROWS_PER_TX: usize = 8;
+    let address = pda::nullifier_set_pda(proposal).0;
+    loop {
+        let data = rpc.get_account_data(&address).context("proposal has no nullifier set")?;
+        let left = instructions::nullifier_set_grows_left(&decode_nullifier_set(&data)?, data.len());
+        if left == 0 {
+            println!("Nullifier set is full size ({} bytes)", data.len());
+            return Ok(());
+        }
+        let ix = instructions::grow_nullifier_set(&signer.pubkey(), proposal);
+        let sig = send(rpc, signer, &vec![ix; left.min(GROWS_PER_TX)], false)?.expect("sent signed");
+        println!("Grew nullifier set, {} step(s) left: {sig}", left.saturating_sub(GROWS_PER_TX));
+    }
+}
+
+/// The credential's serial and its unblinded signature from the issuance
+/// posted for the wallet that requested it, and that wallet.
+fn unblind_credential(
+    rpc: &RpcClient,
+    path: &Path,
+    proposal: &Pubkey,
+    registry: &CredentialRegistry,
+) -> Result<([u8; 32], [u8; 64], Pubkey)> {
+    let (request, voter) = read_credential(path, proposal)?;
+    let issuance = decode_credential_issuance(
+        &rpc.get_account_data(&pda::credential_issuance_pda(proposal, &voter).0)
+            .with_context(|| format!("no credential issued to {voter} yet"))?,
+    )?;
+    let signature = request
+        .unblind(&issuance.blind_signature)
+        .filter(|signature| verify_credential(&registry.registrar_key, proposal, &request.serial(), signature))
+        .ok_or_else(|| anyhow!("the issued signature does not match this credential"))?;
+    Ok((request.serial(), signature, voter))
+}
+
+fn read_compliance_secret(path: &Path) -> Result<EncryptionKeypair> {
+    let secret = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(EncryptionKeypair::from_secret(decode_base64(&secret, "compliance secret")?))
+}
+
+fn read_registrar_key(path: &Path) -> Result<RegistrarKey> {
+    let secret = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    RegistrarKey::from_secret(decode_base64(&secret, "registrar secret")?)
+        .ok_or_else(|| anyhow!("registrar secret is zero"))
+}
+
+fn decode_base64<const N: usize>(value: &str, what: &str) -> Result<[u8; N]> {
+    let bytes = BASE64.decode(value.trim()).with_context(|| format!("{what} is not base64"))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow!("{what} must be {N} bytes, got {}", bytes.len()))
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before unix epoch")
+        .as_secs() as i64
+}
@@ -0,0 +1,39 @@
+//! Resolve `--keypair` into a transaction signer: a keypair file, or a
+//! Ledger via `usb://ledger[?key=<account>/<change>]`.
+
+use anyhow::{anyhow, Context, Result};
+use solana_remote_wallet::{
+    locator::Locator,
+    remote_keypair::generate_remote_keypair,
+    remote_wallet::maybe_wallet_manager,
+};
+use solana_sdk::{
+    derivation_path::DerivationPath,
+    signature::{read_keypair_file, Signer},
+};
+
+pub fn load_signer(path: &str) -> Result<Box<dyn Signer>> {
+    if path.starts_with("usb://") {
+        return load_remote_signer(path);
+    }
+    let keypair = read_keypair_file(path)
+        .map_err(|e| anyhow!("failed to read keypair file {path}: {e}"))?;
+    Ok(Box::new(keypair))
+}
+
+fn load_remote_signer(path: &str) -> Result<Box<dyn Signer>> {
+    let (locator, query) = path.split_once('?').unwrap_or((path, ""));
+    let derivation_path = match query.strip_prefix("key=") {
+        Some(key) => DerivationPath::from_key_str(key)
+            .with_context(|| format!("invalid derivation path {key}"))?,
+        None if query.is_empty() => DerivationPath::default(),
+        None => return Err(anyhow!("unsupported query in {path}, expected ?key=<path>")),
+    };
+    let locator = Locator::new_from_path(locator).context("invalid remote wallet locator")?;
+    let wallet_manager = maybe_wallet_manager()
+        .context("failed to access USB devices (is the `ledger` feature enabled?)")?
+        .ok_or_else(|| anyhow!("no hardware wallet found"))?;
+    let keypair = generate_remote_keypair(locator, derivation_path, &wallet_manager, false, "keypair")
+        .context("failed to connect to hardware wallet")?;
+    Ok(Box::new(keypair))
+}
@@ -0,0 +1,202 @@
+//! Decoders for ArcVote and Arcium account data fetched over RPC.
+
+use anchor_lang::AccountDeserialize;
+use arcium_client::idl::arcium::accounts::{Cluster, MXEAccount};
+
+pub use private_voting::{
+    Allowlist, Ballot, BallotBuffer, BallotCommitment, BallotCredential, Candidate, Category, Commitment,
+    ComplianceRoll, Config, CouncilChamber, CreatorRecord, CredentialIssuance, CredentialRegistry, CredentialStatus,
+    CreditAccount, Dependency, EncryptedBudget, FeeVault, GatingMode, GlobalStats, HeldBallot, HistoryGate, Juror,
+    MatchPayout, MatchingPool, MixQueue, NftBallot, NullifierSet, OptionDetail, OptionDetails, PreferenceMatrix,
+    ProposalAccount, ProposalState, ProposalStatus, ProposalTranslations, RankingTally, RewardPool, RoundRecord,
+    Sanction, SealedIdentity, SpendHistogram, TallyAccount, Translation, TrusteeSet, VoterCreditBank, VoterCredits,
+    VoterRecord, VotingSession, WriteInTally,
+};
+
+pub fn decode_proposal(data: &[u8]) -> anchor_lang::Result<ProposalAccount> {
+    ProposalAccount::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_tally(data: &[u8]) -> anchor_lang::Result<TallyAccount> {
+    TallyAccount::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_voter_record(data: &[u8]) -> anchor_lang::Result<VoterRecord> {
+    VoterRecord::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_reward_pool(data: &[u8]) -> anchor_lang::Result<RewardPool> {
+    RewardPool::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_matching_pool(data: &[u8]) -> anchor_lang::Result<MatchingPool> {
+    MatchingPool::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_match_payout(data: &[u8]) -> anchor_lang::Result<MatchPayout> {
+    MatchPayout::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_global_stats(data: &[u8]) -> anchor_lang::Result<GlobalStats> {
+    GlobalStats::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_config(data: &[u8]) -> anchor_lang::Result<Config> {
+    Config::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_fee_vault(data: &[u8]) -> anchor_lang::Result<FeeVault> {
+    FeeVault::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_category(data: &[u8]) -> anchor_lang::Result<Category> {
+    Category::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_ballot(data: &[u8]) -> anchor_lang::Result<Ballot> {
+    Ballot::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_round_record(data: &[u8]) -> anchor_lang::Result<RoundRecord> {
+    RoundRecord::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_commitment(data: &[u8]) -> anchor_lang::Result<Commitment> {
+    Commitment::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_creator_record(data: &[u8]) -> anchor_lang::Result<CreatorRecord> {
+    CreatorRecord::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_candidate(data: &[u8]) -> anchor_lang::Result<Candidate> {
+    Candidate::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_juror(data: &[u8]) -> anchor_lang::Result<Juror> {
+    Juror::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_write_in_tally(data: &[u8]) -> anchor_lang::Result<WriteInTally> {
+    WriteInTally::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_council_chamber(data: &[u8]) -> anchor_lang::Result<CouncilChamber> {
+    CouncilChamber::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_credential_registry(data: &[u8]) -> anchor_lang::Result<CredentialRegistry> {
+    CredentialRegistry::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_credential_issuance(data: &[u8]) -> anchor_lang::Result<CredentialIssuance> {
+    CredentialIssuance::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_ballot_credential(data: &[u8]) -> anchor_lang::Result<BallotCredential> {
+    BallotCredential::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_history_gate(data: &[u8]) -> anchor_lang::Result<HistoryGate> {
+    HistoryGate::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_allowlist(data: &[u8]) -> anchor_lang::Result<Allowlist> {
+    Allowlist::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_nft_ballot(data: &[u8]) -> anchor_lang::Result<NftBallot> {
+    NftBallot::try_deserialize(&mut &data[..])
+}
+
+/// Just the header; the slots follow it in the account data.
+pub fn decode_nullifier_set(data: &[u8]) -> anchor_lang::Result<NullifierSet> {
+    NullifierSet::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_compliance_roll(data: &[u8]) -> anchor_lang::Result<ComplianceRoll> {
+    ComplianceRoll::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_sealed_identity(data: &[u8]) -> anchor_lang::Result<SealedIdentity> {
+    SealedIdentity::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_trustee_set(data: &[u8]) -> anchor_lang::Result<TrusteeSet> {
+    TrusteeSet::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_dependency(data: &[u8]) -> anchor_lang::Result<Dependency> {
+    Dependency::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_sanction(data: &[u8]) -> anchor_lang::Result<Sanction> {
+    Sanction::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_option_details(data: &[u8]) -> anchor_lang::Result<OptionDetails> {
+    OptionDetails::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_proposal_translations(data: &[u8]) -> anchor_lang::Result<ProposalTranslations> {
+    ProposalTranslations::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_mix_queue(data: &[u8]) -> anchor_lang::Result<MixQueue> {
+    MixQueue::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_ballot_buffer(data: &[u8]) -> anchor_lang::Result<BallotBuffer> {
+    BallotBuffer::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_ballot_commitment(data: &[u8]) -> anchor_lang::Result<BallotCommitment> {
+    BallotCommitment::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_voter_credits(data: &[u8]) -> anchor_lang::Result<VoterCredits> {
+    VoterCredits::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_credit_account(data: &[u8]) -> anchor_lang::Result<CreditAccount> {
+    CreditAccount::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_voter_credit_bank(data: &[u8]) -> anchor_lang::Result<VoterCreditBank> {
+    VoterCreditBank::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_encrypted_budget(data: &[u8]) -> anchor_lang::Result<EncryptedBudget> {
+    EncryptedBudget::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_spend_histogram(data: &[u8]) -> anchor_lang::Result<SpendHistogram> {
+    SpendHistogram::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_ranking_tally(data: &[u8]) -> anchor_lang::Result<RankingTally> {
+    RankingTally::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_preference_matrix(data: &[u8]) -> anchor_lang::Result<PreferenceMatrix> {
+    PreferenceMatrix::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_voting_session(data: &[u8]) -> anchor_lang::Result<VotingSession> {
+    VotingSession::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_cluster(data: &[u8]) -> anchor_lang::Result<Cluster> {
+    Cluster::try_deserialize(&mut &data[..])
+}
+
+/// The MXE's x25519 public key, or `None` while keygen is still running.
+pub fn decode_mxe_public_key(data: &[u8]) -> anchor_lang::Result<Option<[u8; 32]>> {
+    Ok(MXEAccount::try_deserialize(&mut &data[..])?.x25519_pubkey())
+}
+
+/// The address of the MXE's lookup table (see [`crate::pda::mxe_lookup_table`]).
+pub fn decode_mxe_lookup_table(data: &[u8]) -> anchor_lang::Result<anchor_lang::prelude::Pubkey> {
+    let mxe = MXEAccount::try_deserialize(&mut &data[..])?;
+    Ok(crate::pda::mxe_lookup_table(mxe.lut_offset_slot))
+}
@@ -0,0 +1,107 @@
+//! Blind BLS credentials for credential-gated proposals.
+//!
+//! The registrar signs a voter's serial without seeing it.  The voter sends
+//! `r·H(m)` for a secret blinding factor `r`, the registrar returns
+//! `sk·r·H(m)`, and the voter multiplies that by `r⁻¹` to get the plain BLS
+//! signature `sk·H(m)` that `present_credential` checks.  Points are
+//! uncompressed alt_bn128, as the program's syscalls expect.  On a proposal
+//! with a nullifier set, the serial and signature go straight into
+//! `cast_vote_anonymous`, which records the serial's [`nullifier`].
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use solana_alt_bn128_bls::{G1Point, G2Point, HashToCurve, PrivKey, Sha256Normalized};
+use solana_bn254::prelude::alt_bn128_multiplication;
+
+pub use private_voting::{credential_message, nullifier};
+
+use crate::Pubkey;
+
+/// A registrar's BLS secret key.
+#[derive(Clone)]
+pub struct RegistrarKey {
+    secret: Fr,
+}
+
+impl RegistrarKey {
+    /// Big-endian `secret`, reduced modulo the group order; `None` if that
+    /// leaves zero.
+    pub fn from_secret(secret: [u8; 32]) -> Option<Self> {
+        let secret = Fr::from_be_bytes_mod_order(&secret);
+        (!secret.is_zero()).then_some(Self { secret })
+    }
+
+    /// Uncompressed G2 public key for `open_credentials`.
+    pub fn public_key(&self) -> [u8; 128] {
+        let secret = PrivKey(scalar_bytes(self.secret));
+        G2Point::try_from(&secret)
+            .expect("a canonical nonzero scalar is a valid secret key")
+            .0
+    }
+
+    /// Sign a voter's blinded message for `issue_credential`; `None` if it
+    /// is not a G1 point.
+    pub fn sign_blinded(&self, blinded_message: &[u8; 64]) -> Option<[u8; 64]> {
+        multiply(blinded_message, self.secret)
+    }
+}
+
+/// A voter's secret side of one credential: the serial later presented and
+/// the factor that hides it from the registrar.
+#[derive(Clone)]
+pub struct CredentialRequest {
+    proposal: Pubkey,
+    serial: [u8; 32],
+    blinding: Fr,
+}
+
+impl CredentialRequest {
+    /// `serial` and `blinding` must be fresh random bytes, kept secret until
+    /// the credential is presented; `None` if `blinding` reduces to zero.
+    pub fn new(proposal: Pubkey, serial: [u8; 32], blinding: [u8; 32]) -> Option<Self> {
+        let blinding = Fr::from_be_bytes_mod_order(&blinding);
+        (!blinding.is_zero()).then_some(Self {
+            proposal,
+            serial,
+            blinding,
+        })
+    }
+
+    pub fn serial(&self) -> [u8; 32] {
+        self.serial
+    }
+
+    /// `r·H(credential_message)`, for the registrar to sign.
+    pub fn blinded_message(&self) -> [u8; 64] {
+        let point = Sha256Normalized::try_hash_to_curve(credential_message(&self.proposal, &self.serial))
+            .expect("hash-to-curve succeeds for all but a negligible fraction of messages");
+        multiply(&point.0, self.blinding).expect("a hashed point is on the curve")
+    }
+
+    /// The credential signature from the registrar's `blind_signature`;
+    /// `None` if it is not a G1 point.  Check it with [`verify_credential`]
+    /// before presenting.
+    pub fn unblind(&self, blind_signature: &[u8; 64]) -> Option<[u8; 64]> {
+        let inverse = self.blinding.inverse().expect("blinding is nonzero");
+        multiply(blind_signature, inverse)
+    }
+}
+
+/// Whether `signature` is the registrar's credential on `serial`, as
+/// `present_credential` checks it.
+pub fn verify_credential(registrar_key: &[u8; 128], proposal: &Pubkey, serial: &[u8; 32], signature: &[u8; 64]) -> bool {
+    G2Point(*registrar_key)
+        .verify_signature::<Sha256Normalized, _, _>(G1Point(*signature), credential_message(proposal, serial))
+        .is_ok()
+}
+
+fn scalar_bytes(scalar: Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&scalar.into_bigint().to_bytes_be());
+    bytes
+}
+
+fn multiply(point: &[u8; 64], scalar: Fr) -> Option<[u8; 64]> {
+    let input = [&point[..], &scalar_bytes(scalar)].concat();
+    alt_bn128_multiplication(&input).ok()?.try_into().ok()
+}
@@ -0,0 +1,668 @@
+//! Client-side encryption of ballots.
+//!
+//! Mirrors `@arcium-hq/client`: an x25519 key exchange with the MXE public
+//! key yields a shared secret, a Rescue-Prime KDF turns it into a Rescue
+//! key, and each plaintext field is encrypted in CTR mode under a 128-bit
+//! nonce.  Ciphertexts are 32-byte little-endian field elements, exactly what
+//! `cast_vote` expects.
+
+use ff::PrimeField;
+use private_voting::MAX_BALLOT_PROPOSALS;
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::{
+    rescue::{fp_from_le_bytes, fp_to_le_bytes, Fp, RescueCipher},
+    Pubkey,
+};
+
+/// Message voters sign to derive a deterministic encryption key from their
+/// wallet (same as the TypeScript client).
+pub const ENCRYPTION_KEY_MESSAGE: &[u8] = b"arcvote-encryption-key-v1";
+
+/// Added to each -2..+2 Likert score before encryption; the `cast_likert`
+/// circuit discards ballots with any offset score above twice this.
+pub const LIKERT_OFFSET: i8 = 2;
+
+/// Highest score on a STAR ballot; the `cast_star` circuit discards
+/// ballots with any score above it.
+pub const STAR_MAX_SCORE: u64 = 5;
+
+/// Highest score on a range ballot; the `cast_range` circuit discards
+/// ballots with any score above it.
+pub const RANGE_MAX_SCORE: u64 = 10;
+
+/// A voter's quadratic allocation: effective votes per option.  For a
+/// Likert proposal, the offset score per option instead, for a
+/// For/Against/Abstain proposal a one-hot choice, for an approval
+/// proposal a 0 or 1 per option, for a ranked proposal the option at each
+/// place, best first, and for a STAR or range proposal the 0..=5 or
+/// 0..=10 score per option.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VoteAllocation {
+    pub votes: [u64; 4],
+}
+
+impl VoteAllocation {
+    pub fn new(votes: [u64; 4]) -> Self {
+        Self { votes }
+    }
+
+    /// Likert ballot from one -2..+2 score per option, in option order.
+    /// Slots past `scores` stay 0.  `None` for more than 4 scores or a
+    /// score off the scale.
+    pub fn likert(scores: &[i8]) -> Option<Self> {
+        if scores.len() > 4 {
+            return None;
+        }
+        let mut votes = [0; 4];
+        for (vote, score) in votes.iter_mut().zip(scores) {
+            if score.unsigned_abs() > LIKERT_OFFSET.unsigned_abs() {
+                return None;
+            }
+            *vote = (score + LIKERT_OFFSET) as u64;
+        }
+        Some(Self { votes })
+    }
+
+    /// STAR ballot from one 0..=5 score per option, in option order.
+    /// Slots past `scores` stay 0.  `None` for more than 4 scores or a
+    /// score above [`STAR_MAX_SCORE`].
+    pub fn star(scores: &[u8]) -> Option<Self> {
+        Self::scored(scores, STAR_MAX_SCORE)
+    }
+
+    /// Range ballot from one 0..=10 score per option, in option order.
+    /// Slots past `scores` stay 0.  `None` for more than 4 scores or a
+    /// score above [`RANGE_MAX_SCORE`].
+    pub fn range(scores: &[u8]) -> Option<Self> {
+        Self::scored(scores, RANGE_MAX_SCORE)
+    }
+
+    fn scored(scores: &[u8], max: u64) -> Option<Self> {
+        if scores.len() > 4 {
+            return None;
+        }
+        let mut votes = [0; 4];
+        for (vote, score) in votes.iter_mut().zip(scores) {
+            if u64::from(*score) > max {
+                return None;
+            }
+            *vote = u64::from(*score);
+        }
+        Some(Self { votes })
+    }
+
+    /// For/Against/Abstain ballot choosing `option`: 0 For, 1 Against,
+    /// 2 Abstain.  `None` for any other index.
+    pub fn choice(option: usize) -> Option<Self> {
+        if option > 2 {
+            return None;
+        }
+        let mut votes = [0; 4];
+        votes[option] = 1;
+        Some(Self { votes })
+    }
+
+    /// Approval ballot approving each of `options`, in any order.  `None`
+    /// for an index past 3 or one given twice.
+    pub fn approval(options: &[usize]) -> Option<Self> {
+        let mut votes = [0; 4];
+        for option in options {
+            let vote = votes.get_mut(*option)?;
+            if *vote == 1 {
+                return None;
+            }
+            *vote = 1;
+        }
+        Some(Self { votes })
+    }
+
+    /// Whether the `cast_approval` circuit will count this ballot: every
+    /// entry 0 or 1, none past `num_options`, and at most `max_choices`
+    /// approved.
+    pub fn is_approval(&self, max_choices: u8, num_options: u8) -> bool {
+        self.votes
+            .iter()
+            .enumerate()
+            .all(|(i, v)| *v <= u64::from(i < num_options as usize))
+            && self.votes.iter().sum::<u64>() <= max_choices as u64
+    }
+
+    /// Ranked ballot from `order`, the options best first.  Options left
+    /// out follow in index order, since the `cast_ranked_vote`,
+    /// `cast_borda` and `cast_condorcet` circuits want every option ranked.
+    /// `None` for an index past 3 or one given twice.
+    pub fn ranking(order: &[usize]) -> Option<Self> {
+        let mut ranked = [false; 4];
+        for option in order {
+            let seen = ranked.get_mut(*option)?;
+            if *seen {
+                return None;
+            }
+            *seen = true;
+        }
+        let rest = (0..4).filter(|i| !ranked[*i]);
+        let mut votes = [0; 4];
+        for (vote, option) in votes.iter_mut().zip(order.iter().copied().chain(rest)) {
+            *vote = option as u64;
+        }
+        Some(Self { votes })
+    }
+
+    /// Whether the `cast_ranked_vote`, `cast_borda` and `cast_condorcet`
+    /// circuits will count this ballot: an order of the four options whose
+    /// first `num_options` places hold the proposal's options.
+    pub fn is_ranking(&self, num_options: u8) -> bool {
+        let mut sorted = self.votes;
+        sorted.sort_unstable();
+        sorted == [0, 1, 2, 3]
+            && self.votes[..usize::from(num_options.min(4))]
+                .iter()
+                .all(|v| *v < u64::from(num_options))
+    }
+
+    /// Whether the `cast_choice` circuit will count this ballot: exactly
+    /// one of the first three options chosen.
+    pub fn is_one_choice(&self) -> bool {
+        let [c0, c1, c2, c3] = self.votes;
+        c0 <= 1 && c1 <= 1 && c2 <= 1 && c3 == 0 && c0 + c1 + c2 == 1
+    }
+
+    /// Whether the `cast_likert` circuit will count this ballot.
+    pub fn is_on_likert_scale(&self) -> bool {
+        self.votes.iter().all(|v| *v <= 2 * LIKERT_OFFSET as u64)
+    }
+
+    /// Quadratic cost `v0² + v1² + v2² + v3²`, or `None` on overflow.
+    pub fn cost(&self) -> Option<u64> {
+        self.votes
+            .iter()
+            .try_fold(0u64, |acc, v| acc.checked_add(v.checked_mul(*v)?))
+    }
+
+    /// Whether the circuit will count this allocation under `budget`,
+    /// usually [`effective_budget`](crate::validation::effective_budget).
+    pub fn is_within_budget(&self, budget: u64) -> bool {
+        self.cost().is_some_and(|c| c <= budget)
+    }
+}
+
+/// Criteria a jury ballot can score, as the `cast_jury_scores` circuit
+/// lays them out.
+pub const MAX_CRITERIA: usize = private_voting::MAX_CRITERIA as usize;
+
+/// A juror's scores: `scores[criterion][application]`, with unused
+/// criteria and applications left at 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JuryScores {
+    pub scores: [[u64; 4]; MAX_CRITERIA],
+}
+
+impl JuryScores {
+    /// From one row of per-application scores per criterion.  `None` for
+    /// more than [`MAX_CRITERIA`] rows or more than 4 scores in a row.
+    pub fn new(rows: &[Vec<u64>]) -> Option<Self> {
+        if rows.len() > MAX_CRITERIA {
+            return None;
+        }
+        let mut scores = [[0; 4]; MAX_CRITERIA];
+        for (padded, row) in scores.iter_mut().zip(rows) {
+            if row.len() > 4 {
+                return None;
+            }
+            padded[..row.len()].copy_from_slice(row);
+        }
+        Some(Self { scores })
+    }
+
+    /// The first score the `cast_jury_scores` circuit would reject, as
+    /// `(criterion, application)`: above `max_score`, or nonzero past the
+    /// proposal's `criteria` or `applications`.
+    pub fn out_of_range(&self, criteria: u8, max_score: u8, applications: u8) -> Option<(usize, usize)> {
+        (0..MAX_CRITERIA)
+            .flat_map(|c| (0..4).map(move |a| (c, a)))
+            .find(|&(c, a)| {
+                let cap = if c < criteria as usize && a < applications as usize {
+                    u64::from(max_score)
+                } else {
+                    0
+                };
+                self.scores[c][a] > cap
+            })
+    }
+
+    /// Each application's score summed over the criteria.
+    pub fn totals(&self) -> [u64; 4] {
+        let mut totals = [0; 4];
+        for row in &self.scores {
+            for (total, score) in totals.iter_mut().zip(row) {
+                *total += score;
+            }
+        }
+        totals
+    }
+}
+
+/// A voter's x25519 keypair.
+#[derive(Clone)]
+pub struct EncryptionKeypair {
+    secret: StaticSecret,
+}
+
+impl EncryptionKeypair {
+    pub fn from_secret(secret: [u8; 32]) -> Self {
+        Self {
+            secret: StaticSecret::from(secret),
+        }
+    }
+
+    /// `sha256(signature)` of [`ENCRYPTION_KEY_MESSAGE`], matching the
+    /// TypeScript client so both derive the same key from one wallet.
+    pub fn from_signature(signature: &[u8]) -> Self {
+        Self::from_secret(Sha256::digest(signature).into())
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        PublicKey::from(&self.secret).to_bytes()
+    }
+
+    /// x25519 shared secret with the MXE.
+    pub fn shared_secret(&self, mxe_public_key: &[u8; 32]) -> [u8; 32] {
+        self.secret
+            .diffie_hellman(&PublicKey::from(*mxe_public_key))
+            .to_bytes()
+    }
+
+    /// Nonce for a ballot on a proposal with a commit phase: the first 16
+    /// bytes of `sha256(secret ‖ proposal)`.  The vote cast after the phase
+    /// must carry the ciphertexts that were committed to, so both steps
+    /// encrypt the same allocation under this nonce.
+    pub fn commit_nonce(&self, proposal: &Pubkey) -> [u8; 16] {
+        let digest = Sha256::new()
+            .chain_update(self.secret.to_bytes())
+            .chain_update(proposal.as_ref())
+            .finalize();
+        digest[..16].try_into().unwrap()
+    }
+}
+
+/// Rescue cipher keyed from an x25519 shared secret.
+pub struct Cipher {
+    inner: RescueCipher,
+}
+
+impl Cipher {
+    pub fn new(shared_secret: &[u8; 32]) -> Self {
+        Self {
+            inner: RescueCipher::from_shared_secret(shared_secret),
+        }
+    }
+
+    pub fn encrypt(&self, plaintexts: &[u64], nonce: u128) -> Vec<[u8; 32]> {
+        let plaintexts: Vec<Fp> = plaintexts.iter().map(|p| Fp::from(*p)).collect();
+        self.inner
+            .encrypt(&plaintexts, Fp::from_u128(nonce))
+            .into_iter()
+            .map(fp_to_le_bytes)
+            .collect()
+    }
+
+    /// Decrypts ciphertexts back into field elements (little-endian bytes),
+    /// or `None` if a ciphertext is not a canonical field element.
+    pub fn decrypt(&self, ciphertexts: &[[u8; 32]], nonce: u128) -> Option<Vec<[u8; 32]>> {
+        let ciphertexts = ciphertexts
+            .iter()
+            .map(|c| fp_from_le_bytes(*c))
+            .collect::<Option<Vec<Fp>>>()?;
+        Some(
+            self.inner
+                .decrypt(&ciphertexts, Fp::from_u128(nonce))
+                .into_iter()
+                .map(fp_to_le_bytes)
+                .collect(),
+        )
+    }
+}
+
+/// Everything `cast_vote` needs besides the accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptedVote {
+    pub ciphertexts: [[u8; 32]; 4],
+    pub public_key: [u8; 32],
+    pub nonce: u128,
+}
+
+/// Everything `cast_jury_scores` needs besides the accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptedJuryScores {
+    pub ciphertexts: [[u8; 32]; 4 * MAX_CRITERIA],
+    pub public_key: [u8; 32],
+    pub nonce: u128,
+}
+
+/// Everything `cast_ballot_choices` needs besides the accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptedBallotChoices {
+    pub ciphertexts: [[u8; 32]; MAX_BALLOT_PROPOSALS],
+    pub public_key: [u8; 32],
+    pub nonce: u128,
+}
+
+/// Everything `cast_write_in` needs besides the accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptedWriteIn {
+    pub ciphertext: [u8; 32],
+    pub public_key: [u8; 32],
+    pub nonce: u128,
+}
+
+/// Everything `fund_encrypted_budget` needs besides the accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptedCredits {
+    pub ciphertext: [u8; 32],
+    pub public_key: [u8; 32],
+    pub nonce: u128,
+}
+
+/// A voter's registered wallet for `cast_vote_anonymous` on a proposal
+/// with a compliance roll, under the ballot's key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptedIdentity {
+    pub ciphertexts: [[u8; 32]; 2],
+    pub nonce: u128,
+}
+
+/// Candidate hash a write-in of `identifier` carries: the first 16 bytes
+/// of `sha256` over the trimmed, lowercased identifier, little-endian, so
+/// "Jane Doe" and " jane doe" count as one candidate.  Never 0, which the
+/// circuit reserves for an empty slot.
+pub fn write_in_hash(identifier: &str) -> u128 {
+    let digest = Sha256::digest(identifier.trim().to_lowercase().as_bytes());
+    let hash = u128::from_le_bytes(digest[..16].try_into().unwrap());
+    hash.max(1)
+}
+
+/// Encrypt `allocation` for the MXE under `nonce` (16 random bytes, LE).
+pub fn encrypt_vote(
+    keypair: &EncryptionKeypair,
+    mxe_public_key: &[u8; 32],
+    allocation: &VoteAllocation,
+    nonce: [u8; 16],
+) -> EncryptedVote {
+    let nonce = u128::from_le_bytes(nonce);
+    let cipher = Cipher::new(&keypair.shared_secret(mxe_public_key));
+    let ct = cipher.encrypt(&allocation.votes, nonce);
+    EncryptedVote {
+        ciphertexts: [ct[0], ct[1], ct[2], ct[3]],
+        public_key: keypair.public_key(),
+        nonce,
+    }
+}
+
+/// Encrypt a juror's `scores`, criterion-major, for the MXE under `nonce`
+/// (16 random bytes, LE).
+pub fn encrypt_jury_scores(
+    keypair: &EncryptionKeypair,
+    mxe_public_key: &[u8; 32],
+    scores: &JuryScores,
+    nonce: [u8; 16],
+) -> EncryptedJuryScores {
+    let nonce = u128::from_le_bytes(nonce);
+    let cipher = Cipher::new(&keypair.shared_secret(mxe_public_key));
+    let ct = cipher.encrypt(scores.scores.as_flattened(), nonce);
+    EncryptedJuryScores {
+        ciphertexts: ct.try_into().expect("one ciphertext per score"),
+        public_key: keypair.public_key(),
+        nonce,
+    }
+}
+
+/// Encrypt one choice per proposal of a `cast_ballot_choices`, in its
+/// order — 0 For, 1 Against, 2 Abstain — together for the MXE under
+/// `nonce` (16 random bytes, LE).  The slots past `choices` hold 0 and
+/// are ignored.  Panics on more than `MAX_BALLOT_PROPOSALS` choices.
+pub fn encrypt_ballot_choices(
+    keypair: &EncryptionKeypair,
+    mxe_public_key: &[u8; 32],
+    choices: &[u8],
+    nonce: [u8; 16],
+) -> EncryptedBallotChoices {
+    assert!(choices.len() <= MAX_BALLOT_PROPOSALS, "too many ballot choices");
+    let mut slots = [0; MAX_BALLOT_PROPOSALS];
+    for (slot, choice) in slots.iter_mut().zip(choices) {
+        *slot = u64::from(*choice);
+    }
+    let nonce = u128::from_le_bytes(nonce);
+    let cipher = Cipher::new(&keypair.shared_secret(mxe_public_key));
+    let ct = cipher.encrypt(&slots, nonce);
+    EncryptedBallotChoices {
+        ciphertexts: ct.try_into().expect("one ciphertext per entry"),
+        public_key: keypair.public_key(),
+        nonce,
+    }
+}
+
+/// Encrypt a write-in's candidate hash (see [`write_in_hash`]) for the MXE
+/// under `nonce` (16 random bytes, LE).
+pub fn encrypt_write_in(
+    keypair: &EncryptionKeypair,
+    mxe_public_key: &[u8; 32],
+    candidate: u128,
+    nonce: [u8; 16],
+) -> EncryptedWriteIn {
+    let nonce = u128::from_le_bytes(nonce);
+    let cipher = RescueCipher::from_shared_secret(&keypair.shared_secret(mxe_public_key));
+    let ct = cipher.encrypt(&[Fp::from_u128(candidate)], Fp::from_u128(nonce));
+    EncryptedWriteIn {
+        ciphertext: fp_to_le_bytes(ct[0]),
+        public_key: keypair.public_key(),
+        nonce,
+    }
+}
+
+/// Encrypt a voter's budget of `credits` voice credits for the MXE under
+/// `nonce` (16 random bytes, LE).  `keypair` is the proposal authority's.
+pub fn encrypt_credits(
+    keypair: &EncryptionKeypair,
+    mxe_public_key: &[u8; 32],
+    credits: u64,
+    nonce: [u8; 16],
+) -> EncryptedCredits {
+    let nonce = u128::from_le_bytes(nonce);
+    let cipher = Cipher::new(&keypair.shared_secret(mxe_public_key));
+    let ct = cipher.encrypt(&[credits], nonce);
+    EncryptedCredits {
+        ciphertext: ct[0],
+        public_key: keypair.public_key(),
+        nonce,
+    }
+}
+
+/// Encrypt the registered `wallet` an anonymous ballot declares, for the
+/// MXE under `nonce` (16 random bytes, LE, not the ballot's nonce).  Its
+/// first and last 16 bytes are each encrypted as a little-endian u128.
+pub fn encrypt_identity(
+    keypair: &EncryptionKeypair,
+    mxe_public_key: &[u8; 32],
+    wallet: &Pubkey,
+    nonce: [u8; 16],
+) -> EncryptedIdentity {
+    let nonce = u128::from_le_bytes(nonce);
+    let bytes = wallet.to_bytes();
+    let halves = [&bytes[..16], &bytes[16..]].map(|half| Fp::from_u128(u128::from_le_bytes(half.try_into().unwrap())));
+    let cipher = RescueCipher::from_shared_secret(&keypair.shared_secret(mxe_public_key));
+    let ct = cipher.encrypt(&halves, Fp::from_u128(nonce));
+    EncryptedIdentity {
+        ciphertexts: [fp_to_le_bytes(ct[0]), fp_to_le_bytes(ct[1])],
+        nonce,
+    }
+}
+
+/// The wallet in a released `SealedIdentity`, decrypted with the compliance
+/// key's secret; `None` if the ciphertexts don't decrypt to two u128s.
+pub fn decrypt_identity(
+    compliance: &EncryptionKeypair,
+    mxe_public_key: &[u8; 32],
+    ciphertexts: &[[u8; 32]; 2],
+    nonce: u128,
+) -> Option<Pubkey> {
+    let halves = Cipher::new(&compliance.shared_secret(mxe_public_key)).decrypt(ciphertexts, nonce)?;
+    let mut wallet = [0u8; 32];
+    for (out, half) in wallet.chunks_mut(16).zip(&halves) {
+        if half[16..].iter().any(|b| *b != 0) {
+            return None;
+        }
+        out.copy_from_slice(&half[..16]);
+    }
+    Some(Pubkey::new_from_array(wallet))
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// The README scenario from `arcvote-vectors`, cast under its proposal's
+    /// `voice_credits`.
+    const VECTORS: &str = include_str!("../../arcvote-vectors/vectors.json");
+    const VOICE_CREDITS: u64 = 100;
+
+    fn vectors() -> Value {
+        serde_json::from_str(VECTORS).expect("vectors.json parses")
+    }
+
+    fn unhex<const N: usize>(hex: &Value) -> [u8; N] {
+        let hex = hex.as_str().expect("hex string");
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("hex digit"))
+            .collect();
+        bytes.try_into().expect("byte length")
+    }
+
+    fn allocation(ballot: &Value) -> VoteAllocation {
+        let votes: Vec<u64> = ballot["allocation"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_u64().unwrap())
+            .collect();
+        VoteAllocation::new(votes.try_into().unwrap())
+    }
+
+    fn mxe(vectors: &Value) -> EncryptionKeypair {
+        EncryptionKeypair::from_secret(unhex(&vectors["mxe_secret_key"]))
+    }
+
+    #[test]
+    fn ballots_encrypt_to_the_vectors() {
+        let vectors = vectors();
+        let mxe_public_key = mxe(&vectors).public_key();
+        assert_eq!(mxe_public_key, unhex::<32>(&vectors["mxe_public_key"]));
+        for ballot in vectors["cast_vote"].as_array().unwrap() {
+            let keypair = EncryptionKeypair::from_secret(unhex(&ballot["voter_secret_key"]));
+            assert_eq!(keypair.public_key(), unhex::<32>(&ballot["voter_public_key"]));
+            assert_eq!(keypair.shared_secret(&mxe_public_key), unhex::<32>(&ballot["shared_secret"]));
+
+            let vote = encrypt_vote(&keypair, &mxe_public_key, &allocation(ballot), unhex(&ballot["nonce"]));
+            assert_eq!(vote.public_key, keypair.public_key());
+            assert_eq!(vote.nonce.to_string(), ballot["nonce_u128"].as_str().unwrap());
+            for (ciphertext, expected) in vote.ciphertexts.iter().zip(ballot["ciphertexts"].as_array().unwrap()) {
+                assert_eq!(*ciphertext, unhex::<32>(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn the_mxe_decrypts_the_vector_ballots() {
+        let vectors = vectors();
+        let mxe = mxe(&vectors);
+        for ballot in vectors["cast_vote"].as_array().unwrap() {
+            let cipher = Cipher::new(&mxe.shared_secret(&unhex(&ballot["voter_public_key"])));
+            let ciphertexts: Vec<[u8; 32]> = ballot["ciphertexts"].as_array().unwrap().iter().map(unhex).collect();
+            let nonce = u128::from_le_bytes(unhex(&ballot["nonce"]));
+            let expected: Vec<[u8; 32]> = allocation(ballot)
+                .votes
+                .iter()
+                .map(|v| fp_to_le_bytes(Fp::from(*v)))
+                .collect();
+            assert_eq!(cipher.decrypt(&ciphertexts, nonce), Some(expected));
+        }
+    }
+
+    #[test]
+    fn vector_costs_decide_what_the_circuit_counts() {
+        for ballot in vectors()["cast_vote"].as_array().unwrap() {
+            let allocation = allocation(ballot);
+            assert_eq!(allocation.cost(), ballot["cost"].as_u64());
+            assert_eq!(
+                allocation.is_within_budget(VOICE_CREDITS),
+                ballot["counted"].as_bool().unwrap(),
+                "{:?}",
+                allocation.votes
+            );
+        }
+    }
+
+    #[test]
+    fn cipher_round_trips() {
+        let cipher = Cipher::new(&[0x5a; 32]);
+        let plaintexts = [0, 1, 1 << 63, u64::MAX, 42, 7];
+        for nonce in [0, 1, u128::MAX] {
+            let ciphertexts = cipher.encrypt(&plaintexts, nonce);
+            assert_eq!(ciphertexts.len(), plaintexts.len());
+            let expected: Vec<[u8; 32]> = plaintexts.iter().map(|p| fp_to_le_bytes(Fp::from(*p))).collect();
+            assert_eq!(cipher.decrypt(&ciphertexts, nonce), Some(expected));
+        }
+    }
+
+    #[test]
+    fn decrypt_rejects_a_non_canonical_ciphertext() {
+        assert_eq!(Cipher::new(&[0x5a; 32]).decrypt(&[[0xff; 32]], 1), None);
+    }
+
+    #[test]
+    fn identity_round_trips() {
+        let voter = EncryptionKeypair::from_secret([1; 32]);
+        let mxe_public_key = EncryptionKeypair::from_secret([0x4d; 32]).public_key();
+        let wallet = Pubkey::new_from_array(std::array::from_fn(|i| i as u8 + 0xe0));
+        let sealed = encrypt_identity(&voter, &mxe_public_key, &wallet, [0xb0; 16]);
+        assert_eq!(
+            decrypt_identity(&voter, &mxe_public_key, &sealed.ciphertexts, sealed.nonce),
+            Some(wallet)
+        );
+    }
+
+    #[test]
+    fn decrypt_identity_rejects_a_half_wider_than_u128() {
+        let voter = EncryptionKeypair::from_secret([1; 32]);
+        let mxe_public_key = EncryptionKeypair::from_secret([0x4d; 32]).public_key();
+        let nonce = 9u128;
+        let cipher = RescueCipher::from_shared_secret(&voter.shared_secret(&mxe_public_key));
+        let ct = cipher.encrypt(&[Fp::ONE, -Fp::ONE], Fp::from_u128(nonce));
+        let ciphertexts = [fp_to_le_bytes(ct[0]), fp_to_le_bytes(ct[1])];
+        assert_eq!(decrypt_identity(&voter, &mxe_public_key, &ciphertexts, nonce), None);
+    }
+
+    #[test]
+    fn cost_is_none_on_overflow() {
+        let widest = u64::from(u32::MAX);
+        assert_eq!(VoteAllocation::new([widest, 0, 0, 0]).cost(), Some(widest * widest));
+        for votes in [[widest + 1, 0, 0, 0], [u64::MAX, 0, 0, 0], [widest, widest, 0, 0]] {
+            let allocation = VoteAllocation::new(votes);
+            assert_eq!(allocation.cost(), None, "{votes:?}");
+            assert!(!allocation.is_within_budget(u64::MAX), "{votes:?}");
+        }
+    }
+
+    #[test]
+    fn the_budget_is_inclusive() {
+        let allocation = VoteAllocation::new([0, 0, 10, 0]);
+        assert!(allocation.is_within_budget(100));
+        assert!(!allocation.is_within_budget(99));
+        assert!(VoteAllocation::default().is_within_budget(0));
+    }
+}
@@ -0,0 +1,298 @@
+//! Signed archives of finalized proposals (feature `export`), for record
+//! keeping and audits.
+//!
+//! [`fetch_archive`] gathers what an auditor needs once the proposal's
+//! accounts are long closed: the proposal account with its results and the
+//! cluster's attestation over them, the cluster's BLS key (so
+//! [`verify_results`](crate::verify::verify_results) can be rerun offline),
+//! the proposal's tally commitment, spend histogram and write-in tally if it
+//! has them, and every ArcVote event emitted by a transaction that touched
+//! the proposal.  Accounts and events are the `arcvote-types` ones, so the
+//! archive is plain JSON.
+//!
+//! Whoever exports signs the rendered bytes — JSON or CSV alike — with their
+//! wallet.  The signature is detached ([`ArchiveSignature`]), so the archive
+//! itself stays a plain file any tool can read.
+
+use std::str::FromStr;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use arcium_client::idl::arcium::types::SetUnset;
+use arcvote_types as types;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use solana_rpc_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_signature::Signature;
+use solana_transaction_status_client_types::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+use crate::{
+    accounts::{decode_cluster, decode_proposal},
+    pda,
+    rpc::RpcError,
+    verify::{verify_proposal, VerifyError},
+    Pubkey, PROGRAM_ID,
+};
+
+/// Identifies the archive layout; bumped when it changes incompatibly.
+pub const ARCHIVE_FORMAT: &str = "arcvote-archive/1";
+
+/// Prefixed to an archive's SHA-256 to form the message its exporter signs,
+/// so the signature can't pass for a transaction or any other message.
+pub const ARCHIVE_SIGNING_DOMAIN: &[u8] = b"arcvote-archive-v1";
+
+/// Maximum page size of `getSignaturesForAddress`.
+const PAGE_SIZE: usize = 1000;
+
+/// Everything on record about one finalized proposal.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProposalArchive {
+    /// [`ARCHIVE_FORMAT`].
+    pub format: String,
+    pub program: types::Pubkey,
+    pub address: types::Pubkey,
+    /// Slot the accounts were read at.
+    pub slot: u64,
+    /// Its `results`, `winner`, `outcome` and `funded` verified against
+    /// `result_attestation` under `cluster_bls_public_key` at export.
+    pub proposal: types::ProposalAccount,
+    /// The attesting cluster's compressed G2 BLS public key.
+    pub cluster_bls_public_key: types::Bytes<64>,
+    pub commitment: Option<types::Commitment>,
+    pub spend_histogram: Option<types::SpendHistogram>,
+    pub write_in_tally: Option<types::WriteInTally>,
+    /// Oldest first.
+    pub events: Vec<ArchivedEvent>,
+}
+
+/// An event, and the transaction that emitted it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub event: types::Event,
+}
+
+/// Fetch the finalized proposal at `address` and its history, verifying
+/// its results against the cluster's signature first.
+///
+/// Events come from every successful transaction that touched the
+/// proposal account; events naming another proposal are left out.
+pub fn fetch_archive(rpc: &RpcClient, address: &Pubkey) -> Result<ProposalArchive, RpcError> {
+    let slot = rpc.get_slot()?;
+    let data = rpc.get_account_data(address)?;
+    let checked = decode_proposal(&data).map_err(|e| RpcError::InvalidProposal(Box::new(e)))?;
+    let cluster_address = checked.result_attestation.cluster;
+    if !checked.is_finalized {
+        return Err(VerifyError::NotFinalized.into());
+    }
+    let cluster =
+        decode_cluster(&rpc.get_account_data(&cluster_address)?).map_err(|e| RpcError::InvalidCluster(Box::new(e)))?;
+    verify_proposal(&checked, &cluster_address, &cluster)?;
+    let SetUnset::Set(key) = &cluster.bls_public_key else {
+        return Err(VerifyError::ClusterKeyUnset.into());
+    };
+
+    Ok(ProposalArchive {
+        format: ARCHIVE_FORMAT.to_string(),
+        program: PROGRAM_ID.into(),
+        address: (*address).into(),
+        slot,
+        proposal: decode(address, &data)?,
+        cluster_bls_public_key: types::Bytes(key.0),
+        commitment: fetch_optional(rpc, &pda::commitment_pda(address).0)?,
+        spend_histogram: fetch_optional(rpc, &pda::spend_histogram_pda(address).0)?,
+        write_in_tally: fetch_optional(rpc, &pda::write_in_tally_pda(address).0)?,
+        events: fetch_events(rpc, address)?,
+    })
+}
+
+fn decode<T: Discriminator + AnchorDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T, RpcError> {
+    types::decode(data).map_err(|e| RpcError::InvalidExport(format!("account {address}: {e}")))
+}
+
+fn fetch_optional<T: Discriminator + AnchorDeserialize>(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<Option<T>, RpcError> {
+    match rpc.get_account_with_commitment(address, rpc.commitment())?.value {
+        Some(account) => decode(address, &account.data).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn fetch_events(rpc: &RpcClient, address: &Pubkey) -> Result<Vec<ArchivedEvent>, RpcError> {
+    // Pages come newest-first.
+    let mut entries = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(PAGE_SIZE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let Some(last) = page.last() else { break };
+        before = Some(parse_signature(&last.signature)?);
+        let done = page.len() < PAGE_SIZE;
+        entries.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    let proposal = Value::String(address.to_string());
+    let mut events = Vec::new();
+    for entry in entries.iter().rev().filter(|entry| entry.err.is_none()) {
+        let tx = rpc.get_transaction_with_config(
+            &parse_signature(&entry.signature)?,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let Some(OptionSerializer::Some(logs)) = tx.transaction.meta.map(|meta| meta.log_messages) else {
+            continue;
+        };
+        for event in types::decode_logs(&PROGRAM_ID, &logs) {
+            let named = serde_json::to_value(&event).expect("events serialize");
+            if named.get("proposal").is_none_or(|named| *named == proposal) {
+                events.push(ArchivedEvent {
+                    signature: entry.signature.clone(),
+                    slot: entry.slot,
+                    block_time: entry.block_time,
+                    event,
+                });
+            }
+        }
+    }
+    Ok(events)
+}
+
+fn parse_signature(signature: &str) -> Result<Signature, RpcError> {
+    Signature::from_str(signature).map_err(|e| RpcError::InvalidExport(format!("signature {signature}: {e}")))
+}
+
+impl ProposalArchive {
+    /// The archive as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("archives serialize") + "\n"
+    }
+
+    /// The archive as one CSV table with columns `section, slot,
+    /// block_time, signature, name, value`: a row per field of each
+    /// account (`slot` and the transaction columns empty), then a row per
+    /// event, named by its type, with the rest of the event as a JSON value.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("section,slot,block_time,signature,name,value\n");
+        let mut row = |section: &str, event: Option<&ArchivedEvent>, name: &str, value: &Value| {
+            let (slot, block_time, signature) = match event {
+                Some(e) => (
+                    e.slot.to_string(),
+                    e.block_time.map(|t| t.to_string()).unwrap_or_default(),
+                    e.signature.as_str(),
+                ),
+                None => (String::new(), String::new(), ""),
+            };
+            let value = match value {
+                Value::String(s) => s.clone(),
+                value => value.to_string(),
+            };
+            let fields = [section, &slot, &block_time, signature, name, &value];
+            out.push_str(&fields.map(csv_field).join(","));
+            out.push('\n');
+        };
+
+        let header = [
+            ("format", Value::from(self.format.clone())),
+            ("program", Value::from(self.program.to_string())),
+            ("address", Value::from(self.address.to_string())),
+            ("slot", Value::from(self.slot)),
+            (
+                "cluster_bls_public_key",
+                Value::from(self.cluster_bls_public_key.to_string()),
+            ),
+        ];
+        for (name, value) in &header {
+            row("archive", None, name, value);
+        }
+        let accounts = [
+            ("proposal", to_value(&self.proposal)),
+            ("commitment", to_value(&self.commitment)),
+            ("spend_histogram", to_value(&self.spend_histogram)),
+            ("write_in_tally", to_value(&self.write_in_tally)),
+        ];
+        for (section, account) in &accounts {
+            if let Value::Object(fields) = account {
+                for (name, value) in fields {
+                    row(section, None, name, value);
+                }
+            }
+        }
+        for event in &self.events {
+            let mut fields = to_value(&event.event);
+            let kind = fields
+                .as_object_mut()
+                .and_then(|fields| fields.remove("type"))
+                .unwrap_or_default();
+            row("event", Some(event), kind.as_str().unwrap_or_default(), &fields);
+        }
+        out
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("archives serialize")
+}
+
+/// Quote a CSV field if it needs it (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The message an exporter signs for the rendered archive `archive`:
+/// [`ARCHIVE_SIGNING_DOMAIN`] followed by its SHA-256.
+pub fn archive_message(archive: &[u8]) -> Vec<u8> {
+    [ARCHIVE_SIGNING_DOMAIN, &Sha256::digest(archive)].concat()
+}
+
+/// A detached signature over a rendered archive, kept next to it (by the
+/// CLI, as `<archive>.sig`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveSignature {
+    pub signer: types::Pubkey,
+    /// Ed25519 signature of [`archive_message`], base58.
+    pub signature: String,
+    /// SHA-256 of the archive, to spot a mismatched pair at a glance.
+    pub sha256: types::Bytes<32>,
+}
+
+impl ArchiveSignature {
+    pub fn new(archive: &[u8], signer: &Pubkey, signature: &Signature) -> Self {
+        Self {
+            signer: (*signer).into(),
+            signature: signature.to_string(),
+            sha256: types::Bytes(Sha256::digest(archive).into()),
+        }
+    }
+
+    /// Whether `signer` signed exactly `archive`.
+    pub fn verify(&self, archive: &[u8]) -> bool {
+        let Ok(signature) = Signature::from_str(&self.signature) else {
+            return false;
+        };
+        self.sha256.0 == <[u8; 32]>::from(Sha256::digest(archive))
+            && signature.verify(self.signer.0.as_ref(), &archive_message(archive))
+    }
+}
@@ -0,0 +1,4305 @@
+//! Instruction builders.  Each returns a ready-to-sign [`Instruction`];
+//! the `payer` must sign.
+
+use anchor_lang::{
+    solana_program::{
+        entrypoint::MAX_PERMITTED_DATA_INCREASE,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token, memo, token, token_2022};
+use arcium_client::ARCIUM_PROGRAM_ID;
+use private_voting::{
+    accounts, instruction, BatchedVote, GatingMode, IdentityCiphertexts, NullifierSet, ProposalAccount,
+};
+
+pub use private_voting::{
+    BallotKind, CategoryParams, ChamberRule, ConfigParams, DependencyCondition, OptionDetail, ProposalKind, QuorumKind,
+    SanctionAction, SlashReason, VoteOutcome, WeightCurve, BALLOT_BATCH_SIZE, MAX_BALLOT_PROPOSALS,
+};
+
+use crate::{
+    encryption::{
+        EncryptedBallotChoices, EncryptedCredits, EncryptedIdentity, EncryptedJuryScores, EncryptedVote,
+        EncryptedWriteIn,
+    },
+    pda::{
+        self, QueueAccounts, CIRCUIT_CAST_APPROVAL, CIRCUIT_CAST_BALLOT_CHOICES, CIRCUIT_CAST_BORDA,
+        CIRCUIT_CAST_CHOICE, CIRCUIT_CAST_CONDORCET, CIRCUIT_CAST_COUNCIL_CHOICE, CIRCUIT_CAST_JURY_SCORES,
+        CIRCUIT_CAST_LIKERT, CIRCUIT_CAST_PAIRWISE, CIRCUIT_CAST_RANGE, CIRCUIT_CAST_RANKED_VOTE,
+        CIRCUIT_CAST_ROUND_CHOICE, CIRCUIT_CAST_STAR, CIRCUIT_CAST_VOTE, CIRCUIT_CAST_VOTE_BANKED,
+        CIRCUIT_CAST_VOTE_BATCH, CIRCUIT_CAST_VOTE_BUDGETED, CIRCUIT_CAST_VOTE_HISTOGRAM, CIRCUIT_CAST_WRITE_IN,
+        CIRCUIT_INIT_COUNCIL_TALLIES, CIRCUIT_INIT_CREDIT_BANK, CIRCUIT_INIT_PREFERENCES, CIRCUIT_INIT_RANKINGS,
+        CIRCUIT_INIT_SPEND_HISTOGRAM, CIRCUIT_INIT_TALLIES, CIRCUIT_INIT_WRITE_INS, CIRCUIT_RELEASE_IDENTITY,
+        CIRCUIT_REVEAL_BUDGET_BOX, CIRCUIT_REVEAL_CHAMBERS, CIRCUIT_REVEAL_CONDORCET, CIRCUIT_REVEAL_OUTCOME,
+        CIRCUIT_REVEAL_PAIRWISE, CIRCUIT_REVEAL_QUORUM_PROGRESS, CIRCUIT_REVEAL_RANKED, CIRCUIT_REVEAL_RESULTS,
+        CIRCUIT_REVEAL_SPEND_HISTOGRAM, CIRCUIT_REVEAL_STAR, CIRCUIT_REVEAL_WRITE_IN, CIRCUIT_SEAL_BUDGET,
+    },
+    ArciumEnv, PROGRAM_ID,
+};
+
+/// Create the protocol config; `authority` must be the program's upgrade
+/// authority and sign alongside `payer`.
+pub fn initialize_config(payer: &Pubkey, authority: &Pubkey, params: ConfigParams) -> Instruction {
+    let accounts = accounts::InitializeConfig {
+        payer: *payer,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        program: PROGRAM_ID,
+        program_data: pda::program_data(),
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::InitializeConfig { params }.data(),
+    }
+}
+
+/// Replace the protocol config; `admin` must be the current admin.
+pub fn update_config(admin: &Pubkey, params: ConfigParams) -> Instruction {
+    let accounts = accounts::UpdateConfig {
+        admin: *admin,
+        config: pda::config_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::UpdateConfig { params }.data(),
+    }
+}
+
+/// Set `creator`'s registry flags: `whitelisted` for the higher per-epoch
+/// proposal cap, `allowed` for permissioned creation.  `admin` must be the
+/// config admin; `payer` funds a new registry entry.
+pub fn set_creator(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    creator: &Pubkey,
+    whitelisted: bool,
+    allowed: bool,
+) -> Instruction {
+    let accounts = accounts::SetCreator {
+        payer: *payer,
+        admin: *admin,
+        config: pda::config_pda().0,
+        creator_record: pda::creator_record_pda(creator).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetCreator {
+            creator: *creator,
+            whitelisted,
+            allowed,
+        }
+        .data(),
+    }
+}
+
+/// Create or update registry category `category`; `admin` must sign
+/// alongside `payer`, who funds a new entry.
+pub fn set_category(payer: &Pubkey, admin: &Pubkey, category: u16, params: CategoryParams) -> Instruction {
+    let accounts = accounts::SetCategory {
+        payer: *payer,
+        admin: *admin,
+        config: pda::config_pda().0,
+        category_acc: pda::category_pda(category).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetCategory { category, params }.data(),
+    }
+}
+
+/// File `proposal` under registry category `category`; `authority` must
+/// sign, before the first ballot.
+pub fn assign_category(authority: &Pubkey, proposal: &Pubkey, category: u16) -> Instruction {
+    let accounts = accounts::AssignCategory {
+        authority: *authority,
+        proposal_acc: *proposal,
+        category_acc: pda::category_pda(category).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::AssignCategory { category }.data(),
+    }
+}
+
+/// Group `proposals` of `authority`'s, which share a deadline and
+/// registration deadline, into ballot `id`; `authority` signs alongside
+/// `payer`, who funds the ballot.  [`create_ballot_proposals`] builds it
+/// together with the proposals.
+pub fn create_ballot(payer: &Pubkey, authority: &Pubkey, id: u32, title: String, proposals: &[Pubkey]) -> Instruction {
+    let accounts = accounts::CreateBallot {
+        payer: *payer,
+        authority: *authority,
+        ballot_acc: pda::ballot_pda(authority, id).0,
+        system_program: system_program::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(proposals.iter().map(|proposal| AccountMeta::new(*proposal, false)));
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data: instruction::CreateBallot { id, title }.data(),
+    }
+}
+
+/// [`create_proposal`] for each of `proposals`, at its computation offset,
+/// then [`create_ballot`] grouping them.  Sent in one transaction,
+/// compiled against the MXE lookup table to fit more than a couple of
+/// proposals, they and their queued tally initializations land together
+/// or not at all.
+pub fn create_ballot_proposals(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    id: u32,
+    title: String,
+    proposals: Vec<(u64, CreateProposalParams)>,
+) -> Vec<Instruction> {
+    let addresses: Vec<_> = proposals
+        .iter()
+        .map(|(_, params)| pda::proposal_pda(authority, params.id).0)
+        .collect();
+    let mut ixs: Vec<_> = proposals
+        .into_iter()
+        .map(|(computation_offset, params)| create_proposal(env, payer, authority, computation_offset, params))
+        .collect();
+    ixs.push(create_ballot(payer, authority, id, title, &addresses));
+    ixs
+}
+
+/// Have `authority`'s ballot `ballot_id` record each voter's ballots on
+/// its `proposals`, given in the ballot's order, in one `RoundRecord`;
+/// they are then voted with [`cast_round_choice`].
+pub fn open_round_records(authority: &Pubkey, ballot_id: u32, proposals: &[Pubkey]) -> Instruction {
+    let accounts = accounts::OpenRoundRecords {
+        authority: *authority,
+        ballot_acc: pda::ballot_pda(authority, ballot_id).0,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(proposals.iter().map(|proposal| AccountMeta::new(*proposal, false)));
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data: instruction::OpenRoundRecords {}.data(),
+    }
+}
+
+/// Checkpoint `proposal`'s encrypted tally into its `Commitment`, which
+/// `payer` funds on the first call.  Permissionless.
+pub fn commit_tally(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::CommitTally {
+        payer: *payer,
+        proposal_acc: *proposal,
+        tally_acc: pda::tally_pda(proposal).0,
+        commitment: pda::commitment_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CommitTally {}.data(),
+    }
+}
+
+/// Budget each voter at the square root of their `credit_mint` balance,
+/// registered with [`register_credits`].  `authority` must be the proposal
+/// authority and sign, before the first vote.
+pub fn open_sqrt_credits(authority: &Pubkey, proposal: &Pubkey, credit_mint: &Pubkey) -> Instruction {
+    let accounts = accounts::OpenSqrtCredits {
+        authority: *authority,
+        proposal_acc: *proposal,
+        credit_mint: *credit_mint,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenSqrtCredits {}.data(),
+    }
+}
+
+/// Record `voter`'s square-root credits from `voter_token_account`, their
+/// account of the proposal's `credit_mint` (see [`quorum_token_account`]),
+/// whose whole balance is escrowed until the deadline; `token_program`
+/// owns the mint.  `voter` signs and pays the rent.
+pub fn register_credits(
+    voter: &Pubkey,
+    proposal: &Pubkey,
+    voter_token_account: &Pubkey,
+    credit_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let voter_credits = pda::voter_credits_pda(proposal, voter).0;
+    let accounts = accounts::RegisterCredits {
+        voter: *voter,
+        proposal_acc: *proposal,
+        voter_token_account: *voter_token_account,
+        credit_mint: *credit_mint,
+        voter_credits,
+        credit_vault: pda::credit_vault_pda(&voter_credits).0,
+        token_program: *token_program,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::RegisterCredits {}.data(),
+    }
+}
+
+/// Return the tokens `voter` escrowed with [`register_credits`] to
+/// `voter_token_account` once the deadline has passed.  `scope` is the
+/// proposal, or its ballot; `voter` signs and gets the rent back.
+pub fn release_credits(
+    voter: &Pubkey,
+    scope: &Pubkey,
+    voter_token_account: &Pubkey,
+    credit_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let voter_credits = pda::voter_credits_pda(scope, voter).0;
+    let accounts = accounts::ReleaseCredits {
+        voter: *voter,
+        voter_credits,
+        credit_mint: *credit_mint,
+        credit_vault: pda::credit_vault_pda(&voter_credits).0,
+        voter_token_account: *voter_token_account,
+        token_program: *token_program,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ReleaseCredits {}.data(),
+    }
+}
+
+/// Point `ix` — `register_credits` or a ballot cast on `proposal` — at
+/// `voter`'s credits registered with `ballot`, the proposal's `on_ballot`,
+/// instead of with the proposal.
+pub fn with_ballot_credits(mut ix: Instruction, proposal: &Pubkey, ballot: &Pubkey, voter: &Pubkey) -> Instruction {
+    let own = pda::voter_credits_pda(proposal, voter).0;
+    let shared = pda::voter_credits_pda(ballot, voter).0;
+    let own_vault = pda::credit_vault_pda(&own).0;
+    let shared_vault = pda::credit_vault_pda(&shared).0;
+    for meta in ix.accounts.iter_mut() {
+        if meta.pubkey == own {
+            meta.pubkey = shared;
+        } else if meta.pubkey == own_vault {
+            meta.pubkey = shared_vault;
+        }
+    }
+    ix
+}
+
+/// Budget `voter` at `credits` voice credits on a quadratic or
+/// time-weighted proposal; from the first call, voters nobody funded cannot
+/// vote.  `authority` must be the proposal authority and sign, before the
+/// deadline; `payer` pays the rent.
+pub fn fund_credits(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    voter: &Pubkey,
+    credits: u64,
+) -> Instruction {
+    let accounts = accounts::FundCredits {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        credit_account: pda::credit_account_pda(proposal, voter).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::FundCredits { voter: *voter, credits }.data(),
+    }
+}
+
+/// Make every ballot burn one whole `burn_mint` token from the voter (see
+/// [`cast_vote_burning`]).  `authority` must be the proposal authority and
+/// sign, before the first vote.
+pub fn open_burn_to_vote(authority: &Pubkey, proposal: &Pubkey, burn_mint: &Pubkey) -> Instruction {
+    let accounts = accounts::OpenBurnToVote {
+        authority: *authority,
+        proposal_acc: *proposal,
+        burn_mint: *burn_mint,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenBurnToVote {}.data(),
+    }
+}
+
+/// Have every `cast_vote` ask `oracle`, an external program implementing
+/// `can_vote`, for the ballot's weight (see [`cast_vote_with_oracle`]).
+/// `authority` must be the proposal authority and sign, before the first
+/// vote.
+pub fn set_eligibility_oracle(authority: &Pubkey, proposal: &Pubkey, oracle: &Pubkey) -> Instruction {
+    let accounts = accounts::SetEligibilityOracle {
+        authority: *authority,
+        proposal_acc: *proposal,
+        oracle: *oracle,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetEligibilityOracle {}.data(),
+    }
+}
+
+/// Bend the eligibility oracle's weights, or the token balances, with
+/// `curve` inside the `cast_vote` circuit.  `authority` must be the
+/// proposal authority and sign, after [`set_eligibility_oracle`] or
+/// [`open_token_weights`] and before the first vote.
+pub fn set_weight_curve(authority: &Pubkey, proposal: &Pubkey, curve: WeightCurve) -> Instruction {
+    let accounts = accounts::SetWeightCurve {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetWeightCurve { curve }.data(),
+    }
+}
+
+/// Weigh every `cast_vote` by the voter's whole tokens of `weight_mint`,
+/// up to `max_weight` (see [`cast_vote_token_weighted`]).  `authority`
+/// must be the proposal authority and sign, before the first vote.
+pub fn open_token_weights(authority: &Pubkey, proposal: &Pubkey, weight_mint: &Pubkey, max_weight: u64) -> Instruction {
+    let accounts = accounts::OpenTokenWeights {
+        authority: *authority,
+        proposal_acc: *proposal,
+        weight_mint: *weight_mint,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenTokenWeights { max_weight }.data(),
+    }
+}
+
+/// Withdraw collected fees to `recipient`; `admin` must be the config admin.
+pub fn withdraw_fees(admin: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+    let accounts = accounts::WithdrawFees {
+        admin: *admin,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        recipient: *recipient,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::WithdrawFees { amount }.data(),
+    }
+}
+
+/// Send `proposal`'s deposit to `treasury` (the config's); `council` must be
+/// the config council.
+pub fn slash_deposit(
+    council: &Pubkey,
+    proposal: &Pubkey,
+    treasury: &Pubkey,
+    reason: SlashReason,
+) -> Instruction {
+    let accounts = accounts::SlashDeposit {
+        council: *council,
+        config: pda::config_pda().0,
+        proposal_acc: *proposal,
+        treasury: *treasury,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SlashDeposit { reason }.data(),
+    }
+}
+
+/// Parameters for a new proposal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateProposalParams {
+    pub id: u32,
+    pub title: String,
+    pub options: Vec<String>,
+    pub deadline: i64,
+    /// Nonzero to leave `options` empty and fill them by
+    /// [`register_candidate`] until this time, when voting opens.
+    pub registration_deadline: i64,
+    pub voice_credits: u64,
+    /// Voter count, or basis points per `quorum_kind`.
+    pub quorum: u32,
+    pub quorum_kind: QuorumKind,
+    /// Registered voter count for [`QuorumKind::RegisteredBps`].
+    pub electorate: u64,
+    /// Mint whose supply is the denominator for [`QuorumKind::SupplyBps`].
+    pub quorum_mint: Option<Pubkey>,
+    /// Mint whose holders alone may vote, under `GatingMode::Token`.
+    pub gate_mint: Option<Pubkey>,
+    /// Minimum `gate_mint` balance to vote; zero without a gate mint.
+    pub gate_threshold: u64,
+    /// Quadratic allocations, or Likert scores cast with [`cast_likert`].
+    pub ballot: BallotKind,
+    /// Nonce for the MXE-encrypted tallies.
+    pub nonce: u128,
+}
+
+/// `authority` becomes the proposal authority and signs alongside `payer`;
+/// pass the same key for both unless, say, a multisig vault owns the
+/// proposal while a member pays.
+pub fn create_proposal(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    computation_offset: u64,
+    params: CreateProposalParams,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_TALLIES, computation_offset);
+    let proposal = pda::proposal_pda(authority, params.id).0;
+    let accounts = accounts::CreateProposal {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        creator_record: pda::creator_record_pda(authority).0,
+        closed_proposal: pda::closed_proposal_pda(&proposal).0,
+        quorum_mint: params.quorum_mint,
+        gate_mint: params.gate_mint,
+    };
+    let data = instruction::CreateProposal {
+        computation_offset,
+        id: params.id,
+        title: params.title,
+        num_options: params.options.len() as u8,
+        options: params.options,
+        deadline: params.deadline,
+        registration_deadline: params.registration_deadline,
+        voice_credits: params.voice_credits,
+        quorum: params.quorum,
+        quorum_kind: params.quorum_kind,
+        electorate: params.electorate,
+        gate_threshold: params.gate_threshold,
+        ballot: params.ballot,
+        nonce: params.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Parameters for a new poll: a proposal without quorum or deposit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatePollParams {
+    pub id: u32,
+    pub title: String,
+    pub options: Vec<String>,
+    pub deadline: i64,
+    pub voice_credits: u64,
+    pub ballot: BallotKind,
+    /// Nonce for the MXE-encrypted tallies.
+    pub nonce: u128,
+}
+
+/// Signers as for [`create_proposal`].  Anyone may reveal the poll with
+/// [`reveal_poll`] after its deadline.
+pub fn create_poll(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    computation_offset: u64,
+    params: CreatePollParams,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_TALLIES, computation_offset);
+    let proposal = pda::proposal_pda(authority, params.id).0;
+    let accounts = accounts::CreateProposal {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        creator_record: pda::creator_record_pda(authority).0,
+        closed_proposal: pda::closed_proposal_pda(&proposal).0,
+        quorum_mint: None,
+        gate_mint: None,
+    };
+    let data = instruction::CreatePoll {
+        computation_offset,
+        id: params.id,
+        title: params.title,
+        num_options: params.options.len() as u8,
+        options: params.options,
+        deadline: params.deadline,
+        voice_credits: params.voice_credits,
+        ballot: params.ballot,
+        nonce: params.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Stand as a candidate in `proposal` while its registration is open;
+/// `candidate` signs and pays the rent and the config's candidate deposit.
+pub fn register_candidate(candidate: &Pubkey, proposal: &Pubkey, name: String) -> Instruction {
+    let accounts = accounts::RegisterCandidate {
+        candidate: *candidate,
+        proposal_acc: *proposal,
+        candidate_acc: pda::candidate_pda(proposal, candidate).0,
+        config: pda::config_pda().0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::RegisterCandidate { name }.data(),
+    }
+}
+
+/// Reclaim a candidacy's rent and deposit once `proposal` is finalized,
+/// failed quorum or was closed.
+pub fn close_candidate(candidate: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::CloseCandidate {
+        candidate: *candidate,
+        proposal_acc: *proposal,
+        candidate_acc: pda::candidate_pda(proposal, candidate).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CloseCandidate {}.data(),
+    }
+}
+
+/// Make `proposal` an amendment that takes no ballots until `parent` has
+/// finalized with option `winner` winning; `authority` must sign, before
+/// the first ballot.
+pub fn require_parent_outcome(authority: &Pubkey, proposal: &Pubkey, parent: &Pubkey, winner: u8) -> Instruction {
+    let accounts = accounts::RequireParentOutcome {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::RequireParentOutcome {
+            parent: *parent,
+            winner,
+        }
+        .data(),
+    }
+}
+
+/// Open an amendment for voting once its `parent` finalized with the
+/// required winner.  Permissionless; `proposal` need not be the signer's.
+pub fn confirm_parent_outcome(proposal: &Pubkey, parent: &Pubkey) -> Instruction {
+    let accounts = accounts::ConfirmParentOutcome {
+        proposal_acc: *proposal,
+        parent_acc: *parent,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ConfirmParentOutcome {}.data(),
+    }
+}
+
+/// Describe what each option of `proposal` pays or points to, replacing
+/// any details set before; `details[i]` is option i's.  `authority` must
+/// sign alongside `payer`, before the first ballot.
+pub fn set_option_details(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    details: Vec<OptionDetail>,
+) -> Instruction {
+    let accounts = accounts::SetOptionDetails {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        option_details: pda::option_details_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetOptionDetails { details }.data(),
+    }
+}
+
+/// Add or replace `proposal`'s title and option labels in `locale`, a BCP
+/// 47 tag such as `pt-BR`; `options[i]` translates option i.  `authority`
+/// must sign alongside `payer`, at any time.
+pub fn set_translation(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    locale: String,
+    title: String,
+    options: Vec<String>,
+) -> Instruction {
+    let accounts = accounts::SetTranslation {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        translations: pda::translations_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetTranslation { locale, title, options }.data(),
+    }
+}
+
+/// Drop `proposal`'s translation into `locale`; `authority` must sign.
+pub fn remove_translation(authority: &Pubkey, proposal: &Pubkey, locale: String) -> Instruction {
+    let accounts = accounts::RemoveTranslation {
+        authority: *authority,
+        proposal_acc: *proposal,
+        translations: pda::translations_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::RemoveTranslation { locale }.data(),
+    }
+}
+
+/// Keep `proposal` closed to ballots until its For/Against/Abstain `parent`
+/// is decided as `condition` requires.  `authority` must sign alongside
+/// `payer`, before the first ballot.
+pub fn declare_dependency(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    parent: &Pubkey,
+    condition: DependencyCondition,
+) -> Instruction {
+    let accounts = accounts::DeclareDependency {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        parent_acc: *parent,
+        dependency: pda::dependency_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::DeclareDependency { condition }.data(),
+    }
+}
+
+/// Open a chained proposal once its `parent` was decided as its
+/// `Dependency` requires.  Permissionless.
+pub fn resolve_dependency(proposal: &Pubkey, parent: &Pubkey) -> Instruction {
+    let accounts = accounts::ResolveDependency {
+        proposal_acc: *proposal,
+        parent_acc: *parent,
+        dependency: pda::dependency_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ResolveDependency {}.data(),
+    }
+}
+
+/// Put `action` to the vote on the For/Against/Abstain `proposal`.
+/// `authority` must sign alongside `payer`, before the first ballot.
+pub fn declare_sanction(payer: &Pubkey, authority: &Pubkey, proposal: &Pubkey, action: SanctionAction) -> Instruction {
+    let accounts = accounts::DeclareSanction {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        sanction: pda::sanction_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::DeclareSanction { action }.data(),
+    }
+}
+
+/// Carry out the `action` `proposal` passed.  Permissionless; `treasury`
+/// must be `Config.treasury` and is only used by a `SlashDeposit`.
+pub fn execute_sanction(proposal: &Pubkey, action: &SanctionAction, treasury: &Pubkey) -> Instruction {
+    let (target_acc, treasury, creator_record) = match action {
+        SanctionAction::SlashDeposit { proposal, .. } => (Some(*proposal), Some(*treasury), None),
+        SanctionAction::RemoveCreator { creator } => (None, None, Some(pda::creator_record_pda(creator).0)),
+    };
+    let accounts = accounts::ExecuteSanction {
+        proposal_acc: *proposal,
+        sanction: pda::sanction_pda(proposal).0,
+        config: pda::config_pda().0,
+        target_acc,
+        treasury,
+        creator_record,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ExecuteSanction {}.data(),
+    }
+}
+
+/// Seat `juror` on a [`BallotKind::Jury`] proposal's panel before its
+/// deadline; `authority` must sign alongside `payer`, who funds the seat.
+pub fn seat_juror(payer: &Pubkey, authority: &Pubkey, proposal: &Pubkey, juror: &Pubkey) -> Instruction {
+    let accounts = accounts::SeatJuror {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        juror_acc: pda::juror_pda(proposal, juror).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SeatJuror { juror: *juror }.data(),
+    }
+}
+
+/// Reclaim a juror seat's rent once `proposal` is finalized, failed quorum
+/// or was closed; `authority` signs and receives it.
+pub fn close_juror(authority: &Pubkey, proposal: &Pubkey, juror: &Pubkey) -> Instruction {
+    let accounts = accounts::CloseJuror {
+        authority: *authority,
+        proposal_acc: *proposal,
+        juror_acc: pda::juror_pda(proposal, juror).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CloseJuror {}.data(),
+    }
+}
+
+/// Gate a quadratic proposal behind credentials issued by `registrar`,
+/// whose BLS key is `registrar_key` (see
+/// [`RegistrarKey::public_key`](crate::credential::RegistrarKey::public_key)),
+/// before its first ballot; `authority` must sign alongside `payer`.
+pub fn open_credentials(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    registrar: &Pubkey,
+    registrar_key: [u8; 128],
+) -> Instruction {
+    let accounts = accounts::OpenCredentials {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        credential_registry: pda::credential_registry_pda(proposal).0,
+        config: pda::config_pda().0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenCredentials {
+            registrar: *registrar,
+            registrar_key,
+        }
+        .data(),
+    }
+}
+
+/// Post the registrar's `blind_signature` on `voter`'s blinded message; the
+/// `registrar` signs and funds the issuance.
+pub fn issue_credential(
+    registrar: &Pubkey,
+    proposal: &Pubkey,
+    voter: &Pubkey,
+    blinded_message: [u8; 64],
+    blind_signature: [u8; 64],
+) -> Instruction {
+    let accounts = accounts::IssueCredential {
+        registrar: *registrar,
+        proposal_acc: *proposal,
+        credential_registry: pda::credential_registry_pda(proposal).0,
+        issuance: pda::credential_issuance_pda(proposal, voter).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::IssueCredential {
+            voter: *voter,
+            blinded_message,
+            blind_signature,
+        }
+        .data(),
+    }
+}
+
+/// Revoke the credential with `serial`; the `registrar` signs.
+pub fn revoke_credential(registrar: &Pubkey, proposal: &Pubkey, serial: [u8; 32]) -> Instruction {
+    let accounts = accounts::RevokeCredential {
+        registrar: *registrar,
+        proposal_acc: *proposal,
+        credential_registry: pda::credential_registry_pda(proposal).0,
+        credential: pda::credential_pda(proposal, &serial).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::RevokeCredential { serial }.data(),
+    }
+}
+
+/// Present an unblinded credential so `payer` may
+/// [`cast_vote_with_credential`].  Send it from a wallet unconnected to the
+/// registered one, or the ballot is linkable again.
+pub fn present_credential(payer: &Pubkey, proposal: &Pubkey, serial: [u8; 32], signature: [u8; 64]) -> Instruction {
+    let accounts = accounts::PresentCredential {
+        payer: *payer,
+        proposal_acc: *proposal,
+        credential_registry: pda::credential_registry_pda(proposal).0,
+        credential: pda::credential_pda(proposal, &serial).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::PresentCredential { serial, signature }.data(),
+    }
+}
+
+/// View instruction: simulate it and decode the return data as a
+/// `CredentialStatus`.
+pub fn verify_credential(proposal: &Pubkey, serial: [u8; 32], signature: [u8; 64]) -> Instruction {
+    let accounts = accounts::VerifyCredential {
+        credential_registry: pda::credential_registry_pda(proposal).0,
+        credential: pda::credential_pda(proposal, &serial).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::VerifyCredential { serial, signature }.data(),
+    }
+}
+
+/// Let credentials vote anonymously through a `NullifierSet` sized for
+/// `max_voters`.  `authority` must be the proposal authority and sign
+/// alongside `payer`, who funds the first 10 KiB; follow up with
+/// [`grow_nullifier_set`] until [`nullifier_set_grows_left`] is 0.
+pub fn open_nullifier_set(payer: &Pubkey, authority: &Pubkey, proposal: &Pubkey, max_voters: u32) -> Instruction {
+    let accounts = accounts::OpenNullifierSet {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        credential_registry: pda::credential_registry_pda(proposal).0,
+        nullifier_set: pda::nullifier_set_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenNullifierSet { max_voters }.data(),
+    }
+}
+
+/// Extend the proposal's `NullifierSet` by 10 KiB; `payer` funds the rent.
+/// Several fit in one transaction.
+pub fn grow_nullifier_set(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::GrowNullifierSet {
+        payer: *payer,
+        nullifier_set: pda::nullifier_set_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::GrowNullifierSet {}.data(),
+    }
+}
+
+/// How many [`grow_nullifier_set`] calls a set whose account is `data_len`
+/// bytes still needs.
+pub fn nullifier_set_grows_left(set: &NullifierSet, data_len: usize) -> usize {
+    set.full_len().saturating_sub(data_len).div_ceil(MAX_PERMITTED_DATA_INCREASE)
+}
+
+/// View instruction: simulate it and decode the return data as a `bool`,
+/// whether `serial` was spent by [`cast_vote_anonymous`].
+pub fn check_nullifier(proposal: &Pubkey, serial: [u8; 32]) -> Instruction {
+    let accounts = accounts::CheckNullifier {
+        nullifier_set: pda::nullifier_set_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CheckNullifier { serial }.data(),
+    }
+}
+
+/// Make anonymous ballots seal their voter's registered wallet for
+/// release to `compliance_key` (x25519) once the proposal is decided.
+/// `payer` and `authority` must sign.
+pub fn open_compliance_roll(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    compliance_key: [u8; 32],
+) -> Instruction {
+    let accounts = accounts::OpenComplianceRoll {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        credential_registry: pda::credential_registry_pda(proposal).0,
+        compliance_roll: pda::compliance_roll_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenComplianceRoll { compliance_key }.data(),
+    }
+}
+
+/// Queue the re-encryption of the `SealedIdentity` at `sealed_identity` to
+/// the compliance key.  `payer` and `authority` must sign.
+pub fn release_identity(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    sealed_identity: &Pubkey,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_RELEASE_IDENTITY, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::ReleaseIdentity {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        compliance_roll: pda::compliance_roll_pda(&proposal).0,
+        sealed_identity: *sealed_identity,
+    };
+    let data = instruction::ReleaseIdentity {
+        computation_offset,
+        _id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Reclaim a `ComplianceRoll`'s rent once every identity was released;
+/// `authority` signs.
+pub fn close_compliance_roll(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::CloseComplianceRoll {
+        authority: *authority,
+        proposal_acc: *proposal,
+        compliance_roll: pda::compliance_roll_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CloseComplianceRoll {}.data(),
+    }
+}
+
+/// Reclaim a settled proposal's `NullifierSet` rent; `authority` signs.
+pub fn close_nullifier_set(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::CloseNullifierSet {
+        authority: *authority,
+        proposal_acc: *proposal,
+        nullifier_set: pda::nullifier_set_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CloseNullifierSet {}.data(),
+    }
+}
+
+/// Gate a quadratic proposal behind voting history before its first
+/// ballot: `min_prior_ballots` counted ballots elsewhere and, attested by
+/// `attestor`, `min_wallet_age` seconds since the wallet's first
+/// transaction.  `authority` must sign alongside `payer`.
+pub fn open_history_gate(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    attestor: Option<Pubkey>,
+    min_wallet_age: i64,
+    min_prior_ballots: u32,
+) -> Instruction {
+    let accounts = accounts::OpenHistoryGate {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        history_gate: pda::history_gate_pda(proposal).0,
+        config: pda::config_pda().0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenHistoryGate {
+            attestor,
+            min_wallet_age,
+            min_prior_ballots,
+        }
+        .data(),
+    }
+}
+
+/// Vouch for `voter`'s first transaction at `first_seen` and its
+/// `prior_ballots`; the `attestor` signs and funds the credential.
+pub fn attest_history(
+    attestor: &Pubkey,
+    proposal: &Pubkey,
+    voter: &Pubkey,
+    first_seen: i64,
+    prior_ballots: u32,
+) -> Instruction {
+    let accounts = accounts::AttestHistory {
+        attestor: *attestor,
+        proposal_acc: *proposal,
+        history_gate: pda::history_gate_pda(proposal).0,
+        credential: pda::credential_pda(proposal, &voter.to_bytes()).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::AttestHistory {
+            voter: *voter,
+            first_seen,
+            prior_ballots,
+        }
+        .data(),
+    }
+}
+
+/// Qualify `voter` on its `VoterRecord`s from other proposals (see
+/// `rpc::fetch_voter_records`).  Either way
+/// admitted, the wallet votes with [`cast_vote_with_credential`], its key
+/// as the serial.
+pub fn prove_history(voter: &Pubkey, proposal: &Pubkey, voter_records: &[Pubkey]) -> Instruction {
+    let accounts = accounts::ProveHistory {
+        voter: *voter,
+        proposal_acc: *proposal,
+        history_gate: pda::history_gate_pda(proposal).0,
+        credential: pda::credential_pda(proposal, &voter.to_bytes()).0,
+        system_program: system_program::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(
+        voter_records
+            .iter()
+            .map(|record| AccountMeta::new_readonly(*record, false)),
+    );
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data: instruction::ProveHistory {}.data(),
+    }
+}
+
+/// Gate a quadratic proposal behind a list of up to `capacity` wallets
+/// (at most `MAX_ALLOWLIST_VOTERS`) before its first ballot, kept with
+/// [`add_voter`] and [`remove_voter`].  `authority` must sign alongside
+/// `payer`.
+pub fn open_allowlist(payer: &Pubkey, authority: &Pubkey, proposal: &Pubkey, capacity: u16) -> Instruction {
+    let accounts = accounts::OpenAllowlist {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        allowlist: pda::allowlist_pda(proposal).0,
+        config: pda::config_pda().0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenAllowlist { capacity }.data(),
+    }
+}
+
+/// List `voter` on an allowlisted proposal; `authority` must sign.
+pub fn add_voter(authority: &Pubkey, proposal: &Pubkey, voter: &Pubkey) -> Instruction {
+    let accounts = accounts::AddVoter {
+        authority: *authority,
+        proposal_acc: *proposal,
+        allowlist: pda::allowlist_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::AddVoter { voter: *voter }.data(),
+    }
+}
+
+/// Strike `voter` off an allowlisted proposal's list; `authority` must
+/// sign.
+pub fn remove_voter(authority: &Pubkey, proposal: &Pubkey, voter: &Pubkey) -> Instruction {
+    let accounts = accounts::RemoveVoter {
+        authority: *authority,
+        proposal_acc: *proposal,
+        allowlist: pda::allowlist_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::RemoveVoter { voter: *voter }.data(),
+    }
+}
+
+/// Restrict an open proposal to holders of an NFT of the Metaplex-verified
+/// `collection`, one ballot per NFT; `authority` must sign.
+pub fn open_collection_gate(authority: &Pubkey, proposal: &Pubkey, collection: &Pubkey) -> Instruction {
+    let accounts = accounts::OpenCollectionGate {
+        authority: *authority,
+        proposal_acc: *proposal,
+        config: pda::config_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenCollectionGate { collection: *collection }.data(),
+    }
+}
+
+/// Hold ballots cast in the last `window` seconds before the deadline until
+/// voting closes.  `authority` must be the proposal authority and sign
+/// alongside `payer`, who funds the `MixQueue`.
+pub fn open_mix_window(payer: &Pubkey, authority: &Pubkey, proposal_id: u32, window: i64) -> Instruction {
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::OpenMixWindow {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: proposal,
+        mix_queue: pda::mix_queue_pda(&proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenMixWindow { window }.data(),
+    }
+}
+
+/// Let voters buffer quadratic ballots with [`submit_ballot`], to be folded
+/// into the tallies a batch at a time.  Only before the first ballot;
+/// `authority` must be the proposal authority and sign alongside `payer`.
+pub fn open_ballot_buffer(payer: &Pubkey, authority: &Pubkey, proposal_id: u32) -> Instruction {
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::OpenBallotBuffer {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: proposal,
+        ballot_buffer: pda::ballot_buffer_pda(&proposal).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenBallotBuffer {}.data(),
+    }
+}
+
+/// Apply one held ballot of a closed proposal.  Permissionless; `payer`
+/// only signs and pays the fee.
+pub fn release_held_ballot(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::ReleaseHeldBallot {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        mix_queue: pda::mix_queue_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    let data = instruction::ReleaseHeldBallot {
+        computation_offset,
+        _id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Cast a quadratic ballot into the proposal's ballot buffer; no
+/// computation is queued until [`process_ballot_batch`].  `credential` is
+/// the presented `BallotCredential` on a gated proposal.
+pub fn submit_ballot(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+    credential: Option<&Pubkey>,
+) -> Instruction {
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::SubmitBallot {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: proposal,
+        ballot_buffer: pda::ballot_buffer_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        credential: credential.copied(),
+        voter_credits: Some(pda::voter_credits_pda(&proposal, payer).0),
+        credit_account: Some(pda::credit_account_pda(&proposal, payer).0),
+        allowlist: Some(pda::allowlist_pda(&proposal).0),
+        system_program: system_program::ID,
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::SubmitBallot {
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Fold up to four buffered ballots into the tallies with one computation;
+/// fewer only once voting has closed.  Permissionless; `payer` only signs
+/// and pays the fee.
+pub fn process_ballot_batch(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE_BATCH, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::ProcessBallotBatch {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        ballot_buffer: pda::ballot_buffer_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    let data = instruction::ProcessBallotBatch {
+        computation_offset,
+        _id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Take ballot commitments until `commit_deadline`, and after it only the
+/// ballots committed to.  `authority` must be the proposal authority and
+/// sign.
+pub fn open_commit_phase(authority: &Pubkey, proposal_id: u32, commit_deadline: i64) -> Instruction {
+    let accounts = accounts::OpenCommitPhase {
+        authority: *authority,
+        proposal_acc: pda::proposal_pda(authority, proposal_id).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenCommitPhase { commit_deadline }.data(),
+    }
+}
+
+/// Commit `voter` to casting `vote` once the commit phase ends.  Encrypt it
+/// under [`EncryptionKeypair::commit_nonce`](crate::encryption::EncryptionKeypair::commit_nonce)
+/// so the same allocation encrypts to the same ballot again.  `voter` must
+/// sign.
+pub fn commit_ballot(voter: &Pubkey, proposal: &Pubkey, vote: &EncryptedVote) -> Instruction {
+    let accounts = accounts::CommitBallot {
+        voter: *voter,
+        proposal_acc: *proposal,
+        ballot_commitment: pda::ballot_commitment_pda(proposal, voter).0,
+        system_program: system_program::ID,
+    };
+    let commitment = private_voting::ballot_hash(proposal, voter, &vote.ciphertexts, &vote.public_key, vote.nonce);
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CommitBallot { commitment }.data(),
+    }
+}
+
+/// Collect a spend histogram on a quadratic proposal; `authority` must be
+/// the proposal authority and sign alongside `payer`.  Ballots then go
+/// through [`cast_vote_histogram`].
+pub fn open_spend_histogram(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_SPEND_HISTOGRAM, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::OpenSpendHistogram {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        spend_histogram: pda::spend_histogram_pda(&proposal).0,
+    };
+    let data = instruction::OpenSpendHistogram {
+        computation_offset,
+        _id: proposal_id,
+        nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], on a proposal with a spend histogram.
+pub fn cast_vote_histogram(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE_HISTOGRAM, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastVoteHistogram {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        spend_histogram: pda::spend_histogram_pda(&proposal).0,
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastVoteHistogram {
+        computation_offset,
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Reveal the noised spend histogram of a finalized proposal; `authority`
+/// must be the proposal authority and sign alongside `payer`.
+pub fn reveal_spend_histogram(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_SPEND_HISTOGRAM, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealSpendHistogram {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        spend_histogram: pda::spend_histogram_pda(&proposal).0,
+    };
+    let data = instruction::RevealSpendHistogram {
+        computation_offset,
+        _id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Make a quadratic proposal's ballots draw on each voter's
+/// `VoterCreditBank` with the authority (see [`cast_vote_banked`]).
+/// `authority` must be the proposal authority and sign, before the first
+/// vote.
+pub fn open_credit_bank(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::OpenCreditBank {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenCreditBank {}.data(),
+    }
+}
+
+/// Create `payer`'s credit bank with the authority of a banked proposal,
+/// its balance encrypted at 0; `payer` signs and pays the rent.
+pub fn create_credit_bank(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_CREDIT_BANK, computation_offset);
+    let accounts = accounts::CreateCreditBank {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: pda::proposal_pda(authority, proposal_id).0,
+        global_stats: pda::global_stats_pda().0,
+        credit_bank: pda::credit_bank_pda(authority, payer).0,
+    };
+    let data = instruction::CreateCreditBank {
+        computation_offset,
+        _id: proposal_id,
+        nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], on a banked proposal: the budget is the payer's
+/// banked credits plus the proposal's `voice_credits`.
+pub fn cast_vote_banked(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE_BANKED, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastVoteBanked {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        credit_bank: pda::credit_bank_pda(authority, payer).0,
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastVoteBanked {
+        computation_offset,
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Give `voter` a confidential budget on a quadratic or time-weighted
+/// proposal; from the first call, ballots go through
+/// [`cast_vote_budgeted`].  `budget` is encrypted under the proposal
+/// authority's key (see [`crate::encryption::encrypt_credits`]);
+/// `authority` must sign, once per voter, before the deadline; `payer`
+/// pays the rent.
+#[allow(clippy::too_many_arguments)]
+pub fn fund_encrypted_budget(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    computation_offset: u64,
+    voter: &Pubkey,
+    budget: &EncryptedCredits,
+    nonce: u128,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_SEAL_BUDGET, computation_offset);
+    let accounts = accounts::FundEncryptedBudget {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: *proposal,
+        global_stats: pda::global_stats_pda().0,
+        encrypted_budget: pda::encrypted_budget_pda(proposal, voter).0,
+    };
+    let data = instruction::FundEncryptedBudget {
+        computation_offset,
+        voter: *voter,
+        budget: budget.ciphertext,
+        budget_encryption_pubkey: budget.public_key,
+        budget_nonce: budget.nonce,
+        nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], on a proposal with encrypted budgets: the budget is
+/// the payer's `EncryptedBudget`.
+pub fn cast_vote_budgeted(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE_BUDGETED, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastVoteBudgeted {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        encrypted_budget: pda::encrypted_budget_pda(&proposal, payer).0,
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastVoteBudgeted {
+        computation_offset,
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Open a [`BallotKind::Ranked`] proposal for ballots; `authority` must
+/// be the proposal authority and sign alongside `payer`.  Ballots then go
+/// through [`cast_ranked_vote`].
+pub fn open_rankings(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_RANKINGS, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::OpenRankings {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        rankings: pda::rankings_pda(&proposal).0,
+    };
+    let data = instruction::OpenRankings {
+        computation_offset,
+        _id: proposal_id,
+        nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Ranked`] proposal; encrypt the
+/// ranking from [`VoteAllocation::ranking`](crate::encryption::VoteAllocation::ranking).
+pub fn cast_ranked_vote(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_RANKED_VOTE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastRankedVote {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        rankings: pda::rankings_pda(&proposal).0,
+    };
+    let [rank_0, rank_1, rank_2, rank_3] = vote.ciphertexts;
+    let data = instruction::CastRankedVote {
+        computation_offset,
+        _id: proposal_id,
+        rank_0,
+        rank_1,
+        rank_2,
+        rank_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`reveal_results`], for a [`BallotKind::Ranked`] proposal.
+pub fn reveal_ranked(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_RANKED, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealRanked {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        rankings: pda::rankings_pda(&proposal).0,
+    };
+    let data = instruction::RevealRanked {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Open a [`BallotKind::Condorcet`] or [`BallotKind::Star`] proposal for
+/// ballots; `authority` must be the proposal authority and sign alongside
+/// `payer`.  Ballots then go through [`cast_condorcet`] or [`cast_star`].
+pub fn open_preferences(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_PREFERENCES, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::OpenPreferences {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        preferences: pda::preferences_pda(&proposal).0,
+    };
+    let data = instruction::OpenPreferences {
+        computation_offset,
+        _id: proposal_id,
+        nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Condorcet`] proposal; encrypt the
+/// ranking from [`VoteAllocation::ranking`](crate::encryption::VoteAllocation::ranking).
+pub fn cast_condorcet(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_CONDORCET, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastCondorcet {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        preferences: pda::preferences_pda(&proposal).0,
+    };
+    let [rank_0, rank_1, rank_2, rank_3] = vote.ciphertexts;
+    let data = instruction::CastCondorcet {
+        computation_offset,
+        _id: proposal_id,
+        rank_0,
+        rank_1,
+        rank_2,
+        rank_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`reveal_results`], for a [`BallotKind::Condorcet`] proposal.
+pub fn reveal_condorcet(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_CONDORCET, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealCondorcet {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        preferences: pda::preferences_pda(&proposal).0,
+    };
+    let data = instruction::RevealCondorcet {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Star`] proposal; encrypt the
+/// scores from [`VoteAllocation::star`](crate::encryption::VoteAllocation::star).
+pub fn cast_star(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_STAR, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastStar {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        preferences: pda::preferences_pda(&proposal).0,
+    };
+    let [score_0, score_1, score_2, score_3] = vote.ciphertexts;
+    let data = instruction::CastStar {
+        computation_offset,
+        _id: proposal_id,
+        score_0,
+        score_1,
+        score_2,
+        score_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`reveal_results`], for a [`BallotKind::Star`] proposal.
+pub fn reveal_star(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_STAR, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealStar {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+        preferences: pda::preferences_pda(&proposal).0,
+    };
+    let data = instruction::RevealStar {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Let `session_key` vote for `wallet` on `proposal` with
+/// [`cast_vote_session`] until `expires_at`; the `wallet` signs and pays.
+pub fn open_session(wallet: &Pubkey, proposal: &Pubkey, session_key: &Pubkey, expires_at: i64) -> Instruction {
+    let accounts = accounts::OpenSession {
+        wallet: *wallet,
+        proposal_acc: *proposal,
+        session: pda::session_pda(proposal, wallet).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenSession {
+            session_key: *session_key,
+            expires_at,
+        }
+        .data(),
+    }
+}
+
+/// Kill `wallet`'s session on `proposal`; `signer` is the wallet or the
+/// session key, and the rent returns to the wallet.
+pub fn close_session(signer: &Pubkey, proposal: &Pubkey, wallet: &Pubkey) -> Instruction {
+    let accounts = accounts::CloseSession {
+        signer: *signer,
+        wallet: *wallet,
+        session: pda::session_pda(proposal, wallet).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CloseSession {}.data(),
+    }
+}
+
+/// `voter_token_account` is `payer`'s account of the proposal's quorum mint,
+/// required under [`QuorumKind::SupplyBps`] (see [`quorum_token_account`]),
+/// or of its gate mint on a token-gated proposal (see [`voter_token_mint`]).
+pub fn cast_vote(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    cast_vote_as(
+        env,
+        payer,
+        authority,
+        proposal_id,
+        computation_offset,
+        vote,
+        voter_token_account,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Have `authority`'s quadratic or time-weighted `proposal` keep data-less
+/// `VoterRecord`s; it is then voted with [`cast_vote_dataless`].
+pub fn open_dataless_records(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::OpenDatalessRecords {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OpenDatalessRecords {}.data(),
+    }
+}
+
+/// As [`cast_vote`], for a proposal once [`open_dataless_records`] ran:
+/// the payer's `VoterRecord` is created with no data.
+pub fn cast_vote_dataless(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastVoteDataless {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastVoteDataless {
+        computation_offset,
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], on a credential-gated proposal where `payer` presented
+/// the credential with `serial`.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_with_credential(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+    serial: &[u8; 32],
+) -> Instruction {
+    let credential = pda::credential_pda(&pda::proposal_pda(authority, proposal_id).0, serial).0;
+    cast_vote_as(
+        env,
+        payer,
+        authority,
+        proposal_id,
+        computation_offset,
+        vote,
+        voter_token_account,
+        Some(credential),
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// As [`cast_vote`] (or, with `serial`, [`cast_vote_with_credential`]) on
+/// a burn-to-vote proposal: burns one token of `burn_mint`, owned by
+/// `token_program`, from `payer`'s associated token account.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_burning(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+    serial: Option<&[u8; 32]>,
+    burn_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let credential = serial.map(|serial| pda::credential_pda(&pda::proposal_pda(authority, proposal_id).0, serial).0);
+    let burn = BurnAccounts {
+        token_account: quorum_token_account(payer, burn_mint, token_program),
+        mint: *burn_mint,
+        token_program: *token_program,
+    };
+    cast_vote_as(
+        env,
+        payer,
+        authority,
+        proposal_id,
+        computation_offset,
+        vote,
+        voter_token_account,
+        credential,
+        Some(burn),
+        None,
+        None,
+        None,
+    )
+}
+
+/// As [`cast_vote`] (or, with `serial`, [`cast_vote_with_credential`]) on
+/// a proposal with an eligibility oracle: `oracle_accounts` are whatever
+/// the `oracle` program's `can_vote` reads, passed along read-only.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_with_oracle(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+    serial: Option<&[u8; 32]>,
+    oracle: &Pubkey,
+    oracle_accounts: &[Pubkey],
+) -> Instruction {
+    let credential = serial.map(|serial| pda::credential_pda(&pda::proposal_pda(authority, proposal_id).0, serial).0);
+    let mut ix = cast_vote_as(
+        env,
+        payer,
+        authority,
+        proposal_id,
+        computation_offset,
+        vote,
+        voter_token_account,
+        credential,
+        None,
+        Some(*oracle),
+        None,
+        None,
+    );
+    ix.accounts.extend(
+        oracle_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+    ix
+}
+
+/// As [`cast_vote`] (or, with `serial`, [`cast_vote_with_credential`]) on
+/// a token-weighted proposal: the ballot counts with `payer`'s balance in
+/// their associated token account of `weight_mint`, owned by
+/// `token_program`.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_token_weighted(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+    serial: Option<&[u8; 32]>,
+    weight_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let credential = serial.map(|serial| pda::credential_pda(&pda::proposal_pda(authority, proposal_id).0, serial).0);
+    cast_vote_as(
+        env,
+        payer,
+        authority,
+        proposal_id,
+        computation_offset,
+        vote,
+        voter_token_account,
+        credential,
+        None,
+        None,
+        Some(quorum_token_account(payer, weight_mint, token_program)),
+        None,
+    )
+}
+
+/// As [`cast_vote`] on a collection-gated proposal: `payer` votes with
+/// their NFT `nft_mint`, held in their associated token account under
+/// `token_program`.  Each NFT votes once, whoever holds it.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_with_nft(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    nft_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    cast_vote_as(
+        env,
+        payer,
+        authority,
+        proposal_id,
+        computation_offset,
+        vote,
+        Some(&quorum_token_account(payer, nft_mint, token_program)),
+        None,
+        None,
+        None,
+        None,
+        Some(*nft_mint),
+    )
+}
+
+/// The accounts `cast_vote` burns a membership token with.
+struct BurnAccounts {
+    token_account: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cast_vote_as(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+    credential: Option<Pubkey>,
+    burn: Option<BurnAccounts>,
+    eligibility_oracle: Option<Pubkey>,
+    weight_token_account: Option<Pubkey>,
+    nft_mint: Option<Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastVote {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        credential,
+        mix_queue: Some(pda::mix_queue_pda(&proposal).0),
+        slot_hashes: Some(sysvar::slot_hashes::ID),
+        voter_credits: Some(pda::voter_credits_pda(&proposal, payer).0),
+        credit_account: Some(pda::credit_account_pda(&proposal, payer).0),
+        burn_token_account: burn.as_ref().map(|burn| burn.token_account),
+        burn_mint: burn.as_ref().map(|burn| burn.mint),
+        token_program: burn.as_ref().map(|burn| burn.token_program),
+        eligibility_oracle,
+        allowlist: Some(pda::allowlist_pda(&proposal).0),
+        ballot_commitment: Some(pda::ballot_commitment_pda(&proposal, payer).0),
+        weight_token_account,
+        nft_metadata: nft_mint.as_ref().map(|mint| pda::nft_metadata_pda(mint).0),
+        nft_ballot: nft_mint.map(|mint| pda::nft_ballot_pda(&proposal, &mint).0),
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastVote {
+        computation_offset,
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], on a proposal with a `NullifierSet`: the credential
+/// `serial` and its unblinded `signature` go in the ballot, and `payer` —
+/// best a fresh wallet funded for the fees — leaves no `VoterRecord`.
+/// `identity` is required, and only allowed, once the proposal has a
+/// `ComplianceRoll`, encrypted with the same keypair as `vote`.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_anonymous(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    serial: [u8; 32],
+    signature: [u8; 64],
+    identity: Option<&EncryptedIdentity>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastVoteAnonymous {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        credential_registry: pda::credential_registry_pda(&proposal).0,
+        credential: pda::credential_pda(&proposal, &serial).0,
+        nullifier_set: pda::nullifier_set_pda(&proposal).0,
+        mix_queue: Some(pda::mix_queue_pda(&proposal).0),
+        slot_hashes: Some(sysvar::slot_hashes::ID),
+        compliance_roll: identity.map(|_| pda::compliance_roll_pda(&proposal).0),
+        sealed_identity: identity.map(|_| pda::sealed_identity_pda(&proposal, &serial).0),
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastVoteAnonymous {
+        computation_offset,
+        _id: proposal_id,
+        serial,
+        signature,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+        identity: identity.map(|identity| IdentityCiphertexts {
+            ciphertexts: identity.ciphertexts,
+            nonce: identity.nonce,
+        }),
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], signed by `session_key` for `wallet`'s ballot.  The
+/// token account and credential `serial`, if needed, are the wallet's.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_session(
+    env: &ArciumEnv,
+    session_key: &Pubkey,
+    wallet: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+    serial: Option<&[u8; 32]>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastVoteSession {
+        payer: *session_key,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        session: pda::session_pda(&proposal, wallet).0,
+        voter_record: pda::voter_record_pda(&proposal, wallet).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        credential: serial.map(|serial| pda::credential_pda(&proposal, serial).0),
+        mix_queue: Some(pda::mix_queue_pda(&proposal).0),
+        slot_hashes: Some(sysvar::slot_hashes::ID),
+        voter_credits: Some(pda::voter_credits_pda(&proposal, wallet).0),
+        credit_account: Some(pda::credit_account_pda(&proposal, wallet).0),
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastVoteSession {
+        computation_offset,
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// [`cast_vote`] on each `(proposal, vote)` in one instruction, such as
+/// the races of one election; the `i`-th is queued at
+/// `computation_offset + i`.  Only proposals open to anyone, without
+/// credits, burning, an oracle, token weights, a commit phase, a spend
+/// histogram or a supply quorum, and outside their mix window, batch.
+pub fn cast_votes_batch(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    computation_offset: u64,
+    votes: &[(Pubkey, EncryptedVote)],
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_VOTE, computation_offset);
+    let accounts = accounts::CastVotesBatch {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    for (i, (proposal, _)) in votes.iter().enumerate() {
+        let offset = computation_offset.wrapping_add(i as u64);
+        metas.extend([
+            AccountMeta::new(*proposal, false),
+            AccountMeta::new_readonly(pda::tally_pda(proposal).0, false),
+            AccountMeta::new(pda::voter_record_pda(proposal, payer).0, false),
+            AccountMeta::new(QueueAccounts::new(env, CIRCUIT_CAST_VOTE, offset).computation_account, false),
+        ]);
+    }
+    let data = instruction::CastVotesBatch {
+        computation_offset,
+        votes: votes
+            .iter()
+            .map(|(_, vote)| BatchedVote {
+                ciphertexts: vote.ciphertexts,
+                encryption_pubkey: vote.public_key,
+                nonce: vote.nonce,
+            })
+            .collect(),
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Likert`] proposal; encrypt the
+/// scores with [`VoteAllocation::likert`](crate::encryption::VoteAllocation::likert).
+pub fn cast_likert(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_LIKERT, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastLikert {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let [score_0, score_1, score_2, score_3] = vote.ciphertexts;
+    let data = instruction::CastLikert {
+        computation_offset,
+        _id: proposal_id,
+        score_0,
+        score_1,
+        score_2,
+        score_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Jury`] proposal; `payer` must be
+/// a seated juror.  Encrypt the scores with
+/// [`encrypt_jury_scores`](crate::encryption::encrypt_jury_scores).
+pub fn cast_jury_scores(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedJuryScores,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_JURY_SCORES, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastJuryScores {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        juror_acc: pda::juror_pda(&proposal, payer).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let data = instruction::CastJuryScores {
+        computation_offset,
+        _id: proposal_id,
+        scores: ballot.ciphertexts,
+        vote_encryption_pubkey: ballot.public_key,
+        vote_nonce: ballot.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::YesNoAbstain`] proposal; encrypt
+/// the choice with [`VoteAllocation::choice`](crate::encryption::VoteAllocation::choice).
+pub fn cast_choice(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_CHOICE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastChoice {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let [choice_0, choice_1, choice_2, choice_3] = vote.ciphertexts;
+    let data = instruction::CastChoice {
+        computation_offset,
+        _id: proposal_id,
+        choice_0,
+        choice_1,
+        choice_2,
+        choice_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_choice`], for a proposal on `ballot` once
+/// [`open_round_records`] ran: the vote is recorded in the payer's
+/// `RoundRecord` for the ballot.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_round_choice(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    ballot: &Pubkey,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_ROUND_CHOICE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastRoundChoice {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        round_record: pda::round_record_pda(ballot, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let [choice_0, choice_1, choice_2, choice_3] = vote.ciphertexts;
+    let data = instruction::CastRoundChoice {
+        computation_offset,
+        _id: proposal_id,
+        choice_0,
+        choice_1,
+        choice_2,
+        choice_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_choice`], on 1 to [`MAX_BALLOT_PROPOSALS`] `proposals` of
+/// the same ballot in one computation; encrypt their choices, in the same
+/// order, with
+/// [`encrypt_ballot_choices`](crate::encryption::encrypt_ballot_choices).
+pub fn cast_ballot_choices(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    proposals: &[Pubkey],
+    computation_offset: u64,
+    ballot: &EncryptedBallotChoices,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_BALLOT_CHOICES, computation_offset);
+    let accounts = accounts::CastBallotChoices {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    for proposal in proposals {
+        metas.extend([
+            AccountMeta::new(*proposal, false),
+            AccountMeta::new(pda::tally_pda(proposal).0, false),
+            AccountMeta::new(pda::voter_record_pda(proposal, payer).0, false),
+        ]);
+    }
+    let data = instruction::CastBallotChoices {
+        computation_offset,
+        choices: ballot.ciphertexts,
+        vote_encryption_pubkey: ballot.public_key,
+        vote_nonce: ballot.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+/// Give a [`BallotKind::YesNoAbstain`] proposal a council chamber of
+/// `members`, combined with the community's decision per `rule`.
+/// `authority` must be the proposal authority and sign alongside `payer`,
+/// who funds the `CouncilChamber`, before the first ballot; `nonce` seeds
+/// the encrypted council tally.
+#[allow(clippy::too_many_arguments)]
+pub fn open_council(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+    members: Vec<Pubkey>,
+    rule: ChamberRule,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_COUNCIL_TALLIES, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::OpenCouncil {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        council_chamber: pda::council_chamber_pda(&proposal).0,
+    };
+    let data = instruction::OpenCouncil {
+        computation_offset,
+        _id: proposal_id,
+        nonce,
+        members,
+        rule,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Have a [`BallotKind::YesNoAbstain`] proposal's outcome need a larger
+/// margin the lower its turnout: `bias_bps` over a simple majority when
+/// nobody in the electorate votes.  `authority` must be the proposal
+/// authority and sign before the first ballot.
+pub fn set_turnout_bias(authority: &Pubkey, proposal_id: u32, bias_bps: u16) -> Instruction {
+    let accounts = accounts::SetTurnoutBias {
+        authority: *authority,
+        proposal_acc: pda::proposal_pda(authority, proposal_id).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetTurnoutBias { bias_bps }.data(),
+    }
+}
+
+/// Bring a [`BallotKind::YesNoAbstain`] proposal's deadline forward to
+/// `deadline` and hold it to the config's emergency supermajority.
+/// `authority` must be the proposal authority and `council` the config's
+/// council; both sign, before the first ballot.
+pub fn fast_track(authority: &Pubkey, council: &Pubkey, proposal_id: u32, deadline: i64) -> Instruction {
+    let accounts = accounts::FastTrack {
+        authority: *authority,
+        council: *council,
+        config: pda::config_pda().0,
+        proposal_acc: pda::proposal_pda(authority, proposal_id).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::FastTrack { deadline }.data(),
+    }
+}
+
+/// As [`cast_choice`], for a member of the proposal's council; `payer`
+/// is the member.
+pub fn cast_council_choice(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_COUNCIL_CHOICE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastCouncilChoice {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        council_chamber: pda::council_chamber_pda(&proposal).0,
+        council_record: pda::council_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+    };
+    let [choice_0, choice_1, choice_2, choice_3] = vote.ciphertexts;
+    let data = instruction::CastCouncilChoice {
+        computation_offset,
+        _id: proposal_id,
+        choice_0,
+        choice_1,
+        choice_2,
+        choice_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Approval`] proposal; encrypt the
+/// approvals from [`VoteAllocation::approval`](crate::encryption::VoteAllocation::approval).
+pub fn cast_approval(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_APPROVAL, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastApproval {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let [approve_0, approve_1, approve_2, approve_3] = vote.ciphertexts;
+    let data = instruction::CastApproval {
+        computation_offset,
+        _id: proposal_id,
+        approve_0,
+        approve_1,
+        approve_2,
+        approve_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Borda`] proposal; encrypt the
+/// ranking from [`VoteAllocation::ranking`](crate::encryption::VoteAllocation::ranking).
+pub fn cast_borda(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_BORDA, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastBorda {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let [rank_0, rank_1, rank_2, rank_3] = vote.ciphertexts;
+    let data = instruction::CastBorda {
+        computation_offset,
+        _id: proposal_id,
+        rank_0,
+        rank_1,
+        rank_2,
+        rank_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Range`] proposal; encrypt the
+/// scores from [`VoteAllocation::range`](crate::encryption::VoteAllocation::range).
+pub fn cast_range(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_RANGE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastRange {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+    };
+    let [score_0, score_1, score_2, score_3] = vote.ciphertexts;
+    let data = instruction::CastRange {
+        computation_offset,
+        _id: proposal_id,
+        score_0,
+        score_1,
+        score_2,
+        score_3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`cast_vote`], for a [`BallotKind::Pairwise`] round.  The ballot is
+/// an ordinary quadratic allocation; the round holds at most
+/// [`MAX_PAIRWISE_BALLOTS`](private_voting::MAX_PAIRWISE_BALLOTS).
+pub fn cast_pairwise(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote: &EncryptedVote,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_PAIRWISE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastPairwise {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        pairwise_box: pda::pairwise_box_pda(&proposal).0,
+    };
+    let [vote_v0, vote_v1, vote_v2, vote_v3] = vote.ciphertexts;
+    let data = instruction::CastPairwise {
+        computation_offset,
+        _id: proposal_id,
+        vote_v0,
+        vote_v1,
+        vote_v2,
+        vote_v3,
+        vote_encryption_pubkey: vote.public_key,
+        vote_nonce: vote.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Accept write-ins on a quadratic proposal.  `authority` must be the
+/// proposal authority and sign alongside `payer`, who funds the
+/// `WriteInTally`; `nonce` seeds the encrypted table.
+pub fn open_write_ins(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_INIT_WRITE_INS, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::OpenWriteIns {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        write_in_tally: pda::write_in_tally_pda(&proposal).0,
+    };
+    let data = instruction::OpenWriteIns {
+        computation_offset,
+        _id: proposal_id,
+        nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Write a candidate in instead of voting the listed options; see
+/// [`encrypt_write_in`](crate::encryption::encrypt_write_in).
+pub fn cast_write_in(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    write_in: &EncryptedWriteIn,
+    voter_token_account: Option<&Pubkey>,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_CAST_WRITE_IN, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CastWriteIn {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        authority: *authority,
+        proposal_acc: proposal,
+        voter_record: pda::voter_record_pda(&proposal, payer).0,
+        global_stats: pda::global_stats_pda().0,
+        config: pda::config_pda().0,
+        fee_vault: pda::fee_vault_pda().0,
+        voter_token_account: voter_token_account.copied(),
+        write_in_tally: pda::write_in_tally_pda(&proposal).0,
+    };
+    let data = instruction::CastWriteIn {
+        computation_offset,
+        _id: proposal_id,
+        candidate: write_in.ciphertext,
+        vote_encryption_pubkey: write_in.public_key,
+        vote_nonce: write_in.nonce,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// An SPL Memo reading `Voted on proposal #<proposal_id>`, to send after
+/// a ballot so custodial and accounting systems can label the transaction.
+///
+/// Opt-in: the ballot stays encrypted, but the memo spells out in every
+/// explorer that the payer voted, and on what, where otherwise only the
+/// program's accounts say so.
+pub fn vote_memo(proposal_id: u32) -> Instruction {
+    Instruction {
+        program_id: memo::ID,
+        accounts: vec![],
+        data: format!("Voted on proposal #{proposal_id}").into_bytes(),
+    }
+}
+
+/// `authority` must be the proposal authority and sign alongside `payer`.
+pub fn reveal_results(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_RESULTS, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealResults {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    let data = instruction::RevealResults {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`reveal_results`], for a [`BallotKind::YesNoAbstain`] proposal.
+pub fn reveal_outcome(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_OUTCOME, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealOutcome {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    let data = instruction::RevealOutcome {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`reveal_outcome`], for a proposal with a council chamber.
+pub fn reveal_chambers(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_CHAMBERS, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealChambers {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+        council_chamber: pda::council_chamber_pda(&proposal).0,
+    };
+    let data = instruction::RevealChambers {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`reveal_results`], for a [`BallotKind::BudgetBox`] proposal.
+pub fn reveal_budget_box(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_BUDGET_BOX, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealBudgetBox {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    let data = instruction::RevealBudgetBox {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// As [`reveal_results`], for a [`BallotKind::Pairwise`] round.
+pub fn reveal_pairwise(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_PAIRWISE, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealPairwise {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        pairwise_box: pda::pairwise_box_pda(&proposal).0,
+    };
+    let data = instruction::RevealPairwise {
+        computation_offset,
+        id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Reveal the top write-in of a finalized proposal.  `authority` must be
+/// the proposal authority and sign alongside `payer`.
+pub fn reveal_write_in(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_WRITE_IN, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::RevealWriteIn {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        global_stats: pda::global_stats_pda().0,
+        write_in_tally: pda::write_in_tally_pda(&proposal).0,
+    };
+    let data = instruction::RevealWriteIn {
+        computation_offset,
+        _id: proposal_id,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Queue the reveal of the poll at `proposal`.  Permissionless; `payer`
+/// only signs and pays the fee.
+pub fn reveal_poll(env: &ArciumEnv, payer: &Pubkey, proposal: &Pubkey, computation_offset: u64) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_RESULTS, computation_offset);
+    let accounts = accounts::RevealPoll {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: *proposal,
+        tally_acc: pda::tally_pda(proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::RevealPoll { computation_offset }.data(),
+    }
+}
+
+/// Ask whether the proposal's encrypted `total_votes` has reached
+/// `vote_threshold`, revealing only that bit.  `authority` must be the
+/// proposal authority and sign alongside `payer`; the first check fixes the
+/// threshold for later ones.
+pub fn check_quorum_progress(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    computation_offset: u64,
+    vote_threshold: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_QUORUM_PROGRESS, computation_offset);
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CheckQuorumProgress {
+        payer: *payer,
+        authority: *authority,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CheckQuorumProgress {
+            computation_offset,
+            _id: proposal_id,
+            vote_threshold,
+        }
+        .data(),
+    }
+}
+
+/// Require `threshold` of `trustees` to [`approve_reveal`] before any
+/// reveal, and let them [`force_reveal`] `grace_period` seconds after the
+/// deadline.  `authority` must be the proposal authority and sign alongside
+/// `payer`, before the first vote.
+pub fn appoint_trustees(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    trustees: Vec<Pubkey>,
+    threshold: u8,
+    grace_period: i64,
+) -> Instruction {
+    let accounts = accounts::AppointTrustees {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: *proposal,
+        trustee_set: pda::trustee_set_pda(proposal).0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::AppointTrustees {
+        trustees,
+        threshold,
+        grace_period,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Record a trustee's approval of the reveal; `trustee` signs.
+pub fn approve_reveal(trustee: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::ApproveReveal {
+        trustee: *trustee,
+        proposal_acc: *proposal,
+        trustee_set: pda::trustee_set_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ApproveReveal {}.data(),
+    }
+}
+
+/// Queue the reveal in place of a stalled authority.  `trustee` must be one
+/// of the proposal's trustees and sign alongside `payer`.
+pub fn force_reveal(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    trustee: &Pubkey,
+    proposal: &Pubkey,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_RESULTS, computation_offset);
+    let accounts = accounts::ForceReveal {
+        payer: *payer,
+        trustee: *trustee,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: *proposal,
+        tally_acc: pda::tally_pda(proposal).0,
+        global_stats: pda::global_stats_pda().0,
+        trustee_set: pda::trustee_set_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ForceReveal { computation_offset }.data(),
+    }
+}
+
+/// Reclaim a settled proposal's `TrusteeSet` rent; `authority` signs.
+pub fn close_trustee_set(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::CloseTrusteeSet {
+        authority: *authority,
+        proposal_acc: *proposal,
+        trustee_set: pda::trustee_set_pda(proposal).0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CloseTrusteeSet {}.data(),
+    }
+}
+
+/// Name `co_authors` who share the right to [`extend_deadline`] and to
+/// queue the reveal with [`co_author_reveal`].  `authority` must be the
+/// proposal authority and sign, once and before the first vote.
+pub fn set_co_authors(authority: &Pubkey, proposal: &Pubkey, co_authors: Vec<Pubkey>) -> Instruction {
+    let accounts = accounts::SetCoAuthors {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetCoAuthors { co_authors }.data(),
+    }
+}
+
+/// Move the deadline later, before the first vote.  `signer` must be the
+/// authority or a co-author; `category` is the proposal's, if any.
+pub fn extend_deadline(signer: &Pubkey, proposal: &Pubkey, category: Option<u16>, new_deadline: i64) -> Instruction {
+    let accounts = accounts::ExtendDeadline {
+        signer: *signer,
+        proposal_acc: *proposal,
+        category_acc: category.map(|id| pda::category_pda(id).0),
+        config: pda::config_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ExtendDeadline { new_deadline }.data(),
+    }
+}
+
+/// Queue the reveal as one of the proposal's co-authors.  `co_author` signs
+/// alongside `payer`.
+pub fn co_author_reveal(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    co_author: &Pubkey,
+    proposal: &Pubkey,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_RESULTS, computation_offset);
+    let accounts = accounts::CoAuthorReveal {
+        payer: *payer,
+        co_author: *co_author,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: *proposal,
+        tally_acc: pda::tally_pda(proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CoAuthorReveal { computation_offset }.data(),
+    }
+}
+
+/// Let `operator`, such as a keeper's key, queue the reveal with
+/// [`operator_reveal`] and nothing else.  `authority` must be the proposal
+/// authority and sign, once and before the first vote.
+pub fn set_reveal_operator(authority: &Pubkey, proposal: &Pubkey, operator: Pubkey) -> Instruction {
+    let accounts = accounts::SetRevealOperator {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SetRevealOperator { operator }.data(),
+    }
+}
+
+/// Queue the reveal as the proposal's reveal operator.  `operator` signs
+/// alongside `payer`.
+pub fn operator_reveal(
+    env: &ArciumEnv,
+    payer: &Pubkey,
+    operator: &Pubkey,
+    proposal: &Pubkey,
+    computation_offset: u64,
+) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_RESULTS, computation_offset);
+    let accounts = accounts::OperatorReveal {
+        payer: *payer,
+        operator: *operator,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: *proposal,
+        tally_acc: pda::tally_pda(proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::OperatorReveal { computation_offset }.data(),
+    }
+}
+
+/// Let anyone reveal the proposal with [`auto_reveal`] once voting closes.
+/// `authority` must be the proposal authority and sign.
+pub fn enable_auto_reveal(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::EnableAutoReveal {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::EnableAutoReveal {}.data(),
+    }
+}
+
+/// Reveal `proposal`, or mark it quorum-failed, after an
+/// [`enable_auto_reveal`].  Permissionless; `attempt` is the proposal's
+/// `auto_reveals` (0 until a reveal expires), from which the computation
+/// offset is derived, so the instruction can be built before the deadline
+/// and scheduled with an automation service.
+pub fn auto_reveal(env: &ArciumEnv, payer: &Pubkey, proposal: &Pubkey, attempt: u32) -> Instruction {
+    let q = QueueAccounts::new(env, CIRCUIT_REVEAL_RESULTS, pda::auto_reveal_offset(proposal, attempt));
+    let accounts = accounts::AutoReveal {
+        payer: *payer,
+        sign_pda_account: q.sign_pda_account,
+        mxe_account: q.mxe_account,
+        mempool_account: q.mempool_account,
+        executing_pool: q.executing_pool,
+        computation_account: q.computation_account,
+        comp_def_account: q.comp_def_account,
+        cluster_account: q.cluster_account,
+        pool_account: q.pool_account,
+        clock_account: q.clock_account,
+        system_program: system_program::ID,
+        arcium_program: ARCIUM_PROGRAM_ID,
+        proposal_acc: *proposal,
+        tally_acc: pda::tally_pda(proposal).0,
+        global_stats: pda::global_stats_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::AutoReveal {}.data(),
+    }
+}
+
+/// Mark `proposal` as having missed quorum.  Permissionless; `payer` only
+/// signs and pays the fee.
+pub fn mark_quorum_failed(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::MarkQuorumFailed {
+        payer: *payer,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::MarkQuorumFailed {}.data(),
+    }
+}
+
+/// Clear a reveal of `proposal` whose callback timed out.  Permissionless.
+pub fn expire_reveal(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::ExpireReveal {
+        payer: *payer,
+        proposal_acc: *proposal,
+        config: pda::config_pda().0,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ExpireReveal {}.data(),
+    }
+}
+
+/// Call off the reveal of `proposal` in flight; its callback is dropped if
+/// it arrives.  `authority` must be the proposal authority and sign.
+pub fn abort_reveal(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::AbortReveal {
+        authority: *authority,
+        proposal_acc: *proposal,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::AbortReveal {}.data(),
+    }
+}
+
+/// Escrow `amount` of `mint` from the authority's associated token account
+/// as the proposal's participation reward.  Only before the first ballot;
+/// `payer` funds the pool accounts.
+pub fn create_reward_pool(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CreateRewardPool {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: proposal,
+        mint: *mint,
+        authority_token_account: associated_token::get_associated_token_address(authority, mint),
+        reward_pool: pda::reward_pool_pda(&proposal).0,
+        reward_vault: pda::reward_vault_pda(&proposal).0,
+        token_program: token::ID,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CreateRewardPool { amount }.data(),
+    }
+}
+
+/// Add `amount` lamports to the proposal's fee sponsorship, which reimburses
+/// each voter's Arcium fee.  `authority` must sign; only while voting is open.
+pub fn fund_sponsorship(authority: &Pubkey, proposal_id: u32, amount: u64) -> Instruction {
+    let accounts = accounts::FundSponsorship {
+        authority: *authority,
+        proposal_acc: pda::proposal_pda(authority, proposal_id).0,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::FundSponsorship { amount }.data(),
+    }
+}
+
+/// Settle the reward pool of a finalized or quorum-failed `proposal`; the
+/// remainder goes to `authority`'s associated token account.  Permissionless.
+pub fn settle_rewards(
+    payer: &Pubkey,
+    proposal: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    let accounts = accounts::SettleRewards {
+        payer: *payer,
+        proposal_acc: *proposal,
+        reward_pool: pda::reward_pool_pda(proposal).0,
+        reward_vault: pda::reward_vault_pda(proposal).0,
+        authority_token_account: associated_token::get_associated_token_address(authority, mint),
+        token_program: token::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SettleRewards {}.data(),
+    }
+}
+
+/// Claim `voter`'s share into their associated token account, which must
+/// exist (see [`create_token_account`]).
+pub fn claim_reward(voter: &Pubkey, proposal: &Pubkey, mint: &Pubkey) -> Instruction {
+    let accounts = accounts::ClaimReward {
+        voter: *voter,
+        reward_pool: pda::reward_pool_pda(proposal).0,
+        reward_vault: pda::reward_vault_pda(proposal).0,
+        voter_record: pda::voter_record_pda(proposal, voter).0,
+        voter_token_account: associated_token::get_associated_token_address(voter, mint),
+        token_program: token::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ClaimReward {}.data(),
+    }
+}
+
+/// Escrow `amount` of `mint` from the authority's associated token account
+/// to match a quadratic funding round, paying option i's match to
+/// `recipients[i]`.  Only before the first ballot; `payer` funds the pool
+/// accounts.
+pub fn create_matching_pool(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    mint: &Pubkey,
+    amount: u64,
+    recipients: Vec<Pubkey>,
+) -> Instruction {
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CreateMatchingPool {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: proposal,
+        mint: *mint,
+        authority_token_account: associated_token::get_associated_token_address(authority, mint),
+        matching_pool: pda::matching_pool_pda(&proposal).0,
+        matching_vault: pda::matching_vault_pda(&proposal).0,
+        token_program: token::ID,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CreateMatchingPool { amount, recipients }.data(),
+    }
+}
+
+/// Settle the matching pool of a finalized or quorum-failed `proposal`; the
+/// remainder goes to `authority`'s associated token account.  Permissionless.
+pub fn settle_matching(
+    payer: &Pubkey,
+    proposal: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    let accounts = accounts::SettleMatching {
+        payer: *payer,
+        proposal_acc: *proposal,
+        matching_pool: pda::matching_pool_pda(proposal).0,
+        matching_vault: pda::matching_vault_pda(proposal).0,
+        authority_token_account: associated_token::get_associated_token_address(authority, mint),
+        token_program: token::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::SettleMatching {}.data(),
+    }
+}
+
+/// Pay `option`'s match into `recipient`'s associated token account, which
+/// must exist (see [`create_token_account`]).  Permissionless; `payer` funds
+/// the payout receipt.
+pub fn pay_match(
+    payer: &Pubkey,
+    proposal: &Pubkey,
+    option: u8,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    let accounts = accounts::PayMatch {
+        payer: *payer,
+        matching_pool: pda::matching_pool_pda(proposal).0,
+        matching_vault: pda::matching_vault_pda(proposal).0,
+        payout: pda::match_payout_pda(proposal, option).0,
+        recipient_token_account: associated_token::get_associated_token_address(recipient, mint),
+        token_program: token::ID,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::PayMatch { option }.data(),
+    }
+}
+
+/// Create `owner`'s associated token account for `mint` unless it exists.
+pub fn create_token_account(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        payer,
+        owner,
+        mint,
+        &token::ID,
+    )
+}
+
+/// Create the finalized `proposal`'s receipt mint.  Permissionless; `payer`
+/// funds the mint's rent.
+pub fn create_receipt_mint(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let accounts = accounts::CreateReceiptMint {
+        payer: *payer,
+        proposal_acc: *proposal,
+        receipt_mint: pda::receipt_mint_pda(proposal).0,
+        token_program: token_2022::ID,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::CreateReceiptMint {}.data(),
+    }
+}
+
+/// Mint `voter`'s "I voted" receipt into their Token-2022 associated token
+/// account, which must exist (see [`create_receipt_account`]).
+pub fn claim_receipt(voter: &Pubkey, proposal: &Pubkey) -> Instruction {
+    let receipt_mint = pda::receipt_mint_pda(proposal).0;
+    let accounts = accounts::ClaimReceipt {
+        voter: *voter,
+        proposal_acc: *proposal,
+        voter_record: pda::voter_record_pda(proposal, voter).0,
+        receipt_mint,
+        voter_token_account: receipt_account(voter, proposal),
+        token_program: token_2022::ID,
+    };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: instruction::ClaimReceipt {}.data(),
+    }
+}
+
+/// `owner`'s Token-2022 associated token account for `proposal`'s receipt.
+pub fn receipt_account(owner: &Pubkey, proposal: &Pubkey) -> Pubkey {
+    associated_token::get_associated_token_address_with_program_id(
+        owner,
+        &pda::receipt_mint_pda(proposal).0,
+        &token_2022::ID,
+    )
+}
+
+/// `owner`'s associated token account of a supply-quorum `mint` owned by
+/// `token_program` (SPL Token or Token-2022), for [`cast_vote`].
+pub fn quorum_token_account(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    associated_token::get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// Mint of the `voter_token_account` ballots on `proposal` pass: the quorum
+/// mint under [`QuorumKind::SupplyBps`], else the gate mint of a token-gated
+/// proposal.  The program requires the two to match when both apply.
+pub fn voter_token_mint(proposal: &ProposalAccount) -> Option<Pubkey> {
+    match (proposal.quorum_kind, proposal.gating) {
+        (QuorumKind::SupplyBps, _) => Some(proposal.quorum_mint),
+        (_, GatingMode::Token) => Some(proposal.gate_mint),
+        _ => None,
+    }
+}
+
+/// Create `owner`'s receipt token account unless it exists.  The receipt
+/// mint must already exist.
+pub fn create_receipt_account(payer: &Pubkey, owner: &Pubkey, proposal: &Pubkey) -> Instruction {
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        payer,
+        owner,
+        &pda::receipt_mint_pda(proposal).0,
+        &token_2022::ID,
+    )
+}
+
+/// Close a finalized or quorum-failed proposal (after `settle_rewards` if it
+/// has a reward pool); `authority` must be the proposal authority and
+/// receives the rent.  `payer` funds the `ClosedProposal` tombstone that
+/// retires the id; pass the same key for both unless the authority holds
+/// no SOL.
+pub fn close_proposal(payer: &Pubkey, authority: &Pubkey, proposal_id: u32) -> Instruction {
+    let proposal = pda::proposal_pda(authority, proposal_id).0;
+    let accounts = accounts::CloseProposal {
+        payer: *payer,
+        authority: *authority,
+        proposal_acc: proposal,
+        tally_acc: pda::tally_pda(&proposal).0,
+        closed_proposal: pda::closed_proposal_pda(&proposal).0,
+        config: pda::config_pda().0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::CloseProposal { id: proposal_id };
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,54 @@
+//! Rust client SDK for the ArcVote private quadratic voting program.
+//!
+//! Covers the three steps every off-chain integration needs:
+//!
+//! - [`pda`] — derive the proposal, voter-record and Arcium accounts
+//! - [`encryption`] — x25519 key exchange with the MXE and Rescue encryption
+//!   of a [`VoteAllocation`](encryption::VoteAllocation)
+//! - [`instructions`] — build `create_proposal`, `cast_vote`,
+//!   `reveal_results` and `close_proposal` instructions ready to be signed
+//!   and sent
+//! - [`accounts`] — decode proposal, voter-record and MXE account data
+//! - [`reference`] — plaintext model of the circuits, for expected tallies
+//! - [`validation`] — check an allocation against a proposal before paying
+//!   for a computation the circuit would discard
+//! - [`transaction`] — compile unsigned v0 transactions for hardware and
+//!   offline signers
+//! - [`verify`] — check revealed results against the cluster's signature
+//! - [`credential`] — blind and unblind registrar-issued ballot credentials
+//!
+//! By default the SDK never touches the network; fetching the MXE public key
+//! and sending transactions is left to the caller's RPC client.  The `rpc`
+//! feature adds blocking helpers to fetch proposals and dry-run votes, and
+//! the `export` feature signed archives of finalized proposals ([`export`]).
+
+pub mod accounts;
+pub mod credential;
+pub mod encryption;
+#[cfg(feature = "export")]
+pub mod export;
+pub mod instructions;
+pub mod pda;
+pub mod reference;
+pub mod rescue;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod transaction;
+pub mod validation;
+pub mod verify;
+
+pub use anchor_lang::solana_program::{instruction::Instruction, pubkey::Pubkey};
+pub use private_voting::{ErrorCode, ErrorFamily, ID as PROGRAM_ID};
+
+/// Arcium deployment the program is bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArciumEnv {
+    /// Offset of the MPC cluster the MXE is assigned to.
+    pub cluster_offset: u32,
+}
+
+impl ArciumEnv {
+    pub fn new(cluster_offset: u32) -> Self {
+        Self { cluster_offset }
+    }
+}
@@ -0,0 +1,388 @@
+//! Program-derived addresses used by ArcVote and the Arcium accounts its
+//! instructions reference.
+
+use anchor_lang::solana_program::{bpf_loader_upgradeable, pubkey::Pubkey};
+use arcium_client::pda as arcium_pda;
+
+use crate::{ArciumEnv, PROGRAM_ID};
+
+/// Computation offset of a proposal's next `auto_reveal`, given its
+/// `auto_reveals`.
+pub use private_voting::auto_reveal_offset;
+
+/// Metaplex Token Metadata, which owns NFT metadata accounts.
+pub use private_voting::TOKEN_METADATA_PROGRAM_ID;
+
+/// Circuit names, as registered by the `init_*_comp_def` instructions.
+pub const CIRCUIT_INIT_TALLIES: &str = "init_tallies";
+pub const CIRCUIT_CAST_VOTE: &str = "cast_vote";
+pub const CIRCUIT_CAST_LIKERT: &str = "cast_likert";
+pub const CIRCUIT_CAST_CHOICE: &str = "cast_choice";
+pub const CIRCUIT_CAST_BALLOT_CHOICES: &str = "cast_ballot_choices";
+pub const CIRCUIT_CAST_ROUND_CHOICE: &str = "cast_round_choice";
+pub const CIRCUIT_CAST_APPROVAL: &str = "cast_approval";
+pub const CIRCUIT_REVEAL_RESULTS: &str = "reveal_results";
+pub const CIRCUIT_REVEAL_OUTCOME: &str = "reveal_outcome";
+pub const CIRCUIT_REVEAL_BUDGET_BOX: &str = "reveal_budget_box";
+pub const CIRCUIT_CAST_PAIRWISE: &str = "cast_pairwise";
+pub const CIRCUIT_REVEAL_PAIRWISE: &str = "reveal_pairwise";
+pub const CIRCUIT_INIT_WRITE_INS: &str = "init_write_ins";
+pub const CIRCUIT_CAST_WRITE_IN: &str = "cast_write_in";
+pub const CIRCUIT_REVEAL_WRITE_IN: &str = "reveal_write_in";
+pub const CIRCUIT_CAST_JURY_SCORES: &str = "cast_jury_scores";
+pub const CIRCUIT_RELEASE_IDENTITY: &str = "release_identity";
+pub const CIRCUIT_REVEAL_QUORUM_PROGRESS: &str = "reveal_quorum_progress";
+pub const CIRCUIT_INIT_SPEND_HISTOGRAM: &str = "init_spend_histogram";
+pub const CIRCUIT_CAST_VOTE_HISTOGRAM: &str = "cast_vote_histogram";
+pub const CIRCUIT_REVEAL_SPEND_HISTOGRAM: &str = "reveal_spend_histogram";
+pub const CIRCUIT_INIT_COUNCIL_TALLIES: &str = "init_council_tallies";
+pub const CIRCUIT_CAST_COUNCIL_CHOICE: &str = "cast_council_choice";
+pub const CIRCUIT_REVEAL_CHAMBERS: &str = "reveal_chambers";
+pub const CIRCUIT_INIT_RANKINGS: &str = "init_rankings";
+pub const CIRCUIT_CAST_RANKED_VOTE: &str = "cast_ranked_vote";
+pub const CIRCUIT_REVEAL_RANKED: &str = "reveal_ranked";
+pub const CIRCUIT_CAST_BORDA: &str = "cast_borda";
+pub const CIRCUIT_INIT_PREFERENCES: &str = "init_preferences";
+pub const CIRCUIT_CAST_CONDORCET: &str = "cast_condorcet";
+pub const CIRCUIT_REVEAL_CONDORCET: &str = "reveal_condorcet";
+pub const CIRCUIT_CAST_STAR: &str = "cast_star";
+pub const CIRCUIT_REVEAL_STAR: &str = "reveal_star";
+pub const CIRCUIT_CAST_RANGE: &str = "cast_range";
+pub const CIRCUIT_CAST_VOTE_BATCH: &str = "cast_vote_batch";
+pub const CIRCUIT_INIT_CREDIT_BANK: &str = "init_credit_bank";
+pub const CIRCUIT_CAST_VOTE_BANKED: &str = "cast_vote_banked";
+pub const CIRCUIT_SEAL_BUDGET: &str = "seal_budget";
+pub const CIRCUIT_CAST_VOTE_BUDGETED: &str = "cast_vote_budgeted";
+
+/// `[b"proposal", authority, id LE]`
+pub fn proposal_pda(authority: &Pubkey, id: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"proposal", authority.as_ref(), &id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"tally", proposal]`
+pub fn tally_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"tally", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"closed_proposal", proposal]`
+pub fn closed_proposal_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"closed_proposal", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"voter", proposal, voter]`
+pub fn voter_record_pda(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"voter", proposal.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"global_stats"]`
+pub fn global_stats_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"global_stats"], &PROGRAM_ID)
+}
+
+/// `[b"config"]`
+pub fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID)
+}
+
+/// `[b"reward_pool", proposal]`
+pub fn reward_pool_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reward_pool", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"reward_vault", proposal]` — token account holding the reward pool.
+pub fn reward_vault_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reward_vault", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"matching_pool", proposal]`
+pub fn matching_pool_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"matching_pool", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"matching_vault", proposal]` — token account holding the matching pool.
+pub fn matching_vault_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"matching_vault", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"match_payout", proposal, option]` — receipt of one option's match.
+pub fn match_payout_pda(proposal: &Pubkey, option: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"match_payout", proposal.as_ref(), &[option]], &PROGRAM_ID)
+}
+
+/// `[b"receipt_mint", proposal]` — the proposal's non-transferable
+/// Token-2022 "I voted" mint.
+pub fn receipt_mint_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt_mint", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"pairwise_box", proposal]` — the sealed ballots of a pairwise round.
+pub fn pairwise_box_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pairwise_box", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"write_ins", proposal]` — a proposal's encrypted write-in tallies.
+pub fn write_in_tally_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"write_ins", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"council", proposal]` — a dual-chamber proposal's council and its
+/// encrypted tally.
+pub fn council_chamber_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"council", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"council_vote", proposal, member]` — a council member's ballot record.
+pub fn council_record_pda(proposal: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"council_vote", proposal.as_ref(), member.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"candidate", proposal, candidate]` — a candidate's registration.
+pub fn candidate_pda(proposal: &Pubkey, candidate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"candidate", proposal.as_ref(), candidate.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"juror", proposal, juror]` — a juror's seat on a reviewer panel.
+pub fn juror_pda(proposal: &Pubkey, juror: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"juror", proposal.as_ref(), juror.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"credentials", proposal]` — a credential-gated proposal's registrar.
+pub fn credential_registry_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"credentials", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"issuance", proposal, voter]` — a registered voter's blind credential.
+pub fn credential_issuance_pda(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"issuance", proposal.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"credential", proposal, serial]` — a presented or revoked credential.
+pub fn credential_pda(proposal: &Pubkey, serial: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"credential", proposal.as_ref(), serial], &PROGRAM_ID)
+}
+
+/// `[b"nullifiers", proposal]` — credentials spent by anonymous ballots.
+pub fn nullifier_set_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"nullifiers", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"compliance", proposal]` — the key anonymous voters' identities are
+/// released to.
+pub fn compliance_roll_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"compliance", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"identity", proposal, serial]` — the identity sealed with the
+/// anonymous ballot that spent `serial`.
+pub fn sealed_identity_pda(proposal: &Pubkey, serial: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"identity", proposal.as_ref(), serial.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"trustees", proposal]` — the trustees who must approve the reveal.
+pub fn trustee_set_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"trustees", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"dependency", proposal]` — the parent a chained proposal waits on.
+pub fn dependency_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dependency", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"sanction", proposal]` — the slash or removal a proposal votes on.
+pub fn sanction_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sanction", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"option_details", proposal]` — what each option pays or points to.
+pub fn option_details_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"option_details", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"translations", proposal]` — the title and options in other locales.
+pub fn translations_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"translations", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"ballot_commitment", proposal, voter]` — a voter's commit-phase
+/// ballot hash.
+pub fn ballot_commitment_pda(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"ballot_commitment", proposal.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"mix_queue", proposal]` — ballots held in a proposal's mix window.
+pub fn mix_queue_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"mix_queue", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"ballot_buffer", proposal]` — ballots waiting for `process_ballot_batch`.
+pub fn ballot_buffer_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"ballot_buffer", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"credits", scope, voter]` — a voter's square-root credits.  The scope
+/// is the proposal's ballot if it is on one, else the proposal.
+pub fn voter_credits_pda(scope: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"credits", scope.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"credit_vault", voter_credits]` — the tokens a voter's square-root
+/// credits rest on, escrowed until the deadline.
+pub fn credit_vault_pda(voter_credits: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"credit_vault", voter_credits.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"credit_account", proposal, voter]` — a voter's funded budget.
+pub fn credit_account_pda(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"credit_account", proposal.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"credit_bank", authority, voter]` — a voter's banked credits with a
+/// proposal authority.
+pub fn credit_bank_pda(authority: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"credit_bank", authority.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"encrypted_budget", proposal, voter]` — a voter's encrypted budget.
+pub fn encrypted_budget_pda(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"encrypted_budget", proposal.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"history_gate", proposal]` — a history-gated proposal's bar.
+pub fn history_gate_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"history_gate", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"allowlist", proposal]` — an allowlisted proposal's electorate.
+pub fn allowlist_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"allowlist", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"nft_ballot", proposal, mint]` — an NFT's ballot on a
+/// collection-gated proposal.
+pub fn nft_ballot_pda(proposal: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"nft_ballot", proposal.as_ref(), mint.as_ref()], &PROGRAM_ID)
+}
+
+/// The Metaplex metadata account of `mint`, which `cast_vote` reads the
+/// NFT's collection from.
+pub fn nft_metadata_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// `[b"spend_histogram", proposal]` — a proposal's encrypted credit-spend
+/// histogram.
+pub fn spend_histogram_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"spend_histogram", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"rankings", proposal]` — a ranked-choice proposal's encrypted
+/// ballot counts per order.
+pub fn rankings_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rankings", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"preferences", proposal]` — a Condorcet or STAR proposal's
+/// encrypted head-to-head counts.
+pub fn preferences_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"preferences", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"session", proposal, wallet]` — a wallet's voting session key.
+pub fn session_pda(proposal: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"session", proposal.as_ref(), wallet.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"category", id]` — a registry category proposals can be filed under.
+pub fn category_pda(category: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"category", category.to_le_bytes().as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"ballot", authority, id]` — proposals grouped by `create_ballot`.
+pub fn ballot_pda(authority: &Pubkey, id: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"ballot", authority.as_ref(), id.to_le_bytes().as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"round_voter", ballot, voter]` — a voter's ballots on a ballot
+/// keeping round records.
+pub fn round_record_pda(ballot: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"round_voter", ballot.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"commitment", proposal]` — checkpoints of a proposal's encrypted tally.
+pub fn commitment_pda(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"commitment", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"creator", authority]` — the authority's rate-limit registry entry.
+pub fn creator_record_pda(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"creator", authority.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"fee_vault"]`
+pub fn fee_vault_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_vault"], &PROGRAM_ID)
+}
+
+/// The program's upgradeable-loader `ProgramData` account, whose upgrade
+/// authority may call `initialize_config`.
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// The program's Arcium signer PDA (`sign_pda_account`).
+pub fn sign_pda() -> Pubkey {
+    arcium_pda::signer_acc(&PROGRAM_ID)
+}
+
+/// The program's MXE account.
+pub fn mxe_account() -> Pubkey {
+    arcium_pda::mxe_acc(&PROGRAM_ID)
+}
+
+/// The MXE's address lookup table, created at `lut_offset_slot` (a field of
+/// the MXE account).  Covers the Arcium accounts every queue instruction
+/// references.
+pub fn mxe_lookup_table(lut_offset_slot: u64) -> Pubkey {
+    arcium_pda::mxe_lut_acc(&PROGRAM_ID, lut_offset_slot)
+}
+
+/// Computation definition account for one of the program's circuits.
+pub fn comp_def_account(circuit: &str) -> Pubkey {
+    arcium_pda::computation_definition_acc(&PROGRAM_ID, arcium_pda::comp_def_offset(circuit))
+}
+
+/// Arcium accounts shared by every `queue_computation` instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueAccounts {
+    pub sign_pda_account: Pubkey,
+    pub mxe_account: Pubkey,
+    pub mempool_account: Pubkey,
+    pub executing_pool: Pubkey,
+    pub computation_account: Pubkey,
+    pub comp_def_account: Pubkey,
+    pub cluster_account: Pubkey,
+    pub pool_account: Pubkey,
+    pub clock_account: Pubkey,
+}
+
+impl QueueAccounts {
+    pub fn new(env: &ArciumEnv, circuit: &str, computation_offset: u64) -> Self {
+        Self {
+            sign_pda_account: sign_pda(),
+            mxe_account: mxe_account(),
+            mempool_account: arcium_pda::mempool_acc(env.cluster_offset),
+            executing_pool: arcium_pda::execpool_acc(env.cluster_offset),
+            computation_account: arcium_pda::computation_acc(env.cluster_offset, computation_offset),
+            comp_def_account: comp_def_account(circuit),
+            cluster_account: arcium_pda::cluster_acc(env.cluster_offset),
+            pool_account: arcium_pda::FEE_POOL_PDA.0,
+            clock_account: arcium_pda::CLOCK_PDA.0,
+        }
+    }
+}
@@ -0,0 +1,767 @@
+//! Plaintext reference model of the circuits in `encrypted-ixs`.
+//!
+//! Produces the values the MPC cluster computes under encryption, so test
+//! vectors, simulations and result audits can be checked without a cluster.
+//! Allocations are assumed not to overflow `u64`, which the SDK's own
+//! [`VoteAllocation::cost`] already rejects.
+
+use crate::{
+    encryption::{JuryScores, VoteAllocation, RANGE_MAX_SCORE, STAR_MAX_SCORE},
+    instructions::{ChamberRule, VoteOutcome, WeightCurve},
+};
+
+/// `VoteTallies` from the circuits, in plaintext.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tallies {
+    pub options: [u64; 4],
+    pub total_votes: u64,
+}
+
+/// `RevealedResults` from the `reveal_results` circuit, `OutcomeResults`
+/// from `reveal_outcome` when `outcome` is set, or `BudgetBoxResults`
+/// from `reveal_budget_box` when `funded` is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RevealedResults {
+    pub options: [u64; 4],
+    pub total_votes: u64,
+    pub winner: u8,
+    pub outcome: Option<VoteOutcome>,
+    /// Funded options of a budget box, bit i for option i.
+    pub funded: Option<u8>,
+}
+
+/// `ChamberResults` from the `reveal_chambers` circuit: the community's
+/// results carrying the combined outcome, and each chamber's own decision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChamberResults {
+    pub community: RevealedResults,
+    pub community_outcome: VoteOutcome,
+    pub council: Tallies,
+    pub council_outcome: VoteOutcome,
+}
+
+/// `StarResults` from the `reveal_star` circuit: the score totals and
+/// runoff winner, the other finalist, and the ballots preferring each
+/// finalist over the other, the winner's first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StarResults {
+    pub results: RevealedResults,
+    pub runner_up: u8,
+    pub runoff: [u64; 2],
+}
+
+/// `WriteIns` from the circuits, in plaintext: candidate hash and count
+/// per slot, in the order candidates were first written in.  A hash of 0
+/// marks a free slot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WriteIns {
+    pub hashes: [u128; 4],
+    pub counts: [u64; 4],
+}
+
+/// `RankingCounts` from the circuits, in plaintext: counted ranked ballots
+/// per order of the four options, in [`rank_orders`] order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RankingCounts {
+    pub counts: [u64; 24],
+}
+
+/// `Preferences` from the circuits, in plaintext: `wins[4 * i + j]` counts
+/// the ballots ranking or scoring option `i` above option `j`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Preferences {
+    pub wins: [u64; 16],
+}
+
+/// `init_tallies`: every counter starts at zero.
+pub fn init_tallies() -> Tallies {
+    Tallies::default()
+}
+
+/// `cast_vote`: add the allocation if its quadratic cost is within
+/// `budget` ([`effective_budget`](crate::validation::effective_budget), or
+/// the voter's `VoterCredits::credits`), otherwise leave the tallies
+/// untouched.  Returns whether the ballot was counted.
+pub fn cast_vote(tallies: &mut Tallies, allocation: &VoteAllocation, budget: u64) -> bool {
+    cast_weighted_vote(tallies, allocation, 1, budget)
+}
+
+/// `cast_vote` on a time-weighted proposal: as [`cast_vote`], with the
+/// votes multiplied by `weight` (`ProposalAccount::ballot_weight` when the
+/// ballot was cast) after the budget check.
+pub fn cast_weighted_vote(tallies: &mut Tallies, allocation: &VoteAllocation, weight: u64, budget: u64) -> bool {
+    if !allocation.is_within_budget(budget) {
+        return false;
+    }
+    for (tally, votes) in tallies.options.iter_mut().zip(allocation.votes) {
+        *tally += votes * weight;
+    }
+    tallies.total_votes += allocation.votes.iter().sum::<u64>() * weight;
+    true
+}
+
+/// The first step of `cast_vote`, `cast_vote_histogram` and
+/// `cast_pairwise`: the allocation with every option at or past the
+/// proposal's `options` zeroed, so dead options neither cost credits nor
+/// reach the tallies.  Apply it before [`cast_vote`] or
+/// [`reveal_pairwise`] on a proposal with fewer than four options.
+pub fn mask_options(allocation: &VoteAllocation, options: u8) -> VoteAllocation {
+    let mut votes = allocation.votes;
+    for vote in votes.iter_mut().skip(usize::from(options)) {
+        *vote = 0;
+    }
+    VoteAllocation::new(votes)
+}
+
+/// The weight curve inside `cast_vote`: what an eligibility oracle's
+/// `stake` for the voter counts as under `curve`.  The ballot's weight is
+/// then multiplied by it, so pass the product to [`cast_weighted_vote`].
+pub fn curved_stake(stake: u64, curve: WeightCurve) -> u64 {
+    match curve {
+        WeightCurve::Linear => stake,
+        WeightCurve::Cap { max } => stake.min(max),
+        WeightCurve::Sqrt => stake.isqrt(),
+        WeightCurve::Log => u64::from(u64::BITS - stake.leading_zeros()),
+    }
+}
+
+/// `cast_likert`: add the offset scores and count the ballot if every
+/// score is on the scale, otherwise leave the tallies untouched.  Returns
+/// whether the ballot was counted.
+pub fn cast_likert(tallies: &mut Tallies, scores: &VoteAllocation) -> bool {
+    if !scores.is_on_likert_scale() {
+        return false;
+    }
+    for (tally, score) in tallies.options.iter_mut().zip(scores.votes) {
+        *tally += score;
+    }
+    tallies.total_votes += 1;
+    true
+}
+
+/// `cast_choice`: add one to the chosen option and count the ballot if it
+/// is one-hot over For/Against/Abstain, otherwise leave the tallies
+/// untouched.  Returns whether the ballot was counted.
+pub fn cast_choice(tallies: &mut Tallies, choice: &VoteAllocation) -> bool {
+    if !choice.is_one_choice() {
+        return false;
+    }
+    for (tally, chosen) in tallies.options.iter_mut().zip(choice.votes) {
+        *tally += chosen;
+    }
+    tallies.total_votes += 1;
+    true
+}
+
+/// `cast_ballot_choices`: add one to the chosen option of each proposal's
+/// tallies, in order, and count the ballot there if the choice is For (0),
+/// Against (1) or Abstain (2); a choice past Abstain leaves that
+/// proposal's tallies untouched.  Returns whether each was counted.
+pub fn cast_ballot_choices(tallies: &mut [Tallies], choices: &[u64]) -> Vec<bool> {
+    tallies
+        .iter_mut()
+        .zip(choices)
+        .map(|(tallies, &choice)| {
+            if choice > 2 {
+                return false;
+            }
+            tallies.options[choice as usize] += 1;
+            tallies.total_votes += 1;
+            true
+        })
+        .collect()
+}
+
+/// `cast_approval`: add one to every approved option and count the ballot
+/// if it approves at most `max_choices` of the first `options` options,
+/// otherwise leave the tallies untouched.  Returns whether the ballot was
+/// counted.
+pub fn cast_approval(
+    tallies: &mut Tallies,
+    approvals: &VoteAllocation,
+    max_choices: u8,
+    options: u8,
+) -> bool {
+    if !approvals.is_approval(max_choices, options) {
+        return false;
+    }
+    for (tally, approved) in tallies.options.iter_mut().zip(approvals.votes) {
+        *tally += approved;
+    }
+    tallies.total_votes += 1;
+    true
+}
+
+/// The 24 orders of four options, lexicographic, as the circuits lay out
+/// `RankingCounts`.
+pub fn rank_orders() -> impl Iterator<Item = [usize; 4]> {
+    (0..256usize)
+        .map(|n| [n >> 6, (n >> 4) & 3, (n >> 2) & 3, n & 3])
+        .filter(|order| (1..4).all(|i| !order[..i].contains(&order[i])))
+}
+
+/// `init_rankings`: every count starts at zero.
+pub fn init_rankings() -> RankingCounts {
+    RankingCounts::default()
+}
+
+/// `cast_ranked_vote`: count the ballot's order, and its first preference
+/// in the tallies, if it ranks every option with the first `options`
+/// places holding the proposal's own; otherwise leave both untouched.
+/// Returns whether the ballot was counted.
+pub fn cast_ranked_vote(
+    tallies: &mut Tallies,
+    rankings: &mut RankingCounts,
+    ranking: &VoteAllocation,
+    options: u8,
+) -> bool {
+    if !ranking.is_ranking(options) {
+        return false;
+    }
+    let index = rank_orders()
+        .position(|order| order.map(|o| o as u64) == ranking.votes)
+        .expect("a ranking is an order");
+    rankings.counts[index] += 1;
+    tallies.options[ranking.votes[0] as usize] += 1;
+    tallies.total_votes += 1;
+    true
+}
+
+/// `cast_borda`: if the ballot ranks every option with the first
+/// `options` places holding the proposal's own, add `options - 1` points
+/// to the first, one fewer to each after it, and count the ballot;
+/// otherwise leave the tallies untouched.  Returns whether the ballot was
+/// counted.
+pub fn cast_borda(tallies: &mut Tallies, ranking: &VoteAllocation, options: u8) -> bool {
+    if !ranking.is_ranking(options) {
+        return false;
+    }
+    for (place, option) in ranking.votes.iter().take(options as usize).enumerate() {
+        tallies.options[*option as usize] += u64::from(options) - 1 - place as u64;
+    }
+    tallies.total_votes += 1;
+    true
+}
+
+/// `init_preferences`: every count starts at zero.
+pub fn init_preferences() -> Preferences {
+    Preferences::default()
+}
+
+/// `cast_condorcet`: if the ballot ranks every option with the first
+/// `options` places holding the proposal's own, count it for every option
+/// over each one it places below, and its first preference in the tallies;
+/// otherwise leave both untouched.  Returns whether the ballot was counted.
+pub fn cast_condorcet(
+    tallies: &mut Tallies,
+    preferences: &mut Preferences,
+    ranking: &VoteAllocation,
+    options: u8,
+) -> bool {
+    if !ranking.is_ranking(options) {
+        return false;
+    }
+    for (above, i) in ranking.votes.iter().enumerate() {
+        for j in &ranking.votes[above + 1..] {
+            preferences.wins[4 * *i as usize + *j as usize] += 1;
+        }
+    }
+    tallies.options[ranking.votes[0] as usize] += 1;
+    tallies.total_votes += 1;
+    true
+}
+
+/// `cast_star`: if every score is at most [`STAR_MAX_SCORE`] and those
+/// past `options` are 0, add the scores to the tallies, count the ballot
+/// and count every option over each one it scored lower; otherwise leave
+/// both untouched.  Returns whether the ballot was counted.
+pub fn cast_star(tallies: &mut Tallies, preferences: &mut Preferences, scores: &VoteAllocation, options: u8) -> bool {
+    let on_scale = scores.votes.iter().enumerate().all(|(i, s)| {
+        let cap = if i < usize::from(options) { STAR_MAX_SCORE } else { 0 };
+        *s <= cap
+    });
+    if !on_scale {
+        return false;
+    }
+    for (i, above) in scores.votes.iter().enumerate() {
+        for (j, below) in scores.votes.iter().enumerate() {
+            if above > below {
+                preferences.wins[4 * i + j] += 1;
+            }
+        }
+    }
+    for (tally, score) in tallies.options.iter_mut().zip(scores.votes) {
+        *tally += score;
+    }
+    tallies.total_votes += 1;
+    true
+}
+
+/// `cast_range`: if every score is at most [`RANGE_MAX_SCORE`] and zero
+/// past the proposal's `options`, add each score to its option and count
+/// the ballot; otherwise leave the tallies untouched.  Returns whether the
+/// ballot was counted.
+pub fn cast_range(tallies: &mut Tallies, scores: &VoteAllocation, options: u8) -> bool {
+    let on_scale = scores.votes.iter().enumerate().all(|(i, s)| {
+        let cap = if i < usize::from(options) { RANGE_MAX_SCORE } else { 0 };
+        *s <= cap
+    });
+    if !on_scale {
+        return false;
+    }
+    for (tally, score) in tallies.options.iter_mut().zip(scores.votes) {
+        *tally += score;
+    }
+    tallies.total_votes += 1;
+    true
+}
+
+/// `cast_jury_scores`: add each application's scores summed over the
+/// criteria and count the juror if every score is in range for the
+/// proposal's `criteria`, `max_score` and `applications`, otherwise leave
+/// the tallies untouched.  Returns whether the ballot was counted.
+pub fn cast_jury_scores(
+    tallies: &mut Tallies,
+    scores: &JuryScores,
+    criteria: u8,
+    max_score: u8,
+    applications: u8,
+) -> bool {
+    if scores.out_of_range(criteria, max_score, applications).is_some() {
+        return false;
+    }
+    for (tally, total) in tallies.options.iter_mut().zip(scores.totals()) {
+        *tally += total;
+    }
+    tallies.total_votes += 1;
+    true
+}
+
+/// Applications from the highest revealed jury score down, ties in option
+/// order, as `ProposalAccount::jury_ranking` computes it on-chain.
+pub fn jury_ranking(results: &RevealedResults, applications: u8) -> Vec<u8> {
+    let mut ranking: Vec<u8> = (0..applications).collect();
+    ranking.sort_by_key(|i| std::cmp::Reverse(results.options[*i as usize]));
+    ranking
+}
+
+/// Net -2..+2 sentiment per option from revealed Likert results, as
+/// `ProposalAccount::net_sentiment` computes it on-chain.
+pub fn net_sentiment(results: &RevealedResults) -> [i64; 4] {
+    let offset = 2 * results.total_votes as i64;
+    results.options.map(|sum| sum as i64 - offset)
+}
+
+/// `reveal_results`: the winner is the first option with the strictly
+/// highest tally, so ties go to the lowest index.
+pub fn reveal_results(tallies: &Tallies) -> RevealedResults {
+    let mut winner = 0;
+    for (i, tally) in tallies.options.iter().enumerate().skip(1) {
+        if *tally > tallies.options[winner] {
+            winner = i;
+        }
+    }
+    RevealedResults {
+        options: tallies.options,
+        total_votes: tallies.total_votes,
+        winner: winner as u8,
+        outcome: None,
+        funded: None,
+    }
+}
+
+/// `cast_pairwise` then `reveal_pairwise` over a round's ballots, in cast
+/// order, already masked with [`mask_options`].  Ballots over `budget`,
+/// the proposal's [`effective_budget`](crate::validation::effective_budget),
+/// are sealed as zeros.  Each option's funding is Σ v² plus, for every pair,
+/// 2·v_i·v_j scaled by `discount / (discount + overlap)` rounded to the
+/// nearest half (ties round up); a `discount` of 0 keeps every pair
+/// whole, which is (Σ v)².
+pub fn reveal_pairwise(ballots: &[VoteAllocation], discount: u32, budget: u64) -> RevealedResults {
+    let sealed: Vec<[u64; 4]> = ballots
+        .iter()
+        .map(|b| if b.is_within_budget(budget) { b.votes } else { [0; 4] })
+        .collect();
+    let discount = u64::from(discount);
+
+    let mut funding = [0u64; 4];
+    for v in &sealed {
+        for (f, x) in funding.iter_mut().zip(v) {
+            *f += x * x;
+        }
+    }
+    for (i, a) in sealed.iter().enumerate() {
+        for b in &sealed[i + 1..] {
+            let overlap: u64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+            let halves = u64::from(discount == 0 || overlap * 3 <= discount)
+                + u64::from(discount == 0 || overlap <= discount * 3);
+            for (p, f) in funding.iter_mut().enumerate() {
+                *f += halves * a[p] * b[p];
+            }
+        }
+    }
+
+    let mut winner = 0;
+    for (i, f) in funding.iter().enumerate().skip(1) {
+        if *f > funding[winner] {
+            winner = i;
+        }
+    }
+    RevealedResults {
+        options: funding,
+        total_votes: funding.iter().sum(),
+        winner: winner as u8,
+        outcome: None,
+        funded: None,
+    }
+}
+
+/// `reveal_outcome`: Against above `veto_bps` of turnout vetoes (0
+/// disables the veto), otherwise For's share of For and Against must be
+/// over `pass_bps` (5,000 unless fast-tracked) plus `bias_bps` times the
+/// share of `electorate` that didn't vote (0 disables the bias).  The
+/// winner only looks at the three real options.
+pub fn reveal_outcome(
+    tallies: &Tallies,
+    veto_bps: u16,
+    pass_bps: u16,
+    bias_bps: u16,
+    electorate: u64,
+) -> RevealedResults {
+    let [votes_for, against, abstain, _] = tallies.options;
+    let mut winner = 0;
+    for (i, tally) in [against, abstain].into_iter().enumerate() {
+        if tally > tallies.options[winner] {
+            winner = i + 1;
+        }
+    }
+    let absent = electorate.saturating_sub(tallies.total_votes);
+    let outcome = if veto_bps > 0 && against * 10_000 > u64::from(veto_bps) * tallies.total_votes {
+        VoteOutcome::Vetoed
+    } else if u128::from(votes_for) * 10_000 * u128::from(electorate)
+        > (u128::from(pass_bps) * u128::from(electorate) + u128::from(bias_bps) * u128::from(absent))
+            * u128::from(votes_for + against)
+    {
+        VoteOutcome::Passed
+    } else {
+        VoteOutcome::Rejected
+    };
+    RevealedResults {
+        options: tallies.options,
+        total_votes: tallies.total_votes,
+        winner: winner as u8,
+        outcome: Some(outcome),
+        funded: None,
+    }
+}
+
+/// `reveal_chambers`: decide each chamber as [`reveal_outcome`] does, then
+/// combine them per `rule`.  A combined failure is a veto if either
+/// chamber vetoed.
+pub fn reveal_chambers(community: &Tallies, council: &Tallies, veto_bps: u16, rule: ChamberRule) -> ChamberResults {
+    let results = reveal_outcome(community, veto_bps, 5_000, 0, 1);
+    let community_outcome = results.outcome.expect("reveal_outcome decides");
+    let council_outcome = reveal_outcome(council, veto_bps, 5_000, 0, 1).outcome.expect("reveal_outcome decides");
+    let vetoed = community_outcome == VoteOutcome::Vetoed || council_outcome == VoteOutcome::Vetoed;
+    let passed = match rule {
+        ChamberRule::BothMustPass => community_outcome == VoteOutcome::Passed && council_outcome == VoteOutcome::Passed,
+        ChamberRule::Either => community_outcome == VoteOutcome::Passed || council_outcome == VoteOutcome::Passed,
+        ChamberRule::Weighted { council_bps } => {
+            // For's share of For and Against per chamber, an empty one
+            // counting as 0 of 1, weighted and compared to one half.
+            let share = |t: &Tallies| (u128::from(t.options[0]), u128::from(t.options[0] + t.options[1]).max(1));
+            let (community_for, community_cast) = share(community);
+            let (council_for, council_cast) = share(council);
+            let weight = u128::from(council_bps);
+            let weighted_for =
+                (10_000 - weight) * community_for * council_cast + weight * council_for * community_cast;
+            2 * weighted_for > 10_000 * community_cast * council_cast && !vetoed
+        }
+    };
+    let outcome = if passed {
+        VoteOutcome::Passed
+    } else if vetoed {
+        VoteOutcome::Vetoed
+    } else {
+        VoteOutcome::Rejected
+    };
+    ChamberResults {
+        community: RevealedResults {
+            outcome: Some(outcome),
+            ..results
+        },
+        community_outcome,
+        council: *council,
+        council_outcome,
+    }
+}
+
+/// `reveal_budget_box`: of the subsets of options whose `costs` fit in
+/// `budget`, fund the one with the most approvals, the smallest bitmask on
+/// a tie; funding nothing when no approved option fits.
+pub fn reveal_budget_box(tallies: &Tallies, budget: u64, costs: [u64; 4]) -> RevealedResults {
+    let mut best_support = 0;
+    let mut funded = 0u8;
+    for mask in 0..16u8 {
+        let members = (0..4).filter(|i| mask & (1 << i) != 0);
+        let cost: u64 = members.clone().map(|i| costs[i]).sum();
+        let support: u64 = members.map(|i| tallies.options[i]).sum();
+        if cost <= budget && support > best_support {
+            best_support = support;
+            funded = mask;
+        }
+    }
+    RevealedResults {
+        funded: Some(funded),
+        ..reveal_results(tallies)
+    }
+}
+
+/// `reveal_ranked`: instant runoff among the first `options` options.
+/// Each round gives every ballot to its highest-ranked option still
+/// standing; one with more than half the ballots wins, the first on a tie,
+/// otherwise the one with the fewest is eliminated, the last on a tie.
+/// `options` of the result are the deciding round's counts; option 0 wins
+/// when nobody voted.
+pub fn reveal_ranked(rankings: &RankingCounts, options: u8) -> RevealedResults {
+    let total: u64 = rankings.counts.iter().sum();
+    let mut standing: Vec<usize> = (0..usize::from(options.min(4))).collect();
+    let mut votes = [0u64; 4];
+    let mut winner = 0;
+    while let Some(&first) = standing.first() {
+        votes = [0; 4];
+        for (order, count) in rank_orders().zip(rankings.counts) {
+            if let Some(top) = order.iter().find(|o| standing.contains(o)) {
+                votes[*top] += count;
+            }
+        }
+        let leader = standing
+            .iter()
+            .fold(first, |best, &i| if votes[i] > votes[best] { i } else { best });
+        if 2 * votes[leader] > total {
+            winner = leader;
+            break;
+        }
+        let loser = standing
+            .iter()
+            .fold(first, |worst, &i| if votes[i] <= votes[worst] { i } else { worst });
+        standing.retain(|i| *i != loser);
+    }
+    RevealedResults {
+        options: votes,
+        total_votes: total,
+        winner: winner as u8,
+        outcome: None,
+        funded: None,
+    }
+}
+
+/// `reveal_condorcet`: Copeland count among the first `options` options.
+/// `options` of the result are the head-to-head contests each option wins
+/// outright; the one winning most wins, the first on a tie.  The flag is
+/// the circuit's `cycle`: set unless the winner beats every other option.
+pub fn reveal_condorcet(preferences: &Preferences, options: u8) -> (RevealedResults, bool) {
+    let n = usize::from(options.min(4));
+    let mut beaten = [0u64; 4];
+    for (i, count) in beaten.iter_mut().enumerate().take(n) {
+        for j in 0..n {
+            if i != j && preferences.wins[4 * i + j] > preferences.wins[4 * j + i] {
+                *count += 1;
+            }
+        }
+    }
+    let winner = (1..n).fold(0, |best, i| if beaten[i] > beaten[best] { i } else { best });
+    let results = RevealedResults {
+        options: beaten,
+        total_votes: preferences.wins[1] + preferences.wins[4],
+        winner: winner as u8,
+        outcome: None,
+        funded: None,
+    };
+    (results, beaten[winner] + 1 != u64::from(options))
+}
+
+/// `reveal_star`: the two of the first `options` options with the highest
+/// score totals, the first on a tie, are run off; the one more ballots
+/// scored above the other wins, and a tied runoff goes to the higher
+/// total.
+pub fn reveal_star(tallies: &Tallies, preferences: &Preferences, options: u8) -> StarResults {
+    let n = usize::from(options.min(4));
+    let top = |skip: Option<usize>| {
+        (0..n)
+            .filter(|i| Some(*i) != skip)
+            .fold(None, |best: Option<usize>, i| match best {
+                Some(b) if tallies.options[i] <= tallies.options[b] => Some(b),
+                _ => Some(i),
+            })
+            .unwrap_or(0)
+    };
+    let first = top(None);
+    let second = top(Some(first));
+    let over_second = preferences.wins[4 * first + second];
+    let over_first = preferences.wins[4 * second + first];
+    let (winner, runner_up, runoff) = if over_first > over_second {
+        (second, first, [over_first, over_second])
+    } else {
+        (first, second, [over_second, over_first])
+    };
+    StarResults {
+        results: RevealedResults {
+            options: tallies.options,
+            total_votes: tallies.total_votes,
+            winner: winner as u8,
+            outcome: None,
+            funded: None,
+        },
+        runner_up: runner_up as u8,
+        runoff,
+    }
+}
+
+/// `cast_write_in`: count `candidate` in its slot, or claim the first free
+/// one.  Returns whether the write-in was counted; it is dropped for a hash
+/// of 0 or once every slot holds another candidate.
+pub fn cast_write_in(write_ins: &mut WriteIns, candidate: u128) -> bool {
+    if candidate == 0 {
+        return false;
+    }
+    let slot = match write_ins.hashes.iter().position(|h| *h == candidate) {
+        Some(slot) => slot,
+        None => match write_ins.hashes.iter().position(|h| *h == 0) {
+            Some(slot) => {
+                write_ins.hashes[slot] = candidate;
+                slot
+            }
+            None => return false,
+        },
+    };
+    write_ins.counts[slot] += 1;
+    true
+}
+
+/// `reveal_write_in`: the candidate hash with the strictly highest count
+/// and that count, ties going to the earliest written in.  `(0, 0)` when
+/// nobody wrote anyone in.
+pub fn reveal_write_in(write_ins: &WriteIns) -> (u128, u64) {
+    let mut top = 0;
+    for (i, count) in write_ins.counts.iter().enumerate().skip(1) {
+        if *count > write_ins.counts[top] {
+            top = i;
+        }
+    }
+    (write_ins.hashes[top], write_ins.counts[top])
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    /// The README scenario from `arcvote-vectors`, cast under its proposal's
+    /// `voice_credits`.
+    const VECTORS: &str = include_str!("../../arcvote-vectors/vectors.json");
+    const VOICE_CREDITS: u64 = 100;
+
+    fn u64s(value: &Value) -> Vec<u64> {
+        value.as_array().unwrap().iter().map(|v| v.as_u64().unwrap()).collect()
+    }
+
+    fn tallies(value: &Value) -> Tallies {
+        Tallies {
+            options: u64s(&value["options"]).try_into().unwrap(),
+            total_votes: value["total_votes"].as_u64().unwrap(),
+        }
+    }
+
+    #[test]
+    fn cast_vote_replays_the_vectors() {
+        let vectors: Value = serde_json::from_str(VECTORS).expect("vectors.json parses");
+        let mut replayed = init_tallies();
+        assert_eq!(replayed, tallies(&vectors["init_tallies"]));
+        for ballot in vectors["cast_vote"].as_array().unwrap() {
+            let allocation = VoteAllocation::new(u64s(&ballot["allocation"]).try_into().unwrap());
+            let counted = cast_vote(&mut replayed, &allocation, VOICE_CREDITS);
+            assert_eq!(counted, ballot["counted"].as_bool().unwrap(), "{:?}", allocation.votes);
+            assert_eq!(replayed, tallies(&ballot["tallies_after"]));
+        }
+
+        let results = reveal_results(&replayed);
+        let expected = &vectors["reveal_results"];
+        assert_eq!(results.options.to_vec(), u64s(&expected["options"]));
+        assert_eq!(results.total_votes, expected["total_votes"].as_u64().unwrap());
+        assert_eq!(u64::from(results.winner), expected["winner"].as_u64().unwrap());
+    }
+
+    #[test]
+    fn over_budget_and_overflowing_ballots_leave_the_tallies_alone() {
+        let mut tallies = init_tallies();
+        assert!(cast_vote(&mut tallies, &VoteAllocation::new([7, 3, 1, 0]), VOICE_CREDITS));
+        let before = tallies;
+        assert!(!cast_vote(&mut tallies, &VoteAllocation::new([10, 1, 0, 0]), VOICE_CREDITS));
+        for votes in [[u64::MAX, 0, 0, 0], [1 << 32, 0, 0, 0]] {
+            assert!(!cast_vote(&mut tallies, &VoteAllocation::new(votes), u64::MAX));
+        }
+        assert_eq!(tallies, before);
+        assert!(cast_vote(&mut tallies, &VoteAllocation::new([10, 1, 0, 0]), 101));
+    }
+
+    #[test]
+    fn weight_applies_after_the_budget_check() {
+        let mut tallies = init_tallies();
+        assert!(cast_weighted_vote(&mut tallies, &VoteAllocation::new([10, 0, 0, 0]), 3, VOICE_CREDITS));
+        assert_eq!(tallies.options, [30, 0, 0, 0]);
+        assert_eq!(tallies.total_votes, 30);
+        assert!(!cast_weighted_vote(&mut tallies, &VoteAllocation::new([10, 1, 0, 0]), 3, VOICE_CREDITS));
+    }
+
+    #[test]
+    fn masked_options_cost_nothing() {
+        let masked = mask_options(&VoteAllocation::new([3, 0, 9, 9]), 2);
+        assert_eq!(masked.votes, [3, 0, 0, 0]);
+        assert_eq!(masked.cost(), Some(9));
+        assert_eq!(mask_options(&masked, 4), masked);
+    }
+
+    #[test]
+    fn pairwise_seals_over_budget_ballots() {
+        let ballots = [
+            VoteAllocation::new([3, 0, 0, 0]),
+            VoteAllocation::new([4, 0, 0, 0]),
+            VoteAllocation::new([10, 1, 0, 0]),
+        ];
+        let results = reveal_pairwise(&ballots, 0, VOICE_CREDITS);
+        assert_eq!(results.options, [49, 0, 0, 0]);
+        assert_eq!(results.winner, 0);
+        assert_eq!(reveal_pairwise(&ballots, 0, 101).options, [289, 1, 0, 0]);
+    }
+
+    #[test]
+    fn ballot_choices_count_each_race_on_its_own() {
+        let mut one = [init_tallies()];
+        assert_eq!(cast_ballot_choices(&mut one, &[1]), [true]);
+        assert_eq!(one[0].options, [0, 1, 0, 0]);
+
+        let mut two = [init_tallies(); 2];
+        assert_eq!(cast_ballot_choices(&mut two, &[0, 3]), [true, false]);
+        assert_eq!(two[0].options, [1, 0, 0, 0]);
+        assert_eq!(two[1], init_tallies());
+
+        let mut all = [init_tallies(); private_voting::MAX_BALLOT_PROPOSALS];
+        let choices = [0, 1, 2, 0, 1, 2, 0, 1];
+        assert_eq!(cast_ballot_choices(&mut all, &choices), [true; 8]);
+        for (tallies, choice) in all.iter().zip(choices) {
+            assert_eq!(tallies.options[choice as usize], 1);
+            assert_eq!(tallies.total_votes, 1);
+        }
+    }
+
+    #[test]
+    fn ties_go_to_the_lowest_option() {
+        let tallies = Tallies {
+            options: [1, 5, 5, 2],
+            total_votes: 13,
+        };
+        assert_eq!(reveal_results(&tallies).winner, 1);
+    }
+}
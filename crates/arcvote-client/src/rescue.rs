@@ -0,0 +1,349 @@
+//! Self-contained Rescue cipher and Rescue-Prime hash over GF(2^255 - 19),
+//! byte-compatible with the Arcis runtime and `@arcium-hq/client`.
+//!
+//! Kept free of the Arcis compiler so the SDK also builds for
+//! `wasm32-unknown-unknown`.
+
+use ff::{Field, PrimeField};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
+
+#[allow(clippy::derived_hash_with_manual_eq)]
+mod field {
+    use ff::PrimeField;
+
+    /// GF(2^255 - 19), the base field of Curve25519.
+    #[derive(PrimeField)]
+    #[PrimeFieldModulus = "57896044618658097711785492504343953926634992332820282019728792003956564819949"]
+    #[PrimeFieldGenerator = "2"]
+    #[PrimeFieldReprEndianness = "little"]
+    pub struct Fp([u64; 4]);
+}
+
+pub use field::Fp;
+
+const MODULUS_DECIMAL: &str =
+    "57896044618658097711785492504343953926634992332820282019728792003956564819949";
+
+/// S-box exponent: the smallest prime not dividing p - 1.
+const ALPHA: [u64; 1] = [5];
+/// ALPHA⁻¹ mod (p - 1) = (3(p - 1) + 1) / 5.
+const ALPHA_INVERSE: [u64; 4] = [
+    0xcccc_cccc_cccc_ccc1,
+    0xcccc_cccc_cccc_cccc,
+    0xcccc_cccc_cccc_cccc,
+    0x4ccc_cccc_cccc_cccc,
+];
+
+/// Cipher state width (also the key length).
+pub const CIPHER_WIDTH: usize = 5;
+/// Rounds for a 128-bit secure block cipher with width 5 and alpha 5.
+const CIPHER_ROUNDS: usize = 10;
+
+const HASH_WIDTH: usize = 12;
+const HASH_CAPACITY: usize = 5;
+const HASH_RATE: usize = HASH_WIDTH - HASH_CAPACITY;
+/// Rounds for 256-bit security with width 12, capacity 5 and alpha 5.
+const HASH_ROUNDS: usize = 8;
+
+/// Bytes of SHAKE256 output per sampled constant: field size + 16 so the
+/// reduction is statistically close to uniform.
+const SAMPLE_BYTES: usize = 32 + 16;
+
+type Vector = Vec<Fp>;
+type Matrix = Vec<Vector>;
+
+#[derive(Clone, Copy)]
+enum Mode {
+    BlockCipher,
+    Hash,
+}
+
+struct Desc {
+    mode: Mode,
+    mds: Matrix,
+    round_keys: Vec<Vector>,
+}
+
+impl Desc {
+    fn cipher(key: &[Fp; CIPHER_WIDTH]) -> Self {
+        let mds = cauchy_mds(CIPHER_WIDTH);
+        let constants = cipher_constants();
+        // Key schedule: run the permutation on the key with the round
+        // constants and keep every intermediate state.
+        let round_keys = permutation_states(Mode::BlockCipher, &mds, &constants, key.to_vec());
+        Self {
+            mode: Mode::BlockCipher,
+            mds,
+            round_keys,
+        }
+    }
+
+    fn hash() -> Self {
+        Self {
+            mode: Mode::Hash,
+            mds: cauchy_mds(HASH_WIDTH),
+            round_keys: hash_constants(),
+        }
+    }
+
+    fn permute(&self, state: Vector) -> Vector {
+        permutation_states(self.mode, &self.mds, &self.round_keys, state)
+            .pop()
+            .expect("permutation yields at least one state")
+    }
+}
+
+fn permutation_states(mode: Mode, mds: &Matrix, keys: &[Vector], state: Vector) -> Vec<Vector> {
+    let (even, odd): (&[u64], &[u64]) = match mode {
+        Mode::BlockCipher => (&ALPHA_INVERSE, &ALPHA),
+        Mode::Hash => (&ALPHA, &ALPHA_INVERSE),
+    };
+    let mut states = vec![add(&state, &keys[0])];
+    for (r, key) in keys[1..].iter().enumerate() {
+        let exponent = if r % 2 == 0 { even } else { odd };
+        let s: Vector = states[r].iter().map(|x| x.pow_vartime(exponent)).collect();
+        states.push(add(&mat_vec(mds, &s), key));
+    }
+    states
+}
+
+fn cipher_constants() -> Vec<Vector> {
+    let m = CIPHER_WIDTH;
+    let mut reader = shake(b"encrypt everything, compute anything");
+    let mut matrix = sample_matrix(&mut reader, m);
+    let initial = sample_vector(&mut reader, m);
+    let affine = sample_vector(&mut reader, m);
+    while det(&matrix) == Fp::ZERO {
+        matrix = sample_matrix(&mut reader, m);
+    }
+
+    let mut constants = vec![initial];
+    for i in 0..2 * CIPHER_ROUNDS {
+        constants.push(add(&mat_vec(&matrix, &constants[i]), &affine));
+    }
+    constants
+}
+
+fn hash_constants() -> Vec<Vector> {
+    let seed = format!("Rescue-XLIX({MODULUS_DECIMAL},{HASH_WIDTH},{HASH_CAPACITY},256)");
+    let mut reader = shake(seed.as_bytes());
+    // A leading zero key makes the odd-length schedule match Algorithm 3 of
+    // the Rescue-Prime paper.
+    let mut constants = vec![vec![Fp::ZERO; HASH_WIDTH]];
+    for _ in 0..2 * HASH_ROUNDS {
+        constants.push(sample_vector(&mut reader, HASH_WIDTH));
+    }
+    constants
+}
+
+fn shake(seed: &[u8]) -> impl XofReader {
+    let mut hasher = Shake256::default();
+    hasher.update(seed);
+    hasher.finalize_xof()
+}
+
+fn sample(reader: &mut impl XofReader) -> Fp {
+    let mut bytes = [0u8; SAMPLE_BYTES];
+    reader.read(&mut bytes);
+    from_le_bytes_wide(&bytes)
+}
+
+fn sample_vector(reader: &mut impl XofReader, len: usize) -> Vector {
+    (0..len).map(|_| sample(reader)).collect()
+}
+
+fn sample_matrix(reader: &mut impl XofReader, m: usize) -> Matrix {
+    (0..m).map(|_| sample_vector(reader, m)).collect()
+}
+
+/// Cauchy matrix with x_i = i, y_j = -j (1-based), which is always MDS.
+fn cauchy_mds(m: usize) -> Matrix {
+    (1..=m as u64)
+        .map(|i| {
+            (1..=m as u64)
+                .map(|j| (Fp::from(i) + Fp::from(j)).invert().unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+fn add(a: &[Fp], b: &[Fp]) -> Vector {
+    a.iter().zip(b).map(|(x, y)| *x + y).collect()
+}
+
+fn mat_vec(matrix: &Matrix, v: &[Fp]) -> Vector {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(v).fold(Fp::ZERO, |acc, (a, b)| acc + *a * b))
+        .collect()
+}
+
+/// Determinant by Gaussian elimination.
+fn det(matrix: &Matrix) -> Fp {
+    let mut rows = matrix.clone();
+    let n = rows.len();
+    let mut det = Fp::ONE;
+    for col in 0..n {
+        let Some(pivot) = (col..n).find(|&r| rows[r][col] != Fp::ZERO) else {
+            return Fp::ZERO;
+        };
+        if pivot != col {
+            rows.swap(pivot, col);
+            det = -det;
+        }
+        det *= rows[col][col];
+        let inv = rows[col][col].invert().unwrap();
+        for r in col + 1..n {
+            let factor = rows[r][col] * inv;
+            for c in col..n {
+                let delta = factor * rows[col][c];
+                rows[r][c] -= delta;
+            }
+        }
+    }
+    det
+}
+
+/// Reduces an arbitrary-length little-endian integer modulo p.
+fn from_le_bytes_wide(bytes: &[u8]) -> Fp {
+    let two_pow_64 = Fp::from(u64::MAX) + Fp::ONE;
+    bytes.chunks(8).rev().fold(Fp::ZERO, |acc, chunk| {
+        let mut limb = [0u8; 8];
+        limb[..chunk.len()].copy_from_slice(chunk);
+        acc * two_pow_64 + Fp::from(u64::from_le_bytes(limb))
+    })
+}
+
+/// Canonical field element from 32 little-endian bytes, or `None` if ≥ p.
+pub fn fp_from_le_bytes(bytes: [u8; 32]) -> Option<Fp> {
+    Fp::from_repr(field::FpRepr(bytes)).into()
+}
+
+pub fn fp_to_le_bytes(value: Fp) -> [u8; 32] {
+    value.to_repr().0
+}
+
+/// Rescue-Prime sponge (rate 7, capacity 5) truncated to 5 elements.
+pub fn rescue_prime_hash(mut message: Vec<Fp>) -> [Fp; CIPHER_WIDTH] {
+    let desc = Desc::hash();
+    message.push(Fp::ONE);
+    while message.len() % HASH_RATE != 0 {
+        message.push(Fp::ZERO);
+    }
+    let mut state = vec![Fp::ZERO; HASH_WIDTH];
+    for chunk in message.chunks(HASH_RATE) {
+        for (s, m) in state.iter_mut().zip(chunk) {
+            *s += m;
+        }
+        state = desc.permute(state);
+    }
+    [state[0], state[1], state[2], state[3], state[4]]
+}
+
+/// Rescue block cipher in counter mode.
+pub struct RescueCipher {
+    desc: Desc,
+}
+
+impl RescueCipher {
+    pub fn new(key: [Fp; CIPHER_WIDTH]) -> Self {
+        Self {
+            desc: Desc::cipher(&key),
+        }
+    }
+
+    /// Derives the key from an x25519 shared secret with the Rescue-Prime
+    /// KDF `H(1 || Z || 5)` (NIST SP 800-56C, option 1).
+    pub fn from_shared_secret(shared_secret: &[u8; 32]) -> Self {
+        let z = from_le_bytes_wide(shared_secret);
+        Self::new(rescue_prime_hash(vec![
+            Fp::ONE,
+            z,
+            Fp::from(CIPHER_WIDTH as u64),
+        ]))
+    }
+
+    fn keystream(&self, nonce: Fp, len: usize) -> Vec<Fp> {
+        (0..len.div_ceil(CIPHER_WIDTH))
+            .flat_map(|block| {
+                let mut counter = vec![Fp::ZERO; CIPHER_WIDTH];
+                counter[0] = nonce;
+                counter[1] = Fp::from(block as u64);
+                self.desc.permute(counter)
+            })
+            .take(len)
+            .collect()
+    }
+
+    pub fn encrypt(&self, plaintexts: &[Fp], nonce: Fp) -> Vec<Fp> {
+        let stream = self.keystream(nonce, plaintexts.len());
+        plaintexts.iter().zip(stream).map(|(p, k)| *p + k).collect()
+    }
+
+    pub fn decrypt(&self, ciphertexts: &[Fp], nonce: Fp) -> Vec<Fp> {
+        let stream = self.keystream(nonce, ciphertexts.len());
+        ciphertexts.iter().zip(stream).map(|(c, k)| *c - k).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> [Fp; CIPHER_WIDTH] {
+        std::array::from_fn(|i| Fp::from(i as u64 * 1_000_003 + 17))
+    }
+
+    #[test]
+    fn cipher_round_trips_across_blocks() {
+        let cipher = RescueCipher::new(key());
+        // Twelve elements take three keystream blocks.
+        let plaintexts: Vec<Fp> = (0..12).map(|i| Fp::from(i) - Fp::from(6)).collect();
+        let nonce = Fp::from_u128(u128::MAX);
+        let ciphertexts = cipher.encrypt(&plaintexts, nonce);
+        assert_ne!(ciphertexts, plaintexts);
+        assert_eq!(cipher.decrypt(&ciphertexts, nonce), plaintexts);
+    }
+
+    #[test]
+    fn each_nonce_and_block_draws_its_own_keystream() {
+        let cipher = RescueCipher::new(key());
+        let zeros = vec![Fp::ZERO; 2 * CIPHER_WIDTH];
+        let first = cipher.encrypt(&zeros, Fp::ONE);
+        assert_ne!(first, cipher.encrypt(&zeros, Fp::from(2)));
+        assert_ne!(first[..CIPHER_WIDTH], first[CIPHER_WIDTH..]);
+    }
+
+    #[test]
+    fn shared_secret_keys_differ() {
+        let plaintexts = [Fp::from(7)];
+        let a = RescueCipher::from_shared_secret(&[1; 32]).encrypt(&plaintexts, Fp::ONE);
+        let b = RescueCipher::from_shared_secret(&[2; 32]).encrypt(&plaintexts, Fp::ONE);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn field_bytes_round_trip_below_the_modulus() {
+        for value in [Fp::ZERO, Fp::ONE, Fp::from_u128(u128::MAX), -Fp::ONE] {
+            assert_eq!(fp_from_le_bytes(fp_to_le_bytes(value)), Some(value));
+        }
+        // p = 2^255 - 19 itself, little-endian.
+        let mut modulus = [0xff; 32];
+        modulus[0] = 0xed;
+        modulus[31] = 0x7f;
+        assert_eq!(fp_from_le_bytes(modulus), None);
+        assert_eq!(fp_from_le_bytes([0xff; 32]), None);
+    }
+
+    #[test]
+    fn hash_padding_separates_trailing_zeros() {
+        assert_ne!(rescue_prime_hash(vec![]), rescue_prime_hash(vec![Fp::ZERO]));
+        assert_ne!(
+            rescue_prime_hash(vec![Fp::ONE; HASH_RATE - 1]),
+            rescue_prime_hash(vec![Fp::ONE; HASH_RATE])
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Optional RPC helpers (feature `rpc`): fetch proposals, dry-run a vote
+//! before paying Arcium fees, verify revealed results, and fetch the lookup
+//! tables [`UnsignedTransaction`](crate::transaction::UnsignedTransaction)
+//! compiles against.
+
+use std::fmt;
+
+use anchor_lang::Discriminator;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    client_error::Error as ClientError,
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::InstructionError,
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{
+    accounts::{
+        decode_cluster, decode_commitment, decode_config, decode_credit_account, decode_matching_pool,
+        decode_mxe_lookup_table, decode_proposal, decode_reward_pool, decode_sealed_identity, decode_tally,
+        decode_voter_credits, decode_voter_record, Commitment, Config, MatchingPool, ProposalAccount, RewardPool,
+        SealedIdentity, TallyAccount, VoterRecord,
+    },
+    encryption::VoteAllocation,
+    pda,
+    reference::RevealedResults,
+    transaction::AddressLookupTableAccount,
+    validation::{validate_vote, validate_vote_with_credits, VoteError},
+    verify::{verify_final_checkpoint, verify_proposal, VerifyError},
+    ErrorFamily, Instruction, Pubkey,
+};
+
+#[derive(Debug)]
+pub enum RpcError {
+    Rpc(Box<ClientError>),
+    /// The account exists but is not a proposal.
+    InvalidProposal(Box<anchor_lang::error::Error>),
+    /// The voter already has a voter record for this proposal.
+    AlreadyVoted,
+    Invalid(VoteError),
+    /// The program rejected the simulated transaction, with `code` if an
+    /// instruction failed with a custom error.
+    Simulation {
+        error: String,
+        code: Option<u32>,
+        logs: Vec<String>,
+    },
+    /// The attesting cluster account could not be decoded.
+    InvalidCluster(Box<anchor_lang::error::Error>),
+    Verify(VerifyError),
+    /// The protocol config is missing or could not be decoded.
+    InvalidConfig(Box<anchor_lang::error::Error>),
+    /// The proposal has no reward pool, or it could not be decoded.
+    InvalidRewardPool(Box<anchor_lang::error::Error>),
+    /// The proposal has no matching pool, or it could not be decoded.
+    InvalidMatchingPool(Box<anchor_lang::error::Error>),
+    /// The proposal's tally commitment could not be decoded.
+    InvalidCommitment(Box<anchor_lang::error::Error>),
+    /// The proposal's `TallyAccount` is missing or could not be decoded.
+    InvalidTally(Box<anchor_lang::error::Error>),
+    /// An account could not be decoded as an address lookup table or MXE.
+    InvalidLookupTable(String),
+    /// An account or signature could not be read into an export archive.
+    InvalidExport(String),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rpc(e) => write!(f, "rpc error: {e}"),
+            Self::InvalidProposal(e) => write!(f, "failed to decode proposal: {e}"),
+            Self::AlreadyVoted => write!(f, "voter has already voted on this proposal"),
+            Self::Invalid(e) => e.fmt(f),
+            Self::Simulation { error, .. } => match self.error_family() {
+                Some(family) => write!(f, "simulation failed: {error} ({family:?} error)"),
+                None => write!(f, "simulation failed: {error}"),
+            },
+            Self::InvalidCluster(e) => write!(f, "failed to decode cluster: {e}"),
+            Self::Verify(e) => e.fmt(f),
+            Self::InvalidConfig(e) => write!(f, "failed to decode config: {e}"),
+            Self::InvalidRewardPool(e) => write!(f, "failed to decode reward pool: {e}"),
+            Self::InvalidMatchingPool(e) => write!(f, "failed to decode matching pool: {e}"),
+            Self::InvalidCommitment(e) => write!(f, "failed to decode tally commitment: {e}"),
+            Self::InvalidTally(e) => write!(f, "failed to decode tally account: {e}"),
+            Self::InvalidLookupTable(e) => write!(f, "failed to decode lookup table: {e}"),
+            Self::InvalidExport(e) => write!(f, "failed to export: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl RpcError {
+    /// The family of the ArcVote error a simulation failed with.
+    pub fn error_family(&self) -> Option<ErrorFamily> {
+        match self {
+            Self::Simulation { code: Some(code), .. } => ErrorFamily::of(*code),
+            _ => None,
+        }
+    }
+}
+
+impl From<ClientError> for RpcError {
+    fn from(e: ClientError) -> Self {
+        Self::Rpc(Box::new(e))
+    }
+}
+
+impl From<VerifyError> for RpcError {
+    fn from(e: VerifyError) -> Self {
+        Self::Verify(e)
+    }
+}
+
+impl From<VoteError> for RpcError {
+    fn from(e: VoteError) -> Self {
+        Self::Invalid(e)
+    }
+}
+
+pub fn fetch_proposal(
+    rpc: &RpcClient,
+    authority: &Pubkey,
+    proposal_id: u32,
+) -> Result<ProposalAccount, RpcError> {
+    let data = rpc.get_account_data(&pda::proposal_pda(authority, proposal_id).0)?;
+    decode_proposal(&data).map_err(|e| RpcError::InvalidProposal(Box::new(e)))
+}
+
+pub fn fetch_config(rpc: &RpcClient) -> Result<Config, RpcError> {
+    let data = rpc.get_account_data(&pda::config_pda().0)?;
+    decode_config(&data).map_err(|e| RpcError::InvalidConfig(Box::new(e)))
+}
+
+/// The encrypted tallies of the proposal at `proposal`.
+pub fn fetch_tally(rpc: &RpcClient, proposal: &Pubkey) -> Result<TallyAccount, RpcError> {
+    let data = rpc.get_account_data(&pda::tally_pda(proposal).0)?;
+    decode_tally(&data).map_err(|e| RpcError::InvalidTally(Box::new(e)))
+}
+
+pub fn fetch_reward_pool(rpc: &RpcClient, proposal: &Pubkey) -> Result<RewardPool, RpcError> {
+    let data = rpc.get_account_data(&pda::reward_pool_pda(proposal).0)?;
+    decode_reward_pool(&data).map_err(|e| RpcError::InvalidRewardPool(Box::new(e)))
+}
+
+pub fn fetch_matching_pool(rpc: &RpcClient, proposal: &Pubkey) -> Result<MatchingPool, RpcError> {
+    let data = rpc.get_account_data(&pda::matching_pool_pda(proposal).0)?;
+    decode_matching_pool(&data).map_err(|e| RpcError::InvalidMatchingPool(Box::new(e)))
+}
+
+/// Every proposal owned by the program.  Accounts that fail to decode (for
+/// example from an older layout) are skipped.
+pub fn fetch_all_proposals(rpc: &RpcClient) -> Result<Vec<(Pubkey, ProposalAccount)>, RpcError> {
+    let accounts = rpc.get_program_accounts_with_config(
+        &crate::PROGRAM_ID,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                ProposalAccount::DISCRIMINATOR.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| Some((address, decode_proposal(&account.data).ok()?)))
+        .collect())
+}
+
+/// Every `SealedIdentity` of the proposal at `proposal`, released or not.
+pub fn fetch_sealed_identities(rpc: &RpcClient, proposal: &Pubkey) -> Result<Vec<(Pubkey, SealedIdentity)>, RpcError> {
+    let accounts = rpc.get_program_accounts_with_config(
+        &crate::PROGRAM_ID,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, SealedIdentity::DISCRIMINATOR.to_vec())),
+                // After the discriminator and bump.
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(9, proposal.to_bytes().to_vec())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| Some((address, decode_sealed_identity(&account.data).ok()?)))
+        .collect())
+}
+
+/// Every `VoterRecord` of `voter` whose ballot was counted, for
+/// [`prove_history`](crate::instructions::prove_history).
+pub fn fetch_voter_records(rpc: &RpcClient, voter: &Pubkey) -> Result<Vec<(Pubkey, VoterRecord)>, RpcError> {
+    let accounts = rpc.get_program_accounts_with_config(
+        &crate::PROGRAM_ID,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, VoterRecord::DISCRIMINATOR.to_vec())),
+                // After the discriminator, bump and proposal.
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(41, voter.to_bytes().to_vec())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| Some((address, decode_voter_record(&account.data).ok()?)))
+        .filter(|(_, record)| record.counted)
+        .collect())
+}
+
+/// Simulate `instructions` paid by `payer` without signing.  Returns the
+/// program logs on success.
+pub fn simulate(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+) -> Result<Vec<String>, RpcError> {
+    let tx = Transaction::new_with_payer(instructions, Some(payer));
+    let result = rpc
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            },
+        )?
+        .value;
+    let logs = result.logs.unwrap_or_default();
+    match result.err {
+        Some(error) => Err(RpcError::Simulation {
+            code: match error {
+                TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+                _ => None,
+            },
+            error: error.to_string(),
+            logs,
+        }),
+        None => Ok(logs),
+    }
+}
+
+/// Everything that can be checked before a vote is sent: the proposal is
+/// open, the voter has not voted, the allocation fits the budget — the
+/// voter's registered credits on a square-root-credit proposal, their
+/// funded credits on a funded one; a sealed credit bank or encrypted
+/// budget is left to the circuit — and options, and the `cast_vote` instruction simulates
+/// cleanly.  Returns the quadratic cost and the simulation logs.
+pub fn dry_run_vote(
+    rpc: &RpcClient,
+    voter: &Pubkey,
+    authority: &Pubkey,
+    proposal_id: u32,
+    allocation: &VoteAllocation,
+    cast_vote_ix: &Instruction,
+) -> Result<(u64, Vec<String>), RpcError> {
+    let proposal = fetch_proposal(rpc, authority, proposal_id)?;
+    let now = rpc.get_block_time(rpc.get_slot()?)?;
+    let address = pda::proposal_pda(authority, proposal_id).0;
+    let cost = match proposal.credit_mint {
+        None if proposal.credit_bank || proposal.encrypted_budgets => {
+            validate_vote_with_credits(&proposal, allocation, now, u64::MAX)?
+        }
+        None if proposal.funded_credits => {
+            let credits = rpc
+                .get_account_with_commitment(&pda::credit_account_pda(&address, voter).0, rpc.commitment())?
+                .value
+                .and_then(|account| decode_credit_account(&account.data).ok())
+                .ok_or(VoteError::CreditsNotFunded)?;
+            validate_vote_with_credits(&proposal, allocation, now, credits.credits)?
+        }
+        Some(_) => {
+            let scope = proposal.on_ballot.unwrap_or(address);
+            let credits = rpc
+                .get_account_with_commitment(&pda::voter_credits_pda(&scope, voter).0, rpc.commitment())?
+                .value
+                .and_then(|account| decode_voter_credits(&account.data).ok())
+                .ok_or(VoteError::CreditsNotRegistered)?;
+            validate_vote_with_credits(&proposal, allocation, now, credits.credits)?
+        }
+        None => validate_vote(&proposal, allocation, now)?,
+    };
+
+    let voter_record = pda::voter_record_pda(&address, voter).0;
+    if rpc.get_account_with_commitment(&voter_record, rpc.commitment())?.value.is_some() {
+        return Err(RpcError::AlreadyVoted);
+    }
+
+    let logs = simulate(rpc, voter, std::slice::from_ref(cast_vote_ix))?;
+    Ok((cost, logs))
+}
+
+/// Fetch a finalized proposal and the cluster that attested its results,
+/// and verify the cluster's signature.  Returns the verified results.
+pub fn fetch_verified_results(
+    rpc: &RpcClient,
+    authority: &Pubkey,
+    proposal_id: u32,
+) -> Result<RevealedResults, RpcError> {
+    let proposal = fetch_proposal(rpc, authority, proposal_id)?;
+    let cluster_address = proposal.result_attestation.cluster;
+    if !proposal.is_finalized {
+        return Err(VerifyError::NotFinalized.into());
+    }
+    let cluster = decode_cluster(&rpc.get_account_data(&cluster_address)?)
+        .map_err(|e| RpcError::InvalidCluster(Box::new(e)))?;
+    Ok(verify_proposal(&proposal, &cluster_address, &cluster)?)
+}
+
+/// Fetch a proposal's `Commitment`, if anyone checkpointed its tally, and
+/// check that the latest checkpoint is of the proposal's current tally.
+pub fn fetch_verified_commitment(
+    rpc: &RpcClient,
+    authority: &Pubkey,
+    proposal_id: u32,
+) -> Result<Option<Commitment>, RpcError> {
+    let proposal = fetch_proposal(rpc, authority, proposal_id)?;
+    let address = pda::proposal_pda(authority, proposal_id).0;
+    let Some(account) = rpc
+        .get_account_with_commitment(&pda::commitment_pda(&address).0, rpc.commitment())?
+        .value
+    else {
+        return Ok(None);
+    };
+    let commitment = decode_commitment(&account.data).map_err(|e| RpcError::InvalidCommitment(Box::new(e)))?;
+    let tally = fetch_tally(rpc, &address)?;
+    verify_final_checkpoint(&address, &proposal, &tally, &commitment)?;
+    Ok(Some(commitment))
+}
+
+pub fn fetch_lookup_table(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<AddressLookupTableAccount, RpcError> {
+    let data = rpc.get_account_data(address)?;
+    let table = AddressLookupTable::deserialize(&data)
+        .map_err(|e| RpcError::InvalidLookupTable(e.to_string()))?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// The MXE's lookup table, which covers the Arcium accounts of every
+/// queue instruction.
+pub fn fetch_mxe_lookup_table(rpc: &RpcClient) -> Result<AddressLookupTableAccount, RpcError> {
+    let data = rpc.get_account_data(&pda::mxe_account())?;
+    let address =
+        decode_mxe_lookup_table(&data).map_err(|e| RpcError::InvalidLookupTable(e.to_string()))?;
+    fetch_lookup_table(rpc, &address)
+}
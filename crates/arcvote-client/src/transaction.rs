@@ -0,0 +1,118 @@
+//! Unsigned, versioned transactions for hardware and offline signers.
+//!
+//! Casting a vote splits into steps with different trust requirements:
+//!
+//! 1. [`encryption`](crate::encryption) needs only the voter's x25519 secret
+//!    (derived from one wallet message signature);
+//! 2. [`instructions`](crate::instructions) uses public data only;
+//! 3. [`UnsignedTransaction`] compiles the instructions into a v0 message,
+//!    with address lookup tables to keep the Arcium account list within the
+//!    packet size.
+//!
+//! Nothing here holds a private key: hand [`UnsignedTransaction::message_bytes`]
+//! to any signer — a Ledger, an air-gapped machine, a multisig UI — and attach
+//! the returned signatures with [`UnsignedTransaction::into_signed`].
+
+use std::fmt;
+
+pub use solana_hash::Hash;
+pub use solana_message::{AddressLookupTableAccount, VersionedMessage};
+pub use solana_signature::Signature;
+pub use solana_transaction::versioned::VersionedTransaction;
+
+use solana_message::{v0, CompileError};
+
+use crate::{Instruction, Pubkey};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionError {
+    /// The instructions could not be compiled into a v0 message.
+    Compile(CompileError),
+    /// No signature was supplied for a required signer.
+    MissingSignature(Pubkey),
+    /// A signature was supplied for a key that is not a required signer.
+    UnexpectedSigner(Pubkey),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compile(e) => write!(f, "failed to compile message: {e}"),
+            Self::MissingSignature(key) => write!(f, "missing signature for {key}"),
+            Self::UnexpectedSigner(key) => write!(f, "{key} is not a required signer"),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+/// A compiled v0 message awaiting signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsignedTransaction {
+    message: VersionedMessage,
+}
+
+impl UnsignedTransaction {
+    pub fn new(
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+        recent_blockhash: Hash,
+    ) -> Result<Self, TransactionError> {
+        let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+            .map_err(TransactionError::Compile)?;
+        Ok(Self {
+            message: VersionedMessage::V0(message),
+        })
+    }
+
+    pub fn message(&self) -> &VersionedMessage {
+        &self.message
+    }
+
+    /// The bytes every signer signs.
+    pub fn message_bytes(&self) -> Vec<u8> {
+        self.message.serialize()
+    }
+
+    /// Keys that must sign, fee payer first.
+    pub fn signers(&self) -> &[Pubkey] {
+        let required = self.message.header().num_required_signatures as usize;
+        &self.message.static_account_keys()[..required]
+    }
+
+    /// The wire transaction with every signature zeroed, for wallets that
+    /// sign a whole transaction rather than message bytes — a Solana Pay
+    /// transaction request, a wallet adapter's `signTransaction`.
+    pub fn to_unsigned(&self) -> VersionedTransaction {
+        VersionedTransaction {
+            signatures: vec![Signature::default(); self.signers().len()],
+            message: self.message.clone(),
+        }
+    }
+
+    /// Attach signatures (in any order) and produce the wire transaction.
+    pub fn into_signed(
+        self,
+        signatures: &[(Pubkey, Signature)],
+    ) -> Result<VersionedTransaction, TransactionError> {
+        let signers = self.signers();
+        if let Some((key, _)) = signatures.iter().find(|(key, _)| !signers.contains(key)) {
+            return Err(TransactionError::UnexpectedSigner(*key));
+        }
+        let ordered = signers
+            .iter()
+            .map(|signer| {
+                signatures
+                    .iter()
+                    .find(|(key, _)| key == signer)
+                    .map(|(_, signature)| *signature)
+                    .ok_or(TransactionError::MissingSignature(*signer))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(VersionedTransaction {
+            signatures: ordered,
+            message: self.message,
+        })
+    }
+}
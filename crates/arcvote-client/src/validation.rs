@@ -0,0 +1,463 @@
+//! Local checks for a ballot before it is encrypted and sent.
+//!
+//! The `cast_vote` circuit silently drops an over-budget allocation: the
+//! transaction succeeds, the Arcium fee is paid and the voter record is
+//! created, but nothing is counted.  Running [`validate_vote`] first turns
+//! those cases into an error while the voter can still fix the ballot.
+
+use std::fmt;
+
+use crate::{
+    accounts::{ProposalAccount, ProposalState},
+    encryption::{JuryScores, VoteAllocation, LIKERT_OFFSET, RANGE_MAX_SCORE, STAR_MAX_SCORE},
+    instructions::BallotKind,
+    Pubkey,
+};
+
+/// Why an allocation would be rejected or discarded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoteError {
+    /// Results have already been revealed.
+    Finalized,
+    /// An amendment whose parent has not been confirmed to pick `winner`.
+    AwaitingParent { parent: Pubkey, winner: u8 },
+    /// A chained proposal whose parent has not been decided as required.
+    AwaitingDependency,
+    /// A square-root-credit proposal on which the voter has no
+    /// `VoterCredits` yet.
+    CreditsNotRegistered,
+    /// A funded-credit proposal on which nobody funded the voter's
+    /// `CreditAccount`.
+    CreditsNotFunded,
+    /// Candidates are still registering; voting opens at `opens_at`.
+    RegistrationOpen { opens_at: i64 },
+    /// `now` is at or past the proposal deadline.
+    VotingClosed { deadline: i64 },
+    /// Votes were allocated to an option the proposal does not have.
+    UnknownOption { index: usize, num_options: u8 },
+    /// The quadratic cost exceeds the budget; `cost` is `None` on overflow.
+    OverBudget { cost: Option<u64>, budget: u64 },
+    /// A Likert score is off the -2..+2 scale; `value` is the offset score.
+    OffScale { index: usize, value: u64 },
+    /// A For/Against/Abstain ballot does not choose exactly one option.
+    NotOneChoice,
+    /// A jury score is above `max_score`, which is 0 for a criterion or
+    /// application the proposal does not have.
+    ScoreOutOfRange {
+        criterion: usize,
+        application: usize,
+        value: u64,
+        max_score: u8,
+    },
+    /// Jury scores were given for a proposal that is not a jury.
+    NotAJury,
+    /// An approval ballot has an entry other than 0 or 1.
+    NotApproval { index: usize, value: u64 },
+    /// An approval ballot approves more than `max_choices` options.
+    TooManyChoices { chosen: u64, max_choices: u8 },
+    /// A ranked ballot doesn't rank every option exactly once, the
+    /// proposal's own first.
+    NotARanking,
+    /// A STAR score is above `STAR_MAX_SCORE`.
+    OffStarScale { index: usize, value: u64 },
+    /// A range score is above `RANGE_MAX_SCORE`.
+    OffRangeScale { index: usize, value: u64 },
+}
+
+impl fmt::Display for VoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Finalized => write!(f, "proposal is already finalized"),
+            Self::AwaitingParent { parent, winner } => {
+                write!(f, "voting opens once {parent} is confirmed to pick option {winner}")
+            }
+            Self::AwaitingDependency => write!(f, "voting opens once the parent proposal is decided as required"),
+            Self::CreditsNotRegistered => write!(f, "register square-root credits before voting"),
+            Self::CreditsNotFunded => write!(f, "the proposal authority has not funded your voice credits"),
+            Self::RegistrationOpen { opens_at } => {
+                write!(f, "candidate registration is open; voting opens at {opens_at}")
+            }
+            Self::VotingClosed { deadline } => write!(f, "voting closed at {deadline}"),
+            Self::UnknownOption { index, num_options } => write!(
+                f,
+                "votes allocated to option {index} but the proposal has {num_options} option(s)"
+            ),
+            Self::OverBudget {
+                cost: Some(cost),
+                budget,
+            } => write!(f, "quadratic cost {cost} exceeds the {budget} voice credit budget"),
+            Self::OverBudget { cost: None, budget } => {
+                write!(f, "quadratic cost overflows the {budget} voice credit budget")
+            }
+            Self::OffScale { index, value } => {
+                write!(f, "option {index} has offset score {value}, outside 0..=4 (-2..+2)")
+            }
+            Self::NotOneChoice => write!(f, "choose exactly one of For, Against and Abstain"),
+            Self::ScoreOutOfRange {
+                criterion,
+                application,
+                value,
+                max_score,
+            } => write!(
+                f,
+                "criterion {criterion} scores application {application} {value}, above {max_score}"
+            ),
+            Self::NotAJury => write!(f, "proposal does not take jury scores"),
+            Self::NotApproval { index, value } => {
+                write!(f, "option {index} has {value}; approve an option with 1 or leave it 0")
+            }
+            Self::TooManyChoices { chosen, max_choices } => {
+                write!(f, "{chosen} options approved but at most {max_choices} may be")
+            }
+            Self::NotARanking => write!(f, "rank each of the proposal's options exactly once"),
+            Self::OffStarScale { index, value } => {
+                write!(f, "option {index} has score {value}, outside 0..={STAR_MAX_SCORE}")
+            }
+            Self::OffRangeScale { index, value } => {
+                write!(f, "option {index} has score {value}, outside 0..={RANGE_MAX_SCORE}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VoteError {}
+
+/// Voice credits the circuit will honour for `proposal`, unless it assigns
+/// square-root credits: then each voter's budget is their
+/// `VoterCredits::credits`, checked with [`validate_vote_with_credits`].
+pub fn effective_budget(proposal: &ProposalAccount) -> u64 {
+    proposal.voice_credits
+}
+
+/// Check `allocation` against the proposal's state, option count and
+/// budget as of `now` (unix seconds).  Returns the quadratic cost, or 0
+/// for a Likert, STAR, range, For/Against/Abstain, approval, ranked,
+/// Borda or Condorcet proposal, whose ballots are checked against the
+/// scale, for a single choice, against the approval limit or for a full
+/// ranking instead.
+pub fn validate_vote(
+    proposal: &ProposalAccount,
+    allocation: &VoteAllocation,
+    now: i64,
+) -> Result<u64, VoteError> {
+    validate_vote_with_credits(proposal, allocation, now, effective_budget(proposal))
+}
+
+/// As [`validate_vote`], with a quadratic allocation checked against
+/// `budget`: the voter's `VoterCredits::credits` on a square-root-credit
+/// proposal.
+pub fn validate_vote_with_credits(
+    proposal: &ProposalAccount,
+    allocation: &VoteAllocation,
+    now: i64,
+    budget: u64,
+) -> Result<u64, VoteError> {
+    check_voting_open(proposal, now)?;
+
+    // A ranking names every option, the proposal's and the padding alike.
+    if matches!(proposal.ballot, BallotKind::Ranked | BallotKind::Borda | BallotKind::Condorcet) {
+        return if allocation.is_ranking(proposal.num_options) { Ok(0) } else { Err(VoteError::NotARanking) };
+    }
+
+    if let Some(index) = allocation
+        .votes
+        .iter()
+        .enumerate()
+        .skip(proposal.num_options as usize)
+        .find_map(|(i, v)| (*v != 0).then_some(i))
+    {
+        return Err(VoteError::UnknownOption {
+            index,
+            num_options: proposal.num_options,
+        });
+    }
+
+    if proposal.ballot == BallotKind::Star {
+        return match allocation.votes.iter().position(|v| *v > STAR_MAX_SCORE) {
+            Some(index) => Err(VoteError::OffStarScale {
+                index,
+                value: allocation.votes[index],
+            }),
+            None => Ok(0),
+        };
+    }
+    if proposal.ballot == BallotKind::Range {
+        return match allocation.votes.iter().position(|v| *v > RANGE_MAX_SCORE) {
+            Some(index) => Err(VoteError::OffRangeScale {
+                index,
+                value: allocation.votes[index],
+            }),
+            None => Ok(0),
+        };
+    }
+    if proposal.ballot == BallotKind::Likert {
+        return match allocation.votes.iter().position(|v| *v > 2 * LIKERT_OFFSET as u64) {
+            Some(index) => Err(VoteError::OffScale {
+                index,
+                value: allocation.votes[index],
+            }),
+            None => Ok(0),
+        };
+    }
+    if let BallotKind::YesNoAbstain { .. } = proposal.ballot {
+        return if allocation.is_one_choice() { Ok(0) } else { Err(VoteError::NotOneChoice) };
+    }
+    // A budget box takes approvals of any number of its options.
+    let max_choices = match proposal.ballot {
+        BallotKind::Approval { max_choices } => Some(max_choices),
+        BallotKind::BudgetBox { .. } => Some(proposal.num_options),
+        _ => None,
+    };
+    if let Some(max_choices) = max_choices {
+        if let Some(index) = allocation.votes.iter().position(|v| *v > 1) {
+            return Err(VoteError::NotApproval {
+                index,
+                value: allocation.votes[index],
+            });
+        }
+        let chosen = allocation.votes.iter().sum::<u64>();
+        return if chosen <= max_choices as u64 {
+            Ok(0)
+        } else {
+            Err(VoteError::TooManyChoices { chosen, max_choices })
+        };
+    }
+
+    match allocation.cost() {
+        Some(cost) if cost <= budget => Ok(cost),
+        cost => Err(VoteError::OverBudget { cost, budget }),
+    }
+}
+
+/// Check a juror's `scores` against the proposal's state and its jury
+/// criteria, scale and application count as of `now` (unix seconds).
+/// Returns each application's score summed over the criteria.
+pub fn validate_jury_scores(
+    proposal: &ProposalAccount,
+    scores: &JuryScores,
+    now: i64,
+) -> Result<[u64; 4], VoteError> {
+    let BallotKind::Jury { criteria, max_score } = proposal.ballot else {
+        return Err(VoteError::NotAJury);
+    };
+    check_voting_open(proposal, now)?;
+
+    match scores.out_of_range(criteria, max_score, proposal.num_options) {
+        Some((criterion, application)) => Err(VoteError::ScoreOutOfRange {
+            criterion,
+            application,
+            value: scores.scores[criterion][application],
+            max_score: if criterion < criteria as usize && application < proposal.num_options as usize {
+                max_score
+            } else {
+                0
+            },
+        }),
+        None => Ok(scores.totals()),
+    }
+}
+
+fn check_voting_open(proposal: &ProposalAccount, now: i64) -> Result<(), VoteError> {
+    match proposal.state(now) {
+        ProposalState::Finalized => Err(VoteError::Finalized),
+        ProposalState::VotingClosed | ProposalState::QuorumFailed => Err(VoteError::VotingClosed {
+            deadline: proposal.deadline,
+        }),
+        ProposalState::Registration => Err(VoteError::RegistrationOpen {
+            opens_at: proposal.registration_deadline,
+        }),
+        ProposalState::Voting => match proposal.requires_parent_outcome {
+            Some(required) if !proposal.parent_outcome_met => Err(VoteError::AwaitingParent {
+                parent: required.parent,
+                winner: required.winner,
+            }),
+            _ if proposal.awaiting_dependency => Err(VoteError::AwaitingDependency),
+            _ => Ok(()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{AnchorDeserialize, Space};
+    use serde_json::Value;
+
+    use super::*;
+
+    /// The README scenario from `arcvote-vectors`, cast under its proposal's
+    /// `voice_credits`.
+    const VECTORS: &str = include_str!("../../arcvote-vectors/vectors.json");
+
+    const DEADLINE: i64 = 1_000;
+
+    /// A four-option quadratic proposal with 100 voice credits, voting
+    /// until `DEADLINE`.
+    fn proposal() -> ProposalAccount {
+        let zeroed = [0u8; ProposalAccount::INIT_SPACE];
+        let mut proposal = ProposalAccount::deserialize(&mut &zeroed[..]).expect("zeroed proposal");
+        proposal.num_options = 4;
+        proposal.voice_credits = 100;
+        proposal.deadline = DEADLINE;
+        proposal.ballot = BallotKind::Quadratic;
+        proposal
+    }
+
+    fn with_ballot(ballot: BallotKind) -> ProposalAccount {
+        ProposalAccount { ballot, ..proposal() }
+    }
+
+    #[test]
+    fn vector_ballots_pass_or_fail_as_the_circuit_counts_them() {
+        let vectors: Value = serde_json::from_str(VECTORS).expect("vectors.json parses");
+        let proposal = proposal();
+        for ballot in vectors["cast_vote"].as_array().unwrap() {
+            let votes: Vec<u64> = ballot["allocation"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| v.as_u64().unwrap())
+                .collect();
+            let cost = ballot["cost"].as_u64().unwrap();
+            let expected = if ballot["counted"].as_bool().unwrap() {
+                Ok(cost)
+            } else {
+                Err(VoteError::OverBudget {
+                    cost: Some(cost),
+                    budget: 100,
+                })
+            };
+            let allocation = VoteAllocation::new(votes.try_into().unwrap());
+            assert_eq!(validate_vote(&proposal, &allocation, 0), expected);
+        }
+    }
+
+    #[test]
+    fn an_overflowing_allocation_is_over_budget() {
+        let err = validate_vote(&proposal(), &VoteAllocation::new([u64::MAX, 0, 0, 0]), 0).unwrap_err();
+        assert_eq!(err, VoteError::OverBudget { cost: None, budget: 100 });
+        assert_eq!(err.to_string(), "quadratic cost overflows the 100 voice credit budget");
+    }
+
+    #[test]
+    fn voter_credits_replace_the_budget() {
+        let allocation = VoteAllocation::new([10, 1, 0, 0]);
+        assert_eq!(effective_budget(&proposal()), 100);
+        assert_eq!(validate_vote_with_credits(&proposal(), &allocation, 0, 101), Ok(101));
+        assert_eq!(
+            validate_vote_with_credits(&proposal(), &allocation, 0, 100),
+            Err(VoteError::OverBudget {
+                cost: Some(101),
+                budget: 100
+            })
+        );
+    }
+
+    #[test]
+    fn votes_past_the_last_option_are_unknown() {
+        let proposal = ProposalAccount {
+            num_options: 2,
+            ..proposal()
+        };
+        assert_eq!(
+            validate_vote(&proposal, &VoteAllocation::new([1, 0, 1, 0]), 0),
+            Err(VoteError::UnknownOption {
+                index: 2,
+                num_options: 2
+            })
+        );
+    }
+
+    #[test]
+    fn only_the_voting_window_takes_ballots() {
+        let allocation = VoteAllocation::new([1, 0, 0, 0]);
+        assert_eq!(validate_vote(&proposal(), &allocation, DEADLINE - 1), Ok(1));
+        assert_eq!(
+            validate_vote(&proposal(), &allocation, DEADLINE),
+            Err(VoteError::VotingClosed { deadline: DEADLINE })
+        );
+        let registering = ProposalAccount {
+            registration_deadline: 10,
+            ..proposal()
+        };
+        assert_eq!(
+            validate_vote(&registering, &allocation, 9),
+            Err(VoteError::RegistrationOpen { opens_at: 10 })
+        );
+        let finalized = ProposalAccount {
+            is_finalized: true,
+            ..proposal()
+        };
+        assert_eq!(validate_vote(&finalized, &allocation, 0), Err(VoteError::Finalized));
+    }
+
+    #[test]
+    fn scored_ballots_are_checked_against_their_scale() {
+        assert_eq!(
+            validate_vote(&with_ballot(BallotKind::Likert), &VoteAllocation::new([4, 0, 5, 0]), 0),
+            Err(VoteError::OffScale { index: 2, value: 5 })
+        );
+        assert_eq!(
+            validate_vote(&with_ballot(BallotKind::Star), &VoteAllocation::new([5, 6, 0, 0]), 0),
+            Err(VoteError::OffStarScale { index: 1, value: 6 })
+        );
+        assert_eq!(
+            validate_vote(&with_ballot(BallotKind::Range), &VoteAllocation::new([10, 10, 10, 10]), 0),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn choice_ballots_are_checked_for_their_shape() {
+        let yes_no = with_ballot(BallotKind::YesNoAbstain { veto_bps: 0 });
+        assert_eq!(validate_vote(&yes_no, &VoteAllocation::choice(1).unwrap(), 0), Ok(0));
+        assert_eq!(
+            validate_vote(&yes_no, &VoteAllocation::new([1, 1, 0, 0]), 0),
+            Err(VoteError::NotOneChoice)
+        );
+
+        let approval = with_ballot(BallotKind::Approval { max_choices: 2 });
+        assert_eq!(
+            validate_vote(&approval, &VoteAllocation::new([1, 2, 0, 0]), 0),
+            Err(VoteError::NotApproval { index: 1, value: 2 })
+        );
+        assert_eq!(
+            validate_vote(&approval, &VoteAllocation::new([1, 1, 1, 0]), 0),
+            Err(VoteError::TooManyChoices {
+                chosen: 3,
+                max_choices: 2
+            })
+        );
+
+        let ranked = with_ballot(BallotKind::Ranked);
+        assert_eq!(validate_vote(&ranked, &VoteAllocation::ranking(&[2, 0, 3, 1]).unwrap(), 0), Ok(0));
+        assert_eq!(
+            validate_vote(&ranked, &VoteAllocation::new([0, 0, 1, 2]), 0),
+            Err(VoteError::NotARanking)
+        );
+    }
+
+    #[test]
+    fn jury_scores_stay_within_the_jury() {
+        let scores = JuryScores::new(&[vec![3, 1], vec![0, 0, 2]]).unwrap();
+        assert_eq!(validate_jury_scores(&proposal(), &scores, 0), Err(VoteError::NotAJury));
+
+        let jury = ProposalAccount {
+            num_options: 2,
+            ..with_ballot(BallotKind::Jury {
+                criteria: 2,
+                max_score: 5,
+            })
+        };
+        assert_eq!(
+            validate_jury_scores(&jury, &scores, 0),
+            Err(VoteError::ScoreOutOfRange {
+                criterion: 1,
+                application: 2,
+                value: 2,
+                max_score: 0
+            })
+        );
+        let scores = JuryScores::new(&[vec![3, 1], vec![5, 0]]).unwrap();
+        assert_eq!(validate_jury_scores(&jury, &scores, 0), Ok([8, 1, 0, 0]));
+    }
+}
@@ -0,0 +1,172 @@
+//! Off-chain verification of revealed results.
+//!
+//! The reveal callback stores the cluster's BLS signature over the circuit
+//! output in the proposal's [`ResultAttestation`].  Checking it against the
+//! cluster's BLS public key proves the tallies came from the MPC cluster,
+//! without trusting an RPC node's event logs or the proposal authority.
+//!
+//! A proposal's `Commitment` chains checkpoints of its encrypted tally;
+//! [`replay_commitment`] and [`verify_final_checkpoint`] tie the tally a
+//! reveal read to the checkpoints taken while ballots landed.
+
+use std::fmt;
+
+use anchor_lang::AnchorSerialize;
+use arcium_client::idl::arcium::{accounts::Cluster, types::SetUnset};
+use solana_alt_bn128_bls::{G1Point, G2CompressedPoint, Sha256Normalized};
+
+pub use private_voting::{fold_commitment, tally_leaf, ResultAttestation, TallyCommittedEvent};
+
+use crate::{
+    accounts::{Commitment, ProposalAccount, TallyAccount},
+    reference::RevealedResults,
+    Pubkey,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The proposal has no revealed results yet.
+    NotFinalized,
+    /// The attestation names a different cluster than the one supplied.
+    ClusterMismatch { expected: Pubkey, found: Pubkey },
+    /// The cluster has not published a BLS public key.
+    ClusterKeyUnset,
+    /// The signature does not match the results.
+    InvalidSignature,
+    /// A checkpoint's leaf or root does not follow from the ones before.
+    CheckpointMismatch { checkpoint: u32 },
+    /// The latest checkpoint is not of the proposal's current tally.
+    StaleCommitment,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFinalized => write!(f, "proposal results have not been revealed"),
+            Self::ClusterMismatch { expected, found } => {
+                write!(f, "results attested by cluster {found}, expected {expected}")
+            }
+            Self::ClusterKeyUnset => write!(f, "cluster has no BLS public key"),
+            Self::InvalidSignature => write!(f, "cluster signature does not match the results"),
+            Self::CheckpointMismatch { checkpoint } => {
+                write!(f, "tally checkpoint {checkpoint} does not follow from the ones before")
+            }
+            Self::StaleCommitment => write!(f, "latest tally checkpoint is not of the proposal's tally"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// The results stored on a finalized proposal.
+pub fn stored_results(proposal: &ProposalAccount) -> RevealedResults {
+    let r = proposal.results;
+    RevealedResults {
+        options: [r[0], r[1], r[2], r[3]],
+        total_votes: r[4],
+        winner: proposal.winner,
+        outcome: proposal.outcome,
+        funded: proposal.funded,
+    }
+}
+
+/// The exact bytes the cluster signs for a `reveal_results` output, a
+/// `reveal_outcome` output when `results.outcome` is set, or a
+/// `reveal_budget_box` output when `results.funded` is.
+pub fn signed_message(results: &RevealedResults, attestation: &ResultAttestation) -> Vec<u8> {
+    let mut message = Vec::with_capacity(5 * 8 + 2 + 8 + 2);
+    for value in results.options.iter().chain([&results.total_votes]) {
+        message.extend_from_slice(&value.to_le_bytes());
+    }
+    results.winner.serialize(&mut message).expect("writing to a Vec");
+    if let Some(outcome) = results.outcome {
+        message.push(outcome as u8);
+    }
+    if let Some(funded) = results.funded {
+        message.push(funded);
+    }
+    message.extend_from_slice(&attestation.computation_slot.to_le_bytes());
+    message.extend_from_slice(&attestation.slot_counter.to_le_bytes());
+    message
+}
+
+/// Check `results` (e.g. taken from an event or a third-party API) against
+/// `attestation` under the cluster's compressed G2 BLS public key.
+pub fn verify_results(
+    results: &RevealedResults,
+    attestation: &ResultAttestation,
+    cluster_bls_public_key: [u8; 64],
+) -> Result<(), VerifyError> {
+    G2CompressedPoint(cluster_bls_public_key)
+        .verify_signature::<Sha256Normalized, _, _>(
+            G1Point(attestation.signature),
+            signed_message(results, attestation),
+        )
+        .map_err(|_| VerifyError::InvalidSignature)
+}
+
+/// Verify the results stored on `proposal` against the cluster account at
+/// `cluster_address`.  Returns the verified results.
+///
+/// Uses the cluster's current key, so results attested before a key
+/// rotation no longer verify.
+pub fn verify_proposal(
+    proposal: &ProposalAccount,
+    cluster_address: &Pubkey,
+    cluster: &Cluster,
+) -> Result<RevealedResults, VerifyError> {
+    if !proposal.is_finalized {
+        return Err(VerifyError::NotFinalized);
+    }
+    let attestation = &proposal.result_attestation;
+    if attestation.cluster != *cluster_address {
+        return Err(VerifyError::ClusterMismatch {
+            expected: *cluster_address,
+            found: attestation.cluster,
+        });
+    }
+    let SetUnset::Set(key) = &cluster.bls_public_key else {
+        return Err(VerifyError::ClusterKeyUnset);
+    };
+
+    let results = stored_results(proposal);
+    verify_results(&results, attestation, key.0)?;
+    Ok(results)
+}
+
+/// Recompute a `Commitment` root from every `TallyCommittedEvent` of the
+/// proposal at `proposal_address`, in checkpoint order, checking each
+/// event's leaf and root along the way.
+pub fn replay_commitment(proposal_address: &Pubkey, events: &[TallyCommittedEvent]) -> Result<[u8; 32], VerifyError> {
+    let mut root = [0; 32];
+    for (checkpoint, event) in (1..).zip(events) {
+        let leaf = tally_leaf(proposal_address, &event.vote_state, event.nonce, event.applied_ballots);
+        root = fold_commitment(&root, &leaf);
+        if event.checkpoint != checkpoint || event.leaf != leaf || event.root != root {
+            return Err(VerifyError::CheckpointMismatch { checkpoint });
+        }
+    }
+    Ok(root)
+}
+
+/// Check that `commitment`'s latest checkpoint is of the tally `proposal`
+/// holds in `tally`, its `TallyAccount`.  Once a reveal is queued ballots
+/// no longer change it, so for a finalized proposal that is the tally the
+/// revealed results came from.
+pub fn verify_final_checkpoint(
+    proposal_address: &Pubkey,
+    proposal: &ProposalAccount,
+    tally: &TallyAccount,
+    commitment: &Commitment,
+) -> Result<(), VerifyError> {
+    let leaf = tally_leaf(
+        proposal_address,
+        &tally.vote_state,
+        tally.nonce(),
+        proposal.applied_ballots,
+    );
+    if commitment.checkpoints == 0 || commitment.leaf != leaf {
+        return Err(VerifyError::StaleCommitment);
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+-- ArcVote indexer schema (SQLite and Postgres).
+--
+-- Public keys and signatures are base58 text.  u64 counters are stored as
+-- BIGINT; on-chain values never exceed i64::MAX in practice.  Individual
+-- ballots are encrypted on-chain, so `votes` only records that a vote was
+-- counted, never its contents.
+
+-- One row per proposal, from `ProposalCreatedEvent`.  `options` (for
+-- candidate elections), `voter_count`, `finalized` and `quorum_failed` are
+-- kept current by later events.
+CREATE TABLE IF NOT EXISTS proposals (
+    address           TEXT PRIMARY KEY,
+    proposal_id       BIGINT NOT NULL,
+    authority         TEXT NOT NULL,
+    -- 'governance', or 'poll' for non-binding polls without quorum.
+    kind              TEXT NOT NULL,
+    title             TEXT NOT NULL,
+    -- Option labels joined with a newline, in option order.
+    options           TEXT NOT NULL,
+    deadline          BIGINT NOT NULL,
+    voice_credits     BIGINT NOT NULL,
+    -- 'quadratic', 'likert' for -2..+2 scores per option,
+    -- 'yes_no_abstain' for For/Against/Abstain with a veto threshold,
+    -- 'jury' for a reviewer panel's summed scores per application,
+    -- 'approval' for up-to-K approvals per voter, 'budget_box' for
+    -- approvals funded within a budget, 'ranked' for ranked-choice
+    -- ballots counted by instant runoff, 'borda' for rankings scored
+    -- by Borda count, 'condorcet' for rankings compared head to head,
+    -- 'star' for 0..5 scores with a runoff between the top two, or
+    -- 'range' for 0..10 scores averaged per option.
+    ballot            TEXT NOT NULL,
+    quorum            BIGINT NOT NULL,
+    -- 'voters', 'registered_bps', 'supply_bps' or 'registrar_bps';
+    -- `quorum_target` is the turnout (voters, or token base units for
+    -- supply_bps) needed to reveal, as of creation: 0 for registrar_bps,
+    -- whose electorate grows with each credential issued.
+    quorum_kind       TEXT NOT NULL,
+    quorum_target     BIGINT NOT NULL,
+    voter_count       BIGINT NOT NULL DEFAULT 0,
+    finalized         BOOLEAN NOT NULL DEFAULT FALSE,
+    quorum_failed     BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at        BIGINT NOT NULL,
+    created_slot      BIGINT NOT NULL,
+    created_signature TEXT NOT NULL
+);
+
+-- One row per counted ballot (`VoteCastEvent`, emitted by the cast_vote
+-- callback).
+CREATE TABLE IF NOT EXISTS votes (
+    signature   TEXT NOT NULL,
+    proposal    TEXT NOT NULL,
+    voter_count BIGINT NOT NULL,
+    cast_at     BIGINT NOT NULL,
+    slot        BIGINT NOT NULL,
+    PRIMARY KEY (signature, proposal)
+);
+
+-- Final tallies (`ResultsRevealedEvent`).  For 'likert' proposals each
+-- option holds its sum of scores offset by +2 and `total_votes` the ballot
+-- count, so net sentiment is option_i - 2 * total_votes.
+CREATE TABLE IF NOT EXISTS results (
+    proposal    TEXT PRIMARY KEY,
+    option_0    BIGINT NOT NULL,
+    option_1    BIGINT NOT NULL,
+    option_2    BIGINT NOT NULL,
+    option_3    BIGINT NOT NULL,
+    total_votes BIGINT NOT NULL,
+    winner      BIGINT NOT NULL,
+    -- 'passed', 'rejected' or 'vetoed' for 'yes_no_abstain' proposals
+    -- (`OutcomeRevealedEvent`); NULL otherwise.
+    outcome     TEXT,
+    signature   TEXT NOT NULL,
+    slot        BIGINT NOT NULL
+);
+
+-- Callbacks whose computation output was rejected (`CallbackRejectedEvent`).
+CREATE TABLE IF NOT EXISTS rejected_callbacks (
+    signature   TEXT NOT NULL,
+    proposal    TEXT NOT NULL,
+    stage       TEXT NOT NULL,
+    reason      TEXT NOT NULL,
+    rejected_at BIGINT NOT NULL,
+    slot        BIGINT NOT NULL,
+    PRIMARY KEY (signature, proposal, stage)
+);
+
+-- Admin withdrawals from the fee vault (`FeesWithdrawnEvent`).  With the
+-- vault's on-chain counters this reconciles protocol revenue.
+CREATE TABLE IF NOT EXISTS fee_withdrawals (
+    signature       TEXT PRIMARY KEY,
+    recipient       TEXT NOT NULL,
+    amount          BIGINT NOT NULL,
+    total_withdrawn BIGINT NOT NULL,
+    remaining       BIGINT NOT NULL,
+    withdrawn_at    BIGINT NOT NULL,
+    slot            BIGINT NOT NULL
+);
+
+-- Newest fully indexed transaction; indexing resumes after it.
+CREATE TABLE IF NOT EXISTS indexer_cursor (
+    id             BIGINT PRIMARY KEY,
+    last_signature TEXT NOT NULL
+);
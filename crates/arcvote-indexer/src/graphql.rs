@@ -0,0 +1,536 @@
+//! GraphQL API over the indexed data, served with `--graphql-addr`.
+//!
+//! `POST /graphql` runs a query (`{"query": ..., "variables": ...}`) and
+//! `GET /graphql` returns the schema as SDL.  Lists are paged with `first`
+//! (default 50, at most 500) and `offset`, and come back with their
+//! `totalCount`.  The server reads through its own database connection, so
+//! queries never hold up indexing.
+
+use std::{net::SocketAddr, sync::Mutex, thread};
+
+use anyhow::anyhow;
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, Enum, Error, InputObject, Object, OutputType, Result,
+    Schema, SimpleObject,
+};
+use futures_executor::block_on;
+
+use crate::store::{Param, Row, Store, Value};
+
+/// Page size when `first` is not given.
+const DEFAULT_PAGE: i32 = 50;
+/// Largest `first` accepted.
+const MAX_PAGE: i32 = 500;
+
+const PROPOSAL_COLUMNS: &str = "address, proposal_id, authority, kind, title, options, deadline, voice_credits, \
+    ballot, quorum, quorum_kind, quorum_target, voter_count, finalized, quorum_failed, created_at, created_slot, \
+    created_signature";
+
+/// Every indexed event as one table, `timestamp` NULL where the event
+/// carries none.
+const EVENTS: &str = "(\
+    SELECT 'proposal_created' AS kind, address AS proposal, created_signature AS signature, \
+        created_slot AS slot, created_at AS timestamp FROM proposals \
+    UNION ALL SELECT 'vote_cast', proposal, signature, slot, cast_at FROM votes \
+    UNION ALL SELECT 'results_revealed', proposal, signature, slot, NULL FROM results \
+    UNION ALL SELECT 'callback_rejected', proposal, signature, slot, rejected_at FROM rejected_callbacks\
+    ) events";
+
+struct Db(Mutex<Box<dyn Store>>);
+
+/// Serve the API on `addr` from a background thread, reading through
+/// `store`.
+pub fn serve(addr: SocketAddr, store: Box<dyn Store>) -> anyhow::Result<()> {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(Db(Mutex::new(store)))
+        .finish();
+    let server = tiny_http::Server::http(addr).map_err(|e| anyhow!("graphql server: {e}"))?;
+    let content_type =
+        tiny_http::Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let path = request.url().split('?').next().unwrap_or_default();
+            let response = match (request.method(), path) {
+                (tiny_http::Method::Get, "/graphql") => tiny_http::Response::from_string(schema.sdl()),
+                (tiny_http::Method::Post, "/graphql") => {
+                    let mut body = String::new();
+                    let query = request
+                        .as_reader()
+                        .read_to_string(&mut body)
+                        .ok()
+                        .and_then(|_| serde_json::from_str::<async_graphql::Request>(&body).ok());
+                    match query {
+                        Some(query) => {
+                            let result = block_on(schema.execute(query));
+                            let body = serde_json::to_string(&result).expect("responses serialize");
+                            tiny_http::Response::from_string(body).with_header(content_type.clone())
+                        }
+                        None => tiny_http::Response::from_string("expected a GraphQL request").with_status_code(400),
+                    }
+                }
+                _ => tiny_http::Response::from_string("not found").with_status_code(404),
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The proposal at `address`, if indexed.
+    async fn proposal(&self, ctx: &Context<'_>, address: String) -> Result<Option<Proposal>> {
+        let sql = format!("SELECT {PROPOSAL_COLUMNS} FROM proposals WHERE address = $1");
+        let rows = query(ctx, &sql, &[Param::Text(address)])?;
+        rows.into_iter()
+            .next()
+            .map(|row| decode(Proposal::from_row, row))
+            .transpose()
+    }
+
+    /// Proposals matching `filter`, newest first.
+    async fn proposals(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ProposalFilter>,
+        first: Option<i32>,
+        offset: Option<i32>,
+    ) -> Result<Page<Proposal>> {
+        let filter = filter.unwrap_or_default().into_where();
+        let select = Select {
+            columns: PROPOSAL_COLUMNS,
+            from: "proposals",
+            order: "created_slot DESC, address",
+        };
+        page(ctx, select, filter, first, offset, Proposal::from_row)
+    }
+
+    /// Indexed events, newest first, optionally of one proposal or one kind.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        proposal: Option<String>,
+        kind: Option<EventKind>,
+        first: Option<i32>,
+        offset: Option<i32>,
+    ) -> Result<Page<Event>> {
+        let mut filter = Where::default();
+        if let Some(proposal) = proposal {
+            filter.bind("proposal = ?", Param::Text(proposal));
+        }
+        if let Some(kind) = kind {
+            filter.bind("kind = ?", Param::Text(kind.label().to_string()));
+        }
+        let select = Select {
+            columns: "kind, proposal, signature, slot, timestamp",
+            from: EVENTS,
+            order: "slot DESC, signature, kind",
+        };
+        page(ctx, select, filter, first, offset, Event::from_row)
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct ProposalFilter {
+    authority: Option<String>,
+    /// `governance` or `poll`.
+    kind: Option<String>,
+    /// Ballot kind, as in the `ballot` field.
+    ballot: Option<String>,
+    status: Option<ProposalStatus>,
+    /// Case-insensitive substring of the title.
+    title_contains: Option<String>,
+    /// Created at or after this Unix time.
+    created_after: Option<i64>,
+    /// Created before this Unix time.
+    created_before: Option<i64>,
+}
+
+impl ProposalFilter {
+    fn into_where(self) -> Where {
+        let mut filter = Where::default();
+        if let Some(authority) = self.authority {
+            filter.bind("authority = ?", Param::Text(authority));
+        }
+        if let Some(kind) = self.kind {
+            filter.bind("kind = ?", Param::Text(kind));
+        }
+        if let Some(ballot) = self.ballot {
+            filter.bind("ballot = ?", Param::Text(ballot));
+        }
+        match self.status {
+            Some(ProposalStatus::Open) => filter.add("NOT finalized AND NOT quorum_failed"),
+            Some(ProposalStatus::Finalized) => filter.add("finalized"),
+            Some(ProposalStatus::QuorumFailed) => filter.add("quorum_failed"),
+            None => {}
+        }
+        if let Some(title) = self.title_contains {
+            let escaped = title
+                .to_lowercase()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            filter.bind("LOWER(title) LIKE ? ESCAPE '\\'", Param::Text(format!("%{escaped}%")));
+        }
+        if let Some(after) = self.created_after {
+            filter.bind("created_at >= ?", Param::Int(after));
+        }
+        if let Some(before) = self.created_before {
+            filter.bind("created_at < ?", Param::Int(before));
+        }
+        filter
+    }
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    /// Neither finalized nor quorum-failed.
+    Open,
+    Finalized,
+    QuorumFailed,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Proposal {
+    address: String,
+    proposal_id: i64,
+    authority: String,
+    /// `governance`, or `poll` for non-binding polls without quorum.
+    kind: String,
+    title: String,
+    /// Option labels, in option order.
+    options: Vec<String>,
+    deadline: i64,
+    voice_credits: i64,
+    ballot: String,
+    quorum: i64,
+    quorum_kind: String,
+    /// Turnout needed to reveal: voters, or token base units for
+    /// `supply_bps`.
+    quorum_target: i64,
+    voter_count: i64,
+    finalized: bool,
+    quorum_failed: bool,
+    created_at: i64,
+    created_slot: i64,
+    created_signature: String,
+}
+
+impl Proposal {
+    fn from_row(row: &mut Columns) -> Option<Self> {
+        Some(Self {
+            address: row.text()?,
+            proposal_id: row.int()?,
+            authority: row.text()?,
+            kind: row.text()?,
+            title: row.text()?,
+            options: row
+                .text()?
+                .split('\n')
+                .filter(|o| !o.is_empty())
+                .map(String::from)
+                .collect(),
+            deadline: row.int()?,
+            voice_credits: row.int()?,
+            ballot: row.text()?,
+            quorum: row.int()?,
+            quorum_kind: row.text()?,
+            quorum_target: row.int()?,
+            voter_count: row.int()?,
+            finalized: row.int()? != 0,
+            quorum_failed: row.int()? != 0,
+            created_at: row.int()?,
+            created_slot: row.int()?,
+            created_signature: row.text()?,
+        })
+    }
+}
+
+#[ComplexObject]
+impl Proposal {
+    /// Final tallies, once revealed.
+    async fn results(&self, ctx: &Context<'_>) -> Result<Option<Results>> {
+        let rows = query(
+            ctx,
+            "SELECT option_0, option_1, option_2, option_3, total_votes, winner, outcome, signature, slot \
+             FROM results WHERE proposal = $1",
+            &[Param::Text(self.address.clone())],
+        )?;
+        rows.into_iter()
+            .next()
+            .map(|row| decode(Results::from_row, row))
+            .transpose()
+    }
+
+    /// Counted ballots, newest first.  Ballots are encrypted on-chain, so
+    /// only that each was counted is known.
+    async fn votes(&self, ctx: &Context<'_>, first: Option<i32>, offset: Option<i32>) -> Result<Page<Vote>> {
+        let mut filter = Where::default();
+        filter.bind("proposal = ?", Param::Text(self.address.clone()));
+        let select = Select {
+            columns: "signature, voter_count, cast_at, slot",
+            from: "votes",
+            order: "slot DESC, signature",
+        };
+        page(ctx, select, filter, first, offset, Vote::from_row)
+    }
+
+    /// Callbacks whose computation output was rejected, oldest first.
+    async fn rejected_callbacks(&self, ctx: &Context<'_>) -> Result<Vec<RejectedCallback>> {
+        let rows = query(
+            ctx,
+            "SELECT signature, stage, reason, rejected_at, slot FROM rejected_callbacks \
+             WHERE proposal = $1 ORDER BY slot, signature",
+            &[Param::Text(self.address.clone())],
+        )?;
+        rows.into_iter()
+            .map(|row| decode(RejectedCallback::from_row, row))
+            .collect()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Results {
+    /// Per-option tallies.  For `likert` proposals each is the sum of scores
+    /// offset by +2, so net sentiment is `tally - 2 * totalVotes`.
+    tallies: Vec<i64>,
+    total_votes: i64,
+    winner: i64,
+    /// `passed`, `rejected` or `vetoed` for `yes_no_abstain` proposals.
+    outcome: Option<String>,
+    signature: String,
+    slot: i64,
+}
+
+impl Results {
+    fn from_row(row: &mut Columns) -> Option<Self> {
+        Some(Self {
+            tallies: vec![row.int()?, row.int()?, row.int()?, row.int()?],
+            total_votes: row.int()?,
+            winner: row.int()?,
+            outcome: row.opt_text()?,
+            signature: row.text()?,
+            slot: row.int()?,
+        })
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Vote {
+    signature: String,
+    /// The proposal's voter count once this ballot was counted.
+    voter_count: i64,
+    cast_at: i64,
+    slot: i64,
+}
+
+impl Vote {
+    fn from_row(row: &mut Columns) -> Option<Self> {
+        Some(Self {
+            signature: row.text()?,
+            voter_count: row.int()?,
+            cast_at: row.int()?,
+            slot: row.int()?,
+        })
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct RejectedCallback {
+    signature: String,
+    stage: String,
+    reason: String,
+    rejected_at: i64,
+    slot: i64,
+}
+
+impl RejectedCallback {
+    fn from_row(row: &mut Columns) -> Option<Self> {
+        Some(Self {
+            signature: row.text()?,
+            stage: row.text()?,
+            reason: row.text()?,
+            rejected_at: row.int()?,
+            slot: row.int()?,
+        })
+    }
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    ProposalCreated,
+    VoteCast,
+    ResultsRevealed,
+    CallbackRejected,
+}
+
+impl EventKind {
+    fn label(self) -> &'static str {
+        match self {
+            EventKind::ProposalCreated => "proposal_created",
+            EventKind::VoteCast => "vote_cast",
+            EventKind::ResultsRevealed => "results_revealed",
+            EventKind::CallbackRejected => "callback_rejected",
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Event {
+    kind: EventKind,
+    proposal: String,
+    signature: String,
+    slot: i64,
+    /// Unix time the event carried; none for `RESULTS_REVEALED`.
+    timestamp: Option<i64>,
+}
+
+impl Event {
+    fn from_row(row: &mut Columns) -> Option<Self> {
+        let label = row.text()?;
+        Some(Self {
+            kind: [
+                EventKind::ProposalCreated,
+                EventKind::VoteCast,
+                EventKind::ResultsRevealed,
+                EventKind::CallbackRejected,
+            ]
+            .into_iter()
+            .find(|kind| kind.label() == label)?,
+            proposal: row.text()?,
+            signature: row.text()?,
+            slot: row.int()?,
+            timestamp: row.opt_int()?,
+        })
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(concrete(name = "ProposalPage", params(Proposal)))]
+#[graphql(concrete(name = "VotePage", params(Vote)))]
+#[graphql(concrete(name = "EventPage", params(Event)))]
+pub struct Page<T: OutputType> {
+    /// Rows matching the query, across all pages.
+    total_count: i64,
+    has_next_page: bool,
+    nodes: Vec<T>,
+}
+
+/// The columns of one row, taken in `SELECT` order.
+struct Columns(std::vec::IntoIter<Value>);
+
+impl Columns {
+    fn int(&mut self) -> Option<i64> {
+        self.0.next()?.int()
+    }
+
+    fn text(&mut self) -> Option<String> {
+        self.0.next()?.text()
+    }
+
+    fn opt_int(&mut self) -> Option<Option<i64>> {
+        match self.0.next()? {
+            Value::Null => Some(None),
+            value => value.int().map(Some),
+        }
+    }
+
+    fn opt_text(&mut self) -> Option<Option<String>> {
+        match self.0.next()? {
+            Value::Null => Some(None),
+            value => value.text().map(Some),
+        }
+    }
+}
+
+fn decode<T>(from_row: fn(&mut Columns) -> Option<T>, row: Row) -> Result<T> {
+    from_row(&mut Columns(row.into_iter())).ok_or_else(|| Error::new("unexpected row in the index"))
+}
+
+/// A `WHERE` clause built from optional conditions, with its parameters.
+#[derive(Default)]
+struct Where {
+    conditions: Vec<String>,
+    params: Vec<Param>,
+}
+
+impl Where {
+    fn add(&mut self, condition: &str) {
+        self.conditions.push(condition.to_string());
+    }
+
+    /// Add `condition`, with `?` standing for `value`.
+    fn bind(&mut self, condition: &str, value: Param) {
+        self.params.push(value);
+        self.conditions
+            .push(condition.replace('?', &format!("${}", self.params.len())));
+    }
+
+    fn sql(&self) -> String {
+        if self.conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", self.conditions.join(" AND "))
+        }
+    }
+}
+
+struct Select {
+    columns: &'static str,
+    from: &'static str,
+    order: &'static str,
+}
+
+fn query(ctx: &Context<'_>, sql: &str, params: &[Param]) -> Result<Vec<Row>> {
+    let Db(store) = ctx.data::<Db>()?;
+    Ok(store.lock().unwrap().query(sql, params)?)
+}
+
+/// One page of `select` filtered by `filter`, with the total count.
+fn page<T: OutputType>(
+    ctx: &Context<'_>,
+    select: Select,
+    mut filter: Where,
+    first: Option<i32>,
+    offset: Option<i32>,
+    from_row: fn(&mut Columns) -> Option<T>,
+) -> Result<Page<T>> {
+    let first = first.unwrap_or(DEFAULT_PAGE);
+    let offset = offset.unwrap_or(0);
+    if !(1..=MAX_PAGE).contains(&first) {
+        return Err(Error::new(format!("first must be between 1 and {MAX_PAGE}")));
+    }
+    if offset < 0 {
+        return Err(Error::new("offset must not be negative"));
+    }
+
+    let Select { columns, from, order } = select;
+    let count = query(
+        ctx,
+        &format!("SELECT COUNT(*) FROM {from}{}", filter.sql()),
+        &filter.params,
+    )?;
+    let total_count = count.first().and_then(|row| row.first()?.int()).unwrap_or(0);
+
+    let conditions = filter.sql();
+    filter.params.push(Param::Int(first.into()));
+    filter.params.push(Param::Int(offset.into()));
+    let sql = format!(
+        "SELECT {columns} FROM {from}{conditions} ORDER BY {order} LIMIT ${} OFFSET ${}",
+        filter.params.len() - 1,
+        filter.params.len()
+    );
+    let nodes = query(ctx, &sql, &filter.params)?
+        .into_iter()
+        .map(|row| decode(from_row, row))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Page {
+        total_count,
+        has_next_page: i64::from(offset) + (nodes.len() as i64) < total_count,
+        nodes,
+    })
+}
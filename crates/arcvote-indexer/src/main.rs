@@ -0,0 +1,136 @@
+//! `arcvote-indexer` — follow the ArcVote program and write its events into
+//! SQLite (default) or Postgres (`--features postgres`).
+//!
+//! The indexer polls `getSignaturesForAddress` for the program, fetches each
+//! new transaction oldest-first and decodes its logs.  The last indexed
+//! signature is stored with the data, so restarts resume where they left off
+//! and a fresh database backfills the program's full history.
+//!
+//! With `--graphql-addr` it also serves the indexed data as a GraphQL API
+//! (see `graphql`).
+
+mod graphql;
+mod store;
+
+use std::{net::SocketAddr, str::FromStr, thread, time::Duration};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use solana_rpc_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+use store::{SqliteStore, Store, TxContext};
+
+/// Maximum page size of `getSignaturesForAddress`.
+const PAGE_SIZE: usize = 1000;
+
+#[derive(Parser)]
+#[command(name = "arcvote-indexer", version, about = "Index ArcVote events into SQL")]
+struct Cli {
+    /// Solana JSON-RPC endpoint.
+    #[arg(long, short = 'u', env = "ARCVOTE_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// SQLite file path, or a `postgres://` URL.
+    #[arg(long, env = "ARCVOTE_DATABASE", default_value = "arcvote.db")]
+    database: String,
+    /// ArcVote program id.
+    #[arg(long, default_value_t = arcvote_types::ID.into())]
+    program_id: Pubkey,
+    /// Seconds between polls.
+    #[arg(long, default_value_t = 5)]
+    poll_interval: u64,
+    /// Serve a GraphQL API over the indexed data on this address.
+    #[arg(long, env = "ARCVOTE_GRAPHQL_ADDR")]
+    graphql_addr: Option<SocketAddr>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+    let mut store = open_store(&cli.database)?;
+    if let Some(addr) = cli.graphql_addr {
+        graphql::serve(addr, open_store(&cli.database)?)?;
+        println!("serving GraphQL on http://{addr}/graphql");
+    }
+
+    loop {
+        match sync(&rpc, &cli.program_id, store.as_mut()) {
+            Ok(0) => {}
+            Ok(n) => println!("indexed {n} transaction(s)"),
+            Err(e) => eprintln!("sync failed: {e:#}"),
+        }
+        thread::sleep(Duration::from_secs(cli.poll_interval));
+    }
+}
+
+fn open_store(database: &str) -> Result<Box<dyn Store>> {
+    if database.starts_with("postgres://") || database.starts_with("postgresql://") {
+        #[cfg(feature = "postgres")]
+        return Ok(Box::new(store::PostgresStore::connect(database)?));
+        #[cfg(not(feature = "postgres"))]
+        anyhow::bail!("Postgres support requires building with `--features postgres`");
+    }
+    Ok(Box::new(SqliteStore::open(database)?))
+}
+
+/// Index every transaction newer than the stored cursor.
+fn sync(rpc: &RpcClient, program_id: &Pubkey, store: &mut dyn Store) -> Result<usize> {
+    let until = store
+        .last_signature()?
+        .map(|s| Signature::from_str(&s))
+        .transpose()
+        .context("corrupt cursor")?;
+
+    // Pages come newest-first; walk back to the cursor, then replay forward.
+    let mut pending = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            program_id,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(PAGE_SIZE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let Some(last) = page.last() else { break };
+        before = Some(Signature::from_str(&last.signature)?);
+        let done = page.len() < PAGE_SIZE;
+        pending.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    for entry in pending.iter().rev() {
+        let signature = Signature::from_str(&entry.signature)?;
+        let events = if entry.err.is_some() {
+            Vec::new()
+        } else {
+            let tx = rpc.get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )?;
+            match tx.transaction.meta.map(|m| m.log_messages) {
+                Some(OptionSerializer::Some(logs)) => arcvote_types::decode_logs(program_id, &logs),
+                _ => Vec::new(),
+            }
+        };
+        store.record(
+            &TxContext {
+                signature: entry.signature.clone(),
+                slot: entry.slot,
+            },
+            &events,
+        )?;
+    }
+
+    Ok(pending.len())
+}
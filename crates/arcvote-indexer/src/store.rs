@@ -0,0 +1,368 @@
+//! Persistence.  Both backends run the same SQL (see `schema.sql`); each
+//! transaction's events and the cursor update commit atomically.  Reads for
+//! the GraphQL API go through `Store::query` on a separate connection.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use arcvote_types::{BallotKind, CallbackRejectReason, CallbackStage, Event, ProposalKind, QuorumKind, VoteOutcome};
+
+pub const SCHEMA: &str = include_str!("../schema.sql");
+
+const INSERT_PROPOSAL: &str = "INSERT INTO proposals \
+    (address, proposal_id, authority, kind, title, options, deadline, voice_credits, ballot, quorum, \
+     quorum_kind, quorum_target, created_at, created_slot, created_signature) \
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) \
+    ON CONFLICT (address) DO NOTHING";
+const UPDATE_OPTIONS: &str = "UPDATE proposals SET options = $1 WHERE address = $2";
+const INSERT_VOTE: &str = "INSERT INTO votes (signature, proposal, voter_count, cast_at, slot) \
+    VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING";
+const UPDATE_VOTER_COUNT: &str =
+    "UPDATE proposals SET voter_count = $1 WHERE address = $2 AND voter_count < $1";
+const INSERT_RESULTS: &str = "INSERT INTO results \
+    (proposal, option_0, option_1, option_2, option_3, total_votes, winner, signature, slot) \
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) ON CONFLICT (proposal) DO NOTHING";
+const UPDATE_OUTCOME: &str = "UPDATE results SET outcome = $1 WHERE proposal = $2";
+const MARK_FINALIZED: &str = "UPDATE proposals SET finalized = TRUE WHERE address = $1";
+const MARK_QUORUM_FAILED: &str = "UPDATE proposals SET quorum_failed = TRUE WHERE address = $1";
+const INSERT_REJECTED: &str = "INSERT INTO rejected_callbacks \
+    (signature, proposal, stage, reason, rejected_at, slot) \
+    VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING";
+const INSERT_FEE_WITHDRAWAL: &str = "INSERT INTO fee_withdrawals \
+    (signature, recipient, amount, total_withdrawn, remaining, withdrawn_at, slot) \
+    VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT DO NOTHING";
+const UPSERT_CURSOR: &str = "INSERT INTO indexer_cursor (id, last_signature) VALUES (1, $1) \
+    ON CONFLICT (id) DO UPDATE SET last_signature = excluded.last_signature";
+const SELECT_CURSOR: &str = "SELECT last_signature FROM indexer_cursor WHERE id = 1";
+
+/// Where an event was observed.
+pub struct TxContext {
+    pub signature: String,
+    pub slot: u64,
+}
+
+pub enum Param {
+    Text(String),
+    Int(i64),
+}
+
+/// A column read back by `Store::query`.  Booleans read as `Int` (0 or 1)
+/// on both backends.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Int(i64),
+    Text(String),
+}
+
+impl Value {
+    pub fn int(&self) -> Option<i64> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn text(self) -> Option<String> {
+        match self {
+            Value::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+pub type Row = Vec<Value>;
+
+type Statement = (&'static str, Vec<Param>);
+
+pub trait Store: Send {
+    /// Newest fully indexed signature.
+    fn last_signature(&mut self) -> Result<Option<String>>;
+
+    /// Apply one transaction's events and advance the cursor to it.
+    fn record(&mut self, tx: &TxContext, events: &[Event]) -> Result<()>;
+
+    /// Run a read-only `sql` and return its rows.
+    fn query(&mut self, sql: &str, params: &[Param]) -> Result<Vec<Row>>;
+}
+
+fn statements(tx: &TxContext, events: &[Event]) -> Vec<Statement> {
+    use Param::{Int, Text};
+
+    let sig = || Text(tx.signature.clone());
+    let slot = || Int(tx.slot as i64);
+    let mut out = Vec::new();
+
+    for event in events {
+        match event {
+            Event::ProposalCreatedEvent(e) => out.push((
+                INSERT_PROPOSAL,
+                vec![
+                    Text(e.proposal.to_string()),
+                    Int(e.proposal_id.into()),
+                    Text(e.authority.to_string()),
+                    Text(proposal_kind_name(e.kind).to_string()),
+                    Text(e.title.clone()),
+                    Text(e.options.join("\n")),
+                    Int(e.deadline),
+                    Int(e.voice_credits as i64),
+                    Text(ballot_kind_name(e.ballot).to_string()),
+                    Int(e.quorum.into()),
+                    Text(quorum_kind_name(e.quorum_kind).to_string()),
+                    Int(e.quorum_target as i64),
+                    Int(e.timestamp),
+                    slot(),
+                    sig(),
+                ],
+            )),
+            Event::CandidateRegisteredEvent(e) => out.push((
+                UPDATE_OPTIONS,
+                vec![Text(e.options.join("\n")), Text(e.proposal.to_string())],
+            )),
+            Event::VoteCastEvent(e) => {
+                out.push((
+                    INSERT_VOTE,
+                    vec![sig(), Text(e.proposal.to_string()), Int(e.voter_count.into()), Int(e.timestamp), slot()],
+                ));
+                out.push((
+                    UPDATE_VOTER_COUNT,
+                    vec![Int(e.voter_count.into()), Text(e.proposal.to_string())],
+                ));
+            }
+            Event::ResultsRevealedEvent(e) => {
+                out.push((
+                    INSERT_RESULTS,
+                    vec![
+                        Text(e.proposal.to_string()),
+                        Int(e.option_0 as i64),
+                        Int(e.option_1 as i64),
+                        Int(e.option_2 as i64),
+                        Int(e.option_3 as i64),
+                        Int(e.total_votes as i64),
+                        Int(e.winner.into()),
+                        sig(),
+                        slot(),
+                    ],
+                ));
+                out.push((MARK_FINALIZED, vec![Text(e.proposal.to_string())]));
+            }
+            Event::OutcomeRevealedEvent(e) => out.push((
+                UPDATE_OUTCOME,
+                vec![Text(outcome_name(e.outcome).to_string()), Text(e.proposal.to_string())],
+            )),
+            Event::QuorumFailedEvent(e) => {
+                out.push((MARK_QUORUM_FAILED, vec![Text(e.proposal.to_string())]));
+            }
+            Event::CallbackRejectedEvent(e) => out.push((
+                INSERT_REJECTED,
+                vec![
+                    sig(),
+                    Text(e.proposal.to_string()),
+                    Text(stage_name(e.stage).to_string()),
+                    Text(reason_name(e.reason).to_string()),
+                    Int(e.timestamp),
+                    slot(),
+                ],
+            )),
+            Event::FeesWithdrawnEvent(e) => out.push((
+                INSERT_FEE_WITHDRAWAL,
+                vec![
+                    sig(),
+                    Text(e.recipient.to_string()),
+                    Int(e.amount as i64),
+                    Int(e.total_withdrawn as i64),
+                    Int(e.remaining as i64),
+                    Int(e.timestamp),
+                    slot(),
+                ],
+            )),
+            // Events the schema has no table for.
+            _ => {}
+        }
+    }
+
+    out.push((UPSERT_CURSOR, vec![sig()]));
+    out
+}
+
+fn stage_name(stage: CallbackStage) -> &'static str {
+    match stage {
+        CallbackStage::InitTallies => "init_tallies",
+        CallbackStage::CastVote => "cast_vote",
+        CallbackStage::RevealResults => "reveal_results",
+        CallbackStage::QuorumProgress => "quorum_progress",
+    }
+}
+
+fn proposal_kind_name(kind: ProposalKind) -> &'static str {
+    match kind {
+        ProposalKind::Governance => "governance",
+        ProposalKind::Poll => "poll",
+    }
+}
+
+fn ballot_kind_name(kind: BallotKind) -> &'static str {
+    match kind {
+        BallotKind::Quadratic => "quadratic",
+        BallotKind::Likert => "likert",
+        BallotKind::YesNoAbstain { .. } => "yes_no_abstain",
+        BallotKind::Pairwise { .. } => "pairwise",
+        BallotKind::TimeWeighted { .. } => "time_weighted",
+        BallotKind::Jury { .. } => "jury",
+        BallotKind::Approval { .. } => "approval",
+        BallotKind::BudgetBox { .. } => "budget_box",
+        BallotKind::Ranked => "ranked",
+        BallotKind::Borda => "borda",
+        BallotKind::Condorcet => "condorcet",
+        BallotKind::Star => "star",
+        BallotKind::Range => "range",
+    }
+}
+
+fn outcome_name(outcome: VoteOutcome) -> &'static str {
+    match outcome {
+        VoteOutcome::Passed => "passed",
+        VoteOutcome::Rejected => "rejected",
+        VoteOutcome::Vetoed => "vetoed",
+    }
+}
+
+fn quorum_kind_name(kind: QuorumKind) -> &'static str {
+    match kind {
+        QuorumKind::Voters => "voters",
+        QuorumKind::RegisteredBps => "registered_bps",
+        QuorumKind::SupplyBps => "supply_bps",
+        QuorumKind::RegistrarBps => "registrar_bps",
+    }
+}
+
+fn reason_name(reason: CallbackRejectReason) -> &'static str {
+    match reason {
+        CallbackRejectReason::ComputationAborted => "computation_aborted",
+        CallbackRejectReason::VerificationFailed => "verification_failed",
+    }
+}
+
+// ---- SQLite ----
+
+pub struct SqliteStore {
+    conn: rusqlite::Connection,
+}
+
+impl SqliteStore {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = rusqlite::Connection::open(path)?;
+        // The indexer and the GraphQL server each hold a connection.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+}
+
+impl rusqlite::ToSql for Param {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        match self {
+            Param::Text(s) => s.to_sql(),
+            Param::Int(i) => i.to_sql(),
+        }
+    }
+}
+
+impl Store for SqliteStore {
+    fn last_signature(&mut self) -> Result<Option<String>> {
+        use rusqlite::OptionalExtension;
+        Ok(self
+            .conn
+            .query_row(SELECT_CURSOR, [], |row| row.get(0))
+            .optional()?)
+    }
+
+    fn record(&mut self, tx: &TxContext, events: &[Event]) -> Result<()> {
+        let db = self.conn.transaction()?;
+        for (sql, params) in statements(tx, events) {
+            db.execute(sql, rusqlite::params_from_iter(params.iter()))?;
+        }
+        db.commit()?;
+        Ok(())
+    }
+
+    fn query(&mut self, sql: &str, params: &[Param]) -> Result<Vec<Row>> {
+        use rusqlite::types::ValueRef;
+        let mut statement = self.conn.prepare(sql)?;
+        let columns = statement.column_count();
+        let rows = statement.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            (0..columns)
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Integer(i) => Value::Int(i),
+                        ValueRef::Text(t) => Value::Text(String::from_utf8_lossy(t).into_owned()),
+                        ValueRef::Null | ValueRef::Real(_) | ValueRef::Blob(_) => Value::Null,
+                    })
+                })
+                .collect()
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+// ---- Postgres ----
+
+#[cfg(feature = "postgres")]
+pub struct PostgresStore {
+    client: postgres::Client,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresStore {
+    pub fn connect(url: &str) -> Result<Self> {
+        let mut client = postgres::Client::connect(url, postgres::NoTls)?;
+        client.batch_execute(SCHEMA)?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Store for PostgresStore {
+    fn last_signature(&mut self) -> Result<Option<String>> {
+        Ok(self.client.query_opt(SELECT_CURSOR, &[])?.map(|row| row.get(0)))
+    }
+
+    fn record(&mut self, tx: &TxContext, events: &[Event]) -> Result<()> {
+        let mut db = self.client.transaction()?;
+        for (sql, params) in statements(tx, events) {
+            db.execute(sql, &postgres_params(&params))?;
+        }
+        db.commit()?;
+        Ok(())
+    }
+
+    fn query(&mut self, sql: &str, params: &[Param]) -> Result<Vec<Row>> {
+        use postgres::types::Type;
+        let rows = self.client.query(sql, &postgres_params(params))?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (0..row.len())
+                    .map(|i| match *row.columns()[i].type_() {
+                        Type::BOOL => row
+                            .get::<_, Option<bool>>(i)
+                            .map_or(Value::Null, |b| Value::Int(b.into())),
+                        Type::INT8 => row.get::<_, Option<i64>>(i).map_or(Value::Null, Value::Int),
+                        _ => row.get::<_, Option<String>>(i).map_or(Value::Null, Value::Text),
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[cfg(feature = "postgres")]
+fn postgres_params(params: &[Param]) -> Vec<&(dyn postgres::types::ToSql + Sync)> {
+    params
+        .iter()
+        .map(|p| match p {
+            Param::Text(s) => s as &(dyn postgres::types::ToSql + Sync),
+            Param::Int(i) => i as &(dyn postgres::types::ToSql + Sync),
+        })
+        .collect()
+}
@@ -0,0 +1,523 @@
+//! `arcvote-keeper` — drive ArcVote proposals through their lifecycle.
+//!
+//! Each tick the keeper loads every proposal and, depending on its state:
+//!
+//! - queues `reveal_results` (`reveal_outcome` for For/Against/Abstain
+//!   ballots, `reveal_chambers` once they have a council,
+//!   `reveal_pairwise` for pairwise funding rounds) once the
+//!   deadline has passed with quorum met, or
+//!   `reveal_poll` for any poll, so polls reveal themselves at the deadline,
+//!   or `auto_reveal` for a proposal that enabled it, or `operator_reveal`
+//!   for one whose reveal operator is a loaded key,
+//! - calls `mark_quorum_failed` when the deadline passed below quorum,
+//! - calls `confirm_parent_outcome` for an amendment once its parent has
+//!   revealed the required winner, and `resolve_dependency` for a chained
+//!   proposal once its parent was decided as required, so voting on them
+//!   opens by itself,
+//! - calls `execute_sanction` once a proposal declaring a sanction passed,
+//! - calls `release_held_ballot` for each ballot a mix window still holds,
+//! - calls `process_ballot_batch` whenever a proposal's ballot buffer holds
+//!   a full batch, and once voting closes for whatever is left,
+//! - calls `expire_reveal` when a queued reveal never got its callback,
+//! - calls `settle_rewards` for settled proposals with a reward pool,
+//! - calls `settle_matching` for settled funding rounds with a matching
+//!   pool, then `pay_match` for each option,
+//! - with `--commit-tallies`, calls `commit_tally` whenever a proposal's
+//!   tally moved since its last checkpoint, and once more after the reveal,
+//! - closes finalized or quorum-failed proposals, and any nullifier set or
+//!   compliance roll, to reclaim their rent — first calling
+//!   `release_identity` for each identity a compliance roll still seals.
+//!
+//! Reveals and closes must be signed by the proposal authority, so they are
+//! only sent for proposals whose authority is one of the loaded keypairs —
+//! or, for reveals, whose reveal operator is.
+//! The permissionless instructions, poll reveals and auto-reveals included,
+//! are paid by the first keypair.
+
+mod metrics;
+
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, thread, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use arcvote_client::{
+    accounts::{
+        decode_commitment, decode_compliance_roll, decode_dependency, decode_sanction, decode_tally, Dependency,
+        ProposalAccount, ProposalState, Sanction,
+    },
+    instructions::{self, BallotKind, ProposalKind, VoteOutcome, BALLOT_BATCH_SIZE},
+    pda,
+    rpc::{fetch_all_proposals, fetch_config, fetch_matching_pool, fetch_reward_pool, fetch_sealed_identities},
+    verify::verify_final_checkpoint,
+    ArciumEnv, Instruction, Pubkey,
+};
+use clap::Parser;
+use prometheus_client::encoding::EncodeLabelValue;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+use metrics::{Metrics, StateLabels};
+
+#[derive(Parser)]
+#[command(name = "arcvote-keeper", version, about = "Keeper bot for ArcVote proposals")]
+struct Cli {
+    /// Solana JSON-RPC endpoint.
+    #[arg(long, short = 'u', env = "ARCVOTE_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair files of the proposal authorities to act for.  The first one
+    /// also pays for permissionless maintenance.
+    #[arg(long = "keypair", short = 'k', env = "ARCVOTE_KEEPER_KEYPAIRS", value_delimiter = ',', required = true)]
+    keypairs: Vec<String>,
+    /// Offset of the Arcium cluster the MXE is assigned to.
+    #[arg(long, env = "ARCIUM_CLUSTER_OFFSET", default_value_t = 0)]
+    cluster_offset: u32,
+    /// Seconds between ticks.
+    #[arg(long, default_value_t = 30)]
+    poll_interval: u64,
+    /// Address of the Prometheus `/metrics` endpoint.
+    #[arg(long, env = "ARCVOTE_KEEPER_METRICS", default_value = "127.0.0.1:9464")]
+    metrics_addr: SocketAddr,
+    /// Checkpoint every proposal's encrypted tally as ballots land, paid by
+    /// the first keypair.
+    #[arg(long)]
+    commit_tallies: bool,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum Action {
+    Reveal,
+    RevealPoll,
+    AutoReveal,
+    OperatorReveal,
+    ConfirmParent,
+    ResolveDependency,
+    ExecuteSanction,
+    MarkQuorumFailed,
+    ReleaseHeldBallots,
+    ProcessBallotBatches,
+    ExpireReveal,
+    SettleRewards,
+    SettleMatching,
+    CommitTally,
+    Close,
+}
+
+struct Keeper {
+    rpc: RpcClient,
+    env: ArciumEnv,
+    /// Authority keypairs by pubkey.
+    signers: HashMap<Pubkey, Keypair>,
+    /// Pays for permissionless instructions.
+    fee_payer: Pubkey,
+    commit_tallies: bool,
+    metrics: Arc<Metrics>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let mut signers = HashMap::new();
+    let mut fee_payer = None;
+    for path in &cli.keypairs {
+        let keypair = read_keypair_file(path).map_err(|e| anyhow!("failed to read keypair {path}: {e}"))?;
+        fee_payer.get_or_insert(keypair.pubkey());
+        signers.insert(keypair.pubkey(), keypair);
+    }
+
+    let metrics = Arc::new(Metrics::default());
+    metrics.serve(cli.metrics_addr)?;
+
+    let keeper = Keeper {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        env: ArciumEnv::new(cli.cluster_offset),
+        signers,
+        fee_payer: fee_payer.context("no keypair given")?,
+        commit_tallies: cli.commit_tallies,
+        metrics,
+    };
+    println!(
+        "arcvote-keeper acting for {} authority key(s); metrics on http://{}/metrics",
+        keeper.signers.len(),
+        cli.metrics_addr
+    );
+
+    loop {
+        if let Err(e) = keeper.tick() {
+            keeper.metrics.tick_errors.inc();
+            eprintln!("tick failed: {e:#}");
+        }
+        thread::sleep(Duration::from_secs(cli.poll_interval));
+    }
+}
+
+impl Keeper {
+    fn tick(&self) -> Result<()> {
+        let now = self.rpc.get_block_time(self.rpc.get_slot()?)?;
+        let reveal_timeout = fetch_config(&self.rpc)?.reveal_timeout;
+        let proposals = fetch_all_proposals(&self.rpc)?;
+
+        self.metrics.proposals.clear();
+        for (address, proposal) in &proposals {
+            self.metrics
+                .proposals
+                .get_or_create(&StateLabels {
+                    state: state_label(proposal.state(now)),
+                })
+                .inc();
+
+            let Some(action) = self.plan(address, proposal, &proposals, now, reveal_timeout) else {
+                continue;
+            };
+            let result = self.execute(action, address, proposal);
+            self.metrics.record(action, result.is_ok());
+            match result {
+                Ok(sig) => println!("{action:?} proposal {address} (id={}): {sig}", proposal.id),
+                Err(e) => eprintln!("{action:?} proposal {address} (id={}) failed: {e:#}", proposal.id),
+            }
+        }
+
+        self.metrics.last_tick_timestamp.set(now);
+        Ok(())
+    }
+
+    /// What, if anything, to do with `proposal` at cluster time `now`;
+    /// `proposals` is everything loaded this tick, where an amendment's
+    /// parent is looked up.
+    fn plan(
+        &self,
+        address: &Pubkey,
+        proposal: &ProposalAccount,
+        proposals: &[(Pubkey, ProposalAccount)],
+        now: i64,
+        reveal_timeout: i64,
+    ) -> Option<Action> {
+        let ours = self.signers.contains_key(&proposal.authority);
+        match proposal.state(now) {
+            ProposalState::Registration | ProposalState::Voting if proposal.awaiting_parent_outcome() => {
+                let required = proposal.requires_parent_outcome?;
+                let (_, parent) = proposals.iter().find(|(address, _)| *address == required.parent)?;
+                (parent.is_finalized && parent.winner == required.winner).then_some(Action::ConfirmParent)
+            }
+            ProposalState::Registration | ProposalState::Voting if proposal.awaiting_dependency => {
+                let dependency = self.fetch_dependency(address).ok()?;
+                let (_, parent) = proposals.iter().find(|(address, _)| *address == dependency.parent)?;
+                dependency.condition.is_met_by(parent).then_some(Action::ResolveDependency)
+            }
+            ProposalState::Voting if proposal.buffered as usize >= BALLOT_BATCH_SIZE => {
+                Some(Action::ProcessBallotBatches)
+            }
+            ProposalState::Voting if self.tally_moved(address, proposal) => Some(Action::CommitTally),
+            ProposalState::Registration | ProposalState::Voting => None,
+            // The last checkpoint, of the tally the reveal read.
+            ProposalState::Finalized if self.tally_moved(address, proposal) => Some(Action::CommitTally),
+            // Before the close, which takes the proposal the sanction reads.
+            ProposalState::Finalized
+                if proposal.outcome == Some(VoteOutcome::Passed)
+                    && self.fetch_sanction(address).is_ok_and(|sanction| !sanction.executed) =>
+            {
+                Some(Action::ExecuteSanction)
+            }
+            ProposalState::Finalized | ProposalState::QuorumFailed
+                if proposal.has_reward_pool && !proposal.rewards_settled =>
+            {
+                Some(Action::SettleRewards)
+            }
+            ProposalState::Finalized | ProposalState::QuorumFailed
+                if proposal.has_matching_pool && !proposal.matching_settled =>
+            {
+                Some(Action::SettleMatching)
+            }
+            ProposalState::Finalized | ProposalState::QuorumFailed => ours.then_some(Action::Close),
+            ProposalState::VotingClosed if !proposal.quorum_reached() => {
+                Some(Action::MarkQuorumFailed)
+            }
+            ProposalState::VotingClosed if proposal.mix_held > 0 => Some(Action::ReleaseHeldBallots),
+            ProposalState::VotingClosed if proposal.buffered > 0 => Some(Action::ProcessBallotBatches),
+            ProposalState::VotingClosed if proposal.reveal_pending_since != 0 => {
+                (now >= proposal.reveal_pending_since + reveal_timeout).then_some(Action::ExpireReveal)
+            }
+            // Only the trustees can unlock the reveal.
+            ProposalState::VotingClosed if !proposal.trustees_approved() => None,
+            ProposalState::VotingClosed if proposal.kind == ProposalKind::Poll => Some(Action::RevealPoll),
+            ProposalState::VotingClosed if ours => Some(Action::Reveal),
+            ProposalState::VotingClosed if self.operator(proposal).is_some() => Some(Action::OperatorReveal),
+            ProposalState::VotingClosed => proposal.auto_reveal.then_some(Action::AutoReveal),
+        }
+    }
+
+    fn execute(&self, action: Action, address: &Pubkey, proposal: &ProposalAccount) -> Result<String> {
+        let (signer, ixs) = match action {
+            Action::Reveal => {
+                let authority = &self.signers[&proposal.authority];
+                let reveal = match proposal.ballot {
+                    BallotKind::YesNoAbstain { .. } if proposal.dual_chamber => instructions::reveal_chambers,
+                    BallotKind::YesNoAbstain { .. } => instructions::reveal_outcome,
+                    BallotKind::Pairwise { .. } => instructions::reveal_pairwise,
+                    BallotKind::BudgetBox { .. } => instructions::reveal_budget_box,
+                    BallotKind::Ranked => instructions::reveal_ranked,
+                    BallotKind::Condorcet => instructions::reveal_condorcet,
+                    BallotKind::Star => instructions::reveal_star,
+                    BallotKind::Quadratic
+                    | BallotKind::Likert
+                    | BallotKind::TimeWeighted { .. }
+                    | BallotKind::Jury { .. }
+                    | BallotKind::Approval { .. }
+                    | BallotKind::Borda
+                    | BallotKind::Range => instructions::reveal_results,
+                };
+                let ix = reveal(
+                    &self.env,
+                    &authority.pubkey(),
+                    &authority.pubkey(),
+                    proposal.id,
+                    rand::random(),
+                );
+                (authority, vec![ix])
+            }
+            Action::OperatorReveal => {
+                let operator = self.operator(proposal).ok_or_else(|| anyhow!("not the reveal operator"))?;
+                let ix = instructions::operator_reveal(
+                    &self.env,
+                    &operator.pubkey(),
+                    &operator.pubkey(),
+                    address,
+                    rand::random(),
+                );
+                (operator, vec![ix])
+            }
+            Action::RevealPoll => (
+                &self.signers[&self.fee_payer],
+                vec![instructions::reveal_poll(&self.env, &self.fee_payer, address, rand::random())],
+            ),
+            Action::AutoReveal => (
+                &self.signers[&self.fee_payer],
+                vec![instructions::auto_reveal(
+                    &self.env,
+                    &self.fee_payer,
+                    address,
+                    proposal.auto_reveals,
+                )],
+            ),
+            Action::ConfirmParent => {
+                let parent = proposal
+                    .requires_parent_outcome
+                    .ok_or_else(|| anyhow!("proposal has no parent outcome"))?
+                    .parent;
+                (
+                    &self.signers[&self.fee_payer],
+                    vec![instructions::confirm_parent_outcome(address, &parent)],
+                )
+            }
+            Action::ResolveDependency => (
+                &self.signers[&self.fee_payer],
+                vec![instructions::resolve_dependency(address, &self.fetch_dependency(address)?.parent)],
+            ),
+            Action::ExecuteSanction => {
+                let treasury = fetch_config(&self.rpc)?.treasury;
+                let action = self.fetch_sanction(address)?.action;
+                (
+                    &self.signers[&self.fee_payer],
+                    vec![instructions::execute_sanction(address, &action, &treasury)],
+                )
+            }
+            Action::Close => {
+                let authority = &self.signers[&proposal.authority];
+                let mut ixs = vec![instructions::close_proposal(&authority.pubkey(), &authority.pubkey(), proposal.id)];
+                if self.rpc.get_account(&pda::nullifier_set_pda(address).0).is_ok() {
+                    ixs.insert(0, instructions::close_nullifier_set(&authority.pubkey(), address));
+                }
+                if let Ok(data) = self.rpc.get_account_data(&pda::compliance_roll_pda(address).0) {
+                    // Sealed identities can't be released once the proposal
+                    // is gone; release them now and close on a later tick.
+                    let roll = decode_compliance_roll(&data)?;
+                    if roll.released < roll.sealed {
+                        return self.release_identities(authority, address, proposal);
+                    }
+                    ixs.insert(0, instructions::close_compliance_roll(&authority.pubkey(), address));
+                }
+                if self.rpc.get_account(&pda::trustee_set_pda(address).0).is_ok() {
+                    ixs.insert(0, instructions::close_trustee_set(&authority.pubkey(), address));
+                }
+                (authority, ixs)
+            }
+            Action::MarkQuorumFailed => (
+                &self.signers[&self.fee_payer],
+                vec![instructions::mark_quorum_failed(&self.fee_payer, address)],
+            ),
+            Action::ReleaseHeldBallots => {
+                // One computation per ballot, each in its own transaction.
+                let payer = &self.signers[&self.fee_payer];
+                let mut sig = String::new();
+                for _ in 0..proposal.mix_held {
+                    let ix = instructions::release_held_ballot(
+                        &self.env,
+                        &self.fee_payer,
+                        &proposal.authority,
+                        proposal.id,
+                        rand::random(),
+                    );
+                    sig = self.send(payer, &[ix])?;
+                }
+                return Ok(sig);
+            }
+            Action::ProcessBallotBatches => {
+                // Before the deadline only full batches; after it, the
+                // last partial one too.
+                let payer = &self.signers[&self.fee_payer];
+                let now = self.rpc.get_block_time(self.rpc.get_slot()?)?;
+                let batches = match proposal.state(now) {
+                    ProposalState::Voting => proposal.buffered as usize / BALLOT_BATCH_SIZE,
+                    _ => (proposal.buffered as usize).div_ceil(BALLOT_BATCH_SIZE),
+                };
+                let mut sig = String::new();
+                for _ in 0..batches {
+                    let ix = instructions::process_ballot_batch(
+                        &self.env,
+                        &self.fee_payer,
+                        &proposal.authority,
+                        proposal.id,
+                        rand::random(),
+                    );
+                    sig = self.send(payer, &[ix])?;
+                }
+                return Ok(sig);
+            }
+            Action::ExpireReveal => (
+                &self.signers[&self.fee_payer],
+                vec![instructions::expire_reveal(&self.fee_payer, address)],
+            ),
+            Action::CommitTally => (
+                &self.signers[&self.fee_payer],
+                vec![instructions::commit_tally(&self.fee_payer, address)],
+            ),
+            Action::SettleRewards => {
+                // The remainder goes to the authority's token account, which
+                // may not exist yet.
+                let mint = fetch_reward_pool(&self.rpc, address)?.mint;
+                (
+                    &self.signers[&self.fee_payer],
+                    vec![
+                        instructions::create_token_account(&self.fee_payer, &proposal.authority, &mint),
+                        instructions::settle_rewards(&self.fee_payer, address, &proposal.authority, &mint),
+                    ],
+                )
+            }
+            Action::SettleMatching => return self.settle_matching(address, proposal),
+        };
+        self.send(signer, &ixs)
+    }
+
+    /// Queue `release_identity` for each sealed identity not yet released,
+    /// one transaction each.
+    fn release_identities(&self, authority: &Keypair, address: &Pubkey, proposal: &ProposalAccount) -> Result<String> {
+        let mut sig = String::new();
+        for (sealed, _) in fetch_sealed_identities(&self.rpc, address)?
+            .into_iter()
+            .filter(|(_, identity)| !identity.released)
+        {
+            let ix = instructions::release_identity(
+                &self.env,
+                &authority.pubkey(),
+                &authority.pubkey(),
+                proposal.id,
+                rand::random(),
+                &sealed,
+            );
+            sig = self.send(authority, &[ix])?;
+        }
+        Ok(sig)
+    }
+
+    /// Settle `proposal`'s matching pool, then pay each option's match,
+    /// one transaction each.
+    fn settle_matching(&self, address: &Pubkey, proposal: &ProposalAccount) -> Result<String> {
+        let payer = &self.signers[&self.fee_payer];
+        let mint = fetch_matching_pool(&self.rpc, address)?.mint;
+        // The remainder goes to the authority's token account, which may
+        // not exist yet.
+        let mut sig = self.send(
+            payer,
+            &[
+                instructions::create_token_account(&self.fee_payer, &proposal.authority, &mint),
+                instructions::settle_matching(&self.fee_payer, address, &proposal.authority, &mint),
+            ],
+        )?;
+        let pool = fetch_matching_pool(&self.rpc, address)?;
+        for (option, recipient) in pool.recipients.iter().enumerate() {
+            let option = option as u8;
+            if pool.paid & (1 << option) != 0 {
+                continue;
+            }
+            sig = self.send(
+                payer,
+                &[
+                    instructions::create_token_account(&self.fee_payer, recipient, &mint),
+                    instructions::pay_match(&self.fee_payer, address, option, recipient, &mint),
+                ],
+            )?;
+        }
+        Ok(sig)
+    }
+
+    /// Whether tally checkpoints are on and ballots have landed on
+    /// `proposal` since its latest one.
+    fn tally_moved(&self, address: &Pubkey, proposal: &ProposalAccount) -> bool {
+        if !self.commit_tallies || proposal.applied_ballots == 0 {
+            return false;
+        }
+        let tally = self.rpc.get_account_data(&pda::tally_pda(address).0);
+        let Some(tally) = tally.ok().and_then(|data| decode_tally(&data).ok()) else {
+            return false;
+        };
+        match self.rpc.get_account_data(&pda::commitment_pda(address).0) {
+            Ok(data) => decode_commitment(&data)
+                .is_ok_and(|commitment| verify_final_checkpoint(address, proposal, &tally, &commitment).is_err()),
+            Err(_) => true,
+        }
+    }
+
+    /// The loaded keypair of `proposal`'s reveal operator, if its ballot
+    /// is one `operator_reveal` reveals.
+    fn operator(&self, proposal: &ProposalAccount) -> Option<&Keypair> {
+        let reveals_results = matches!(
+            proposal.ballot,
+            BallotKind::Quadratic
+                | BallotKind::Likert
+                | BallotKind::TimeWeighted { .. }
+                | BallotKind::Jury { .. }
+                | BallotKind::Approval { .. }
+                | BallotKind::Borda
+                | BallotKind::Range
+        );
+        self.signers.get(&proposal.reveal_operator.filter(|_| reveals_results)?)
+    }
+
+    fn fetch_dependency(&self, address: &Pubkey) -> Result<Dependency> {
+        let data = self.rpc.get_account_data(&pda::dependency_pda(address).0)?;
+        Ok(decode_dependency(&data)?)
+    }
+
+    fn fetch_sanction(&self, address: &Pubkey) -> Result<Sanction> {
+        let data = self.rpc.get_account_data(&pda::sanction_pda(address).0)?;
+        Ok(decode_sanction(&data)?)
+    }
+
+    fn send(&self, signer: &Keypair, ixs: &[Instruction]) -> Result<String> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&signer.pubkey()), &[signer], blockhash);
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?.to_string())
+    }
+}
+
+fn state_label(state: ProposalState) -> &'static str {
+    match state {
+        ProposalState::Registration => "registration",
+        ProposalState::Voting => "voting",
+        ProposalState::VotingClosed => "voting_closed",
+        ProposalState::Finalized => "finalized",
+        ProposalState::QuorumFailed => "quorum_failed",
+    }
+}
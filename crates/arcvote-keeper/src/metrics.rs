@@ -0,0 +1,95 @@
+//! Prometheus metrics, served in the OpenMetrics text format on `/metrics`.
+
+use std::{net::SocketAddr, sync::Arc, thread};
+
+use anyhow::{anyhow, Result};
+use prometheus_client::{
+    encoding::{text::encode, EncodeLabelSet, EncodeLabelValue},
+    metrics::{counter::Counter, family::Family, gauge::Gauge},
+    registry::Registry,
+};
+
+use crate::Action;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum Outcome {
+    Ok,
+    Failed,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ActionLabels {
+    pub action: Action,
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct StateLabels {
+    pub state: &'static str,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    pub actions: Family<ActionLabels, Counter>,
+    pub proposals: Family<StateLabels, Gauge>,
+    pub tick_errors: Counter,
+    pub last_tick_timestamp: Gauge,
+}
+
+impl Metrics {
+    fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "arcvote_keeper_actions",
+            "Transactions sent by the keeper, by action and outcome",
+            self.actions.clone(),
+        );
+        registry.register(
+            "arcvote_keeper_proposals",
+            "Proposals seen in the last tick, by lifecycle state",
+            self.proposals.clone(),
+        );
+        registry.register(
+            "arcvote_keeper_tick_errors",
+            "Ticks that failed before evaluating proposals",
+            self.tick_errors.clone(),
+        );
+        registry.register(
+            "arcvote_keeper_last_tick_timestamp_seconds",
+            "Cluster time of the last completed tick",
+            self.last_tick_timestamp.clone(),
+        );
+    }
+
+    pub fn record(&self, action: Action, ok: bool) {
+        let outcome = if ok { Outcome::Ok } else { Outcome::Failed };
+        self.actions.get_or_create(&ActionLabels { action, outcome }).inc();
+    }
+
+    /// Start the `/metrics` endpoint on a background thread.
+    pub fn serve(self: &Arc<Self>, addr: SocketAddr) -> Result<()> {
+        let mut registry = Registry::default();
+        self.register(&mut registry);
+        let server = tiny_http::Server::http(addr).map_err(|e| anyhow!("metrics server: {e}"))?;
+        let content_type = tiny_http::Header::from_bytes(
+            "Content-Type",
+            "application/openmetrics-text; version=1.0.0; charset=utf-8",
+        )
+        .expect("static header is valid");
+
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let response = if request.url() == "/metrics" {
+                    let mut body = String::new();
+                    match encode(&mut body, &registry) {
+                        Ok(()) => tiny_http::Response::from_string(body).with_header(content_type.clone()),
+                        Err(_) => tiny_http::Response::from_string("encoding failed").with_status_code(500),
+                    }
+                } else {
+                    tiny_http::Response::from_string("not found").with_status_code(404)
+                };
+                let _ = request.respond(response);
+            }
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! `arcvote-pay` — cast ArcVote ballots from any Solana Pay wallet.
+//!
+//! The server answers Solana Pay transaction requests.  A link names a
+//! proposal and the ballot to cast on it:
+//!
+//! ```text
+//! solana:https%3A%2F%2Fvote.example.org%2Fvote%2F<proposal>%3Fvotes%3D3%2C1%2C0%2C0
+//! ```
+//!
+//! Shown as a QR code, it lets a phone wallet vote without a dApp.  On
+//! scanning, the wallet fetches `GET /vote/<proposal>` for the label and
+//! icon to show, then `POST`s its account; the server answers with a
+//! `cast_vote` transaction for that account, the ballot already encrypted to
+//! the MXE, which the wallet signs and sends.  `GET /link/<proposal>?votes=…`
+//! returns the link for a ballot, ready to render as a QR code.
+//!
+//! The ballot is encrypted here rather than in the wallet, under a fresh
+//! key per request: the chain and the cluster only ever see ciphertext, but
+//! the server — like anyone who reads the link — sees the allocation.
+
+mod vote;
+
+use std::{sync::Arc, thread};
+
+use anyhow::{anyhow, Result};
+use arcvote_client::{ArciumEnv, Pubkey};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Parser;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::{json, Value};
+use solana_rpc_client::rpc_client::RpcClient;
+
+/// Shown by wallets next to the label.
+const ICON: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 64 64"><rect width="64" height="64" rx="12" fill="#4f46e5"/><path d="M18 33l9 9 19-20" fill="none" stroke="#fff" stroke-width="6" stroke-linecap="round" stroke-linejoin="round"/></svg>"##;
+
+#[derive(Parser)]
+#[command(
+    name = "arcvote-pay",
+    version,
+    about = "Serve Solana Pay transaction requests that cast ArcVote ballots"
+)]
+struct Cli {
+    /// Solana JSON-RPC endpoint.
+    #[arg(long, short = 'u', env = "ARCVOTE_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Offset of the Arcium cluster the MXE is assigned to.
+    #[arg(long, env = "ARCIUM_CLUSTER_OFFSET", default_value_t = 0)]
+    cluster_offset: u32,
+    /// Address to listen on.
+    #[arg(long, env = "ARCVOTE_PAY_LISTEN", default_value = "127.0.0.1:8920")]
+    listen: String,
+    /// HTTPS URL wallets reach this server at, e.g. `https://vote.example.org`.
+    #[arg(long, env = "ARCVOTE_PAY_URL")]
+    public_url: String,
+    /// Label wallets show for the request.
+    #[arg(long, default_value = "ArcVote")]
+    label: String,
+}
+
+struct Service {
+    rpc: RpcClient,
+    env: ArciumEnv,
+    public_url: String,
+    label: String,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let service = Arc::new(Service {
+        rpc: RpcClient::new(cli.url),
+        env: ArciumEnv::new(cli.cluster_offset),
+        public_url: cli.public_url.trim_end_matches('/').to_string(),
+        label: cli.label,
+    });
+    let server = tiny_http::Server::http(&cli.listen).map_err(|e| anyhow!("binding {}: {e}", cli.listen))?;
+    eprintln!(
+        "serving transaction requests on {} as {}",
+        cli.listen, service.public_url
+    );
+
+    for request in server.incoming_requests() {
+        let service = service.clone();
+        thread::spawn(move || service.handle(request));
+    }
+    Ok(())
+}
+
+type Response = tiny_http::Response<std::io::Cursor<Vec<u8>>>;
+
+impl Service {
+    fn handle(&self, mut request: tiny_http::Request) {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let response = match (request.method(), route(path)) {
+            // CORS preflight: wallets may fetch from a web view.
+            (tiny_http::Method::Options, _) => tiny_http::Response::from_data(Vec::new()).with_status_code(204),
+            (tiny_http::Method::Get, Some(Route::Icon)) => {
+                tiny_http::Response::from_string(ICON).with_header(header("Content-Type", "image/svg+xml"))
+            }
+            (tiny_http::Method::Get, Some(Route::Link(address))) => match vote::parse_votes(query) {
+                Ok(allocation) => tiny_http::Response::from_string(self.link(&address, &allocation.votes)),
+                Err(e) => tiny_http::Response::from_string(e).with_status_code(400),
+            },
+            (tiny_http::Method::Get, Some(Route::Vote(_))) => json_response(
+                200,
+                json!({ "label": self.label, "icon": format!("{}/icon.svg", self.public_url) }),
+            ),
+            (tiny_http::Method::Post, Some(Route::Vote(address))) => {
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                match self.transaction(&address, query, &body) {
+                    Ok(response) => json_response(200, response),
+                    Err(e) => json_response(400, json!({ "message": format!("{e:#}") })),
+                }
+            }
+            _ => tiny_http::Response::from_string("not found").with_status_code(404),
+        };
+        let response = response
+            .with_header(header("Access-Control-Allow-Origin", "*"))
+            .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"))
+            .with_header(header("Access-Control-Allow-Headers", "Content-Type"));
+        let _ = request.respond(response);
+    }
+
+    /// The Solana Pay link casting `votes` on the proposal at `address`.
+    fn link(&self, address: &Pubkey, votes: &[u64; 4]) -> String {
+        let votes = votes.map(|v| v.to_string()).join(",");
+        let request = format!("{}/vote/{address}?votes={votes}", self.public_url);
+        format!("solana:{}", utf8_percent_encode(&request, NON_ALPHANUMERIC))
+    }
+
+    /// Answer a wallet's `POST`: the signer-to-be is its `account`.
+    fn transaction(&self, address: &Pubkey, query: &str, body: &str) -> Result<Value> {
+        let allocation = vote::parse_votes(query).map_err(|e| anyhow!(e))?;
+        let account: Value = serde_json::from_str(body).map_err(|_| anyhow!("expected {{\"account\": <pubkey>}}"))?;
+        let voter: Pubkey = account["account"]
+            .as_str()
+            .ok_or_else(|| anyhow!("expected {{\"account\": <pubkey>}}"))?
+            .parse()?;
+        let (tx, message) = vote::cast(&self.rpc, &self.env, address, &allocation, &voter)?;
+        let tx = bincode::serialize(&tx).expect("transactions serialize");
+        Ok(json!({ "transaction": STANDARD.encode(tx), "message": message }))
+    }
+}
+
+enum Route {
+    Icon,
+    /// `/link/<proposal>`: the Solana Pay link for a ballot.
+    Link(Pubkey),
+    /// `/vote/<proposal>`: the transaction request itself.
+    Vote(Pubkey),
+}
+
+fn route(path: &str) -> Option<Route> {
+    if path == "/icon.svg" {
+        return Some(Route::Icon);
+    }
+    let (kind, address) = path.trim_matches('/').split_once('/')?;
+    let address = address.parse().ok()?;
+    match kind {
+        "link" => Some(Route::Link(address)),
+        "vote" => Some(Route::Vote(address)),
+        _ => None,
+    }
+}
+
+fn header(name: &str, value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(name, value).expect("static header is valid")
+}
+
+fn json_response(status: u16, body: Value) -> Response {
+    tiny_http::Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+}
@@ -0,0 +1,126 @@
+//! The ballot a link asks for, and the transaction that casts it.
+
+use anyhow::{anyhow, bail, Context, Result};
+use arcvote_client::{
+    accounts::{decode_mxe_public_key, decode_proposal, GatingMode},
+    encryption::{encrypt_vote, EncryptionKeypair, VoteAllocation},
+    instructions::{self, BallotKind},
+    pda,
+    rpc::{dry_run_vote, fetch_mxe_lookup_table},
+    transaction::{UnsignedTransaction, VersionedTransaction},
+    ArciumEnv, Pubkey,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+
+/// The `votes` query parameter: up to four comma-separated vote counts, one
+/// per option, missing ones zero.
+pub fn parse_votes(query: &str) -> Result<VoteAllocation, String> {
+    let votes = form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "votes")
+        .map(|(_, value)| value)
+        .ok_or("missing ?votes=")?;
+    let votes: Vec<u64> = votes
+        .split(',')
+        .map(|count| count.trim().parse())
+        .collect::<Result<_, _>>()
+        .ok()
+        .filter(|votes: &Vec<u64>| votes.len() <= 4)
+        .ok_or_else(|| format!("votes must be up to four counts, like 3,1,0,0; got {votes:?}"))?;
+    let mut padded = [0; 4];
+    padded[..votes.len()].copy_from_slice(&votes);
+    Ok(VoteAllocation::new(padded))
+}
+
+/// A transaction casting `allocation` on the proposal at `address`, paid
+/// and signed by `voter` alone, and a line for the wallet to show with it.
+///
+/// Only ballots `cast_vote` takes as they stand are built: quadratic and
+/// time-weighted proposals, open, allowlisted or history-gated.  Anything
+/// the program would refuse — a closed proposal, a second vote, a ballot
+/// over budget — is refused here, by the same dry run the CLI does, so the
+/// wallet shows the reason instead of a failed transaction.
+pub fn cast(
+    rpc: &RpcClient,
+    env: &ArciumEnv,
+    address: &Pubkey,
+    allocation: &VoteAllocation,
+    voter: &Pubkey,
+) -> Result<(VersionedTransaction, String)> {
+    let data = rpc.get_account_data(address).context("failed to fetch proposal")?;
+    let proposal = decode_proposal(&data).context("not an ArcVote proposal")?;
+    let cast_ix = match proposal.ballot {
+        BallotKind::Quadratic | BallotKind::TimeWeighted { .. } if proposal.spend_histogram => {
+            instructions::cast_vote_histogram
+        }
+        BallotKind::Quadratic | BallotKind::TimeWeighted { .. } => instructions::cast_vote,
+        _ => bail!("this proposal doesn't take a quadratic ballot; vote with the arcvote CLI"),
+    };
+    if proposal.burn_mint.is_some() || proposal.eligibility_oracle.is_some() || proposal.weight_mint.is_some() {
+        bail!("this proposal burns a token, asks an eligibility oracle or weighs balances; vote with the arcvote CLI");
+    }
+    let serial = match proposal.gating {
+        GatingMode::Open | GatingMode::Allowlist | GatingMode::Token => None,
+        GatingMode::History => Some(voter.to_bytes()),
+        GatingMode::Credential => bail!("this proposal is credential-gated; vote with the arcvote CLI"),
+        GatingMode::Collection => bail!("this proposal is NFT-gated; vote with the arcvote CLI"),
+    };
+
+    let mxe_data = rpc
+        .get_account_data(&pda::mxe_account())
+        .context("failed to fetch MXE account")?;
+    let mxe_public_key =
+        decode_mxe_public_key(&mxe_data)?.ok_or_else(|| anyhow!("MXE keygen has not completed yet"))?;
+    // The wallet can't derive the voter's own key without signing a second
+    // message, so each ballot gets a fresh one.
+    let keypair = EncryptionKeypair::from_secret(rand::random());
+    let vote = encrypt_vote(&keypair, &mxe_public_key, allocation, rand::random());
+
+    // Supply quorums weigh, and token gates check, the voter's holding.
+    let token_account = match instructions::voter_token_mint(&proposal) {
+        Some(mint) => {
+            let owner = rpc.get_account(&mint).context("failed to fetch quorum or gate mint")?.owner;
+            Some(instructions::quorum_token_account(voter, &mint, &owner))
+        }
+        None => None,
+    };
+
+    let authority = proposal.authority;
+    let computation_offset = rand::random();
+    let ix = match serial {
+        Some(serial) => instructions::cast_vote_with_credential(
+            env,
+            voter,
+            &authority,
+            proposal.id,
+            computation_offset,
+            &vote,
+            token_account.as_ref(),
+            &serial,
+        ),
+        None => cast_ix(
+            env,
+            voter,
+            &authority,
+            proposal.id,
+            computation_offset,
+            &vote,
+            token_account.as_ref(),
+        ),
+    };
+    // Credits registered once for the whole ballot.
+    let ix = match proposal.on_ballot {
+        Some(ballot) if proposal.credit_mint.is_some() => {
+            instructions::with_ballot_credits(ix, address, &ballot, voter)
+        }
+        _ => ix,
+    };
+    let (cost, _) = dry_run_vote(rpc, voter, &authority, proposal.id, allocation, &ix)?;
+
+    let lookup_table = fetch_mxe_lookup_table(rpc).context("failed to fetch MXE lookup table")?;
+    let tx = UnsignedTransaction::new(voter, &[ix], &[lookup_table], rpc.get_latest_blockhash()?)?;
+    let message = format!(
+        "Vote {:?} on #{} {} ({cost} voice credits)",
+        allocation.votes, proposal.id, proposal.title
+    );
+    Ok((tx.to_unsigned(), message))
+}
@@ -0,0 +1,276 @@
+//! `arcvote-relay` — forward ArcVote program events to HTTP endpoints and
+//! Slack, for teams watching elections.
+//!
+//! Transactions arrive from either source, or both:
+//!
+//! - `--ws-url`: `logsSubscribe` for transactions mentioning the program, on
+//!   a validator's PubSub endpoint or a provider's Geyser-fed one.  Events
+//!   landing while the subscription reconnects are missed.
+//! - `--webhook-listen`: Helius-style webhooks.  A "raw" webhook on the
+//!   program address posts to `/webhook` an array of transactions as
+//!   `getTransaction` returns them, and retries until it gets a 200.
+//!
+//! Each successful transaction's logs are decoded into `arcvote-types`
+//! events, filtered by `--event` if given, and relayed as
+//!
+//! ```json
+//! {"signature":"5Kq…","slot":301234567,"event":{"type":"VoteCastEvent","proposal":"7xK…",…}}
+//! ```
+//!
+//! to every `--forward` URL, with a one-line summary to every `--slack`
+//! incoming webhook.  A transaction both sources deliver, or a webhook
+//! retried, is relayed once.
+
+mod sink;
+
+use std::{
+    collections::{HashSet, VecDeque},
+    net::SocketAddr,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use arcvote_types::Event;
+use clap::Parser;
+use serde::Serialize;
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+
+use sink::Sinks;
+
+/// Signatures remembered to relay each transaction once.
+const SEEN_CAPACITY: usize = 10_000;
+
+#[derive(Parser)]
+#[command(
+    name = "arcvote-relay",
+    version,
+    about = "Relay ArcVote events to HTTP endpoints and Slack"
+)]
+struct Cli {
+    /// Solana PubSub (WebSocket) endpoint to follow the program's logs on.
+    #[arg(long, env = "ARCVOTE_WS_URL", required_unless_present = "webhook_listen")]
+    ws_url: Option<String>,
+    /// Accept Helius-style webhooks on this address, at `/webhook`.
+    #[arg(long, env = "ARCVOTE_RELAY_LISTEN")]
+    webhook_listen: Option<SocketAddr>,
+    /// `Authorization` header webhook requests must carry.
+    #[arg(long, env = "ARCVOTE_RELAY_WEBHOOK_AUTH", requires = "webhook_listen")]
+    webhook_auth: Option<String>,
+    /// URL to POST each event to as JSON; repeat for several.
+    #[arg(long = "forward", env = "ARCVOTE_RELAY_FORWARD", value_delimiter = ',')]
+    forwards: Vec<String>,
+    /// Slack incoming-webhook URL to post each event to; repeat for several.
+    #[arg(long = "slack", env = "ARCVOTE_RELAY_SLACK", value_delimiter = ',')]
+    slack: Vec<String>,
+    /// Relay only these event types, e.g. `ResultsRevealedEvent`; repeat
+    /// for several.
+    #[arg(long = "event", value_delimiter = ',')]
+    events: Vec<String>,
+    /// ArcVote program id.
+    #[arg(long, default_value_t = arcvote_types::ID.into())]
+    program_id: Pubkey,
+}
+
+/// A successful transaction that touched the program.
+struct Transaction {
+    signature: String,
+    slot: u64,
+    logs: Vec<String>,
+}
+
+/// An event as relayed, with the transaction that emitted it.
+#[derive(Serialize)]
+pub struct Relayed {
+    pub signature: String,
+    pub slot: u64,
+    pub event: Event,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if cli.forwards.is_empty() && cli.slack.is_empty() {
+        bail!("nothing to relay to; pass --forward or --slack");
+    }
+    let sinks = Sinks::new(&cli.forwards, &cli.slack)?;
+
+    let (sender, transactions) = mpsc::channel();
+    if let Some(ws_url) = cli.ws_url.clone() {
+        let sender = sender.clone();
+        let program_id = cli.program_id;
+        thread::spawn(move || loop {
+            if let Err(e) = follow(&ws_url, &program_id, &sender) {
+                eprintln!("subscription dropped: {e:#}");
+            }
+            thread::sleep(Duration::from_secs(5));
+        });
+    }
+    if let Some(addr) = cli.webhook_listen {
+        let server = tiny_http::Server::http(addr).map_err(|e| anyhow!("binding {addr}: {e}"))?;
+        eprintln!("accepting webhooks on http://{addr}/webhook");
+        let sender = sender.clone();
+        let auth = cli.webhook_auth.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                receive(request, auth.as_deref(), &sender);
+            }
+        });
+    }
+    drop(sender);
+
+    let mut seen = Seen::default();
+    for transaction in transactions {
+        if !seen.insert(&transaction.signature) {
+            continue;
+        }
+        for event in arcvote_types::decode_logs(&cli.program_id, &transaction.logs) {
+            let relayed = Relayed {
+                signature: transaction.signature.clone(),
+                slot: transaction.slot,
+                event,
+            };
+            if cli.events.is_empty() || cli.events.contains(&event_type(&relayed.event)) {
+                sinks.deliver(&relayed);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The event's type name, e.g. `VoteCastEvent`.
+pub fn event_type(event: &Event) -> String {
+    let value = serde_json::to_value(event).expect("events serialize");
+    value["type"].as_str().unwrap_or_default().to_string()
+}
+
+/// Pass every successful transaction mentioning the program to `sender`
+/// until the subscription drops.
+fn follow(ws_url: &str, program_id: &Pubkey, sender: &mpsc::Sender<Transaction>) -> Result<()> {
+    let (mut socket, _) = tungstenite::connect(ws_url).with_context(|| format!("connecting to {ws_url}"))?;
+    let subscribe = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "logsSubscribe",
+        "params": [{ "mentions": [program_id.to_string()] }, { "commitment": "confirmed" }],
+    });
+    socket.send(tungstenite::Message::text(subscribe.to_string()))?;
+
+    loop {
+        let tungstenite::Message::Text(text) = socket.read()? else {
+            continue;
+        };
+        let message: Value = serde_json::from_str(&text)?;
+        if let Some(error) = message.get("error") {
+            bail!("logsSubscribe refused: {error}");
+        }
+        if message["method"] != "logsNotification" {
+            continue;
+        }
+        let result = &message["params"]["result"];
+        let value = &result["value"];
+        if !value["err"].is_null() {
+            continue;
+        }
+        let signature = value["signature"]
+            .as_str()
+            .ok_or_else(|| anyhow!("notification without signature"))?;
+        sender.send(Transaction {
+            signature: signature.to_string(),
+            slot: result["context"]["slot"].as_u64().unwrap_or_default(),
+            logs: strings(&value["logs"]),
+        })?;
+    }
+}
+
+/// Answer one webhook request, passing its transactions to `sender`.
+fn receive(mut request: tiny_http::Request, auth: Option<&str>, sender: &mpsc::Sender<Transaction>) {
+    let authorized = auth.is_none_or(|auth| {
+        request
+            .headers()
+            .iter()
+            .any(|header| header.field.equiv("Authorization") && header.value.as_str() == auth)
+    });
+    let status = if *request.method() != tiny_http::Method::Post || request.url() != "/webhook" {
+        404
+    } else if !authorized {
+        401
+    } else {
+        let mut body = String::new();
+        let parsed = request
+            .as_reader()
+            .read_to_string(&mut body)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| webhook_transactions(&body));
+        match parsed {
+            Ok(transactions) => {
+                for transaction in transactions {
+                    let _ = sender.send(transaction);
+                }
+                200
+            }
+            Err(e) => {
+                eprintln!("rejected webhook: {e:#}");
+                400
+            }
+        }
+    };
+    let _ = request.respond(tiny_http::Response::empty(status));
+}
+
+/// The successful transactions of a webhook body: an array in
+/// `getTransaction` form.
+fn webhook_transactions(body: &str) -> Result<Vec<Transaction>> {
+    let payload: Value = serde_json::from_str(body)?;
+    let items = payload
+        .as_array()
+        .ok_or_else(|| anyhow!("expected an array of transactions"))?;
+    items
+        .iter()
+        .filter(|item| item["meta"]["err"].is_null())
+        .map(|item| {
+            Ok(Transaction {
+                signature: item["transaction"]["signatures"][0]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("transaction without signature"))?
+                    .to_string(),
+                slot: item["slot"]
+                    .as_u64()
+                    .ok_or_else(|| anyhow!("transaction without slot"))?,
+                logs: strings(&item["meta"]["logMessages"]),
+            })
+        })
+        .collect()
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|line| line.as_str().map(str::to_string))
+        .collect()
+}
+
+/// The most recent signatures relayed, oldest forgotten first.
+#[derive(Default)]
+struct Seen {
+    order: VecDeque<String>,
+    set: HashSet<String>,
+}
+
+impl Seen {
+    /// Remember `signature`; false if it was already remembered.
+    fn insert(&mut self, signature: &str) -> bool {
+        if !self.set.insert(signature.to_string()) {
+            return false;
+        }
+        self.order.push_back(signature.to_string());
+        if self.order.len() > SEEN_CAPACITY {
+            let oldest = self.order.pop_front().expect("just pushed");
+            self.set.remove(&oldest);
+        }
+        true
+    }
+}
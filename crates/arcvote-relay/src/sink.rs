@@ -0,0 +1,81 @@
+//! Where events go: JSON endpoints and Slack incoming webhooks.
+
+use std::{fmt::Write, thread, time::Duration};
+
+use anyhow::{Context, Result};
+use reqwest::{blocking::Client, Url};
+use serde_json::{json, Value};
+
+use crate::{event_type, Relayed};
+
+/// Tries per delivery before giving up on it.
+const ATTEMPTS: u32 = 3;
+
+pub struct Sinks {
+    client: Client,
+    forwards: Vec<Url>,
+    slack: Vec<Url>,
+}
+
+impl Sinks {
+    pub fn new(forwards: &[String], slack: &[String]) -> Result<Self> {
+        let parse = |url: &String| Url::parse(url).with_context(|| format!("invalid URL {url:?}"));
+        Ok(Self {
+            client: Client::builder().timeout(Duration::from_secs(10)).build()?,
+            forwards: forwards.iter().map(parse).collect::<Result<_>>()?,
+            slack: slack.iter().map(parse).collect::<Result<_>>()?,
+        })
+    }
+
+    /// Deliver `relayed` everywhere, in turn.  A sink that still fails
+    /// after retrying is logged and skipped, so it doesn't hold up the
+    /// next event for long.
+    pub fn deliver(&self, relayed: &Relayed) {
+        let body = serde_json::to_value(relayed).expect("events serialize");
+        for url in &self.forwards {
+            self.post(url, &body);
+        }
+        if !self.slack.is_empty() {
+            let message = json!({ "text": summary(relayed, &body["event"]) });
+            for url in &self.slack {
+                self.post(url, &message);
+            }
+        }
+    }
+
+    fn post(&self, url: &Url, body: &Value) {
+        let mut delay = Duration::from_secs(1);
+        for attempt in 1..=ATTEMPTS {
+            let sent = self.client.post(url.clone()).json(body).send();
+            match sent.and_then(|response| response.error_for_status()) {
+                Ok(_) => return,
+                // Slack webhook URLs are secrets; log the host only.
+                Err(e) if attempt == ATTEMPTS => eprintln!(
+                    "giving up on {}: {}",
+                    url.host_str().unwrap_or_default(),
+                    e.without_url()
+                ),
+                Err(_) => {
+                    thread::sleep(delay);
+                    delay *= 2;
+                }
+            }
+        }
+    }
+}
+
+/// One line for chat, e.g. "*VoteCastEvent* on proposal `7xK…` (slot
+/// 301234567, tx `5Kq…`)", then the event's other fields.
+fn summary(relayed: &Relayed, event: &Value) -> String {
+    let mut text = format!("*{}*", event_type(&relayed.event));
+    let mut fields = event.as_object().cloned().unwrap_or_default();
+    fields.remove("type");
+    if let Some(Value::String(proposal)) = fields.remove("proposal") {
+        write!(text, " on proposal `{proposal}`").unwrap();
+    }
+    write!(text, " (slot {}, tx `{}`)", relayed.slot, relayed.signature).unwrap();
+    if !fields.is_empty() {
+        write!(text, "\n```{}```", Value::Object(fields)).unwrap();
+    }
+    text
+}
@@ -0,0 +1,192 @@
+//! The proposals the service knows about and the clients it streams them to.
+//!
+//! Updates arrive from two places: the account subscription, whenever a
+//! proposal account changes, and the clock, whose ticks move proposals from
+//! `Registration` to `Voting` to `VotingClosed` without touching their
+//! accounts.  Either way, a client hears a `proposal` message with the new
+//! view and, when the lifecycle state changed, a `lifecycle` message first.
+
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Mutex},
+};
+
+use arcvote_client::{
+    accounts::{ProposalAccount, ProposalState},
+    Pubkey,
+};
+use serde::Serialize;
+
+/// A message sent to clients, as one JSON text frame.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// A proposal's current state and turnout: on connect, whenever its
+    /// account changes, and whenever its state does.
+    Proposal(ProposalView),
+    /// A proposal moved between lifecycle states.
+    Lifecycle {
+        address: String,
+        id: u32,
+        from: &'static str,
+        to: &'static str,
+        /// Cluster time the move was seen at.
+        at: i64,
+    },
+}
+
+#[derive(Serialize)]
+pub struct ProposalView {
+    pub address: String,
+    pub id: u32,
+    pub authority: String,
+    pub title: String,
+    pub state: &'static str,
+    pub deadline: i64,
+    /// Seconds until the deadline (0 once it has passed).
+    pub time_remaining: i64,
+    pub voter_count: u32,
+    pub turnout: u64,
+    pub quorum_target: u64,
+    pub quorum_reached: bool,
+    pub quorum_progress_bps: u16,
+}
+
+impl ProposalView {
+    fn new(address: &Pubkey, proposal: &ProposalAccount, now: i64) -> Self {
+        let status = proposal.status(now);
+        Self {
+            address: address.to_string(),
+            id: proposal.id,
+            authority: proposal.authority.to_string(),
+            title: proposal.title.clone(),
+            state: state_label(status.state),
+            deadline: proposal.deadline,
+            time_remaining: status.time_remaining,
+            voter_count: status.voter_count,
+            turnout: status.turnout,
+            quorum_target: status.quorum_target,
+            quorum_reached: status.quorum_reached,
+            quorum_progress_bps: status.quorum_progress_bps,
+        }
+    }
+}
+
+/// A connected client: the proposal it follows, if only one, and where its
+/// messages go.
+struct Client {
+    filter: Option<Pubkey>,
+    tx: mpsc::Sender<String>,
+}
+
+#[derive(Default)]
+struct State {
+    proposals: HashMap<Pubkey, (ProposalAccount, ProposalState)>,
+    clients: Vec<Client>,
+    /// Cluster time of the last tick.
+    now: i64,
+}
+
+#[derive(Default)]
+pub struct Hub {
+    state: Mutex<State>,
+}
+
+impl Hub {
+    /// Record the current account of the proposal at `address`.
+    pub fn update(&self, address: Pubkey, proposal: ProposalAccount) {
+        let mut state = self.state.lock().unwrap();
+        let now = state.now;
+        let current = proposal.state(now);
+        let mut messages = Vec::new();
+        if let Some((_, from)) = state.proposals.get(&address).filter(|(_, from)| *from != current) {
+            messages.push(lifecycle(&address, proposal.id, *from, current, now));
+        }
+        messages.push(Message::Proposal(ProposalView::new(&address, &proposal, now)));
+        state.proposals.insert(address, (proposal, current));
+        state.broadcast(&address, &messages);
+    }
+
+    /// Move the clock to `now` and announce every proposal whose state it
+    /// changed.
+    pub fn tick(&self, now: i64) {
+        let mut state = self.state.lock().unwrap();
+        state.now = now;
+        let mut moved = Vec::new();
+        for (address, (proposal, seen)) in state.proposals.iter_mut() {
+            let current = proposal.state(now);
+            if current != *seen {
+                moved.push((
+                    *address,
+                    vec![
+                        lifecycle(address, proposal.id, *seen, current, now),
+                        Message::Proposal(ProposalView::new(address, proposal, now)),
+                    ],
+                ));
+                *seen = current;
+            }
+        }
+        for (address, messages) in moved {
+            state.broadcast(&address, &messages);
+        }
+    }
+
+    /// Add a client following `filter`, or every proposal, and return the
+    /// snapshot to send it before anything from `rx`.
+    pub fn connect(&self, filter: Option<Pubkey>) -> (Vec<String>, mpsc::Receiver<String>) {
+        let mut state = self.state.lock().unwrap();
+        let now = state.now;
+        let mut snapshot: Vec<_> = state
+            .proposals
+            .iter()
+            .filter(|(address, _)| filter.is_none_or(|f| f == **address))
+            .map(|(address, (proposal, _))| ProposalView::new(address, proposal, now))
+            .collect();
+        snapshot.sort_by_key(|view| view.id);
+        let snapshot = snapshot
+            .into_iter()
+            .map(|view| encode(&Message::Proposal(view)))
+            .collect();
+        let (tx, rx) = mpsc::channel();
+        state.clients.push(Client { filter, tx });
+        (snapshot, rx)
+    }
+}
+
+impl State {
+    /// Send `messages` about the proposal at `address` to every client
+    /// following it, dropping clients that went away.
+    fn broadcast(&mut self, address: &Pubkey, messages: &[Message]) {
+        let messages: Vec<String> = messages.iter().map(encode).collect();
+        self.clients.retain(|client| {
+            if client.filter.is_some_and(|f| f != *address) {
+                return true;
+            }
+            messages.iter().all(|m| client.tx.send(m.clone()).is_ok())
+        });
+    }
+}
+
+fn lifecycle(address: &Pubkey, id: u32, from: ProposalState, to: ProposalState, at: i64) -> Message {
+    Message::Lifecycle {
+        address: address.to_string(),
+        id,
+        from: state_label(from),
+        to: state_label(to),
+        at,
+    }
+}
+
+fn encode(message: &Message) -> String {
+    serde_json::to_string(message).expect("messages serialize")
+}
+
+fn state_label(state: ProposalState) -> &'static str {
+    match state {
+        ProposalState::Registration => "registration",
+        ProposalState::Voting => "voting",
+        ProposalState::VotingClosed => "voting_closed",
+        ProposalState::Finalized => "finalized",
+        ProposalState::QuorumFailed => "quorum_failed",
+    }
+}
@@ -0,0 +1,211 @@
+//! `arcvote-stream` — stream live ArcVote proposal state over WebSocket.
+//!
+//! The service subscribes to the program's proposal accounts
+//! (`programSubscribe` on the validator's PubSub endpoint) and keeps the
+//! latest view of each.  A client that connects to `/` follows every
+//! proposal; one that connects to `/proposals/<address>` follows only that
+//! one.  Either way it first receives a `proposal` message per proposal
+//! followed, then:
+//!
+//! - a `proposal` message whenever a proposal account changes — a ballot
+//!   landed, turnout moved, a reveal finalized it — carrying its state,
+//!   voter count, turnout and quorum progress,
+//! - a `lifecycle` message whenever a proposal moves between states, whether
+//!   an instruction moved it or the clock did: registration and voting open
+//!   and close by time alone, so the service re-evaluates every proposal
+//!   against cluster time each tick.
+//!
+//! Messages are JSON text frames tagged by `type`.  When the subscription
+//! drops the service reconnects and reloads every proposal, so clients see
+//! any change they missed as a fresh `proposal` message.
+
+mod hub;
+
+use std::{
+    cell::Cell,
+    net::{SocketAddr, TcpListener, TcpStream},
+    rc::Rc,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use anchor_lang::Discriminator;
+use anyhow::{anyhow, bail, Context, Result};
+use arcvote_client::{
+    accounts::{decode_proposal, ProposalAccount},
+    rpc::fetch_all_proposals,
+    Pubkey, PROGRAM_ID,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Parser;
+use serde_json::{json, Value};
+use solana_rpc_client::rpc_client::RpcClient;
+use tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::StatusCode,
+};
+
+use hub::Hub;
+
+#[derive(Parser)]
+#[command(
+    name = "arcvote-stream",
+    version,
+    about = "Stream live ArcVote proposal state over WebSocket"
+)]
+struct Cli {
+    /// Solana JSON-RPC endpoint.
+    #[arg(long, short = 'u', env = "ARCVOTE_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Solana PubSub (WebSocket) endpoint.
+    #[arg(long, env = "ARCVOTE_WS_URL", default_value = "ws://127.0.0.1:8900")]
+    ws_url: String,
+    /// Address clients connect to.
+    #[arg(long, env = "ARCVOTE_STREAM_LISTEN", default_value = "127.0.0.1:8910")]
+    listen: SocketAddr,
+    /// Seconds between re-evaluating proposals against cluster time.
+    #[arg(long, default_value_t = 1)]
+    tick_interval: u64,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let hub = Arc::new(Hub::default());
+    let rpc = RpcClient::new(cli.url.clone());
+    hub.tick(cluster_time(&rpc)?);
+
+    let listener = TcpListener::bind(cli.listen).with_context(|| format!("binding {}", cli.listen))?;
+    eprintln!("streaming proposals on ws://{}", cli.listen);
+
+    {
+        let hub = hub.clone();
+        let rpc = RpcClient::new(cli.url.clone());
+        let interval = Duration::from_secs(cli.tick_interval.max(1));
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            match cluster_time(&rpc) {
+                Ok(now) => hub.tick(now),
+                Err(e) => eprintln!("tick failed: {e:#}"),
+            }
+        });
+    }
+    {
+        let hub = hub.clone();
+        thread::spawn(move || loop {
+            if let Err(e) = follow(&cli.ws_url, &rpc, &hub) {
+                eprintln!("subscription dropped: {e:#}");
+            }
+            thread::sleep(Duration::from_secs(5));
+        });
+    }
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let hub = hub.clone();
+        thread::spawn(move || {
+            if let Err(e) = serve(stream, &hub) {
+                eprintln!("client: {e:#}");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn cluster_time(rpc: &RpcClient) -> Result<i64> {
+    Ok(rpc.get_block_time(rpc.get_slot()?)?)
+}
+
+/// Subscribe to proposal accounts, load every proposal, then feed account
+/// notifications to `hub` until the subscription drops.  Loading after
+/// subscribing means no change falls between the two.
+fn follow(ws_url: &str, rpc: &RpcClient, hub: &Hub) -> Result<()> {
+    let (mut socket, _) = tungstenite::connect(ws_url).with_context(|| format!("connecting to {ws_url}"))?;
+    let subscribe = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "programSubscribe",
+        "params": [
+            PROGRAM_ID.to_string(),
+            {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "filters": [{
+                    "memcmp": {
+                        "offset": 0,
+                        "bytes": STANDARD.encode(ProposalAccount::DISCRIMINATOR),
+                        "encoding": "base64",
+                    },
+                }],
+            },
+        ],
+    });
+    socket.send(tungstenite::Message::text(subscribe.to_string()))?;
+
+    for (address, proposal) in fetch_all_proposals(rpc)? {
+        hub.update(address, proposal);
+    }
+
+    loop {
+        let tungstenite::Message::Text(text) = socket.read()? else {
+            continue;
+        };
+        let message: Value = serde_json::from_str(&text)?;
+        if let Some(error) = message.get("error") {
+            bail!("programSubscribe refused: {error}");
+        }
+        if message["method"] != "programNotification" {
+            continue;
+        }
+        let value = &message["params"]["result"]["value"];
+        let address: Pubkey = value["pubkey"]
+            .as_str()
+            .ok_or_else(|| anyhow!("notification without pubkey"))?
+            .parse()?;
+        let data = value["account"]["data"][0]
+            .as_str()
+            .ok_or_else(|| anyhow!("notification without base64 data"))?;
+        // Closed accounts and older layouts don't decode; skip them.
+        if let Ok(proposal) = decode_proposal(&STANDARD.decode(data)?) {
+            hub.update(address, proposal);
+        }
+    }
+}
+
+/// Complete the handshake with a client, send it the snapshot for the path
+/// it asked for, then forward messages until it goes away.
+fn serve(stream: TcpStream, hub: &Hub) -> Result<()> {
+    let filter = Rc::new(Cell::new(None));
+    let requested = filter.clone();
+    let mut socket = tungstenite::accept_hdr(stream, move |request: &Request, response: Response| {
+        match parse_path(request.uri().path()) {
+            Ok(f) => {
+                requested.set(f);
+                Ok(response)
+            }
+            Err(e) => {
+                let mut error = ErrorResponse::new(Some(e));
+                *error.status_mut() = StatusCode::NOT_FOUND;
+                Err(error)
+            }
+        }
+    })
+    .map_err(|e| anyhow!("handshake failed: {e}"))?;
+    let (snapshot, rx) = hub.connect(filter.get());
+    for message in snapshot.into_iter().chain(rx) {
+        socket.send(tungstenite::Message::text(message))?;
+    }
+    Ok(())
+}
+
+/// `/` for every proposal, `/proposals/<address>` for one.
+fn parse_path(path: &str) -> Result<Option<Pubkey>, String> {
+    match path.trim_end_matches('/') {
+        "" => Ok(None),
+        path => path
+            .strip_prefix("/proposals/")
+            .and_then(|address| address.parse().ok())
+            .map(Some)
+            .ok_or_else(|| format!("no stream at {path}")),
+    }
+}
@@ -0,0 +1,287 @@
+//! Generate a Rust type for every ArcVote account and event, and for every
+//! type they contain, from the program IDL (`private_voting.json`).
+//!
+//! Structs and enums mirror the IDL field for field, so their Borsh encoding
+//! is the program's.  Public keys become `Pubkey` and byte arrays `Bytes<N>`,
+//! which serialize as base58 and hex; 128-bit integers serialize as decimal
+//! strings.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    fmt::Write,
+    fs,
+    path::Path,
+};
+
+use serde_json::Value;
+
+const IDL: &str = "private_voting.json";
+
+/// Accounts the IDL lists because instructions take them, but which belong
+/// to the Arcium program; `arcium-client` decodes those.
+const FOREIGN_ACCOUNTS: [&str; 6] = [
+    "ArciumSignerAccount",
+    "ClockAccount",
+    "Cluster",
+    "ComputationDefinitionAccount",
+    "FeePool",
+    "MXEAccount",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed={IDL}");
+    let idl: Value = serde_json::from_str(&fs::read_to_string(IDL).expect("read the IDL")).expect("parse the IDL");
+
+    let types: BTreeMap<&str, &Value> = idl["types"]
+        .as_array()
+        .expect("IDL types")
+        .iter()
+        .map(|ty| (ty["name"].as_str().expect("type name"), ty))
+        .collect();
+    let accounts = named(&idl["accounts"], |name| !FOREIGN_ACCOUNTS.contains(&name));
+    let events = named(&idl["events"], |_| true);
+
+    // Every type reachable from an account or event.
+    let mut wanted = BTreeSet::new();
+    let mut pending: Vec<&str> = accounts.iter().chain(&events).map(|(name, _)| *name).collect();
+    while let Some(name) = pending.pop() {
+        if wanted.insert(name) {
+            let ty = types.get(name).unwrap_or_else(|| panic!("IDL has no type {name}"));
+            assert!(ty.get("generics").is_none(), "generic IDL type {name} is not supported");
+            for field in fields(&ty["type"]) {
+                defined_in(&field["type"], &mut pending);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    writeln!(out, "// Generated by build.rs from {IDL}.  Do not edit.").unwrap();
+    let address = idl["address"].as_str().expect("IDL address");
+    writeln!(out, "\n/// Program id the IDL was built for.").unwrap();
+    writeln!(
+        out,
+        "pub const ID: Pubkey = Pubkey(anchor_lang::prelude::Pubkey::from_str_const({address:?}));"
+    )
+    .unwrap();
+    for name in &wanted {
+        type_def(&mut out, types[name], &types);
+    }
+    for (name, discriminator) in accounts.iter().chain(&events) {
+        writeln!(
+            out,
+            "\nimpl anchor_lang::Discriminator for {name} {{\n    const DISCRIMINATOR: &'static [u8] = &{discriminator};\n}}"
+        )
+        .unwrap();
+    }
+    union(
+        &mut out,
+        "Account",
+        "Any ArcVote account, tagged by its type name.",
+        &accounts,
+    );
+    union(
+        &mut out,
+        "Event",
+        "Any ArcVote event, tagged by its type name.",
+        &events,
+    );
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("private_voting.rs");
+    fs::write(path, out).expect("write the generated types");
+}
+
+/// `(name, discriminator)` of each entry of an IDL `accounts` or `events`
+/// list that `keep` accepts.
+fn named(list: &Value, keep: impl Fn(&str) -> bool) -> Vec<(&str, String)> {
+    list.as_array()
+        .expect("IDL list")
+        .iter()
+        .map(|entry| {
+            (
+                entry["name"].as_str().expect("name"),
+                entry["discriminator"].to_string(),
+            )
+        })
+        .filter(|(name, _)| keep(name))
+        .collect()
+}
+
+/// The named fields of a struct, or of every variant of an enum.
+fn fields(ty: &Value) -> Vec<&Value> {
+    fn all(fields: &Value) -> Vec<&Value> {
+        let fields = fields.as_array().map(Vec::as_slice).unwrap_or_default();
+        assert!(
+            fields.iter().all(|f| f.get("name").is_some()),
+            "tuple fields are not supported"
+        );
+        fields.iter().collect()
+    }
+    match ty["kind"].as_str() {
+        Some("struct") => all(&ty["fields"]),
+        Some("enum") => ty["variants"]
+            .as_array()
+            .expect("enum variants")
+            .iter()
+            .flat_map(|variant| all(&variant["fields"]))
+            .collect(),
+        kind => panic!("unsupported IDL type kind {kind:?}"),
+    }
+}
+
+/// Push the names of the defined types `ty` refers to onto `out`.
+fn defined_in<'a>(ty: &'a Value, out: &mut Vec<&'a str>) {
+    if let Some(defined) = ty.get("defined") {
+        out.push(defined["name"].as_str().expect("defined name"));
+    } else if let Some(inner) = ty.get("option").or_else(|| ty.get("vec")) {
+        defined_in(inner, out);
+    } else if let Some(array) = ty.get("array") {
+        defined_in(&array[0], out);
+    }
+}
+
+fn rust_type(ty: &Value) -> String {
+    if let Some(name) = ty.as_str() {
+        return match name {
+            "bool" | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128" => name.to_string(),
+            "string" => "String".to_string(),
+            "bytes" => "Vec<u8>".to_string(),
+            "pubkey" => "Pubkey".to_string(),
+            _ => panic!("unsupported IDL type {name}"),
+        };
+    }
+    if let Some(inner) = ty.get("option") {
+        return format!("Option<{}>", rust_type(inner));
+    }
+    if let Some(inner) = ty.get("vec") {
+        return format!("Vec<{}>", rust_type(inner));
+    }
+    if let Some(array) = ty.get("array") {
+        let len = array[1].as_u64().expect("array length");
+        if array[0] == "u8" {
+            return format!("Bytes<{len}>");
+        }
+        // serde implements arrays up to 32 elements.
+        assert!(len <= 32, "array of {len} {} is not supported", array[0]);
+        return format!("[{}; {len}]", rust_type(&array[0]));
+    }
+    if let Some(defined) = ty.get("defined") {
+        return defined["name"].as_str().expect("defined name").to_string();
+    }
+    panic!("unsupported IDL type {ty}")
+}
+
+/// Whether `ty` holds 128-bit integers directly, or in options, vectors or
+/// arrays, and so serializes through `decimal`.
+fn is_wide(ty: &Value) -> bool {
+    match ty.as_str() {
+        Some(name) => matches!(name, "u128" | "i128"),
+        None => ty
+            .get("option")
+            .or_else(|| ty.get("vec"))
+            .or_else(|| ty.get("array").map(|array| &array[0]))
+            .is_some_and(is_wide),
+    }
+}
+
+/// Whether every field of the struct or enum `ty` is `Copy`.
+fn is_copy(ty: &Value, types: &BTreeMap<&str, &Value>) -> bool {
+    fn field_is_copy(ty: &Value, types: &BTreeMap<&str, &Value>) -> bool {
+        if let Some(name) = ty.as_str() {
+            return !matches!(name, "string" | "bytes");
+        }
+        if let Some(inner) = ty.get("option") {
+            return field_is_copy(inner, types);
+        }
+        if let Some(array) = ty.get("array") {
+            return field_is_copy(&array[0], types);
+        }
+        if let Some(defined) = ty.get("defined") {
+            return is_copy(&types[defined["name"].as_str().expect("defined name")]["type"], types);
+        }
+        false
+    }
+    fields(ty).iter().all(|field| field_is_copy(&field["type"], types))
+}
+
+fn docs(out: &mut String, indent: &str, item: &Value) {
+    for line in item["docs"].as_array().into_iter().flatten() {
+        let line = line.as_str().expect("doc line");
+        if line.is_empty() {
+            writeln!(out, "{indent}///").unwrap();
+        } else {
+            writeln!(out, "{indent}/// {line}").unwrap();
+        }
+    }
+}
+
+fn named_fields(out: &mut String, indent: &str, fields: &Value, visibility: &str) {
+    for field in fields.as_array().into_iter().flatten() {
+        docs(out, indent, field);
+        let name = field["name"].as_str().expect("field name");
+        if is_wide(&field["type"]) {
+            writeln!(out, "{indent}#[serde(with = \"decimal\")]").unwrap();
+        }
+        writeln!(out, "{indent}{visibility}{name}: {},", rust_type(&field["type"])).unwrap();
+    }
+}
+
+fn type_def(out: &mut String, ty: &Value, types: &BTreeMap<&str, &Value>) {
+    let name = ty["name"].as_str().expect("type name");
+    writeln!(out).unwrap();
+    docs(out, "", ty);
+    let copy = if is_copy(&ty["type"], types) { "Copy, " } else { "" };
+    writeln!(
+        out,
+        "#[derive(Clone, {copy}Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, Serialize, Deserialize)]"
+    )
+    .unwrap();
+    let body = &ty["type"];
+    if body["kind"] == "struct" {
+        writeln!(out, "pub struct {name} {{").unwrap();
+        named_fields(out, "    ", &body["fields"], "pub ");
+    } else {
+        writeln!(out, "pub enum {name} {{").unwrap();
+        for variant in body["variants"].as_array().expect("enum variants") {
+            docs(out, "    ", variant);
+            let variant_name = variant["name"].as_str().expect("variant name");
+            if variant.get("fields").is_some() {
+                writeln!(out, "    {variant_name} {{").unwrap();
+                named_fields(out, "        ", &variant["fields"], "");
+                writeln!(out, "    }},").unwrap();
+            } else {
+                writeln!(out, "    {variant_name},").unwrap();
+            }
+        }
+    }
+    writeln!(out, "}}").unwrap();
+}
+
+/// An enum over `members` with a `decode` that dispatches on the
+/// discriminator.
+fn union(out: &mut String, name: &str, doc: &str, members: &[(&str, String)]) {
+    writeln!(out, "\n/// {doc}").unwrap();
+    writeln!(out, "#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]").unwrap();
+    writeln!(out, "#[allow(clippy::large_enum_variant)]").unwrap();
+    writeln!(out, "#[serde(tag = \"type\")]\npub enum {name} {{").unwrap();
+    for (member, _) in members {
+        writeln!(out, "    {member}({member}),").unwrap();
+    }
+    writeln!(out, "}}\n\nimpl {name} {{").unwrap();
+    writeln!(
+        out,
+        "    /// Decode by discriminator; `None` when `data` is none of these or\n    /// doesn't decode."
+    )
+    .unwrap();
+    writeln!(out, "    pub fn decode(data: &[u8]) -> Option<Self> {{").unwrap();
+    writeln!(out, "        let discriminator = data.get(..8)?;").unwrap();
+    for (member, _) in members {
+        writeln!(
+            out,
+            "        if discriminator == <{member} as anchor_lang::Discriminator>::DISCRIMINATOR {{\n            \
+             return decode(data).ok().map(Self::{member});\n        }}"
+        )
+        .unwrap();
+    }
+    writeln!(out, "        None\n    }}\n}}").unwrap();
+}
@@ -0,0 +1,290 @@
+//! ArcVote accounts and events as plain Rust types, for off-chain consumers
+//! that want to decode and serialize them without building the program.
+//!
+//! Every type is generated at build time from `private_voting.json`, the
+//! IDL `anchor build` writes to `target/idl/`, so the types change exactly
+//! when the program does.  Each derives Borsh (the on-chain encoding) and
+//! serde (for JSON and friends); `Pubkey` serializes as base58, `Bytes` as
+//! hex and 128-bit integers as decimal strings.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let data = Vec::new();
+//! let proposal: arcvote_types::ProposalAccount = arcvote_types::decode(&data)?;
+//! let json = serde_json::to_string(&proposal)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! After changing an account or event, refresh the IDL:
+//!
+//! ```text
+//! anchor build && cp target/idl/private_voting.json crates/arcvote-types/
+//! ```
+
+use std::{fmt, io, str::FromStr};
+
+use anchor_lang::{prelude::borsh, AnchorDeserialize, AnchorSerialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+include!(concat!(env!("OUT_DIR"), "/private_voting.rs"));
+
+/// Decode an account or event of type `T`: its 8-byte discriminator, then
+/// its Borsh body.  Bytes past the body (unused account space) are ignored.
+pub fn decode<T: Discriminator + AnchorDeserialize>(data: &[u8]) -> io::Result<T> {
+    match data.split_at_checked(T::DISCRIMINATOR.len()) {
+        Some((discriminator, mut body)) if discriminator == T::DISCRIMINATOR => T::deserialize(&mut body),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "discriminator mismatch")),
+    }
+}
+
+/// Every ArcVote event `program_id` emitted in one transaction's logs.
+///
+/// `emit!` writes `Program data: <base64>`: the event's discriminator, then
+/// its Borsh body.  Data lines are attributed to the program on top of the
+/// invocation stack, so another program's are never misread as ours.
+pub fn decode_logs(program_id: &anchor_lang::prelude::Pubkey, logs: &[String]) -> Vec<Event> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() == Some(&program_id.as_str()) {
+                if let Some(event) = STANDARD.decode(data).ok().and_then(|d| Event::decode(&d)) {
+                    events.push(event);
+                }
+            }
+        } else if let Some((id, status)) = rest.split_once(' ') {
+            if status.starts_with("invoke [") {
+                stack.push(id);
+            } else if status == "success" || status.starts_with("failed") {
+                stack.pop();
+            }
+        }
+    }
+
+    events
+}
+
+/// A public key.  Borsh-encoded as its 32 bytes, serialized as base58.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, AnchorSerialize, AnchorDeserialize)]
+pub struct Pubkey(pub anchor_lang::prelude::Pubkey);
+
+impl From<anchor_lang::prelude::Pubkey> for Pubkey {
+    fn from(key: anchor_lang::prelude::Pubkey) -> Self {
+        Self(key)
+    }
+}
+
+impl From<Pubkey> for anchor_lang::prelude::Pubkey {
+    fn from(key: Pubkey) -> Self {
+        key.0
+    }
+}
+
+impl fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Pubkey {
+    type Err = <anchor_lang::prelude::Pubkey as FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl Serialize for Pubkey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pubkey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <String as Deserialize>::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// A fixed-size byte string — a hash, ciphertext or signature.
+/// Borsh-encoded as its bytes, serialized as lowercase hex.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> AnchorSerialize for Bytes<N> {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.0)
+    }
+}
+
+impl<const N: usize> AnchorDeserialize for Bytes<N> {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; N];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl<const N: usize> fmt::Display for Bytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl<const N: usize> fmt::Debug for Bytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<const N: usize> FromStr for Bytes<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 2 * N {
+            return Err(format!("expected {} hex digits, got {}", 2 * N, s.len()));
+        }
+        let mut bytes = [0; N];
+        for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|e| e.to_string())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|e| e.to_string())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl<const N: usize> Serialize for Bytes<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Bytes<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <String as Deserialize>::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// 128-bit integers as decimal strings, alone or in options, vectors and
+/// arrays: JSON numbers lose precision past 2^53, and serde can't buffer
+/// 128-bit integers for the tagged `Account` and `Event`.
+mod decimal {
+    use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// A type `decimal` can serialize, through its `Repr`.
+    pub trait Decimal: Sized {
+        type Repr: Serialize + DeserializeOwned;
+
+        fn to_repr(&self) -> Self::Repr;
+
+        fn from_repr(repr: Self::Repr) -> Result<Self, String>;
+    }
+
+    macro_rules! wide {
+        ($($ty:ty),*) => {$(
+            impl Decimal for $ty {
+                type Repr = String;
+
+                fn to_repr(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_repr(repr: String) -> Result<Self, String> {
+                    repr.parse().map_err(|e: std::num::ParseIntError| e.to_string())
+                }
+            }
+        )*};
+    }
+
+    wide!(u128, i128);
+
+    impl<T: Decimal> Decimal for Option<T> {
+        type Repr = Option<T::Repr>;
+
+        fn to_repr(&self) -> Self::Repr {
+            self.as_ref().map(T::to_repr)
+        }
+
+        fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+            repr.map(T::from_repr).transpose()
+        }
+    }
+
+    impl<T: Decimal> Decimal for Vec<T> {
+        type Repr = Vec<T::Repr>;
+
+        fn to_repr(&self) -> Self::Repr {
+            self.iter().map(T::to_repr).collect()
+        }
+
+        fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+            repr.into_iter().map(T::from_repr).collect()
+        }
+    }
+
+    impl<T: Decimal, const N: usize> Decimal for [T; N] {
+        type Repr = Vec<T::Repr>;
+
+        fn to_repr(&self) -> Self::Repr {
+            self.iter().map(T::to_repr).collect()
+        }
+
+        fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+            let items = Vec::<T>::from_repr(repr)?;
+            let len = items.len();
+            items.try_into().map_err(|_| format!("expected {N} items, got {len}"))
+        }
+    }
+
+    pub fn serialize<T: Decimal, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        value.to_repr().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: Decimal, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        T::from_repr(T::Repr::deserialize(deserializer)?).map_err(<D::Error as serde::de::Error>::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::Path};
+
+    /// The committed IDL is the one the program builds.  Compiles the program
+    /// with `idl-build`, so it only runs on request:
+    /// `cargo test -p arcvote-types -- --ignored`.
+    #[test]
+    #[ignore]
+    fn idl_matches_the_program() {
+        let program = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../programs/private-voting");
+        // The builder turns a set toolchain into a malformed `cargo +...`.
+        env::remove_var("RUSTUP_TOOLCHAIN");
+        let built = anchor_lang_idl::build::IdlBuilder::new()
+            .program_path(program)
+            .skip_lint(true)
+            .build()
+            .expect("build the IDL");
+        let committed: serde_json::Value =
+            serde_json::from_str(include_str!("../private_voting.json")).expect("parse the IDL");
+        assert!(
+            serde_json::to_value(built).unwrap() == committed,
+            "private_voting.json is stale: anchor build && cp target/idl/private_voting.json crates/arcvote-types/"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! `arcvote-vectors` — print reproducible test vectors for ballot encryption
+//! and the three circuits.
+//!
+//! Every key and nonce is fixed, so the output never changes unless the
+//! encryption scheme or circuit semantics do.  Other SDKs (and auditors) can
+//! replay each ballot through their own x25519 + Rescue implementation and
+//! compare ciphertexts byte-for-byte, then compare tallies against the
+//! plaintext circuit model.
+//!
+//! ```text
+//! cargo run -p arcvote-vectors > crates/arcvote-vectors/vectors.json
+//! cargo run -p arcvote-vectors -- --check crates/arcvote-vectors/vectors.json
+//! ```
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use anyhow::{Context, Result};
+use arcvote_client::{
+    encryption::{encrypt_vote, EncryptionKeypair, VoteAllocation},
+    reference::{self, Tallies},
+};
+use clap::Parser;
+use serde_json::{json, Value};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Bump when the output layout changes.
+const VERSION: u32 = 1;
+
+/// Stand-in MXE secret; real clusters hold theirs in MPC.
+const MXE_SECRET: [u8; 32] = [0x4d; 32];
+
+/// The README proposal's `voice_credits`.
+const VOICE_CREDITS: u64 = 100;
+
+/// The README scenario, followed by an over-budget ballot (cost 101) that the
+/// circuit must drop and an empty ballot that costs nothing.
+const BALLOTS: [[u64; 4]; 5] = [[7, 3, 1, 0], [5, 5, 0, 0], [0, 0, 10, 0], [10, 1, 0, 0], [0, 0, 0, 0]];
+
+#[derive(Parser)]
+#[command(name = "arcvote-vectors", version, about = "Generate ArcVote test vectors")]
+struct Cli {
+    /// Compare against an existing vectors file instead of printing.
+    #[arg(long)]
+    check: Option<PathBuf>,
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    let vectors = serde_json::to_string_pretty(&generate())? + "\n";
+
+    let Some(path) = cli.check else {
+        print!("{vectors}");
+        return Ok(ExitCode::SUCCESS);
+    };
+    let existing = fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    if existing == vectors {
+        println!("{} is up to date", path.display());
+        Ok(ExitCode::SUCCESS)
+    } else {
+        eprintln!("{} differs from the generated vectors", path.display());
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn generate() -> Value {
+    let mxe_public_key = PublicKey::from(&StaticSecret::from(MXE_SECRET)).to_bytes();
+    let mut tallies = reference::init_tallies();
+    let initial = tallies_json(&tallies);
+
+    let ballots: Vec<Value> = BALLOTS
+        .iter()
+        .enumerate()
+        .map(|(i, votes)| {
+            let secret = [i as u8 + 1; 32];
+            let nonce = [0xa0 + i as u8; 16];
+            let keypair = EncryptionKeypair::from_secret(secret);
+            let allocation = VoteAllocation::new(*votes);
+            let vote = encrypt_vote(&keypair, &mxe_public_key, &allocation, nonce);
+            let counted = reference::cast_vote(&mut tallies, &allocation, VOICE_CREDITS);
+
+            json!({
+                "voter_secret_key": hex(&secret),
+                "voter_public_key": hex(&vote.public_key),
+                "shared_secret": hex(&keypair.shared_secret(&mxe_public_key)),
+                "nonce": hex(&nonce),
+                "nonce_u128": vote.nonce.to_string(),
+                "allocation": votes,
+                "cost": allocation.cost(),
+                "counted": counted,
+                "ciphertexts": vote.ciphertexts.iter().map(|c| hex(c)).collect::<Vec<_>>(),
+                "tallies_after": tallies_json(&tallies),
+            })
+        })
+        .collect();
+
+    let results = reference::reveal_results(&tallies);
+    json!({
+        "version": VERSION,
+        "description": "x25519 key exchange with the MXE, Rescue-Prime KDF, Rescue-CTR; \
+                        byte strings are hex, field elements 32-byte little-endian",
+        "mxe_secret_key": hex(&MXE_SECRET),
+        "mxe_public_key": hex(&mxe_public_key),
+        "init_tallies": initial,
+        "cast_vote": ballots,
+        "reveal_results": {
+            "options": results.options,
+            "total_votes": results.total_votes,
+            "winner": results.winner,
+        },
+    })
+}
+
+fn tallies_json(tallies: &Tallies) -> Value {
+    json!({ "options": tallies.options, "total_votes": tallies.total_votes })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
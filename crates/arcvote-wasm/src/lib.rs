@@ -0,0 +1,2844 @@
+//! WebAssembly bindings for `arcvote-client`.
+//!
+//! Browser dApps call these instead of re-implementing ballot encryption in
+//! TypeScript, so the ciphertext layout can never drift from the Rust SDK.
+//! Build with `wasm-pack build crates/arcvote-wasm --target web`.
+//!
+//! Public keys cross the boundary as 32-byte `Uint8Array`s, u64/u128
+//! values as `BigInt`s.
+
+use arcvote_client::{
+    credential::{self, CredentialRequest, RegistrarKey},
+    encryption::{self, EncryptedVote, EncryptionKeypair, VoteAllocation},
+    instructions::{
+        self, BallotKind, ChamberRule, CreatePollParams, CreateProposalParams, DependencyCondition, OptionDetail,
+        QuorumKind, SanctionAction, SlashReason, WeightCurve,
+    },
+    pda, ArciumEnv, Instruction, Pubkey,
+};
+use wasm_bindgen::prelude::*;
+
+/// An encrypted ballot, ready for `buildCastVote`.
+#[wasm_bindgen]
+pub struct EncryptedBallot {
+    inner: EncryptedVote,
+}
+
+#[wasm_bindgen]
+impl EncryptedBallot {
+    /// The four 32-byte ciphertexts, concatenated.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertexts(&self) -> Vec<u8> {
+        self.inner.ciphertexts.concat()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.inner.public_key.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u128 {
+        self.inner.nonce
+    }
+}
+
+/// An encrypted write-in, ready for `buildCastWriteIn`.
+#[wasm_bindgen]
+pub struct EncryptedWriteIn {
+    inner: encryption::EncryptedWriteIn,
+}
+
+#[wasm_bindgen]
+impl EncryptedWriteIn {
+    /// The 32-byte ciphertext of the candidate hash.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertext(&self) -> Vec<u8> {
+        self.inner.ciphertext.to_vec()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.inner.public_key.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u128 {
+        self.inner.nonce
+    }
+}
+
+/// An encrypted credit budget, ready for `buildFundEncryptedBudget`.
+#[wasm_bindgen]
+pub struct EncryptedCredits {
+    inner: encryption::EncryptedCredits,
+}
+
+#[wasm_bindgen]
+impl EncryptedCredits {
+    /// The 32-byte ciphertext of the budget.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertext(&self) -> Vec<u8> {
+        self.inner.ciphertext.to_vec()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.inner.public_key.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u128 {
+        self.inner.nonce
+    }
+}
+
+/// An encrypted registered wallet, for `buildCastVoteAnonymous` on a
+/// proposal with a compliance roll.
+#[wasm_bindgen]
+pub struct EncryptedIdentity {
+    inner: encryption::EncryptedIdentity,
+}
+
+#[wasm_bindgen]
+impl EncryptedIdentity {
+    /// The two 32-byte ciphertexts, concatenated.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertexts(&self) -> Vec<u8> {
+        self.inner.ciphertexts.concat()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u128 {
+        self.inner.nonce
+    }
+}
+
+/// Encrypted jury scores, ready for `buildCastJuryScores`.
+#[wasm_bindgen]
+pub struct EncryptedJuryScores {
+    inner: encryption::EncryptedJuryScores,
+}
+
+#[wasm_bindgen]
+impl EncryptedJuryScores {
+    /// The twelve 32-byte ciphertexts, criterion-major, concatenated.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertexts(&self) -> Vec<u8> {
+        self.inner.ciphertexts.concat()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.inner.public_key.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u128 {
+        self.inner.nonce
+    }
+}
+
+/// Encrypted choices on up to eight proposals of one ballot, ready for
+/// `buildCastBallotChoices`.
+#[wasm_bindgen]
+pub struct EncryptedBallotChoices {
+    inner: encryption::EncryptedBallotChoices,
+}
+
+#[wasm_bindgen]
+impl EncryptedBallotChoices {
+    /// The eight 32-byte ciphertexts, one per proposal slot, concatenated.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertexts(&self) -> Vec<u8> {
+        self.inner.ciphertexts.concat()
+    }
+
+    #[wasm_bindgen(getter, js_name = publicKey)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.inner.public_key.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u128 {
+        self.inner.nonce
+    }
+}
+
+/// One account of a built instruction.
+#[wasm_bindgen]
+pub struct AccountMeta {
+    pubkey: Pubkey,
+    #[wasm_bindgen(readonly, js_name = isSigner)]
+    pub is_signer: bool,
+    #[wasm_bindgen(readonly, js_name = isWritable)]
+    pub is_writable: bool,
+}
+
+#[wasm_bindgen]
+impl AccountMeta {
+    #[wasm_bindgen(getter)]
+    pub fn pubkey(&self) -> Vec<u8> {
+        self.pubkey.to_bytes().to_vec()
+    }
+}
+
+/// A built instruction; map it to `TransactionInstruction` in JS.
+#[wasm_bindgen]
+pub struct BuiltInstruction {
+    inner: Instruction,
+}
+
+#[wasm_bindgen]
+impl BuiltInstruction {
+    #[wasm_bindgen(getter, js_name = programId)]
+    pub fn program_id(&self) -> Vec<u8> {
+        self.inner.program_id.to_bytes().to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn keys(&self) -> Vec<AccountMeta> {
+        self.inner
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<u8> {
+        self.inner.data.clone()
+    }
+}
+
+impl From<Instruction> for BuiltInstruction {
+    fn from(inner: Instruction) -> Self {
+        Self { inner }
+    }
+}
+
+/// The voter's x25519 public key, derived from their wallet signature of
+/// `ENCRYPTION_KEY_MESSAGE`.
+#[wasm_bindgen(js_name = encryptionPublicKey)]
+pub fn encryption_public_key(signature: &[u8]) -> Vec<u8> {
+    EncryptionKeypair::from_signature(signature)
+        .public_key()
+        .to_vec()
+}
+
+/// The message wallets sign to derive the encryption key.
+#[wasm_bindgen(js_name = encryptionKeyMessage)]
+pub fn encryption_key_message() -> Vec<u8> {
+    encryption::ENCRYPTION_KEY_MESSAGE.to_vec()
+}
+
+/// Quadratic cost of an allocation, or `undefined` on overflow.
+#[wasm_bindgen(js_name = quadraticCost)]
+pub fn quadratic_cost(votes: &[u64]) -> Result<Option<u64>, JsError> {
+    Ok(allocation(votes)?.cost())
+}
+
+/// Nonce to encrypt a ballot under on a proposal with a commit phase,
+/// so the ballot cast after the phase is the one committed to.
+#[wasm_bindgen(js_name = commitNonce)]
+pub fn commit_nonce(signature: &[u8], proposal: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(EncryptionKeypair::from_signature(signature)
+        .commit_nonce(&pubkey(proposal)?)
+        .to_vec())
+}
+
+/// Encrypt an allocation (up to 4 options) for the MXE.
+/// `nonce` must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptVote)]
+pub fn encrypt_vote(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    votes: &[u64],
+    nonce: &[u8],
+) -> Result<EncryptedBallot, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    Ok(EncryptedBallot {
+        inner: encryption::encrypt_vote(&keypair, &bytes32(mxe_public_key)?, &allocation(votes)?, nonce),
+    })
+}
+
+/// Encrypt a Likert ballot, one -2..+2 score per option (up to 4), for
+/// `buildCastLikert`.  `nonce` must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptLikert)]
+pub fn encrypt_likert(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    scores: &[i8],
+    nonce: &[u8],
+) -> Result<EncryptedBallot, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    let scores = VoteAllocation::likert(scores)
+        .ok_or_else(|| JsError::new("at most 4 scores, each from -2 to 2"))?;
+    Ok(EncryptedBallot {
+        inner: encryption::encrypt_vote(&keypair, &bytes32(mxe_public_key)?, &scores, nonce),
+    })
+}
+
+/// Encrypt a STAR ballot, one 0..=5 score per option (up to 4), for
+/// `buildCastStar`.  `nonce` must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptStar)]
+pub fn encrypt_star(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    scores: &[u8],
+    nonce: &[u8],
+) -> Result<EncryptedBallot, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    let scores = VoteAllocation::star(scores).ok_or_else(|| JsError::new("at most 4 scores, each from 0 to 5"))?;
+    Ok(EncryptedBallot {
+        inner: encryption::encrypt_vote(&keypair, &bytes32(mxe_public_key)?, &scores, nonce),
+    })
+}
+
+/// Encrypt a range ballot, one 0..=10 score per option (up to 4), for
+/// `buildCastRange`.  `nonce` must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptRange)]
+pub fn encrypt_range(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    scores: &[u8],
+    nonce: &[u8],
+) -> Result<EncryptedBallot, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    let scores = VoteAllocation::range(scores).ok_or_else(|| JsError::new("at most 4 scores, each from 0 to 10"))?;
+    Ok(EncryptedBallot {
+        inner: encryption::encrypt_vote(&keypair, &bytes32(mxe_public_key)?, &scores, nonce),
+    })
+}
+
+/// Encrypt a For/Against/Abstain ballot choosing `option` (0 For,
+/// 1 Against, 2 Abstain), for `buildCastChoice`.  `nonce` must be 16 fresh
+/// random bytes.
+#[wasm_bindgen(js_name = encryptChoice)]
+pub fn encrypt_choice(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    option: u8,
+    nonce: &[u8],
+) -> Result<EncryptedBallot, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    let choice = VoteAllocation::choice(option.into())
+        .ok_or_else(|| JsError::new("option must be 0 (For), 1 (Against) or 2 (Abstain)"))?;
+    Ok(EncryptedBallot {
+        inner: encryption::encrypt_vote(&keypair, &bytes32(mxe_public_key)?, &choice, nonce),
+    })
+}
+
+/// Encrypt For/Against/Abstain choices on one to eight proposals of one
+/// ballot, one byte each — 0 (For), 1 (Against) or 2 (Abstain) — in the
+/// order `buildCastBallotChoices` takes the proposals.  `nonce` must be
+/// 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptBallotChoices)]
+pub fn encrypt_ballot_choices(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    choices: &[u8],
+    nonce: &[u8],
+) -> Result<EncryptedBallotChoices, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    if !(1..=instructions::MAX_BALLOT_PROPOSALS).contains(&choices.len()) {
+        return Err(JsError::new("give one to eight choices"));
+    }
+    if choices.iter().any(|choice| *choice > 2) {
+        return Err(JsError::new("options must be 0 (For), 1 (Against) or 2 (Abstain)"));
+    }
+    Ok(EncryptedBallotChoices {
+        inner: encryption::encrypt_ballot_choices(&keypair, &bytes32(mxe_public_key)?, choices, nonce),
+    })
+}
+
+/// Encrypt an approval ballot approving each of `options` (distinct
+/// indices 0..=3), for `buildCastApproval`.  `nonce` must be 16 fresh
+/// random bytes.
+#[wasm_bindgen(js_name = encryptApproval)]
+pub fn encrypt_approval(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    options: &[u8],
+    nonce: &[u8],
+) -> Result<EncryptedBallot, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    let options: Vec<usize> = options.iter().map(|o| usize::from(*o)).collect();
+    let approvals = VoteAllocation::approval(&options)
+        .ok_or_else(|| JsError::new("options must be distinct, from 0 to 3"))?;
+    Ok(EncryptedBallot {
+        inner: encryption::encrypt_vote(&keypair, &bytes32(mxe_public_key)?, &approvals, nonce),
+    })
+}
+
+/// Encrypt a ranked ballot for `buildCastRankedVote`, `buildCastBorda` or
+/// `buildCastCondorcet`: `order` lists options (distinct indices 0..=3)
+/// best first, and those left out follow in index order.  `nonce` must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptRanking)]
+pub fn encrypt_ranking(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    order: &[u8],
+    nonce: &[u8],
+) -> Result<EncryptedBallot, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    let order: Vec<usize> = order.iter().map(|o| usize::from(*o)).collect();
+    let ranking = VoteAllocation::ranking(&order)
+        .ok_or_else(|| JsError::new("options must be distinct, from 0 to 3"))?;
+    Ok(EncryptedBallot {
+        inner: encryption::encrypt_vote(&keypair, &bytes32(mxe_public_key)?, &ranking, nonce),
+    })
+}
+
+/// Encrypt a juror's scores for `buildCastJuryScores`: four per criterion
+/// (one per application, 0 past the last), criterion-major, for up to 3
+/// criteria.  `nonce` must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptJuryScores)]
+pub fn encrypt_jury_scores(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    scores: &[u64],
+    nonce: &[u8],
+) -> Result<EncryptedJuryScores, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    if scores.len() % 4 != 0 {
+        return Err(JsError::new("give four scores per criterion"));
+    }
+    let rows: Vec<Vec<u64>> = scores.chunks(4).map(<[u64]>::to_vec).collect();
+    let scores = encryption::JuryScores::new(&rows).ok_or_else(|| JsError::new("at most 3 criteria are supported"))?;
+    Ok(EncryptedJuryScores {
+        inner: encryption::encrypt_jury_scores(&keypair, &bytes32(mxe_public_key)?, &scores, nonce),
+    })
+}
+
+/// The candidate hash a write-in of `identifier` carries, to match a
+/// revealed top write-in against names.
+#[wasm_bindgen(js_name = writeInHash)]
+pub fn write_in_hash(identifier: &str) -> u128 {
+    encryption::write_in_hash(identifier)
+}
+
+/// Encrypt a write-in of `identifier` for `buildCastWriteIn`.  `nonce`
+/// must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptWriteIn)]
+pub fn encrypt_write_in(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    identifier: &str,
+    nonce: &[u8],
+) -> Result<EncryptedWriteIn, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    Ok(EncryptedWriteIn {
+        inner: encryption::encrypt_write_in(
+            &keypair,
+            &bytes32(mxe_public_key)?,
+            encryption::write_in_hash(identifier),
+            nonce,
+        ),
+    })
+}
+
+/// Encrypt a voter's budget of `credits` for the MXE; `signature` is the
+/// proposal authority's.  `nonce` must be 16 fresh random bytes.
+#[wasm_bindgen(js_name = encryptCredits)]
+pub fn encrypt_credits(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    credits: u64,
+    nonce: &[u8],
+) -> Result<EncryptedCredits, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    Ok(EncryptedCredits {
+        inner: encryption::encrypt_credits(&keypair, &bytes32(mxe_public_key)?, credits, nonce),
+    })
+}
+
+/// Encrypt the registered `wallet` an anonymous ballot declares, under the
+/// same `signature`-derived key as the ballot.  `nonce` must be 16 fresh
+/// random bytes, not the ballot's.
+#[wasm_bindgen(js_name = encryptIdentity)]
+pub fn encrypt_identity(
+    signature: &[u8],
+    mxe_public_key: &[u8],
+    wallet: &[u8],
+    nonce: &[u8],
+) -> Result<EncryptedIdentity, JsError> {
+    let keypair = EncryptionKeypair::from_signature(signature);
+    let nonce: [u8; 16] = nonce
+        .try_into()
+        .map_err(|_| JsError::new("nonce must be 16 bytes"))?;
+    Ok(EncryptedIdentity {
+        inner: encryption::encrypt_identity(&keypair, &bytes32(mxe_public_key)?, &pubkey(wallet)?, nonce),
+    })
+}
+
+/// The registrar's 128-byte BLS public key for `buildOpenCredentials`,
+/// from its 32-byte secret.
+#[wasm_bindgen(js_name = registrarPublicKey)]
+pub fn registrar_public_key(secret: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(registrar_key(secret)?.public_key().to_vec())
+}
+
+/// The 64-byte blinded message a voter hands the registrar for a credential
+/// on `proposal`.  `serial` and `blinding` are 32 fresh random bytes each,
+/// kept secret.
+#[wasm_bindgen(js_name = blindCredential)]
+pub fn blind_credential(proposal: &[u8], serial: &[u8], blinding: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(credential_request(proposal, serial, blinding)?.blinded_message().to_vec())
+}
+
+/// The registrar's signature on a blinded message, for `buildIssueCredential`.
+#[wasm_bindgen(js_name = signBlindedCredential)]
+pub fn sign_blinded_credential(secret: &[u8], blinded_message: &[u8]) -> Result<Vec<u8>, JsError> {
+    registrar_key(secret)?
+        .sign_blinded(&byte_array(blinded_message)?)
+        .map(|signature| signature.to_vec())
+        .ok_or_else(|| JsError::new("blinded message is not a curve point"))
+}
+
+/// The credential signature for `buildPresentCredential`, from the
+/// issuance's blind signature; fails unless it verifies under
+/// `registrar_key`.
+#[wasm_bindgen(js_name = unblindCredential)]
+pub fn unblind_credential(
+    proposal: &[u8],
+    serial: &[u8],
+    blinding: &[u8],
+    blind_signature: &[u8],
+    registrar_key: &[u8],
+) -> Result<Vec<u8>, JsError> {
+    let request = credential_request(proposal, serial, blinding)?;
+    let (proposal, registrar_key) = (pubkey(proposal)?, byte_array(registrar_key)?);
+    request
+        .unblind(&byte_array(blind_signature)?)
+        .filter(|signature| credential::verify_credential(&registrar_key, &proposal, &request.serial(), signature))
+        .map(|signature| signature.to_vec())
+        .ok_or_else(|| JsError::new("the blind signature does not match this credential"))
+}
+
+#[wasm_bindgen(js_name = proposalAddress)]
+pub fn proposal_address(authority: &[u8], id: u32) -> Result<Vec<u8>, JsError> {
+    Ok(pda::proposal_pda(&pubkey(authority)?, id).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = voterRecordAddress)]
+pub fn voter_record_address(proposal: &[u8], voter: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::voter_record_pda(&pubkey(proposal)?, &pubkey(voter)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = sessionAddress)]
+pub fn session_address(proposal: &[u8], wallet: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::session_pda(&pubkey(proposal)?, &pubkey(wallet)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = nullifierSetAddress)]
+pub fn nullifier_set_address(proposal: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::nullifier_set_pda(&pubkey(proposal)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = complianceRollAddress)]
+pub fn compliance_roll_address(proposal: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::compliance_roll_pda(&pubkey(proposal)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = sealedIdentityAddress)]
+pub fn sealed_identity_address(proposal: &[u8], serial: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::sealed_identity_pda(&pubkey(proposal)?, &bytes32(serial)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = mixQueueAddress)]
+pub fn mix_queue_address(proposal: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::mix_queue_pda(&pubkey(proposal)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = ballotBufferAddress)]
+pub fn ballot_buffer_address(proposal: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::ballot_buffer_pda(&pubkey(proposal)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = creditAccountAddress)]
+pub fn credit_account_address(proposal: &[u8], voter: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::credit_account_pda(&pubkey(proposal)?, &pubkey(voter)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = creditBankAddress)]
+pub fn credit_bank_address(authority: &[u8], voter: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::credit_bank_pda(&pubkey(authority)?, &pubkey(voter)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = encryptedBudgetAddress)]
+pub fn encrypted_budget_address(proposal: &[u8], voter: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::encrypted_budget_pda(&pubkey(proposal)?, &pubkey(voter)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = translationsAddress)]
+pub fn translations_address(proposal: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::translations_pda(&pubkey(proposal)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = candidateAddress)]
+pub fn candidate_address(proposal: &[u8], candidate: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::candidate_pda(&pubkey(proposal)?, &pubkey(candidate)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = jurorAddress)]
+pub fn juror_address(proposal: &[u8], juror: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::juror_pda(&pubkey(proposal)?, &pubkey(juror)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = credentialAddress)]
+pub fn credential_address(proposal: &[u8], serial: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::credential_pda(&pubkey(proposal)?, &bytes32(serial)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = nftBallotAddress)]
+pub fn nft_ballot_address(proposal: &[u8], mint: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(pda::nft_ballot_pda(&pubkey(proposal)?, &pubkey(mint)?).0.to_bytes().to_vec())
+}
+
+#[wasm_bindgen(js_name = quorumTokenAccount)]
+pub fn quorum_token_account(owner: &[u8], mint: &[u8], token_program: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(instructions::quorum_token_account(&pubkey(owner)?, &pubkey(mint)?, &pubkey(token_program)?)
+        .to_bytes()
+        .to_vec())
+}
+
+#[wasm_bindgen(js_name = buildCreateProposal)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_create_proposal(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    computation_offset: u64,
+    id: u32,
+    title: String,
+    options: Vec<String>,
+    deadline: i64,
+    registration_deadline: i64,
+    voice_credits: u64,
+    quorum: u32,
+    quorum_kind: u8,
+    electorate: u64,
+    quorum_mint: Option<Vec<u8>>,
+    gate_mint: Option<Vec<u8>>,
+    gate_threshold: u64,
+    ballot: u8,
+    ballot_param: u32,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::create_proposal(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        computation_offset,
+        CreateProposalParams {
+            id,
+            title,
+            options,
+            deadline,
+            registration_deadline,
+            voice_credits,
+            quorum,
+            quorum_kind: quorum_kind_from(quorum_kind)?,
+            electorate,
+            quorum_mint: quorum_mint.as_deref().map(pubkey).transpose()?,
+            gate_mint: gate_mint.as_deref().map(pubkey).transpose()?,
+            gate_threshold,
+            ballot: ballot_kind_from(ballot, ballot_param)?,
+            nonce,
+        },
+    )
+    .into())
+}
+
+/// As `buildCreateProposal`, for a budget box: `costs` holds one cost per
+/// option, funded out of `budget` when the proposal is revealed with
+/// `buildRevealBudgetBox`.  Voters cast with `encryptApproval`.
+#[wasm_bindgen(js_name = buildCreateBudgetBox)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_create_budget_box(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    computation_offset: u64,
+    id: u32,
+    title: String,
+    options: Vec<String>,
+    deadline: i64,
+    quorum: u32,
+    quorum_kind: u8,
+    electorate: u64,
+    quorum_mint: Option<Vec<u8>>,
+    budget: u64,
+    costs: &[u64],
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    if costs.len() != options.len() {
+        return Err(JsError::new("give one cost per option"));
+    }
+    Ok(instructions::create_proposal(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        computation_offset,
+        CreateProposalParams {
+            id,
+            title,
+            options,
+            deadline,
+            registration_deadline: 0,
+            voice_credits: 0,
+            quorum,
+            quorum_kind: quorum_kind_from(quorum_kind)?,
+            electorate,
+            quorum_mint: quorum_mint.as_deref().map(pubkey).transpose()?,
+            gate_mint: None,
+            gate_threshold: 0,
+            ballot: BallotKind::BudgetBox {
+                budget,
+                costs: allocation(costs)?.votes,
+            },
+            nonce,
+        },
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCreatePoll)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_create_poll(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    computation_offset: u64,
+    id: u32,
+    title: String,
+    options: Vec<String>,
+    deadline: i64,
+    voice_credits: u64,
+    ballot: u8,
+    ballot_param: u32,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::create_poll(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        computation_offset,
+        CreatePollParams {
+            id,
+            title,
+            options,
+            deadline,
+            voice_credits,
+            ballot: ballot_kind_from(ballot, ballot_param)?,
+            nonce,
+        },
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildAssignCategory)]
+pub fn build_assign_category(authority: &[u8], proposal: &[u8], category: u16) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::assign_category(&pubkey(authority)?, &pubkey(proposal)?, category).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenSqrtCredits)]
+pub fn build_open_sqrt_credits(
+    authority: &[u8],
+    proposal: &[u8],
+    credit_mint: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_sqrt_credits(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(credit_mint)?).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenBurnToVote)]
+pub fn build_open_burn_to_vote(
+    authority: &[u8],
+    proposal: &[u8],
+    burn_mint: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_burn_to_vote(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(burn_mint)?).into())
+}
+
+#[wasm_bindgen(js_name = buildSetEligibilityOracle)]
+pub fn build_set_eligibility_oracle(
+    authority: &[u8],
+    proposal: &[u8],
+    oracle: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::set_eligibility_oracle(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(oracle)?).into())
+}
+
+/// `curve` as `weight_curve_from`.
+#[wasm_bindgen(js_name = buildSetWeightCurve)]
+pub fn build_set_weight_curve(
+    authority: &[u8],
+    proposal: &[u8],
+    curve: u8,
+    cap: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::set_weight_curve(&pubkey(authority)?, &pubkey(proposal)?, weight_curve_from(curve, cap)?).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenTokenWeights)]
+pub fn build_open_token_weights(
+    authority: &[u8],
+    proposal: &[u8],
+    weight_mint: &[u8],
+    max_weight: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_token_weights(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(weight_mint)?, max_weight).into())
+}
+
+/// `voter_token_account` is the voter's account of the credit mint, whose
+/// balance is escrowed until the deadline.
+#[wasm_bindgen(js_name = buildRegisterCredits)]
+pub fn build_register_credits(
+    voter: &[u8],
+    proposal: &[u8],
+    voter_token_account: &[u8],
+    credit_mint: &[u8],
+    token_program: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::register_credits(
+        &pubkey(voter)?,
+        &pubkey(proposal)?,
+        &pubkey(voter_token_account)?,
+        &pubkey(credit_mint)?,
+        &pubkey(token_program)?,
+    )
+    .into())
+}
+
+/// `scope` is the proposal, or the ballot the credits were registered
+/// with.
+#[wasm_bindgen(js_name = buildReleaseCredits)]
+pub fn build_release_credits(
+    voter: &[u8],
+    scope: &[u8],
+    voter_token_account: &[u8],
+    credit_mint: &[u8],
+    token_program: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::release_credits(
+        &pubkey(voter)?,
+        &pubkey(scope)?,
+        &pubkey(voter_token_account)?,
+        &pubkey(credit_mint)?,
+        &pubkey(token_program)?,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildFundCredits)]
+pub fn build_fund_credits(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    voter: &[u8],
+    credits: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::fund_credits(&pubkey(payer)?, &pubkey(authority)?, &pubkey(proposal)?, &pubkey(voter)?, credits)
+        .into())
+}
+
+#[wasm_bindgen(js_name = buildRegisterCandidate)]
+pub fn build_register_candidate(candidate: &[u8], proposal: &[u8], name: String) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::register_candidate(&pubkey(candidate)?, &pubkey(proposal)?, name).into())
+}
+
+#[wasm_bindgen(js_name = buildCloseCandidate)]
+pub fn build_close_candidate(candidate: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::close_candidate(&pubkey(candidate)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildRequireParentOutcome)]
+pub fn build_require_parent_outcome(
+    authority: &[u8],
+    proposal: &[u8],
+    parent: &[u8],
+    winner: u8,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::require_parent_outcome(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(parent)?, winner).into())
+}
+
+#[wasm_bindgen(js_name = buildConfirmParentOutcome)]
+pub fn build_confirm_parent_outcome(proposal: &[u8], parent: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::confirm_parent_outcome(&pubkey(proposal)?, &pubkey(parent)?).into())
+}
+
+/// Option i is described by the i-th 32-byte key of `recipients` (all
+/// zeros for none), `amounts[i]` and `uris[i]` (empty for none).
+#[wasm_bindgen(js_name = buildSetOptionDetails)]
+pub fn build_set_option_details(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    recipients: &[u8],
+    amounts: &[u64],
+    uris: Vec<String>,
+) -> Result<BuiltInstruction, JsError> {
+    let recipients = recipients.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    if amounts.len() != recipients.len() || uris.len() != recipients.len() {
+        return Err(JsError::new("recipients, amounts and uris must have one entry per option"));
+    }
+    let details = recipients
+        .into_iter()
+        .zip(amounts)
+        .zip(uris)
+        .map(|((recipient, amount), metadata_uri)| OptionDetail {
+            recipient: (recipient != Pubkey::default()).then_some(recipient),
+            amount: *amount,
+            metadata_uri,
+        })
+        .collect();
+    Ok(instructions::set_option_details(&pubkey(payer)?, &pubkey(authority)?, &pubkey(proposal)?, details).into())
+}
+
+/// `options[i]` translates option i into `locale`.
+#[wasm_bindgen(js_name = buildSetTranslation)]
+pub fn build_set_translation(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    locale: String,
+    title: String,
+    options: Vec<String>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::set_translation(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        locale,
+        title,
+        options,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRemoveTranslation)]
+pub fn build_remove_translation(
+    authority: &[u8],
+    proposal: &[u8],
+    locale: String,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::remove_translation(&pubkey(authority)?, &pubkey(proposal)?, locale).into())
+}
+
+/// `condition`: 0 opens the proposal if `parent` passes, 1 if it fails.
+#[wasm_bindgen(js_name = buildDeclareDependency)]
+pub fn build_declare_dependency(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    parent: &[u8],
+    condition: u8,
+) -> Result<BuiltInstruction, JsError> {
+    let condition = match condition {
+        0 => DependencyCondition::Passes,
+        1 => DependencyCondition::Fails,
+        _ => return Err(JsError::new("unknown dependency condition")),
+    };
+    Ok(instructions::declare_dependency(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        &pubkey(parent)?,
+        condition,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildResolveDependency)]
+pub fn build_resolve_dependency(proposal: &[u8], parent: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::resolve_dependency(&pubkey(proposal)?, &pubkey(parent)?).into())
+}
+
+/// `action` as `sanction_action_from`.
+#[wasm_bindgen(js_name = buildDeclareSanction)]
+pub fn build_declare_sanction(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    action: u8,
+    target: &[u8],
+    reason: u8,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::declare_sanction(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        sanction_action_from(action, target, reason)?,
+    )
+    .into())
+}
+
+/// The sanction `proposal` declared, as in `buildDeclareSanction`;
+/// `treasury` is `Config.treasury`.
+#[wasm_bindgen(js_name = buildExecuteSanction)]
+pub fn build_execute_sanction(
+    proposal: &[u8],
+    action: u8,
+    target: &[u8],
+    reason: u8,
+    treasury: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    let action = sanction_action_from(action, target, reason)?;
+    Ok(instructions::execute_sanction(&pubkey(proposal)?, &action, &pubkey(treasury)?).into())
+}
+
+#[wasm_bindgen(js_name = buildSeatJuror)]
+pub fn build_seat_juror(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    juror: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::seat_juror(&pubkey(payer)?, &pubkey(authority)?, &pubkey(proposal)?, &pubkey(juror)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCloseJuror)]
+pub fn build_close_juror(authority: &[u8], proposal: &[u8], juror: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::close_juror(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(juror)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCastVote)]
+pub fn build_cast_vote(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCastVoteWithCredential)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_vote_with_credential(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+    serial: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_with_credential(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+        &bytes32(serial)?,
+    )
+    .into())
+}
+
+/// `serial` names the payer's credential on a gated proposal;
+/// `token_program` owns `burn_mint`.
+#[wasm_bindgen(js_name = buildCastVoteBurning)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_vote_burning(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+    serial: Option<Vec<u8>>,
+    burn_mint: &[u8],
+    token_program: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_burning(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+        serial.as_deref().map(bytes32).transpose()?.as_ref(),
+        &pubkey(burn_mint)?,
+        &pubkey(token_program)?,
+    )
+    .into())
+}
+
+/// `serial` names the payer's credential on a gated proposal;
+/// `token_program` owns `weight_mint`.
+#[wasm_bindgen(js_name = buildCastVoteTokenWeighted)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_vote_token_weighted(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+    serial: Option<Vec<u8>>,
+    weight_mint: &[u8],
+    token_program: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_token_weighted(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+        serial.as_deref().map(bytes32).transpose()?.as_ref(),
+        &pubkey(weight_mint)?,
+        &pubkey(token_program)?,
+    )
+    .into())
+}
+
+/// `token_program` owns `nft_mint`, which must be of the proposal's
+/// collection.
+#[wasm_bindgen(js_name = buildCastVoteWithNft)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_vote_with_nft(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    nft_mint: &[u8],
+    token_program: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_with_nft(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        &pubkey(nft_mint)?,
+        &pubkey(token_program)?,
+    )
+    .into())
+}
+
+/// `serial` names the payer's credential on a gated proposal;
+/// `oracle_accounts` are the oracle's accounts, concatenated.
+#[wasm_bindgen(js_name = buildCastVoteWithOracle)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_vote_with_oracle(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+    serial: Option<Vec<u8>>,
+    oracle: &[u8],
+    oracle_accounts: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    let oracle_accounts = oracle_accounts.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    Ok(instructions::cast_vote_with_oracle(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+        serial.as_deref().map(bytes32).transpose()?.as_ref(),
+        &pubkey(oracle)?,
+        &oracle_accounts,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildOpenCredentials)]
+pub fn build_open_credentials(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    registrar: &[u8],
+    registrar_key: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_credentials(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        &pubkey(registrar)?,
+        byte_array(registrar_key)?,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildIssueCredential)]
+pub fn build_issue_credential(
+    registrar: &[u8],
+    proposal: &[u8],
+    voter: &[u8],
+    blinded_message: &[u8],
+    blind_signature: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::issue_credential(
+        &pubkey(registrar)?,
+        &pubkey(proposal)?,
+        &pubkey(voter)?,
+        byte_array(blinded_message)?,
+        byte_array(blind_signature)?,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevokeCredential)]
+pub fn build_revoke_credential(registrar: &[u8], proposal: &[u8], serial: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::revoke_credential(&pubkey(registrar)?, &pubkey(proposal)?, bytes32(serial)?).into())
+}
+
+/// Sign with a wallet unconnected to the registered one.
+#[wasm_bindgen(js_name = buildPresentCredential)]
+pub fn build_present_credential(
+    payer: &[u8],
+    proposal: &[u8],
+    serial: &[u8],
+    signature: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::present_credential(&pubkey(payer)?, &pubkey(proposal)?, bytes32(serial)?, byte_array(signature)?).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenHistoryGate)]
+pub fn build_open_history_gate(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    attestor: Option<Vec<u8>>,
+    min_wallet_age: i64,
+    min_prior_ballots: u32,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_history_gate(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        attestor.as_deref().map(pubkey).transpose()?,
+        min_wallet_age,
+        min_prior_ballots,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildAttestHistory)]
+pub fn build_attest_history(
+    attestor: &[u8],
+    proposal: &[u8],
+    voter: &[u8],
+    first_seen: i64,
+    prior_ballots: u32,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::attest_history(
+        &pubkey(attestor)?,
+        &pubkey(proposal)?,
+        &pubkey(voter)?,
+        first_seen,
+        prior_ballots,
+    )
+    .into())
+}
+
+/// `voter_records` are the wallet's counted `VoterRecord` addresses on
+/// other proposals, concatenated.
+#[wasm_bindgen(js_name = buildProveHistory)]
+pub fn build_prove_history(voter: &[u8], proposal: &[u8], voter_records: &[u8]) -> Result<BuiltInstruction, JsError> {
+    let voter_records = voter_records.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    Ok(instructions::prove_history(&pubkey(voter)?, &pubkey(proposal)?, &voter_records).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenAllowlist)]
+pub fn build_open_allowlist(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    capacity: u16,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_allowlist(&pubkey(payer)?, &pubkey(authority)?, &pubkey(proposal)?, capacity).into())
+}
+
+#[wasm_bindgen(js_name = buildAddVoter)]
+pub fn build_add_voter(authority: &[u8], proposal: &[u8], voter: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::add_voter(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(voter)?).into())
+}
+
+#[wasm_bindgen(js_name = buildRemoveVoter)]
+pub fn build_remove_voter(authority: &[u8], proposal: &[u8], voter: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::remove_voter(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(voter)?).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenCollectionGate)]
+pub fn build_open_collection_gate(
+    authority: &[u8],
+    proposal: &[u8],
+    collection: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_collection_gate(&pubkey(authority)?, &pubkey(proposal)?, &pubkey(collection)?).into())
+}
+
+/// Send `buildGrowNullifierSet` afterwards until the set is full size.
+#[wasm_bindgen(js_name = buildOpenNullifierSet)]
+pub fn build_open_nullifier_set(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    max_voters: u32,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_nullifier_set(&pubkey(payer)?, &pubkey(authority)?, &pubkey(proposal)?, max_voters).into())
+}
+
+#[wasm_bindgen(js_name = buildGrowNullifierSet)]
+pub fn build_grow_nullifier_set(payer: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::grow_nullifier_set(&pubkey(payer)?, &pubkey(proposal)?).into())
+}
+
+/// `signature` is from `unblindCredential`; `payer` should be a wallet
+/// unconnected to the voter's.  `identity`, from `encryptIdentity`, is
+/// required once the proposal has a compliance roll.
+#[wasm_bindgen(js_name = buildCastVoteAnonymous)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_vote_anonymous(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    serial: &[u8],
+    signature: &[u8],
+    identity: Option<EncryptedIdentity>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_anonymous(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        bytes32(serial)?,
+        byte_array(signature)?,
+        identity.as_ref().map(|identity| &identity.inner),
+    )
+    .into())
+}
+
+/// Simulate it; the return data is one byte, 1 if `serial` was spent.
+#[wasm_bindgen(js_name = buildCheckNullifier)]
+pub fn build_check_nullifier(proposal: &[u8], serial: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::check_nullifier(&pubkey(proposal)?, bytes32(serial)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCloseNullifierSet)]
+pub fn build_close_nullifier_set(authority: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::close_nullifier_set(&pubkey(authority)?, &pubkey(proposal)?).into())
+}
+
+/// Signed by `payer` and `authority`; `compliance_key` is a 32-byte x25519
+/// public key.
+#[wasm_bindgen(js_name = buildOpenComplianceRoll)]
+pub fn build_open_compliance_roll(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    compliance_key: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_compliance_roll(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        bytes32(compliance_key)?,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildReleaseIdentity)]
+pub fn build_release_identity(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    sealed_identity: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::release_identity(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &pubkey(sealed_identity)?,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCloseComplianceRoll)]
+pub fn build_close_compliance_roll(authority: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::close_compliance_roll(&pubkey(authority)?, &pubkey(proposal)?).into())
+}
+
+/// Signed by the wallet, authorizing a browser-held `session_key`.
+#[wasm_bindgen(js_name = buildOpenSession)]
+pub fn build_open_session(
+    wallet: &[u8],
+    proposal: &[u8],
+    session_key: &[u8],
+    expires_at: i64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_session(&pubkey(wallet)?, &pubkey(proposal)?, &pubkey(session_key)?, expires_at).into())
+}
+
+/// Signed by the wallet or the session key.
+#[wasm_bindgen(js_name = buildCloseSession)]
+pub fn build_close_session(signer: &[u8], proposal: &[u8], wallet: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::close_session(&pubkey(signer)?, &pubkey(proposal)?, &pubkey(wallet)?).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenDatalessRecords)]
+pub fn build_open_dataless_records(authority: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_dataless_records(&pubkey(authority)?, &pubkey(proposal)?).into())
+}
+
+/// `buildCastVote` on a proposal keeping data-less records.
+#[wasm_bindgen(js_name = buildCastVoteDataless)]
+pub fn build_cast_vote_dataless(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_dataless(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// Signed by the session key alone; `serial` is the wallet's presented
+/// credential on a gated proposal.
+#[wasm_bindgen(js_name = buildCastVoteSession)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_vote_session(
+    cluster_offset: u32,
+    session_key: &[u8],
+    wallet: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+    serial: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_session(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(session_key)?,
+        &pubkey(wallet)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+        serial.as_deref().map(bytes32).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// `proposals` are the proposal addresses, concatenated, one per ballot in
+/// `ballots`; the `i`-th is queued at `computationOffset + i`.
+#[wasm_bindgen(js_name = buildCastVotesBatch)]
+pub fn build_cast_votes_batch(
+    cluster_offset: u32,
+    payer: &[u8],
+    computation_offset: u64,
+    proposals: &[u8],
+    ballots: Vec<EncryptedBallot>,
+) -> Result<BuiltInstruction, JsError> {
+    let proposals = proposals.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    if proposals.len() != ballots.len() {
+        return Err(JsError::new("expected one proposal per ballot"));
+    }
+    let votes: Vec<_> = proposals.into_iter().zip(ballots.iter().map(|ballot| ballot.inner)).collect();
+    Ok(instructions::cast_votes_batch(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        computation_offset,
+        &votes,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildOpenMixWindow)]
+pub fn build_open_mix_window(payer: &[u8], authority: &[u8], proposal_id: u32, window: i64) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_mix_window(&pubkey(payer)?, &pubkey(authority)?, proposal_id, window).into())
+}
+
+#[wasm_bindgen(js_name = buildOpenBallotBuffer)]
+pub fn build_open_ballot_buffer(payer: &[u8], authority: &[u8], proposal_id: u32) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_ballot_buffer(&pubkey(payer)?, &pubkey(authority)?, proposal_id).into())
+}
+
+/// Buffers `ballot`; a keeper folds it in with `buildProcessBallotBatch`.
+#[wasm_bindgen(js_name = buildSubmitBallot)]
+pub fn build_submit_ballot(
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+    credential: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::submit_ballot(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+        credential.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// Processes up to four buffered ballots; send one per four `buffered`.
+#[wasm_bindgen(js_name = buildProcessBallotBatch)]
+pub fn build_process_ballot_batch(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::process_ballot_batch(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildOpenCommitPhase)]
+pub fn build_open_commit_phase(
+    authority: &[u8],
+    proposal_id: u32,
+    commit_deadline: i64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_commit_phase(&pubkey(authority)?, proposal_id, commit_deadline).into())
+}
+
+/// Commits to `ballot`, encrypted under `commitNonce`; cast the same
+/// ballot with `buildCastVote` once the commit phase ends.
+#[wasm_bindgen(js_name = buildCommitBallot)]
+pub fn build_commit_ballot(
+    voter: &[u8],
+    proposal: &[u8],
+    ballot: &EncryptedBallot,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::commit_ballot(&pubkey(voter)?, &pubkey(proposal)?, &ballot.inner).into())
+}
+
+/// Releases one held ballot; send one per `mix_held` once voting closes.
+#[wasm_bindgen(js_name = buildReleaseHeldBallot)]
+pub fn build_release_held_ballot(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::release_held_ballot(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildOpenSpendHistogram)]
+pub fn build_open_spend_histogram(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_spend_histogram(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        nonce,
+    )
+    .into())
+}
+
+/// Casts on a proposal whose `spend_histogram` is set, in place of
+/// `buildCastVote`.
+#[wasm_bindgen(js_name = buildCastVoteHistogram)]
+pub fn build_cast_vote_histogram(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_histogram(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealSpendHistogram)]
+pub fn build_reveal_spend_histogram(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_spend_histogram(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildOpenCreditBank)]
+pub fn build_open_credit_bank(authority: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_credit_bank(&pubkey(authority)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCreateCreditBank)]
+pub fn build_create_credit_bank(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::create_credit_bank(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        nonce,
+    )
+    .into())
+}
+
+/// Casts on a proposal whose `credit_bank` is set, in place of
+/// `buildCastVote`.
+#[wasm_bindgen(js_name = buildCastVoteBanked)]
+pub fn build_cast_vote_banked(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_banked(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildFundEncryptedBudget)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_fund_encrypted_budget(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    computation_offset: u64,
+    voter: &[u8],
+    budget: &EncryptedCredits,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::fund_encrypted_budget(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        computation_offset,
+        &pubkey(voter)?,
+        &budget.inner,
+        nonce,
+    )
+    .into())
+}
+
+/// Casts on a proposal whose `encrypted_budgets` is set, in place of
+/// `buildCastVote`.
+#[wasm_bindgen(js_name = buildCastVoteBudgeted)]
+pub fn build_cast_vote_budgeted(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_vote_budgeted(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// Before the first ballot of a ranked-choice proposal.
+#[wasm_bindgen(js_name = buildOpenRankings)]
+pub fn build_open_rankings(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_rankings(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        nonce,
+    )
+    .into())
+}
+
+/// Casts a ballot from `encryptRanking`.
+#[wasm_bindgen(js_name = buildCastRankedVote)]
+pub fn build_cast_ranked_vote(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_ranked_vote(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealRanked)]
+pub fn build_reveal_ranked(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_ranked(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+/// Casts a ballot from `encryptRanking` on a Borda-count proposal.
+#[wasm_bindgen(js_name = buildCastBorda)]
+pub fn build_cast_borda(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_borda(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// Casts a ballot from `encryptRange` on a range-voting proposal.
+#[wasm_bindgen(js_name = buildCastRange)]
+pub fn build_cast_range(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_range(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// Before the first ballot of a Condorcet or STAR proposal.
+#[wasm_bindgen(js_name = buildOpenPreferences)]
+pub fn build_open_preferences(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_preferences(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        nonce,
+    )
+    .into())
+}
+
+/// Casts a ballot from `encryptRanking` on a Condorcet proposal.
+#[wasm_bindgen(js_name = buildCastCondorcet)]
+pub fn build_cast_condorcet(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_condorcet(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealCondorcet)]
+pub fn build_reveal_condorcet(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_condorcet(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+/// Casts a ballot from `encryptStar`.
+#[wasm_bindgen(js_name = buildCastStar)]
+pub fn build_cast_star(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_star(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealStar)]
+pub fn build_reveal_star(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_star(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCastLikert)]
+pub fn build_cast_likert(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_likert(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCastChoice)]
+pub fn build_cast_choice(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_choice(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// `buildCastChoice` on a proposal whose ballot keeps round records.
+#[wasm_bindgen(js_name = buildCastRoundChoice)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_cast_round_choice(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    on_ballot: &[u8],
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_round_choice(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        &pubkey(on_ballot)?,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// `proposals` are the ballot's, in its order, concatenated.
+#[wasm_bindgen(js_name = buildOpenRoundRecords)]
+pub fn build_open_round_records(
+    authority: &[u8],
+    ballot_id: u32,
+    proposals: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    let proposals = proposals.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    Ok(instructions::open_round_records(&pubkey(authority)?, ballot_id, &proposals).into())
+}
+
+/// Casts `encryptBallotChoices` on proposals of the same ballot, by
+/// address: `proposals` are their 32-byte keys, concatenated, in the order
+/// of the choices.
+#[wasm_bindgen(js_name = buildCastBallotChoices)]
+pub fn build_cast_ballot_choices(
+    cluster_offset: u32,
+    payer: &[u8],
+    proposals: &[u8],
+    computation_offset: u64,
+    ballot: &EncryptedBallotChoices,
+) -> Result<BuiltInstruction, JsError> {
+    let proposals = proposals.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    Ok(instructions::cast_ballot_choices(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &proposals,
+        computation_offset,
+        &ballot.inner,
+    )
+    .into())
+}
+
+/// `members` is their 32-byte keys, concatenated; `rule` as
+/// `chamber_rule_from`.
+#[wasm_bindgen(js_name = buildOpenCouncil)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_open_council(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+    members: &[u8],
+    rule: u8,
+    council_bps: u16,
+) -> Result<BuiltInstruction, JsError> {
+    let members = members.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    Ok(instructions::open_council(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        nonce,
+        members,
+        chamber_rule_from(rule, council_bps)?,
+    )
+    .into())
+}
+
+/// Casts a council member's `encryptChoice` ballot; `payer` is the member.
+#[wasm_bindgen(js_name = buildCastCouncilChoice)]
+pub fn build_cast_council_choice(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_council_choice(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildSetTurnoutBias)]
+pub fn build_set_turnout_bias(authority: &[u8], proposal_id: u32, bias_bps: u16) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::set_turnout_bias(&pubkey(authority)?, proposal_id, bias_bps).into())
+}
+
+/// `council` is `Config.council`, which signs alongside `authority`.
+#[wasm_bindgen(js_name = buildFastTrack)]
+pub fn build_fast_track(
+    authority: &[u8],
+    council: &[u8],
+    proposal_id: u32,
+    deadline: i64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::fast_track(&pubkey(authority)?, &pubkey(council)?, proposal_id, deadline).into())
+}
+
+#[wasm_bindgen(js_name = buildCastApproval)]
+pub fn build_cast_approval(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_approval(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCastPairwise)]
+pub fn build_cast_pairwise(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedBallot,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_pairwise(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildOpenWriteIns)]
+pub fn build_open_write_ins(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    nonce: u128,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::open_write_ins(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        nonce,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCastWriteIn)]
+pub fn build_cast_write_in(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    write_in: &EncryptedWriteIn,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_write_in(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &write_in.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCastJuryScores)]
+pub fn build_cast_jury_scores(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    ballot: &EncryptedJuryScores,
+    voter_token_account: Option<Vec<u8>>,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::cast_jury_scores(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        &ballot.inner,
+        voter_token_account.as_deref().map(pubkey).transpose()?.as_ref(),
+    )
+    .into())
+}
+
+/// An SPL Memo "Voted on proposal #`proposal_id`" to add after a ballot,
+/// if the voter opts in: it makes the vote legible to custodians and
+/// accountants, and to anyone reading the chain.
+#[wasm_bindgen(js_name = buildVoteMemo)]
+pub fn build_vote_memo(proposal_id: u32) -> BuiltInstruction {
+    instructions::vote_memo(proposal_id).into()
+}
+
+#[wasm_bindgen(js_name = buildRevealResults)]
+pub fn build_reveal_results(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_results(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealOutcome)]
+pub fn build_reveal_outcome(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_outcome(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+/// In place of `buildRevealOutcome` once the proposal has a council.
+#[wasm_bindgen(js_name = buildRevealChambers)]
+pub fn build_reveal_chambers(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_chambers(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealPairwise)]
+pub fn build_reveal_pairwise(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_pairwise(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealBudgetBox)]
+pub fn build_reveal_budget_box(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_budget_box(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealWriteIn)]
+pub fn build_reveal_write_in(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_write_in(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildRevealPoll)]
+pub fn build_reveal_poll(
+    cluster_offset: u32,
+    payer: &[u8],
+    proposal: &[u8],
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::reveal_poll(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(proposal)?,
+        computation_offset,
+    )
+    .into())
+}
+
+/// The first check fixes `vote_threshold`; later ones must repeat it.
+#[wasm_bindgen(js_name = buildCheckQuorumProgress)]
+pub fn build_check_quorum_progress(
+    cluster_offset: u32,
+    payer: &[u8],
+    authority: &[u8],
+    proposal_id: u32,
+    computation_offset: u64,
+    vote_threshold: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::check_quorum_progress(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        proposal_id,
+        computation_offset,
+        vote_threshold,
+    )
+    .into())
+}
+
+/// `trustees` is their 32-byte keys, concatenated.
+#[wasm_bindgen(js_name = buildAppointTrustees)]
+pub fn build_appoint_trustees(
+    payer: &[u8],
+    authority: &[u8],
+    proposal: &[u8],
+    trustees: &[u8],
+    threshold: u8,
+    grace_period: i64,
+) -> Result<BuiltInstruction, JsError> {
+    let trustees = trustees.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    Ok(instructions::appoint_trustees(
+        &pubkey(payer)?,
+        &pubkey(authority)?,
+        &pubkey(proposal)?,
+        trustees,
+        threshold,
+        grace_period,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildApproveReveal)]
+pub fn build_approve_reveal(trustee: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::approve_reveal(&pubkey(trustee)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildForceReveal)]
+pub fn build_force_reveal(
+    cluster_offset: u32,
+    payer: &[u8],
+    trustee: &[u8],
+    proposal: &[u8],
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::force_reveal(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(trustee)?,
+        &pubkey(proposal)?,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildCloseTrusteeSet)]
+pub fn build_close_trustee_set(authority: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::close_trustee_set(&pubkey(authority)?, &pubkey(proposal)?).into())
+}
+
+/// `co_authors` is their 32-byte keys, concatenated.
+#[wasm_bindgen(js_name = buildSetCoAuthors)]
+pub fn build_set_co_authors(authority: &[u8], proposal: &[u8], co_authors: &[u8]) -> Result<BuiltInstruction, JsError> {
+    let co_authors = co_authors.chunks(32).map(pubkey).collect::<Result<Vec<_>, _>>()?;
+    Ok(instructions::set_co_authors(&pubkey(authority)?, &pubkey(proposal)?, co_authors).into())
+}
+
+/// `category` is the proposal's, if it has one.
+#[wasm_bindgen(js_name = buildExtendDeadline)]
+pub fn build_extend_deadline(
+    signer: &[u8],
+    proposal: &[u8],
+    category: Option<u16>,
+    new_deadline: i64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::extend_deadline(&pubkey(signer)?, &pubkey(proposal)?, category, new_deadline).into())
+}
+
+#[wasm_bindgen(js_name = buildCoAuthorReveal)]
+pub fn build_co_author_reveal(
+    cluster_offset: u32,
+    payer: &[u8],
+    co_author: &[u8],
+    proposal: &[u8],
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::co_author_reveal(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(co_author)?,
+        &pubkey(proposal)?,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildSetRevealOperator)]
+pub fn build_set_reveal_operator(
+    authority: &[u8],
+    proposal: &[u8],
+    operator: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::set_reveal_operator(&pubkey(authority)?, &pubkey(proposal)?, pubkey(operator)?).into())
+}
+
+#[wasm_bindgen(js_name = buildOperatorReveal)]
+pub fn build_operator_reveal(
+    cluster_offset: u32,
+    payer: &[u8],
+    operator: &[u8],
+    proposal: &[u8],
+    computation_offset: u64,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::operator_reveal(
+        &ArciumEnv::new(cluster_offset),
+        &pubkey(payer)?,
+        &pubkey(operator)?,
+        &pubkey(proposal)?,
+        computation_offset,
+    )
+    .into())
+}
+
+#[wasm_bindgen(js_name = buildEnableAutoReveal)]
+pub fn build_enable_auto_reveal(authority: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::enable_auto_reveal(&pubkey(authority)?, &pubkey(proposal)?).into())
+}
+
+/// `attempt` is the proposal's `auto_reveals`: 0 for the reveal at the
+/// deadline, so the instruction can be scheduled before voting closes.
+#[wasm_bindgen(js_name = buildAutoReveal)]
+pub fn build_auto_reveal(
+    cluster_offset: u32,
+    payer: &[u8],
+    proposal: &[u8],
+    attempt: u32,
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::auto_reveal(&ArciumEnv::new(cluster_offset), &pubkey(payer)?, &pubkey(proposal)?, attempt).into())
+}
+
+#[wasm_bindgen(js_name = autoRevealOffset)]
+pub fn auto_reveal_offset(proposal: &[u8], attempt: u32) -> Result<u64, JsError> {
+    Ok(pda::auto_reveal_offset(&pubkey(proposal)?, attempt))
+}
+
+#[wasm_bindgen(js_name = buildMarkQuorumFailed)]
+pub fn build_mark_quorum_failed(payer: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::mark_quorum_failed(&pubkey(payer)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildExpireReveal)]
+pub fn build_expire_reveal(payer: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::expire_reveal(&pubkey(payer)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildAbortReveal)]
+pub fn build_abort_reveal(authority: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::abort_reveal(&pubkey(authority)?, &pubkey(proposal)?).into())
+}
+
+/// Claim a participation reward into the voter's associated token account;
+/// prepend `buildCreateTokenAccount` if it may not exist yet.
+#[wasm_bindgen(js_name = buildClaimReward)]
+pub fn build_claim_reward(voter: &[u8], proposal: &[u8], mint: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::claim_reward(&pubkey(voter)?, &pubkey(proposal)?, &pubkey(mint)?).into())
+}
+
+/// Settle a funding round's matching pool; the remainder goes to the
+/// authority's associated token account.
+#[wasm_bindgen(js_name = buildSettleMatching)]
+pub fn build_settle_matching(
+    payer: &[u8],
+    proposal: &[u8],
+    authority: &[u8],
+    mint: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::settle_matching(&pubkey(payer)?, &pubkey(proposal)?, &pubkey(authority)?, &pubkey(mint)?).into())
+}
+
+/// Pay an option's match into its recipient's associated token account;
+/// prepend `buildCreateTokenAccount` if it may not exist yet.
+#[wasm_bindgen(js_name = buildPayMatch)]
+pub fn build_pay_match(
+    payer: &[u8],
+    proposal: &[u8],
+    option: u8,
+    recipient: &[u8],
+    mint: &[u8],
+) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::pay_match(&pubkey(payer)?, &pubkey(proposal)?, option, &pubkey(recipient)?, &pubkey(mint)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCreateTokenAccount)]
+pub fn build_create_token_account(payer: &[u8], owner: &[u8], mint: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::create_token_account(&pubkey(payer)?, &pubkey(owner)?, &pubkey(mint)?).into())
+}
+
+/// Claim the "I voted" receipt of a finalized proposal; prepend
+/// `buildCreateReceiptAccount` (and `buildCreateReceiptMint` for the first
+/// claimer).
+#[wasm_bindgen(js_name = buildClaimReceipt)]
+pub fn build_claim_receipt(voter: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::claim_receipt(&pubkey(voter)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCreateReceiptMint)]
+pub fn build_create_receipt_mint(payer: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::create_receipt_mint(&pubkey(payer)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCreateReceiptAccount)]
+pub fn build_create_receipt_account(payer: &[u8], owner: &[u8], proposal: &[u8]) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::create_receipt_account(&pubkey(payer)?, &pubkey(owner)?, &pubkey(proposal)?).into())
+}
+
+#[wasm_bindgen(js_name = buildCloseProposal)]
+pub fn build_close_proposal(payer: &[u8], authority: &[u8], proposal_id: u32) -> Result<BuiltInstruction, JsError> {
+    Ok(instructions::close_proposal(&pubkey(payer)?, &pubkey(authority)?, proposal_id).into())
+}
+
+/// `QuorumKind` by index: 0 voters, 1 registered bps, 2 supply bps, 3
+/// registrar bps.
+fn quorum_kind_from(kind: u8) -> Result<QuorumKind, JsError> {
+    match kind {
+        0 => Ok(QuorumKind::Voters),
+        1 => Ok(QuorumKind::RegisteredBps),
+        2 => Ok(QuorumKind::SupplyBps),
+        3 => Ok(QuorumKind::RegistrarBps),
+        _ => Err(JsError::new("unknown quorum kind")),
+    }
+}
+
+/// `ChamberRule` by index: 0 both must pass, 1 either, 2 weighted with
+/// `council_bps` on the council.  `council_bps` is ignored otherwise.
+/// `SanctionAction` by index: 0 slashes the deposit of the proposal at
+/// `target` for `reason` (`SlashReason` by index), 1 removes the creator
+/// `target`.
+fn sanction_action_from(action: u8, target: &[u8], reason: u8) -> Result<SanctionAction, JsError> {
+    let target = pubkey(target)?;
+    match action {
+        0 => {
+            let reason = match reason {
+                0 => SlashReason::Spam,
+                1 => SlashReason::Duplicate,
+                2 => SlashReason::Abusive,
+                3 => SlashReason::Misleading,
+                4 => SlashReason::Other,
+                _ => return Err(JsError::new("unknown slash reason")),
+            };
+            Ok(SanctionAction::SlashDeposit { proposal: target, reason })
+        }
+        1 => Ok(SanctionAction::RemoveCreator { creator: target }),
+        _ => Err(JsError::new("unknown sanction action")),
+    }
+}
+
+/// `WeightCurve` by index: 0 linear, 1 capped at `cap`, 2 square root, 3
+/// log.  `cap` is ignored otherwise.
+fn weight_curve_from(curve: u8, cap: u64) -> Result<WeightCurve, JsError> {
+    match curve {
+        0 => Ok(WeightCurve::Linear),
+        1 => Ok(WeightCurve::Cap { max: cap }),
+        2 => Ok(WeightCurve::Sqrt),
+        3 => Ok(WeightCurve::Log),
+        _ => Err(JsError::new("unknown weight curve")),
+    }
+}
+
+fn chamber_rule_from(rule: u8, council_bps: u16) -> Result<ChamberRule, JsError> {
+    match rule {
+        0 => Ok(ChamberRule::BothMustPass),
+        1 => Ok(ChamberRule::Either),
+        2 => Ok(ChamberRule::Weighted { council_bps }),
+        _ => Err(JsError::new("unknown chamber rule")),
+    }
+}
+
+/// `BallotKind` by index: 0 quadratic, 1 Likert, 2 For/Against/Abstain
+/// with `param` as `veto_bps`, 3 pairwise with `param` as `discount`, 4
+/// time-weighted with `param` as `start_pct << 16 | end_pct`, 5 jury with
+/// `param` as `criteria << 8 | max_score`, 6 approval with `param` as
+/// `max_choices`, 7 ranked-choice, 8 Borda count, 9 Condorcet, 10 STAR,
+/// 11 range.  `param` is ignored otherwise.
+fn ballot_kind_from(kind: u8, param: u32) -> Result<BallotKind, JsError> {
+    match kind {
+        0 => Ok(BallotKind::Quadratic),
+        1 => Ok(BallotKind::Likert),
+        2 => Ok(BallotKind::YesNoAbstain {
+            veto_bps: u16::try_from(param).map_err(|_| JsError::new("veto_bps out of range"))?,
+        }),
+        3 => Ok(BallotKind::Pairwise { discount: param }),
+        4 => Ok(BallotKind::TimeWeighted {
+            start_pct: (param >> 16) as u16,
+            end_pct: param as u16,
+        }),
+        5 => Ok(BallotKind::Jury {
+            criteria: (param >> 8) as u8,
+            max_score: param as u8,
+        }),
+        6 => Ok(BallotKind::Approval {
+            max_choices: u8::try_from(param).map_err(|_| JsError::new("max_choices out of range"))?,
+        }),
+        7 => Ok(BallotKind::Ranked),
+        8 => Ok(BallotKind::Borda),
+        9 => Ok(BallotKind::Condorcet),
+        10 => Ok(BallotKind::Star),
+        11 => Ok(BallotKind::Range),
+        _ => Err(JsError::new("unknown ballot kind")),
+    }
+}
+
+fn allocation(votes: &[u64]) -> Result<VoteAllocation, JsError> {
+    if votes.len() > 4 {
+        return Err(JsError::new("at most 4 options are supported"));
+    }
+    let mut padded = [0u64; 4];
+    padded[..votes.len()].copy_from_slice(votes);
+    Ok(VoteAllocation::new(padded))
+}
+
+fn bytes32(bytes: &[u8]) -> Result<[u8; 32], JsError> {
+    bytes
+        .try_into()
+        .map_err(|_| JsError::new("expected 32 bytes"))
+}
+
+fn byte_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], JsError> {
+    bytes
+        .try_into()
+        .map_err(|_| JsError::new(&format!("expected {N} bytes")))
+}
+
+fn registrar_key(secret: &[u8]) -> Result<RegistrarKey, JsError> {
+    RegistrarKey::from_secret(bytes32(secret)?).ok_or_else(|| JsError::new("registrar secret is zero"))
+}
+
+fn credential_request(proposal: &[u8], serial: &[u8], blinding: &[u8]) -> Result<CredentialRequest, JsError> {
+    CredentialRequest::new(pubkey(proposal)?, bytes32(serial)?, bytes32(blinding)?)
+        .ok_or_else(|| JsError::new("blinding factor is zero"))
+}
+
+fn pubkey(bytes: &[u8]) -> Result<Pubkey, JsError> {
+    Ok(Pubkey::new_from_array(bytes32(bytes)?))
+}
+
+// `JsError` needs a JS host, so only the happy paths run natively.
+#[cfg(test)]
+mod tests {
+    use arcvote_client::encryption::Cipher;
+
+    use super::*;
+
+    const SIGNATURE: [u8; 64] = [0x51; 64];
+
+    fn mxe_public_key() -> [u8; 32] {
+        EncryptionKeypair::from_secret([0x4d; 32]).public_key()
+    }
+
+    #[test]
+    fn encrypt_vote_matches_the_sdk() {
+        let nonce = [0xa0; 16];
+        let ballot = encrypt_vote(&SIGNATURE, &mxe_public_key(), &[7, 3, 1], &nonce).unwrap();
+        let keypair = EncryptionKeypair::from_signature(&SIGNATURE);
+        let expected = encryption::encrypt_vote(
+            &keypair,
+            &mxe_public_key(),
+            &VoteAllocation::new([7, 3, 1, 0]),
+            nonce,
+        );
+        assert_eq!(ballot.ciphertexts(), expected.ciphertexts.concat());
+        assert_eq!(ballot.public_key(), encryption_public_key(&SIGNATURE));
+        assert_eq!(ballot.nonce(), u128::from_le_bytes(nonce));
+    }
+
+    #[test]
+    fn the_mxe_decrypts_a_wasm_ballot() {
+        let ballot = encrypt_vote(&SIGNATURE, &mxe_public_key(), &[10, 1, 0, 0], &[0xa3; 16]).unwrap();
+        let mxe = EncryptionKeypair::from_secret([0x4d; 32]);
+        let cipher = Cipher::new(&mxe.shared_secret(&bytes32(&ballot.public_key()).unwrap()));
+        let ciphertexts: Vec<[u8; 32]> = ballot
+            .ciphertexts()
+            .chunks(32)
+            .map(|c| c.try_into().unwrap())
+            .collect();
+        let votes: Vec<u64> = cipher
+            .decrypt(&ciphertexts, ballot.nonce())
+            .unwrap()
+            .iter()
+            .map(|v| u64::from_le_bytes(v[..8].try_into().unwrap()))
+            .collect();
+        assert_eq!(votes, [10, 1, 0, 0]);
+    }
+
+    #[test]
+    fn credits_round_trip() {
+        let credits = encrypt_credits(&SIGNATURE, &mxe_public_key(), u64::MAX, &[0xc0; 16]).unwrap();
+        let cipher = Cipher::new(&EncryptionKeypair::from_signature(&SIGNATURE).shared_secret(&mxe_public_key()));
+        let plaintext = cipher.decrypt(&[bytes32(&credits.ciphertext()).unwrap()], credits.nonce()).unwrap();
+        assert_eq!(plaintext[0][..8], u64::MAX.to_le_bytes());
+        assert!(plaintext[0][8..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn identity_round_trips() {
+        let wallet = [0x77; 32];
+        let sealed = encrypt_identity(&SIGNATURE, &mxe_public_key(), &wallet, &[0xb0; 16]).unwrap();
+        let ciphertexts = [
+            bytes32(&sealed.ciphertexts()[..32]).unwrap(),
+            bytes32(&sealed.ciphertexts()[32..]).unwrap(),
+        ];
+        let decrypted = encryption::decrypt_identity(
+            &EncryptionKeypair::from_signature(&SIGNATURE),
+            &mxe_public_key(),
+            &ciphertexts,
+            sealed.nonce(),
+        );
+        assert_eq!(decrypted, Some(Pubkey::new_from_array(wallet)));
+    }
+
+    #[test]
+    fn quadratic_cost_flags_over_budget_and_overflow() {
+        // The vectors' over-budget ballot, one credit past the README's 100.
+        assert_eq!(quadratic_cost(&[10, 1]).unwrap(), Some(101));
+        assert_eq!(quadratic_cost(&[7, 3, 1, 0]).unwrap(), Some(59));
+        assert_eq!(quadratic_cost(&[1 << 32]).unwrap(), None);
+        assert_eq!(quadratic_cost(&[u64::from(u32::MAX); 2]).unwrap(), None);
+    }
+}
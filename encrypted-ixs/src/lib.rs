@@ -15,7 +15,8 @@ mod circuits {
 
     /// A voter's quadratic credit allocation across options.
     /// Each field is the number of effective votes for that option.
-    /// Quadratic cost: v0² + v1² + v2² + v3² must be ≤ 100 voice credits.
+    /// Quadratic cost: v0² + v1² + v2² + v3² must be within the voter's
+    /// voice credits.
     pub struct VoteAllocation {
         v0: u64,
         v1: u64,
@@ -39,18 +40,81 @@ mod circuits {
     /// Cast a quadratic vote.
     ///
     /// The MPC cluster computes v0² + v1² + v2² + v3² and only counts the
-    /// vote if the total cost ≤ 100 voice credits.  Individual allocations
-    /// are never revealed — only aggregated tallies.
+    /// vote if the total cost ≤ the plaintext `budget`: the proposal's
+    /// `voice_credits`, or the voter's registered square-root credits.  Individual
+    /// allocations are never revealed — only aggregated tallies.
+    ///
+    /// Counted votes are multiplied by the plaintext `weight`: 1 for a plain
+    /// quadratic proposal, or the time-weighted proposal's percentage for
+    /// the moment the ballot was cast.  The budget applies before weighting.
+    ///
+    /// `weight` is further multiplied by `stake`, the eligibility oracle's
+    /// weight for the voter times their governance token balance (each 1
+    /// without one), after the proposal's weight curve: 0 linear, 1 capped
+    /// at `cap`, 2 its integer square root, 3 its bit length, roughly
+    /// 1 + log2.  `cap` is ignored otherwise.
+    ///
+    /// Votes for options at or past the proposal's plaintext `options` are
+    /// zeroed before anything else, so they neither cost credits nor reach
+    /// the tallies.
     ///
     /// MPC executes both branches of the budget check (no information leakage).
+    #[allow(clippy::too_many_arguments)]
     #[instruction]
     pub fn cast_vote(
         alloc_ctxt: Enc<Shared, VoteAllocation>,
         tallies_ctxt: Enc<Mxe, VoteTallies>,
+        weight: u64,
+        budget: u64,
+        stake: u64,
+        curve: u64,
+        cap: u64,
+        options: u64,
     ) -> Enc<Mxe, VoteTallies> {
-        let alloc = alloc_ctxt.to_arcis();
+        let mut alloc = alloc_ctxt.to_arcis();
         let mut tallies = tallies_ctxt.to_arcis();
 
+        if options <= 3u64 {
+            alloc.v3 = 0u64;
+        }
+        if options <= 2u64 {
+            alloc.v2 = 0u64;
+        }
+        if options <= 1u64 {
+            alloc.v1 = 0u64;
+        }
+
+        // Integer square root, one bit at a time from the top: any u64
+        // root fits in 32 bits, and so its square in a u64.
+        let mut root = 0u64;
+        let mut bit = 2147483648u64;
+        for _ in 0..32 {
+            let candidate = root + bit;
+            if candidate * candidate <= stake {
+                root = candidate;
+            }
+            bit /= 2u64;
+        }
+        // Bit length: 1 for a stake of 1, 64 for the largest.
+        let mut bits = 0u64;
+        let mut rest = stake;
+        for _ in 0..64 {
+            if rest > 0u64 {
+                bits += 1u64;
+            }
+            rest /= 2u64;
+        }
+        let curved = if curve == 1u64 {
+            if stake < cap { stake } else { cap }
+        } else if curve == 2u64 {
+            root
+        } else if curve == 3u64 {
+            bits
+        } else {
+            stake
+        };
+        let weight = weight * curved;
+
         // Quadratic cost — sum of squares
         let cost = alloc.v0 * alloc.v0
                  + alloc.v1 * alloc.v1
@@ -58,17 +122,569 @@ mod circuits {
                  + alloc.v3 * alloc.v3;
 
         // Budget enforcement inside MPC
-        if cost <= 100u64 {
-            tallies.option_0 += alloc.v0;
-            tallies.option_1 += alloc.v1;
-            tallies.option_2 += alloc.v2;
-            tallies.option_3 += alloc.v3;
-            tallies.total_votes += alloc.v0 + alloc.v1 + alloc.v2 + alloc.v3;
+        if cost <= budget {
+            tallies.option_0 += alloc.v0 * weight;
+            tallies.option_1 += alloc.v1 * weight;
+            tallies.option_2 += alloc.v2 * weight;
+            tallies.option_3 += alloc.v3 * weight;
+            tallies.total_votes += (alloc.v0 + alloc.v1 + alloc.v2 + alloc.v3) * weight;
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// Fold a batch of up to four buffered quadratic ballots into the
+    /// tallies in one computation.
+    ///
+    /// Each ballot is counted as `cast_vote` counts one with a stake of 1:
+    /// votes for options at or past `options` are zeroed, and the rest are
+    /// added times the ballot's plaintext `weight_i` if their quadratic
+    /// cost is within its `budget_i`.  A batch short of four ballots fills
+    /// the empty slots with a copy of its first ballot at weight 0, which
+    /// adds nothing.
+    #[allow(clippy::too_many_arguments, clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_vote_batch(
+        first_ctxt: Enc<Shared, VoteAllocation>,
+        second_ctxt: Enc<Shared, VoteAllocation>,
+        third_ctxt: Enc<Shared, VoteAllocation>,
+        fourth_ctxt: Enc<Shared, VoteAllocation>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        weight_0: u64,
+        weight_1: u64,
+        weight_2: u64,
+        weight_3: u64,
+        budget_0: u64,
+        budget_1: u64,
+        budget_2: u64,
+        budget_3: u64,
+        options: u64,
+    ) -> Enc<Mxe, VoteTallies> {
+        let first = first_ctxt.to_arcis();
+        let second = second_ctxt.to_arcis();
+        let third = third_ctxt.to_arcis();
+        let fourth = fourth_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        let ballots = [
+            [first.v0, first.v1, first.v2, first.v3],
+            [second.v0, second.v1, second.v2, second.v3],
+            [third.v0, third.v1, third.v2, third.v3],
+            [fourth.v0, fourth.v1, fourth.v2, fourth.v3],
+        ];
+        let weights = [weight_0, weight_1, weight_2, weight_3];
+        let budgets = [budget_0, budget_1, budget_2, budget_3];
+
+        for i in 0..4 {
+            let v0 = ballots[i][0];
+            let v1 = if options <= 1u64 { 0u64 } else { ballots[i][1] };
+            let v2 = if options <= 2u64 { 0u64 } else { ballots[i][2] };
+            let v3 = if options <= 3u64 { 0u64 } else { ballots[i][3] };
+            let cost = v0 * v0 + v1 * v1 + v2 * v2 + v3 * v3;
+            if cost <= budgets[i] {
+                let weight = weights[i];
+                tallies.option_0 += v0 * weight;
+                tallies.option_1 += v1 * weight;
+                tallies.option_2 += v2 * weight;
+                tallies.option_3 += v3 * weight;
+                tallies.total_votes += (v0 + v1 + v2 + v3) * weight;
+            }
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// A voter's voice credits: banked with one proposal authority, or
+    /// their encrypted budget on one proposal.
+    pub struct CreditBalance {
+        credits: u64,
+    }
+
+    /// Initialize an empty credit bank.
+    #[instruction]
+    pub fn init_credit_bank(mxe: Mxe) -> Enc<Mxe, CreditBalance> {
+        mxe.from_arcis(CreditBalance { credits: 0 })
+    }
+
+    /// Cast a quadratic vote against the voter's banked credits.
+    ///
+    /// The proposal's plaintext `grant` joins the balance first.  A ballot
+    /// whose quadratic cost fits the result is counted as `cast_vote`
+    /// counts one with a stake of 1, and its cost leaves the bank; what is
+    /// left carries over to the voter's next banked ballot.  The balance
+    /// is re-encrypted either way, so neither it nor the spend is revealed.
+    #[instruction]
+    pub fn cast_vote_banked(
+        alloc_ctxt: Enc<Shared, VoteAllocation>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        bank_ctxt: Enc<Mxe, CreditBalance>,
+        weight: u64,
+        grant: u64,
+        options: u64,
+    ) -> (Enc<Mxe, VoteTallies>, Enc<Mxe, CreditBalance>) {
+        let alloc = alloc_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+        let mut bank = bank_ctxt.to_arcis();
+
+        let v0 = alloc.v0;
+        let v1 = if options <= 1u64 { 0u64 } else { alloc.v1 };
+        let v2 = if options <= 2u64 { 0u64 } else { alloc.v2 };
+        let v3 = if options <= 3u64 { 0u64 } else { alloc.v3 };
+        let cost = v0 * v0 + v1 * v1 + v2 * v2 + v3 * v3;
+
+        bank.credits += grant;
+        if cost <= bank.credits {
+            tallies.option_0 += v0 * weight;
+            tallies.option_1 += v1 * weight;
+            tallies.option_2 += v2 * weight;
+            tallies.option_3 += v3 * weight;
+            tallies.total_votes += (v0 + v1 + v2 + v3) * weight;
+            bank.credits -= cost;
+        }
+
+        (
+            tallies_ctxt.owner.from_arcis(tallies),
+            bank_ctxt.owner.from_arcis(bank),
+        )
+    }
+
+    /// Re-encrypt a voter's credit budget from the authority's key to the
+    /// cluster's, so only the authority ever sees the amount.
+    #[instruction]
+    pub fn seal_budget(budget_ctxt: Enc<Shared, CreditBalance>, mxe: Mxe) -> Enc<Mxe, CreditBalance> {
+        mxe.from_arcis(budget_ctxt.to_arcis())
+    }
+
+    /// Cast a quadratic vote against the voter's encrypted budget.
+    ///
+    /// A ballot whose quadratic cost fits the budget is counted as
+    /// `cast_vote` counts one with a stake of 1, and its cost leaves the
+    /// budget.  The budget is re-encrypted either way, so neither it nor
+    /// the spend is revealed.
+    #[instruction]
+    pub fn cast_vote_budgeted(
+        alloc_ctxt: Enc<Shared, VoteAllocation>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        budget_ctxt: Enc<Mxe, CreditBalance>,
+        weight: u64,
+        options: u64,
+    ) -> (Enc<Mxe, VoteTallies>, Enc<Mxe, CreditBalance>) {
+        let alloc = alloc_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+        let mut budget = budget_ctxt.to_arcis();
+
+        let v0 = alloc.v0;
+        let v1 = if options <= 1u64 { 0u64 } else { alloc.v1 };
+        let v2 = if options <= 2u64 { 0u64 } else { alloc.v2 };
+        let v3 = if options <= 3u64 { 0u64 } else { alloc.v3 };
+        let cost = v0 * v0 + v1 * v1 + v2 * v2 + v3 * v3;
+
+        if cost <= budget.credits {
+            tallies.option_0 += v0 * weight;
+            tallies.option_1 += v1 * weight;
+            tallies.option_2 += v2 * weight;
+            tallies.option_3 += v3 * weight;
+            tallies.total_votes += (v0 + v1 + v2 + v3) * weight;
+            budget.credits -= cost;
+        }
+
+        (
+            tallies_ctxt.owner.from_arcis(tallies),
+            budget_ctxt.owner.from_arcis(budget),
+        )
+    }
+
+    /// A voter's Likert scores, one per option.  Each is the -2..+2 score
+    /// plus 2, so 0 is "strongly against" and 4 "strongly for".
+    pub struct LikertScores {
+        s0: u64,
+        s1: u64,
+        s2: u64,
+        s3: u64,
+    }
+
+    /// Cast a Likert ballot.
+    ///
+    /// Adds each offset score to its option's counter and counts the ballot
+    /// in `total_votes`, so option_i - 2 * total_votes is the net sentiment.
+    /// A ballot with any score above 4 is discarded whole.
+    #[instruction]
+    pub fn cast_likert(
+        scores_ctxt: Enc<Shared, LikertScores>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+    ) -> Enc<Mxe, VoteTallies> {
+        let scores = scores_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        if scores.s0 <= 4u64 && scores.s1 <= 4u64 && scores.s2 <= 4u64 && scores.s3 <= 4u64 {
+            tallies.option_0 += scores.s0;
+            tallies.option_1 += scores.s1;
+            tallies.option_2 += scores.s2;
+            tallies.option_3 += scores.s3;
+            tallies.total_votes += 1u64;
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// A For/Against/Abstain ballot, one-hot: exactly one of c0 (For), c1
+    /// (Against) and c2 (Abstain) is 1, and c3 is unused.
+    pub struct Choice {
+        c0: u64,
+        c1: u64,
+        c2: u64,
+        c3: u64,
+    }
+
+    /// Cast a For/Against/Abstain ballot.
+    ///
+    /// Adds one to the chosen option and counts the ballot in
+    /// `total_votes`.  A ballot that is not one-hot over the first three
+    /// options is discarded whole.
+    #[instruction]
+    pub fn cast_choice(
+        choice_ctxt: Enc<Shared, Choice>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+    ) -> Enc<Mxe, VoteTallies> {
+        let choice = choice_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        let one_hot = choice.c0 <= 1u64
+            && choice.c1 <= 1u64
+            && choice.c2 <= 1u64
+            && choice.c3 == 0u64
+            && choice.c0 + choice.c1 + choice.c2 == 1u64;
+        if one_hot {
+            tallies.option_0 += choice.c0;
+            tallies.option_1 += choice.c1;
+            tallies.option_2 += choice.c2;
+            tallies.total_votes += 1u64;
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// Cast a For/Against/Abstain ballot on a proposal whose ballot keeps
+    /// round records.
+    ///
+    /// Counts exactly as `cast_choice` does; the circuit is separate so its
+    /// callback can mark the voter's round record instead of a voter
+    /// record.
+    #[instruction]
+    pub fn cast_round_choice(
+        choice_ctxt: Enc<Shared, Choice>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+    ) -> Enc<Mxe, VoteTallies> {
+        let choice = choice_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        let one_hot = choice.c0 <= 1u64
+            && choice.c1 <= 1u64
+            && choice.c2 <= 1u64
+            && choice.c3 == 0u64
+            && choice.c0 + choice.c1 + choice.c2 == 1u64;
+        if one_hot {
+            tallies.option_0 += choice.c0;
+            tallies.option_1 += choice.c1;
+            tallies.option_2 += choice.c2;
+            tallies.total_votes += 1u64;
         }
 
         tallies_ctxt.owner.from_arcis(tallies)
     }
 
+    /// For/Against/Abstain ballots on the proposals of one
+    /// `cast_ballot_choices`, in its order: `choice[i]` is 0 (For),
+    /// 1 (Against) or 2 (Abstain) on the `i`-th.  One value per proposal
+    /// rather than a `Choice` each, so all eight fit a transaction.
+    pub struct BallotChoices {
+        choice: [u64; 8],
+    }
+
+    /// Cast For/Against/Abstain ballots on up to eight proposals of one
+    /// ballot in a single computation.
+    ///
+    /// Each of the first `proposals` choices is counted into its own
+    /// proposal's tallies as `cast_choice` counts the matching one-hot
+    /// ballot, and a choice past Abstain is discarded without affecting
+    /// the others.  A call on fewer than eight proposals fills the empty
+    /// slots with a copy of its first proposal's tallies, which pass
+    /// through untouched and are dropped by the callback.
+    #[allow(clippy::too_many_arguments, clippy::needless_range_loop, clippy::type_complexity)]
+    #[instruction]
+    pub fn cast_ballot_choices(
+        choices_ctxt: Enc<Shared, BallotChoices>,
+        tallies_0: Enc<Mxe, VoteTallies>,
+        tallies_1: Enc<Mxe, VoteTallies>,
+        tallies_2: Enc<Mxe, VoteTallies>,
+        tallies_3: Enc<Mxe, VoteTallies>,
+        tallies_4: Enc<Mxe, VoteTallies>,
+        tallies_5: Enc<Mxe, VoteTallies>,
+        tallies_6: Enc<Mxe, VoteTallies>,
+        tallies_7: Enc<Mxe, VoteTallies>,
+        proposals: u64,
+    ) -> (
+        Enc<Mxe, VoteTallies>,
+        Enc<Mxe, VoteTallies>,
+        Enc<Mxe, VoteTallies>,
+        Enc<Mxe, VoteTallies>,
+        Enc<Mxe, VoteTallies>,
+        Enc<Mxe, VoteTallies>,
+        Enc<Mxe, VoteTallies>,
+        Enc<Mxe, VoteTallies>,
+    ) {
+        let choices = choices_ctxt.to_arcis();
+        let mut races = [
+            tallies_0.to_arcis(),
+            tallies_1.to_arcis(),
+            tallies_2.to_arcis(),
+            tallies_3.to_arcis(),
+            tallies_4.to_arcis(),
+            tallies_5.to_arcis(),
+            tallies_6.to_arcis(),
+            tallies_7.to_arcis(),
+        ];
+
+        for i in 0..8 {
+            let c = choices.choice[i];
+            if (i as u64) < proposals && c <= 2u64 {
+                races[i].option_0 += if c == 0u64 { 1u64 } else { 0u64 };
+                races[i].option_1 += if c == 1u64 { 1u64 } else { 0u64 };
+                races[i].option_2 += if c == 2u64 { 1u64 } else { 0u64 };
+                races[i].total_votes += 1u64;
+            }
+        }
+
+        let [race_0, race_1, race_2, race_3, race_4, race_5, race_6, race_7] = races;
+        (
+            tallies_0.owner.from_arcis(race_0),
+            tallies_1.owner.from_arcis(race_1),
+            tallies_2.owner.from_arcis(race_2),
+            tallies_3.owner.from_arcis(race_3),
+            tallies_4.owner.from_arcis(race_4),
+            tallies_5.owner.from_arcis(race_5),
+            tallies_6.owner.from_arcis(race_6),
+            tallies_7.owner.from_arcis(race_7),
+        )
+    }
+
+    /// An approval ballot: `approved[i]` is 1 to approve option `i`.
+    pub struct Approvals {
+        approved: [u64; 4],
+    }
+
+    /// Cast an approval ballot.
+    ///
+    /// Adds one to every approved option and counts the ballot in
+    /// `total_votes`.  A ballot approving more than `max_choices` options,
+    /// any option from `options` on, or with an entry above 1 is discarded
+    /// whole.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_approval(
+        approvals_ctxt: Enc<Shared, Approvals>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        max_choices: u64,
+        options: u64,
+    ) -> Enc<Mxe, VoteTallies> {
+        let ballot = approvals_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        let mut valid = true;
+        let mut chosen = 0u64;
+        for i in 0..4 {
+            let cap = if (i as u64) < options { 1u64 } else { 0u64 };
+            if ballot.approved[i] > cap {
+                valid = false;
+            }
+            chosen += ballot.approved[i];
+        }
+        if valid && chosen <= max_choices {
+            tallies.option_0 += ballot.approved[0];
+            tallies.option_1 += ballot.approved[1];
+            tallies.option_2 += ballot.approved[2];
+            tallies.option_3 += ballot.approved[3];
+            tallies.total_votes += 1u64;
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// Seal a quadratic ballot for a pairwise-discounted round.
+    ///
+    /// The allocation is budget-checked exactly like `cast_vote` but kept
+    /// whole, re-encrypted to the cluster, because the pairwise discount
+    /// needs every pair of ballots at reveal.  A ballot over the plaintext
+    /// `budget`, the proposal's `voice_credits`, is sealed as all zeros.
+    /// Votes for options at or past `options` are zeroed first, as in
+    /// `cast_vote`.
+    #[instruction]
+    pub fn cast_pairwise(
+        alloc_ctxt: Enc<Shared, VoteAllocation>,
+        mxe: Mxe,
+        budget: u64,
+        options: u64,
+    ) -> Enc<Mxe, VoteAllocation> {
+        let mut alloc = alloc_ctxt.to_arcis();
+
+        if options <= 3u64 {
+            alloc.v3 = 0u64;
+        }
+        if options <= 2u64 {
+            alloc.v2 = 0u64;
+        }
+        if options <= 1u64 {
+            alloc.v1 = 0u64;
+        }
+
+        let cost = alloc.v0 * alloc.v0
+                 + alloc.v1 * alloc.v1
+                 + alloc.v2 * alloc.v2
+                 + alloc.v3 * alloc.v3;
+        if cost > budget {
+            alloc = VoteAllocation { v0: 0, v1: 0, v2: 0, v3: 0 };
+        }
+
+        mxe.from_arcis(alloc)
+    }
+
+    /// A juror's scores, criterion-major: `scores[c * 4 + a]` scores
+    /// application (option) `a` on criterion `c`, for up to 3 criteria.
+    pub struct JuryScores {
+        scores: [u64; 12],
+    }
+
+    /// Cast a juror's scores.
+    ///
+    /// Every score must be at most `max_score`, and those for criteria
+    /// from `criteria` on or applications from `applications` on must be
+    /// 0, or the whole ballot is discarded.  Each application's scores are
+    /// summed over the criteria into its counter and the juror is counted
+    /// in `total_votes`, so no juror's or criterion's score is revealed.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_jury_scores(
+        scores_ctxt: Enc<Shared, JuryScores>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        max_score: u64,
+        criteria: u64,
+        applications: u64,
+    ) -> Enc<Mxe, VoteTallies> {
+        let jury = scores_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        let mut valid = true;
+        let mut totals = [0u64; 4];
+        for c in 0..3 {
+            for a in 0..4 {
+                let cap = if (c as u64) < criteria && (a as u64) < applications {
+                    max_score
+                } else {
+                    0u64
+                };
+                let score = jury.scores[c * 4 + a];
+                if score > cap {
+                    valid = false;
+                }
+                totals[a] += score;
+            }
+        }
+        if valid {
+            tallies.option_0 += totals[0];
+            tallies.option_1 += totals[1];
+            tallies.option_2 += totals[2];
+            tallies.option_3 += totals[3];
+            tallies.total_votes += 1u64;
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// A write-in vote: the voter's hash of a candidate identifier,
+    /// truncated to 128 bits.  0 is reserved for an empty slot.
+    pub struct WriteIn {
+        candidate: u128,
+    }
+
+    /// Encrypted write-in tallies: up to 4 distinct candidate hashes, each
+    /// with its vote count.  A slot whose hash is 0 is free.
+    pub struct WriteIns {
+        hashes: [u128; 4],
+        counts: [u64; 4],
+    }
+
+    /// Initialize an empty write-in table.
+    #[instruction]
+    pub fn init_write_ins(mxe: Mxe) -> Enc<Mxe, WriteIns> {
+        let write_ins = WriteIns {
+            hashes: [0u128; 4],
+            counts: [0u64; 4],
+        };
+        mxe.from_arcis(write_ins)
+    }
+
+    /// Cast a write-in vote.
+    ///
+    /// Adds one to the slot already holding the candidate's hash, or else
+    /// claims the first free slot for it.  Once all 4 slots hold other
+    /// candidates, or for a hash of 0, the vote is discarded.  Which slot
+    /// matched is never revealed.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_write_in(
+        write_in_ctxt: Enc<Shared, WriteIn>,
+        write_ins_ctxt: Enc<Mxe, WriteIns>,
+    ) -> Enc<Mxe, WriteIns> {
+        let write_in = write_in_ctxt.to_arcis();
+        let mut write_ins = write_ins_ctxt.to_arcis();
+
+        let mut placed = write_in.candidate == 0u128;
+        for i in 0..4 {
+            if !placed && write_ins.hashes[i] == write_in.candidate {
+                write_ins.counts[i] += 1u64;
+                placed = true;
+            }
+        }
+        for i in 0..4 {
+            if !placed && write_ins.hashes[i] == 0u128 {
+                write_ins.hashes[i] = write_in.candidate;
+                write_ins.counts[i] = 1u64;
+                placed = true;
+            }
+        }
+
+        write_ins_ctxt.owner.from_arcis(write_ins)
+    }
+
+    /// Plaintext top write-in.
+    pub struct WriteInResult {
+        candidate: u128,
+        votes: u64,
+    }
+
+    /// Reveal the write-in with the most votes, the earliest written in on
+    /// a tie.  Every other hash and count stays encrypted.  `candidate` is
+    /// 0 when nobody wrote anyone in.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn reveal_write_in(write_ins_ctxt: Enc<Mxe, WriteIns>) -> WriteInResult {
+        let write_ins = write_ins_ctxt.to_arcis();
+
+        let mut candidate = write_ins.hashes[0];
+        let mut votes = write_ins.counts[0];
+        for i in 1..4 {
+            if write_ins.counts[i] > votes {
+                candidate = write_ins.hashes[i];
+                votes = write_ins.counts[i];
+            }
+        }
+
+        WriteInResult {
+            candidate: candidate.reveal(),
+            votes: votes.reveal(),
+        }
+    }
+
     /// Plaintext results returned after reveal.
     pub struct RevealedResults {
         option_0: u64,
@@ -96,7 +712,6 @@ mod circuits {
             winner = 2;
         }
         if tallies.option_3 > max_votes {
-            max_votes = tallies.option_3;
             winner = 3;
         }
 
@@ -109,4 +724,1067 @@ mod circuits {
             winner: winner.reveal(),
         }
     }
+
+    /// Plaintext For/Against/Abstain results with the governance outcome.
+    pub struct OutcomeResults {
+        option_0: u64,
+        option_1: u64,
+        option_2: u64,
+        option_3: u64,
+        total_votes: u64,
+        winner: u8,
+        outcome: u8,
+    }
+
+    /// Reveal a For/Against/Abstain tally and decide it.
+    ///
+    /// Against above `veto_bps` basis points of turnout (all three options)
+    /// vetoes the proposal whatever For got; otherwise it passes when For's
+    /// share of For and Against is over `pass_bps` (5,000, or a fast-track
+    /// supermajority) plus `bias_bps` times the share of the `electorate`
+    /// that stayed home.  A full turnout needs `pass_bps`, an empty one
+    /// `pass_bps` plus `bias_bps`, so a handful of voters can't quietly
+    /// carry a vote nobody else showed up for.  `outcome` is 0 passed, 1
+    /// rejected, 2 vetoed; a `veto_bps` of 0 disables the veto and a
+    /// `bias_bps` of 0 the bias.
+    ///
+    /// F/(F + A) > p/10000 + b·(E - T)/(10000·E) is multiplied out by
+    /// 10000·E·(F + A).  Ballots count one each and the electorate is
+    /// capped at a million, so nothing overflows.
+    #[instruction]
+    pub fn reveal_outcome(
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        veto_bps: u64,
+        pass_bps: u64,
+        bias_bps: u64,
+        electorate: u64,
+    ) -> OutcomeResults {
+        let tallies = tallies_ctxt.to_arcis();
+
+        let mut max_votes = tallies.option_0;
+        let mut winner: u8 = 0;
+        if tallies.option_1 > max_votes {
+            max_votes = tallies.option_1;
+            winner = 1;
+        }
+        if tallies.option_2 > max_votes {
+            winner = 2;
+        }
+
+        let mut absent = 0u64;
+        if tallies.total_votes < electorate {
+            absent = electorate - tallies.total_votes;
+        }
+        let mut outcome: u8 = 1;
+        if tallies.option_0 * 10_000u64 * electorate
+            > (pass_bps * electorate + bias_bps * absent) * (tallies.option_0 + tallies.option_1)
+        {
+            outcome = 0;
+        }
+        if veto_bps > 0u64 && tallies.option_1 * 10_000u64 > veto_bps * tallies.total_votes {
+            outcome = 2;
+        }
+
+        OutcomeResults {
+            option_0: tallies.option_0.reveal(),
+            option_1: tallies.option_1.reveal(),
+            option_2: tallies.option_2.reveal(),
+            option_3: tallies.option_3.reveal(),
+            total_votes: tallies.total_votes.reveal(),
+            winner: winner.reveal(),
+            outcome: outcome.reveal(),
+        }
+    }
+
+    /// Plaintext budget-box results with the funded set.
+    pub struct BudgetBoxResults {
+        option_0: u64,
+        option_1: u64,
+        option_2: u64,
+        option_3: u64,
+        total_votes: u64,
+        winner: u8,
+        funded: u8,
+    }
+
+    /// Reveal a budget box and pick what it funds.
+    ///
+    /// Tries every subset of the options, keeps those whose costs fit in
+    /// `budget`, and funds the one with the most approvals in total, the
+    /// smallest (by bitmask) on a tie, so an option nobody approved is
+    /// never funded just because it fits.  `funded` has bit i set when
+    /// option i is funded; an option past the proposal's last costs 0 and
+    /// has no approvals, so it never changes the choice.
+    #[allow(clippy::too_many_arguments)]
+    #[instruction]
+    pub fn reveal_budget_box(
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        budget: u64,
+        cost_0: u64,
+        cost_1: u64,
+        cost_2: u64,
+        cost_3: u64,
+    ) -> BudgetBoxResults {
+        let tallies = tallies_ctxt.to_arcis();
+
+        let mut max_votes = tallies.option_0;
+        let mut winner: u8 = 0;
+        if tallies.option_1 > max_votes {
+            max_votes = tallies.option_1;
+            winner = 1;
+        }
+        if tallies.option_2 > max_votes {
+            max_votes = tallies.option_2;
+            winner = 2;
+        }
+        if tallies.option_3 > max_votes {
+            winner = 3;
+        }
+
+        // Subsets in bitmask order: x3 outermost, so the mask only grows.
+        let mut best_support = 0u64;
+        let mut funded: u8 = 0;
+        for x3 in 0..2u64 {
+            for x2 in 0..2u64 {
+                for x1 in 0..2u64 {
+                    for x0 in 0..2u64 {
+                        let cost = x0 * cost_0 + x1 * cost_1 + x2 * cost_2 + x3 * cost_3;
+                        let support = x0 * tallies.option_0
+                            + x1 * tallies.option_1
+                            + x2 * tallies.option_2
+                            + x3 * tallies.option_3;
+                        if cost <= budget && support > best_support {
+                            best_support = support;
+                            funded = (x0 + 2 * x1 + 4 * x2 + 8 * x3) as u8;
+                        }
+                    }
+                }
+            }
+        }
+
+        BudgetBoxResults {
+            option_0: tallies.option_0.reveal(),
+            option_1: tallies.option_1.reveal(),
+            option_2: tallies.option_2.reveal(),
+            option_3: tallies.option_3.reveal(),
+            total_votes: tallies.total_votes.reveal(),
+            winner: winner.reveal(),
+            funded: funded.reveal(),
+        }
+    }
+
+    /// Reveal a pairwise-discounted quadratic funding round.
+    ///
+    /// Each option's funding is (Σ v)² expanded as Σ v² plus the cross
+    /// term 2·v_i·v_j of every pair of ballots, with each pair's cross term
+    /// scaled by CLR's M / (M + overlap), where overlap = Σ_p v_ip·v_jp is
+    /// how much the pair backs the same options and M is `discount`.  The
+    /// factor is rounded to the nearest half, which needs two comparisons
+    /// instead of a division: full below M/3, half up to 3M, none beyond.
+    /// A `discount` of 0 leaves every pair at full weight (plain QF).
+    ///
+    /// Only slots whose bit is set in `filled` hold a sealed ballot.
+    /// `total_votes` is the round's total funding.
+    #[allow(clippy::too_many_arguments, clippy::needless_range_loop)]
+    #[instruction]
+    pub fn reveal_pairwise(
+        b0: Enc<Mxe, VoteAllocation>,
+        b1: Enc<Mxe, VoteAllocation>,
+        b2: Enc<Mxe, VoteAllocation>,
+        b3: Enc<Mxe, VoteAllocation>,
+        b4: Enc<Mxe, VoteAllocation>,
+        b5: Enc<Mxe, VoteAllocation>,
+        b6: Enc<Mxe, VoteAllocation>,
+        b7: Enc<Mxe, VoteAllocation>,
+        filled: u64,
+        discount: u64,
+    ) -> RevealedResults {
+        let sealed = [
+            b0.to_arcis(),
+            b1.to_arcis(),
+            b2.to_arcis(),
+            b3.to_arcis(),
+            b4.to_arcis(),
+            b5.to_arcis(),
+            b6.to_arcis(),
+            b7.to_arcis(),
+        ];
+        let mut v = [[0u64; 4]; 8];
+        let mut bit = 1u64;
+        for i in 0..8 {
+            if (filled / bit) % 2u64 == 1u64 {
+                v[i] = [sealed[i].v0, sealed[i].v1, sealed[i].v2, sealed[i].v3];
+            }
+            bit *= 2u64;
+        }
+
+        let mut funding = [0u64; 4];
+        for i in 0..8 {
+            for p in 0..4 {
+                funding[p] += v[i][p] * v[i][p];
+            }
+        }
+        for i in 0..8 {
+            for j in (i + 1)..8 {
+                let mut overlap = 0u64;
+                for p in 0..4 {
+                    overlap += v[i][p] * v[j][p];
+                }
+                // Halves of the pair's factor: 2·v_i·v_j·k = halves·v_i·v_j.
+                let mut halves = 0u64;
+                if discount == 0u64 || overlap * 3u64 <= discount {
+                    halves += 1u64;
+                }
+                if discount == 0u64 || overlap <= discount * 3u64 {
+                    halves += 1u64;
+                }
+                for p in 0..4 {
+                    funding[p] += halves * v[i][p] * v[j][p];
+                }
+            }
+        }
+
+        let mut max_votes = funding[0];
+        let mut winner: u8 = 0;
+        if funding[1] > max_votes {
+            max_votes = funding[1];
+            winner = 1;
+        }
+        if funding[2] > max_votes {
+            max_votes = funding[2];
+            winner = 2;
+        }
+        if funding[3] > max_votes {
+            winner = 3;
+        }
+
+        RevealedResults {
+            option_0: funding[0].reveal(),
+            option_1: funding[1].reveal(),
+            option_2: funding[2].reveal(),
+            option_3: funding[3].reveal(),
+            total_votes: (funding[0] + funding[1] + funding[2] + funding[3]).reveal(),
+            winner: winner.reveal(),
+        }
+    }
+
+    /// A voter's registered wallet: its first and last 16 bytes.
+    pub struct Identity {
+        head: u128,
+        tail: u128,
+    }
+
+    /// Re-encrypt an anonymous voter's sealed identity from the voter's
+    /// key to the compliance key, so only its holder can read who took
+    /// part.  The ballot itself never enters this circuit.
+    #[instruction]
+    pub fn release_identity(
+        identity_ctxt: Enc<Shared, Identity>,
+        compliance: Shared,
+    ) -> Enc<Shared, Identity> {
+        compliance.from_arcis(identity_ctxt.to_arcis())
+    }
+
+    /// Reveal one bit mid-vote: whether the encrypted `total_votes` has
+    /// reached `threshold`.  Neither the tallies nor the total leave the
+    /// cluster.
+    #[instruction]
+    pub fn reveal_quorum_progress(tallies_ctxt: Enc<Mxe, VoteTallies>, threshold: u64) -> bool {
+        let tallies = tallies_ctxt.to_arcis();
+        (tallies.total_votes >= threshold).reveal()
+    }
+
+    /// Counted quadratic ballots per option and credit-spend bucket,
+    /// option-major: ballots that spent 1-9, 16-49 or 64 or more credits
+    /// on the option (1-3, 4-7 or 8 or more votes).  Ballots that left an option
+    /// alone are not bucketed for it.
+    pub struct SpendHistogram {
+        counts: [u64; 12],
+    }
+
+    /// Initialize an empty spend histogram.
+    #[instruction]
+    pub fn init_spend_histogram(mxe: Mxe) -> Enc<Mxe, SpendHistogram> {
+        mxe.from_arcis(SpendHistogram { counts: [0u64; 12] })
+    }
+
+    /// Cast a quadratic vote as `cast_vote` does, and add a ballot within
+    /// the plaintext `budget` to the spend histogram too.  Which buckets moved is never
+    /// revealed.  Votes for options at or past `options` are zeroed first.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_vote_histogram(
+        alloc_ctxt: Enc<Shared, VoteAllocation>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        histogram_ctxt: Enc<Mxe, SpendHistogram>,
+        weight: u64,
+        budget: u64,
+        options: u64,
+    ) -> (Enc<Mxe, VoteTallies>, Enc<Mxe, SpendHistogram>) {
+        let alloc = alloc_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+        let mut histogram = histogram_ctxt.to_arcis();
+
+        let mut votes = [alloc.v0, alloc.v1, alloc.v2, alloc.v3];
+        for i in 0..4 {
+            if (i as u64) >= options {
+                votes[i] = 0u64;
+            }
+        }
+        let cost = votes[0] * votes[0] + votes[1] * votes[1] + votes[2] * votes[2] + votes[3] * votes[3];
+
+        if cost <= budget {
+            tallies.option_0 += votes[0] * weight;
+            tallies.option_1 += votes[1] * weight;
+            tallies.option_2 += votes[2] * weight;
+            tallies.option_3 += votes[3] * weight;
+            tallies.total_votes += (votes[0] + votes[1] + votes[2] + votes[3]) * weight;
+
+            for i in 0..4 {
+                let v = votes[i];
+                if v >= 8u64 {
+                    histogram.counts[3 * i + 2] += 1u64;
+                } else if v >= 4u64 {
+                    histogram.counts[3 * i + 1] += 1u64;
+                } else if v >= 1u64 {
+                    histogram.counts[3 * i] += 1u64;
+                }
+            }
+        }
+
+        (
+            tallies_ctxt.owner.from_arcis(tallies),
+            histogram_ctxt.owner.from_arcis(histogram),
+        )
+    }
+
+    /// Reveal the spend histogram with differential-privacy noise: each
+    /// count gets an independent Binomial(64, 1/2) - 32 (standard deviation
+    /// 4) drawn inside the cluster, then is floored at 0.  One ballot moves
+    /// at most one count per option by one, which the noise hides; the
+    /// exact counts never leave the cluster.
+    // Arcis has no `saturating_sub`.
+    #[allow(clippy::needless_range_loop, clippy::implicit_saturating_sub)]
+    #[instruction]
+    pub fn reveal_spend_histogram(histogram_ctxt: Enc<Mxe, SpendHistogram>) -> [u64; 12] {
+        let histogram = histogram_ctxt.to_arcis();
+
+        let mut noised = [0u64; 12];
+        for i in 0..12 {
+            let mut count = histogram.counts[i];
+            for _ in 0..64 {
+                if ArcisRNG::bool() {
+                    count += 1u64;
+                }
+            }
+            noised[i] = if count >= 32u64 { count - 32u64 } else { 0u64 };
+        }
+
+        noised.reveal()
+    }
+
+    /// Initialize a council chamber's tallies, zeroed as `init_tallies`
+    /// zeroes a proposal's.
+    #[instruction]
+    pub fn init_council_tallies(mxe: Mxe) -> Enc<Mxe, VoteTallies> {
+        let tallies = VoteTallies {
+            option_0: 0,
+            option_1: 0,
+            option_2: 0,
+            option_3: 0,
+            total_votes: 0,
+        };
+        mxe.from_arcis(tallies)
+    }
+
+    /// Cast a council member's For/Against/Abstain ballot on a
+    /// dual-chamber proposal, into the council's own tallies.  As
+    /// `cast_choice`, a ballot that is not one-hot is discarded whole.
+    #[instruction]
+    pub fn cast_council_choice(
+        choice_ctxt: Enc<Shared, Choice>,
+        council_ctxt: Enc<Mxe, VoteTallies>,
+    ) -> Enc<Mxe, VoteTallies> {
+        let choice = choice_ctxt.to_arcis();
+        let mut council = council_ctxt.to_arcis();
+
+        let one_hot = choice.c0 <= 1u64
+            && choice.c1 <= 1u64
+            && choice.c2 <= 1u64
+            && choice.c3 == 0u64
+            && choice.c0 + choice.c1 + choice.c2 == 1u64;
+        if one_hot {
+            council.option_0 += choice.c0;
+            council.option_1 += choice.c1;
+            council.option_2 += choice.c2;
+            council.total_votes += 1u64;
+        }
+
+        council_ctxt.owner.from_arcis(council)
+    }
+
+    /// Plaintext results of a dual-chamber proposal: the community tally,
+    /// its winner and the combined outcome laid out as `OutcomeResults`,
+    /// then the community's own outcome, the council tally and the
+    /// council's outcome.
+    pub struct ChamberResults {
+        option_0: u64,
+        option_1: u64,
+        option_2: u64,
+        option_3: u64,
+        total_votes: u64,
+        winner: u8,
+        outcome: u8,
+        community_outcome: u8,
+        council_0: u64,
+        council_1: u64,
+        council_2: u64,
+        council_3: u64,
+        council_total: u64,
+        council_outcome: u8,
+    }
+
+    /// Reveal both tallies of a dual-chamber proposal and decide it.
+    ///
+    /// Each chamber is decided as `reveal_outcome` decides a proposal,
+    /// against the same `veto_bps`: 0 passed, 1 rejected, 2 vetoed.
+    /// `rule` combines them: 0 passes when both chambers pass, 1 when
+    /// either does, and 2 when For's share of For and Against, weighted
+    /// `council_bps` to the council and the rest to the community, is over
+    /// half and neither chamber vetoed.  A combined failure is a veto when
+    /// a chamber vetoed, otherwise a rejection.
+    ///
+    /// The weighted share compares fractions without dividing:
+    /// (10000 - w)·Fc/Dc + w·Fk/Dk > 1/2 is multiplied out by 2·10000·Dc·Dk,
+    /// with an empty chamber's denominator taken as 1.  Ballots count one
+    /// each, so with at most a million per chamber nothing overflows.
+    #[instruction]
+    pub fn reveal_chambers(
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        council_ctxt: Enc<Mxe, VoteTallies>,
+        veto_bps: u64,
+        rule: u64,
+        council_bps: u64,
+    ) -> ChamberResults {
+        let tallies = tallies_ctxt.to_arcis();
+        let council = council_ctxt.to_arcis();
+
+        let mut max_votes = tallies.option_0;
+        let mut winner: u8 = 0;
+        if tallies.option_1 > max_votes {
+            max_votes = tallies.option_1;
+            winner = 1;
+        }
+        if tallies.option_2 > max_votes {
+            winner = 2;
+        }
+
+        let mut community_outcome: u8 = 1;
+        if tallies.option_0 > tallies.option_1 {
+            community_outcome = 0;
+        }
+        if veto_bps > 0u64 && tallies.option_1 * 10_000u64 > veto_bps * tallies.total_votes {
+            community_outcome = 2;
+        }
+        let mut council_outcome: u8 = 1;
+        if council.option_0 > council.option_1 {
+            council_outcome = 0;
+        }
+        if veto_bps > 0u64 && council.option_1 * 10_000u64 > veto_bps * council.total_votes {
+            council_outcome = 2;
+        }
+
+        let mut community_cast = tallies.option_0 + tallies.option_1;
+        if community_cast == 0u64 {
+            community_cast = 1u64;
+        }
+        let mut council_cast = council.option_0 + council.option_1;
+        if council_cast == 0u64 {
+            council_cast = 1u64;
+        }
+        let weighted_for = (10_000u64 - council_bps) * tallies.option_0 * council_cast
+            + council_bps * council.option_0 * community_cast;
+        let vetoed = community_outcome == 2u8 || council_outcome == 2u8;
+
+        let mut passed = community_outcome == 0u8 && council_outcome == 0u8;
+        if rule == 1u64 {
+            passed = community_outcome == 0u8 || council_outcome == 0u8;
+        }
+        if rule == 2u64 {
+            passed = 2u64 * weighted_for > 10_000u64 * community_cast * council_cast && !vetoed;
+        }
+        let mut outcome: u8 = 1;
+        if vetoed {
+            outcome = 2;
+        }
+        if passed {
+            outcome = 0;
+        }
+
+        ChamberResults {
+            option_0: tallies.option_0.reveal(),
+            option_1: tallies.option_1.reveal(),
+            option_2: tallies.option_2.reveal(),
+            option_3: tallies.option_3.reveal(),
+            total_votes: tallies.total_votes.reveal(),
+            winner: winner.reveal(),
+            outcome: outcome.reveal(),
+            community_outcome: community_outcome.reveal(),
+            council_0: council.option_0.reveal(),
+            council_1: council.option_1.reveal(),
+            council_2: council.option_2.reveal(),
+            council_3: council.option_3.reveal(),
+            council_total: council.total_votes.reveal(),
+            council_outcome: council_outcome.reveal(),
+        }
+    }
+
+    /// The 24 orders of four options, lexicographic.  A `RankingCounts`
+    /// keeps one count per order, at the order's index here.
+    const RANK_ORDERS: [[usize; 4]; 24] = [
+        [0, 1, 2, 3], [0, 1, 3, 2], [0, 2, 1, 3], [0, 2, 3, 1], [0, 3, 1, 2], [0, 3, 2, 1],
+        [1, 0, 2, 3], [1, 0, 3, 2], [1, 2, 0, 3], [1, 2, 3, 0], [1, 3, 0, 2], [1, 3, 2, 0],
+        [2, 0, 1, 3], [2, 0, 3, 1], [2, 1, 0, 3], [2, 1, 3, 0], [2, 3, 0, 1], [2, 3, 1, 0],
+        [3, 0, 1, 2], [3, 0, 2, 1], [3, 1, 0, 2], [3, 1, 2, 0], [3, 2, 0, 1], [3, 2, 1, 0],
+    ];
+
+    /// A ranked ballot, for instant runoff or a Borda count: `ranks[k]` is
+    /// the option ranked k-th, best first.  Every option appears once,
+    /// those the proposal lacks last.
+    pub struct Ranking {
+        ranks: [u64; 4],
+    }
+
+    /// Counted ranked ballots per order of the four options, indexed as
+    /// `RANK_ORDERS`.  Instant runoff needs whole orders, not just first
+    /// preferences, since an eliminated option's ballots move on.
+    pub struct RankingCounts {
+        counts: [u64; 24],
+    }
+
+    /// Initialize an empty ranking tally.
+    #[instruction]
+    pub fn init_rankings(mxe: Mxe) -> Enc<Mxe, RankingCounts> {
+        mxe.from_arcis(RankingCounts { counts: [0u64; 24] })
+    }
+
+    /// Cast a ranked ballot.
+    ///
+    /// Adds one to the count of the ballot's order and to its first
+    /// preference's tally, and counts it in `total_votes`, so the running
+    /// tallies hold first preferences.  A ballot that isn't an order of the
+    /// four options, or that ranks an option from `options` on among the
+    /// first `options` places, is discarded whole.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_ranked_vote(
+        ranking_ctxt: Enc<Shared, Ranking>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        rankings_ctxt: Enc<Mxe, RankingCounts>,
+        options: u64,
+    ) -> (Enc<Mxe, VoteTallies>, Enc<Mxe, RankingCounts>) {
+        let ballot = ranking_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+        let mut rankings = rankings_ctxt.to_arcis();
+
+        let mut valid = true;
+        for k in 0..4 {
+            if (k as u64) < options && ballot.ranks[k] >= options {
+                valid = false;
+            }
+        }
+        let mut counted = false;
+        for p in 0..24 {
+            let mut matched = valid;
+            for k in 0..4 {
+                if ballot.ranks[k] != RANK_ORDERS[p][k] as u64 {
+                    matched = false;
+                }
+            }
+            if matched {
+                rankings.counts[p] += 1u64;
+                counted = true;
+            }
+        }
+        if counted {
+            let first = ballot.ranks[0];
+            if first == 0u64 {
+                tallies.option_0 += 1u64;
+            }
+            if first == 1u64 {
+                tallies.option_1 += 1u64;
+            }
+            if first == 2u64 {
+                tallies.option_2 += 1u64;
+            }
+            if first == 3u64 {
+                tallies.option_3 += 1u64;
+            }
+            tallies.total_votes += 1u64;
+        }
+
+        (
+            tallies_ctxt.owner.from_arcis(tallies),
+            rankings_ctxt.owner.from_arcis(rankings),
+        )
+    }
+
+    /// Reveal a ranked-choice proposal by instant runoff.
+    ///
+    /// Options from `options` on start eliminated.  Each round gives every
+    /// ballot to its highest-ranked option still standing: one with more
+    /// than half the ballots wins, otherwise the one with the fewest is
+    /// eliminated (the later one on a tie) and the next round recounts.
+    /// Four options need at most three eliminations, so four rounds always
+    /// decide.  Only the deciding round is revealed: `option_*` are its
+    /// counts and `total_votes` the ballots, while the elimination order
+    /// stays inside the cluster.  With no ballots option 0 wins, as in
+    /// `reveal_results`.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn reveal_ranked(rankings_ctxt: Enc<Mxe, RankingCounts>, options: u64) -> RevealedResults {
+        let rankings = rankings_ctxt.to_arcis();
+
+        let mut total = 0u64;
+        for p in 0..24 {
+            total += rankings.counts[p];
+        }
+        let mut eliminated = [false; 4];
+        for i in 0..4 {
+            eliminated[i] = (i as u64) >= options;
+        }
+
+        let mut decided = false;
+        let mut winner: u8 = 0;
+        let mut votes = [0u64; 4];
+        for _ in 0..4 {
+            let mut round = [0u64; 4];
+            for p in 0..24 {
+                let mut placed = false;
+                for k in 0..4 {
+                    let option = RANK_ORDERS[p][k];
+                    if !placed && !eliminated[option] {
+                        round[option] += rankings.counts[p];
+                        placed = true;
+                    }
+                }
+            }
+
+            let mut seen = false;
+            let mut most = 0u64;
+            let mut leader: u8 = 0;
+            let mut fewest = 0u64;
+            let mut loser: u8 = 0;
+            for i in 0..4 {
+                if !eliminated[i] {
+                    if !seen || round[i] > most {
+                        most = round[i];
+                        leader = i as u8;
+                    }
+                    if !seen || round[i] <= fewest {
+                        fewest = round[i];
+                        loser = i as u8;
+                    }
+                    seen = true;
+                }
+            }
+
+            let majority = 2u64 * most > total;
+            for i in 0..4 {
+                if !decided {
+                    votes[i] = round[i];
+                    if !majority && loser == i as u8 {
+                        eliminated[i] = true;
+                    }
+                }
+            }
+            if !decided && majority {
+                winner = leader;
+                decided = true;
+            }
+        }
+
+        RevealedResults {
+            option_0: votes[0].reveal(),
+            option_1: votes[1].reveal(),
+            option_2: votes[2].reveal(),
+            option_3: votes[3].reveal(),
+            total_votes: total.reveal(),
+            winner: winner.reveal(),
+        }
+    }
+
+    /// Cast a Borda ballot.
+    ///
+    /// The option at each of the first `options` places earns a point for
+    /// every place of the proposal's below it — `options - 1` for first,
+    /// none for last — added to its tally, and the ballot is counted in
+    /// `total_votes`, so `reveal_results` names the option with the most
+    /// points.  A ballot that doesn't rank each of the four options once,
+    /// or that ranks an option from `options` on among the first `options`
+    /// places, is discarded whole.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_borda(
+        ranking_ctxt: Enc<Shared, Ranking>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        options: u64,
+    ) -> Enc<Mxe, VoteTallies> {
+        let ballot = ranking_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        let mut valid = true;
+        let mut points = [0u64; 4];
+        for k in 0..4 {
+            if (k as u64) < options && ballot.ranks[k] >= options {
+                valid = false;
+            }
+            let mut earned = 0u64;
+            for j in 0..4 {
+                if j > k && (j as u64) < options {
+                    earned += 1u64;
+                }
+            }
+            for i in 0..4 {
+                if ballot.ranks[k] == i as u64 {
+                    points[i] += earned;
+                }
+            }
+        }
+        for i in 0..4 {
+            let mut placed = 0u64;
+            for k in 0..4 {
+                if ballot.ranks[k] == i as u64 {
+                    placed += 1u64;
+                }
+            }
+            if placed != 1u64 {
+                valid = false;
+            }
+        }
+        if valid {
+            tallies.option_0 += points[0];
+            tallies.option_1 += points[1];
+            tallies.option_2 += points[2];
+            tallies.option_3 += points[3];
+            tallies.total_votes += 1u64;
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// A range ballot: a 0..=10 score per option.
+    pub struct RangeScores {
+        scores: [u64; 4],
+    }
+
+    /// Cast a range ballot.
+    ///
+    /// Adds each score to its option's tally and counts the ballot in
+    /// `total_votes`, so `reveal_results` reveals the score totals and the
+    /// ballot count: every option's average is its total over that count,
+    /// which the program divides out in plaintext instead of here.  A
+    /// ballot with a score above 10, or above 0 for an option from
+    /// `options` on, is discarded whole.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_range(
+        scores_ctxt: Enc<Shared, RangeScores>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        options: u64,
+    ) -> Enc<Mxe, VoteTallies> {
+        let ballot = scores_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+
+        let mut valid = true;
+        for i in 0..4 {
+            let cap = if (i as u64) < options { 10u64 } else { 0u64 };
+            if ballot.scores[i] > cap {
+                valid = false;
+            }
+        }
+        if valid {
+            tallies.option_0 += ballot.scores[0];
+            tallies.option_1 += ballot.scores[1];
+            tallies.option_2 += ballot.scores[2];
+            tallies.option_3 += ballot.scores[3];
+            tallies.total_votes += 1u64;
+        }
+
+        tallies_ctxt.owner.from_arcis(tallies)
+    }
+
+    /// Head-to-head counts for a Condorcet or STAR proposal:
+    /// `wins[4 * i + j]` ballots rank or score option i above option j.
+    /// The diagonal stays zero.
+    pub struct Preferences {
+        wins: [u64; 16],
+    }
+
+    /// Initialize an empty preference matrix.
+    #[instruction]
+    pub fn init_preferences(mxe: Mxe) -> Enc<Mxe, Preferences> {
+        mxe.from_arcis(Preferences { wins: [0u64; 16] })
+    }
+
+    /// Cast a Condorcet ballot.
+    ///
+    /// For every pair of options adds one to the count of the one ranked
+    /// higher beating the other, adds one to the first preference's tally
+    /// and counts the ballot in `total_votes`.  A ballot is discarded whole
+    /// as in `cast_borda`.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_condorcet(
+        ranking_ctxt: Enc<Shared, Ranking>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        preferences_ctxt: Enc<Mxe, Preferences>,
+        options: u64,
+    ) -> (Enc<Mxe, VoteTallies>, Enc<Mxe, Preferences>) {
+        let ballot = ranking_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+        let mut preferences = preferences_ctxt.to_arcis();
+
+        let mut valid = true;
+        let mut place = [0u64; 4];
+        let mut placed = [0u64; 4];
+        for k in 0..4 {
+            if (k as u64) < options && ballot.ranks[k] >= options {
+                valid = false;
+            }
+            for i in 0..4 {
+                if ballot.ranks[k] == i as u64 {
+                    place[i] += k as u64;
+                    placed[i] += 1u64;
+                }
+            }
+        }
+        for i in 0..4 {
+            if placed[i] != 1u64 {
+                valid = false;
+            }
+        }
+        if valid {
+            for i in 0..4 {
+                for j in 0..4 {
+                    if i != j && place[i] < place[j] {
+                        preferences.wins[4 * i + j] += 1u64;
+                    }
+                }
+            }
+            let first = ballot.ranks[0];
+            if first == 0u64 {
+                tallies.option_0 += 1u64;
+            }
+            if first == 1u64 {
+                tallies.option_1 += 1u64;
+            }
+            if first == 2u64 {
+                tallies.option_2 += 1u64;
+            }
+            if first == 3u64 {
+                tallies.option_3 += 1u64;
+            }
+            tallies.total_votes += 1u64;
+        }
+
+        (
+            tallies_ctxt.owner.from_arcis(tallies),
+            preferences_ctxt.owner.from_arcis(preferences),
+        )
+    }
+
+    /// Plaintext Condorcet results: `option_*` count the head-to-head
+    /// contests each option wins outright.
+    pub struct CondorcetResults {
+        option_0: u64,
+        option_1: u64,
+        option_2: u64,
+        option_3: u64,
+        total_votes: u64,
+        winner: u8,
+        cycle: bool,
+    }
+
+    /// Reveal a Condorcet proposal.
+    ///
+    /// Compares every pair of the first `options` options; a tied pair is
+    /// a win for neither.  The winner is the option with the most wins, the
+    /// lowest on a tie, and `cycle` is set unless it beats every other
+    /// option — the Condorcet winner.  Every ballot ranks option 0 against
+    /// option 1 one way or the other, so those two counts sum to the
+    /// ballots.  The counts themselves are never revealed.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn reveal_condorcet(preferences_ctxt: Enc<Mxe, Preferences>, options: u64) -> CondorcetResults {
+        let preferences = preferences_ctxt.to_arcis();
+
+        let mut beaten = [0u64; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                if i != j
+                    && (i as u64) < options
+                    && (j as u64) < options
+                    && preferences.wins[4 * i + j] > preferences.wins[4 * j + i]
+                {
+                    beaten[i] += 1u64;
+                }
+            }
+        }
+
+        let mut most = beaten[0];
+        let mut winner: u8 = 0;
+        for i in 1..4 {
+            if (i as u64) < options && beaten[i] > most {
+                most = beaten[i];
+                winner = i as u8;
+            }
+        }
+        let cycle = most + 1u64 != options;
+        let total = preferences.wins[1] + preferences.wins[4];
+
+        CondorcetResults {
+            option_0: beaten[0].reveal(),
+            option_1: beaten[1].reveal(),
+            option_2: beaten[2].reveal(),
+            option_3: beaten[3].reveal(),
+            total_votes: total.reveal(),
+            winner: winner.reveal(),
+            cycle: cycle.reveal(),
+        }
+    }
+
+    /// A STAR ballot: a 0..=5 score per option.
+    pub struct StarScores {
+        scores: [u64; 4],
+    }
+
+    /// Cast a STAR ballot.
+    ///
+    /// Adds each score to its option's tally, counts the ballot in
+    /// `total_votes` and, for every pair of options scored differently,
+    /// one to the count of the higher-scored one beating the other.  A
+    /// ballot with a score above 5, or above 0 for an option from
+    /// `options` on, is discarded whole.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn cast_star(
+        scores_ctxt: Enc<Shared, StarScores>,
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        preferences_ctxt: Enc<Mxe, Preferences>,
+        options: u64,
+    ) -> (Enc<Mxe, VoteTallies>, Enc<Mxe, Preferences>) {
+        let ballot = scores_ctxt.to_arcis();
+        let mut tallies = tallies_ctxt.to_arcis();
+        let mut preferences = preferences_ctxt.to_arcis();
+
+        let mut valid = true;
+        for i in 0..4 {
+            let cap = if (i as u64) < options { 5u64 } else { 0u64 };
+            if ballot.scores[i] > cap {
+                valid = false;
+            }
+        }
+        if valid {
+            for i in 0..4 {
+                for j in 0..4 {
+                    if i != j && ballot.scores[i] > ballot.scores[j] {
+                        preferences.wins[4 * i + j] += 1u64;
+                    }
+                }
+            }
+            tallies.option_0 += ballot.scores[0];
+            tallies.option_1 += ballot.scores[1];
+            tallies.option_2 += ballot.scores[2];
+            tallies.option_3 += ballot.scores[3];
+            tallies.total_votes += 1u64;
+        }
+
+        (
+            tallies_ctxt.owner.from_arcis(tallies),
+            preferences_ctxt.owner.from_arcis(preferences),
+        )
+    }
+
+    /// Plaintext STAR results: `option_*` are the score totals, and
+    /// `runoff_*` the ballots preferring each finalist over the other.
+    pub struct StarResults {
+        option_0: u64,
+        option_1: u64,
+        option_2: u64,
+        option_3: u64,
+        total_votes: u64,
+        winner: u8,
+        runner_up: u8,
+        runoff_winner: u64,
+        runoff_runner_up: u64,
+    }
+
+    /// Reveal a STAR proposal.
+    ///
+    /// The two of the first `options` options with the highest score
+    /// totals, the lower index on a tie, go to an automatic runoff: the
+    /// one more ballots scored above the other wins, and a tied runoff
+    /// goes to the higher total.  The score totals, both finalists and the
+    /// runoff counts are revealed; the other head-to-head counts are not.
+    #[allow(clippy::needless_range_loop)]
+    #[instruction]
+    pub fn reveal_star(
+        tallies_ctxt: Enc<Mxe, VoteTallies>,
+        preferences_ctxt: Enc<Mxe, Preferences>,
+        options: u64,
+    ) -> StarResults {
+        let tallies = tallies_ctxt.to_arcis();
+        let preferences = preferences_ctxt.to_arcis();
+        let totals = [tallies.option_0, tallies.option_1, tallies.option_2, tallies.option_3];
+
+        let mut first: u8 = 0;
+        let mut best = totals[0];
+        for i in 1..4 {
+            if (i as u64) < options && totals[i] > best {
+                best = totals[i];
+                first = i as u8;
+            }
+        }
+        let mut second: u8 = 0;
+        let mut next = 0u64;
+        let mut found = false;
+        for i in 0..4 {
+            if (i as u64) < options && (i as u8) != first && (!found || totals[i] > next) {
+                second = i as u8;
+                next = totals[i];
+                found = true;
+            }
+        }
+
+        let mut over_second = 0u64;
+        let mut over_first = 0u64;
+        for i in 0..4 {
+            for j in 0..4 {
+                if (i as u8) == first && (j as u8) == second {
+                    over_second = preferences.wins[4 * i + j];
+                }
+                if (i as u8) == second && (j as u8) == first {
+                    over_first = preferences.wins[4 * i + j];
+                }
+            }
+        }
+        let upset = over_first > over_second;
+        let winner = if upset { second } else { first };
+        let runner_up = if upset { first } else { second };
+        let runoff_winner = if upset { over_first } else { over_second };
+        let runoff_runner_up = if upset { over_second } else { over_first };
+
+        StarResults {
+            option_0: tallies.option_0.reveal(),
+            option_1: tallies.option_1.reveal(),
+            option_2: tallies.option_2.reveal(),
+            option_3: tallies.option_3.reveal(),
+            total_votes: tallies.total_votes.reveal(),
+            winner: winner.reveal(),
+            runner_up: runner_up.reveal(),
+            runoff_winner: runoff_winner.reveal(),
+            runoff_runner_up: runoff_runner_up.reveal(),
+        }
+    }
 }
@@ -0,0 +1,461 @@
+//! The ballot buffer.
+//!
+//! Ballots sent with `submit_ballot` wait in the proposal's `BallotBuffer`
+//! until `process_ballot_batch` folds `BALLOT_BATCH_SIZE` of them into the
+//! tallies with one computation.
+
+use crate::*;
+
+pub(crate) fn open_ballot_buffer(ctx: Context<OpenBallotBuffer>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal_acc;
+    require!(
+        matches!(
+            proposal.ballot,
+            BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+        ),
+        ErrorCode::WrongBallotKind
+    );
+    require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+    check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+    require!(!proposal.spend_histogram, ErrorCode::HistogramUnsupported);
+    require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+    require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+
+    let buffer = &mut ctx.accounts.ballot_buffer;
+    buffer.bump = ctx.bumps.ballot_buffer;
+    buffer.proposal = proposal.key();
+
+    emit!(BallotBufferOpenedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn submit_ballot(
+    ctx: Context<SubmitBallot>,
+    vote_v0: [u8; 32],
+    vote_v1: [u8; 32],
+    vote_v2: [u8; 32],
+    vote_v3: [u8; 32],
+    vote_encryption_pubkey: [u8; 32],
+    vote_nonce: u128,
+) -> Result<()> {
+    let proposal = &ctx.accounts.proposal_acc;
+    require!(!proposal.spend_histogram, ErrorCode::HistogramBallotRequired);
+    require!(!proposal.credit_bank, ErrorCode::BankedBallotRequired);
+    require!(!proposal.encrypted_budgets, ErrorCode::BudgetedBallotRequired);
+    require!(proposal.burn_mint.is_none(), ErrorCode::BurnToVoteUnsupported);
+    require!(
+        proposal.eligibility_oracle.is_none(),
+        ErrorCode::EligibilityOracleUnsupported
+    );
+    require!(proposal.weight_mint.is_none(), ErrorCode::TokenWeightsUnsupported);
+    require!(proposal.commit_deadline == 0, ErrorCode::CommitPhaseUnsupported);
+    let now = Clock::get()?.unix_timestamp;
+    require!(!proposal.in_mix_window(now), ErrorCode::MixWindowUnsupported);
+    let weight = proposal.ballot_weight(now);
+    let budget = ballot_budget(
+        proposal,
+        ctx.accounts.voter_credits.as_ref(),
+        ctx.accounts.credit_account.as_ref(),
+    )?;
+    let voter_record_bump = ctx.bumps.voter_record;
+    let accounts = &mut *ctx.accounts;
+    admit_ballot(
+        &mut accounts.proposal_acc,
+        &mut accounts.voter_record,
+        voter_record_bump,
+        accounts.payer.key(),
+        accounts.voter_token_account.as_deref(),
+        accounts.credential.as_deref(),
+        accounts.allowlist.as_ref(),
+        None,
+        &mut accounts.global_stats,
+        &accounts.config,
+    )?;
+
+    collect_fee(
+        &ctx.accounts.payer,
+        &mut ctx.accounts.fee_vault,
+        &ctx.accounts.system_program,
+        ctx.accounts.config.vote_fee,
+    )?;
+
+    let buffer = &mut ctx.accounts.ballot_buffer;
+    require!(buffer.ballots.len() < MAX_BUFFERED_BALLOTS, ErrorCode::BallotBufferFull);
+    buffer.ballots.push(HeldBallot {
+        voter_record: ctx.accounts.voter_record.key(),
+        ciphertexts: [vote_v0, vote_v1, vote_v2, vote_v3],
+        encryption_pubkey: vote_encryption_pubkey,
+        nonce: vote_nonce,
+        weight,
+        budget,
+    });
+    let proposal = &mut ctx.accounts.proposal_acc;
+    proposal.buffered = proposal.buffered.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+    emit!(BallotBufferedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        buffered: proposal.buffered,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn process_ballot_batch(
+    ctx: Context<ProcessBallotBatch>,
+    computation_offset: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let buffer = &mut ctx.accounts.ballot_buffer;
+    require!(!buffer.ballots.is_empty(), ErrorCode::NoBufferedBallots);
+    require!(
+        buffer.ballots.len() >= BALLOT_BATCH_SIZE || now >= ctx.accounts.proposal_acc.deadline,
+        ErrorCode::BallotBatchNotFull
+    );
+    let count = buffer.ballots.len().min(BALLOT_BATCH_SIZE);
+    let batch: Vec<HeldBallot> = buffer.ballots.drain(..count).collect();
+    let proposal = &mut ctx.accounts.proposal_acc;
+    proposal.buffered = proposal
+        .buffered
+        .checked_sub(count as u32)
+        .ok_or(ErrorCode::CounterOverflow)?;
+
+    // ArgBuilder order must match circuit params:
+    // cast_vote_batch(first..fourth: Enc<Shared, VoteAllocation>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+    //                 weight_0..3: u64, budget_0..3: u64, options: u64)
+    // Empty slots repeat the first ballot at weight 0.
+    let mut args = ArgBuilder::new();
+    for slot in 0..BALLOT_BATCH_SIZE {
+        let ballot = batch.get(slot).unwrap_or(&batch[0]);
+        args = args
+            .x25519_pubkey(ballot.encryption_pubkey)
+            .plaintext_u128(ballot.nonce)
+            .encrypted_u64(ballot.ciphertexts[0])
+            .encrypted_u64(ballot.ciphertexts[1])
+            .encrypted_u64(ballot.ciphertexts[2])
+            .encrypted_u64(ballot.ciphertexts[3]);
+    }
+    args = args.plaintext_u128(ctx.accounts.tally_acc.load()?.nonce()).account(
+        ctx.accounts.tally_acc.key(),
+        8, // discriminator
+        32 * 5, // 5 encrypted u64 counters
+    );
+    for slot in 0..BALLOT_BATCH_SIZE {
+        args = args.plaintext_u64(batch.get(slot).map_or(0, |ballot| ballot.weight));
+    }
+    for slot in 0..BALLOT_BATCH_SIZE {
+        args = args.plaintext_u64(batch.get(slot).map_or(0, |ballot| ballot.budget));
+    }
+    let args = args.plaintext_u64(proposal.num_options.into()).build();
+
+    let mut callback_accounts = vec![
+        CallbackAccount {
+            pubkey: proposal.key(),
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: ctx.accounts.tally_acc.key(),
+            is_writable: true,
+        },
+        CallbackAccount {
+            pubkey: ctx.accounts.global_stats.key(),
+            is_writable: true,
+        },
+    ];
+    callback_accounts.extend((0..BALLOT_BATCH_SIZE).map(|slot| match batch.get(slot) {
+        Some(ballot) => CallbackAccount {
+            pubkey: ballot.voter_record,
+            is_writable: true,
+        },
+        // The program id: `None` in the callback.
+        None => CallbackAccount {
+            pubkey: crate::ID,
+            is_writable: false,
+        },
+    }));
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    let lamports_before = ctx.accounts.payer.lamports();
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![CastVoteBatchCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &callback_accounts,
+        )?],
+        1,
+        0,
+    )?;
+
+    reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+}
+
+pub(crate) fn cast_vote_batch_callback(
+    ctx: Context<CastVoteBatchCallback>,
+    output: SignedComputationOutputs<CastVoteBatchOutput>,
+) -> Result<()> {
+    settle_computation(&mut ctx.accounts.proposal_acc);
+    // No reveal can be queued while a ballot is buffered, so a batch's
+    // ballots share one generation.
+    if let Some(voter_record) = ctx.accounts.voter_record_0.as_ref() {
+        if !is_current_generation(&ctx.accounts.proposal_acc, voter_record.tally_generation) {
+            return Ok(());
+        }
+    }
+    let aborted = matches!(output, SignedComputationOutputs::Failure);
+    let o = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CastVoteBatchOutput { field_0 }) => field_0,
+        Err(_) => {
+            return reject_callback(
+                &mut ctx.accounts.global_stats,
+                ctx.accounts.proposal_acc.key(),
+                ctx.accounts.proposal_acc.id,
+                CallbackStage::CastVote,
+                aborted,
+            )
+        }
+    };
+
+    let accounts = &mut *ctx.accounts;
+    for voter_record in [
+        accounts.voter_record_0.as_mut(),
+        accounts.voter_record_1.as_mut(),
+        accounts.voter_record_2.as_mut(),
+        accounts.voter_record_3.as_mut(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        count_ballot(
+            &mut accounts.proposal_acc,
+            &accounts.tally_acc,
+            voter_record,
+            o.ciphertexts,
+            o.nonce,
+        )?;
+    }
+    Ok(())
+}
+
+// ============================================================
+// Account Structs
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenBallotBuffer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + BallotBuffer::INIT_SPACE,
+        seeds = [b"ballot_buffer", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub ballot_buffer: Account<'info, BallotBuffer>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct SubmitBallot<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"ballot_buffer", proposal_acc.key().as_ref()],
+        bump = ballot_buffer.bump,
+    )]
+    pub ballot_buffer: Box<Account<'info, BallotBuffer>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// As for `cast_vote`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    /// As for `cast_vote`.
+    pub credential: Option<Box<Account<'info, BallotCredential>>>,
+    /// CHECK: as for `cast_vote`.
+    #[account(
+        seeds = [b"credits", proposal_acc.registration_scope(proposal_acc.key()).as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_credits: Option<UncheckedAccount<'info>>,
+    /// CHECK: as for `cast_vote`.
+    #[account(seeds = [b"credit_account", proposal_acc.key().as_ref(), payer.key().as_ref()], bump)]
+    pub credit_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: as for `cast_vote`.
+    #[account(seeds = [b"allowlist", proposal_acc.key().as_ref()], bump)]
+    pub allowlist: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("cast_vote_batch", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct ProcessBallotBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        mut,
+        seeds = [b"ballot_buffer", proposal_acc.key().as_ref()],
+        bump = ballot_buffer.bump,
+    )]
+    pub ballot_buffer: Box<Account<'info, BallotBuffer>>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+#[callback_accounts("cast_vote_batch")]
+#[derive(Accounts)]
+pub struct CastVoteBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    /// The batch's ballots in buffer order; `None` past its last.
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record_0.voter.as_ref()],
+        bump = voter_record_0.bump,
+    )]
+    pub voter_record_0: Option<Account<'info, VoterRecord>>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record_1.voter.as_ref()],
+        bump = voter_record_1.bump,
+    )]
+    pub voter_record_1: Option<Account<'info, VoterRecord>>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record_2.voter.as_ref()],
+        bump = voter_record_2.bump,
+    )]
+    pub voter_record_2: Option<Account<'info, VoterRecord>>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record_3.voter.as_ref()],
+        bump = voter_record_3.bump,
+    )]
+    pub voter_record_3: Option<Account<'info, VoterRecord>>,
+}
+
+// ============================================================
+// State Accounts
+// ============================================================
+
+/// Ballots submitted to a proposal, one PDA per proposal
+/// `[b"ballot_buffer", proposal]`, created by `open_ballot_buffer`.
+/// Filled by `submit_ballot` and emptied, oldest first, by
+/// `process_ballot_batch`.
+#[account]
+#[derive(InitSpace)]
+pub struct BallotBuffer {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    #[max_len(MAX_BUFFERED_BALLOTS)]
+    pub ballots: Vec<HeldBallot>,
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[event]
+pub struct BallotBufferOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+}
+
+#[event]
+pub struct BallotBufferedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Ballots now waiting for `process_ballot_batch`.
+    pub buffered: u32,
+}
@@ -0,0 +1,1326 @@
+//! Voice-credit budgets other than a proposal's flat `voice_credits`.
+//!
+//! - Square-root credits: `floor(sqrt(balance))` of an escrowed mint
+//!   balance, in a `VoterCredits`.
+//! - Funded credits: an amount the authority sets per voter, in a
+//!   `CreditAccount`.
+//! - Credit banks: a `VoterCreditBank` per authority and voter, carrying
+//!   unspent credits from one proposal to the next.
+//! - Encrypted budgets: a confidential per-voter grant, in an
+//!   `EncryptedBudget`.
+
+use crate::*;
+
+pub(crate) fn open_sqrt_credits(ctx: Context<OpenSqrtCredits>) -> Result<()> {
+    let mint = ctx.accounts.credit_mint.key();
+    let proposal = &mut ctx.accounts.proposal_acc;
+    require!(
+        matches!(
+            proposal.ballot,
+            BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+        ),
+        ErrorCode::WrongBallotKind
+    );
+    require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+    check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+    require!(proposal.credit_mint.is_none(), ErrorCode::SqrtCreditsAlreadyOpen);
+    require!(!proposal.spend_histogram, ErrorCode::SqrtCreditsUnsupported);
+    require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+    require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+    require!(!proposal.funded_credits, ErrorCode::FundedCreditsUnsupported);
+    proposal.credit_mint = Some(mint);
+    require!(
+        proposal.weight_mint.is_none() || proposal.token_weight_fits(proposal.budget_ceiling()),
+        ErrorCode::InvalidTokenWeightCap
+    );
+
+    emit!(SqrtCreditsOpenedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        mint,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn register_credits(ctx: Context<RegisterCredits>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal_acc;
+    let mint = proposal.credit_mint.ok_or(ErrorCode::SqrtCreditsNotOpen)?;
+    check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+
+    let voter = ctx.accounts.voter.key();
+    let holding = &ctx.accounts.voter_token_account;
+    require!(
+        ctx.accounts.credit_mint.key() == mint && holding.mint == mint && holding.owner == voter,
+        ErrorCode::InvalidCreditAccount
+    );
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: holding.to_account_info(),
+                mint: ctx.accounts.credit_mint.to_account_info(),
+                to: ctx.accounts.credit_vault.to_account_info(),
+                authority: ctx.accounts.voter.to_account_info(),
+            },
+        ),
+        holding.amount,
+        ctx.accounts.credit_mint.decimals,
+    )?;
+    // What arrived, net of any transfer fee, is what the credits rest on.
+    ctx.accounts.credit_vault.reload()?;
+    let balance = ctx.accounts.credit_vault.amount;
+    let credits = integer_sqrt(balance);
+    require!(credits > 0, ErrorCode::NoVoiceCredits);
+
+    let record = &mut ctx.accounts.voter_credits;
+    record.bump = ctx.bumps.voter_credits;
+    record.proposal = proposal.registration_scope(proposal.key());
+    record.voter = voter;
+    record.balance = balance;
+    record.credits = credits;
+    record.credit_mint = mint;
+    record.unlocks_at = proposal.deadline;
+
+    emit!(CreditsRegisteredEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        voter,
+        balance,
+        credits,
+        ballot: proposal.on_ballot,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn release_credits(ctx: Context<ReleaseCredits>) -> Result<()> {
+    let record = &ctx.accounts.voter_credits;
+    require!(
+        Clock::get()?.unix_timestamp >= record.unlocks_at,
+        ErrorCode::CreditsLocked
+    );
+
+    let voter = ctx.accounts.voter.key();
+    let vault = &ctx.accounts.credit_vault;
+    let seeds: &[&[u8]] = &[b"credits", record.proposal.as_ref(), voter.as_ref(), &[record.bump]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: vault.to_account_info(),
+                mint: ctx.accounts.credit_mint.to_account_info(),
+                to: ctx.accounts.voter_token_account.to_account_info(),
+                authority: record.to_account_info(),
+            },
+            &[seeds],
+        ),
+        vault.amount,
+        ctx.accounts.credit_mint.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: vault.to_account_info(),
+            destination: ctx.accounts.voter.to_account_info(),
+            authority: record.to_account_info(),
+        },
+        &[seeds],
+    ))?;
+
+    emit!(CreditsReleasedEvent {
+        scope: record.proposal,
+        voter,
+        balance: vault.amount,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn fund_credits(ctx: Context<FundCredits>, voter: Pubkey, credits: u64) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal_acc;
+    require!(
+        matches!(
+            proposal.ballot,
+            BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+        ),
+        ErrorCode::WrongBallotKind
+    );
+    check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+    require!(proposal.credit_mint.is_none(), ErrorCode::SqrtCreditsAlreadyOpen);
+    require!(!proposal.spend_histogram, ErrorCode::FundedCreditsUnsupported);
+    require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+    require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+    require!(credits > 0, ErrorCode::NoVoiceCredits);
+    require!(
+        proposal.weight_mint.is_none() || proposal.token_weight_fits(credits),
+        ErrorCode::InvalidTokenWeightCap
+    );
+    if !proposal.funded_credits {
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        proposal.funded_credits = true;
+    }
+
+    let account = &mut ctx.accounts.credit_account;
+    account.bump = ctx.bumps.credit_account;
+    account.proposal = proposal.key();
+    account.voter = voter;
+    account.credits = credits;
+
+    emit!(CreditsFundedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        voter,
+        credits,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn open_credit_bank(ctx: Context<OpenCreditBank>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal_acc;
+    require!(
+        matches!(
+            proposal.ballot,
+            BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+        ),
+        ErrorCode::WrongBallotKind
+    );
+    require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+    check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+    require!(
+        proposal.mix_window == 0
+            && proposal.commit_deadline == 0
+            && proposal.gating == GatingMode::Open
+            && proposal.credit_mint.is_none()
+            && !proposal.funded_credits
+            && proposal.burn_mint.is_none()
+            && proposal.eligibility_oracle.is_none()
+            && proposal.weight_mint.is_none()
+            && !proposal.spend_histogram
+            && !proposal.encrypted_budgets,
+        ErrorCode::CreditBankUnsupported
+    );
+    proposal.credit_bank = true;
+
+    emit!(CreditBankOpenedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn create_credit_bank(
+    ctx: Context<CreateCreditBank>,
+    computation_offset: u64,
+    nonce: u128,
+) -> Result<()> {
+    require!(ctx.accounts.proposal_acc.credit_bank, ErrorCode::CreditBankNotOpen);
+
+    let bank = &mut ctx.accounts.credit_bank;
+    bank.bump = ctx.bumps.credit_bank;
+    bank.authority = ctx.accounts.authority.key();
+    bank.voter = ctx.accounts.payer.key();
+
+    // init_credit_bank(mxe: Mxe)
+    let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![InitCreditBankCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.credit_bank.key(),
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub(crate) fn init_credit_bank_callback(
+    ctx: Context<InitCreditBankCallback>,
+    output: SignedComputationOutputs<InitCreditBankOutput>,
+) -> Result<()> {
+    settle_computation(&mut ctx.accounts.proposal_acc);
+    let aborted = matches!(output, SignedComputationOutputs::Failure);
+    let o = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(InitCreditBankOutput { field_0 }) => field_0,
+        Err(_) => {
+            return reject_callback(
+                &mut ctx.accounts.global_stats,
+                ctx.accounts.proposal_acc.key(),
+                ctx.accounts.proposal_acc.id,
+                CallbackStage::InitTallies,
+                aborted,
+            )
+        }
+    };
+
+    let bank = &mut ctx.accounts.credit_bank;
+    bank.balance = o.ciphertexts[0];
+    bank.nonce = o.nonce;
+    bank.ready = true;
+
+    emit!(CreditBankCreatedEvent {
+        authority: bank.authority,
+        voter: bank.voter,
+    });
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cast_vote_banked(
+    ctx: Context<CastVoteBanked>,
+    computation_offset: u64,
+    vote_v0: [u8; 32],
+    vote_v1: [u8; 32],
+    vote_v2: [u8; 32],
+    vote_v3: [u8; 32],
+    vote_encryption_pubkey: [u8; 32],
+    vote_nonce: u128,
+) -> Result<()> {
+    require!(ctx.accounts.proposal_acc.credit_bank, ErrorCode::CreditBankNotOpen);
+    require!(ctx.accounts.credit_bank.ready, ErrorCode::CreditBankNotReady);
+    require!(!ctx.accounts.credit_bank.pending, ErrorCode::CreditBankBusy);
+    let weight = ctx
+        .accounts
+        .proposal_acc
+        .ballot_weight(Clock::get()?.unix_timestamp);
+    let voter_record_bump = ctx.bumps.voter_record;
+    let accounts = &mut *ctx.accounts;
+    admit_ballot(
+        &mut accounts.proposal_acc,
+        &mut accounts.voter_record,
+        voter_record_bump,
+        accounts.payer.key(),
+        accounts.voter_token_account.as_deref(),
+        None,
+        None,
+        None,
+        &mut accounts.global_stats,
+        &accounts.config,
+    )?;
+    accounts.credit_bank.pending = true;
+
+    collect_fee(
+        &ctx.accounts.payer,
+        &mut ctx.accounts.fee_vault,
+        &ctx.accounts.system_program,
+        ctx.accounts.config.vote_fee,
+    )?;
+
+    // cast_vote_banked(alloc_ctxt: Enc<Shared, VoteAllocation>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+    //                  bank_ctxt: Enc<Mxe, CreditBalance>, weight: u64, grant: u64, options: u64)
+    let args = ArgBuilder::new()
+        .x25519_pubkey(vote_encryption_pubkey)
+        .plaintext_u128(vote_nonce)
+        .encrypted_u64(vote_v0)
+        .encrypted_u64(vote_v1)
+        .encrypted_u64(vote_v2)
+        .encrypted_u64(vote_v3)
+        .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+        .account(
+            ctx.accounts.tally_acc.key(),
+            8, // discriminator
+            32 * 5, // 5 encrypted u64 counters
+        )
+        .plaintext_u128(ctx.accounts.credit_bank.nonce)
+        .account(
+            ctx.accounts.credit_bank.key(),
+            8 + 1, // discriminator + bump
+            32,
+        )
+        .plaintext_u64(weight)
+        .plaintext_u64(ctx.accounts.proposal_acc.voice_credits)
+        .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    let lamports_before = ctx.accounts.payer.lamports();
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![CastVoteBankedCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.voter_record.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.credit_bank.key(),
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+}
+
+pub(crate) fn cast_vote_banked_callback(
+    ctx: Context<CastVoteBankedCallback>,
+    output: SignedComputationOutputs<CastVoteBankedOutput>,
+) -> Result<()> {
+    settle_computation(&mut ctx.accounts.proposal_acc);
+    // Whatever became of the ballot, the bank is free again; it keeps
+    // its old balance unless the ballot is counted.
+    ctx.accounts.credit_bank.pending = false;
+    if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+        return Ok(());
+    }
+    let aborted = matches!(output, SignedComputationOutputs::Failure);
+    let (tallies, balance) = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CastVoteBankedOutput {
+            field_0: CastVoteBankedOutputStruct0 { field_0, field_1 },
+        }) => (field_0, field_1),
+        Err(_) => {
+            return reject_callback(
+                &mut ctx.accounts.global_stats,
+                ctx.accounts.proposal_acc.key(),
+                ctx.accounts.proposal_acc.id,
+                CallbackStage::CastVote,
+                aborted,
+            )
+        }
+    };
+
+    ctx.accounts.credit_bank.balance = balance.ciphertexts[0];
+    ctx.accounts.credit_bank.nonce = balance.nonce;
+
+    count_ballot(
+        &mut ctx.accounts.proposal_acc,
+        &ctx.accounts.tally_acc,
+        &mut ctx.accounts.voter_record,
+        tallies.ciphertexts,
+        tallies.nonce,
+    )
+}
+
+pub(crate) fn fund_encrypted_budget(
+    ctx: Context<FundEncryptedBudget>,
+    computation_offset: u64,
+    voter: Pubkey,
+    budget: [u8; 32],
+    budget_encryption_pubkey: [u8; 32],
+    budget_nonce: u128,
+    nonce: u128,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal_acc;
+    require!(
+        matches!(
+            proposal.ballot,
+            BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+        ),
+        ErrorCode::WrongBallotKind
+    );
+    check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+    if !proposal.encrypted_budgets {
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(
+            proposal.mix_window == 0
+                && proposal.commit_deadline == 0
+                && proposal.gating == GatingMode::Open
+                && proposal.credit_mint.is_none()
+                && !proposal.funded_credits
+                && proposal.burn_mint.is_none()
+                && proposal.eligibility_oracle.is_none()
+                && proposal.weight_mint.is_none()
+                && !proposal.spend_histogram
+                && !proposal.credit_bank,
+            ErrorCode::EncryptedBudgetsUnsupported
+        );
+        proposal.encrypted_budgets = true;
+    }
+
+    let account = &mut ctx.accounts.encrypted_budget;
+    account.bump = ctx.bumps.encrypted_budget;
+    account.proposal = proposal.key();
+    account.voter = voter;
+
+    // seal_budget(budget_ctxt: Enc<Shared, CreditBalance>, mxe: Mxe)
+    let args = ArgBuilder::new()
+        .x25519_pubkey(budget_encryption_pubkey)
+        .plaintext_u128(budget_nonce)
+        .encrypted_u64(budget)
+        .plaintext_u128(nonce)
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![SealBudgetCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.encrypted_budget.key(),
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+pub(crate) fn seal_budget_callback(
+    ctx: Context<SealBudgetCallback>,
+    output: SignedComputationOutputs<SealBudgetOutput>,
+) -> Result<()> {
+    settle_computation(&mut ctx.accounts.proposal_acc);
+    let aborted = matches!(output, SignedComputationOutputs::Failure);
+    let o = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(SealBudgetOutput { field_0 }) => field_0,
+        Err(_) => {
+            return reject_callback(
+                &mut ctx.accounts.global_stats,
+                ctx.accounts.proposal_acc.key(),
+                ctx.accounts.proposal_acc.id,
+                CallbackStage::InitTallies,
+                aborted,
+            )
+        }
+    };
+
+    let budget = &mut ctx.accounts.encrypted_budget;
+    budget.balance = o.ciphertexts[0];
+    budget.nonce = o.nonce;
+    budget.ready = true;
+
+    emit!(EncryptedBudgetFundedEvent {
+        proposal: budget.proposal,
+        proposal_id: ctx.accounts.proposal_acc.id,
+        voter: budget.voter,
+    });
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cast_vote_budgeted(
+    ctx: Context<CastVoteBudgeted>,
+    computation_offset: u64,
+    vote_v0: [u8; 32],
+    vote_v1: [u8; 32],
+    vote_v2: [u8; 32],
+    vote_v3: [u8; 32],
+    vote_encryption_pubkey: [u8; 32],
+    vote_nonce: u128,
+) -> Result<()> {
+    require!(ctx.accounts.encrypted_budget.ready, ErrorCode::EncryptedBudgetNotReady);
+    let weight = ctx
+        .accounts
+        .proposal_acc
+        .ballot_weight(Clock::get()?.unix_timestamp);
+    let voter_record_bump = ctx.bumps.voter_record;
+    let accounts = &mut *ctx.accounts;
+    admit_ballot(
+        &mut accounts.proposal_acc,
+        &mut accounts.voter_record,
+        voter_record_bump,
+        accounts.payer.key(),
+        accounts.voter_token_account.as_deref(),
+        None,
+        None,
+        None,
+        &mut accounts.global_stats,
+        &accounts.config,
+    )?;
+
+    collect_fee(
+        &ctx.accounts.payer,
+        &mut ctx.accounts.fee_vault,
+        &ctx.accounts.system_program,
+        ctx.accounts.config.vote_fee,
+    )?;
+
+    // cast_vote_budgeted(alloc_ctxt: Enc<Shared, VoteAllocation>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+    //                    budget_ctxt: Enc<Mxe, CreditBalance>, weight: u64, options: u64)
+    let args = ArgBuilder::new()
+        .x25519_pubkey(vote_encryption_pubkey)
+        .plaintext_u128(vote_nonce)
+        .encrypted_u64(vote_v0)
+        .encrypted_u64(vote_v1)
+        .encrypted_u64(vote_v2)
+        .encrypted_u64(vote_v3)
+        .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+        .account(
+            ctx.accounts.tally_acc.key(),
+            8, // discriminator
+            32 * 5, // 5 encrypted u64 counters
+        )
+        .plaintext_u128(ctx.accounts.encrypted_budget.nonce)
+        .account(
+            ctx.accounts.encrypted_budget.key(),
+            8 + 1, // discriminator + bump
+            32,
+        )
+        .plaintext_u64(weight)
+        .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    let lamports_before = ctx.accounts.payer.lamports();
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![CastVoteBudgetedCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.voter_record.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.encrypted_budget.key(),
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+}
+
+pub(crate) fn cast_vote_budgeted_callback(
+    ctx: Context<CastVoteBudgetedCallback>,
+    output: SignedComputationOutputs<CastVoteBudgetedOutput>,
+) -> Result<()> {
+    settle_computation(&mut ctx.accounts.proposal_acc);
+    if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+        return Ok(());
+    }
+    let aborted = matches!(output, SignedComputationOutputs::Failure);
+    let (tallies, budget) = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CastVoteBudgetedOutput {
+            field_0: CastVoteBudgetedOutputStruct0 { field_0, field_1 },
+        }) => (field_0, field_1),
+        Err(_) => {
+            return reject_callback(
+                &mut ctx.accounts.global_stats,
+                ctx.accounts.proposal_acc.key(),
+                ctx.accounts.proposal_acc.id,
+                CallbackStage::CastVote,
+                aborted,
+            )
+        }
+    };
+
+    ctx.accounts.encrypted_budget.balance = budget.ciphertexts[0];
+    ctx.accounts.encrypted_budget.nonce = budget.nonce;
+    count_ballot(
+        &mut ctx.accounts.proposal_acc,
+        &ctx.accounts.tally_acc,
+        &mut ctx.accounts.voter_record,
+        tallies.ciphertexts,
+        tallies.nonce,
+    )
+}
+
+// ============================================================
+// Account Structs
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenSqrtCredits<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub credit_mint: InterfaceAccount<'info, token_interface::Mint>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterCredits<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    /// The voter's account of `proposal_acc.credit_mint`, emptied into
+    /// `credit_vault`.
+    #[account(mut)]
+    pub voter_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub credit_mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(
+        init, payer = voter,
+        space = 8 + VoterCredits::INIT_SPACE,
+        seeds = [b"credits", proposal_acc.registration_scope(proposal_acc.key()).as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub voter_credits: Account<'info, VoterCredits>,
+    #[account(
+        init, payer = voter,
+        seeds = [b"credit_vault", voter_credits.key().as_ref()],
+        bump,
+        token::mint = credit_mint,
+        token::authority = voter_credits,
+        token::token_program = token_program,
+    )]
+    pub credit_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub token_program: Interface<'info, token_interface::TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseCredits<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    #[account(
+        mut, close = voter,
+        has_one = credit_mint,
+        seeds = [b"credits", voter_credits.proposal.as_ref(), voter.key().as_ref()],
+        bump = voter_credits.bump,
+    )]
+    pub voter_credits: Account<'info, VoterCredits>,
+    pub credit_mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(mut, seeds = [b"credit_vault", voter_credits.key().as_ref()], bump)]
+    pub credit_vault: InterfaceAccount<'info, token_interface::TokenAccount>,
+    /// Receives the escrowed tokens.
+    #[account(mut, token::mint = credit_mint, token::authority = voter)]
+    pub voter_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub token_program: Interface<'info, token_interface::TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(voter: Pubkey)]
+pub struct FundCredits<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + CreditAccount::INIT_SPACE,
+        seeds = [b"credit_account", proposal_acc.key().as_ref(), voter.as_ref()],
+        bump,
+    )]
+    pub credit_account: Account<'info, CreditAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenCreditBank<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[queue_computation_accounts("init_credit_bank", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CreateCreditBank<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_CREDIT_BANK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterCreditBank::INIT_SPACE,
+        seeds = [b"credit_bank", authority.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub credit_bank: Box<Account<'info, VoterCreditBank>>,
+}
+
+#[callback_accounts("init_credit_bank")]
+#[derive(Accounts)]
+pub struct InitCreditBankCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_CREDIT_BANK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"credit_bank", credit_bank.authority.as_ref(), credit_bank.voter.as_ref()],
+        bump = credit_bank.bump,
+    )]
+    pub credit_bank: Account<'info, VoterCreditBank>,
+}
+
+#[queue_computation_accounts("cast_vote_banked", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastVoteBanked<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_BANKED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"credit_bank", authority.key().as_ref(), payer.key().as_ref()],
+        bump = credit_bank.bump,
+    )]
+    pub credit_bank: Box<Account<'info, VoterCreditBank>>,
+}
+
+#[callback_accounts("cast_vote_banked")]
+#[derive(Accounts)]
+pub struct CastVoteBankedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_BANKED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"credit_bank", proposal_acc.authority.as_ref(), voter_record.voter.as_ref()],
+        bump = credit_bank.bump,
+    )]
+    pub credit_bank: Account<'info, VoterCreditBank>,
+}
+
+#[queue_computation_accounts("seal_budget", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, voter: Pubkey)]
+pub struct FundEncryptedBudget<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SEAL_BUDGET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + EncryptedBudget::INIT_SPACE,
+        seeds = [b"encrypted_budget", proposal_acc.key().as_ref(), voter.as_ref()],
+        bump,
+    )]
+    pub encrypted_budget: Box<Account<'info, EncryptedBudget>>,
+}
+
+#[callback_accounts("seal_budget")]
+#[derive(Accounts)]
+pub struct SealBudgetCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SEAL_BUDGET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"encrypted_budget", proposal_acc.key().as_ref(), encrypted_budget.voter.as_ref()],
+        bump = encrypted_budget.bump,
+    )]
+    pub encrypted_budget: Account<'info, EncryptedBudget>,
+}
+
+#[queue_computation_accounts("cast_vote_budgeted", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastVoteBudgeted<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_BUDGETED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"encrypted_budget", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump = encrypted_budget.bump,
+    )]
+    pub encrypted_budget: Box<Account<'info, EncryptedBudget>>,
+}
+
+#[callback_accounts("cast_vote_budgeted")]
+#[derive(Accounts)]
+pub struct CastVoteBudgetedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_BUDGETED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"encrypted_budget", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = encrypted_budget.bump,
+    )]
+    pub encrypted_budget: Account<'info, EncryptedBudget>,
+}
+
+// ============================================================
+// State Accounts
+// ============================================================
+
+/// A voter's budget on a proposal, PDA `[b"credit_account", proposal,
+/// voter]`, set by `fund_credits`.
+#[account]
+#[derive(InitSpace)]
+pub struct CreditAccount {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// The budget their ballot is checked against.
+    pub credits: u64,
+}
+
+/// A voter's square-root credits, PDA `[b"credits", scope, voter]`,
+/// created by `register_credits` and closed by `release_credits`.  The
+/// scope is the proposal's `registration_scope`: the proposal, or its
+/// `Ballot`.  The registered tokens sit in the token account
+/// `[b"credit_vault", voter_credits]`, owned by this PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct VoterCredits {
+    pub bump: u8,
+    /// The scope: a proposal, or a ballot.
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// The voter's `credit_mint` balance at registration.
+    pub balance: u64,
+    /// `floor(sqrt(balance))`, the budget their ballot is checked against.
+    pub credits: u64,
+    /// The mint `balance` is of.
+    pub credit_mint: Pubkey,
+    /// The deadline at registration; `release_credits` returns the tokens
+    /// from then on.
+    pub unlocks_at: i64,
+}
+
+/// A voter's banked voice credits with one proposal authority — a DAO —
+/// PDA `[b"credit_bank", authority, voter]`, created by
+/// `create_credit_bank`.  Each `cast_vote_banked` adds the proposal's
+/// `voice_credits` and deducts what the ballot spent, inside MPC.
+#[account]
+#[derive(InitSpace)]
+pub struct VoterCreditBank {
+    pub bump: u8,
+    /// Encrypted balance.  Must stay first: the bank circuits read it at a
+    /// fixed offset.
+    pub balance: [u8; 32],
+    pub nonce: u128,
+    pub authority: Pubkey,
+    pub voter: Pubkey,
+    /// The balance is initialized; `cast_vote_banked` accepts ballots.
+    pub ready: bool,
+    /// A banked ballot is queued and its callback has not landed.
+    pub pending: bool,
+}
+
+/// A voter's confidential credit budget on one proposal, PDA
+/// `[b"encrypted_budget", proposal, voter]`, funded by
+/// `fund_encrypted_budget`.  `cast_vote_budgeted` checks the ballot
+/// against it and deducts what the ballot spent, inside MPC.
+#[account]
+#[derive(InitSpace)]
+pub struct EncryptedBudget {
+    pub bump: u8,
+    /// Encrypted balance.  Must stay first: the budget circuits read it at
+    /// a fixed offset.
+    pub balance: [u8; 32],
+    pub nonce: u128,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// The budget is sealed; `cast_vote_budgeted` accepts the voter's ballot.
+    pub ready: bool,
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[event]
+pub struct SqrtCreditsOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct CreditsFundedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub voter: Pubkey,
+    pub credits: u64,
+}
+
+#[event]
+pub struct CreditsRegisteredEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub voter: Pubkey,
+    pub balance: u64,
+    pub credits: u64,
+    /// The ballot the registration serves, if the proposal is on one.
+    pub ballot: Option<Pubkey>,
+}
+
+#[event]
+pub struct CreditsReleasedEvent {
+    /// The `VoterCredits` scope: a proposal, or a ballot.
+    pub scope: Pubkey,
+    pub voter: Pubkey,
+    pub balance: u64,
+}
+
+#[event]
+pub struct CreditBankOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+}
+
+#[event]
+pub struct CreditBankCreatedEvent {
+    pub authority: Pubkey,
+    pub voter: Pubkey,
+}
+
+/// A voter's encrypted budget is sealed.  The amount is never emitted.
+#[event]
+pub struct EncryptedBudgetFundedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub voter: Pubkey,
+}
+
+// ============================================================
+// Helpers
+// ============================================================
+
+/// Voice credits a ballot is checked against: the voter's registered
+/// `VoterCredits` once `open_sqrt_credits` ran, their `CreditAccount` once
+/// `fund_credits` did, otherwise the proposal's `voice_credits`.
+pub(crate) fn ballot_budget(
+    proposal: &ProposalAccount,
+    voter_credits: Option<&UncheckedAccount>,
+    credit_account: Option<&UncheckedAccount>,
+) -> Result<u64> {
+    if proposal.funded_credits {
+        let info = credit_account.ok_or(ErrorCode::CreditsNotFunded)?;
+        // Its seeds pin the address, so only `fund_credits` can have
+        // written it.
+        let account = CreditAccount::try_deserialize(&mut &info.try_borrow_data()?[..])
+            .map_err(|_| error!(ErrorCode::CreditsNotFunded))?;
+        return Ok(account.credits);
+    }
+    if proposal.credit_mint.is_none() {
+        return Ok(proposal.voice_credits);
+    }
+    let info = voter_credits.ok_or(ErrorCode::CreditsNotRegistered)?;
+    // Its seeds pin the address, so only `register_credits` can have
+    // written it; on a ballot, possibly from a proposal of another mint.
+    let record = VoterCredits::try_deserialize(&mut &info.try_borrow_data()?[..])
+        .map_err(|_| error!(ErrorCode::CreditsNotRegistered))?;
+    require!(
+        Some(record.credit_mint) == proposal.credit_mint,
+        ErrorCode::CreditsNotRegistered
+    );
+    Ok(record.credits)
+}
@@ -0,0 +1,258 @@
+//! Data-less voter records.
+//!
+//! On a proposal opened with `open_dataless_records`, `cast_vote_dataless`
+//! creates the voter's `[b"voter", proposal, voter]` PDA with no data: its
+//! existence is the vote, at about half a full record's rent.
+
+use crate::*;
+
+pub(crate) fn open_dataless_records(ctx: Context<OpenDatalessRecords>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal_acc;
+    let now = Clock::get()?.unix_timestamp;
+    require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+    check_stage(proposal.state(now), Stage::Setup)?;
+    require!(!proposal.dataless_records, ErrorCode::DatalessRecordsAlreadyOpen);
+    check_plain_ballot(proposal, now)?;
+    require!(
+        proposal.gating == GatingMode::Open && !proposal.has_reward_pool && proposal.mix_window == 0,
+        ErrorCode::DatalessRecordsUnsupported
+    );
+    proposal.dataless_records = true;
+
+    emit!(DatalessRecordsOpenedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+    });
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cast_vote_dataless(
+    ctx: Context<CastVoteDataless>,
+    computation_offset: u64,
+    vote_v0: [u8; 32],
+    vote_v1: [u8; 32],
+    vote_v2: [u8; 32],
+    vote_v3: [u8; 32],
+    vote_encryption_pubkey: [u8; 32],
+    vote_nonce: u128,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    check_plain_ballot(&ctx.accounts.proposal_acc, now)?;
+    let voter_record_bump = ctx.bumps.voter_record;
+    let accounts = &mut *ctx.accounts;
+    admit_dataless_ballot(
+        &mut accounts.proposal_acc,
+        &accounts.voter_record,
+        voter_record_bump,
+        &accounts.payer,
+        accounts.voter_token_account.as_deref(),
+        &accounts.system_program,
+        &mut accounts.global_stats,
+        &accounts.config,
+    )?;
+
+    collect_fee(
+        &ctx.accounts.payer,
+        &mut ctx.accounts.fee_vault,
+        &ctx.accounts.system_program,
+        ctx.accounts.config.vote_fee,
+    )?;
+
+    // As `cast_vote`, with no oracle or token weight: a stake of 1.
+    let proposal = &ctx.accounts.proposal_acc;
+    let (curve, cap) = proposal.weight_curve.circuit_args();
+    let args = ArgBuilder::new()
+        .x25519_pubkey(vote_encryption_pubkey)
+        .plaintext_u128(vote_nonce)
+        .encrypted_u64(vote_v0)
+        .encrypted_u64(vote_v1)
+        .encrypted_u64(vote_v2)
+        .encrypted_u64(vote_v3)
+        .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+        .account(ctx.accounts.tally_acc.key(), 8, 32 * 5)
+        .plaintext_u64(proposal.ballot_weight(now))
+        .plaintext_u64(proposal.voice_credits)
+        .plaintext_u64(1)
+        .plaintext_u64(curve)
+        .plaintext_u64(cap)
+        .plaintext_u64(proposal.num_options.into())
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    let lamports_before = ctx.accounts.payer.lamports();
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![CastVoteCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+}
+
+// ============================================================
+// Account Structs
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenDatalessRecords<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[queue_computation_accounts("cast_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastVoteDataless<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+        constraint = proposal_acc.dataless_records @ ErrorCode::DatalessRecordsNotOpen,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    /// CHECK: the payer's data-less `VoterRecord`, created by the ballot;
+    /// already there means the payer has voted.
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[event]
+pub struct DatalessRecordsOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+}
+
+// ============================================================
+// Helpers
+// ============================================================
+
+/// As `admit_ballot`, for a proposal keeping data-less records: creates
+/// the voter's `VoterRecord` at `voter_record` with no data, its
+/// existence the vote.  `open_dataless_records` only takes proposals open
+/// to anyone, so there is no credential or allowlist to check.
+#[allow(clippy::too_many_arguments)]
+fn admit_dataless_ballot<'info>(
+    proposal: &mut Account<'info, ProposalAccount>,
+    voter_record: &UncheckedAccount<'info>,
+    voter_record_bump: u8,
+    payer: &Signer<'info>,
+    voter_token_account: Option<&InterfaceAccount<'info, token_interface::TokenAccount>>,
+    system_program: &Program<'info, System>,
+    global_stats: &mut GlobalStats,
+    config: &Config,
+) -> Result<()> {
+    check_ballot_open(proposal, config)?;
+    require!(proposal.gating == GatingMode::Open, ErrorCode::DatalessRecordsUnsupported);
+    require!(voter_record.lamports() == 0, ErrorCode::AlreadyVoted);
+
+    let voter = payer.key();
+    let proposal_key = proposal.key();
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.to_account_info(),
+                to: voter_record.to_account_info(),
+            },
+            &[&[b"voter", proposal_key.as_ref(), voter.as_ref(), &[voter_record_bump]]],
+        ),
+        Rent::get()?.minimum_balance(0),
+        0,
+        &crate::ID,
+    )?;
+
+    record_turnout(proposal, global_stats)?;
+    weigh_turnout(proposal, voter, voter_token_account)
+}
+
+/// Apply the tallies of a ballot marked by a data-less `VoterRecord`.  It
+/// counts toward `counted_voters` like any other, with no record to flag.
+pub(crate) fn count_dataless_ballot(
+    proposal: &mut Account<ProposalAccount>,
+    tally: &AccountLoader<TallyAccount>,
+    vote_state: [[u8; 32]; 5],
+    nonce: u128,
+) -> Result<()> {
+    proposal.counted_voters = proposal
+        .counted_voters
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    count_anonymous_ballot(proposal, tally, vote_state, nonce)
+}
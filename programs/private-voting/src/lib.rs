@@ -1,10 +1,192 @@
+// Anchor's generated `cpi` wrappers mirror the instruction signatures,
+// which already carry `#[allow(clippy::too_many_arguments)]` themselves.
+#![cfg_attr(feature = "cpi", allow(clippy::too_many_arguments))]
+
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::token_2022::{self, spl_token_2022, Token2022};
+use anchor_spl::token_interface;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use solana_alt_bn128_bls::{HashToCurve, Sha256Normalized, G2_MINUS_ONE};
+use solana_bn254::prelude::alt_bn128_pairing;
+use solana_sha256_hasher::hashv;
+
+pub mod ballot_buffer;
+pub mod credits;
+pub mod dataless;
+pub mod mix_window;
+pub mod round_records;
+pub mod state;
+
+pub use ballot_buffer::*;
+pub use credits::*;
+pub use dataless::*;
+pub use mix_window::*;
+pub use round_records::*;
+use state::{check_stage, try_transition, Stage};
 
 const COMP_DEF_OFFSET_INIT_TALLIES: u32 = comp_def_offset("init_tallies");
 const COMP_DEF_OFFSET_CAST_VOTE: u32 = comp_def_offset("cast_vote");
+const COMP_DEF_OFFSET_CAST_LIKERT: u32 = comp_def_offset("cast_likert");
+const COMP_DEF_OFFSET_CAST_CHOICE: u32 = comp_def_offset("cast_choice");
+const COMP_DEF_OFFSET_CAST_BALLOT_CHOICES: u32 = comp_def_offset("cast_ballot_choices");
+const COMP_DEF_OFFSET_CAST_ROUND_CHOICE: u32 = comp_def_offset("cast_round_choice");
+const COMP_DEF_OFFSET_CAST_APPROVAL: u32 = comp_def_offset("cast_approval");
 const COMP_DEF_OFFSET_REVEAL_RESULTS: u32 = comp_def_offset("reveal_results");
+const COMP_DEF_OFFSET_REVEAL_OUTCOME: u32 = comp_def_offset("reveal_outcome");
+const COMP_DEF_OFFSET_REVEAL_BUDGET_BOX: u32 = comp_def_offset("reveal_budget_box");
+const COMP_DEF_OFFSET_CAST_PAIRWISE: u32 = comp_def_offset("cast_pairwise");
+const COMP_DEF_OFFSET_REVEAL_PAIRWISE: u32 = comp_def_offset("reveal_pairwise");
+const COMP_DEF_OFFSET_INIT_WRITE_INS: u32 = comp_def_offset("init_write_ins");
+const COMP_DEF_OFFSET_CAST_WRITE_IN: u32 = comp_def_offset("cast_write_in");
+const COMP_DEF_OFFSET_REVEAL_WRITE_IN: u32 = comp_def_offset("reveal_write_in");
+const COMP_DEF_OFFSET_CAST_JURY_SCORES: u32 = comp_def_offset("cast_jury_scores");
+const COMP_DEF_OFFSET_RELEASE_IDENTITY: u32 = comp_def_offset("release_identity");
+const COMP_DEF_OFFSET_REVEAL_QUORUM_PROGRESS: u32 = comp_def_offset("reveal_quorum_progress");
+const COMP_DEF_OFFSET_INIT_SPEND_HISTOGRAM: u32 = comp_def_offset("init_spend_histogram");
+const COMP_DEF_OFFSET_CAST_VOTE_HISTOGRAM: u32 = comp_def_offset("cast_vote_histogram");
+const COMP_DEF_OFFSET_REVEAL_SPEND_HISTOGRAM: u32 = comp_def_offset("reveal_spend_histogram");
+const COMP_DEF_OFFSET_INIT_COUNCIL_TALLIES: u32 = comp_def_offset("init_council_tallies");
+const COMP_DEF_OFFSET_CAST_COUNCIL_CHOICE: u32 = comp_def_offset("cast_council_choice");
+const COMP_DEF_OFFSET_REVEAL_CHAMBERS: u32 = comp_def_offset("reveal_chambers");
+const COMP_DEF_OFFSET_INIT_RANKINGS: u32 = comp_def_offset("init_rankings");
+const COMP_DEF_OFFSET_CAST_RANKED_VOTE: u32 = comp_def_offset("cast_ranked_vote");
+const COMP_DEF_OFFSET_REVEAL_RANKED: u32 = comp_def_offset("reveal_ranked");
+const COMP_DEF_OFFSET_INIT_PREFERENCES: u32 = comp_def_offset("init_preferences");
+const COMP_DEF_OFFSET_CAST_CONDORCET: u32 = comp_def_offset("cast_condorcet");
+const COMP_DEF_OFFSET_REVEAL_CONDORCET: u32 = comp_def_offset("reveal_condorcet");
+const COMP_DEF_OFFSET_CAST_STAR: u32 = comp_def_offset("cast_star");
+const COMP_DEF_OFFSET_REVEAL_STAR: u32 = comp_def_offset("reveal_star");
+const COMP_DEF_OFFSET_CAST_BORDA: u32 = comp_def_offset("cast_borda");
+const COMP_DEF_OFFSET_CAST_RANGE: u32 = comp_def_offset("cast_range");
+const COMP_DEF_OFFSET_CAST_VOTE_BATCH: u32 = comp_def_offset("cast_vote_batch");
+const COMP_DEF_OFFSET_INIT_CREDIT_BANK: u32 = comp_def_offset("init_credit_bank");
+const COMP_DEF_OFFSET_CAST_VOTE_BANKED: u32 = comp_def_offset("cast_vote_banked");
+const COMP_DEF_OFFSET_SEAL_BUDGET: u32 = comp_def_offset("seal_budget");
+const COMP_DEF_OFFSET_CAST_VOTE_BUDGETED: u32 = comp_def_offset("cast_vote_budgeted");
+
+/// Ballots a `BallotKind::Pairwise` round can hold: every pair is compared
+/// inside one `reveal_pairwise` computation.
+pub const MAX_PAIRWISE_BALLOTS: usize = 8;
+
+/// Distinct write-in candidates a proposal tallies; later ones are dropped.
+pub const MAX_WRITE_INS: usize = 4;
+
+/// Criteria a `BallotKind::Jury` panel can score each application on.
+pub const MAX_CRITERIA: u8 = 3;
+
+
+/// Ballots a `BallotBuffer` can hold until `process_ballot_batch` folds
+/// them into the tallies.
+pub const MAX_BUFFERED_BALLOTS: usize = 32;
+
+/// Buffered ballots one `cast_vote_batch` computation folds in.
+pub const BALLOT_BATCH_SIZE: usize = 4;
+
+/// Largest `BallotKind::TimeWeighted` percentage: a 100x multiplier.
+pub const MAX_TIME_WEIGHT_PCT: u16 = 10_000;
+
+/// Keys a `TrusteeSet` can hold.
+pub const MAX_TRUSTEES: usize = 5;
+
+/// Co-authors a proposal can name alongside its authority.
+pub const MAX_CO_AUTHORS: usize = 3;
+
+/// Members a `CouncilChamber` can seat.
+pub const MAX_COUNCIL_MEMBERS: usize = 16;
+
+/// Largest `turnout_bias_bps`: an empty turnout then needs every ballot
+/// cast For.
+pub const MAX_TURNOUT_BIAS_BPS: u16 = 5_000;
+
+/// Counts in a `SpendHistogram`: three credit-spend buckets for each of the
+/// four options.
+pub const SPEND_HISTOGRAM_COUNTS: usize = 12;
+
+/// Counts in a `RankingTally`: one for each of the 24 orders of four
+/// options.
+pub const RANKING_ORDERS: usize = 24;
+
+/// Counts in a `PreferenceMatrix`: one for each ordered pair of the four
+/// options, the diagonal included.
+pub const PREFERENCE_CELLS: usize = 16;
+
+/// Suggested `Config::reveal_timeout`: seconds a queued reveal may stay
+/// without a callback before `expire_reveal` lets it be queued again.
+pub const DEFAULT_REVEAL_TIMEOUT_SECS: i64 = 600;
+
+/// Domain separator of `credential_message`.
+pub const CREDENTIAL_DOMAIN: &[u8] = b"arcvote-credential-v1";
+
+/// Domain separator of `nullifier`.
+pub const NULLIFIER_DOMAIN: &[u8] = b"arcvote-nullifier-v1";
+
+/// Domain separator of `auto_reveal_offset`.
+pub const AUTO_REVEAL_DOMAIN: &[u8] = b"arcvote-auto-reveal-v1";
+
+/// Domain separator of `tally_leaf` and `fold_commitment`.
+pub const COMMITMENT_DOMAIN: &[u8] = b"arcvote-tally-commitment-v1";
+
+/// Domain separator of `ballot_hash`.
+pub const BALLOT_COMMITMENT_DOMAIN: &[u8] = b"arcvote-ballot-commitment-v1";
+
+/// Domain separator of a `MixQueue`'s seed and the draws made from it.
+pub const MIX_SEED_DOMAIN: &[u8] = b"arcvote-mix-seed-v1";
+
+/// Largest `NullifierSet::max_voters`: two 16-byte slots per voter must fit
+/// in a 10 MiB account.
+pub const MAX_NULLIFIER_VOTERS: u32 = 300_000;
+
+/// Longest proposal title, in bytes.
+pub const MAX_TITLE_LEN: usize = 100;
+
+/// Longest option label or candidate name, in bytes.
+pub const MAX_OPTION_LEN: usize = 32;
+
+/// Longest option metadata URI, in bytes.
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+/// Most locales one `ProposalTranslations` holds.
+pub const MAX_TRANSLATIONS: usize = 8;
+
+/// Longest translation locale tag, in bytes: room for `zh-Hant-TW` and
+/// the like.
+pub const MAX_LOCALE_LEN: usize = 16;
+
+/// Most proposals one `Ballot` groups.
+pub const MAX_BALLOT_PROPOSALS: usize = 8;
+
+/// Most ballots one `cast_votes_batch` queues; more would overflow a
+/// transaction.
+pub const MAX_BATCH_VOTES: usize = 4;
+
+/// Ballots one proposal admits.  Turnout stays far from wrapping
+/// `voter_count`, and from skewing quorum math built on it.
+pub const MAX_VOTERS: u32 = 1_000_000;
+
+/// Largest `Allowlist::capacity`: committees beyond this are better served
+/// by credentials than by a list `cast_vote` scans.
+pub const MAX_ALLOWLIST_VOTERS: u16 = 256;
+
+/// Instruction an eligibility oracle implements: `can_vote`, Anchor's
+/// discriminator (`sha256("global:can_vote")[..8]`) followed by the voter
+/// and proposal keys.  Its accounts are the voter and proposal, read-only
+/// and unsigned, then whatever `cast_vote` was passed as remaining
+/// accounts.  It answers with the ballot's weight, a little-endian `u64`,
+/// as return data; 0 refuses the voter.
+pub const CAN_VOTE_DISCRIMINATOR: [u8; 8] = [61, 90, 31, 17, 121, 232, 166, 6];
+
+/// Largest weight an eligibility oracle may answer.  Bounding it lets
+/// `open_token_weights` check its cap against the heaviest ballot an
+/// oracle can vouch for.
+pub const MAX_ORACLE_WEIGHT: u64 = 10_000;
+
+/// Metaplex Token Metadata, owner of the metadata accounts that name an
+/// NFT's verified collection.
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 
 declare_id!("11111111111111111111111111111111");
 
@@ -26,18 +208,295 @@ pub mod private_voting {
         Ok(())
     }
 
+    pub fn init_likert_comp_def(ctx: Context<InitLikertCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_choice_comp_def(ctx: Context<InitChoiceCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_ballot_choices_comp_def(ctx: Context<InitBallotChoicesCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_round_choice_comp_def(ctx: Context<InitRoundChoiceCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_approval_comp_def(ctx: Context<InitApprovalCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
     pub fn init_reveal_comp_def(ctx: Context<InitRevealCompDef>) -> Result<()> {
         init_comp_def(ctx.accounts, None, None)?;
         Ok(())
     }
 
+    pub fn init_outcome_comp_def(ctx: Context<InitOutcomeCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_budget_box_comp_def(ctx: Context<InitBudgetBoxCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_pairwise_comp_def(ctx: Context<InitPairwiseCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_pairwise_reveal_comp_def(ctx: Context<InitPairwiseRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_write_ins_comp_def(ctx: Context<InitWriteInsCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_write_in_comp_def(ctx: Context<InitWriteInCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_write_in_reveal_comp_def(ctx: Context<InitWriteInRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_jury_comp_def(ctx: Context<InitJuryCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_identity_comp_def(ctx: Context<InitIdentityCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_quorum_progress_comp_def(ctx: Context<InitQuorumProgressCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_spend_histogram_comp_def(ctx: Context<InitSpendHistogramCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_histogram_vote_comp_def(ctx: Context<InitHistogramVoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_histogram_reveal_comp_def(ctx: Context<InitHistogramRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_council_tallies_comp_def(ctx: Context<InitCouncilTalliesCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_council_choice_comp_def(ctx: Context<InitCouncilChoiceCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_chambers_reveal_comp_def(ctx: Context<InitChambersRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_rankings_comp_def(ctx: Context<InitRankingsCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_ranked_vote_comp_def(ctx: Context<InitRankedVoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_ranked_reveal_comp_def(ctx: Context<InitRankedRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_preferences_comp_def(ctx: Context<InitPreferencesCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_condorcet_vote_comp_def(ctx: Context<InitCondorcetVoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_condorcet_reveal_comp_def(ctx: Context<InitCondorcetRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_star_vote_comp_def(ctx: Context<InitStarVoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_star_reveal_comp_def(ctx: Context<InitStarRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_borda_comp_def(ctx: Context<InitBordaCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_range_comp_def(ctx: Context<InitRangeCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_vote_batch_comp_def(ctx: Context<InitVoteBatchCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_credit_bank_comp_def(ctx: Context<InitCreditBankCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_banked_vote_comp_def(ctx: Context<InitBankedVoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_seal_budget_comp_def(ctx: Context<InitSealBudgetCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_budgeted_vote_comp_def(ctx: Context<InitBudgetedVoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    // ================================================================
+    // Configuration
+    // ================================================================
+
+    /// Create the protocol `Config` and its `FeeVault`.  Only the program's
+    /// upgrade authority may sign it, once; `params.admin` then takes over.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        let vault = &mut ctx.accounts.fee_vault;
+        vault.bump = ctx.bumps.fee_vault;
+        vault.total_collected = 0;
+        vault.total_refunded = 0;
+        vault.total_withdrawn = 0;
+
+        let config = &mut ctx.accounts.config;
+        config.bump = ctx.bumps.config;
+        config.apply(&params)?;
+
+        emit_config_updated(config)
+    }
+
+    /// Replace every setting, including the admin.  Admin only.
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.apply(&params)?;
+
+        emit_config_updated(config)
+    }
+
+    /// Set `creator`'s registry flags: `whitelisted` for the higher
+    /// per-epoch proposal cap, `allowed` to create proposals while
+    /// `Config.permissioned_creation` is on.  Admin only.
+    pub fn set_creator(
+        ctx: Context<SetCreator>,
+        creator: Pubkey,
+        whitelisted: bool,
+        allowed: bool,
+    ) -> Result<()> {
+        let record = &mut ctx.accounts.creator_record;
+        record.bump = ctx.bumps.creator_record;
+        record.authority = creator;
+        record.whitelisted = whitelisted;
+        record.allowed = allowed;
+
+        let clock = Clock::get()?;
+        emit!(CreatorUpdatedEvent {
+            creator,
+            whitelisted,
+            allowed,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Move `amount` collected lamports from the fee vault to `recipient`.
+    /// Admin only; the vault's rent reserve can never be withdrawn.
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.fee_vault;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount <= vault.balance(), ErrorCode::InsufficientFees);
+
+        vault.total_withdrawn = vault
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::AmountOverflow)?;
+        vault.sub_lamports(amount)?;
+        ctx.accounts.recipient.add_lamports(amount)?;
+
+        let clock = Clock::get()?;
+        emit!(FeesWithdrawnEvent {
+            fee_vault: vault.key(),
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+            total_withdrawn: vault.total_withdrawn,
+            remaining: vault.balance(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     // ================================================================
     // Proposal Lifecycle
     // ================================================================
 
     /// Create a new proposal with up to 4 options, a voting deadline,
-    /// a voice credit budget, and a quorum threshold.
+    /// a voice credit budget, and a quorum threshold read per `quorum_kind`:
+    /// a voter count, or basis points of `electorate` registered voters, of
+    /// the voters the registrar issues credentials to before the first
+    /// ballot, or of the `quorum_mint` supply snapshotted here.
+    /// With a `gate_mint` only wallets holding at least `gate_threshold` of
+    /// it may vote, each passing its token account of the mint.
+    /// With a nonzero `registration_deadline` the options start empty and
+    /// are filled by `register_candidate` until that deadline freezes them.
+    /// `ballot` picks quadratic allocations (`cast_vote`), -2..+2 Likert
+    /// scores per option (`cast_likert`), For/Against/Abstain with a veto
+    /// (`cast_choice`, revealed by `reveal_outcome`), a pairwise-discounted
+    /// quadratic funding round (`cast_pairwise`, revealed by
+    /// `reveal_pairwise`), a reviewer panel's scores (`cast_jury_scores`
+    /// from jurors seated with `seat_juror`), approval of up to K options
+    /// (`cast_approval`) or a budget box (`cast_approval`, revealed by
+    /// `reveal_budget_box`).
     /// Queues an MPC computation to initialize encrypted tallies.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
         computation_offset: u64,
@@ -46,46 +505,85 @@ pub mod private_voting {
         options: Vec<String>,
         num_options: u8,
         deadline: i64,
+        registration_deadline: i64,
         voice_credits: u64,
         quorum: u32,
+        quorum_kind: QuorumKind,
+        electorate: u64,
+        gate_threshold: u64,
+        ballot: BallotKind,
         nonce: u128,
     ) -> Result<()> {
-        let proposal = &mut ctx.accounts.proposal_acc;
-        proposal.bump = ctx.bumps.proposal_acc;
-        proposal.id = id;
-        proposal.authority = ctx.accounts.payer.key();
-        proposal.nonce = nonce;
-        proposal.title = title;
-        proposal.options = options;
-        proposal.num_options = num_options;
-        proposal.deadline = deadline;
-        proposal.voice_credits = voice_credits;
-        proposal.quorum = quorum;
-        proposal.is_finalized = false;
-        proposal.voter_count = 0;
-        proposal.vote_state = [[0; 32]; 5];
-
-        let args = ArgBuilder::new().plaintext_u128(nonce).build();
-
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        open_proposal(
+            ctx,
+            ProposalKind::Governance,
+            computation_offset,
+            id,
+            title,
+            options,
+            num_options,
+            deadline,
+            registration_deadline,
+            voice_credits,
+            quorum,
+            quorum_kind,
+            electorate,
+            gate_threshold,
+            ballot,
+            nonce,
+        )
+    }
 
-        queue_computation(
-            ctx.accounts,
+    /// Create a non-binding poll for a temperature check.  It tallies with
+    /// the same circuits as a proposal but has no quorum and posts no
+    /// deposit, and anyone may reveal it with `reveal_poll` once the
+    /// deadline passes.  The proposal fee and per-epoch cap still apply.
+    /// A veto binds nothing on a poll, so For/Against/Abstain ballots are
+    /// for proposals only, and pairwise rounds need their authority's
+    /// `reveal_pairwise`.  Polls are never token-gated.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_poll(
+        ctx: Context<CreateProposal>,
+        computation_offset: u64,
+        id: u32,
+        title: String,
+        options: Vec<String>,
+        num_options: u8,
+        deadline: i64,
+        voice_credits: u64,
+        ballot: BallotKind,
+        nonce: u128,
+    ) -> Result<()> {
+        require!(
+            matches!(
+                ballot,
+                BallotKind::Quadratic
+                    | BallotKind::Likert
+                    | BallotKind::TimeWeighted { .. }
+                    | BallotKind::Approval { .. }
+                    | BallotKind::Borda
+                    | BallotKind::Range
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        open_proposal(
+            ctx,
+            ProposalKind::Poll,
             computation_offset,
-            args,
-            vec![InitTalliesCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[CallbackAccount {
-                    pubkey: ctx.accounts.proposal_acc.key(),
-                    is_writable: true,
-                }],
-            )?],
-            1,
+            id,
+            title,
+            options,
+            num_options,
+            deadline,
             0,
-        )?;
-
-        Ok(())
+            voice_credits,
+            0,
+            QuorumKind::Voters,
+            0,
+            0,
+            ballot,
+            nonce,
+        )
     }
 
     #[arcium_callback(encrypted_ix = "init_tallies")]
@@ -93,310 +591,18414 @@ pub mod private_voting {
         ctx: Context<InitTalliesCallback>,
         output: SignedComputationOutputs<InitTalliesOutput>,
     ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
         let o = match output.verify_output(
             &ctx.accounts.cluster_account,
             &ctx.accounts.computation_account,
         ) {
             Ok(InitTalliesOutput { field_0 }) => field_0,
-            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::InitTallies,
+                    aborted,
+                )
+            }
         };
 
-        ctx.accounts.proposal_acc.vote_state = o.ciphertexts;
-        ctx.accounts.proposal_acc.nonce = o.nonce;
-
-        Ok(())
+        store_tally(&ctx.accounts.tally_acc, o.ciphertexts, o.nonce)
     }
 
     // ================================================================
-    // Quadratic Voting
+    // Categories
     // ================================================================
 
-    /// Cast a quadratic vote.
-    ///
-    /// The voter encrypts their credit allocation (v0, v1, v2, v3) where
-    /// each value is the number of effective votes for that option.
-    /// The quadratic cost v0² + v1² + v2² + v3² is verified inside MPC
-    /// against the 100 voice credit budget.  Nobody sees individual allocations.
-    ///
-    /// Creates a VoterRecord PDA to prevent double-voting.
-    pub fn cast_vote(
-        ctx: Context<CastVote>,
-        computation_offset: u64,
-        _id: u32,
-        vote_v0: [u8; 32],
-        vote_v1: [u8; 32],
-        vote_v2: [u8; 32],
-        vote_v3: [u8; 32],
-        vote_encryption_pubkey: [u8; 32],
-        vote_nonce: u128,
-    ) -> Result<()> {
-        let clock = Clock::get()?;
+    /// Create or update category `category` in the registry.  Admin only;
+    /// changes apply to proposals assigned afterwards.
+    pub fn set_category(ctx: Context<SetCategory>, category: u16, params: CategoryParams) -> Result<()> {
         require!(
-            clock.unix_timestamp < ctx.accounts.proposal_acc.deadline,
-            ErrorCode::VotingPeriodEnded
+            !params.name.is_empty()
+                && params.name.len() <= 32
+                && params.min_duration >= 0
+                && (params.max_duration == 0 || params.max_duration >= params.min_duration),
+            ErrorCode::InvalidCategory
         );
 
+        let entry = &mut ctx.accounts.category_acc;
+        entry.bump = ctx.bumps.category_acc;
+        entry.id = category;
+        entry.name = params.name.clone();
+        entry.default_quorum = params.default_quorum;
+        entry.min_duration = params.min_duration;
+        entry.max_duration = params.max_duration;
+        entry.active = params.active;
+
+        emit!(CategoryUpdatedEvent {
+            category,
+            name: params.name,
+            default_quorum: params.default_quorum,
+            min_duration: params.min_duration,
+            max_duration: params.max_duration,
+            active: params.active,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// File the proposal under an active category.  Its voting period
+    /// (creation to deadline) must fit the category's bounds, and a
+    /// voter-count quorum left at 0 takes the category's default.
+    /// Authority-only, once, before the first ballot.
+    pub fn assign_category(ctx: Context<AssignCategory>, category: u16) -> Result<()> {
+        let entry = &ctx.accounts.category_acc;
+        require!(entry.active, ErrorCode::CategoryInactive);
+
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(proposal.category.is_none(), ErrorCode::CategoryAlreadySet);
+        let duration = proposal.deadline - proposal.created_at;
         require!(
-            !ctx.accounts.proposal_acc.is_finalized,
-            ErrorCode::ProposalAlreadyFinalized
+            duration >= entry.min_duration && (entry.max_duration == 0 || duration <= entry.max_duration),
+            ErrorCode::DurationOutsideCategory
         );
+        proposal.category = Some(category);
+        if proposal.quorum_kind == QuorumKind::Voters && proposal.quorum == 0 {
+            proposal.quorum = entry.default_quorum;
+        }
 
-        // VoterRecord init fails if PDA already exists = double vote prevention
-        let voter_record = &mut ctx.accounts.voter_record;
-        voter_record.bump = ctx.bumps.voter_record;
-        voter_record.proposal = ctx.accounts.proposal_acc.key();
-        voter_record.voter = ctx.accounts.payer.key();
-        voter_record.has_voted = true;
+        emit!(CategoryAssignedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            category,
+            quorum: proposal.quorum,
+        });
 
-        ctx.accounts.proposal_acc.voter_count += 1;
+        Ok(())
+    }
 
-        // ArgBuilder order must match circuit params:
-        // cast_vote(alloc_ctxt: Enc<Shared, VoteAllocation>, tallies_ctxt: Enc<Mxe, VoteTallies>)
-        let args = ArgBuilder::new()
-            // VoteAllocation: Enc<Shared, VoteAllocation>
-            .x25519_pubkey(vote_encryption_pubkey)
-            .plaintext_u128(vote_nonce)
-            .encrypted_u64(vote_v0)
-            .encrypted_u64(vote_v1)
-            .encrypted_u64(vote_v2)
-            .encrypted_u64(vote_v3)
-            // VoteTallies: Enc<Mxe, VoteTallies>
-            .plaintext_u128(ctx.accounts.proposal_acc.nonce)
-            .account(
-                ctx.accounts.proposal_acc.key(),
-                8 + 1, // discriminator + bump
-                32 * 5, // 5 encrypted u64 counters
-            )
-            .build();
+    // ================================================================
+    // Ballots
+    // ================================================================
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    /// Group the proposals passed as writable remaining accounts into
+    /// ballot `id`, such as the races of one election.  Sent after their
+    /// `create_proposal`s in the same transaction, it makes their creation
+    /// and queued tally initializations all-or-nothing.  The 2 to
+    /// `MAX_BALLOT_PROPOSALS` proposals are the authority's, on no other
+    /// ballot, before their first ballot and square-root credits, and share
+    /// one deadline and registration deadline, which the ballot records.
+    pub fn create_ballot(ctx: Context<CreateBallot>, id: u32, title: String) -> Result<()> {
+        let title = sanitize_label(&title, MAX_TITLE_LEN).ok_or(ErrorCode::InvalidTitle)?;
+        require!(
+            (2..=MAX_BALLOT_PROPOSALS).contains(&ctx.remaining_accounts.len()),
+            ErrorCode::InvalidBallot
+        );
+        let authority = ctx.accounts.authority.key();
+        let ballot_key = ctx.accounts.ballot_acc.key();
+        let now = Clock::get()?.unix_timestamp;
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            vec![CastVoteCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[CallbackAccount {
-                    pubkey: ctx.accounts.proposal_acc.key(),
-                    is_writable: true,
-                }],
-            )?],
-            1,
-            0,
-        )?;
+        let mut proposals = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut shared = None;
+        for info in ctx.remaining_accounts {
+            require!(
+                info.owner == &crate::ID && info.is_writable && !proposals.contains(info.key),
+                ErrorCode::InvalidBallot
+            );
+            let mut proposal = ProposalAccount::try_deserialize(&mut &info.try_borrow_data()?[..])
+                .map_err(|_| error!(ErrorCode::InvalidBallot))?;
+            require!(proposal.authority == authority, ErrorCode::InvalidAuthority);
+            require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+            check_stage(proposal.state(now), Stage::Setup)?;
+            require!(proposal.on_ballot.is_none(), ErrorCode::ProposalAlreadyOnBallot);
+            // Registrations made so far would be the proposal's, not the
+            // ballot's.
+            require!(proposal.credit_mint.is_none(), ErrorCode::SqrtCreditsAlreadyOpen);
+            let deadlines = (proposal.deadline, proposal.registration_deadline);
+            require!(*shared.get_or_insert(deadlines) == deadlines, ErrorCode::InvalidBallot);
+            proposal.on_ballot = Some(ballot_key);
+            proposal.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            proposals.push(info.key());
+        }
+        let (deadline, registration_deadline) = shared.ok_or(ErrorCode::InvalidBallot)?;
+
+        let ballot = &mut ctx.accounts.ballot_acc;
+        ballot.bump = ctx.bumps.ballot_acc;
+        ballot.id = id;
+        ballot.authority = authority;
+        ballot.title = title.clone();
+        ballot.deadline = deadline;
+        ballot.registration_deadline = registration_deadline;
+        ballot.proposals = proposals.clone();
+
+        emit!(BallotCreatedEvent {
+            ballot: ballot_key,
+            ballot_id: id,
+            authority,
+            title,
+            proposals,
+            deadline,
+            registration_deadline,
+            timestamp: now,
+        });
 
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "cast_vote")]
-    pub fn cast_vote_callback(
-        ctx: Context<CastVoteCallback>,
-        output: SignedComputationOutputs<CastVoteOutput>,
-    ) -> Result<()> {
-        let o = match output.verify_output(
-            &ctx.accounts.cluster_account,
-            &ctx.accounts.computation_account,
-        ) {
-            Ok(CastVoteOutput { field_0 }) => field_0,
-            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    /// Record the ballot's votes per voter rather than per proposal: each
+    /// voter gets one `RoundRecord` `[b"round_voter", ballot, voter]` with
+    /// a bit per proposal, so a ten-question election costs one rent
+    /// deposit per voter instead of ten.  The ballot's proposals are
+    /// passed as writable remaining accounts in its order; each must be a
+    /// For/Against/Abstain proposal open to anyone, without a reward pool,
+    /// before its first ballot.  From then on they are voted with
+    /// `cast_round_choice`, which every other ballot path refuses them for.
+    pub fn open_round_records(ctx: Context<OpenRoundRecords>) -> Result<()> {
+        round_records::open_round_records(ctx)
+    }
 
-        ctx.accounts.proposal_acc.vote_state = o.ciphertexts;
-        ctx.accounts.proposal_acc.nonce = o.nonce;
+    // ================================================================
+    // Tally Commitments
+    // ================================================================
 
-        let clock = Clock::get()?;
-        emit!(VoteCastEvent {
-            proposal_id: ctx.accounts.proposal_acc.id,
-            timestamp: clock.unix_timestamp,
-            voter_count: ctx.accounts.proposal_acc.voter_count,
+    /// Checkpoint the encrypted tally: fold its `tally_leaf` — ciphertexts,
+    /// nonce and applied-ballot count — into the proposal's `Commitment`,
+    /// created on the first call.  Permissionless; a keeper calls it
+    /// periodically and once more after the last ballot lands, and `payer`
+    /// funds the account.  Replaying the `TallyCommittedEvent`s recomputes
+    /// the root, so light clients and auditors can check that the tally a
+    /// reveal read is the one every ballot went into.  Refused while the
+    /// tally is unchanged since the last checkpoint.
+    pub fn commit_tally(ctx: Context<CommitTally>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        let (vote_state, nonce) = {
+            let tally = ctx.accounts.tally_acc.load()?;
+            (tally.vote_state, tally.nonce())
+        };
+        let leaf = tally_leaf(&proposal.key(), &vote_state, nonce, proposal.applied_ballots);
+        let commitment = &mut ctx.accounts.commitment;
+        require!(
+            commitment.checkpoints == 0 || leaf != commitment.leaf,
+            ErrorCode::TallyUnchanged
+        );
+        let now = Clock::get()?.unix_timestamp;
+        commitment.bump = ctx.bumps.commitment;
+        commitment.proposal = proposal.key();
+        commitment.root = fold_commitment(&commitment.root, &leaf);
+        commitment.leaf = leaf;
+        commitment.applied_ballots = proposal.applied_ballots;
+        commitment.checkpoints = commitment.checkpoints.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+        commitment.committed_at = now;
+
+        emit!(TallyCommittedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            vote_state,
+            nonce,
+            applied_ballots: proposal.applied_ballots,
+            leaf,
+            root: commitment.root,
+            checkpoint: commitment.checkpoints,
+            timestamp: now,
         });
 
         Ok(())
     }
 
     // ================================================================
-    // Reveal
+    // Square-Root Credits
     // ================================================================
 
-    /// Reveal results.  Only callable by the proposal authority, after the
-    /// deadline, and only when quorum is met (voter_count >= quorum).
-    pub fn reveal_results(
-        ctx: Context<RevealResults>,
-        computation_offset: u64,
-        id: u32,
-    ) -> Result<()> {
+    /// Give each voter `floor(sqrt(balance))` voice credits, from their
+    /// balance of `credit_mint`, instead of the flat `voice_credits`: the canonical
+    /// quadratic-voting weight curve, so ten times the tokens buy only about
+    /// three times the say.  Voters `register_credits` before casting with
+    /// `cast_vote` or `cast_vote_session`; anonymous and histogram ballots
+    /// are refused.  Registering escrows the tokens until the deadline,
+    /// which from then on can't be extended.  Authority-only, on a
+    /// quadratic or time-weighted proposal, before the first ballot.
+    pub fn open_sqrt_credits(ctx: Context<OpenSqrtCredits>) -> Result<()> {
+        credits::open_sqrt_credits(ctx)
+    }
+
+    /// Record the voter's square-root credits from their balance of the
+    /// proposal's `credit_mint`, once per voter and until the deadline.  The
+    /// whole balance moves into the record's `credit_vault` until the
+    /// deadline, so the same tokens can't register again from another
+    /// wallet.  The voter signs and pays for the `VoterCredits` and its
+    /// vault.  On a `Ballot` the record is the ballot's, and serves every
+    /// proposal on it that opened credits of the same mint.
+    pub fn register_credits(ctx: Context<RegisterCredits>) -> Result<()> {
+        credits::register_credits(ctx)
+    }
+
+    /// Return the tokens `register_credits` escrowed once the deadline has
+    /// passed, closing the `VoterCredits` and its vault.  Voter-only.
+    pub fn release_credits(ctx: Context<ReleaseCredits>) -> Result<()> {
+        credits::release_credits(ctx)
+    }
+
+    // ================================================================
+    // Funded Credits
+    // ================================================================
+
+    /// Give `voter` a budget of `credits` voice credits on a quadratic or
+    /// time-weighted proposal, in place of its flat `voice_credits`, so
+    /// contributors can be handed more than newcomers.  Authority-only —
+    /// a program PDA authority can fund by rule — and until the deadline;
+    /// calling it again sets a new amount.  The first call, which must
+    /// come before the first ballot, makes every voter's budget their
+    /// `CreditAccount`: unfunded voters fail with `CreditsNotFunded`.  Not
+    /// with square-root credits, and anonymous, histogram and batched
+    /// ballots are refused.
+    pub fn fund_credits(ctx: Context<FundCredits>, voter: Pubkey, credits: u64) -> Result<()> {
+        credits::fund_credits(ctx, voter, credits)
+    }
+
+    // ================================================================
+    // Burn-to-Vote
+    // ================================================================
+
+    /// Make each `cast_vote` burn one whole `burn_mint` token from the
+    /// voter: one token, one ballot, and every sybil wallet must be funded
+    /// with a token that is gone once it votes.  Session, anonymous and
+    /// histogram ballots can't burn on the voter's behalf and are refused.
+    /// Authority-only, on a quadratic or time-weighted proposal, before the
+    /// first ballot.
+    pub fn open_burn_to_vote(ctx: Context<OpenBurnToVote>) -> Result<()> {
+        let mint = &ctx.accounts.burn_mint;
+        // A whole token, 10^decimals base units, must fit in a u64.
+        require!(mint.decimals <= 19, ErrorCode::InvalidBurnMint);
+        let mint = mint.key();
+        let proposal = &mut ctx.accounts.proposal_acc;
         require!(
-            ctx.accounts.payer.key() == ctx.accounts.proposal_acc.authority,
-            ErrorCode::InvalidAuthority
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
         );
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(proposal.burn_mint.is_none(), ErrorCode::BurnToVoteAlreadyOpen);
+        require!(!proposal.spend_histogram, ErrorCode::BurnToVoteUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        proposal.burn_mint = Some(mint);
 
-        let clock = Clock::get()?;
+        emit!(BurnToVoteOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            mint,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Eligibility Oracle
+    // ================================================================
+
+    /// Hand eligibility to `oracle`, an external program implementing
+    /// `can_vote` (see `CAN_VOTE_DISCRIMINATOR`): every `cast_vote` asks it
+    /// about the payer and multiplies the ballot's weight by its answer, at
+    /// most `MAX_ORACLE_WEIGHT`.  With token weights open, their cap must
+    /// leave room for that.  Session, anonymous and histogram ballots
+    /// can't be vouched for and are refused.  Authority-only, on a quadratic or time-weighted
+    /// proposal, before the first ballot.
+    pub fn set_eligibility_oracle(ctx: Context<SetEligibilityOracle>) -> Result<()> {
+        let oracle = ctx.accounts.oracle.key();
+        require!(oracle != crate::ID, ErrorCode::InvalidEligibilityOracle);
+        let proposal = &mut ctx.accounts.proposal_acc;
         require!(
-            clock.unix_timestamp >= ctx.accounts.proposal_acc.deadline,
-            ErrorCode::VotingPeriodNotEnded
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
         );
-
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
         require!(
-            !ctx.accounts.proposal_acc.is_finalized,
-            ErrorCode::ProposalAlreadyFinalized
+            proposal.eligibility_oracle.is_none(),
+            ErrorCode::EligibilityOracleAlreadySet
         );
-
+        require!(!proposal.spend_histogram, ErrorCode::EligibilityOracleUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        proposal.eligibility_oracle = Some(oracle);
         require!(
-            ctx.accounts.proposal_acc.voter_count >= ctx.accounts.proposal_acc.quorum,
-            ErrorCode::QuorumNotMet
+            proposal.weight_mint.is_none() || proposal.token_weight_fits(proposal.budget_ceiling()),
+            ErrorCode::InvalidTokenWeightCap
         );
 
-        msg!(
-            "Revealing results for proposal {} (id={})",
-            ctx.accounts.proposal_acc.title,
-            id
+        emit!(EligibilityOracleSetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            oracle,
+        });
+
+        Ok(())
+    }
+
+    /// Bend the eligibility oracle's weights, or the token balances of
+    /// `open_token_weights`, with `curve` before they multiply a ballot:
+    /// capped, square-rooted or logarithmic, so the largest holders aren't
+    /// handed the vote.  The `cast_vote` circuit applies the curve, so the
+    /// weight each ballot counts with is never computed outside MPC.  Mix
+    /// windows hold ballots already weighted and are refused.
+    /// Authority-only, after `set_eligibility_oracle` or
+    /// `open_token_weights`, before the first ballot; `Linear` undoes it.
+    pub fn set_weight_curve(ctx: Context<SetWeightCurve>, curve: WeightCurve) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(
+            (proposal.eligibility_oracle.is_some() || proposal.weight_mint.is_some()) && proposal.mix_window == 0,
+            ErrorCode::WeightCurveUnsupported
         );
+        require!(curve != WeightCurve::Cap { max: 0 }, ErrorCode::InvalidWeightCurve);
+        proposal.weight_curve = curve;
 
-        let args = ArgBuilder::new()
-            .plaintext_u128(ctx.accounts.proposal_acc.nonce)
-            .account(
-                ctx.accounts.proposal_acc.key(),
-                8 + 1,
-                32 * 5,
-            )
-            .build();
+        emit!(WeightCurveSetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            curve,
+        });
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            vec![RevealResultsCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
-                &[CallbackAccount {
-                    pubkey: ctx.accounts.proposal_acc.key(),
-                    is_writable: true,
-                }],
-            )?],
-            1,
-            0,
-        )?;
+    // ================================================================
+    // Token-Weighted Voting
+    // ================================================================
+
+    /// Weigh each `cast_vote` by the payer's balance of `weight_mint`, read
+    /// from the token account passed with the ballot and fed to the circuit
+    /// as the stake: one whole token, one vote, up to `max_weight` tokens,
+    /// bent by `set_weight_curve` if set and multiplied by the eligibility
+    /// oracle's weight if there is one.  `max_weight` must keep
+    /// `MAX_VOTERS` ballots of the largest budget the proposal allows, at
+    /// `MAX_ORACLE_WEIGHT` with an oracle, from overflowing the tallies.  The balance is read as the ballot lands,
+    /// so pair it with a non-transferable or locked mint.  Session,
+    /// anonymous and histogram ballots can't show a balance and are
+    /// refused.  Authority-only, on a quadratic or time-weighted proposal,
+    /// before the first ballot.
+    pub fn open_token_weights(ctx: Context<OpenTokenWeights>, max_weight: u64) -> Result<()> {
+        let mint = ctx.accounts.weight_mint.key();
+        let decimals = ctx.accounts.weight_mint.decimals;
+        // A whole token, 10^decimals base units, must fit in a u64.
+        require!(decimals <= 19, ErrorCode::InvalidWeightMint);
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(proposal.weight_mint.is_none(), ErrorCode::TokenWeightsAlreadyOpen);
+        require!(!proposal.spend_histogram, ErrorCode::TokenWeightsUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        proposal.weight_mint = Some(mint);
+        proposal.weight_decimals = decimals;
+        proposal.max_token_weight = max_weight;
+        require!(
+            max_weight > 0 && proposal.token_weight_fits(proposal.budget_ceiling()),
+            ErrorCode::InvalidTokenWeightCap
+        );
+
+        emit!(TokenWeightsOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            mint,
+            max_weight,
+        });
 
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "reveal_results")]
-    pub fn reveal_results_callback(
-        ctx: Context<RevealResultsCallback>,
-        output: SignedComputationOutputs<RevealResultsOutput>,
-    ) -> Result<()> {
-        let o = match output.verify_output(
-            &ctx.accounts.cluster_account,
-            &ctx.accounts.computation_account,
-        ) {
-            Ok(RevealResultsOutput {
-                field_0,
-                field_1,
-                field_2,
-                field_3,
-                field_4,
-                field_5,
-            }) => (field_0, field_1, field_2, field_3, field_4, field_5),
-            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    // ================================================================
+    // Candidate Registration
+    // ================================================================
+
+    /// Stand as a candidate while the proposal's registration window is
+    /// open.  `name` becomes the next option, first come first served, and
+    /// `Config.candidate_deposit` is bonded on the `Candidate` account.
+    pub fn register_candidate(ctx: Context<RegisterCandidate>, name: String) -> Result<()> {
+        let clock = Clock::get()?;
+        let proposal = &mut ctx.accounts.proposal_acc;
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Registration)?;
+        let name = sanitize_label(&name, MAX_OPTION_LEN).ok_or(ErrorCode::InvalidCandidateName)?;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(proposal.options.len() < 4, ErrorCode::CandidateSlotsFull);
+        require!(!proposal.options.contains(&name), ErrorCode::DuplicateOption);
+
+        let index = proposal.options.len() as u8;
+        proposal.options.push(name.clone());
+        proposal.num_options = index + 1;
 
-        ctx.accounts.proposal_acc.is_finalized = true;
+        let deposit = ctx.accounts.config.candidate_deposit;
+        let candidate = &mut ctx.accounts.candidate_acc;
+        candidate.bump = ctx.bumps.candidate_acc;
+        candidate.proposal = proposal.key();
+        candidate.candidate = ctx.accounts.candidate.key();
+        candidate.index = index;
+        candidate.name = name.clone();
+        candidate.deposit = deposit;
 
-        emit!(ResultsRevealedEvent {
+        if deposit > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.candidate.to_account_info(),
+                        to: ctx.accounts.candidate_acc.to_account_info(),
+                    },
+                ),
+                deposit,
+            )?;
+        }
+
+        emit!(CandidateRegisteredEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
             proposal_id: ctx.accounts.proposal_acc.id,
-            option_0: o.0,
-            option_1: o.1,
-            option_2: o.2,
-            option_3: o.3,
-            total_votes: o.4,
-            winner: o.5,
+            candidate: ctx.accounts.candidate.key(),
+            index,
+            name,
+            options: ctx.accounts.proposal_acc.options.clone(),
+            deposit,
+            timestamp: clock.unix_timestamp,
         });
 
         Ok(())
     }
-}
 
-// ============================================================
-// Account Structs — Computation Definition Initializers
-// ============================================================
+    /// Close a `Candidate` account, returning its rent and deposit to the
+    /// candidate, once the proposal is finalized, failed quorum, or was
+    /// closed.
+    pub fn close_candidate(ctx: Context<CloseCandidate>) -> Result<()> {
+        // A closed proposal was settled first, so only a live one is checked.
+        let info = ctx.accounts.proposal_acc.to_account_info();
+        if !info.data_is_empty() {
+            let proposal = ProposalAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+        }
 
-#[init_computation_definition_accounts("init_tallies", payer)]
-#[derive(Accounts)]
-pub struct InitTalliesCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account, checked by arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
-    /// CHECK: address_lookup_table, checked by arcium program.
-    pub address_lookup_table: UncheckedAccount<'info>,
-    #[account(address = LUT_PROGRAM_ID)]
-    /// CHECK: lut_program is the Address Lookup Table program.
-    pub lut_program: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+        Ok(())
+    }
 
-#[init_computation_definition_accounts("cast_vote", payer)]
-#[derive(Accounts)]
-pub struct InitVoteCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account, checked by arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
-    /// CHECK: address_lookup_table, checked by arcium program.
-    pub address_lookup_table: UncheckedAccount<'info>,
-    #[account(address = LUT_PROGRAM_ID)]
-    /// CHECK: lut_program is the Address Lookup Table program.
-    pub lut_program: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+    // ================================================================
+    // Option Details
+    // ================================================================
 
-#[init_computation_definition_accounts("reveal_results", payer)]
-#[derive(Accounts)]
-pub struct InitRevealCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    /// CHECK: comp_def_account, checked by arcium program.
-    pub comp_def_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
-    /// CHECK: address_lookup_table, checked by arcium program.
-    pub address_lookup_table: UncheckedAccount<'info>,
-    #[account(address = LUT_PROGRAM_ID)]
-    /// CHECK: lut_program is the Address Lookup Table program.
-    pub lut_program: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
-}
+    /// Say what each option concretely means — who it pays, how much, and
+    /// a metadata URI — so execution adapters can act on the winner
+    /// without out-of-band configuration.  `details[i]` describes option
+    /// i; options past the end have none.  Authority-only, and may be
+    /// called again to replace them until the first ballot freezes the
+    /// options.
+    pub fn set_option_details(ctx: Context<SetOptionDetails>, details: Vec<OptionDetail>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(
+            details.len() <= proposal.options.len()
+                && details.iter().all(|detail| {
+                    detail.metadata_uri.len() <= MAX_METADATA_URI_LEN
+                        && !detail.metadata_uri.chars().any(|c| c.is_whitespace() || is_hidden_char(c))
+                }),
+            ErrorCode::InvalidOptionDetails
+        );
 
-// ============================================================
-// Account Structs — Proposal
-// ============================================================
+        let acc = &mut ctx.accounts.option_details;
+        acc.bump = ctx.bumps.option_details;
+        acc.proposal = proposal.key();
+        acc.details = details.clone();
 
-#[queue_computation_accounts("init_tallies", payer)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64, id: u32)]
-pub struct CreateProposal<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed, space = 9, payer = payer,
-        seeds = [&SIGN_PDA_SEED], bump,
+        emit!(OptionDetailsSetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            details,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Translations
+    // ================================================================
+
+    /// Add or replace the proposal's title and option labels in `locale`,
+    /// a BCP 47 tag such as `pt-BR`, so front ends can render the ballot in
+    /// the voter's language.  `options[i]` translates option i; options
+    /// past the end fall back to the canonical label, which never changes.
+    /// Authority-only, at any time: a translation carries no weight, and
+    /// every change is announced with `TranslationSetEvent`.
+    pub fn set_translation(
+        ctx: Context<SetTranslation>,
+        locale: String,
+        title: String,
+        options: Vec<String>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(
+            is_locale(&locale) && options.len() <= proposal.options.len(),
+            ErrorCode::InvalidTranslation
+        );
+        let title = sanitize_label(&title, MAX_TITLE_LEN).ok_or(ErrorCode::InvalidTitle)?;
+        let options = options
+            .iter()
+            .map(|option| sanitize_label(option, MAX_OPTION_LEN).ok_or(ErrorCode::InvalidOptionLabel))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let acc = &mut ctx.accounts.translations;
+        acc.bump = ctx.bumps.translations;
+        acc.proposal = proposal.key();
+        let translation = Translation {
+            locale: locale.clone(),
+            title: title.clone(),
+            options: options.clone(),
+        };
+        match acc.translations.iter_mut().find(|t| t.locale == locale) {
+            Some(existing) => *existing = translation,
+            None => {
+                require!(
+                    acc.translations.len() < MAX_TRANSLATIONS,
+                    ErrorCode::TooManyTranslations
+                );
+                acc.translations.push(translation);
+            }
+        }
+
+        emit!(TranslationSetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            locale,
+            title,
+            options,
+        });
+
+        Ok(())
+    }
+
+    /// Drop the proposal's translation into `locale`.  Authority-only.
+    pub fn remove_translation(ctx: Context<RemoveTranslation>, locale: String) -> Result<()> {
+        let acc = &mut ctx.accounts.translations;
+        let index = acc
+            .translations
+            .iter()
+            .position(|t| t.locale == locale)
+            .ok_or(ErrorCode::TranslationNotFound)?;
+        acc.translations.remove(index);
+
+        emit!(TranslationRemovedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            locale,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Amendments
+    // ================================================================
+
+    /// Make the proposal an amendment of `parent`: it takes no ballots
+    /// until `confirm_parent_outcome` shows `parent` finalized with option
+    /// `winner` winning.  Authority-only, before the first ballot.
+    pub fn require_parent_outcome(ctx: Context<RequireParentOutcome>, parent: Pubkey, winner: u8) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(
+            parent != proposal.key() && winner < 4,
+            ErrorCode::InvalidParentOutcome
+        );
+        proposal.requires_parent_outcome = Some(ParentOutcome { parent, winner });
+        proposal.parent_outcome_met = false;
+
+        emit!(ParentOutcomeRequiredEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            parent,
+            winner,
+        });
+
+        Ok(())
+    }
+
+    /// Open an amendment for voting once its parent has finalized with the
+    /// required winner.  Permissionless.
+    pub fn confirm_parent_outcome(ctx: Context<ConfirmParentOutcome>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let required = proposal
+            .requires_parent_outcome
+            .ok_or(ErrorCode::NoParentOutcome)?;
+        let parent = &ctx.accounts.parent_acc;
+        require!(
+            parent.key() == required.parent,
+            ErrorCode::InvalidParentOutcome
+        );
+        require!(
+            parent.is_finalized && parent.winner == required.winner,
+            ErrorCode::ParentOutcomeNotMet
+        );
+        proposal.parent_outcome_met = true;
+
+        emit!(ParentOutcomeMetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            parent: parent.key(),
+            winner: parent.winner,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Proposal Chains
+    // ================================================================
+
+    /// Chain the proposal to a For/Against/Abstain `parent`: it takes no
+    /// ballots until `resolve_dependency` shows the parent decided as
+    /// `condition` requires.  Authority-only, before the first ballot; the
+    /// `Dependency` PDA records the link.
+    pub fn declare_dependency(ctx: Context<DeclareDependency>, condition: DependencyCondition) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        let parent = &ctx.accounts.parent_acc;
+        require!(
+            parent.key() != proposal.key() && matches!(parent.ballot, BallotKind::YesNoAbstain { .. }),
+            ErrorCode::InvalidDependency
+        );
+        proposal.awaiting_dependency = true;
+
+        let dependency = &mut ctx.accounts.dependency;
+        dependency.bump = ctx.bumps.dependency;
+        dependency.proposal = proposal.key();
+        dependency.parent = parent.key();
+        dependency.condition = condition;
+        dependency.resolved = false;
+
+        emit!(DependencyDeclaredEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            parent: parent.key(),
+            condition,
+        });
+
+        Ok(())
+    }
+
+    /// Open a chained proposal for voting once its parent was decided the
+    /// way its `Dependency` requires.  Permissionless.
+    pub fn resolve_dependency(ctx: Context<ResolveDependency>) -> Result<()> {
+        let dependency = &mut ctx.accounts.dependency;
+        let parent = &ctx.accounts.parent_acc;
+        require!(
+            parent.key() == dependency.parent,
+            ErrorCode::InvalidDependency
+        );
+        require!(
+            dependency.condition.is_met_by(parent),
+            ErrorCode::DependencyNotMet
+        );
+        dependency.resolved = true;
+
+        let proposal = &mut ctx.accounts.proposal_acc;
+        proposal.awaiting_dependency = false;
+
+        emit!(DependencyResolvedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            parent: parent.key(),
+            condition: dependency.condition,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Sanctions
+    // ================================================================
+
+    /// Put a sanction to the vote: the For/Against/Abstain proposal decides
+    /// whether to slash another proposal's deposit or strip a creator of
+    /// its registry flags, and `execute_sanction` carries it out if the
+    /// proposal passes.  Authority-only, before the first ballot; the
+    /// `Sanction` PDA records the action.
+    pub fn declare_sanction(ctx: Context<DeclareSanction>, action: SanctionAction) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(
+            matches!(proposal.ballot, BallotKind::YesNoAbstain { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(
+            action.target() != proposal.key() && action.target() != proposal.authority,
+            ErrorCode::InvalidSanction
+        );
+
+        let sanction = &mut ctx.accounts.sanction;
+        sanction.bump = ctx.bumps.sanction;
+        sanction.proposal = proposal.key();
+        sanction.action = action;
+        sanction.executed = false;
+
+        emit!(SanctionDeclaredEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            action,
+        });
+
+        Ok(())
+    }
+
+    /// Carry out a sanction its proposal passed: move the target proposal's
+    /// deposit to `Config.treasury`, or clear the creator's `whitelisted`
+    /// and `allowed` flags.  Permissionless, once.
+    pub fn execute_sanction(ctx: Context<ExecuteSanction>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        let sanction = &mut ctx.accounts.sanction;
+        require!(!sanction.executed, ErrorCode::SanctionAlreadyExecuted);
+        require!(
+            proposal.outcome == Some(VoteOutcome::Passed),
+            ErrorCode::SanctionNotPassed
+        );
+        sanction.executed = true;
+
+        let amount = match sanction.action {
+            SanctionAction::SlashDeposit { proposal: target, .. } => {
+                let (Some(target_acc), Some(treasury)) = (&mut ctx.accounts.target_acc, &ctx.accounts.treasury) else {
+                    return err!(ErrorCode::InvalidSanctionTarget);
+                };
+                require!(target_acc.key() == target, ErrorCode::InvalidSanctionTarget);
+                require!(treasury.key() == ctx.accounts.config.treasury, ErrorCode::InvalidTreasury);
+                let amount = target_acc.deposit;
+                require!(amount > 0, ErrorCode::NoDeposit);
+                target_acc.deposit = 0;
+                target_acc.sub_lamports(amount)?;
+                treasury.add_lamports(amount)?;
+                amount
+            }
+            SanctionAction::RemoveCreator { creator } => {
+                let Some(record) = &mut ctx.accounts.creator_record else {
+                    return err!(ErrorCode::InvalidSanctionTarget);
+                };
+                require!(record.authority == creator, ErrorCode::InvalidSanctionTarget);
+                record.whitelisted = false;
+                record.allowed = false;
+                0
+            }
+        };
+
+        let clock = Clock::get()?;
+        emit!(SanctionExecutedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            action: sanction.action,
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Write-ins
+    // ================================================================
+
+    /// Accept write-in candidates on a quadratic proposal whose option
+    /// list can't be exhaustive.  Authority-only, before the deadline.
+    /// Creates the proposal's `WriteInTally` and queues an MPC computation
+    /// to initialize its encrypted table; `cast_write_in` is open once the
+    /// callback lands.
+    pub fn open_write_ins(
+        ctx: Context<OpenWriteIns>,
+        computation_offset: u64,
+        _id: u32,
+        nonce: u128,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+
+        let write_in_tally = &mut ctx.accounts.write_in_tally;
+        write_in_tally.bump = ctx.bumps.write_in_tally;
+        write_in_tally.proposal = proposal.key();
+
+        // init_write_ins(mxe: Mxe)
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![InitWriteInsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.write_in_tally.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_write_ins")]
+    pub fn init_write_ins_callback(
+        ctx: Context<InitWriteInsCallback>,
+        output: SignedComputationOutputs<InitWriteInsOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitWriteInsOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::InitTallies,
+                    aborted,
+                )
+            }
+        };
+
+        let write_in_tally = &mut ctx.accounts.write_in_tally;
+        write_in_tally.state = o.ciphertexts;
+        write_in_tally.nonce = o.nonce;
+        write_in_tally.ready = true;
+
+        Ok(())
+    }
+
+    /// Cast a write-in instead of a listed-option ballot: the voter's hash
+    /// of a candidate identifier, truncated to 128 bits and encrypted.
+    /// The circuit adds it to the matching entry of the `WriteInTally`, or
+    /// claims a free one, without revealing which; past `MAX_WRITE_INS`
+    /// distinct candidates new names are dropped.  The voter record,
+    /// turnout, fees and sponsorship are as for `cast_vote`, so a voter
+    /// either votes the listed options or writes someone in.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_write_in(
+        ctx: Context<CastWriteIn>,
+        computation_offset: u64,
+        _id: u32,
+        candidate: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.write_in_tally.ready, ErrorCode::WriteInsNotReady);
+        require!(
+            ctx.accounts.proposal_acc.commit_deadline == 0,
+            ErrorCode::CommitPhaseUnsupported
+        );
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_write_in(write_in_ctxt: Enc<Shared, WriteIn>, write_ins_ctxt: Enc<Mxe, WriteIns>)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u128(candidate)
+            .plaintext_u128(ctx.accounts.write_in_tally.nonce)
+            .account(
+                ctx.accounts.write_in_tally.key(),
+                8 + 1, // discriminator + bump
+                32 * 2 * MAX_WRITE_INS as u32, // encrypted hashes then counts
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastWriteInCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.write_in_tally.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_write_in")]
+    pub fn cast_write_in_callback(
+        ctx: Context<CastWriteInCallback>,
+        output: SignedComputationOutputs<CastWriteInOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastWriteInOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.write_in_tally.state = o.ciphertexts;
+        ctx.accounts.write_in_tally.nonce = o.nonce;
+
+        mark_counted(
+            ctx.accounts.proposal_acc.key(),
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.voter_record,
+        )
+    }
+
+    /// Reveal the top write-in and its vote count once the proposal is
+    /// finalized.  Authority-only, once per proposal; every other write-in
+    /// stays encrypted.
+    pub fn reveal_write_in(
+        ctx: Context<RevealWriteIn>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        check_stage(
+            ctx.accounts.proposal_acc.state(Clock::get()?.unix_timestamp),
+            Stage::Finalized,
+        )?;
+        require!(
+            !ctx.accounts.write_in_tally.revealed,
+            ErrorCode::WriteInAlreadyRevealed
+        );
+
+        // reveal_write_in(write_ins_ctxt: Enc<Mxe, WriteIns>)
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.write_in_tally.nonce)
+            .account(
+                ctx.accounts.write_in_tally.key(),
+                8 + 1, // discriminator + bump
+                32 * 2 * MAX_WRITE_INS as u32, // encrypted hashes then counts
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealWriteInCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.write_in_tally.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_write_in")]
+    pub fn reveal_write_in_callback(
+        ctx: Context<RevealWriteInCallback>,
+        output: SignedComputationOutputs<RevealWriteInOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let (candidate, votes) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealWriteInOutput {
+                field_0: RevealWriteInOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        let write_in_tally = &mut ctx.accounts.write_in_tally;
+        write_in_tally.revealed = true;
+        write_in_tally.top_candidate = candidate;
+        write_in_tally.top_votes = votes;
+
+        emit!(WriteInRevealedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            candidate,
+            votes,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Jury
+    // ================================================================
+
+    /// Seat `juror` on a jury proposal's reviewer panel.  Authority-only,
+    /// before the deadline; `payer` funds the `Juror` account.
+    pub fn seat_juror(ctx: Context<SeatJuror>, juror: Pubkey) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(
+            matches!(proposal.ballot, BallotKind::Jury { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+
+        let juror_acc = &mut ctx.accounts.juror_acc;
+        juror_acc.bump = ctx.bumps.juror_acc;
+        juror_acc.proposal = proposal.key();
+        juror_acc.juror = juror;
+        juror_acc.authority = ctx.accounts.authority.key();
+
+        emit!(JurorSeatedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            juror,
+        });
+
+        Ok(())
+    }
+
+    /// Close a `Juror` account, returning its rent to the authority, once
+    /// the proposal is finalized, failed quorum, or was closed.
+    pub fn close_juror(ctx: Context<CloseJuror>) -> Result<()> {
+        // A closed proposal was settled first, so only a live one is checked.
+        let info = ctx.accounts.proposal_acc.to_account_info();
+        if !info.data_is_empty() {
+            let proposal = ProposalAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+        }
+
+        Ok(())
+    }
+
+    /// Cast a seated juror's scores: an encrypted 0..=`max_score` score
+    /// per application (option) and criterion, criterion-major, with
+    /// unused criteria and applications 0.  A ballot breaking either rule
+    /// is discarded inside MPC.  Only each application's sum over jurors
+    /// and criteria is ever revealed.  Accounts, fees and double-vote
+    /// prevention are as for `cast_vote`, plus the payer's `Juror` seat.
+    pub fn cast_jury_scores(
+        ctx: Context<CastJuryScores>,
+        computation_offset: u64,
+        _id: u32,
+        scores: [[u8; 32]; 12],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        let BallotKind::Jury { criteria, max_score } = ctx.accounts.proposal_acc.ballot else {
+            return err!(ErrorCode::WrongBallotKind);
+        };
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_jury_scores(scores_ctxt: Enc<Shared, JuryScores>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+        //                  max_score: u64, criteria: u64, applications: u64)
+        let mut args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce);
+        for score in scores {
+            args = args.encrypted_u64(score);
+        }
+        let args = args
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u64(max_score.into())
+            .plaintext_u64(criteria.into())
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastJuryScoresCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_jury_scores")]
+    pub fn cast_jury_scores_callback(
+        ctx: Context<CastJuryScoresCallback>,
+        output: SignedComputationOutputs<CastJuryScoresOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastJuryScoresOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            o.ciphertexts,
+            o.nonce,
+        )
+    }
+
+    // ================================================================
+    // Council chamber
+    // ================================================================
+
+    /// Give a For/Against/Abstain proposal a second electorate: `members`
+    /// vote in a council chamber with its own encrypted tally, and
+    /// `reveal_chambers` decides the proposal from both per `rule`.
+    /// Authority-only, before the first ballot.  Creates the proposal's
+    /// `CouncilChamber` and queues an MPC computation to initialize its
+    /// tally; `cast_council_choice` is open once the callback lands.
+    pub fn open_council(
+        ctx: Context<OpenCouncil>,
+        computation_offset: u64,
+        _id: u32,
+        nonce: u128,
+        members: Vec<Pubkey>,
+        rule: ChamberRule,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(
+            matches!(proposal.ballot, BallotKind::YesNoAbstain { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(proposal.turnout_bias_bps == 0, ErrorCode::TurnoutBiasUnsupported);
+        require!(proposal.supermajority_bps == 0, ErrorCode::FastTrackUnsupported);
+        require!(
+            !members.is_empty()
+                && members.len() <= MAX_COUNCIL_MEMBERS
+                && members.iter().enumerate().all(|(i, m)| !members[..i].contains(m))
+                && rule.council_bps() <= 10_000,
+            ErrorCode::InvalidCouncil
+        );
+
+        let chamber = &mut ctx.accounts.council_chamber;
+        chamber.bump = ctx.bumps.council_chamber;
+        chamber.proposal = proposal.key();
+        chamber.rule = rule;
+        chamber.members = members;
+
+        let proposal = &mut ctx.accounts.proposal_acc;
+        proposal.dual_chamber = true;
+
+        emit!(CouncilOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            members: ctx.accounts.council_chamber.members.len() as u8,
+            rule,
+        });
+
+        // init_council_tallies(mxe: Mxe)
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![InitCouncilTalliesCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.council_chamber.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_council_tallies")]
+    pub fn init_council_tallies_callback(
+        ctx: Context<InitCouncilTalliesCallback>,
+        output: SignedComputationOutputs<InitCouncilTalliesOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitCouncilTalliesOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::InitTallies,
+                    aborted,
+                )
+            }
+        };
+
+        let chamber = &mut ctx.accounts.council_chamber;
+        chamber.vote_state = o.ciphertexts;
+        chamber.nonce = o.nonce;
+        chamber.ready = true;
+
+        Ok(())
+    }
+
+    /// Cast a council member's For/Against/Abstain ballot into the council
+    /// tally, encrypted as for `cast_choice`.  The payer must be one of the
+    /// chamber's members; their `[b"council_vote", proposal, member]`
+    /// record prevents a second ballot.  Council ballots pay the vote fee
+    /// but stay out of the proposal's turnout and quorum, which measure
+    /// the community chamber.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_council_choice(
+        ctx: Context<CastCouncilChoice>,
+        computation_offset: u64,
+        _id: u32,
+        choice_0: [u8; 32],
+        choice_1: [u8; 32],
+        choice_2: [u8; 32],
+        choice_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        let member = ctx.accounts.payer.key();
+        require!(ctx.accounts.council_chamber.ready, ErrorCode::CouncilNotReady);
+        require!(
+            ctx.accounts.council_chamber.members.contains(&member),
+            ErrorCode::NotACouncilMember
+        );
+        check_ballot_open(&ctx.accounts.proposal_acc, &ctx.accounts.config)?;
+
+        // Record init fails if the PDA already exists: one ballot per member.
+        let council_record = &mut ctx.accounts.council_record;
+        council_record.bump = ctx.bumps.council_record;
+        council_record.proposal = ctx.accounts.proposal_acc.key();
+        council_record.voter = member;
+        council_record.tally_generation = ctx.accounts.proposal_acc.tally_generation;
+        let chamber = &mut ctx.accounts.council_chamber;
+        chamber.ballots = chamber.ballots.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_council_choice(choice_ctxt: Enc<Shared, Choice>, council_ctxt: Enc<Mxe, VoteTallies>)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(choice_0)
+            .encrypted_u64(choice_1)
+            .encrypted_u64(choice_2)
+            .encrypted_u64(choice_3)
+            .plaintext_u128(ctx.accounts.council_chamber.nonce)
+            .account(
+                ctx.accounts.council_chamber.key(),
+                8 + 1, // discriminator + bump
+                32 * 5,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastCouncilChoiceCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.council_record.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.council_chamber.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_council_choice")]
+    pub fn cast_council_choice_callback(
+        ctx: Context<CastCouncilChoiceCallback>,
+        output: SignedComputationOutputs<CastCouncilChoiceOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.council_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastCouncilChoiceOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.council_chamber.vote_state = o.ciphertexts;
+        ctx.accounts.council_chamber.nonce = o.nonce;
+        ctx.accounts.council_record.counted = true;
+
+        Ok(())
+    }
+
+    /// Reveal both chambers of a dual-chamber proposal and decide it
+    /// inside MPC.  Each chamber is decided as `reveal_outcome` decides a
+    /// proposal, against the same veto threshold, and the chamber's rule
+    /// combines them into the proposal's `outcome`.  Authority-only, with
+    /// the same deadline and quorum checks as `reveal_outcome`, which a
+    /// dual-chamber proposal can't use.
+    pub fn reveal_chambers(
+        ctx: Context<RevealChambers>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        let BallotKind::YesNoAbstain { veto_bps } = ctx.accounts.proposal_acc.ballot else {
+            return err!(ErrorCode::WrongBallotKind);
+        };
+        require!(ctx.accounts.council_chamber.ready, ErrorCode::CouncilNotReady);
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing both chambers for proposal {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        // reveal_chambers(tallies_ctxt: Enc<Mxe, VoteTallies>, council_ctxt: Enc<Mxe, VoteTallies>,
+        //                 veto_bps: u64, rule: u64, council_bps: u64)
+        let rule = ctx.accounts.council_chamber.rule;
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u128(ctx.accounts.council_chamber.nonce)
+            .account(
+                ctx.accounts.council_chamber.key(),
+                8 + 1,
+                32 * 5,
+            )
+            .plaintext_u64(veto_bps.into())
+            .plaintext_u64(rule.code())
+            .plaintext_u64(rule.council_bps().into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealChambersCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.council_chamber.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_chambers")]
+    pub fn reveal_chambers_callback(
+        ctx: Context<RevealChambersCallback>,
+        output: SignedComputationOutputs<RevealChambersOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealChambersOutput { field_0 }) => field_0,
+            Err(_) => {
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        let outcome = VoteOutcome::from_code(o.field_6);
+        let community_outcome = VoteOutcome::from_code(o.field_7);
+        let council_outcome = VoteOutcome::from_code(o.field_13);
+        ctx.accounts.proposal_acc.outcome = Some(outcome);
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.field_0, o.field_1, o.field_2, o.field_3, o.field_4],
+            o.field_5,
+        )?;
+
+        let chamber = &mut ctx.accounts.council_chamber;
+        chamber.results = [o.field_8, o.field_9, o.field_10, o.field_11, o.field_12];
+        chamber.community_outcome = Some(community_outcome);
+        chamber.council_outcome = Some(council_outcome);
+
+        emit!(ChambersRevealedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            community_outcome,
+            council_for: o.field_8,
+            council_against: o.field_9,
+            council_abstain: o.field_10,
+            council_outcome,
+            outcome,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Turnout Bias
+    // ================================================================
+
+    /// Raise the margin a For/Against/Abstain proposal needs as turnout
+    /// falls: `reveal_outcome` passes it when For's share of For and
+    /// Against is over half plus `bias_bps` times the share of the
+    /// electorate that didn't vote.  Authority-only, before the first
+    /// ballot, on a proposal with a `RegisteredBps` quorum.
+    pub fn set_turnout_bias(ctx: Context<SetTurnoutBias>, bias_bps: u16) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(proposal.ballot, BallotKind::YesNoAbstain { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(
+            proposal.quorum_kind == QuorumKind::RegisteredBps && (1..=MAX_TURNOUT_BIAS_BPS).contains(&bias_bps),
+            ErrorCode::InvalidTurnoutBias
+        );
+        require!(!proposal.dual_chamber, ErrorCode::TurnoutBiasUnsupported);
+        proposal.turnout_bias_bps = bias_bps;
+
+        emit!(TurnoutBiasSetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            bias_bps,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Fast Track
+    // ================================================================
+
+    /// Fast-track a For/Against/Abstain proposal for a security-critical
+    /// decision: bring its deadline forward to `deadline`, as little as
+    /// `Config.emergency_min_duration` from now, whatever its category
+    /// allows, and require `Config.emergency_supermajority_bps` of For and
+    /// Against to pass it.  The authority and `Config.council` both sign,
+    /// before the first ballot.
+    pub fn fast_track(ctx: Context<FastTrack>, deadline: i64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(config.emergency_supermajority_bps > 0, ErrorCode::FastTrackDisabled);
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(proposal.ballot, BallotKind::YesNoAbstain { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        let now = Clock::get()?.unix_timestamp;
+        check_stage(proposal.state(now), Stage::Setup)?;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(proposal.supermajority_bps == 0, ErrorCode::AlreadyFastTracked);
+        require!(proposal.on_ballot.is_none(), ErrorCode::DeadlineSetByBallot);
+        require!(!proposal.dual_chamber, ErrorCode::FastTrackUnsupported);
+        require!(
+            deadline >= now.saturating_add(config.emergency_min_duration)
+                && deadline > proposal.registration_deadline
+                && deadline <= proposal.deadline,
+            ErrorCode::InvalidFastTrack
+        );
+        proposal.deadline = deadline;
+        proposal.supermajority_bps = config.emergency_supermajority_bps;
+
+        emit!(FastTrackedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            council: ctx.accounts.council.key(),
+            deadline,
+            supermajority_bps: proposal.supermajority_bps,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Credentials
+    // ================================================================
+
+    /// Gate a quadratic proposal behind registrar-issued credentials.
+    /// Authority-only, before the first ballot.  `registrar` signs
+    /// issuances and revocations; `registrar_key` is its uncompressed
+    /// alt_bn128 G2 BLS public key, under which every credential verifies.
+    /// From then on `cast_vote` admits only wallets holding a presented
+    /// `BallotCredential`.
+    pub fn open_credentials(
+        ctx: Context<OpenCredentials>,
+        registrar: Pubkey,
+        registrar_key: [u8; 128],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.config.allows(GatingMode::Credential),
+            ErrorCode::GatingModeNotAllowed
+        );
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.spend_histogram, ErrorCode::HistogramUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        require!(proposal.gating == GatingMode::Open, ErrorCode::ProposalAlreadyGated);
+        proposal.gating = GatingMode::Credential;
+
+        let registry = &mut ctx.accounts.credential_registry;
+        registry.bump = ctx.bumps.credential_registry;
+        registry.proposal = proposal.key();
+        registry.registrar = registrar;
+        registry.registrar_key = registrar_key;
+
+        emit!(CredentialsOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            registrar,
+        });
+
+        Ok(())
+    }
+
+    /// Issue a blind credential to the registered `voter`.  The voter hashes
+    /// `credential_message` for a secret serial to G1 and multiplies it by
+    /// a secret blinding factor; the registrar signs that `blinded_message`
+    /// and posts the `blind_signature` here for the voter to unblind.
+    /// Neither value reveals the serial, so the credential later presented
+    /// can't be linked to `voter`.  One issuance per voter.  Under
+    /// `QuorumKind::RegistrarBps` each issuance grows the proposal's
+    /// `electorate`, and none is taken once the first ballot fixed it.
+    pub fn issue_credential(
+        ctx: Context<IssueCredential>,
+        voter: Pubkey,
+        blinded_message: [u8; 64],
+        blind_signature: [u8; 64],
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        // A registrar quorum counts the voters registered before voting.
+        require!(
+            proposal.quorum_kind != QuorumKind::RegistrarBps || !proposal.is_frozen(),
+            ErrorCode::ElectorateFixed
+        );
+        let registry = &mut ctx.accounts.credential_registry;
+        require!(
+            blinded_message != [0; 64]
+                && bls_pairing_holds(&blinded_message, &registry.registrar_key, &blind_signature),
+            ErrorCode::InvalidCredential
+        );
+        registry.issued = registry.issued.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+        if proposal.quorum_kind == QuorumKind::RegistrarBps {
+            proposal.electorate = registry.issued as u64;
+        }
+
+        let issuance = &mut ctx.accounts.issuance;
+        issuance.bump = ctx.bumps.issuance;
+        issuance.proposal = proposal.key();
+        issuance.voter = voter;
+        issuance.blinded_message = blinded_message;
+        issuance.blind_signature = blind_signature;
+
+        emit!(CredentialIssuedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            voter,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke the credential with `serial`, e.g. after its holder reported
+    /// it lost.  Registrar-only.  An unpresented serial can never be
+    /// presented; a presented one no longer admits its holder to
+    /// `cast_vote`.  Ballots already cast stay counted.
+    pub fn revoke_credential(ctx: Context<RevokeCredential>, serial: [u8; 32]) -> Result<()> {
+        let credential = &mut ctx.accounts.credential;
+        require!(!credential.revoked, ErrorCode::CredentialRevoked);
+        credential.bump = ctx.bumps.credential;
+        credential.proposal = ctx.accounts.proposal_acc.key();
+        credential.serial = serial;
+        credential.revoked = true;
+        ctx.accounts.credential_registry.revoked = ctx
+            .accounts
+            .credential_registry
+            .revoked
+            .checked_add(1)
+            .ok_or(ErrorCode::CounterOverflow)?;
+
+        emit!(CredentialRevokedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            serial,
+        });
+
+        Ok(())
+    }
+
+    /// Present an unblinded credential from the wallet that will vote, which
+    /// need not be the registered one.  Checks `signature` on
+    /// `credential_message(proposal, serial)` under the registrar's key and
+    /// spends `serial`: its `BallotCredential` can only be created once, so
+    /// a credential admits one wallet and a revoked serial none.
+    pub fn present_credential(
+        ctx: Context<PresentCredential>,
+        serial: [u8; 32],
+        signature: [u8; 64],
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        let registry = &mut ctx.accounts.credential_registry;
+        require!(!registry.anonymous, ErrorCode::AnonymousCredentials);
+        require!(
+            credential_signature_valid(&registry.registrar_key, &proposal.key(), &serial, &signature),
+            ErrorCode::InvalidCredential
+        );
+        registry.presented = registry.presented.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+        let credential = &mut ctx.accounts.credential;
+        credential.bump = ctx.bumps.credential;
+        credential.proposal = proposal.key();
+        credential.serial = serial;
+        credential.holder = ctx.accounts.payer.key();
+
+        Ok(())
+    }
+
+    /// Whether `signature` is a credential for `serial` and what became of
+    /// the serial, so a voter can check a credential before presenting it.
+    /// Serials spent by `cast_vote_anonymous` show as unspent here; see
+    /// `check_nullifier`.
+    pub fn verify_credential(
+        ctx: Context<VerifyCredential>,
+        serial: [u8; 32],
+        signature: [u8; 64],
+    ) -> Result<CredentialStatus> {
+        let registry = &ctx.accounts.credential_registry;
+        if !credential_signature_valid(&registry.registrar_key, &registry.proposal, &serial, &signature) {
+            return Ok(CredentialStatus::Invalid);
+        }
+        let info = ctx.accounts.credential.to_account_info();
+        if info.data_is_empty() {
+            return Ok(CredentialStatus::Unspent);
+        }
+        let credential = BallotCredential::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(if credential.revoked {
+            CredentialStatus::Revoked
+        } else {
+            CredentialStatus::Presented
+        })
+    }
+
+    // ================================================================
+    // Nullifier Set
+    // ================================================================
+
+    /// Let a credential-gated proposal's credentials vote anonymously.
+    /// Rather than a `BallotCredential` per presented serial and a
+    /// `VoterRecord` per wallet, `cast_vote_anonymous` checks the credential
+    /// itself and records its serial's `nullifier` in one `NullifierSet`,
+    /// at 32 bytes a voter for up to `max_voters`.  Authority-only, before
+    /// any credential is presented or ballot cast; `present_credential` is
+    /// refused from then on.  The set starts at the 10 KiB one instruction
+    /// may allocate, and `grow_nullifier_set` takes it the rest of the way.
+    pub fn open_nullifier_set(ctx: Context<OpenNullifierSet>, max_voters: u32) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        // An anonymous wallet has no quorum-mint balance to count.
+        require!(
+            (1..=MAX_NULLIFIER_VOTERS).contains(&max_voters)
+                && proposal.quorum_kind != QuorumKind::SupplyBps,
+            ErrorCode::InvalidNullifierSet
+        );
+        let registry = &mut ctx.accounts.credential_registry;
+        require!(registry.presented == 0, ErrorCode::VotingAlreadyStarted);
+        registry.anonymous = true;
+
+        let set = &mut ctx.accounts.nullifier_set;
+        set.bump = ctx.bumps.nullifier_set;
+        set.proposal = proposal.key();
+        set.authority = proposal.authority;
+        set.max_voters = max_voters;
+        set.slots = 2 * max_voters;
+        set.count = 0;
+
+        emit!(NullifierSetOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            max_voters,
+        });
+
+        Ok(())
+    }
+
+    /// Extend a `NullifierSet` by up to 10 KiB toward its full size.  Anyone
+    /// may pay the rent; `cast_vote_anonymous` refuses ballots until the set
+    /// is full size.
+    pub fn grow_nullifier_set(ctx: Context<GrowNullifierSet>) -> Result<()> {
+        let info = ctx.accounts.nullifier_set.to_account_info();
+        let full_len = ctx.accounts.nullifier_set.full_len();
+        require!(info.data_len() < full_len, ErrorCode::InvalidNullifierSet);
+        let len = full_len.min(info.data_len() + MAX_PERMITTED_DATA_INCREASE);
+
+        let rent = Rent::get()?.minimum_balance(len).saturating_sub(info.lamports());
+        if rent > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                rent,
+            )?;
+        }
+        // New bytes are zeroed, i.e. empty slots.
+        info.resize(len)?;
+
+        Ok(())
+    }
+
+    /// Cast a quadratic vote with a credential instead of from a known
+    /// wallet, on a proposal with a `NullifierSet`.  Checks `signature` on
+    /// `serial` as `present_credential` does, then inserts the serial's
+    /// `nullifier`, so each credential votes once and no account links the
+    /// ballot to the payer — which can be any fee-paying wallet.  A serial
+    /// with a `BallotCredential` was revoked and is refused.  Anonymous
+    /// ballots earn no reward share or receipt; fees, sponsorship and the
+    /// mix window are as for `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_anonymous(
+        ctx: Context<CastVoteAnonymous>,
+        computation_offset: u64,
+        _id: u32,
+        serial: [u8; 32],
+        signature: [u8; 64],
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+        identity: Option<IdentityCiphertexts>,
+    ) -> Result<()> {
+        require!(
+            matches!(
+                ctx.accounts.proposal_acc.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(
+            ctx.accounts.proposal_acc.credit_mint.is_none(),
+            ErrorCode::SqrtCreditsUnsupported
+        );
+        require!(
+            !ctx.accounts.proposal_acc.funded_credits,
+            ErrorCode::FundedCreditsUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.burn_mint.is_none(),
+            ErrorCode::BurnToVoteUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.eligibility_oracle.is_none(),
+            ErrorCode::EligibilityOracleUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.weight_mint.is_none(),
+            ErrorCode::TokenWeightsUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.commit_deadline == 0,
+            ErrorCode::CommitPhaseUnsupported
+        );
+        require!(
+            !ctx.accounts.proposal_acc.dataless_records,
+            ErrorCode::DatalessRecordRequired
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let weight = ctx.accounts.proposal_acc.ballot_weight(now);
+        check_ballot_open(&ctx.accounts.proposal_acc, &ctx.accounts.config)?;
+
+        let proposal_key = ctx.accounts.proposal_acc.key();
+        require!(
+            credential_signature_valid(
+                &ctx.accounts.credential_registry.registrar_key,
+                &proposal_key,
+                &serial,
+                &signature
+            ),
+            ErrorCode::InvalidCredential
+        );
+        require!(ctx.accounts.credential.data_is_empty(), ErrorCode::CredentialRevoked);
+        insert_nullifier(&mut ctx.accounts.nullifier_set, &nullifier(&proposal_key, &serial))?;
+        ctx.accounts.credential_registry.presented = ctx
+            .accounts
+            .credential_registry
+            .presented
+            .checked_add(1)
+            .ok_or(ErrorCode::CounterOverflow)?;
+
+        if ctx.accounts.credential_registry.compliance_roll {
+            let sealed_identity_bump = ctx.bumps.sealed_identity;
+            let accounts = &mut *ctx.accounts;
+            let (Some(roll), Some(sealed), Some(identity), Some(bump)) = (
+                accounts.compliance_roll.as_mut(),
+                accounts.sealed_identity.as_mut(),
+                identity,
+                sealed_identity_bump,
+            ) else {
+                return err!(ErrorCode::IdentityRequired);
+            };
+            // Reusing the ballot's nonce under the same key would leak the
+            // difference between the two plaintexts.
+            require!(identity.nonce != vote_nonce, ErrorCode::InvalidIdentity);
+            sealed.set_inner(SealedIdentity {
+                bump,
+                proposal: proposal_key,
+                encryption_key: vote_encryption_pubkey,
+                nonce: identity.nonce,
+                ciphertexts: identity.ciphertexts,
+                released: false,
+            });
+            roll.sealed = roll.sealed.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+        } else {
+            require!(
+                identity.is_none() && ctx.accounts.sealed_identity.is_none(),
+                ErrorCode::InvalidIdentity
+            );
+        }
+        record_turnout(&mut ctx.accounts.proposal_acc, &mut ctx.accounts.global_stats)?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // The program id stands in for the missing `VoterRecord`.
+        if ctx.accounts.proposal_acc.in_mix_window(now) {
+            let budget = ctx.accounts.proposal_acc.voice_credits;
+            return hold_ballot(
+                &mut ctx.accounts.proposal_acc,
+                ctx.accounts.mix_queue.as_ref(),
+                ctx.accounts.slot_hashes.as_ref(),
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                HeldBallot {
+                    voter_record: crate::ID,
+                    ciphertexts: [vote_v0, vote_v1, vote_v2, vote_v3],
+                    encryption_pubkey: vote_encryption_pubkey,
+                    nonce: vote_nonce,
+                    weight,
+                    budget,
+                },
+            );
+        }
+
+        // Same circuit and argument order as `cast_vote`.
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(vote_v0)
+            .encrypted_u64(vote_v1)
+            .encrypted_u64(vote_v2)
+            .encrypted_u64(vote_v3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8, // discriminator
+                32 * 5, // 5 encrypted u64 counters
+            )
+            .plaintext_u64(weight)
+            .plaintext_u64(ctx.accounts.proposal_acc.voice_credits)
+            // No oracle weight to curve: a stake of 1, linear.
+            .plaintext_u64(1)
+            .plaintext_u64(0)
+            .plaintext_u64(0)
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastVoteCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: crate::ID,
+                        is_writable: false,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    /// Whether the credential `serial` was spent by `cast_vote_anonymous`,
+    /// so a voter can check a credential before casting.
+    pub fn check_nullifier(ctx: Context<CheckNullifier>, serial: [u8; 32]) -> Result<bool> {
+        let set = &ctx.accounts.nullifier_set;
+        if set.count == 0 {
+            return Ok(false);
+        }
+        let nullifier = nullifier(&set.proposal, &serial);
+        let info = set.to_account_info();
+        let data = info.try_borrow_data()?;
+        let table = &data[NullifierSet::HEADER_LEN..];
+        let index = nullifier_slot(table, set.slots, &nullifier);
+        Ok(table[index * 16..(index + 1) * 16] == nullifier)
+    }
+
+    /// Close a `NullifierSet`, returning its rent to the authority, once
+    /// the proposal is finalized, failed quorum, or was closed.
+    pub fn close_nullifier_set(ctx: Context<CloseNullifierSet>) -> Result<()> {
+        // A closed proposal was settled first, so only a live one is checked.
+        let info = ctx.accounts.proposal_acc.to_account_info();
+        if !info.data_is_empty() {
+            let proposal = ProposalAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+        }
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Compliance Roll
+    // ================================================================
+
+    /// Have every `cast_vote_anonymous` ballot carry its voter's registered
+    /// wallet as a `SealedIdentity`, encrypted to the voter's key, for
+    /// `release_identity` to re-encrypt to `compliance_key` once the
+    /// proposal is decided.  For regulated elections that must audit who
+    /// took part: only the key's holder learns the list, and nobody learns
+    /// a ballot.  Wallet ballots need no roll, as their signer is public.
+    /// Authority-only, on a proposal with a `NullifierSet`, before any
+    /// ballot is cast.
+    pub fn open_compliance_roll(ctx: Context<OpenComplianceRoll>, compliance_key: [u8; 32]) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        let registry = &mut ctx.accounts.credential_registry;
+        require!(registry.anonymous, ErrorCode::ComplianceNeedsAnonymity);
+        registry.compliance_roll = true;
+
+        let roll = &mut ctx.accounts.compliance_roll;
+        roll.bump = ctx.bumps.compliance_roll;
+        roll.proposal = proposal.key();
+        roll.authority = proposal.authority;
+        roll.compliance_key = compliance_key;
+        roll.sealed = 0;
+        roll.released = 0;
+
+        emit!(ComplianceRollOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            compliance_key,
+        });
+
+        Ok(())
+    }
+
+    /// Re-encrypt one `SealedIdentity` to the roll's compliance key once
+    /// the proposal is finalized or failed quorum.  Authority-only, once
+    /// per identity; the callback overwrites it in place.
+    pub fn release_identity(
+        ctx: Context<ReleaseIdentity>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+        let sealed = &ctx.accounts.sealed_identity;
+        require!(!sealed.released, ErrorCode::IdentityAlreadyReleased);
+
+        // A fresh nonce per release, from the computation account.
+        let compliance_nonce = u128::from_le_bytes(
+            ctx.accounts.computation_account.key().to_bytes()[..16]
+                .try_into()
+                .unwrap(),
+        );
+
+        // release_identity(identity_ctxt: Enc<Shared, Identity>, compliance: Shared)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(sealed.encryption_key)
+            .plaintext_u128(sealed.nonce)
+            .encrypted_u128(sealed.ciphertexts[0])
+            .encrypted_u128(sealed.ciphertexts[1])
+            .x25519_pubkey(ctx.accounts.compliance_roll.compliance_key)
+            .plaintext_u128(compliance_nonce)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![ReleaseIdentityCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.compliance_roll.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.sealed_identity.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "release_identity")]
+    pub fn release_identity_callback(
+        ctx: Context<ReleaseIdentityCallback>,
+        output: SignedComputationOutputs<ReleaseIdentityOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ReleaseIdentityOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        // A second release queued before the first landed.
+        let sealed = &mut ctx.accounts.sealed_identity;
+        if sealed.released {
+            return Ok(());
+        }
+        sealed.encryption_key = o.encryption_key;
+        sealed.nonce = o.nonce;
+        sealed.ciphertexts = o.ciphertexts;
+        sealed.released = true;
+
+        let roll = &mut ctx.accounts.compliance_roll;
+        roll.released = roll.released.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+        emit!(IdentityReleasedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            identity: sealed.key(),
+            released: roll.released,
+            sealed: roll.sealed,
+        });
+
+        Ok(())
+    }
+
+    /// Close a `ComplianceRoll`, returning its rent to the authority, once
+    /// every identity on it was released, or the proposal was closed.
+    pub fn close_compliance_roll(ctx: Context<CloseComplianceRoll>) -> Result<()> {
+        let info = ctx.accounts.proposal_acc.to_account_info();
+        if !info.data_is_empty() {
+            let proposal = ProposalAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+            let roll = &ctx.accounts.compliance_roll;
+            require!(roll.released == roll.sealed, ErrorCode::IdentitiesPending);
+        }
+
+        Ok(())
+    }
+
+    // ================================================================
+    // History Gating
+    // ================================================================
+
+    /// Gate a quadratic proposal behind voting history, so that a fresh
+    /// wallet can't vote.  Authority-only, before the first ballot.  A
+    /// wallet qualifies with at least `min_prior_ballots` counted ballots on
+    /// other proposals and, if `min_wallet_age` is set, that many seconds
+    /// since its first transaction.  `prove_history` counts the wallet's
+    /// own `VoterRecord`s; wallet age is only known off-chain, so it needs
+    /// an `attestor` to vouch with `attest_history`.  Either admits the
+    /// wallet to `cast_vote` with a `BallotCredential` whose serial is the
+    /// wallet's key.
+    pub fn open_history_gate(
+        ctx: Context<OpenHistoryGate>,
+        attestor: Option<Pubkey>,
+        min_wallet_age: i64,
+        min_prior_ballots: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.config.allows(GatingMode::History),
+            ErrorCode::GatingModeNotAllowed
+        );
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.spend_histogram, ErrorCode::HistogramUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        require!(proposal.gating == GatingMode::Open, ErrorCode::ProposalAlreadyGated);
+        require!(
+            min_wallet_age >= 0
+                && (min_wallet_age > 0 || min_prior_ballots > 0)
+                && (min_wallet_age == 0 || attestor.is_some()),
+            ErrorCode::InvalidHistoryGate
+        );
+        proposal.gating = GatingMode::History;
+
+        let gate = &mut ctx.accounts.history_gate;
+        gate.bump = ctx.bumps.history_gate;
+        gate.proposal = proposal.key();
+        gate.attestor = attestor;
+        gate.min_wallet_age = min_wallet_age;
+        gate.min_prior_ballots = min_prior_ballots;
+
+        emit!(HistoryGateOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            attestor,
+            min_wallet_age,
+            min_prior_ballots,
+        });
+
+        Ok(())
+    }
+
+    /// Admit `voter` on the attestor's word that its first transaction was
+    /// at `first_seen` and that it cast `prior_ballots` ballots before,
+    /// counting any the attestor indexes beyond this program.
+    /// Attestor-only; a wallet is admitted once.
+    pub fn attest_history(
+        ctx: Context<AttestHistory>,
+        voter: Pubkey,
+        first_seen: i64,
+        prior_ballots: u32,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        check_stage(ctx.accounts.proposal_acc.state(now), Stage::Setup)?;
+        let gate = &ctx.accounts.history_gate;
+        require!(
+            gate.attestor == Some(ctx.accounts.attestor.key()),
+            ErrorCode::InvalidAttestor
+        );
+        require!(
+            now.saturating_sub(first_seen) >= gate.min_wallet_age,
+            ErrorCode::WalletTooYoung
+        );
+        require!(
+            prior_ballots >= gate.min_prior_ballots,
+            ErrorCode::TooFewPriorBallots
+        );
+
+        let bump = ctx.bumps.credential;
+        let accounts = &mut *ctx.accounts;
+        admit_history(
+            &accounts.proposal_acc,
+            &mut accounts.history_gate,
+            &mut accounts.credential,
+            bump,
+            voter,
+            prior_ballots,
+            true,
+        )?;
+
+        Ok(())
+    }
+
+    /// Admit the signing wallet on its own record: the `VoterRecord`s of
+    /// its counted ballots on other proposals, passed as remaining
+    /// accounts.  Refused on a gate with a `min_wallet_age`, which no
+    /// record shows.  Each record stands for a ballot the wallet paid the
+    /// vote fee and computation for, which is the cost a sybil must repeat.
+    pub fn prove_history(ctx: Context<ProveHistory>) -> Result<()> {
+        check_stage(
+            ctx.accounts.proposal_acc.state(Clock::get()?.unix_timestamp),
+            Stage::Setup,
+        )?;
+        let gate = &ctx.accounts.history_gate;
+        require!(gate.min_wallet_age == 0, ErrorCode::WalletAgeUnproven);
+        let voter = ctx.accounts.voter.key();
+        let prior_ballots = count_prior_ballots(ctx.accounts.proposal_acc.key(), voter, ctx.remaining_accounts)?;
+        require!(
+            prior_ballots >= gate.min_prior_ballots,
+            ErrorCode::TooFewPriorBallots
+        );
+
+        let bump = ctx.bumps.credential;
+        let accounts = &mut *ctx.accounts;
+        admit_history(
+            &accounts.proposal_acc,
+            &mut accounts.history_gate,
+            &mut accounts.credential,
+            bump,
+            voter,
+            prior_ballots,
+            false,
+        )?;
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Allowlist
+    // ================================================================
+
+    /// Gate a quadratic proposal behind an explicit list of up to
+    /// `capacity` wallets, kept by the authority with `add_voter` and
+    /// `remove_voter` — for a small committee, simpler than credentials.
+    /// Authority-only, before the first ballot.  `cast_vote` then admits
+    /// only listed payers.
+    pub fn open_allowlist(ctx: Context<OpenAllowlist>, capacity: u16) -> Result<()> {
+        require!(
+            ctx.accounts.config.allows(GatingMode::Allowlist),
+            ErrorCode::GatingModeNotAllowed
+        );
+        require!(
+            capacity > 0 && capacity <= MAX_ALLOWLIST_VOTERS,
+            ErrorCode::InvalidAllowlistCapacity
+        );
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.spend_histogram, ErrorCode::HistogramUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        require!(proposal.gating == GatingMode::Open, ErrorCode::ProposalAlreadyGated);
+        proposal.gating = GatingMode::Allowlist;
+
+        let allowlist = &mut ctx.accounts.allowlist;
+        allowlist.bump = ctx.bumps.allowlist;
+        allowlist.proposal = proposal.key();
+        allowlist.capacity = capacity;
+
+        emit!(AllowlistOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            capacity,
+        });
+
+        Ok(())
+    }
+
+    /// List `voter`, while voting is open.  Authority-only.
+    pub fn add_voter(ctx: Context<AddVoter>, voter: Pubkey) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        let allowlist = &mut ctx.accounts.allowlist;
+        require!(!allowlist.voters.contains(&voter), ErrorCode::VoterAlreadyListed);
+        require!(
+            allowlist.voters.len() < allowlist.capacity as usize,
+            ErrorCode::AllowlistFull
+        );
+        allowlist.voters.push(voter);
+
+        emit!(AllowlistUpdatedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            voter,
+            listed: true,
+            count: allowlist.voters.len() as u16,
+        });
+
+        Ok(())
+    }
+
+    /// Strike `voter` off the list, freeing its slot.  Authority-only.  A
+    /// ballot it already cast stays counted.
+    pub fn remove_voter(ctx: Context<RemoveVoter>, voter: Pubkey) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        let allowlist = &mut ctx.accounts.allowlist;
+        let index = allowlist
+            .voters
+            .iter()
+            .position(|listed| *listed == voter)
+            .ok_or(ErrorCode::NotOnAllowlist)?;
+        allowlist.voters.swap_remove(index);
+
+        emit!(AllowlistUpdatedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            voter,
+            listed: false,
+            count: allowlist.voters.len() as u16,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // NFT Collections
+    // ================================================================
+
+    /// Gate a quadratic proposal to holders of an NFT of `collection`, a
+    /// Metaplex collection mint.  Authority-only, before the first ballot.
+    /// `cast_vote` then takes the payer's NFT token account and metadata,
+    /// admits the NFT only as a verified member of the collection, and
+    /// claims it with an `NftBallot` so it can't vote again from another
+    /// wallet.
+    pub fn open_collection_gate(ctx: Context<OpenCollectionGate>, collection: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.config.allows(GatingMode::Collection),
+            ErrorCode::GatingModeNotAllowed
+        );
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(!proposal.spend_histogram, ErrorCode::HistogramUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        // `voter_token_account` carries the NFT, so it can't also weigh a
+        // supply quorum.
+        require!(
+            matches!(
+                proposal.quorum_kind,
+                QuorumKind::Voters | QuorumKind::RegisteredBps
+            ),
+            ErrorCode::CollectionGateUnsupported
+        );
+        require!(proposal.gating == GatingMode::Open, ErrorCode::ProposalAlreadyGated);
+        proposal.gating = GatingMode::Collection;
+        proposal.gate_mint = collection;
+
+        emit!(CollectionGateOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            collection,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Session Keys
+    // ================================================================
+
+    /// Let `session_key` cast the signing wallet's ballot on this proposal
+    /// with `cast_vote_session` until `expires_at`, so a browser can vote
+    /// with a throwaway key while the wallet signs only this authorization.
+    /// The ballot stays the wallet's: its `VoterRecord`, credential, quorum
+    /// tokens, rewards and receipt.  One session per wallet and proposal;
+    /// `close_session` kills it.
+    pub fn open_session(ctx: Context<OpenSession>, session_key: Pubkey, expires_at: i64) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        let now = Clock::get()?.unix_timestamp;
+        check_stage(proposal.state(now), Stage::Setup)?;
+        require!(
+            expires_at > now && session_key != ctx.accounts.wallet.key(),
+            ErrorCode::InvalidSession
+        );
+
+        let session = &mut ctx.accounts.session;
+        session.bump = ctx.bumps.session;
+        session.proposal = proposal.key();
+        session.wallet = ctx.accounts.wallet.key();
+        session.session_key = session_key;
+        session.expires_at = expires_at;
+
+        emit!(SessionOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            wallet: session.wallet,
+            session_key,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Kill a session, expired or not, returning its rent to the wallet.
+    /// The wallet or the session key itself may close it, e.g. on logout.
+    pub fn close_session(ctx: Context<CloseSession>) -> Result<()> {
+        emit!(SessionClosedEvent {
+            proposal: ctx.accounts.session.proposal,
+            wallet: ctx.accounts.session.wallet,
+            session_key: ctx.accounts.session.session_key,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Data-less Voter Records
+    // ================================================================
+
+    /// Mark ballots with data-less `VoterRecord`s: each creates the payer's
+    /// `[b"voter", proposal, voter]` PDA with no data, so its existence is
+    /// the vote and the rent about half a full record's.  Nothing is left
+    /// to flag a ballot counted or rewarded, so the proposal must be open
+    /// to anyone, without a reward pool, mix window or any budget or
+    /// weight mode, and its records don't qualify for `prove_history`.
+    /// Authority-only, on a quadratic or time-weighted proposal, before
+    /// the first ballot.  From then on it is voted with
+    /// `cast_vote_dataless`, which every other ballot path refuses it for.
+    pub fn open_dataless_records(ctx: Context<OpenDatalessRecords>) -> Result<()> {
+        dataless::open_dataless_records(ctx)
+    }
+
+    /// As `cast_vote` without any of its optional accounts but the quorum
+    /// token account, on a proposal keeping data-less records (see
+    /// `open_dataless_records`): the payer's `VoterRecord` is created with
+    /// no data, and a second ballot still finds it there.  Same circuit
+    /// and callback as `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_dataless(
+        ctx: Context<CastVoteDataless>,
+        computation_offset: u64,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        dataless::cast_vote_dataless(ctx, computation_offset, vote_v0, vote_v1, vote_v2, vote_v3, vote_encryption_pubkey, vote_nonce)
+    }
+
+    // ================================================================
+    // Mix Window
+    // ================================================================
+
+    /// Hold ballots cast in the last `window` seconds before the deadline
+    /// instead of applying them as they arrive.  Authority-only, before the
+    /// first ballot, on a quadratic (or time-weighted) proposal.  Late
+    /// ballots then wait in the proposal's `MixQueue` and are only added to
+    /// the tallies, in random order, by `release_held_ballot` once voting
+    /// has closed, so nothing about the tally changes in step with them.
+    pub fn open_mix_window(ctx: Context<OpenMixWindow>, window: i64) -> Result<()> {
+        mix_window::open_mix_window(ctx, window)
+    }
+
+    /// Apply one held ballot after the deadline.  Permissionless; the
+    /// keeper calls it until the queue is empty, and `reveal_results` waits
+    /// for that.  Which ballot goes next is drawn from the queue's seed,
+    /// which the slot hashes of the holding transactions fixed by the
+    /// deadline, so the caller can't choose the order.  Runs the
+    /// `cast_vote` circuit and callback, so the ballot is budget-checked
+    /// and weighted as if applied when cast.  The payer's Arcium fee is
+    /// reimbursed from the sponsorship like a voter's.
+    pub fn release_held_ballot(
+        ctx: Context<ReleaseHeldBallot>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        mix_window::release_held_ballot(ctx, computation_offset)
+    }
+
+    // ================================================================
+    // Ballot Buffer
+    // ================================================================
+
+    /// Let voters buffer ballots instead of paying a computation each.
+    /// Authority-only, before the first ballot, on a quadratic (or
+    /// time-weighted) proposal without a spend histogram.  Ballots sent
+    /// with `submit_ballot` then wait in the proposal's `BallotBuffer`
+    /// until `process_ballot_batch` folds `BALLOT_BATCH_SIZE` of them into
+    /// the tallies with one computation.  `cast_vote` keeps working
+    /// alongside.
+    pub fn open_ballot_buffer(ctx: Context<OpenBallotBuffer>) -> Result<()> {
+        ballot_buffer::open_ballot_buffer(ctx)
+    }
+
+    /// Cast a quadratic ballot into the proposal's `BallotBuffer` rather
+    /// than queueing it: admitted, recorded and charged the vote fee as
+    /// `cast_vote` would, with its weight and budget fixed now, but no
+    /// Arcium fee until `process_ballot_batch` folds it in with others.
+    /// Burn-to-vote, oracle, token-weighted and commit-phase proposals
+    /// need `cast_vote`, as do ballots inside a mix window.
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_ballot(
+        ctx: Context<SubmitBallot>,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        ballot_buffer::submit_ballot(ctx, vote_v0, vote_v1, vote_v2, vote_v3, vote_encryption_pubkey, vote_nonce)
+    }
+
+    /// Fold the oldest `BALLOT_BATCH_SIZE` buffered ballots into the
+    /// tallies with one `cast_vote_batch` computation.  Permissionless;
+    /// the keeper calls it whenever a batch fills, and once voting has
+    /// closed for whatever is left, which `reveal_results` waits for.
+    /// Before the deadline only a full batch is processed.  The payer's
+    /// Arcium fee is reimbursed from the sponsorship like a voter's.
+    pub fn process_ballot_batch(
+        ctx: Context<ProcessBallotBatch>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        ballot_buffer::process_ballot_batch(ctx, computation_offset)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_vote_batch")]
+    pub fn cast_vote_batch_callback(
+        ctx: Context<CastVoteBatchCallback>,
+        output: SignedComputationOutputs<CastVoteBatchOutput>,
+    ) -> Result<()> {
+        ballot_buffer::cast_vote_batch_callback(ctx, output)
+    }
+
+    // ================================================================
+    // Commit Phase
+    // ================================================================
+
+    /// Split voting into a commit phase ending at `commit_deadline` and a
+    /// voting phase after it.  Authority-only, before the first ballot, on
+    /// a quadratic (or time-weighted) proposal.  During the commit phase
+    /// voters post `commit_ballot` with the hash of the ballot they will
+    /// cast, and no ballot is taken; afterwards `cast_vote` only takes the
+    /// ballot a voter committed to.  Nobody can adapt their ballot to the
+    /// last minute's turnout, or replay a ballot seen on-chain, since every
+    /// ballot was fixed before any was cast.
+    pub fn open_commit_phase(ctx: Context<OpenCommitPhase>, commit_deadline: i64) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(now), Stage::Setup)?;
+        require!(proposal.commit_deadline == 0, ErrorCode::CommitPhaseAlreadyOpen);
+        require!(
+            commit_deadline > now
+                && commit_deadline > proposal.registration_deadline
+                && commit_deadline < proposal.deadline,
+            ErrorCode::InvalidCommitPhase
+        );
+        require!(!proposal.spend_histogram, ErrorCode::CommitPhaseUnsupported);
+        require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+        require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+        proposal.commit_deadline = commit_deadline;
+
+        emit!(CommitPhaseOpenedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            commit_deadline,
+        });
+
+        Ok(())
+    }
+
+    /// Commit to a ballot during the commit phase: `commitment` is
+    /// `ballot_hash` of the encrypted ballot the voter will cast.  One per
+    /// voter; the `BallotCommitment` PDA can't be replaced.
+    pub fn commit_ballot(ctx: Context<CommitBallot>, commitment: [u8; 32]) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        let now = Clock::get()?.unix_timestamp;
+        check_stage(proposal.state(now), Stage::Voting)?;
+        require!(now < proposal.commit_deadline, ErrorCode::CommitPhaseEnded);
+
+        let record = &mut ctx.accounts.ballot_commitment;
+        record.bump = ctx.bumps.ballot_commitment;
+        record.proposal = proposal.key();
+        record.voter = ctx.accounts.voter.key();
+        record.commitment = commitment;
+        record.committed_at = now;
+
+        emit!(BallotCommittedEvent {
+            proposal: proposal.key(),
+            voter: record.voter,
+            commitment,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Spend Histograms
+    // ================================================================
+
+    /// Collect a spend histogram on a quadratic proposal: for each option,
+    /// how many ballots spent 1-9, 16-49 or 64+ credits on it, which
+    /// shows how concentrated its support was.  Authority-only, before the
+    /// first ballot; not with a mix window or credentials.  Creates the
+    /// proposal's `SpendHistogram` and queues an MPC computation to
+    /// initialize its encrypted counts.  From then on ballots go through
+    /// `cast_vote_histogram`, which is open once the callback lands.
+    pub fn open_spend_histogram(
+        ctx: Context<OpenSpendHistogram>,
+        computation_offset: u64,
+        _id: u32,
+        nonce: u128,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(
+            proposal.mix_window == 0
+                && proposal.commit_deadline == 0
+                && proposal.gating == GatingMode::Open
+                && proposal.credit_mint.is_none()
+                && !proposal.funded_credits
+                && proposal.burn_mint.is_none()
+                && proposal.eligibility_oracle.is_none()
+                && proposal.weight_mint.is_none()
+                && !proposal.credit_bank
+                && !proposal.encrypted_budgets,
+            ErrorCode::HistogramUnsupported
+        );
+        proposal.spend_histogram = true;
+
+        let histogram = &mut ctx.accounts.spend_histogram;
+        histogram.bump = ctx.bumps.spend_histogram;
+        histogram.proposal = proposal.key();
+
+        // init_spend_histogram(mxe: Mxe)
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![InitSpendHistogramCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.spend_histogram.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_spend_histogram")]
+    pub fn init_spend_histogram_callback(
+        ctx: Context<InitSpendHistogramCallback>,
+        output: SignedComputationOutputs<InitSpendHistogramOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitSpendHistogramOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::InitTallies,
+                    aborted,
+                )
+            }
+        };
+
+        let histogram = &mut ctx.accounts.spend_histogram;
+        histogram.state = o.ciphertexts;
+        histogram.nonce = o.nonce;
+        histogram.ready = true;
+
+        Ok(())
+    }
+
+    /// Cast a quadratic vote on a proposal with a spend histogram: as
+    /// `cast_vote`, and the circuit also adds a ballot within budget to the
+    /// histogram's buckets, without revealing which.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_histogram(
+        ctx: Context<CastVoteHistogram>,
+        computation_offset: u64,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(ctx.accounts.spend_histogram.ready, ErrorCode::HistogramNotReady);
+        require!(
+            ctx.accounts.proposal_acc.credit_mint.is_none(),
+            ErrorCode::SqrtCreditsUnsupported
+        );
+        require!(
+            !ctx.accounts.proposal_acc.funded_credits,
+            ErrorCode::FundedCreditsUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.burn_mint.is_none(),
+            ErrorCode::BurnToVoteUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.eligibility_oracle.is_none(),
+            ErrorCode::EligibilityOracleUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.weight_mint.is_none(),
+            ErrorCode::TokenWeightsUnsupported
+        );
+        let weight = ctx
+            .accounts
+            .proposal_acc
+            .ballot_weight(Clock::get()?.unix_timestamp);
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_vote_histogram(alloc_ctxt: Enc<Shared, VoteAllocation>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+        //                     histogram_ctxt: Enc<Mxe, SpendHistogram>, weight: u64, budget: u64,
+        //                     options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(vote_v0)
+            .encrypted_u64(vote_v1)
+            .encrypted_u64(vote_v2)
+            .encrypted_u64(vote_v3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8, // discriminator
+                32 * 5, // 5 encrypted u64 counters
+            )
+            .plaintext_u128(ctx.accounts.spend_histogram.nonce)
+            .account(
+                ctx.accounts.spend_histogram.key(),
+                8 + 1, // discriminator + bump
+                32 * SPEND_HISTOGRAM_COUNTS as u32,
+            )
+            .plaintext_u64(weight)
+            .plaintext_u64(ctx.accounts.proposal_acc.voice_credits)
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastVoteHistogramCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.spend_histogram.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_vote_histogram")]
+    pub fn cast_vote_histogram_callback(
+        ctx: Context<CastVoteHistogramCallback>,
+        output: SignedComputationOutputs<CastVoteHistogramOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let (tallies, histogram) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastVoteHistogramOutput {
+                field_0: CastVoteHistogramOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.spend_histogram.state = histogram.ciphertexts;
+        ctx.accounts.spend_histogram.nonce = histogram.nonce;
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            tallies.ciphertexts,
+            tallies.nonce,
+        )
+    }
+
+    /// Reveal the spend histogram once the proposal is finalized, with
+    /// noise drawn inside the cluster so that no count pins down a single
+    /// ballot.  Authority-only, once per proposal.
+    pub fn reveal_spend_histogram(
+        ctx: Context<RevealSpendHistogram>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        check_stage(
+            ctx.accounts.proposal_acc.state(Clock::get()?.unix_timestamp),
+            Stage::Finalized,
+        )?;
+        require!(
+            !ctx.accounts.spend_histogram.revealed,
+            ErrorCode::HistogramAlreadyRevealed
+        );
+
+        // reveal_spend_histogram(histogram_ctxt: Enc<Mxe, SpendHistogram>)
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.spend_histogram.nonce)
+            .account(
+                ctx.accounts.spend_histogram.key(),
+                8 + 1, // discriminator + bump
+                32 * SPEND_HISTOGRAM_COUNTS as u32,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealSpendHistogramCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.spend_histogram.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_spend_histogram")]
+    pub fn reveal_spend_histogram_callback(
+        ctx: Context<RevealSpendHistogramCallback>,
+        output: SignedComputationOutputs<RevealSpendHistogramOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let counts = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealSpendHistogramOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        let histogram = &mut ctx.accounts.spend_histogram;
+        histogram.revealed = true;
+        histogram.counts = counts;
+
+        emit!(SpendHistogramRevealedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            counts,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Credit Banks
+    // ================================================================
+
+    /// Let a quadratic proposal's voters carry credits over: ballots go
+    /// through `cast_vote_banked`, which adds the proposal's
+    /// `voice_credits` to the voter's `VoterCreditBank` with the authority
+    /// and checks the ballot against the sum, and what it leaves unspent
+    /// stays for the authority's next banked proposal.  Authority-only,
+    /// before the first ballot; only on open proposals without a mix
+    /// window, commit phase or any other budget or weight mode.
+    pub fn open_credit_bank(ctx: Context<OpenCreditBank>) -> Result<()> {
+        credits::open_credit_bank(ctx)
+    }
+
+    /// Create the payer's `VoterCreditBank` with a banked proposal's
+    /// authority, and queue an MPC computation to set its encrypted
+    /// balance to 0.  `cast_vote_banked` accepts the voter's ballots once
+    /// the callback lands.  One bank serves every proposal of the
+    /// authority.
+    pub fn create_credit_bank(
+        ctx: Context<CreateCreditBank>,
+        computation_offset: u64,
+        _id: u32,
+        nonce: u128,
+    ) -> Result<()> {
+        credits::create_credit_bank(ctx, computation_offset, nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "init_credit_bank")]
+    pub fn init_credit_bank_callback(
+        ctx: Context<InitCreditBankCallback>,
+        output: SignedComputationOutputs<InitCreditBankOutput>,
+    ) -> Result<()> {
+        credits::init_credit_bank_callback(ctx, output)
+    }
+
+    /// Cast a quadratic vote on a banked proposal: as `cast_vote`, but the
+    /// circuit adds the proposal's `voice_credits` to the payer's banked
+    /// balance, checks the ballot against the sum, and deducts what a
+    /// counted ballot spent.  The new balance is re-encrypted whether or
+    /// not the ballot counted, so nobody learns what it spent.  One banked
+    /// ballot per voter is in flight at a time: the next fails with
+    /// `CreditBankBusy` until the callback lands.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_banked(
+        ctx: Context<CastVoteBanked>,
+        computation_offset: u64,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        credits::cast_vote_banked(ctx, computation_offset, vote_v0, vote_v1, vote_v2, vote_v3, vote_encryption_pubkey, vote_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_vote_banked")]
+    pub fn cast_vote_banked_callback(
+        ctx: Context<CastVoteBankedCallback>,
+        output: SignedComputationOutputs<CastVoteBankedOutput>,
+    ) -> Result<()> {
+        credits::cast_vote_banked_callback(ctx, output)
+    }
+
+    // ================================================================
+    // Encrypted Budgets
+    // ================================================================
+
+    /// Give `voter` a confidential budget on a quadratic or time-weighted
+    /// proposal, in place of its flat `voice_credits`: `budget` is the
+    /// amount encrypted to the cluster under the authority's x25519 key,
+    /// and an MPC computation re-encrypts it into the voter's
+    /// `EncryptedBudget`, so nobody but the authority learns the grant.
+    /// Authority-only, once per voter, until the deadline.  The first
+    /// call, which must come before the first ballot, makes the
+    /// proposal's ballots go through `cast_vote_budgeted`; only on open
+    /// proposals without a mix window, commit phase or any other budget
+    /// or weight mode.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fund_encrypted_budget(
+        ctx: Context<FundEncryptedBudget>,
+        computation_offset: u64,
+        voter: Pubkey,
+        budget: [u8; 32],
+        budget_encryption_pubkey: [u8; 32],
+        budget_nonce: u128,
+        nonce: u128,
+    ) -> Result<()> {
+        credits::fund_encrypted_budget(ctx, computation_offset, voter, budget, budget_encryption_pubkey, budget_nonce, nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "seal_budget")]
+    pub fn seal_budget_callback(
+        ctx: Context<SealBudgetCallback>,
+        output: SignedComputationOutputs<SealBudgetOutput>,
+    ) -> Result<()> {
+        credits::seal_budget_callback(ctx, output)
+    }
+
+    /// Cast a quadratic vote on a proposal with encrypted budgets: as
+    /// `cast_vote`, but the circuit checks the ballot against the payer's
+    /// `EncryptedBudget` rather than a plaintext budget, and deducts what
+    /// a counted ballot spent.  The budget is re-encrypted whether or not
+    /// the ballot counted, so neither the grant nor the spend is revealed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_budgeted(
+        ctx: Context<CastVoteBudgeted>,
+        computation_offset: u64,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        credits::cast_vote_budgeted(ctx, computation_offset, vote_v0, vote_v1, vote_v2, vote_v3, vote_encryption_pubkey, vote_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_vote_budgeted")]
+    pub fn cast_vote_budgeted_callback(
+        ctx: Context<CastVoteBudgetedCallback>,
+        output: SignedComputationOutputs<CastVoteBudgetedOutput>,
+    ) -> Result<()> {
+        credits::cast_vote_budgeted_callback(ctx, output)
+    }
+
+    // ================================================================
+    // Ranked-Choice Voting
+    // ================================================================
+
+    /// Open a ranked-choice proposal for ballots.  Authority-only, before
+    /// the first ballot.  Creates the proposal's `RankingTally` and queues
+    /// an MPC computation to initialize its encrypted counts, one per order
+    /// of the options; `cast_ranked_vote` is open once the callback lands.
+    pub fn open_rankings(
+        ctx: Context<OpenRankings>,
+        computation_offset: u64,
+        _id: u32,
+        nonce: u128,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(proposal.ballot == BallotKind::Ranked, ErrorCode::WrongBallotKind);
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+
+        let rankings = &mut ctx.accounts.rankings;
+        rankings.bump = ctx.bumps.rankings;
+        rankings.proposal = ctx.accounts.proposal_acc.key();
+
+        // init_rankings(mxe: Mxe)
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![InitRankingsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.rankings.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_rankings")]
+    pub fn init_rankings_callback(
+        ctx: Context<InitRankingsCallback>,
+        output: SignedComputationOutputs<InitRankingsOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitRankingsOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::InitTallies,
+                    aborted,
+                )
+            }
+        };
+
+        let rankings = &mut ctx.accounts.rankings;
+        rankings.state = o.ciphertexts;
+        rankings.nonce = o.nonce;
+        rankings.ready = true;
+
+        Ok(())
+    }
+
+    /// Cast a ranked ballot: the encrypted option at each place, best
+    /// first, ranking every option.  The circuit adds it to the count of
+    /// its order in the `RankingTally` and its first preference to the
+    /// running tallies.  A ballot that isn't a full ranking of the
+    /// proposal's options is discarded inside MPC.  Accounts, fees and
+    /// double-vote prevention are as for `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_ranked_vote(
+        ctx: Context<CastRankedVote>,
+        computation_offset: u64,
+        _id: u32,
+        rank_0: [u8; 32],
+        rank_1: [u8; 32],
+        rank_2: [u8; 32],
+        rank_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Ranked,
+            ErrorCode::WrongBallotKind
+        );
+        require!(ctx.accounts.rankings.ready, ErrorCode::RankingsNotReady);
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_ranked_vote(ranking_ctxt: Enc<Shared, Ranking>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+        //                  rankings_ctxt: Enc<Mxe, RankingCounts>, options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(rank_0)
+            .encrypted_u64(rank_1)
+            .encrypted_u64(rank_2)
+            .encrypted_u64(rank_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u128(ctx.accounts.rankings.nonce)
+            .account(
+                ctx.accounts.rankings.key(),
+                8 + 1, // discriminator + bump
+                32 * RANKING_ORDERS as u32,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastRankedVoteCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.rankings.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_ranked_vote")]
+    pub fn cast_ranked_vote_callback(
+        ctx: Context<CastRankedVoteCallback>,
+        output: SignedComputationOutputs<CastRankedVoteOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let (tallies, rankings) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastRankedVoteOutput {
+                field_0: CastRankedVoteOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.rankings.state = rankings.ciphertexts;
+        ctx.accounts.rankings.nonce = rankings.nonce;
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            tallies.ciphertexts,
+            tallies.nonce,
+        )
+    }
+
+    /// Reveal a ranked-choice proposal by instant runoff inside MPC: round
+    /// by round, the option with the fewest ballots is eliminated and its
+    /// ballots move to their next preference, until one option holds a
+    /// majority.  Only the deciding round's counts are revealed.
+    /// Authority-only, with the same deadline and quorum checks as
+    /// `reveal_results`, and finishes in its shape.
+    pub fn reveal_ranked(
+        ctx: Context<RevealRanked>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Ranked,
+            ErrorCode::WrongBallotKind
+        );
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing ranked proposal {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        // reveal_ranked(rankings_ctxt: Enc<Mxe, RankingCounts>, options: u64)
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.rankings.nonce)
+            .account(
+                ctx.accounts.rankings.key(),
+                8 + 1, // discriminator + bump
+                32 * RANKING_ORDERS as u32,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealRankedCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_ranked")]
+    pub fn reveal_ranked_callback(
+        ctx: Context<RevealRankedCallback>,
+        output: SignedComputationOutputs<RevealRankedOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealRankedOutput {
+                field_0:
+                    RevealRankedOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4, field_5),
+            Err(_) => {
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.0, o.1, o.2, o.3, o.4],
+            o.5,
+        )
+    }
+
+    // ================================================================
+    // Condorcet Voting
+    // ================================================================
+
+    /// Open a Condorcet or STAR proposal for ballots.  Authority-only,
+    /// before the first ballot.  Creates the proposal's `PreferenceMatrix`
+    /// and queues an MPC computation to initialize its encrypted counts,
+    /// one per ordered pair of options; `cast_condorcet` or `cast_star` is
+    /// open once the callback lands.
+    pub fn open_preferences(
+        ctx: Context<OpenPreferences>,
+        computation_offset: u64,
+        _id: u32,
+        nonce: u128,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        require!(
+            matches!(proposal.ballot, BallotKind::Condorcet | BallotKind::Star),
+            ErrorCode::WrongBallotKind
+        );
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+
+        let preferences = &mut ctx.accounts.preferences;
+        preferences.bump = ctx.bumps.preferences;
+        preferences.proposal = ctx.accounts.proposal_acc.key();
+
+        // init_preferences(mxe: Mxe)
+        let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![InitPreferencesCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.preferences.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_preferences")]
+    pub fn init_preferences_callback(
+        ctx: Context<InitPreferencesCallback>,
+        output: SignedComputationOutputs<InitPreferencesOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitPreferencesOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::InitTallies,
+                    aborted,
+                )
+            }
+        };
+
+        let preferences = &mut ctx.accounts.preferences;
+        preferences.state = o.ciphertexts;
+        preferences.nonce = o.nonce;
+        preferences.ready = true;
+
+        Ok(())
+    }
+
+    /// Cast a Condorcet ballot: the encrypted option at each place, best
+    /// first, ranking every option.  For every pair of options the circuit
+    /// counts the higher-ranked one's win in the `PreferenceMatrix`, and
+    /// adds the ballot's first preference to the running tallies.  A
+    /// ballot that isn't a full ranking of the proposal's options is
+    /// discarded inside MPC.  Accounts, fees and double-vote prevention
+    /// are as for `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_condorcet(
+        ctx: Context<CastCondorcet>,
+        computation_offset: u64,
+        _id: u32,
+        rank_0: [u8; 32],
+        rank_1: [u8; 32],
+        rank_2: [u8; 32],
+        rank_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Condorcet,
+            ErrorCode::WrongBallotKind
+        );
+        require!(ctx.accounts.preferences.ready, ErrorCode::PreferencesNotReady);
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_condorcet(ranking_ctxt: Enc<Shared, Ranking>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+        //                preferences_ctxt: Enc<Mxe, Preferences>, options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(rank_0)
+            .encrypted_u64(rank_1)
+            .encrypted_u64(rank_2)
+            .encrypted_u64(rank_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u128(ctx.accounts.preferences.nonce)
+            .account(
+                ctx.accounts.preferences.key(),
+                8 + 1, // discriminator + bump
+                32 * PREFERENCE_CELLS as u32,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastCondorcetCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.preferences.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_condorcet")]
+    pub fn cast_condorcet_callback(
+        ctx: Context<CastCondorcetCallback>,
+        output: SignedComputationOutputs<CastCondorcetOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let (tallies, preferences) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastCondorcetOutput {
+                field_0: CastCondorcetOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.preferences.state = preferences.ciphertexts;
+        ctx.accounts.preferences.nonce = preferences.nonce;
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            tallies.ciphertexts,
+            tallies.nonce,
+        )
+    }
+
+    /// Reveal a Condorcet proposal: the circuit compares every pair of
+    /// options head to head and reveals how many contests each one wins,
+    /// the winner, and whether that winner beats every other option.  When
+    /// none does — a cycle, or a tie at the top — the winner is the option
+    /// with the most wins, the lowest on a tie, and the matrix records the
+    /// cycle.  The head-to-head counts stay sealed.  Authority-only, with
+    /// the same deadline and quorum checks as `reveal_results`.
+    pub fn reveal_condorcet(
+        ctx: Context<RevealCondorcet>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Condorcet,
+            ErrorCode::WrongBallotKind
+        );
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing Condorcet proposal {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        // reveal_condorcet(preferences_ctxt: Enc<Mxe, Preferences>, options: u64)
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.preferences.nonce)
+            .account(
+                ctx.accounts.preferences.key(),
+                8 + 1, // discriminator + bump
+                32 * PREFERENCE_CELLS as u32,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealCondorcetCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.preferences.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_condorcet")]
+    pub fn reveal_condorcet_callback(
+        ctx: Context<RevealCondorcetCallback>,
+        output: SignedComputationOutputs<RevealCondorcetOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealCondorcetOutput {
+                field_0:
+                    RevealCondorcetOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                        field_6,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4, field_5, field_6),
+            Err(_) => {
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.preferences.cycle = o.6;
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.0, o.1, o.2, o.3, o.4],
+            o.5,
+        )?;
+
+        emit!(CondorcetRevealedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            winner: o.5,
+            cycle: o.6,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // STAR Voting
+    // ================================================================
+
+    /// Cast a STAR ballot: an encrypted 0..=5 score per option.  The
+    /// circuit adds the scores to the running tallies and, for every pair
+    /// of options scored differently, counts the higher-scored one's win
+    /// in the `PreferenceMatrix` for the runoff.  A ballot with a score off
+    /// the scale, or scoring an option past the proposal's, is discarded
+    /// inside MPC.  Accounts, fees and double-vote prevention are as for
+    /// `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_star(
+        ctx: Context<CastStar>,
+        computation_offset: u64,
+        _id: u32,
+        score_0: [u8; 32],
+        score_1: [u8; 32],
+        score_2: [u8; 32],
+        score_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Star,
+            ErrorCode::WrongBallotKind
+        );
+        require!(ctx.accounts.preferences.ready, ErrorCode::PreferencesNotReady);
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_star(scores_ctxt: Enc<Shared, StarScores>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+        //           preferences_ctxt: Enc<Mxe, Preferences>, options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(score_0)
+            .encrypted_u64(score_1)
+            .encrypted_u64(score_2)
+            .encrypted_u64(score_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u128(ctx.accounts.preferences.nonce)
+            .account(
+                ctx.accounts.preferences.key(),
+                8 + 1, // discriminator + bump
+                32 * PREFERENCE_CELLS as u32,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastStarCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.preferences.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_star")]
+    pub fn cast_star_callback(
+        ctx: Context<CastStarCallback>,
+        output: SignedComputationOutputs<CastStarOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let (tallies, preferences) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastStarOutput {
+                field_0: CastStarOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.preferences.state = preferences.ciphertexts;
+        ctx.accounts.preferences.nonce = preferences.nonce;
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            tallies.ciphertexts,
+            tallies.nonce,
+        )
+    }
+
+    /// Reveal a STAR proposal: the circuit takes the two options with the
+    /// highest score totals and runs them off head to head, the one more
+    /// ballots scored higher winning and a tied runoff going to the higher
+    /// total.  The score totals, both finalists and the runoff counts are
+    /// revealed; the other head-to-head counts stay sealed.  Authority-only,
+    /// with the same deadline and quorum checks as `reveal_results`.
+    pub fn reveal_star(
+        ctx: Context<RevealStar>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Star,
+            ErrorCode::WrongBallotKind
+        );
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing STAR proposal {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        // reveal_star(tallies_ctxt: Enc<Mxe, VoteTallies>, preferences_ctxt: Enc<Mxe, Preferences>,
+        //             options: u64)
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u128(ctx.accounts.preferences.nonce)
+            .account(
+                ctx.accounts.preferences.key(),
+                8 + 1, // discriminator + bump
+                32 * PREFERENCE_CELLS as u32,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealStarCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_star")]
+    pub fn reveal_star_callback(
+        ctx: Context<RevealStarCallback>,
+        output: SignedComputationOutputs<RevealStarOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealStarOutput {
+                field_0:
+                    RevealStarOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                        field_6,
+                        field_7,
+                        field_8,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4, field_5, field_6, field_7, field_8),
+            Err(_) => {
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.0, o.1, o.2, o.3, o.4],
+            o.5,
+        )?;
+
+        emit!(StarRevealedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            winner: o.5,
+            runner_up: o.6,
+            runoff_winner: o.7,
+            runoff_runner_up: o.8,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Quadratic Voting
+    // ================================================================
+
+    /// Cast a quadratic vote.
+    ///
+    /// The voter encrypts their credit allocation (v0, v1, v2, v3) where
+    /// each value is the number of effective votes for that option.
+    /// The quadratic cost v0² + v1² + v2² + v3² is verified inside MPC
+    /// against the proposal's `voice_credits`, or the payer's `VoterCredits`
+    /// once `open_sqrt_credits` ran.  Nobody sees individual allocations.
+    /// On a `BallotKind::TimeWeighted` proposal the circuit multiplies the
+    /// votes by `ProposalAccount::ballot_weight` as of this instruction.
+    /// Once `open_token_weights` ran it multiplies them by the payer's
+    /// balance of the weight mint too.
+    /// On a credential- or history-gated proposal the payer must hold a
+    /// `BallotCredential`; on a collection-gated one, an NFT of the
+    /// collection that hasn't voted yet.  Inside an open mix window the
+    /// ballot is held in the `MixQueue` instead of queued.  After a commit
+    /// phase the ballot must match the payer's `BallotCommitment`.
+    ///
+    /// Creates a VoterRecord PDA to prevent double-voting.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote<'info>(
+        ctx: Context<'_, '_, '_, 'info, CastVote<'info>>,
+        computation_offset: u64,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            matches!(
+                ctx.accounts.proposal_acc.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(
+            !ctx.accounts.proposal_acc.spend_histogram,
+            ErrorCode::HistogramBallotRequired
+        );
+        require!(
+            !ctx.accounts.proposal_acc.credit_bank,
+            ErrorCode::BankedBallotRequired
+        );
+        require!(
+            !ctx.accounts.proposal_acc.encrypted_budgets,
+            ErrorCode::BudgetedBallotRequired
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let eligible_weight = ask_eligibility_oracle(
+            &ctx.accounts.proposal_acc,
+            &ctx.accounts.payer,
+            ctx.accounts.eligibility_oracle.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        let token_weight = weigh_by_tokens(
+            &ctx.accounts.proposal_acc,
+            &ctx.accounts.payer.key(),
+            ctx.accounts.weight_token_account.as_deref(),
+        )?;
+        let stake = eligible_weight
+            .checked_mul(token_weight)
+            .ok_or(ErrorCode::AmountOverflow)?;
+        let time_weight = ctx.accounts.proposal_acc.ballot_weight(now);
+        // Every curve bends a stake down, never up, so a product that fits
+        // here fits inside MPC.
+        let weight = time_weight
+            .checked_mul(stake)
+            .ok_or(ErrorCode::AmountOverflow)?;
+        let budget = ballot_budget(
+            &ctx.accounts.proposal_acc,
+            ctx.accounts.voter_credits.as_ref(),
+            ctx.accounts.credit_account.as_ref(),
+        )?;
+        check_ballot_commitment(
+            &ctx.accounts.proposal_acc,
+            &ctx.accounts.payer.key(),
+            ctx.accounts.ballot_commitment.as_ref(),
+            &[vote_v0, vote_v1, vote_v2, vote_v3],
+            &vote_encryption_pubkey,
+            vote_nonce,
+        )?;
+        let nft_mint = admit_nft(
+            &ctx.accounts.proposal_acc,
+            &ctx.accounts.payer,
+            ctx.accounts.voter_token_account.as_deref(),
+            ctx.accounts.nft_metadata.as_ref(),
+            ctx.accounts.nft_ballot.as_ref(),
+            &ctx.accounts.system_program,
+        )?;
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            accounts.credential.as_deref(),
+            accounts.allowlist.as_ref(),
+            nft_mint,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+        burn_membership_token(
+            &accounts.proposal_acc,
+            &accounts.payer,
+            accounts.burn_token_account.as_deref(),
+            accounts.burn_mint.as_deref(),
+            accounts.token_program.as_ref(),
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        if ctx.accounts.proposal_acc.in_mix_window(now) {
+            return hold_ballot(
+                &mut ctx.accounts.proposal_acc,
+                ctx.accounts.mix_queue.as_ref(),
+                ctx.accounts.slot_hashes.as_ref(),
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                HeldBallot {
+                    voter_record: ctx.accounts.voter_record.key(),
+                    ciphertexts: [vote_v0, vote_v1, vote_v2, vote_v3],
+                    encryption_pubkey: vote_encryption_pubkey,
+                    nonce: vote_nonce,
+                    weight,
+                    budget,
+                },
+            );
+        }
+
+        // ArgBuilder order must match circuit params:
+        // cast_vote(alloc_ctxt: Enc<Shared, VoteAllocation>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+        //           weight: u64, budget: u64, stake: u64, curve: u64, cap: u64, options: u64)
+        let (curve, cap) = ctx.accounts.proposal_acc.weight_curve.circuit_args();
+        let args = ArgBuilder::new()
+            // VoteAllocation: Enc<Shared, VoteAllocation>
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(vote_v0)
+            .encrypted_u64(vote_v1)
+            .encrypted_u64(vote_v2)
+            .encrypted_u64(vote_v3)
+            // VoteTallies: Enc<Mxe, VoteTallies>
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8, // discriminator
+                32 * 5, // 5 encrypted u64 counters
+            )
+            .plaintext_u64(time_weight)
+            .plaintext_u64(budget)
+            .plaintext_u64(stake)
+            .plaintext_u64(curve)
+            .plaintext_u64(cap)
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastVoteCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_vote")]
+    pub fn cast_vote_callback(
+        ctx: Context<CastVoteCallback>,
+        output: SignedComputationOutputs<CastVoteOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let dataless = ctx.accounts.proposal_acc.dataless_records;
+        // `cast_vote_anonymous` ballots have no `VoterRecord` to stamp, and
+        // `cast_vote_dataless` ones a record without data.  Those were cast
+        // while voting was open, before any reveal could start a
+        // generation, so they belong to the first.
+        let generation = match ctx.accounts.voter_record.as_ref() {
+            Some(voter_record) => Some(voter_record.tally_generation),
+            None if dataless => Some(0),
+            None => None,
+        };
+        if let Some(generation) = generation {
+            if !is_current_generation(&ctx.accounts.proposal_acc, generation) {
+                return Ok(());
+            }
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastVoteOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        match ctx.accounts.voter_record.as_mut() {
+            Some(voter_record) => count_ballot(
+                &mut ctx.accounts.proposal_acc,
+                &ctx.accounts.tally_acc,
+                voter_record,
+                o.ciphertexts,
+                o.nonce,
+            ),
+            None if dataless => count_dataless_ballot(
+                &mut ctx.accounts.proposal_acc,
+                &ctx.accounts.tally_acc,
+                o.ciphertexts,
+                o.nonce,
+            ),
+            // `cast_vote_anonymous` ballots have no `VoterRecord`.
+            None => count_anonymous_ballot(
+                &mut ctx.accounts.proposal_acc,
+                &ctx.accounts.tally_acc,
+                o.ciphertexts,
+                o.nonce,
+            ),
+        }
+    }
+
+    /// Cast quadratic votes on up to `MAX_BATCH_VOTES` proposals in one
+    /// transaction, such as the races of one election.  Each ballot queues
+    /// its own `cast_vote` computation, at `computation_offset + i` for the
+    /// `i`-th, but they share the payer's fee transfer and the cluster, fee
+    /// pool and clock accounts.  Each ballot's accounts follow as remaining
+    /// accounts, four per ballot: its proposal (writable), the proposal's
+    /// `TallyAccount`, the payer's `VoterRecord` on it (writable, created
+    /// here, so a double vote still fails) and its computation account
+    /// (writable).
+    ///
+    /// Only proposals `cast_vote` takes without extra accounts batch: open
+    /// to anyone, without a supply quorum, square-root credits, burn-to-vote,
+    /// an eligibility oracle, token weights, a commit phase or a spend
+    /// histogram, and outside any mix window.
+    pub fn cast_votes_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastVotesBatch<'info>>,
+        computation_offset: u64,
+        votes: Vec<BatchedVote>,
+    ) -> Result<()> {
+        require!(
+            (1..=MAX_BATCH_VOTES).contains(&votes.len())
+                && ctx.remaining_accounts.len() == 4 * votes.len(),
+            ErrorCode::InvalidVoteBatch
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let voter = ctx.accounts.payer.key();
+        let fee = ctx
+            .accounts
+            .config
+            .vote_fee
+            .checked_mul(votes.len() as u64)
+            .ok_or(ErrorCode::AmountOverflow)?;
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            fee,
+        )?;
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let mut proposals = Vec::with_capacity(votes.len());
+        for (i, (vote, group)) in votes.iter().zip(ctx.remaining_accounts.chunks_exact(4)).enumerate() {
+            let [proposal_info, tally_info, record_info, computation_info] = group else {
+                return err!(ErrorCode::InvalidVoteBatch);
+            };
+            require!(
+                proposal_info.is_writable && !proposals.contains(proposal_info.key),
+                ErrorCode::InvalidVoteBatch
+            );
+            proposals.push(proposal_info.key());
+            let mut proposal = Account::<ProposalAccount>::try_from(proposal_info)?;
+            let tally = AccountLoader::<TallyAccount>::try_from(tally_info)?;
+            require_keys_eq!(tally.load()?.proposal, proposal.key(), ErrorCode::InvalidVoteBatch);
+            let offset = computation_offset
+                .checked_add(i as u64)
+                .ok_or(ErrorCode::InvalidVoteBatch)?;
+            require_keys_eq!(
+                computation_info.key(),
+                derive_comp_pda!(offset, ctx.accounts.mxe_account, ErrorCode::ClusterNotSet),
+                ErrorCode::InvalidVoteBatch
+            );
+
+            check_plain_ballot(&proposal, now)?;
+
+            let (mut voter_record, voter_record_bump) = init_voter_record(
+                &ctx.accounts.payer,
+                record_info,
+                &proposal.key(),
+                &ctx.accounts.system_program,
+            )?;
+            admit_ballot(
+                &mut proposal,
+                &mut voter_record,
+                voter_record_bump,
+                voter,
+                None,
+                None,
+                None,
+                None,
+                &mut ctx.accounts.global_stats,
+                &ctx.accounts.config,
+            )?;
+
+            // As `cast_vote`, with no oracle or token weight: a stake of 1.
+            let (curve, cap) = proposal.weight_curve.circuit_args();
+            let args = ArgBuilder::new()
+                .x25519_pubkey(vote.encryption_pubkey)
+                .plaintext_u128(vote.nonce)
+                .encrypted_u64(vote.ciphertexts[0])
+                .encrypted_u64(vote.ciphertexts[1])
+                .encrypted_u64(vote.ciphertexts[2])
+                .encrypted_u64(vote.ciphertexts[3])
+                .plaintext_u128(tally.load()?.nonce())
+                .account(tally.key(), 8, 32 * 5)
+                .plaintext_u64(proposal.ballot_weight(now))
+                .plaintext_u64(proposal.voice_credits)
+                .plaintext_u64(1)
+                .plaintext_u64(curve)
+                .plaintext_u64(cap)
+                .plaintext_u64(proposal.num_options.into())
+                .build();
+
+            ctx.accounts.computation_account = UncheckedAccount::try_from(computation_info);
+            let lamports_before = ctx.accounts.payer.lamports();
+            track_computation(&mut proposal)?;
+            queue_computation(
+                ctx.accounts,
+                offset,
+                args,
+                vec![CastVoteCallback::callback_ix(
+                    offset,
+                    &ctx.accounts.mxe_account,
+                    &[
+                        CallbackAccount {
+                            pubkey: proposal.key(),
+                            is_writable: true,
+                        },
+                        CallbackAccount {
+                            pubkey: tally.key(),
+                            is_writable: true,
+                        },
+                        CallbackAccount {
+                            pubkey: ctx.accounts.global_stats.key(),
+                            is_writable: true,
+                        },
+                        CallbackAccount {
+                            pubkey: voter_record.key(),
+                            is_writable: true,
+                        },
+                    ],
+                )?],
+                1,
+                0,
+            )?;
+            reimburse_arcium_fee(&mut proposal, &ctx.accounts.payer, lamports_before)?;
+
+            proposal.exit(&crate::ID)?;
+            voter_record.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    /// Cast a quadratic vote for a wallet with its session key: as
+    /// `cast_vote`, but the payer is the `session_key` of the wallet's
+    /// unexpired `VotingSession`, and the `VoterRecord` — and any credential,
+    /// `VoterCredits` or quorum token account — is the wallet's.  The
+    /// session key pays the fees and rent.  Held in the mix window like
+    /// `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_session(
+        ctx: Context<CastVoteSession>,
+        computation_offset: u64,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            matches!(
+                ctx.accounts.proposal_acc.ballot,
+                BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(
+            !ctx.accounts.proposal_acc.spend_histogram,
+            ErrorCode::HistogramBallotRequired
+        );
+        require!(
+            !ctx.accounts.proposal_acc.credit_bank,
+            ErrorCode::BankedBallotRequired
+        );
+        require!(
+            !ctx.accounts.proposal_acc.encrypted_budgets,
+            ErrorCode::BudgetedBallotRequired
+        );
+        require!(
+            ctx.accounts.proposal_acc.burn_mint.is_none(),
+            ErrorCode::BurnToVoteUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.eligibility_oracle.is_none(),
+            ErrorCode::EligibilityOracleUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.weight_mint.is_none(),
+            ErrorCode::TokenWeightsUnsupported
+        );
+        require!(
+            ctx.accounts.proposal_acc.commit_deadline == 0,
+            ErrorCode::CommitPhaseUnsupported
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < ctx.accounts.session.expires_at, ErrorCode::SessionExpired);
+        let weight = ctx.accounts.proposal_acc.ballot_weight(now);
+        let budget = ballot_budget(
+            &ctx.accounts.proposal_acc,
+            ctx.accounts.voter_credits.as_ref(),
+            ctx.accounts.credit_account.as_ref(),
+        )?;
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.session.wallet,
+            accounts.voter_token_account.as_deref(),
+            accounts.credential.as_deref(),
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        if ctx.accounts.proposal_acc.in_mix_window(now) {
+            return hold_ballot(
+                &mut ctx.accounts.proposal_acc,
+                ctx.accounts.mix_queue.as_ref(),
+                ctx.accounts.slot_hashes.as_ref(),
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                HeldBallot {
+                    voter_record: ctx.accounts.voter_record.key(),
+                    ciphertexts: [vote_v0, vote_v1, vote_v2, vote_v3],
+                    encryption_pubkey: vote_encryption_pubkey,
+                    nonce: vote_nonce,
+                    weight,
+                    budget,
+                },
+            );
+        }
+
+        // Same circuit and argument order as `cast_vote`.
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(vote_v0)
+            .encrypted_u64(vote_v1)
+            .encrypted_u64(vote_v2)
+            .encrypted_u64(vote_v3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8, // discriminator
+                32 * 5, // 5 encrypted u64 counters
+            )
+            .plaintext_u64(weight)
+            .plaintext_u64(budget)
+            // Sessions can't be vouched for: a stake of 1, linear.
+            .plaintext_u64(1)
+            .plaintext_u64(0)
+            .plaintext_u64(0)
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastVoteCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    /// Cast a Likert ballot: an encrypted score per option on a -2..+2
+    /// scale, each sent offset by +2 (0..=4).  A ballot with any score off
+    /// the scale is discarded inside MPC.  Accounts, fees and
+    /// double-vote prevention are as for `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_likert(
+        ctx: Context<CastLikert>,
+        computation_offset: u64,
+        _id: u32,
+        score_0: [u8; 32],
+        score_1: [u8; 32],
+        score_2: [u8; 32],
+        score_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Likert,
+            ErrorCode::WrongBallotKind
+        );
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_likert(scores_ctxt: Enc<Shared, LikertScores>, tallies_ctxt: Enc<Mxe, VoteTallies>)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(score_0)
+            .encrypted_u64(score_1)
+            .encrypted_u64(score_2)
+            .encrypted_u64(score_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastLikertCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_likert")]
+    pub fn cast_likert_callback(
+        ctx: Context<CastLikertCallback>,
+        output: SignedComputationOutputs<CastLikertOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastLikertOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            o.ciphertexts,
+            o.nonce,
+        )
+    }
+
+    /// Cast a For/Against/Abstain ballot: a one-hot encrypted choice over
+    /// options 0 (For), 1 (Against) and 2 (Abstain), with `choice_3`
+    /// always 0.  Anything else is discarded inside MPC.  Accounts, fees
+    /// and double-vote prevention are as for `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_choice(
+        ctx: Context<CastChoice>,
+        computation_offset: u64,
+        _id: u32,
+        choice_0: [u8; 32],
+        choice_1: [u8; 32],
+        choice_2: [u8; 32],
+        choice_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            matches!(ctx.accounts.proposal_acc.ballot, BallotKind::YesNoAbstain { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_choice(choice_ctxt: Enc<Shared, Choice>, tallies_ctxt: Enc<Mxe, VoteTallies>)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(choice_0)
+            .encrypted_u64(choice_1)
+            .encrypted_u64(choice_2)
+            .encrypted_u64(choice_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastChoiceCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_choice")]
+    pub fn cast_choice_callback(
+        ctx: Context<CastChoiceCallback>,
+        output: SignedComputationOutputs<CastChoiceOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastChoiceOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            o.ciphertexts,
+            o.nonce,
+        )
+    }
+
+    /// Cast For/Against/Abstain ballots on 1 to `MAX_BALLOT_PROPOSALS`
+    /// proposals of the same `Ballot` in one transaction, counted by a
+    /// single MPC computation, so a voter working through an election
+    /// signs and pays Arcium once.  Each proposal's account, `TallyAccount`
+    /// and the payer's `VoterRecord` follow as writable remaining accounts,
+    /// in the order of `choices`: one encrypted index per proposal, 0 For,
+    /// 1 Against or 2 Abstain, its unused slots ignored.  The
+    /// `cast_ballot_choices` circuit discards a choice past Abstain without
+    /// touching the others.  Each proposal admits the ballot as
+    /// `cast_choice` does, with its own `VoterRecord`, turnout and vote fee.
+    /// A proposal counting turnout in token supply needs the voter's token
+    /// account, so takes `cast_choice` instead.
+    pub fn cast_ballot_choices<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastBallotChoices<'info>>,
+        computation_offset: u64,
+        choices: [[u8; 32]; MAX_BALLOT_PROPOSALS],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        let count = ctx.remaining_accounts.len() / 3;
+        require!(
+            (1..=MAX_BALLOT_PROPOSALS).contains(&count) && ctx.remaining_accounts.len() == 3 * count,
+            ErrorCode::InvalidBallot
+        );
+        let voter = ctx.accounts.payer.key();
+        let fee = ctx
+            .accounts
+            .config
+            .vote_fee
+            .checked_mul(count as u64)
+            .ok_or(ErrorCode::AmountOverflow)?;
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            fee,
+        )?;
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let mut races: Vec<(Account<ProposalAccount>, AccountLoader<TallyAccount>, Account<VoterRecord>)> =
+            Vec::with_capacity(count);
+        for group in ctx.remaining_accounts.chunks_exact(3) {
+            let [proposal_info, tally_info, record_info] = group else {
+                return err!(ErrorCode::InvalidBallot);
+            };
+            require!(
+                proposal_info.is_writable
+                    && tally_info.is_writable
+                    && races.iter().all(|(proposal, _, _)| proposal.key() != proposal_info.key()),
+                ErrorCode::InvalidBallot
+            );
+            let mut proposal = Account::<ProposalAccount>::try_from(proposal_info)?;
+            require!(
+                matches!(proposal.ballot, BallotKind::YesNoAbstain { .. }),
+                ErrorCode::WrongBallotKind
+            );
+            let on_ballot = races.first().map_or(proposal.on_ballot, |(first, _, _)| first.on_ballot);
+            require!(
+                proposal.on_ballot.is_some() && proposal.on_ballot == on_ballot,
+                ErrorCode::InvalidBallot
+            );
+            let tally = AccountLoader::<TallyAccount>::try_from(tally_info)?;
+            require_keys_eq!(tally.load()?.proposal, proposal.key(), ErrorCode::InvalidBallot);
+
+            let (mut voter_record, voter_record_bump) = init_voter_record(
+                &ctx.accounts.payer,
+                record_info,
+                &proposal.key(),
+                &ctx.accounts.system_program,
+            )?;
+            admit_ballot(
+                &mut proposal,
+                &mut voter_record,
+                voter_record_bump,
+                voter,
+                None,
+                None,
+                None,
+                None,
+                &mut ctx.accounts.global_stats,
+                &ctx.accounts.config,
+            )?;
+            races.push((proposal, tally, voter_record));
+        }
+
+        // cast_ballot_choices(choices_ctxt: Enc<Shared, BallotChoices>,
+        //                     tallies_0..7: Enc<Mxe, VoteTallies>, proposals: u64)
+        // Unused slots carry the first proposal's tallies, as the circuit
+        // passes them through untouched.
+        let mut args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce);
+        for choice in choices {
+            args = args.encrypted_u64(choice);
+        }
+        for slot in 0..MAX_BALLOT_PROPOSALS {
+            let (_, tally, _) = races.get(slot).unwrap_or(&races[0]);
+            args = args.plaintext_u128(tally.load()?.nonce()).account(tally.key(), 8, 32 * 5);
+        }
+        let args = args.plaintext_u64(count as u64).build();
+
+        let mut callback_accounts = vec![CallbackAccount {
+            pubkey: ctx.accounts.global_stats.key(),
+            is_writable: true,
+        }];
+        for (proposal, tally, voter_record) in &mut races {
+            track_computation(proposal)?;
+            for pubkey in [proposal.key(), tally.key(), voter_record.key()] {
+                callback_accounts.push(CallbackAccount {
+                    pubkey,
+                    is_writable: true,
+                });
+            }
+        }
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastBallotChoicesCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        // Each sponsorship covers what the earlier ones left unpaid.
+        for (proposal, _, voter_record) in &mut races {
+            reimburse_arcium_fee(proposal, &ctx.accounts.payer, lamports_before)?;
+            proposal.exit(&crate::ID)?;
+            voter_record.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    /// The proposals, tallies and voter records follow as remaining
+    /// accounts, in the order `cast_ballot_choices` queued them.  A
+    /// callback's `Context` can't lend them out as `Account`s, so they are
+    /// read and written back by hand.
+    #[arcium_callback(encrypted_ix = "cast_ballot_choices")]
+    pub fn cast_ballot_choices_callback(
+        ctx: Context<CastBallotChoicesCallback>,
+        output: SignedComputationOutputs<CastBallotChoicesOutput>,
+    ) -> Result<()> {
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let outputs = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastBallotChoicesOutput {
+                field_0:
+                    CastBallotChoicesOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                        field_6,
+                        field_7,
+                    },
+            }) => Some([field_0, field_1, field_2, field_3, field_4, field_5, field_6, field_7]),
+            Err(_) => None,
+        };
+
+        // A reveal queued on one proposal supersedes only its own slot.
+        for (slot, group) in ctx.remaining_accounts.chunks_exact(3).enumerate() {
+            let [proposal_info, tally_info, record_info] = group else {
+                return err!(ErrorCode::InvalidBallot);
+            };
+            require!(
+                [proposal_info, tally_info, record_info]
+                    .iter()
+                    .all(|info| info.owner == &crate::ID),
+                ErrorCode::InvalidBallot
+            );
+            let mut proposal = ProposalAccount::try_deserialize(&mut &proposal_info.try_borrow_data()?[..])?;
+            let mut voter_record = VoterRecord::try_deserialize(&mut &record_info.try_borrow_data()?[..])?;
+            settle_computation(&mut proposal);
+            match &outputs {
+                Some(outputs) => {
+                    if is_current_generation(&proposal, voter_record.tally_generation) {
+                        let mut data = tally_info.try_borrow_mut_data()?;
+                        require!(
+                            data.starts_with(TallyAccount::DISCRIMINATOR),
+                            ErrorCode::InvalidBallot
+                        );
+                        let tally = bytemuck::from_bytes_mut::<TallyAccount>(
+                            &mut data[8..8 + std::mem::size_of::<TallyAccount>()],
+                        );
+                        apply_ballot(
+                            proposal_info.key(),
+                            &mut proposal,
+                            tally,
+                            &mut voter_record,
+                            outputs[slot].ciphertexts,
+                            outputs[slot].nonce,
+                        )?;
+                    }
+                }
+                None => reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    proposal_info.key(),
+                    proposal.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )?,
+            }
+            proposal.try_serialize(&mut &mut proposal_info.try_borrow_mut_data()?[..])?;
+            voter_record.try_serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+        }
+        Ok(())
+    }
+
+    /// As `cast_choice`, on a proposal whose ballot keeps round records
+    /// (see `open_round_records`).  Instead of a `VoterRecord`, the ballot
+    /// sets the proposal's bit in the payer's `RoundRecord` for the
+    /// ballot, created with their first race; a bit already set is a
+    /// double vote.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_round_choice(
+        ctx: Context<CastRoundChoice>,
+        computation_offset: u64,
+        _id: u32,
+        choice_0: [u8; 32],
+        choice_1: [u8; 32],
+        choice_2: [u8; 32],
+        choice_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        round_records::cast_round_choice(ctx, computation_offset, choice_0, choice_1, choice_2, choice_3, vote_encryption_pubkey, vote_nonce)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_round_choice")]
+    pub fn cast_round_choice_callback(
+        ctx: Context<CastRoundChoiceCallback>,
+        output: SignedComputationOutputs<CastRoundChoiceOutput>,
+    ) -> Result<()> {
+        round_records::cast_round_choice_callback(ctx, output)
+    }
+
+    /// Cast an approval ballot: an encrypted 0 or 1 per option, approving
+    /// at most the proposal's `max_choices` options with equal weight, or
+    /// any number of a budget box's.  A ballot over the limit, or
+    /// approving an option the proposal does not have, is discarded inside
+    /// MPC.  Accounts, fees and double-vote prevention are as for
+    /// `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_approval(
+        ctx: Context<CastApproval>,
+        computation_offset: u64,
+        _id: u32,
+        approve_0: [u8; 32],
+        approve_1: [u8; 32],
+        approve_2: [u8; 32],
+        approve_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        let max_choices = match ctx.accounts.proposal_acc.ballot {
+            BallotKind::Approval { max_choices } => max_choices,
+            BallotKind::BudgetBox { .. } => ctx.accounts.proposal_acc.num_options,
+            _ => return err!(ErrorCode::WrongBallotKind),
+        };
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_approval(approvals_ctxt: Enc<Shared, Approvals>, tallies_ctxt: Enc<Mxe, VoteTallies>,
+        //               max_choices: u64, options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(approve_0)
+            .encrypted_u64(approve_1)
+            .encrypted_u64(approve_2)
+            .encrypted_u64(approve_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u64(max_choices.into())
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastApprovalCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_approval")]
+    pub fn cast_approval_callback(
+        ctx: Context<CastApprovalCallback>,
+        output: SignedComputationOutputs<CastApprovalOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastApprovalOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            o.ciphertexts,
+            o.nonce,
+        )
+    }
+
+    /// Cast a Borda ballot: the encrypted option at each place, best
+    /// first, ranking every option.  With n options the first place earns
+    /// n - 1 points and each place after it one fewer, added to the
+    /// running tallies, so `reveal_results` names the option with the most
+    /// points.  A ballot that isn't a full ranking of the proposal's
+    /// options is discarded inside MPC.  Accounts, fees and double-vote
+    /// prevention are as for `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_borda(
+        ctx: Context<CastBorda>,
+        computation_offset: u64,
+        _id: u32,
+        rank_0: [u8; 32],
+        rank_1: [u8; 32],
+        rank_2: [u8; 32],
+        rank_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Borda,
+            ErrorCode::WrongBallotKind
+        );
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_borda(ranking_ctxt: Enc<Shared, Ranking>, tallies_ctxt: Enc<Mxe, VoteTallies>, options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(rank_0)
+            .encrypted_u64(rank_1)
+            .encrypted_u64(rank_2)
+            .encrypted_u64(rank_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastBordaCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_borda")]
+    pub fn cast_borda_callback(
+        ctx: Context<CastBordaCallback>,
+        output: SignedComputationOutputs<CastBordaOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastBordaOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            o.ciphertexts,
+            o.nonce,
+        )
+    }
+
+    /// Cast a range ballot: an encrypted 0..=10 score per option, added to
+    /// the running tallies, so `reveal_results` names the option with the
+    /// highest total, and so the highest average.  A ballot with a score
+    /// above 10, or above 0 for an option the proposal lacks, is discarded
+    /// inside MPC.  Accounts, fees and double-vote prevention are as for
+    /// `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_range(
+        ctx: Context<CastRange>,
+        computation_offset: u64,
+        _id: u32,
+        score_0: [u8; 32],
+        score_1: [u8; 32],
+        score_2: [u8; 32],
+        score_3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.ballot == BallotKind::Range,
+            ErrorCode::WrongBallotKind
+        );
+        let voter_record_bump = ctx.bumps.voter_record;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // cast_range(scores_ctxt: Enc<Shared, RangeScores>, tallies_ctxt: Enc<Mxe, VoteTallies>, options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(score_0)
+            .encrypted_u64(score_1)
+            .encrypted_u64(score_2)
+            .encrypted_u64(score_3)
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastRangeCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.tally_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_range")]
+    pub fn cast_range_callback(
+        ctx: Context<CastRangeCallback>,
+        output: SignedComputationOutputs<CastRangeOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastRangeOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        count_ballot(
+            &mut ctx.accounts.proposal_acc,
+            &ctx.accounts.tally_acc,
+            &mut ctx.accounts.voter_record,
+            o.ciphertexts,
+            o.nonce,
+        )
+    }
+
+    /// Cast a ballot in a pairwise-discounted quadratic funding round.
+    ///
+    /// Takes the same encrypted allocation as `cast_vote` and checks the
+    /// same budget, but instead of adding it to the running tallies the
+    /// circuit seals it whole into the next slot of the round's
+    /// `PairwiseBox`, since the discount compares every pair of ballots at
+    /// reveal.  A round holds at most `MAX_PAIRWISE_BALLOTS` ballots.
+    /// Accounts, fees and double-vote prevention are as for `cast_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_pairwise(
+        ctx: Context<CastPairwise>,
+        computation_offset: u64,
+        _id: u32,
+        vote_v0: [u8; 32],
+        vote_v1: [u8; 32],
+        vote_v2: [u8; 32],
+        vote_v3: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+    ) -> Result<()> {
+        require!(
+            matches!(ctx.accounts.proposal_acc.ballot, BallotKind::Pairwise { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        let voter_record_bump = ctx.bumps.voter_record;
+        let pairwise_box_bump = ctx.bumps.pairwise_box;
+        let accounts = &mut *ctx.accounts;
+        admit_ballot(
+            &mut accounts.proposal_acc,
+            &mut accounts.voter_record,
+            voter_record_bump,
+            accounts.payer.key(),
+            accounts.voter_token_account.as_deref(),
+            None,
+            None,
+            None,
+            &mut accounts.global_stats,
+            &accounts.config,
+        )?;
+
+        let pairwise_box = &mut accounts.pairwise_box;
+        let slot = pairwise_box.count as usize;
+        require!(slot < MAX_PAIRWISE_BALLOTS, ErrorCode::PairwiseRoundFull);
+        pairwise_box.bump = pairwise_box_bump;
+        pairwise_box.voters[slot] = accounts.payer.key();
+        pairwise_box.count = pairwise_box.count.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.fee_vault,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.vote_fee,
+        )?;
+
+        // The sealed ballot's nonce comes from the computation account, so
+        // a voter cannot pick one already used for another ballot in the
+        // box and compare ciphertexts.
+        let seal_nonce = u128::from_le_bytes(
+            ctx.accounts.computation_account.key().to_bytes()[..16]
+                .try_into()
+                .unwrap(),
+        );
+
+        // cast_pairwise(alloc_ctxt: Enc<Shared, VoteAllocation>, mxe: Mxe, budget: u64, options: u64)
+        let args = ArgBuilder::new()
+            .x25519_pubkey(vote_encryption_pubkey)
+            .plaintext_u128(vote_nonce)
+            .encrypted_u64(vote_v0)
+            .encrypted_u64(vote_v1)
+            .encrypted_u64(vote_v2)
+            .encrypted_u64(vote_v3)
+            .plaintext_u128(seal_nonce)
+            .plaintext_u64(ctx.accounts.proposal_acc.voice_credits)
+            .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let lamports_before = ctx.accounts.payer.lamports();
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![CastPairwiseCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.voter_record.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.pairwise_box.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+    }
+
+    #[arcium_callback(encrypted_ix = "cast_pairwise")]
+    pub fn cast_pairwise_callback(
+        ctx: Context<CastPairwiseCallback>,
+        output: SignedComputationOutputs<CastPairwiseOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_generation(&ctx.accounts.proposal_acc, ctx.accounts.voter_record.tally_generation) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CastPairwiseOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::CastVote,
+                    aborted,
+                )
+            }
+        };
+
+        let pairwise_box = &mut ctx.accounts.pairwise_box;
+        let slot = pairwise_box.voters[..pairwise_box.count as usize]
+            .iter()
+            .position(|voter| *voter == ctx.accounts.voter_record.voter)
+            .ok_or(ErrorCode::PairwiseSlotNotFound)?;
+        pairwise_box.ballots[slot] = o.ciphertexts;
+        pairwise_box.nonces[slot] = o.nonce;
+        pairwise_box.filled |= 1 << slot;
+
+        mark_counted(
+            ctx.accounts.proposal_acc.key(),
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.voter_record,
+        )
+    }
+
+    // ================================================================
+    // Quorum Progress
+    // ================================================================
+
+    /// Ask the cluster, while voting is open, whether the encrypted
+    /// `total_votes` has reached `vote_threshold` — one bit, so organizers
+    /// know whether to keep campaigning without seeing the tallies.  The
+    /// first check fixes the threshold; later checks must repeat it, so
+    /// the total can't be narrowed down by moving it.  Authority-only.
+    /// Finishes in `reveal_quorum_progress_callback`.
+    pub fn check_quorum_progress(
+        ctx: Context<CheckQuorumProgress>,
+        computation_offset: u64,
+        _id: u32,
+        vote_threshold: u64,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            !matches!(proposal.ballot, BallotKind::Pairwise { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        let now = Clock::get()?.unix_timestamp;
+        check_stage(proposal.state(now), Stage::Voting)?;
+        require!(vote_threshold > 0, ErrorCode::InvalidVoteThreshold);
+        if proposal.vote_threshold == 0 {
+            proposal.vote_threshold = vote_threshold;
+        }
+        require!(
+            proposal.vote_threshold == vote_threshold,
+            ErrorCode::InvalidVoteThreshold
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(ctx.accounts.tally_acc.key(), 8, 32 * 5)
+            .plaintext_u64(vote_threshold)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealQuorumProgressCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_quorum_progress")]
+    pub fn reveal_quorum_progress_callback(
+        ctx: Context<RevealQuorumProgressCallback>,
+        output: SignedComputationOutputs<RevealQuorumProgressOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let reached = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealQuorumProgressOutput { field_0 }) => field_0,
+            Err(_) => {
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::QuorumProgress,
+                    aborted,
+                )
+            }
+        };
+
+        // Tallies only grow, so a check that lands late can't undo one
+        // that already saw the threshold reached.
+        let proposal = &mut ctx.accounts.proposal_acc;
+        proposal.vote_threshold_reached |= reached;
+        proposal.progress_checked_at = Clock::get()?.unix_timestamp;
+
+        emit!(QuorumProgressEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            vote_threshold: proposal.vote_threshold,
+            reached: proposal.vote_threshold_reached,
+            timestamp: proposal.progress_checked_at,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Trustees
+    // ================================================================
+
+    /// Name the trustees who hold the keys to a proposal's results: no
+    /// reveal can be queued until `threshold` of them `approve_reveal`,
+    /// and once approved, any of them may `force_reveal` if the authority
+    /// hasn't revealed `grace_period` seconds after the deadline.  Fixed
+    /// before voting starts, so voters know who can unlock the results.
+    /// Authority-only, once per proposal; `payer` funds the `TrusteeSet`.
+    pub fn appoint_trustees(
+        ctx: Context<AppointTrustees>,
+        trustees: Vec<Pubkey>,
+        threshold: u8,
+        grace_period: i64,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(
+            (1..=MAX_TRUSTEES).contains(&trustees.len())
+                && (1..=trustees.len()).contains(&threshold.into())
+                && grace_period > 0
+                && trustees.iter().enumerate().all(|(i, t)| !trustees[..i].contains(t)),
+            ErrorCode::InvalidTrusteeSet
+        );
+        proposal.trustee_threshold = threshold;
+
+        let set = &mut ctx.accounts.trustee_set;
+        set.bump = ctx.bumps.trustee_set;
+        set.proposal = proposal.key();
+        set.authority = proposal.authority;
+        set.trustees = trustees.clone();
+        set.approved = 0;
+        set.grace_period = grace_period;
+
+        emit!(TrusteesAppointedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            trustees,
+            threshold,
+            grace_period,
+        });
+
+        Ok(())
+    }
+
+    /// Record a trustee's approval of the reveal, once voting has closed.
+    /// Once per trustee; the reveal unlocks at the set's threshold.
+    pub fn approve_reveal(ctx: Context<ApproveReveal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Closed)?;
+
+        let trustee = ctx.accounts.trustee.key();
+        let set = &mut ctx.accounts.trustee_set;
+        let index = set
+            .trustees
+            .iter()
+            .position(|t| *t == trustee)
+            .ok_or(ErrorCode::NotATrustee)?;
+        require!(set.approved & (1 << index) == 0, ErrorCode::TrusteeAlreadyApproved);
+        set.approved |= 1 << index;
+        proposal.trustee_approvals = proposal
+            .trustee_approvals
+            .checked_add(1)
+            .ok_or(ErrorCode::CounterOverflow)?;
+
+        emit!(RevealApprovedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            trustee,
+            approvals: proposal.trustee_approvals,
+            threshold: proposal.trustee_threshold,
+        });
+
+        Ok(())
+    }
+
+    /// Reveal over a stalled authority: once the trustees approved and
+    /// `grace_period` has passed since the deadline with no reveal, any
+    /// trustee may queue it, with `reveal_results`' quorum checks.  For
+    /// polls and the ballots `reveal_results` reveals.  Finishes in
+    /// `reveal_results_callback`.
+    pub fn force_reveal(ctx: Context<ForceReveal>, computation_offset: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let trustee = ctx.accounts.trustee.key();
+        require!(
+            ctx.accounts.trustee_set.trustees.contains(&trustee),
+            ErrorCode::NotATrustee
+        );
+        let grace_period = ctx.accounts.trustee_set.grace_period;
+
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let is_poll = proposal.kind == ProposalKind::Poll;
+        require!(
+            is_poll
+                || matches!(
+                    proposal.ballot,
+                    BallotKind::Quadratic
+                        | BallotKind::Likert
+                        | BallotKind::TimeWeighted { .. }
+                        | BallotKind::Jury { .. }
+                        | BallotKind::Approval { .. }
+                        | BallotKind::Borda
+                        | BallotKind::Range
+                ),
+            ErrorCode::WrongBallotKind
+        );
+        require!(
+            clock.unix_timestamp >= proposal.deadline.saturating_add(grace_period),
+            ErrorCode::RevealGraceNotOver
+        );
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Closed)?;
+        require!(is_poll || proposal.quorum_reached(), ErrorCode::QuorumNotMet);
+        require!(proposal.mix_held == 0, ErrorCode::HeldBallotsPending);
+        require!(proposal.buffered == 0, ErrorCode::BufferedBallotsPending);
+        require!(proposal.trustees_approved(), ErrorCode::TrusteeApprovalPending);
+        require!(
+            proposal.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(proposal, ctx.accounts.computation_account.key(), clock.unix_timestamp);
+
+        emit!(RevealForcedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            trustee,
+            timestamp: clock.unix_timestamp,
+        });
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(ctx.accounts.tally_acc.key(), 8, 32 * 5)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealResultsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Close a `TrusteeSet`, returning its rent to the authority, once the
+    /// proposal is finalized, failed quorum, or was closed.
+    pub fn close_trustee_set(ctx: Context<CloseTrusteeSet>) -> Result<()> {
+        let info = ctx.accounts.proposal_acc.to_account_info();
+        if !info.data_is_empty() {
+            let proposal = ProposalAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+        }
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Co-authors
+    // ================================================================
+
+    /// Name up to `MAX_CO_AUTHORS` keys that share the authority's rights to
+    /// `extend_deadline` and to queue the reveal with `co_author_reveal`, so
+    /// the proposal outlives a lost authority key.  Authority-only, once per
+    /// proposal and before the first ballot; the CLI sends it with
+    /// `create_proposal`.
+    pub fn set_co_authors(ctx: Context<SetCoAuthors>, co_authors: Vec<Pubkey>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(proposal.co_authors.is_empty(), ErrorCode::CoAuthorsAlreadySet);
+        require!(
+            (1..=MAX_CO_AUTHORS).contains(&co_authors.len())
+                && !co_authors.contains(&proposal.authority)
+                && co_authors.iter().enumerate().all(|(i, c)| !co_authors[..i].contains(c)),
+            ErrorCode::InvalidCoAuthors
+        );
+        proposal.co_authors = co_authors.clone();
+
+        emit!(CoAuthorsSetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            co_authors,
+        });
+
+        Ok(())
+    }
+
+    /// Push the deadline out to `new_deadline`.  The authority or a
+    /// co-author signs, before the first ballot freezes the deadline; a
+    /// categorized proposal passes its `Category` and stays within its
+    /// `max_duration`.  A proposal on a `Ballot` keeps the ballot's, and
+    /// one with square-root credits the one its voters escrowed tokens
+    /// until.
+    pub fn extend_deadline(ctx: Context<ExtendDeadline>, new_deadline: i64) -> Result<()> {
+        let signer = ctx.accounts.signer.key();
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.shares_authority(&signer), ErrorCode::InvalidAuthority);
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        require!(proposal.on_ballot.is_none(), ErrorCode::DeadlineSetByBallot);
+        require!(proposal.credit_mint.is_none(), ErrorCode::DeadlineSetByCredits);
+        let now = Clock::get()?.unix_timestamp;
+        check_stage(proposal.state(now), Stage::Setup)?;
+        require!(
+            !proposal.has_computations_in_flight(now, ctx.accounts.config.reveal_timeout),
+            ErrorCode::ComputationsInFlight
+        );
+        require!(new_deadline > proposal.deadline, ErrorCode::InvalidDeadlineExtension);
+        if let Some(id) = proposal.category {
+            let entry = ctx
+                .accounts
+                .category_acc
+                .as_ref()
+                .filter(|c| c.id == id)
+                .ok_or(ErrorCode::DurationOutsideCategory)?;
+            require!(
+                entry.max_duration == 0 || new_deadline - proposal.created_at <= entry.max_duration,
+                ErrorCode::DurationOutsideCategory
+            );
+        }
+        proposal.deadline = new_deadline;
+
+        emit!(DeadlineExtendedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            signer,
+            deadline: new_deadline,
+        });
+
+        Ok(())
+    }
+
+    /// `reveal_results` on a co-author's signature, with the same checks,
+    /// for when the authority can't sign.  Finishes in
+    /// `reveal_results_callback`.
+    pub fn co_author_reveal(ctx: Context<CoAuthorReveal>, computation_offset: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let co_author = ctx.accounts.co_author.key();
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.co_authors.contains(&co_author), ErrorCode::InvalidAuthority);
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic
+                    | BallotKind::Likert
+                    | BallotKind::TimeWeighted { .. }
+                    | BallotKind::Jury { .. }
+                    | BallotKind::Approval { .. }
+                    | BallotKind::Borda
+                    | BallotKind::Range
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Closed)?;
+        require!(proposal.quorum_reached(), ErrorCode::QuorumNotMet);
+        require!(proposal.mix_held == 0, ErrorCode::HeldBallotsPending);
+        require!(proposal.buffered == 0, ErrorCode::BufferedBallotsPending);
+        require!(proposal.trustees_approved(), ErrorCode::TrusteeApprovalPending);
+        require!(
+            proposal.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(proposal, ctx.accounts.computation_account.key(), clock.unix_timestamp);
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(ctx.accounts.tally_acc.key(), 8, 32 * 5)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealResultsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Reveal Operator
+    // ================================================================
+
+    /// Designate `operator`, such as a keeper service's key, to queue the
+    /// reveal with `operator_reveal` once the deadline passes.  Every other
+    /// authority power stays with the authority, and the operator can't
+    /// extend the deadline or close the proposal.  Authority-only, once per
+    /// proposal and before the first ballot; the CLI sends it with
+    /// `create_proposal`.
+    pub fn set_reveal_operator(ctx: Context<SetRevealOperator>, operator: Pubkey) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        require!(proposal.reveal_operator.is_none(), ErrorCode::RevealOperatorAlreadySet);
+        require!(operator != proposal.authority, ErrorCode::InvalidRevealOperator);
+        proposal.reveal_operator = Some(operator);
+
+        emit!(RevealOperatorSetEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            authority: proposal.authority,
+            operator,
+        });
+
+        Ok(())
+    }
+
+    /// `reveal_results` on the reveal operator's signature, with the same
+    /// checks.  Finishes in `reveal_results_callback`.
+    pub fn operator_reveal(ctx: Context<OperatorReveal>, computation_offset: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let operator = ctx.accounts.operator.key();
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.reveal_operator == Some(operator), ErrorCode::InvalidAuthority);
+        require!(
+            matches!(
+                proposal.ballot,
+                BallotKind::Quadratic
+                    | BallotKind::Likert
+                    | BallotKind::TimeWeighted { .. }
+                    | BallotKind::Jury { .. }
+                    | BallotKind::Approval { .. }
+                    | BallotKind::Borda
+                    | BallotKind::Range
+            ),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Closed)?;
+        require!(proposal.quorum_reached(), ErrorCode::QuorumNotMet);
+        require!(proposal.mix_held == 0, ErrorCode::HeldBallotsPending);
+        require!(proposal.buffered == 0, ErrorCode::BufferedBallotsPending);
+        require!(proposal.trustees_approved(), ErrorCode::TrusteeApprovalPending);
+        require!(
+            proposal.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(proposal, ctx.accounts.computation_account.key(), clock.unix_timestamp);
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(ctx.accounts.tally_acc.key(), 8, 32 * 5)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealResultsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Reveal
+    // ================================================================
+
+    /// Reveal results.  Only callable by the proposal authority, after the
+    /// deadline, and only when quorum is met (turnout >= quorum target).
+    pub fn reveal_results(
+        ctx: Context<RevealResults>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.proposal_acc.authority,
+            ErrorCode::InvalidAuthority
+        );
+        require!(
+            matches!(
+                ctx.accounts.proposal_acc.ballot,
+                BallotKind::Quadratic
+                    | BallotKind::Likert
+                    | BallotKind::TimeWeighted { .. }
+                    | BallotKind::Jury { .. }
+                    | BallotKind::Approval { .. }
+                    | BallotKind::Borda
+                    | BallotKind::Range
+            ),
+            ErrorCode::WrongBallotKind
+        );
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(ctx.accounts.proposal_acc.mix_held == 0, ErrorCode::HeldBallotsPending);
+        require!(ctx.accounts.proposal_acc.buffered == 0, ErrorCode::BufferedBallotsPending);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing results for proposal {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealResultsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Queue the reveal of a poll whose deadline has passed.  Polls have no
+    /// reveal authority, so anyone may call this; the keeper does as soon
+    /// as voting closes.  Finishes in `reveal_results_callback`.
+    pub fn reveal_poll(ctx: Context<RevealPoll>, computation_offset: u64) -> Result<()> {
+        require!(
+            ctx.accounts.proposal_acc.kind == ProposalKind::Poll,
+            ErrorCode::NotAPoll
+        );
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.mix_held == 0, ErrorCode::HeldBallotsPending);
+        require!(ctx.accounts.proposal_acc.buffered == 0, ErrorCode::BufferedBallotsPending);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealResultsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Let anyone reveal the proposal with `auto_reveal` once voting
+    /// closes, so an automation service can fire the reveal at the deadline
+    /// instead of the authority or a keeper holding its key.
+    /// Authority-only, before the deadline; for polls and the ballots
+    /// `reveal_results` reveals.
+    pub fn enable_auto_reveal(ctx: Context<EnableAutoReveal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(
+            proposal.kind == ProposalKind::Poll
+                || matches!(
+                    proposal.ballot,
+                    BallotKind::Quadratic
+                        | BallotKind::Likert
+                        | BallotKind::TimeWeighted { .. }
+                        | BallotKind::Jury { .. }
+                        | BallotKind::Approval { .. }
+                        | BallotKind::Borda
+                        | BallotKind::Range
+                ),
+            ErrorCode::WrongBallotKind
+        );
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+        proposal.auto_reveal = true;
+
+        emit!(AutoRevealEnabledEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            deadline: proposal.deadline,
+            computation_offset: auto_reveal_offset(&proposal.key(), proposal.auto_reveals),
+        });
+
+        Ok(())
+    }
+
+    /// Reveal a proposal that opted in with `enable_auto_reveal`, or mark
+    /// it quorum-failed if it closed below quorum.  Takes no arguments: the
+    /// computation offset is `auto_reveal_offset(proposal, auto_reveals)`,
+    /// so every account is known in advance and an automation thread can
+    /// schedule the call for the deadline.  After `expire_reveal` the next
+    /// call uses the next offset.  Anyone may call it; `payer` pays the
+    /// Arcium fee.  Finishes in `reveal_results_callback`.
+    pub fn auto_reveal(ctx: Context<AutoReveal>) -> Result<()> {
+        let clock = Clock::get()?;
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.auto_reveal, ErrorCode::AutoRevealDisabled);
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Closed)?;
+
+        if proposal.kind != ProposalKind::Poll && !proposal.quorum_reached() {
+            fail_quorum(proposal, clock.unix_timestamp)?;
+            return Ok(());
+        }
+
+        require!(proposal.mix_held == 0, ErrorCode::HeldBallotsPending);
+        require!(proposal.buffered == 0, ErrorCode::BufferedBallotsPending);
+        require!(proposal.trustees_approved(), ErrorCode::TrusteeApprovalPending);
+        require!(
+            proposal.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(proposal, ctx.accounts.computation_account.key(), clock.unix_timestamp);
+        let computation_offset = auto_reveal_offset(&proposal.key(), proposal.auto_reveals);
+        proposal.auto_reveals = proposal.auto_reveals.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(ctx.accounts.tally_acc.key(), 8, 32 * 5)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealResultsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_results")]
+    pub fn reveal_results_callback(
+        ctx: Context<RevealResultsCallback>,
+        output: SignedComputationOutputs<RevealResultsOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        // Kept so auditors can re-verify the results without trusting events.
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealResultsOutput {
+                field_0:
+                    RevealResultsOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4, field_5),
+            Err(_) => {
+                // Let the authority queue the reveal again right away.
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.0, o.1, o.2, o.3, o.4],
+            o.5,
+        )?;
+        if ctx.accounts.proposal_acc.ballot == BallotKind::Likert {
+            emit!(SentimentRevealedEvent {
+                proposal: ctx.accounts.proposal_acc.key(),
+                proposal_id: ctx.accounts.proposal_acc.id,
+                ballots: o.4,
+                net_sentiment: ctx.accounts.proposal_acc.net_sentiment(),
+            });
+        }
+        if ctx.accounts.proposal_acc.ballot == BallotKind::Range {
+            emit!(RangeAveragesEvent {
+                proposal: ctx.accounts.proposal_acc.key(),
+                proposal_id: ctx.accounts.proposal_acc.id,
+                ballots: o.4,
+                average_scores: ctx.accounts.proposal_acc.average_scores(),
+            });
+        }
+        if let BallotKind::Jury { .. } = ctx.accounts.proposal_acc.ballot {
+            emit!(JuryRankingEvent {
+                proposal: ctx.accounts.proposal_acc.key(),
+                proposal_id: ctx.accounts.proposal_acc.id,
+                jurors: o.4,
+                scores: [o.0, o.1, o.2, o.3],
+                ranking: ctx.accounts.proposal_acc.jury_ranking(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reveal a For/Against/Abstain proposal and decide it inside MPC
+    /// against its veto threshold and turnout bias.  Authority-only, with
+    /// the same deadline and quorum checks as `reveal_results`.
+    pub fn reveal_outcome(
+        ctx: Context<RevealOutcome>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        let BallotKind::YesNoAbstain { veto_bps } = ctx.accounts.proposal_acc.ballot else {
+            return err!(ErrorCode::WrongBallotKind);
+        };
+        require!(!ctx.accounts.proposal_acc.dual_chamber, ErrorCode::DualChamberReveal);
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing outcome for proposal {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        // reveal_outcome(tallies_ctxt: Enc<Mxe, VoteTallies>, veto_bps: u64, pass_bps: u64, bias_bps: u64,
+        //                electorate: u64)
+        let pass_bps = match ctx.accounts.proposal_acc.supermajority_bps {
+            0 => 5_000,
+            bps => bps,
+        };
+        let electorate = ctx.accounts.proposal_acc.electorate.clamp(1, MAX_VOTERS as u64);
+        let args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u64(veto_bps.into())
+            .plaintext_u64(pass_bps.into())
+            .plaintext_u64(ctx.accounts.proposal_acc.turnout_bias_bps.into())
+            .plaintext_u64(electorate)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealOutcomeCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_outcome")]
+    pub fn reveal_outcome_callback(
+        ctx: Context<RevealOutcomeCallback>,
+        output: SignedComputationOutputs<RevealOutcomeOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealOutcomeOutput {
+                field_0:
+                    RevealOutcomeOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                        field_6,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4, field_5, field_6),
+            Err(_) => {
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        let outcome = VoteOutcome::from_code(o.6);
+        ctx.accounts.proposal_acc.outcome = Some(outcome);
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.0, o.1, o.2, o.3, o.4],
+            o.5,
+        )?;
+
+        emit!(OutcomeRevealedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            votes_for: o.0,
+            votes_against: o.1,
+            votes_abstain: o.2,
+            outcome,
+        });
+
+        Ok(())
+    }
+
+    /// Reveal a budget box and pick the options it funds inside MPC: the
+    /// subset with the most approvals whose costs fit in the budget.
+    /// Authority-only, with the same deadline and quorum checks as
+    /// `reveal_results`.
+    pub fn reveal_budget_box(
+        ctx: Context<RevealBudgetBox>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        let BallotKind::BudgetBox { budget, costs } = ctx.accounts.proposal_acc.ballot else {
+            return err!(ErrorCode::WrongBallotKind);
+        };
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing budget box {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        // reveal_budget_box(tallies_ctxt: Enc<Mxe, VoteTallies>, budget: u64,
+        //                   cost_0: u64, cost_1: u64, cost_2: u64, cost_3: u64)
+        let mut args = ArgBuilder::new()
+            .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+            .account(
+                ctx.accounts.tally_acc.key(),
+                8,
+                32 * 5,
+            )
+            .plaintext_u64(budget);
+        for cost in costs {
+            args = args.plaintext_u64(cost);
+        }
+        let args = args.build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealBudgetBoxCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_budget_box")]
+    pub fn reveal_budget_box_callback(
+        ctx: Context<RevealBudgetBoxCallback>,
+        output: SignedComputationOutputs<RevealBudgetBoxOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealBudgetBoxOutput {
+                field_0:
+                    RevealBudgetBoxOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                        field_6,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4, field_5, field_6),
+            Err(_) => {
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        ctx.accounts.proposal_acc.funded = Some(o.6);
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.0, o.1, o.2, o.3, o.4],
+            o.5,
+        )?;
+
+        let (funded, spent) = ctx.accounts.proposal_acc.funded_options();
+        emit!(BudgetBoxFundedEvent {
+            proposal: ctx.accounts.proposal_acc.key(),
+            proposal_id: ctx.accounts.proposal_acc.id,
+            funded,
+            spent,
+        });
+
+        Ok(())
+    }
+
+    /// Reveal a pairwise-discounted quadratic funding round.  The circuit
+    /// unseals every ballot in the round's `PairwiseBox` and reveals each
+    /// option's funding, with the cross-subsidy of every pair of voters
+    /// discounted by how much they back the same options.  Authority-only,
+    /// with the same deadline and quorum checks as `reveal_results`;
+    /// finishes in `reveal_results`' shape, so attestations verify alike.
+    pub fn reveal_pairwise(
+        ctx: Context<RevealPairwise>,
+        computation_offset: u64,
+        id: u32,
+    ) -> Result<()> {
+        let BallotKind::Pairwise { discount } = ctx.accounts.proposal_acc.ballot else {
+            return err!(ErrorCode::WrongBallotKind);
+        };
+
+        let clock = Clock::get()?;
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+
+        require!(ctx.accounts.proposal_acc.quorum_reached(), ErrorCode::QuorumNotMet);
+
+        require!(
+            ctx.accounts.proposal_acc.trustees_approved(),
+            ErrorCode::TrusteeApprovalPending
+        );
+
+        require!(
+            ctx.accounts.proposal_acc.reveal_pending_since == 0,
+            ErrorCode::RevealAlreadyPending
+        );
+        begin_reveal(
+            &mut ctx.accounts.proposal_acc,
+            ctx.accounts.computation_account.key(),
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Revealing pairwise round {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        // A round nobody voted in still needs the box to read from.
+        ctx.accounts.pairwise_box.bump = ctx.bumps.pairwise_box;
+
+        // reveal_pairwise(b0..b7: Enc<Mxe, VoteAllocation>, filled: u64, discount: u64)
+        // Slots outside `filled` are zeros, which the circuit ignores.
+        let pairwise_box = &ctx.accounts.pairwise_box;
+        let mut args = ArgBuilder::new();
+        for (slot, nonce) in pairwise_box.nonces.iter().enumerate() {
+            args = args.plaintext_u128(*nonce).account(
+                pairwise_box.key(),
+                8 + 1 + 32 * 4 * slot as u32, // discriminator + bump + earlier slots
+                32 * 4,                       // 4 encrypted u64 votes
+            );
+        }
+        let args = args
+            .plaintext_u64(pairwise_box.filled.into())
+            .plaintext_u64(discount.into())
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        track_computation(&mut ctx.accounts.proposal_acc)?;
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![RevealPairwiseCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.proposal_acc.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.global_stats.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_pairwise")]
+    pub fn reveal_pairwise_callback(
+        ctx: Context<RevealPairwiseCallback>,
+        output: SignedComputationOutputs<RevealPairwiseOutput>,
+    ) -> Result<()> {
+        settle_computation(&mut ctx.accounts.proposal_acc);
+        if !is_current_reveal(&ctx.accounts.proposal_acc, &ctx.accounts.computation_account) {
+            return Ok(());
+        }
+        let aborted = matches!(output, SignedComputationOutputs::Failure);
+        let signature = match &output {
+            SignedComputationOutputs::Success(_, signature) => *signature,
+            _ => [0; 64],
+        };
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealPairwiseOutput {
+                field_0:
+                    RevealPairwiseOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4, field_5),
+            Err(_) => {
+                ctx.accounts.proposal_acc.reveal_pending_since = 0;
+                return reject_callback(
+                    &mut ctx.accounts.global_stats,
+                    ctx.accounts.proposal_acc.key(),
+                    ctx.accounts.proposal_acc.id,
+                    CallbackStage::RevealResults,
+                    aborted,
+                )
+            }
+        };
+
+        record_results(
+            &mut ctx.accounts.proposal_acc,
+            &mut ctx.accounts.global_stats,
+            ctx.accounts.cluster_account.key(),
+            &ctx.accounts.computation_account,
+            signature,
+            [o.0, o.1, o.2, o.3, o.4],
+            o.5,
+        )
+    }
+
+    /// Call off the reveal in flight — queued too early, say, or while a
+    /// challenge is pending — as long as its callback hasn't landed.  The
+    /// proposal goes back to waiting for a reveal, and the callback is
+    /// dropped if it arrives.  Auto-reveal is switched off, so the reveal
+    /// is queued again only when the authority chooses (polls excepted).
+    /// Authority-only.
+    pub fn abort_reveal(ctx: Context<AbortReveal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        require!(proposal.reveal_pending_since != 0, ErrorCode::NoPendingReveal);
+
+        let queued_at = proposal.reveal_pending_since;
+        let computation = proposal.reveal_computation;
+        proposal.reveal_pending_since = 0;
+        proposal.reveal_computation = Pubkey::default();
+        proposal.auto_reveal = false;
+
+        emit!(RevealAbortedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            computation,
+            queued_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Maintenance (permissionless)
+    // ================================================================
+
+    /// Mark a proposal whose deadline passed without reaching quorum.  Its
+    /// results can never be revealed, so this settles it and makes it
+    /// closable.  Anyone may call it.
+    pub fn mark_quorum_failed(ctx: Context<MarkQuorumFailed>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let clock = Clock::get()?;
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Closed)?;
+        require!(!proposal.quorum_reached(), ErrorCode::QuorumReached);
+
+        fail_quorum(proposal, clock.unix_timestamp)?;
+
+        Ok(())
+    }
+
+    /// Clear a reveal whose callback never arrived within
+    /// `Config::reveal_timeout`, so the authority can queue it again.  Anyone
+    /// may call it.
+    pub fn expire_reveal(ctx: Context<ExpireReveal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let clock = Clock::get()?;
+        require!(proposal.reveal_pending_since != 0, ErrorCode::NoPendingReveal);
+        require!(
+            clock.unix_timestamp
+                >= proposal
+                    .reveal_pending_since
+                    .saturating_add(ctx.accounts.config.reveal_timeout),
+            ErrorCode::RevealNotStale
+        );
+
+        let queued_at = proposal.reveal_pending_since;
+        proposal.reveal_pending_since = 0;
+
+        emit!(RevealExpiredEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            queued_at,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Fee Sponsorship
+    // ================================================================
+
+    /// Add `amount` lamports to the proposal's fee sponsorship.  Each
+    /// `cast_vote` is reimbursed its Arcium computation fee from it until it
+    /// runs out; whatever is left returns to the authority with
+    /// `close_proposal`.  Authority only, while voting is open.
+    pub fn fund_sponsorship(ctx: Context<FundSponsorship>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Setup)?;
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.proposal_acc.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        let proposal = &mut ctx.accounts.proposal_acc;
+        proposal.sponsor_balance = proposal
+            .sponsor_balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::AmountOverflow)?;
+
+        emit!(SponsorshipFundedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            amount,
+            balance: proposal.sponsor_balance,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Council
+    // ================================================================
+
+    /// Confiscate an abusive or spam proposal's deposit and send it to
+    /// `Config.treasury`.  Council only; `reason` is recorded in the event.
+    pub fn slash_deposit(ctx: Context<SlashDeposit>, reason: SlashReason) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let amount = proposal.deposit;
+        require!(amount > 0, ErrorCode::NoDeposit);
+
+        proposal.deposit = 0;
+        proposal.sub_lamports(amount)?;
+        ctx.accounts.treasury.add_lamports(amount)?;
+
+        let clock = Clock::get()?;
+        emit!(DepositSlashedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            authority: proposal.authority,
+            council: ctx.accounts.council.key(),
+            treasury: ctx.accounts.treasury.key(),
+            amount,
+            reason,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Participation Rewards
+    // ================================================================
+
+    /// Escrow `amount` tokens of `mint` as a participation reward.  Authority
+    /// only, and only before the first ballot — typically in the same
+    /// transaction as `create_proposal` — so voters know the reward up front.
+    pub fn create_reward_pool(ctx: Context<CreateRewardPool>, amount: u64) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let clock = Clock::get()?;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Setup)?;
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        // Shares are claimed with a `VoterRecord`, which round ballots lack.
+        require!(proposal.round_slot.is_none(), ErrorCode::RoundRecordsUnsupported);
+        require!(!proposal.dataless_records, ErrorCode::DatalessRecordsUnsupported);
+        proposal.has_reward_pool = true;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.bump = ctx.bumps.reward_pool;
+        pool.proposal = proposal.key();
+        pool.mint = ctx.accounts.mint.key();
+        pool.total_amount = amount;
+        pool.settled = false;
+        pool.eligible_voters = 0;
+        pool.share = 0;
+        pool.claimed_voters = 0;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(RewardPoolCreatedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            mint: pool.mint,
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Fix each counted voter's share once the proposal is settled, and
+    /// return the remainder (rounding dust, or everything if the proposal
+    /// failed quorum or counted nobody) to the authority.  Anyone may call
+    /// it; `close_proposal` requires it.
+    pub fn settle_rewards(ctx: Context<SettleRewards>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(!pool.settled, ErrorCode::RewardsAlreadySettled);
+        let eligible = if proposal.is_finalized {
+            proposal.counted_voters
+        } else {
+            0
+        };
+        let share = if eligible == 0 {
+            0
+        } else {
+            pool.total_amount / eligible as u64
+        };
+        let refund = pool.total_amount - share * eligible as u64;
+
+        pool.settled = true;
+        pool.eligible_voters = eligible;
+        pool.share = share;
+        proposal.rewards_settled = true;
+
+        if refund > 0 {
+            let proposal_key = proposal.key();
+            let seeds: &[&[u8]] = &[b"reward_pool", proposal_key.as_ref(), &[pool.bump]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.reward_vault.to_account_info(),
+                        to: ctx.accounts.authority_token_account.to_account_info(),
+                        authority: pool.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                refund,
+            )?;
+        }
+
+        let clock = Clock::get()?;
+        emit!(RewardsSettledEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            eligible_voters: eligible,
+            share,
+            refunded: refund,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pay a counted voter their share of a settled reward pool.  Only
+    /// participation is rewarded: the share is the same for every ballot.
+    pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let record = &mut ctx.accounts.voter_record;
+        require!(pool.settled, ErrorCode::RewardsNotSettled);
+        require!(record.counted, ErrorCode::VoteNotCounted);
+        require!(!record.reward_claimed, ErrorCode::RewardAlreadyClaimed);
+
+        record.reward_claimed = true;
+        pool.claimed_voters = pool.claimed_voters.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+        let seeds: &[&[u8]] = &[b"reward_pool", pool.proposal.as_ref(), &[pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: ctx.accounts.voter_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            pool.share,
+        )?;
+
+        let clock = Clock::get()?;
+        emit!(RewardClaimedEvent {
+            proposal: pool.proposal,
+            voter: record.voter,
+            amount: pool.share,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Matching Funds
+    // ================================================================
+
+    /// Escrow `amount` tokens of `mint` to match a quadratic funding round,
+    /// and name the wallet each option's match is paid to.  Authority only,
+    /// and only before the first ballot, so backers know where the match
+    /// goes.
+    pub fn create_matching_pool(
+        ctx: Context<CreateMatchingPool>,
+        amount: u64,
+        recipients: Vec<Pubkey>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        let clock = Clock::get()?;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            matches!(proposal.ballot, BallotKind::Pairwise { .. }),
+            ErrorCode::WrongBallotKind
+        );
+        require!(
+            recipients.len() == proposal.num_options as usize,
+            ErrorCode::InvalidRecipients
+        );
+        check_stage(proposal.state(clock.unix_timestamp), Stage::Setup)?;
+        require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+        proposal.has_matching_pool = true;
+
+        let pool = &mut ctx.accounts.matching_pool;
+        pool.bump = ctx.bumps.matching_pool;
+        pool.proposal = proposal.key();
+        pool.mint = ctx.accounts.mint.key();
+        pool.total_amount = amount;
+        pool.recipients = recipients;
+        pool.settled = false;
+        pool.matches = [0; 4];
+        pool.paid = 0;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.authority_token_account.to_account_info(),
+                    to: ctx.accounts.matching_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(MatchingPoolCreatedEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            mint: pool.mint,
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Split the pool between the options in proportion to their revealed
+    /// funding once the round is settled, and return the remainder
+    /// (rounding dust, or everything if the round failed quorum or drew no
+    /// funding) to the authority.  Anyone may call it; `close_proposal`
+    /// requires it.
+    pub fn settle_matching(ctx: Context<SettleMatching>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Settled)?;
+
+        let pool = &mut ctx.accounts.matching_pool;
+        require!(!pool.settled, ErrorCode::MatchingAlreadySettled);
+        let funding = if proposal.is_finalized {
+            proposal.results[4]
+        } else {
+            0
+        };
+        let mut matches = [0u64; 4];
+        if funding > 0 {
+            for (option, amount) in matches.iter_mut().enumerate().take(pool.recipients.len()) {
+                // At most total_amount, since an option's funding is part
+                // of the total.
+                *amount = (pool.total_amount as u128 * proposal.results[option] as u128 / funding as u128) as u64;
+            }
+        }
+        let refund = pool.total_amount - matches.iter().sum::<u64>();
+
+        pool.settled = true;
+        pool.matches = matches;
+        proposal.matching_settled = true;
+
+        if refund > 0 {
+            let proposal_key = proposal.key();
+            let seeds: &[&[u8]] = &[b"matching_pool", proposal_key.as_ref(), &[pool.bump]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.matching_vault.to_account_info(),
+                        to: ctx.accounts.authority_token_account.to_account_info(),
+                        authority: pool.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                refund,
+            )?;
+        }
+
+        let clock = Clock::get()?;
+        emit!(MatchingSettledEvent {
+            proposal: proposal.key(),
+            proposal_id: proposal.id,
+            matches,
+            refunded: refund,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pay `option`'s match to its recipient's token account, and record
+    /// the payout in a receipt `[b"match_payout", proposal, option]` that
+    /// also keeps it from being paid twice.  Anyone may call it once the
+    /// pool is settled: the tokens only ever go to the named recipient.
+    pub fn pay_match(ctx: Context<PayMatch>, option: u8) -> Result<()> {
+        let pool = &mut ctx.accounts.matching_pool;
+        require!(pool.settled, ErrorCode::MatchingNotSettled);
+        let recipient = *pool.recipients.get(option as usize).ok_or(ErrorCode::InvalidChoice)?;
+        require_keys_eq!(
+            ctx.accounts.recipient_token_account.owner,
+            recipient,
+            ErrorCode::InvalidRecipient
+        );
+
+        let amount = pool.matches[option as usize];
+        pool.paid |= 1 << option;
+
+        let clock = Clock::get()?;
+        let receipt = &mut ctx.accounts.payout;
+        receipt.bump = ctx.bumps.payout;
+        receipt.proposal = pool.proposal;
+        receipt.option = option;
+        receipt.recipient = recipient;
+        receipt.amount = amount;
+        receipt.paid_at = clock.unix_timestamp;
+
+        if amount > 0 {
+            let seeds: &[&[u8]] = &[b"matching_pool", pool.proposal.as_ref(), &[pool.bump]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.matching_vault.to_account_info(),
+                        to: ctx.accounts.recipient_token_account.to_account_info(),
+                        authority: pool.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                amount,
+            )?;
+        }
+
+        emit!(MatchPaidEvent {
+            proposal: pool.proposal,
+            option,
+            recipient,
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Participation Receipts
+    // ================================================================
+
+    /// Create the proposal's "I voted" mint `[b"receipt_mint", proposal]`:
+    /// a Token-2022 mint with the NonTransferable extension, zero decimals
+    /// and itself as mint authority.  Anyone may call it once the proposal
+    /// is finalized.
+    pub fn create_receipt_mint(ctx: Context<CreateReceiptMint>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Finalized)?;
+
+        let mint = &ctx.accounts.receipt_mint;
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let proposal_key = proposal.key();
+        let seeds: &[&[u8]] = &[b"receipt_mint", proposal_key.as_ref(), &[ctx.bumps.receipt_mint]];
+
+        let space = spl_token_2022::extension::ExtensionType::try_calculate_account_len::<
+            spl_token_2022::state::Mint,
+        >(&[spl_token_2022::extension::ExtensionType::NonTransferable])?;
+        // Same as Anchor's `init`: tolerate lamports sent to the address
+        // beforehand, which would make `create_account` fail.
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let rent = Rent::get()?.minimum_balance(space);
+        if mint.lamports() == 0 {
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program,
+                    anchor_lang::system_program::CreateAccount {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: mint.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                rent,
+                space as u64,
+                token_program.key,
+            )?;
+        } else {
+            let top_up = rent.saturating_sub(mint.lamports());
+            if top_up > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        system_program.clone(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.payer.to_account_info(),
+                            to: mint.to_account_info(),
+                        },
+                    ),
+                    top_up,
+                )?;
+            }
+            anchor_lang::system_program::allocate(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    anchor_lang::system_program::Allocate {
+                        account_to_allocate: mint.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                space as u64,
+            )?;
+            anchor_lang::system_program::assign(
+                CpiContext::new_with_signer(
+                    system_program,
+                    anchor_lang::system_program::Assign {
+                        account_to_assign: mint.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                token_program.key,
+            )?;
+        }
+        token_interface::non_transferable_mint_initialize(CpiContext::new(
+            token_program.clone(),
+            token_interface::NonTransferableMintInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.to_account_info(),
+            },
+        ))?;
+        token_2022::initialize_mint2(
+            CpiContext::new(
+                token_program,
+                token_2022::InitializeMint2 {
+                    mint: mint.to_account_info(),
+                },
+            ),
+            0,
+            mint.key,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    /// Mint one non-transferable receipt to a counted voter of a finalized
+    /// proposal.  Issued from the VoterRecord alone, so it proves
+    /// participation and nothing about the ballot.
+    pub fn claim_receipt(ctx: Context<ClaimReceipt>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal_acc;
+        let record = &mut ctx.accounts.voter_record;
+        check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Finalized)?;
+        require!(record.counted, ErrorCode::VoteNotCounted);
+        require!(!record.receipt_claimed, ErrorCode::ReceiptAlreadyClaimed);
+        record.receipt_claimed = true;
+
+        let proposal_key = proposal.key();
+        let seeds: &[&[u8]] = &[b"receipt_mint", proposal_key.as_ref(), &[ctx.bumps.receipt_mint]];
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.voter_token_account.to_account_info(),
+                    authority: ctx.accounts.receipt_mint.to_account_info(),
+                },
+                &[seeds],
+            ),
+            1,
+        )?;
+
+        let clock = Clock::get()?;
+        emit!(ReceiptClaimedEvent {
+            proposal: proposal_key,
+            voter: record.voter,
+            mint: ctx.accounts.receipt_mint.key(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Close
+    // ================================================================
+
+    /// Close a finalized (or quorum-failed) proposal and its `TallyAccount`
+    /// and return their rent to the authority.  The payer funds a
+    /// `ClosedProposal` tombstone at the freed address, so `create_proposal`
+    /// can't reuse the id while records keyed by the old proposal survive.
+    pub fn close_proposal(ctx: Context<CloseProposal>, id: u32) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.proposal_acc.authority,
+            ErrorCode::InvalidAuthority
+        );
+
+        check_stage(
+            ctx.accounts.proposal_acc.state(Clock::get()?.unix_timestamp),
+            Stage::Settled,
+        )?;
+
+        // Claims no longer need the proposal once its reward pool is settled.
+        require!(
+            !ctx.accounts.proposal_acc.has_reward_pool || ctx.accounts.proposal_acc.rewards_settled,
+            ErrorCode::RewardsNotSettled
+        );
+        // Likewise payouts, once the matching pool is.
+        require!(
+            !ctx.accounts.proposal_acc.has_matching_pool || ctx.accounts.proposal_acc.matching_settled,
+            ErrorCode::MatchingNotSettled
+        );
+
+        // A late callback would find the account gone.
+        require!(
+            !ctx.accounts
+                .proposal_acc
+                .has_computations_in_flight(Clock::get()?.unix_timestamp, ctx.accounts.config.reveal_timeout),
+            ErrorCode::ComputationsInFlight
+        );
+
+        msg!(
+            "Closing proposal {} (id={})",
+            ctx.accounts.proposal_acc.title,
+            id
+        );
+
+        ctx.accounts.closed_proposal.set_inner(ClosedProposal {
+            bump: ctx.bumps.closed_proposal,
+            authority: ctx.accounts.authority.key(),
+            id,
+            closed_at: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ================================================================
+    // Views
+    // ================================================================
+
+    /// Read-only proposal status, intended to be called through transaction
+    /// simulation.  The `ProposalStatus` is delivered as return data so thin
+    /// clients don't need to deserialize the full `ProposalAccount`.
+    pub fn get_proposal_status(ctx: Context<GetProposalStatus>) -> Result<ProposalStatus> {
+        let clock = Clock::get()?;
+        Ok(ctx.accounts.proposal_acc.status(clock.unix_timestamp))
+    }
+}
+
+/// Shared body of `create_proposal` and `create_poll`: charge the creator,
+/// initialize the proposal account and queue `init_tallies`.
+#[allow(clippy::too_many_arguments)]
+fn open_proposal(
+    ctx: Context<CreateProposal>,
+    kind: ProposalKind,
+    computation_offset: u64,
+    id: u32,
+    title: String,
+    options: Vec<String>,
+    num_options: u8,
+    deadline: i64,
+    registration_deadline: i64,
+    voice_credits: u64,
+    quorum: u32,
+    quorum_kind: QuorumKind,
+    electorate: u64,
+    gate_threshold: u64,
+    ballot: BallotKind,
+    nonce: u128,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    // Polls are non-binding, so there is nothing for the council to slash.
+    let deposit = match kind {
+        ProposalKind::Governance => config.proposal_deposit,
+        ProposalKind::Poll => 0,
+    };
+    // The denominator is fixed here so reveal-time checks cannot drift
+    // with later registrations or mints; a registrar's electorate is
+    // counted by `issue_credential` until the first ballot fixes it.
+    let (electorate, quorum_mint) = match quorum_kind {
+        QuorumKind::Voters | QuorumKind::RegistrarBps => (0, Pubkey::default()),
+        QuorumKind::RegisteredBps => {
+            require!(electorate > 0, ErrorCode::InvalidQuorum);
+            (electorate, Pubkey::default())
+        }
+        QuorumKind::SupplyBps => {
+            let mint = ctx
+                .accounts
+                .quorum_mint
+                .as_ref()
+                .ok_or(ErrorCode::QuorumMintRequired)?;
+            (mint.supply, mint.key())
+        }
+    };
+    require!(
+        quorum_kind == QuorumKind::Voters || quorum <= 10_000,
+        ErrorCode::InvalidQuorum
+    );
+    let title = sanitize_label(&title, MAX_TITLE_LEN).ok_or(ErrorCode::InvalidTitle)?;
+    let options = options
+        .iter()
+        .map(|option| sanitize_label(option, MAX_OPTION_LEN).ok_or(ErrorCode::InvalidOptionLabel))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    require!(
+        options
+            .iter()
+            .enumerate()
+            .all(|(i, option)| !options[..i].contains(option)),
+        ErrorCode::DuplicateOption
+    );
+    if let BallotKind::YesNoAbstain { veto_bps } = ballot {
+        require!(
+            options.len() == 3 && num_options == 3 && registration_deadline == 0 && veto_bps <= 10_000,
+            ErrorCode::InvalidVetoBallot
+        );
+    }
+    // Every ballot that spends credits is checked against them in MPC.
+    if matches!(
+        ballot,
+        BallotKind::Quadratic | BallotKind::TimeWeighted { .. } | BallotKind::Pairwise { .. }
+    ) {
+        require!(voice_credits > 0, ErrorCode::InvalidVoiceCredits);
+    }
+    if let BallotKind::TimeWeighted { start_pct, end_pct } = ballot {
+        require!(
+            (1..=MAX_TIME_WEIGHT_PCT).contains(&start_pct) && (1..=MAX_TIME_WEIGHT_PCT).contains(&end_pct),
+            ErrorCode::InvalidTimeWeight
+        );
+    }
+    if let BallotKind::Jury { criteria, max_score } = ballot {
+        require!(
+            (1..=MAX_CRITERIA).contains(&criteria) && max_score > 0,
+            ErrorCode::InvalidJuryBallot
+        );
+    }
+    if let BallotKind::Approval { max_choices } = ballot {
+        require!((1..=4).contains(&max_choices), ErrorCode::InvalidApprovalBallot);
+    }
+    if let BallotKind::BudgetBox { budget, costs } = ballot {
+        require!(
+            budget > 0
+                && registration_deadline == 0
+                && costs.iter().enumerate().all(|(i, c)| (*c > 0) == (i < num_options as usize)),
+            ErrorCode::InvalidBudgetBox
+        );
+    }
+    if registration_deadline != 0 {
+        require!(
+            options.is_empty() && num_options == 0,
+            ErrorCode::OptionsSetByCandidates
+        );
+        require!(
+            Clock::get()?.unix_timestamp < registration_deadline && registration_deadline < deadline,
+            ErrorCode::InvalidRegistrationDeadline
+        );
+    }
+    // A token gate is fixed here, so holders know the bar before the
+    // first ballot.  Under `SupplyBps` the same token account weighs the
+    // turnout, so the mints must match.
+    let (gating, gate_mint) = match ctx.accounts.gate_mint.as_ref() {
+        Some(mint) => {
+            require!(
+                gate_threshold > 0
+                    && quorum_kind != QuorumKind::RegistrarBps
+                    && (quorum_kind != QuorumKind::SupplyBps || mint.key() == quorum_mint),
+                ErrorCode::InvalidTokenGate
+            );
+            (GatingMode::Token, mint.key())
+        }
+        None => {
+            require!(gate_threshold == 0, ErrorCode::InvalidTokenGate);
+            (GatingMode::Open, Pubkey::default())
+        }
+    };
+    require!(!config.paused, ErrorCode::ProtocolPaused);
+    require!(config.allows(gating), ErrorCode::GatingModeNotAllowed);
+
+    let creator = &mut ctx.accounts.creator_record;
+    creator.bump = ctx.bumps.creator_record;
+    creator.authority = ctx.accounts.authority.key();
+    require!(
+        !config.permissioned_creation || creator.allowed,
+        ErrorCode::CreatorNotAllowed
+    );
+    creator.record_proposal(Clock::get()?.epoch, config)?;
+
+    let proposal = &mut ctx.accounts.proposal_acc;
+    proposal.bump = ctx.bumps.proposal_acc;
+    proposal.id = id;
+    proposal.authority = ctx.accounts.authority.key();
+    proposal.kind = kind;
+    proposal.title = title;
+    proposal.options = options;
+    proposal.num_options = num_options;
+    proposal.deadline = deadline;
+    proposal.registration_deadline = registration_deadline;
+    proposal.voice_credits = voice_credits;
+    proposal.ballot = ballot;
+    proposal.quorum = quorum;
+    proposal.quorum_kind = quorum_kind;
+    proposal.electorate = electorate;
+    proposal.quorum_mint = quorum_mint;
+    proposal.snapshot_slot = Clock::get()?.slot;
+    proposal.created_at = Clock::get()?.unix_timestamp;
+    proposal.turnout_weight = 0;
+    proposal.is_finalized = false;
+    proposal.voter_count = 0;
+    proposal.counted_voters = 0;
+    proposal.quorum_failed = false;
+    proposal.reveal_pending_since = 0;
+    proposal.results = [0; 5];
+    proposal.winner = 0;
+    proposal.outcome = None;
+    proposal.funded = None;
+    proposal.gating = gating;
+    proposal.gate_mint = gate_mint;
+    proposal.gate_threshold = gate_threshold;
+    proposal.result_attestation = ResultAttestation::default();
+    proposal.has_reward_pool = false;
+    proposal.rewards_settled = false;
+    proposal.has_matching_pool = false;
+    proposal.matching_settled = false;
+    proposal.deposit = deposit;
+    proposal.sponsor_balance = 0;
+    proposal.sponsored_votes = 0;
+
+    // Fresh accounts are zeroed, so the counters start empty until
+    // `init_tallies_callback` stores their encryption.
+    let mut tally = ctx.accounts.tally_acc.load_init()?;
+    tally.set_nonce(nonce);
+    tally.proposal = proposal.key();
+    tally.bump = ctx.bumps.tally_acc;
+    drop(tally);
+
+    let stats = &mut ctx.accounts.global_stats;
+    stats.bump = ctx.bumps.global_stats;
+    stats.total_proposals = stats.total_proposals.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+    collect_fee(
+        &ctx.accounts.payer,
+        &mut ctx.accounts.fee_vault,
+        &ctx.accounts.system_program,
+        ctx.accounts.config.proposal_fee,
+    )?;
+    // The bond rides on the proposal account itself: `close_proposal`
+    // returns it with the rent unless the council slashed it.
+    if deposit > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.proposal_acc.to_account_info(),
+                },
+            ),
+            deposit,
+        )?;
+    }
+
+    let clock = Clock::get()?;
+    emit!(ProposalCreatedEvent {
+        proposal: ctx.accounts.proposal_acc.key(),
+        proposal_id: id,
+        authority: ctx.accounts.proposal_acc.authority,
+        kind,
+        title: ctx.accounts.proposal_acc.title.clone(),
+        options: ctx.accounts.proposal_acc.options.clone(),
+        deadline,
+        voice_credits,
+        ballot,
+        quorum,
+        quorum_kind,
+        quorum_target: ctx.accounts.proposal_acc.quorum_target(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    let args = ArgBuilder::new().plaintext_u128(nonce).build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![InitTalliesCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    Ok(())
+}
+
+/// Checks and bookkeeping shared by `cast_vote` and `cast_likert` before
+/// the ballot is queued: the voting window, the voter's credential, the new
+/// voter record and turnout.
+#[allow(clippy::too_many_arguments)]
+fn admit_ballot(
+    proposal: &mut Account<ProposalAccount>,
+    voter_record: &mut Account<VoterRecord>,
+    voter_record_bump: u8,
+    voter: Pubkey,
+    voter_token_account: Option<&InterfaceAccount<token_interface::TokenAccount>>,
+    credential: Option<&Account<BallotCredential>>,
+    allowlist: Option<&UncheckedAccount>,
+    nft_mint: Option<Pubkey>,
+    global_stats: &mut GlobalStats,
+    config: &Config,
+) -> Result<()> {
+    check_ballot_open(proposal, config)?;
+    require!(proposal.round_slot.is_none(), ErrorCode::RoundRecordRequired);
+    require!(!proposal.dataless_records, ErrorCode::DatalessRecordRequired);
+
+    if matches!(proposal.gating, GatingMode::Credential | GatingMode::History) {
+        let credential = credential.ok_or(ErrorCode::CredentialRequired)?;
+        require!(
+            credential.proposal == proposal.key() && credential.holder == voter,
+            ErrorCode::InvalidCredential
+        );
+        require!(!credential.revoked, ErrorCode::CredentialRevoked);
+    }
+    if proposal.gating == GatingMode::Allowlist {
+        let info = allowlist.ok_or(ErrorCode::AllowlistRequired)?;
+        // Its seeds pin the address, so only `open_allowlist` can have
+        // written it.
+        let allowlist = Allowlist::try_deserialize(&mut &info.try_borrow_data()?[..])
+            .map_err(|_| error!(ErrorCode::AllowlistRequired))?;
+        require!(allowlist.voters.contains(&voter), ErrorCode::NotOnAllowlist);
+    }
+    if proposal.gating == GatingMode::Token {
+        let holding = voter_token_account.ok_or(ErrorCode::GateTokenAccountRequired)?;
+        require!(
+            holding.mint == proposal.gate_mint && holding.owner == voter,
+            ErrorCode::GateTokenAccountRequired
+        );
+        require!(holding.amount >= proposal.gate_threshold, ErrorCode::GateBalanceTooLow);
+    }
+    // `admit_nft` checked the NFT; only `cast_vote` passes one.
+    if proposal.gating == GatingMode::Collection {
+        require!(nft_mint.is_some(), ErrorCode::NftRequired);
+    }
+
+    // VoterRecord init fails if PDA already exists = double vote prevention
+    voter_record.bump = voter_record_bump;
+    voter_record.proposal = proposal.key();
+    voter_record.voter = voter;
+    voter_record.counted = false;
+    voter_record.reward_claimed = false;
+    voter_record.receipt_claimed = false;
+    voter_record.tally_generation = proposal.tally_generation;
+    voter_record.nft_mint = nft_mint;
+
+    record_turnout(proposal, global_stats)?;
+    weigh_turnout(proposal, voter, voter_token_account)
+}
+
+/// On a collection-gated proposal, check that `payer` holds an NFT that is
+/// a verified member of the collection — `nft_token_account` and its
+/// Metaplex `nft_metadata` — and claim the NFT's `NftBallot`, so passing
+/// it to another wallet doesn't buy a second ballot.  Returns the NFT's
+/// mint, which `admit_ballot` records; `None` on other proposals.
+fn admit_nft<'info>(
+    proposal: &Account<'info, ProposalAccount>,
+    payer: &Signer<'info>,
+    nft_token_account: Option<&InterfaceAccount<'info, token_interface::TokenAccount>>,
+    nft_metadata: Option<&UncheckedAccount<'info>>,
+    nft_ballot: Option<&UncheckedAccount<'info>>,
+    system_program: &Program<'info, System>,
+) -> Result<Option<Pubkey>> {
+    if proposal.gating != GatingMode::Collection {
+        return Ok(None);
+    }
+    let holding = nft_token_account.ok_or(ErrorCode::NftRequired)?;
+    require!(
+        holding.owner == payer.key() && holding.amount > 0,
+        ErrorCode::NftRequired
+    );
+    let mint = holding.mint;
+
+    let metadata = nft_metadata.ok_or(ErrorCode::NftRequired)?;
+    let (address, _) = Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    );
+    require!(
+        metadata.key() == address && *metadata.owner == TOKEN_METADATA_PROGRAM_ID,
+        ErrorCode::InvalidNftMetadata
+    );
+    let fields = NftMetadata::deserialize(&mut &metadata.try_borrow_data()?[..])
+        .map_err(|_| error!(ErrorCode::InvalidNftMetadata))?;
+    require!(
+        fields.key == NftMetadata::METADATA_V1 && fields.mint == mint,
+        ErrorCode::InvalidNftMetadata
+    );
+    require!(
+        matches!(fields.collection, Some(NftCollection { verified: true, key }) if key == proposal.gate_mint),
+        ErrorCode::NotInCollection
+    );
+
+    let info = nft_ballot.ok_or(ErrorCode::NftRequired)?;
+    let proposal_key = proposal.key();
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"nft_ballot", proposal_key.as_ref(), mint.as_ref()], &crate::ID);
+    require_keys_eq!(info.key(), address, ErrorCode::NftRequired);
+    require!(info.lamports() == 0, ErrorCode::NftAlreadyVoted);
+
+    let space = 8 + NftBallot::INIT_SPACE;
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.to_account_info(),
+                to: info.to_account_info(),
+            },
+            &[&[b"nft_ballot", proposal_key.as_ref(), mint.as_ref(), &[bump]]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+    let ballot = NftBallot {
+        bump,
+        proposal: proposal_key,
+        mint,
+        voter: payer.key(),
+    };
+    ballot.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    Ok(Some(mint))
+}
+
+/// The head of a Metaplex `Metadata` account, Borsh-encoded, as far as the
+/// `collection` field `admit_nft` reads.
+#[derive(AnchorDeserialize)]
+struct NftMetadata {
+    key: u8,
+    _update_authority: Pubkey,
+    mint: Pubkey,
+    _name: String,
+    _symbol: String,
+    _uri: String,
+    _seller_fee_basis_points: u16,
+    _creators: Option<Vec<NftCreator>>,
+    _primary_sale_happened: bool,
+    _is_mutable: bool,
+    _edition_nonce: Option<u8>,
+    _token_standard: Option<u8>,
+    collection: Option<NftCollection>,
+}
+
+impl NftMetadata {
+    /// Metaplex's `Key::MetadataV1`.
+    const METADATA_V1: u8 = 4;
+}
+
+#[derive(AnchorDeserialize)]
+struct NftCreator {
+    _address: Pubkey,
+    _verified: bool,
+    _share: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct NftCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Refuse a quadratic ballot the proposal needs more for than `cast_vote`
+/// takes without extra accounts: square-root or funded credits,
+/// burn-to-vote, an eligibility oracle, token weights, a commit phase, a
+/// spend histogram, a credit bank or encrypted budgets, or an open mix
+/// window at `now`.  What `cast_votes_batch` and `cast_vote_dataless` cast.
+fn check_plain_ballot(proposal: &ProposalAccount, now: i64) -> Result<()> {
+    require!(
+        matches!(proposal.ballot, BallotKind::Quadratic | BallotKind::TimeWeighted { .. }),
+        ErrorCode::WrongBallotKind
+    );
+    require!(!proposal.spend_histogram, ErrorCode::HistogramBallotRequired);
+    require!(!proposal.credit_bank, ErrorCode::BankedBallotRequired);
+    require!(!proposal.encrypted_budgets, ErrorCode::BudgetedBallotRequired);
+    require!(proposal.credit_mint.is_none(), ErrorCode::SqrtCreditsUnsupported);
+    require!(!proposal.funded_credits, ErrorCode::FundedCreditsUnsupported);
+    require!(proposal.burn_mint.is_none(), ErrorCode::BurnToVoteUnsupported);
+    require!(
+        proposal.eligibility_oracle.is_none(),
+        ErrorCode::EligibilityOracleUnsupported
+    );
+    require!(proposal.weight_mint.is_none(), ErrorCode::TokenWeightsUnsupported);
+    require!(proposal.commit_deadline == 0, ErrorCode::CommitPhaseUnsupported);
+    require!(!proposal.in_mix_window(now), ErrorCode::MixWindowUnsupported);
+    Ok(())
+}
+
+/// Create `payer`'s `VoterRecord` on `proposal` at `info`, as `init`
+/// would for `cast_vote`, for a ballot whose record comes in as a
+/// remaining account.  A record already there fails with `AlreadyVoted`.
+fn init_voter_record<'info>(
+    payer: &Signer<'info>,
+    info: &'info AccountInfo<'info>,
+    proposal: &Pubkey,
+    system_program: &Program<'info, System>,
+) -> Result<(Account<'info, VoterRecord>, u8)> {
+    let voter = payer.key();
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"voter", proposal.as_ref(), voter.as_ref()], &crate::ID);
+    require_keys_eq!(info.key(), address, ErrorCode::InvalidVoteBatch);
+    require!(info.lamports() == 0, ErrorCode::AlreadyVoted);
+
+    let space = 8 + VoterRecord::INIT_SPACE;
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.to_account_info(),
+                to: info.clone(),
+            },
+            &[&[b"voter", proposal.as_ref(), voter.as_ref(), &[bump]]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+    Ok((Account::try_from_unchecked(info)?, bump))
+}
+
+/// Under a supply quorum, add the voter's holding of the quorum mint to
+/// the turnout.
+fn weigh_turnout(
+    proposal: &mut ProposalAccount,
+    voter: Pubkey,
+    voter_token_account: Option<&InterfaceAccount<token_interface::TokenAccount>>,
+) -> Result<()> {
+    if proposal.quorum_kind == QuorumKind::SupplyBps {
+        let holding = voter_token_account.ok_or(ErrorCode::QuorumTokenAccountRequired)?;
+        require!(
+            holding.mint == proposal.quorum_mint && holding.owner == voter,
+            ErrorCode::QuorumTokenAccountRequired
+        );
+        proposal.turnout_weight = proposal
+            .turnout_weight
+            .checked_add(holding.amount)
+            .ok_or(ErrorCode::AmountOverflow)?;
+    }
+
+    Ok(())
+}
+
+/// Issue the `BallotCredential` that admits `voter` to a history-gated
+/// proposal.
+fn admit_history(
+    proposal: &ProposalAccount,
+    gate: &mut HistoryGate,
+    credential: &mut BallotCredential,
+    bump: u8,
+    voter: Pubkey,
+    prior_ballots: u32,
+    attested: bool,
+) -> Result<()> {
+    credential.bump = bump;
+    credential.proposal = gate.proposal;
+    credential.serial = voter.to_bytes();
+    credential.holder = voter;
+    gate.admitted = gate.admitted.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+    emit!(HistoryAdmittedEvent {
+        proposal: gate.proposal,
+        proposal_id: proposal.id,
+        voter,
+        prior_ballots,
+        attested,
+    });
+    Ok(())
+}
+
+/// Counted ballots `voter` cast on proposals other than `proposal`, from
+/// their `VoterRecord`s.  Only this program writes a `VoterRecord`, so one
+/// it owns is genuine; a record passed twice counts once.
+fn count_prior_ballots(proposal: Pubkey, voter: Pubkey, records: &[AccountInfo]) -> Result<u32> {
+    let mut proposals = Vec::with_capacity(records.len());
+    for info in records {
+        require!(info.owner == &crate::ID, ErrorCode::InvalidHistoryRecord);
+        let record = VoterRecord::try_deserialize(&mut &info.try_borrow_data()?[..])
+            .map_err(|_| error!(ErrorCode::InvalidHistoryRecord))?;
+        require!(
+            record.voter == voter && record.proposal != proposal && record.counted,
+            ErrorCode::InvalidHistoryRecord
+        );
+        proposals.push(record.proposal);
+    }
+    proposals.sort_unstable();
+    proposals.dedup();
+    Ok(proposals.len() as u32)
+}
+
+/// Whether `proposal` takes ballots right now.
+fn check_ballot_open(proposal: &ProposalAccount, config: &Config) -> Result<()> {
+    require!(!config.paused, ErrorCode::ProtocolPaused);
+
+    let now = Clock::get()?.unix_timestamp;
+    check_stage(proposal.state(now), Stage::Voting)?;
+    require!(now >= proposal.commit_deadline, ErrorCode::CommitPhaseOpen);
+    // Only the registrar's credentials make up the electorate.
+    require!(
+        proposal.quorum_kind != QuorumKind::RegistrarBps || proposal.gating == GatingMode::Credential,
+        ErrorCode::CredentialRequired
+    );
+    require!(
+        !proposal.awaiting_parent_outcome(),
+        ErrorCode::ParentOutcomeNotMet
+    );
+    require!(!proposal.awaiting_dependency, ErrorCode::DependencyNotMet);
+
+    Ok(())
+}
+
+/// Add an admitted ballot to the turnout.  The first one records
+/// `first_vote_slot`, freezing the proposal's options, deadline and budget.
+fn record_turnout(proposal: &mut ProposalAccount, global_stats: &mut GlobalStats) -> Result<()> {
+    if !proposal.is_frozen() {
+        proposal.first_vote_slot = Clock::get()?.slot;
+    }
+    require!(proposal.voter_count < MAX_VOTERS, ErrorCode::VoterLimitReached);
+    proposal.voter_count += 1;
+    global_stats.total_ballots = global_stats
+        .total_ballots
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+
+    Ok(())
+}
+
+/// Once `open_commit_phase` ran, `voter`'s ballot must hash to the
+/// commitment they posted during the commit phase.
+fn check_ballot_commitment(
+    proposal: &Account<ProposalAccount>,
+    voter: &Pubkey,
+    ballot_commitment: Option<&UncheckedAccount>,
+    ciphertexts: &[[u8; 32]; 4],
+    encryption_pubkey: &[u8; 32],
+    nonce: u128,
+) -> Result<()> {
+    if proposal.commit_deadline == 0 {
+        return Ok(());
+    }
+    let info = ballot_commitment.ok_or(ErrorCode::BallotNotCommitted)?;
+    // Its seeds pin the address, so only `commit_ballot` can have
+    // written it.
+    let record = BallotCommitment::try_deserialize(&mut &info.try_borrow_data()?[..])
+        .map_err(|_| error!(ErrorCode::BallotNotCommitted))?;
+    require!(
+        record.commitment == ballot_hash(&proposal.key(), voter, ciphertexts, encryption_pubkey, nonce),
+        ErrorCode::BallotNotCommitted
+    );
+    Ok(())
+}
+
+/// `floor(sqrt(n))`, by Newton's method from `ceil(n / 2)`.
+fn integer_sqrt(n: u64) -> u64 {
+    let mut root = n;
+    let mut next = n / 2 + n % 2;
+    while next < root {
+        root = next;
+        next = (root + n / root) / 2;
+    }
+    root
+}
+
+/// `text` with surrounding whitespace trimmed, if it is fit to show as a
+/// title or option label: not empty, at most `max_len` bytes, and free of
+/// control characters and of characters that render as nothing or reorder
+/// the text around them, which would let two labels look alike.  Borsh
+/// already refuses a string that isn't valid UTF-8.
+pub fn sanitize_label(text: &str, max_len: usize) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty() && text.len() <= max_len && !text.chars().any(is_hidden_char)).then(|| text.to_string())
+}
+
+/// `tag` looks like a BCP 47 language tag: subtags of ASCII letters and
+/// digits joined by single hyphens, starting with a 2 or 3 letter
+/// language, at most `MAX_LOCALE_LEN` bytes in all.
+fn is_locale(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let language = subtags.next().unwrap_or_default();
+    tag.len() <= MAX_LOCALE_LEN
+        && (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| !subtag.is_empty() && subtag.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Control characters; zero-width spaces, joiners and non-joiners; bidi
+/// marks, embeddings, overrides and isolates; the word joiner, invisible
+/// operators and other default-ignorable format characters; fillers; and
+/// tag characters.
+fn is_hidden_char(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{00AD}'
+                | '\u{034F}'
+                | '\u{061C}'
+                | '\u{115F}'
+                | '\u{1160}'
+                | '\u{17B4}'
+                | '\u{17B5}'
+                | '\u{180B}'..='\u{180F}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{206F}'
+                | '\u{3164}'
+                | '\u{FEFF}'
+                | '\u{FFA0}'
+                | '\u{E0000}'..='\u{E007F}'
+        )
+}
+
+/// Burn one whole `burn_mint` token from the payer's account, the price of
+/// a ballot once `open_burn_to_vote` ran.
+fn burn_membership_token<'info>(
+    proposal: &ProposalAccount,
+    payer: &Signer<'info>,
+    token_account: Option<&InterfaceAccount<'info, token_interface::TokenAccount>>,
+    mint: Option<&InterfaceAccount<'info, token_interface::Mint>>,
+    token_program: Option<&Interface<'info, token_interface::TokenInterface>>,
+) -> Result<()> {
+    let Some(burn_mint) = proposal.burn_mint else {
+        return Ok(());
+    };
+    let (Some(token_account), Some(mint), Some(token_program)) = (token_account, mint, token_program) else {
+        return err!(ErrorCode::MembershipTokenRequired);
+    };
+    require!(
+        mint.key() == burn_mint && token_account.mint == burn_mint,
+        ErrorCode::MembershipTokenRequired
+    );
+
+    token_interface::burn_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            token_interface::BurnChecked {
+                mint: mint.to_account_info(),
+                from: token_account.to_account_info(),
+                authority: payer.to_account_info(),
+            },
+        ),
+        10u64.pow(mint.decimals.into()),
+        mint.decimals,
+    )
+}
+
+/// Ask the proposal's eligibility oracle, if it has one, what weight the
+/// payer's ballot carries, up to `MAX_ORACLE_WEIGHT`; 1 without an oracle.  `remaining` is forwarded
+/// read-only, so the oracle sees whatever it needs but can't write or sign
+/// on the voter's behalf.
+fn ask_eligibility_oracle<'info>(
+    proposal: &Account<'info, ProposalAccount>,
+    payer: &Signer<'info>,
+    oracle: Option<&UncheckedAccount<'info>>,
+    remaining: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let Some(oracle_id) = proposal.eligibility_oracle else {
+        return Ok(1);
+    };
+    let oracle = oracle
+        .filter(|oracle| oracle.key() == oracle_id)
+        .ok_or(ErrorCode::EligibilityOracleRequired)?;
+
+    let mut data = CAN_VOTE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(payer.key().as_ref());
+    data.extend_from_slice(proposal.key().as_ref());
+    let mut accounts = vec![
+        AccountMeta::new_readonly(payer.key(), false),
+        AccountMeta::new_readonly(proposal.key(), false),
+    ];
+    accounts.extend(
+        remaining
+            .iter()
+            .map(|info| AccountMeta::new_readonly(info.key(), false)),
+    );
+    let mut infos = vec![payer.to_account_info(), proposal.to_account_info()];
+    infos.extend(remaining.iter().cloned());
+    infos.push(oracle.to_account_info());
+    anchor_lang::solana_program::program::invoke(
+        &anchor_lang::solana_program::instruction::Instruction {
+            program_id: oracle_id,
+            accounts,
+            data,
+        },
+        &infos,
+    )?;
+
+    let (program, answer) =
+        anchor_lang::solana_program::program::get_return_data().ok_or(ErrorCode::InvalidOracleResponse)?;
+    require!(program == oracle_id, ErrorCode::InvalidOracleResponse);
+    let weight = u64::from_le_bytes(
+        answer
+            .try_into()
+            .map_err(|_| error!(ErrorCode::InvalidOracleResponse))?,
+    );
+    require!(weight > 0, ErrorCode::NotEligible);
+    require!(weight <= MAX_ORACLE_WEIGHT, ErrorCode::OracleWeightTooHigh);
+    Ok(weight)
+}
+
+/// The payer's whole tokens of the proposal's `weight_mint`, if it has
+/// one, up to its `max_token_weight`, as the stake their ballot carries;
+/// 1 without one.
+fn weigh_by_tokens(
+    proposal: &ProposalAccount,
+    payer: &Pubkey,
+    holding: Option<&InterfaceAccount<token_interface::TokenAccount>>,
+) -> Result<u64> {
+    let Some(mint) = proposal.weight_mint else {
+        return Ok(1);
+    };
+    let holding = holding
+        .filter(|holding| holding.mint == mint && holding.owner == *payer)
+        .ok_or(ErrorCode::InvalidWeightAccount)?;
+    let tokens = holding.amount / 10u64.pow(proposal.weight_decimals.into());
+    require!(tokens > 0, ErrorCode::NoWeightTokens);
+    Ok(tokens.min(proposal.max_token_weight))
+}
+
+/// Settle a proposal whose deadline passed below quorum: its results can
+/// never be revealed.
+fn fail_quorum(proposal: &mut Account<ProposalAccount>, timestamp: i64) -> Result<()> {
+    try_transition(proposal.state(timestamp), ProposalState::QuorumFailed)?;
+    proposal.quorum_failed = true;
+
+    emit!(QuorumFailedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        voter_count: proposal.voter_count,
+        quorum: proposal.quorum,
+        turnout: proposal.turnout(),
+        quorum_target: proposal.quorum_target(),
+        timestamp,
+    });
+    Ok(())
+}
+
+/// Message a credential's `serial` is signed as: domain-separated and bound
+/// to one proposal, so a credential can't be replayed on another.
+pub fn credential_message(proposal: &Pubkey, serial: &[u8; 32]) -> Vec<u8> {
+    [CREDENTIAL_DOMAIN, proposal.as_ref(), serial].concat()
+}
+
+/// Computation offset of a proposal's `attempt`-th `auto_reveal`, so an
+/// automation service knows its accounts before the deadline.
+pub fn auto_reveal_offset(proposal: &Pubkey, attempt: u32) -> u64 {
+    let hash = hashv(&[AUTO_REVEAL_DOMAIN, proposal.as_ref(), &attempt.to_le_bytes()]).to_bytes();
+    u64::from_le_bytes(hash[..8].try_into().unwrap())
+}
+
+/// What `commit_tally` checkpoints: a proposal's encrypted tally, its nonce
+/// and the ballots applied to it.
+pub fn tally_leaf(proposal: &Pubkey, vote_state: &[[u8; 32]; 5], nonce: u128, applied_ballots: u32) -> [u8; 32] {
+    hashv(&[
+        COMMITMENT_DOMAIN,
+        proposal.as_ref(),
+        &vote_state.concat(),
+        &nonce.to_le_bytes(),
+        &applied_ballots.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// A `Commitment` root after checkpointing `leaf`.  The first checkpoint
+/// folds into the all-zero root.
+pub fn fold_commitment(root: &[u8; 32], leaf: &[u8; 32]) -> [u8; 32] {
+    hashv(&[COMMITMENT_DOMAIN, root, leaf]).to_bytes()
+}
+
+/// What a voter commits to with `commit_ballot`: their encrypted
+/// `cast_vote` ballot, bound to the proposal and the voter.
+pub fn ballot_hash(
+    proposal: &Pubkey,
+    voter: &Pubkey,
+    ciphertexts: &[[u8; 32]; 4],
+    encryption_pubkey: &[u8; 32],
+    nonce: u128,
+) -> [u8; 32] {
+    hashv(&[
+        BALLOT_COMMITMENT_DOMAIN,
+        proposal.as_ref(),
+        voter.as_ref(),
+        &ciphertexts.concat(),
+        encryption_pubkey,
+        &nonce.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// What `cast_vote_anonymous` records for a credential `serial`: a hash
+/// bound to the proposal, low bit set so it never reads as an empty slot.
+pub fn nullifier(proposal: &Pubkey, serial: &[u8; 32]) -> [u8; 16] {
+    let hash = hashv(&[NULLIFIER_DOMAIN, proposal.as_ref(), serial]).to_bytes();
+    let mut nullifier = [0u8; 16];
+    nullifier.copy_from_slice(&hash[..16]);
+    nullifier[15] |= 1;
+    nullifier
+}
+
+/// Record `nullifier` in the set's table.  Fails if it is already there or
+/// the set has its `max_voters`.
+fn insert_nullifier(set: &mut Account<NullifierSet>, nullifier: &[u8; 16]) -> Result<()> {
+    require!(set.count < set.max_voters, ErrorCode::NullifierSetFull);
+    let info = set.to_account_info();
+    require!(info.data_len() == set.full_len(), ErrorCode::NullifierSetNotAllocated);
+    {
+        let mut data = info.try_borrow_mut_data()?;
+        let table = &mut data[NullifierSet::HEADER_LEN..];
+        let index = nullifier_slot(table, set.slots, nullifier);
+        let slot = &mut table[index * 16..(index + 1) * 16];
+        require!(slot != nullifier, ErrorCode::NullifierSpent);
+        slot.copy_from_slice(nullifier);
+    }
+    set.count = set.count.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+    Ok(())
+}
+
+/// Index of `nullifier`'s slot in an open-addressed `table`, or of the
+/// empty slot it goes in: linear probing from the slot its first 8 bytes
+/// pick.  The table is at most half full, so probes stay short and an
+/// empty slot is always found.
+fn nullifier_slot(table: &[u8], slots: u32, nullifier: &[u8; 16]) -> usize {
+    let slots = slots as usize;
+    let mut index = (u64::from_le_bytes(nullifier[..8].try_into().unwrap()) % slots as u64) as usize;
+    loop {
+        let slot = &table[index * 16..(index + 1) * 16];
+        if slot == nullifier || slot == [0; 16] {
+            return index;
+        }
+        index = (index + 1) % slots;
+    }
+}
+
+/// Unblinded credential check: `signature` is the registrar's BLS
+/// signature on `credential_message(proposal, serial)`.
+fn credential_signature_valid(
+    registrar_key: &[u8; 128],
+    proposal: &Pubkey,
+    serial: &[u8; 32],
+    signature: &[u8; 64],
+) -> bool {
+    Sha256Normalized::try_hash_to_curve(credential_message(proposal, serial))
+        .is_ok_and(|point| bls_pairing_holds(&point.0, registrar_key, signature))
+}
+
+/// BLS pairing check `e(message, key) == e(signature, G2)` on alt_bn128
+/// for an uncompressed G1 `message` point, so a blinded point verifies
+/// as well as a hashed one.
+fn bls_pairing_holds(message: &[u8; 64], key: &[u8; 128], signature: &[u8; 64]) -> bool {
+    let mut input = [0u8; 384];
+    input[..64].copy_from_slice(message);
+    input[64..192].copy_from_slice(key);
+    input[192..256].copy_from_slice(signature);
+    input[256..].copy_from_slice(&G2_MINUS_ONE);
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    alt_bn128_pairing(&input).is_ok_and(|result| result == one)
+}
+
+/// Reimburse whatever Arcium charged the voter out of the proposal's
+/// sponsorship, as far as it goes.
+fn reimburse_arcium_fee(
+    proposal: &mut Account<ProposalAccount>,
+    voter: &Signer,
+    lamports_before: u64,
+) -> Result<()> {
+    let arcium_fee = lamports_before.saturating_sub(voter.lamports());
+    let sponsored = arcium_fee.min(proposal.sponsor_balance);
+    if sponsored > 0 {
+        proposal.sponsor_balance -= sponsored;
+        proposal.sponsored_votes = proposal
+            .sponsored_votes
+            .checked_add(1)
+            .ok_or(ErrorCode::CounterOverflow)?;
+        proposal.sub_lamports(sponsored)?;
+        voter.add_lamports(sponsored)?;
+    }
+    Ok(())
+}
+
+/// Store a callback's tallies and the nonce they were encrypted under.
+fn store_tally(tally: &AccountLoader<TallyAccount>, vote_state: [[u8; 32]; 5], nonce: u128) -> Result<()> {
+    let mut tally = tally.load_mut()?;
+    tally.vote_state = vote_state;
+    tally.set_nonce(nonce);
+    Ok(())
+}
+
+/// Store the tallies a ballot's callback returned and mark it counted.
+fn count_ballot(
+    proposal: &mut Account<ProposalAccount>,
+    tally: &AccountLoader<TallyAccount>,
+    voter_record: &mut Account<VoterRecord>,
+    vote_state: [[u8; 32]; 5],
+    nonce: u128,
+) -> Result<()> {
+    let key = proposal.key();
+    apply_ballot(key, proposal, &mut *tally.load_mut()?, voter_record, vote_state, nonce)
+}
+
+/// `count_ballot` on accounts read by hand, as a callback reads the ones
+/// it takes as remaining accounts.
+fn apply_ballot(
+    proposal_key: Pubkey,
+    proposal: &mut ProposalAccount,
+    tally: &mut TallyAccount,
+    voter_record: &mut VoterRecord,
+    vote_state: [[u8; 32]; 5],
+    nonce: u128,
+) -> Result<()> {
+    tally.vote_state = vote_state;
+    tally.set_nonce(nonce);
+    proposal.applied_ballots = proposal
+        .applied_ballots
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    mark_counted(proposal_key, proposal, voter_record)
+}
+
+/// Count a ballot whose cast callback succeeded.
+fn mark_counted(
+    proposal_key: Pubkey,
+    proposal: &mut ProposalAccount,
+    voter_record: &mut VoterRecord,
+) -> Result<()> {
+    proposal.counted_voters = proposal
+        .counted_voters
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    voter_record.counted = true;
+    emit_vote_cast(proposal_key, proposal)
+}
+
+/// Apply an anonymous ballot's tallies.  It stays out of `counted_voters`:
+/// with no `VoterRecord` it could never claim its reward share.
+fn count_anonymous_ballot(
+    proposal: &mut Account<ProposalAccount>,
+    tally: &AccountLoader<TallyAccount>,
+    vote_state: [[u8; 32]; 5],
+    nonce: u128,
+) -> Result<()> {
+    store_tally(tally, vote_state, nonce)?;
+    proposal.applied_ballots = proposal
+        .applied_ballots
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    emit_vote_cast(proposal.key(), proposal)
+}
+
+fn emit_vote_cast(proposal_key: Pubkey, proposal: &ProposalAccount) -> Result<()> {
+    let clock = Clock::get()?;
+    emit!(VoteCastEvent {
+        proposal: proposal_key,
+        proposal_id: proposal.id,
+        timestamp: clock.unix_timestamp,
+        voter_count: proposal.voter_count,
+    });
+
+    Ok(())
+}
+
+/// Transfer a protocol fee from `payer` into the fee vault.
+fn collect_fee<'info>(
+    payer: &Signer<'info>,
+    vault: &mut Account<'info, FeeVault>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.to_account_info(),
+                to: vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    vault.total_collected = vault
+        .total_collected
+        .checked_add(amount)
+        .ok_or(ErrorCode::AmountOverflow)?;
+    Ok(())
+}
+
+fn emit_config_updated(config: &Account<Config>) -> Result<()> {
+    let clock = Clock::get()?;
+    emit!(ConfigUpdatedEvent {
+        config: config.key(),
+        admin: config.admin,
+        proposal_fee: config.proposal_fee,
+        vote_fee: config.vote_fee,
+        reveal_timeout: config.reveal_timeout,
+        paused: config.paused,
+        allowed_gating_modes: config.allowed_gating_modes,
+        proposal_deposit: config.proposal_deposit,
+        council: config.council,
+        treasury: config.treasury,
+        proposals_per_epoch: config.proposals_per_epoch,
+        whitelisted_proposals_per_epoch: config.whitelisted_proposals_per_epoch,
+        permissioned_creation: config.permissioned_creation,
+        candidate_deposit: config.candidate_deposit,
+        emergency_min_duration: config.emergency_min_duration,
+        emergency_supermajority_bps: config.emergency_supermajority_bps,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Store revealed results with the cluster's attestation, finalize the
+/// proposal and emit `ResultsRevealedEvent`.  Shared by the
+/// `reveal_results`, `reveal_outcome` and `reveal_pairwise` callbacks.
+fn record_results(
+    proposal: &mut Account<ProposalAccount>,
+    global_stats: &mut GlobalStats,
+    cluster: Pubkey,
+    computation_account: &UncheckedAccount,
+    signature: [u8; 64],
+    results: [u64; 5],
+    winner: u8,
+) -> Result<()> {
+    let (computation_slot, slot_counter) = computation_slot_info(computation_account)?;
+
+    try_transition(proposal.state(Clock::get()?.unix_timestamp), ProposalState::Finalized)?;
+    proposal.is_finalized = true;
+    proposal.reveal_pending_since = 0;
+    proposal.results = results;
+    proposal.winner = winner;
+    proposal.result_attestation = ResultAttestation {
+        cluster,
+        signature,
+        computation_slot,
+        slot_counter,
+    };
+    global_stats.total_finalized = global_stats
+        .total_finalized
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+
+    emit!(ResultsRevealedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        option_0: results[0],
+        option_1: results[1],
+        option_2: results[2],
+        option_3: results[3],
+        total_votes: results[4],
+        winner,
+    });
+    Ok(())
+}
+
+/// Record a reveal queued at `now` in `computation`, and start a new tally
+/// generation: ballots still in flight were stamped with the old one, so
+/// their callbacks can't change tallies the reveal may already have read.
+fn begin_reveal(proposal: &mut ProposalAccount, computation: Pubkey, now: i64) {
+    proposal.reveal_pending_since = now;
+    proposal.reveal_computation = computation;
+    proposal.tally_generation = proposal.tally_generation.wrapping_add(1);
+}
+
+/// Count a computation about to be queued for `proposal`.
+fn track_computation(proposal: &mut ProposalAccount) -> Result<()> {
+    proposal.pending_computations = proposal
+        .pending_computations
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    proposal.last_queued_at = Clock::get()?.unix_timestamp;
+    Ok(())
+}
+
+/// Count a callback as landed, whatever it then does with its output.
+fn settle_computation(proposal: &mut ProposalAccount) {
+    proposal.pending_computations = proposal.pending_computations.saturating_sub(1);
+}
+
+/// Whether a ballot callback belongs to the proposal's current tally
+/// generation, not one a reveal has superseded since the ballot was cast
+/// in `tally_generation`.
+fn is_current_generation(proposal: &ProposalAccount, tally_generation: u32) -> bool {
+    if tally_generation == proposal.tally_generation {
+        return true;
+    }
+    msg!("Dropping the callback of a ballot superseded by a reveal");
+    false
+}
+
+/// Whether a reveal callback belongs to the proposal's latest reveal, not
+/// one that was aborted or queued again since.
+fn is_current_reveal(proposal: &ProposalAccount, computation_account: &UncheckedAccount) -> bool {
+    if computation_account.key() == proposal.reveal_computation {
+        return true;
+    }
+    msg!("Dropping the callback of a superseded or aborted reveal");
+    false
+}
+
+/// Slot and per-slot counter of a computation account, which the cluster
+/// signs together with the outputs.  Same offsets `verify_output` reads.
+fn computation_slot_info(computation_account: &UncheckedAccount) -> Result<(u64, u16)> {
+    const SLOT_OFFSET: usize = 100;
+    const SLOT_COUNTER_OFFSET: usize = 108;
+
+    let data = computation_account.try_borrow_data()?;
+    let slot = data
+        .get(SLOT_OFFSET..SLOT_OFFSET + 8)
+        .ok_or(ErrorCode::InvalidComputationAccount)?;
+    let counter = data
+        .get(SLOT_COUNTER_OFFSET..SLOT_COUNTER_OFFSET + 2)
+        .ok_or(ErrorCode::InvalidComputationAccount)?;
+    Ok((
+        u64::from_le_bytes(slot.try_into().unwrap()),
+        u16::from_le_bytes(counter.try_into().unwrap()),
+    ))
+}
+
+/// Record a computation output that failed verification.
+///
+/// Returns `Ok` on purpose: an `Err` would roll back the transaction and
+/// the `CallbackRejectedEvent` with it, leaving nothing for operators to see.
+fn reject_callback(
+    stats: &mut GlobalStats,
+    proposal: Pubkey,
+    proposal_id: u32,
+    stage: CallbackStage,
+    aborted: bool,
+) -> Result<()> {
+    stats.total_failed_computations = stats
+        .total_failed_computations
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+
+    let reason = if aborted {
+        CallbackRejectReason::ComputationAborted
+    } else {
+        CallbackRejectReason::VerificationFailed
+    };
+    msg!(
+        "Rejected {:?} callback for proposal {}: {:?}",
+        stage,
+        proposal_id,
+        reason
+    );
+
+    let clock = Clock::get()?;
+    emit!(CallbackRejectedEvent {
+        proposal,
+        proposal_id,
+        stage,
+        reason,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// ============================================================
+// CPI Builders
+// ============================================================
+
+/// Typed instruction builders for Anchor programs that drive ArcVote
+/// through CPI.
+///
+/// Each builder derives every account the instruction needs — including the
+/// Arcium queue accounts and the accounts the callback will be invoked with —
+/// so the caller only supplies the matching `AccountInfo`s to
+/// `invoke`/`invoke_signed`.  The comp-def initializers and callbacks are
+/// deliberately absent: the former are one-time deployment steps and the
+/// latter are only ever invoked by the Arcium program.
+#[cfg(feature = "cpi")]
+pub mod cpi_builders {
+    use anchor_lang::{
+        solana_program::{
+            instruction::{AccountMeta, Instruction},
+            system_program, sysvar,
+        },
+        InstructionData, ToAccountMetas,
+    };
+    use arcium_client::pda as arcium_pda;
+
+    use super::*;
+
+    /// Arcium accounts shared by every instruction that queues a computation.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct QueueAccounts {
+        pub sign_pda_account: Pubkey,
+        pub mxe_account: Pubkey,
+        pub mempool_account: Pubkey,
+        pub executing_pool: Pubkey,
+        pub computation_account: Pubkey,
+        pub comp_def_account: Pubkey,
+        pub cluster_account: Pubkey,
+        pub pool_account: Pubkey,
+        pub clock_account: Pubkey,
+    }
+
+    impl QueueAccounts {
+        pub fn new(cluster_offset: u32, comp_def_offset: u32, computation_offset: u64) -> Self {
+            Self {
+                sign_pda_account: arcium_pda::signer_acc(&ID),
+                mxe_account: arcium_pda::mxe_acc(&ID),
+                mempool_account: arcium_pda::mempool_acc(cluster_offset),
+                executing_pool: arcium_pda::execpool_acc(cluster_offset),
+                computation_account: arcium_pda::computation_acc(cluster_offset, computation_offset),
+                comp_def_account: arcium_pda::computation_definition_acc(&ID, comp_def_offset),
+                cluster_account: arcium_pda::cluster_acc(cluster_offset),
+                pool_account: ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+                clock_account: ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+            }
+        }
+    }
+
+    /// `[b"proposal", authority, id LE]`
+    pub fn proposal_address(authority: &Pubkey, id: u32) -> Pubkey {
+        Pubkey::find_program_address(&[b"proposal", authority.as_ref(), &id.to_le_bytes()], &ID).0
+    }
+
+    /// `[b"tally", proposal]`
+    pub fn tally_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"tally", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"closed_proposal", proposal]`
+    pub fn closed_proposal_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"closed_proposal", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"voter", proposal, voter]`
+    pub fn voter_record_address(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"voter", proposal.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"global_stats"]`
+    pub fn global_stats_address() -> Pubkey {
+        Pubkey::find_program_address(&[b"global_stats"], &ID).0
+    }
+
+    /// `[b"config"]`
+    pub fn config_address() -> Pubkey {
+        Pubkey::find_program_address(&[b"config"], &ID).0
+    }
+
+    /// `[b"fee_vault"]`
+    pub fn fee_vault_address() -> Pubkey {
+        Pubkey::find_program_address(&[b"fee_vault"], &ID).0
+    }
+
+    /// `[b"creator", authority]`
+    pub fn creator_record_address(authority: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"creator", authority.as_ref()], &ID).0
+    }
+
+    /// Accounts the `reveal_results` callback is invoked with, in order.
+    /// The program attaches these itself when queueing; they are exposed so
+    /// integrators can pre-fund or watch them.
+    pub fn callback_accounts(proposal: &Pubkey) -> [CallbackAccount; 2] {
+        [
+            CallbackAccount {
+                pubkey: *proposal,
+                is_writable: true,
+            },
+            CallbackAccount {
+                pubkey: global_stats_address(),
+                is_writable: true,
+            },
+        ]
+    }
+
+    /// Accounts the `init_tallies` callback is invoked with: the above with
+    /// the proposal's `TallyAccount`, which it writes, after the proposal.
+    pub fn init_tallies_callback_accounts(proposal: &Pubkey) -> [CallbackAccount; 3] {
+        let [proposal_acc, global_stats] = callback_accounts(proposal);
+        [
+            proposal_acc,
+            CallbackAccount {
+                pubkey: tally_address(proposal),
+                is_writable: true,
+            },
+            global_stats,
+        ]
+    }
+
+    /// Accounts the `cast_vote` callback is invoked with: the above plus the
+    /// voter's record, which it marks as counted.
+    pub fn cast_vote_callback_accounts(
+        proposal: &Pubkey,
+        voter: &Pubkey,
+    ) -> [CallbackAccount; 4] {
+        let [proposal_acc, tally_acc, global_stats] = init_tallies_callback_accounts(proposal);
+        [
+            proposal_acc,
+            tally_acc,
+            global_stats,
+            CallbackAccount {
+                pubkey: voter_record_address(proposal, voter),
+                is_writable: true,
+            },
+        ]
+    }
+
+    /// `[b"pairwise_box", proposal]`
+    pub fn pairwise_box_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"pairwise_box", proposal.as_ref()], &ID).0
+    }
+
+    pub fn write_in_tally_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"write_ins", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"reward_pool", proposal]`
+    pub fn reward_pool_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"reward_pool", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"reward_vault", proposal]`
+    pub fn reward_vault_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"reward_vault", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"ballot_commitment", proposal, voter]`
+    pub fn ballot_commitment_address(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"ballot_commitment", proposal.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"option_details", proposal]`
+    pub fn option_details_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"option_details", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"translations", proposal]`
+    pub fn translations_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"translations", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"matching_pool", proposal]`
+    pub fn matching_pool_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"matching_pool", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"matching_vault", proposal]`
+    pub fn matching_vault_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"matching_vault", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"match_payout", proposal, option]`
+    pub fn match_payout_address(proposal: &Pubkey, option: u8) -> Pubkey {
+        Pubkey::find_program_address(&[b"match_payout", proposal.as_ref(), &[option]], &ID).0
+    }
+
+    /// `[b"receipt_mint", proposal]`
+    pub fn receipt_mint_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"receipt_mint", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"candidate", proposal, candidate]`
+    pub fn candidate_address(proposal: &Pubkey, candidate: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"candidate", proposal.as_ref(), candidate.as_ref()], &ID).0
+    }
+
+    /// `[b"juror", proposal, juror]`
+    pub fn juror_address(proposal: &Pubkey, juror: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"juror", proposal.as_ref(), juror.as_ref()], &ID).0
+    }
+
+    /// `[b"credentials", proposal]`
+    pub fn credential_registry_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"credentials", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"issuance", proposal, voter]`
+    pub fn credential_issuance_address(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"issuance", proposal.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"credential", proposal, serial]`
+    pub fn credential_address(proposal: &Pubkey, serial: &[u8; 32]) -> Pubkey {
+        Pubkey::find_program_address(&[b"credential", proposal.as_ref(), serial], &ID).0
+    }
+
+    /// `[b"nullifiers", proposal]`
+    pub fn nullifier_set_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"nullifiers", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"compliance", proposal]`
+    pub fn compliance_roll_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"compliance", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"identity", proposal, serial]`
+    pub fn sealed_identity_address(proposal: &Pubkey, serial: &[u8; 32]) -> Pubkey {
+        Pubkey::find_program_address(&[b"identity", proposal.as_ref(), serial], &ID).0
+    }
+
+    /// `[b"trustees", proposal]`
+    pub fn trustee_set_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"trustees", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"mix_queue", proposal]`
+    pub fn mix_queue_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"mix_queue", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"ballot_buffer", proposal]`
+    pub fn ballot_buffer_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"ballot_buffer", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"credits", scope, voter]`: the scope is the proposal, or its
+    /// ballot if it is on one.
+    pub fn voter_credits_address(scope: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"credits", scope.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"credit_vault", voter_credits]`
+    pub fn credit_vault_address(voter_credits: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"credit_vault", voter_credits.as_ref()], &ID).0
+    }
+
+    /// `[b"credit_account", proposal, voter]`
+    pub fn credit_account_address(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"credit_account", proposal.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"credit_bank", authority, voter]`
+    pub fn credit_bank_address(authority: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"credit_bank", authority.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"encrypted_budget", proposal, voter]`
+    pub fn encrypted_budget_address(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"encrypted_budget", proposal.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"history_gate", proposal]`
+    pub fn history_gate_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"history_gate", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"allowlist", proposal]`
+    pub fn allowlist_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"allowlist", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"nft_ballot", proposal, mint]`
+    pub fn nft_ballot_address(proposal: &Pubkey, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"nft_ballot", proposal.as_ref(), mint.as_ref()], &ID).0
+    }
+
+    /// The Metaplex metadata account of `mint`.
+    pub fn nft_metadata_address(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+            &TOKEN_METADATA_PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// `[b"spend_histogram", proposal]`
+    pub fn spend_histogram_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"spend_histogram", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"rankings", proposal]`
+    pub fn rankings_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"rankings", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"preferences", proposal]`
+    pub fn preferences_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"preferences", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"council", proposal]`
+    pub fn council_chamber_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"council", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"council_vote", proposal, member]`
+    pub fn council_record_address(proposal: &Pubkey, member: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"council_vote", proposal.as_ref(), member.as_ref()], &ID).0
+    }
+
+    /// `[b"category", id]`
+    pub fn category_address(category: u16) -> Pubkey {
+        Pubkey::find_program_address(&[b"category", category.to_le_bytes().as_ref()], &ID).0
+    }
+
+    /// `[b"ballot", authority, id]`
+    pub fn ballot_address(authority: &Pubkey, id: u32) -> Pubkey {
+        Pubkey::find_program_address(&[b"ballot", authority.as_ref(), id.to_le_bytes().as_ref()], &ID).0
+    }
+
+    /// `[b"round_voter", ballot, voter]`
+    pub fn round_record_address(ballot: &Pubkey, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"round_voter", ballot.as_ref(), voter.as_ref()], &ID).0
+    }
+
+    /// `[b"commitment", proposal]`
+    pub fn commitment_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"commitment", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"dependency", proposal]`
+    pub fn dependency_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"dependency", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"sanction", proposal]`
+    pub fn sanction_address(proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"sanction", proposal.as_ref()], &ID).0
+    }
+
+    /// `[b"session", proposal, wallet]`
+    pub fn session_address(proposal: &Pubkey, wallet: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"session", proposal.as_ref(), wallet.as_ref()], &ID).0
+    }
+
+    fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id: ID,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    /// Arguments of `create_proposal`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct CreateProposalArgs {
+        pub id: u32,
+        pub title: String,
+        pub options: Vec<String>,
+        pub deadline: i64,
+        /// Nonzero to fill the (empty) options by candidate registration.
+        pub registration_deadline: i64,
+        pub voice_credits: u64,
+        pub quorum: u32,
+        pub quorum_kind: QuorumKind,
+        /// Registered voter count for `QuorumKind::RegisteredBps`.
+        pub electorate: u64,
+        /// Mint for `QuorumKind::SupplyBps`.
+        pub quorum_mint: Option<Pubkey>,
+        /// Mint whose holders alone may vote, under `GatingMode::Token`.
+        pub gate_mint: Option<Pubkey>,
+        /// Minimum `gate_mint` balance; zero without a gate mint.
+        pub gate_threshold: u64,
+        pub ballot: BallotKind,
+        pub nonce: u128,
+    }
+
+    /// `payer` and `authority` sign; `authority` becomes the proposal
+    /// authority — typically a PDA of the calling program signing through
+    /// `invoke_signed`.  Pass the same key twice when one account does both.
+    pub fn create_proposal(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+        args: CreateProposalArgs,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_TALLIES, computation_offset);
+        let proposal = proposal_address(authority, args.id);
+        build(
+            accounts::CreateProposal {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                creator_record: creator_record_address(authority),
+                closed_proposal: closed_proposal_address(&proposal),
+                quorum_mint: args.quorum_mint,
+                gate_mint: args.gate_mint,
+            },
+            instruction::CreateProposal {
+                computation_offset,
+                id: args.id,
+                title: args.title,
+                num_options: args.options.len() as u8,
+                options: args.options,
+                deadline: args.deadline,
+                registration_deadline: args.registration_deadline,
+                voice_credits: args.voice_credits,
+                quorum: args.quorum,
+                quorum_kind: args.quorum_kind,
+                electorate: args.electorate,
+                gate_threshold: args.gate_threshold,
+                ballot: args.ballot,
+                nonce: args.nonce,
+            },
+        )
+    }
+
+    /// Arguments of `create_poll`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct CreatePollArgs {
+        pub id: u32,
+        pub title: String,
+        pub options: Vec<String>,
+        pub deadline: i64,
+        pub voice_credits: u64,
+        pub ballot: BallotKind,
+        pub nonce: u128,
+    }
+
+    /// Signers as for [`create_proposal`].
+    pub fn create_poll(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+        args: CreatePollArgs,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_TALLIES, computation_offset);
+        let proposal = proposal_address(authority, args.id);
+        build(
+            accounts::CreateProposal {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                creator_record: creator_record_address(authority),
+                closed_proposal: closed_proposal_address(&proposal),
+                quorum_mint: None,
+                gate_mint: None,
+            },
+            instruction::CreatePoll {
+                computation_offset,
+                id: args.id,
+                title: args.title,
+                num_options: args.options.len() as u8,
+                options: args.options,
+                deadline: args.deadline,
+                voice_credits: args.voice_credits,
+                ballot: args.ballot,
+                nonce: args.nonce,
+            },
+        )
+    }
+
+    /// `candidate` must sign and pays the rent and deposit.
+    pub fn register_candidate(candidate: &Pubkey, proposal: &Pubkey, name: String) -> Instruction {
+        build(
+            accounts::RegisterCandidate {
+                candidate: *candidate,
+                proposal_acc: *proposal,
+                candidate_acc: candidate_address(proposal, candidate),
+                config: config_address(),
+                system_program: system_program::ID,
+            },
+            instruction::RegisterCandidate { name },
+        )
+    }
+
+    /// `candidate` must sign and receives the rent and deposit.
+    pub fn close_candidate(candidate: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CloseCandidate {
+                candidate: *candidate,
+                proposal_acc: *proposal,
+                candidate_acc: candidate_address(proposal, candidate),
+            },
+            instruction::CloseCandidate {},
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn require_parent_outcome(authority: &Pubkey, proposal: &Pubkey, parent: &Pubkey, winner: u8) -> Instruction {
+        build(
+            accounts::RequireParentOutcome {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::RequireParentOutcome {
+                parent: *parent,
+                winner,
+            },
+        )
+    }
+
+    /// Permissionless.
+    pub fn confirm_parent_outcome(proposal: &Pubkey, parent: &Pubkey) -> Instruction {
+        build(
+            accounts::ConfirmParentOutcome {
+                proposal_acc: *proposal,
+                parent_acc: *parent,
+            },
+            instruction::ConfirmParentOutcome {},
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn set_option_details(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        details: Vec<OptionDetail>,
+    ) -> Instruction {
+        build(
+            accounts::SetOptionDetails {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                option_details: option_details_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::SetOptionDetails { details },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn set_translation(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        locale: String,
+        title: String,
+        options: Vec<String>,
+    ) -> Instruction {
+        build(
+            accounts::SetTranslation {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                translations: translations_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::SetTranslation { locale, title, options },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn remove_translation(authority: &Pubkey, proposal: &Pubkey, locale: String) -> Instruction {
+        build(
+            accounts::RemoveTranslation {
+                authority: *authority,
+                proposal_acc: *proposal,
+                translations: translations_address(proposal),
+            },
+            instruction::RemoveTranslation { locale },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn declare_dependency(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        parent: &Pubkey,
+        condition: DependencyCondition,
+    ) -> Instruction {
+        build(
+            accounts::DeclareDependency {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                parent_acc: *parent,
+                dependency: dependency_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::DeclareDependency { condition },
+        )
+    }
+
+    /// Permissionless.
+    pub fn resolve_dependency(proposal: &Pubkey, parent: &Pubkey) -> Instruction {
+        build(
+            accounts::ResolveDependency {
+                proposal_acc: *proposal,
+                parent_acc: *parent,
+                dependency: dependency_address(proposal),
+            },
+            instruction::ResolveDependency {},
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn declare_sanction(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        action: SanctionAction,
+    ) -> Instruction {
+        build(
+            accounts::DeclareSanction {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                sanction: sanction_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::DeclareSanction { action },
+        )
+    }
+
+    /// Permissionless; `treasury` must be `Config.treasury` and is only
+    /// passed for a `SlashDeposit`.
+    pub fn execute_sanction(proposal: &Pubkey, action: &SanctionAction, treasury: &Pubkey) -> Instruction {
+        let (target_acc, treasury, creator_record) = match action {
+            SanctionAction::SlashDeposit { proposal, .. } => (Some(*proposal), Some(*treasury), None),
+            SanctionAction::RemoveCreator { creator } => (None, None, Some(creator_record_address(creator))),
+        };
+        build(
+            accounts::ExecuteSanction {
+                proposal_acc: *proposal,
+                sanction: sanction_address(proposal),
+                config: config_address(),
+                target_acc,
+                treasury,
+                creator_record,
+            },
+            instruction::ExecuteSanction {},
+        )
+    }
+
+    /// `authority` must sign; `payer` funds the `Juror` account.
+    pub fn seat_juror(payer: &Pubkey, authority: &Pubkey, proposal: &Pubkey, juror: &Pubkey) -> Instruction {
+        build(
+            accounts::SeatJuror {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                juror_acc: juror_address(proposal, juror),
+                system_program: system_program::ID,
+            },
+            instruction::SeatJuror { juror: *juror },
+        )
+    }
+
+    /// `authority` must sign and receives the rent.
+    pub fn close_juror(authority: &Pubkey, proposal: &Pubkey, juror: &Pubkey) -> Instruction {
+        build(
+            accounts::CloseJuror {
+                authority: *authority,
+                proposal_acc: *proposal,
+                juror_acc: juror_address(proposal, juror),
+            },
+            instruction::CloseJuror {},
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Jury` proposal; `voter` must
+    /// hold a `Juror` seat.
+    pub fn cast_jury_scores(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedJuryScores,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_JURY_SCORES, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastJuryScores {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                juror_acc: juror_address(&proposal, voter),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastJuryScores {
+                computation_offset,
+                _id: proposal_id,
+                scores: ballot.ciphertexts,
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// `authority` signs and `payer` funds the `WriteInTally`.
+    pub fn open_write_ins(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_WRITE_INS, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::OpenWriteIns {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                write_in_tally: write_in_tally_address(&proposal),
+            },
+            instruction::OpenWriteIns {
+                computation_offset,
+                _id: proposal_id,
+                nonce,
+            },
+        )
+    }
+
+    pub fn cast_write_in(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        write_in: &EncryptedWriteIn,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_WRITE_IN, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastWriteIn {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                write_in_tally: write_in_tally_address(&proposal),
+            },
+            instruction::CastWriteIn {
+                computation_offset,
+                _id: proposal_id,
+                candidate: write_in.ciphertext,
+                vote_encryption_pubkey: write_in.encryption_pubkey,
+                vote_nonce: write_in.nonce,
+            },
+        )
+    }
+
+    pub fn reveal_write_in(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_WRITE_IN, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealWriteIn {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                write_in_tally: write_in_tally_address(&proposal),
+            },
+            instruction::RevealWriteIn {
+                computation_offset,
+                _id: proposal_id,
+            },
+        )
+    }
+
+    /// Encrypted ballot accepted by `cast_vote`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct EncryptedBallot {
+        pub ciphertexts: [[u8; 32]; 4],
+        pub encryption_pubkey: [u8; 32],
+        pub nonce: u128,
+    }
+
+    /// Encrypted criterion-major scores accepted by `cast_jury_scores`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct EncryptedJuryScores {
+        pub ciphertexts: [[u8; 32]; 12],
+        pub encryption_pubkey: [u8; 32],
+        pub nonce: u128,
+    }
+
+    /// Encrypted choice indices accepted by `cast_ballot_choices`, one per
+    /// proposal in its order, unused slots last.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct EncryptedBallotChoices {
+        pub ciphertexts: [[u8; 32]; MAX_BALLOT_PROPOSALS],
+        pub encryption_pubkey: [u8; 32],
+        pub nonce: u128,
+    }
+
+    /// Encrypted candidate hash accepted by `cast_write_in`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct EncryptedWriteIn {
+        pub ciphertext: [u8; 32],
+        pub encryption_pubkey: [u8; 32],
+        pub nonce: u128,
+    }
+
+    /// Encrypted credit budget accepted by `fund_encrypted_budget`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct EncryptedCredits {
+        pub ciphertext: [u8; 32],
+        pub encryption_pubkey: [u8; 32],
+        pub nonce: u128,
+    }
+
+    /// `authority` must sign; `payer` funds the `CredentialRegistry`.
+    pub fn open_credentials(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        registrar: &Pubkey,
+        registrar_key: [u8; 128],
+    ) -> Instruction {
+        build(
+            accounts::OpenCredentials {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                credential_registry: credential_registry_address(proposal),
+                config: config_address(),
+                system_program: system_program::ID,
+            },
+            instruction::OpenCredentials {
+                registrar: *registrar,
+                registrar_key,
+            },
+        )
+    }
+
+    /// `registrar` signs and funds the `CredentialIssuance`.
+    pub fn issue_credential(
+        registrar: &Pubkey,
+        proposal: &Pubkey,
+        voter: &Pubkey,
+        blinded_message: [u8; 64],
+        blind_signature: [u8; 64],
+    ) -> Instruction {
+        build(
+            accounts::IssueCredential {
+                registrar: *registrar,
+                proposal_acc: *proposal,
+                credential_registry: credential_registry_address(proposal),
+                issuance: credential_issuance_address(proposal, voter),
+                system_program: system_program::ID,
+            },
+            instruction::IssueCredential {
+                voter: *voter,
+                blinded_message,
+                blind_signature,
+            },
+        )
+    }
+
+    /// `registrar` signs and funds the serial's `BallotCredential` if it was
+    /// never presented.
+    pub fn revoke_credential(registrar: &Pubkey, proposal: &Pubkey, serial: [u8; 32]) -> Instruction {
+        build(
+            accounts::RevokeCredential {
+                registrar: *registrar,
+                proposal_acc: *proposal,
+                credential_registry: credential_registry_address(proposal),
+                credential: credential_address(proposal, &serial),
+                system_program: system_program::ID,
+            },
+            instruction::RevokeCredential { serial },
+        )
+    }
+
+    /// `holder` signs, funds the `BallotCredential` and may then
+    /// [`cast_vote_with_credential`].
+    pub fn present_credential(
+        holder: &Pubkey,
+        proposal: &Pubkey,
+        serial: [u8; 32],
+        signature: [u8; 64],
+    ) -> Instruction {
+        build(
+            accounts::PresentCredential {
+                payer: *holder,
+                proposal_acc: *proposal,
+                credential_registry: credential_registry_address(proposal),
+                credential: credential_address(proposal, &serial),
+                system_program: system_program::ID,
+            },
+            instruction::PresentCredential { serial, signature },
+        )
+    }
+
+    /// The `CredentialStatus` comes back as return data.
+    pub fn verify_credential(proposal: &Pubkey, serial: [u8; 32], signature: [u8; 64]) -> Instruction {
+        build(
+            accounts::VerifyCredential {
+                credential_registry: credential_registry_address(proposal),
+                credential: credential_address(proposal, &serial),
+            },
+            instruction::VerifyCredential { serial, signature },
+        )
+    }
+
+    /// `payer` and `authority` must sign; `payer` funds the first 10 KiB of
+    /// the `NullifierSet`.
+    pub fn open_nullifier_set(payer: &Pubkey, authority: &Pubkey, proposal: &Pubkey, max_voters: u32) -> Instruction {
+        build(
+            accounts::OpenNullifierSet {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                credential_registry: credential_registry_address(proposal),
+                nullifier_set: nullifier_set_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::OpenNullifierSet { max_voters },
+        )
+    }
+
+    /// `payer` signs and funds the next 10 KiB.
+    pub fn grow_nullifier_set(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::GrowNullifierSet {
+                payer: *payer,
+                nullifier_set: nullifier_set_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::GrowNullifierSet {},
+        )
+    }
+
+    /// `payer` signs and pays; nothing on chain ties it to the ballot.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_anonymous(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        serial: [u8; 32],
+        signature: [u8; 64],
+        ballot: &EncryptedBallot,
+        identity: Option<IdentityCiphertexts>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastVoteAnonymous {
+                payer: *payer,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                credential_registry: credential_registry_address(&proposal),
+                credential: credential_address(&proposal, &serial),
+                nullifier_set: nullifier_set_address(&proposal),
+                mix_queue: Some(mix_queue_address(&proposal)),
+                slot_hashes: Some(sysvar::slot_hashes::ID),
+                compliance_roll: identity.map(|_| compliance_roll_address(&proposal)),
+                sealed_identity: identity.map(|_| sealed_identity_address(&proposal, &serial)),
+            },
+            instruction::CastVoteAnonymous {
+                computation_offset,
+                _id: proposal_id,
+                serial,
+                signature,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+                identity,
+            },
+        )
+    }
+
+    /// Whether `serial` was spent comes back as return data.
+    pub fn check_nullifier(proposal: &Pubkey, serial: [u8; 32]) -> Instruction {
+        build(
+            accounts::CheckNullifier {
+                nullifier_set: nullifier_set_address(proposal),
+            },
+            instruction::CheckNullifier { serial },
+        )
+    }
+
+    /// `authority` must sign and receives the rent.
+    pub fn close_nullifier_set(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CloseNullifierSet {
+                authority: *authority,
+                proposal_acc: *proposal,
+                nullifier_set: nullifier_set_address(proposal),
+            },
+            instruction::CloseNullifierSet {},
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn open_compliance_roll(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        compliance_key: [u8; 32],
+    ) -> Instruction {
+        build(
+            accounts::OpenComplianceRoll {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                credential_registry: credential_registry_address(proposal),
+                compliance_roll: compliance_roll_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::OpenComplianceRoll { compliance_key },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn release_identity(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        sealed_identity: &Pubkey,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_RELEASE_IDENTITY, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::ReleaseIdentity {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                compliance_roll: compliance_roll_address(&proposal),
+                sealed_identity: *sealed_identity,
+            },
+            instruction::ReleaseIdentity {
+                computation_offset,
+                _id: proposal_id,
+            },
+        )
+    }
+
+    /// `authority` must sign and receives the rent.
+    pub fn close_compliance_roll(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CloseComplianceRoll {
+                authority: *authority,
+                proposal_acc: *proposal,
+                compliance_roll: compliance_roll_address(proposal),
+            },
+            instruction::CloseComplianceRoll {},
+        )
+    }
+
+    /// `payer` and `authority` must sign; `payer` funds the `HistoryGate`.
+    pub fn open_history_gate(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        attestor: Option<Pubkey>,
+        min_wallet_age: i64,
+        min_prior_ballots: u32,
+    ) -> Instruction {
+        build(
+            accounts::OpenHistoryGate {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                history_gate: history_gate_address(proposal),
+                config: config_address(),
+                system_program: system_program::ID,
+            },
+            instruction::OpenHistoryGate {
+                attestor,
+                min_wallet_age,
+                min_prior_ballots,
+            },
+        )
+    }
+
+    /// `attestor` signs and funds the voter's `BallotCredential`.
+    pub fn attest_history(
+        attestor: &Pubkey,
+        proposal: &Pubkey,
+        voter: &Pubkey,
+        first_seen: i64,
+        prior_ballots: u32,
+    ) -> Instruction {
+        build(
+            accounts::AttestHistory {
+                attestor: *attestor,
+                proposal_acc: *proposal,
+                history_gate: history_gate_address(proposal),
+                credential: credential_address(proposal, &voter.to_bytes()),
+                system_program: system_program::ID,
+            },
+            instruction::AttestHistory {
+                voter: *voter,
+                first_seen,
+                prior_ballots,
+            },
+        )
+    }
+
+    /// `voter` signs and funds its `BallotCredential`; `voter_records` are
+    /// its `VoterRecord`s on other proposals.
+    pub fn prove_history(voter: &Pubkey, proposal: &Pubkey, voter_records: &[Pubkey]) -> Instruction {
+        let mut ix = build(
+            accounts::ProveHistory {
+                voter: *voter,
+                proposal_acc: *proposal,
+                history_gate: history_gate_address(proposal),
+                credential: credential_address(proposal, &voter.to_bytes()),
+                system_program: system_program::ID,
+            },
+            instruction::ProveHistory {},
+        );
+        ix.accounts.extend(
+            voter_records
+                .iter()
+                .map(|record| AccountMeta::new_readonly(*record, false)),
+        );
+        ix
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn open_allowlist(payer: &Pubkey, authority: &Pubkey, proposal: &Pubkey, capacity: u16) -> Instruction {
+        build(
+            accounts::OpenAllowlist {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                allowlist: allowlist_address(proposal),
+                config: config_address(),
+                system_program: system_program::ID,
+            },
+            instruction::OpenAllowlist { capacity },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn add_voter(authority: &Pubkey, proposal: &Pubkey, voter: &Pubkey) -> Instruction {
+        build(
+            accounts::AddVoter {
+                authority: *authority,
+                proposal_acc: *proposal,
+                allowlist: allowlist_address(proposal),
+            },
+            instruction::AddVoter { voter: *voter },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn remove_voter(authority: &Pubkey, proposal: &Pubkey, voter: &Pubkey) -> Instruction {
+        build(
+            accounts::RemoveVoter {
+                authority: *authority,
+                proposal_acc: *proposal,
+                allowlist: allowlist_address(proposal),
+            },
+            instruction::RemoveVoter { voter: *voter },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn open_collection_gate(authority: &Pubkey, proposal: &Pubkey, collection: &Pubkey) -> Instruction {
+        build(
+            accounts::OpenCollectionGate {
+                authority: *authority,
+                proposal_acc: *proposal,
+                config: config_address(),
+            },
+            instruction::OpenCollectionGate {
+                collection: *collection,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn open_mix_window(payer: &Pubkey, authority: &Pubkey, proposal_id: u32, window: i64) -> Instruction {
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::OpenMixWindow {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: proposal,
+                mix_queue: mix_queue_address(&proposal),
+                system_program: system_program::ID,
+            },
+            instruction::OpenMixWindow { window },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn open_ballot_buffer(payer: &Pubkey, authority: &Pubkey, proposal_id: u32) -> Instruction {
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::OpenBallotBuffer {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: proposal,
+                ballot_buffer: ballot_buffer_address(&proposal),
+                system_program: system_program::ID,
+            },
+            instruction::OpenBallotBuffer {},
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn open_commit_phase(authority: &Pubkey, proposal_id: u32, commit_deadline: i64) -> Instruction {
+        build(
+            accounts::OpenCommitPhase {
+                authority: *authority,
+                proposal_acc: proposal_address(authority, proposal_id),
+            },
+            instruction::OpenCommitPhase { commit_deadline },
+        )
+    }
+
+    /// `voter` must sign.  `commitment` is [`ballot_hash`] of the ballot
+    /// `voter` will cast.
+    pub fn commit_ballot(voter: &Pubkey, proposal: &Pubkey, commitment: [u8; 32]) -> Instruction {
+        build(
+            accounts::CommitBallot {
+                voter: *voter,
+                proposal_acc: *proposal,
+                ballot_commitment: ballot_commitment_address(proposal, voter),
+                system_program: system_program::ID,
+            },
+            instruction::CommitBallot { commitment },
+        )
+    }
+
+    /// Permissionless; `payer` pays the Arcium fee.
+    pub fn release_held_ballot(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::ReleaseHeldBallot {
+                payer: *payer,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                mix_queue: mix_queue_address(&proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::ReleaseHeldBallot {
+                computation_offset,
+                _id: proposal_id,
+            },
+        )
+    }
+
+    /// `voter` must sign.  `serial` names the presented credential on a
+    /// gated proposal.
+    pub fn submit_ballot(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+        serial: Option<&[u8; 32]>,
+    ) -> Instruction {
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::SubmitBallot {
+                payer: *voter,
+                authority: *authority,
+                proposal_acc: proposal,
+                ballot_buffer: ballot_buffer_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                credential: serial.map(|serial| credential_address(&proposal, serial)),
+                voter_credits: Some(voter_credits_address(&proposal, voter)),
+                credit_account: Some(credit_account_address(&proposal, voter)),
+                allowlist: Some(allowlist_address(&proposal)),
+                system_program: system_program::ID,
+            },
+            instruction::SubmitBallot {
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// Permissionless; `payer` pays the Arcium fee.
+    pub fn process_ballot_batch(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE_BATCH, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::ProcessBallotBatch {
+                payer: *payer,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                ballot_buffer: ballot_buffer_address(&proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::ProcessBallotBatch {
+                computation_offset,
+                _id: proposal_id,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn open_spend_histogram(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_SPEND_HISTOGRAM, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::OpenSpendHistogram {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                spend_histogram: spend_histogram_address(&proposal),
+            },
+            instruction::OpenSpendHistogram {
+                computation_offset,
+                _id: proposal_id,
+                nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], on a proposal with a spend histogram.
+    pub fn cast_vote_histogram(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE_HISTOGRAM, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastVoteHistogram {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                spend_histogram: spend_histogram_address(&proposal),
+            },
+            instruction::CastVoteHistogram {
+                computation_offset,
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    pub fn reveal_spend_histogram(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_SPEND_HISTOGRAM, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealSpendHistogram {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                spend_histogram: spend_histogram_address(&proposal),
+            },
+            instruction::RevealSpendHistogram {
+                computation_offset,
+                _id: proposal_id,
+            },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn open_credit_bank(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::OpenCreditBank {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::OpenCreditBank {},
+        )
+    }
+
+    /// `voter` must sign and pays the rent; the bank serves every proposal
+    /// of `authority`.
+    pub fn create_credit_bank(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_CREDIT_BANK, computation_offset);
+        build(
+            accounts::CreateCreditBank {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal_address(authority, proposal_id),
+                global_stats: global_stats_address(),
+                credit_bank: credit_bank_address(authority, voter),
+            },
+            instruction::CreateCreditBank {
+                computation_offset,
+                _id: proposal_id,
+                nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], on a banked proposal.
+    pub fn cast_vote_banked(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE_BANKED, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastVoteBanked {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                credit_bank: credit_bank_address(authority, voter),
+            },
+            instruction::CastVoteBanked {
+                computation_offset,
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// `authority` must sign; `payer` pays the rent.  `budget` is
+    /// encrypted under the authority's key, once per voter.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fund_encrypted_budget(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+        voter: &Pubkey,
+        budget: &EncryptedCredits,
+        nonce: u128,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_SEAL_BUDGET, computation_offset);
+        build(
+            accounts::FundEncryptedBudget {
+                payer: *payer,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: *proposal,
+                global_stats: global_stats_address(),
+                encrypted_budget: encrypted_budget_address(proposal, voter),
+            },
+            instruction::FundEncryptedBudget {
+                computation_offset,
+                voter: *voter,
+                budget: budget.ciphertext,
+                budget_encryption_pubkey: budget.encryption_pubkey,
+                budget_nonce: budget.nonce,
+                nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], on a proposal with encrypted budgets.
+    pub fn cast_vote_budgeted(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE_BUDGETED, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastVoteBudgeted {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                encrypted_budget: encrypted_budget_address(&proposal, voter),
+            },
+            instruction::CastVoteBudgeted {
+                computation_offset,
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign, before the first ballot of a
+    /// `BallotKind::Ranked` proposal.
+    pub fn open_rankings(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_RANKINGS, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::OpenRankings {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                rankings: rankings_address(&proposal),
+            },
+            instruction::OpenRankings {
+                computation_offset,
+                _id: proposal_id,
+                nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Ranked` proposal; the ballot's
+    /// ciphertexts are the options in rank order.
+    pub fn cast_ranked_vote(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_RANKED_VOTE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastRankedVote {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                rankings: rankings_address(&proposal),
+            },
+            instruction::CastRankedVote {
+                computation_offset,
+                _id: proposal_id,
+                rank_0: ballot.ciphertexts[0],
+                rank_1: ballot.ciphertexts[1],
+                rank_2: ballot.ciphertexts[2],
+                rank_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`reveal_outcome`], for a `BallotKind::Ranked` proposal.
+    pub fn reveal_ranked(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_RANKED, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealRanked {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                rankings: rankings_address(&proposal),
+            },
+            instruction::RevealRanked {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign, before the first ballot of a
+    /// `BallotKind::Condorcet` proposal.
+    pub fn open_preferences(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_PREFERENCES, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::OpenPreferences {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                preferences: preferences_address(&proposal),
+            },
+            instruction::OpenPreferences {
+                computation_offset,
+                _id: proposal_id,
+                nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Condorcet` proposal; the ballot's
+    /// ciphertexts are the options in rank order.
+    pub fn cast_condorcet(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_CONDORCET, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastCondorcet {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                preferences: preferences_address(&proposal),
+            },
+            instruction::CastCondorcet {
+                computation_offset,
+                _id: proposal_id,
+                rank_0: ballot.ciphertexts[0],
+                rank_1: ballot.ciphertexts[1],
+                rank_2: ballot.ciphertexts[2],
+                rank_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`reveal_outcome`], for a `BallotKind::Condorcet` proposal.
+    pub fn reveal_condorcet(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_CONDORCET, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealCondorcet {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                preferences: preferences_address(&proposal),
+            },
+            instruction::RevealCondorcet {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Star` proposal; the ballot's
+    /// ciphertexts are the 0..=5 scores in option order.
+    pub fn cast_star(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_STAR, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastStar {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                preferences: preferences_address(&proposal),
+            },
+            instruction::CastStar {
+                computation_offset,
+                _id: proposal_id,
+                score_0: ballot.ciphertexts[0],
+                score_1: ballot.ciphertexts[1],
+                score_2: ballot.ciphertexts[2],
+                score_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`reveal_outcome`], for a `BallotKind::Star` proposal.
+    pub fn reveal_star(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_STAR, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealStar {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+                preferences: preferences_address(&proposal),
+            },
+            instruction::RevealStar {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign, before the first ballot of a
+    /// For/Against/Abstain proposal.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_council(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        nonce: u128,
+        members: Vec<Pubkey>,
+        rule: ChamberRule,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_INIT_COUNCIL_TALLIES, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::OpenCouncil {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                council_chamber: council_chamber_address(&proposal),
+            },
+            instruction::OpenCouncil {
+                computation_offset,
+                _id: proposal_id,
+                nonce,
+                members,
+                rule,
+            },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn set_turnout_bias(authority: &Pubkey, proposal_id: u32, bias_bps: u16) -> Instruction {
+        build(
+            accounts::SetTurnoutBias {
+                authority: *authority,
+                proposal_acc: proposal_address(authority, proposal_id),
+            },
+            instruction::SetTurnoutBias { bias_bps },
+        )
+    }
+
+    /// `authority` and `council` must sign.
+    pub fn fast_track(authority: &Pubkey, council: &Pubkey, proposal_id: u32, deadline: i64) -> Instruction {
+        build(
+            accounts::FastTrack {
+                authority: *authority,
+                council: *council,
+                config: config_address(),
+                proposal_acc: proposal_address(authority, proposal_id),
+            },
+            instruction::FastTrack { deadline },
+        )
+    }
+
+    /// As [`cast_choice`], for a council member; `member` signs and pays.
+    pub fn cast_council_choice(
+        member: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_COUNCIL_CHOICE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastCouncilChoice {
+                payer: *member,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                council_chamber: council_chamber_address(&proposal),
+                council_record: council_record_address(&proposal, member),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+            },
+            instruction::CastCouncilChoice {
+                computation_offset,
+                _id: proposal_id,
+                choice_0: ballot.ciphertexts[0],
+                choice_1: ballot.ciphertexts[1],
+                choice_2: ballot.ciphertexts[2],
+                choice_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`reveal_outcome`], for a proposal with a council chamber.
+    pub fn reveal_chambers(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_CHAMBERS, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealChambers {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+                council_chamber: council_chamber_address(&proposal),
+            },
+            instruction::RevealChambers {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// `wallet` must sign and pays the session's rent.
+    pub fn open_session(wallet: &Pubkey, proposal: &Pubkey, session_key: &Pubkey, expires_at: i64) -> Instruction {
+        build(
+            accounts::OpenSession {
+                wallet: *wallet,
+                proposal_acc: *proposal,
+                session: session_address(proposal, wallet),
+                system_program: system_program::ID,
+            },
+            instruction::OpenSession {
+                session_key: *session_key,
+                expires_at,
+            },
+        )
+    }
+
+    /// `signer` is the wallet or the session key; the rent goes to the wallet.
+    pub fn close_session(signer: &Pubkey, proposal: &Pubkey, wallet: &Pubkey) -> Instruction {
+        build(
+            accounts::CloseSession {
+                signer: *signer,
+                wallet: *wallet,
+                session: session_address(proposal, wallet),
+            },
+            instruction::CloseSession {},
+        )
+    }
+
+    pub fn open_dataless_records(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::OpenDatalessRecords {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::OpenDatalessRecords {},
+        )
+    }
+
+    /// `voter_token_account` is the voter's account of the quorum mint,
+    /// required when the proposal uses `QuorumKind::SupplyBps`.
+    pub fn cast_vote(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        cast_vote_as(
+            voter,
+            authority,
+            proposal_id,
+            cluster_offset,
+            computation_offset,
+            ballot,
+            voter_token_account,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// As [`cast_vote`], on a credential-gated proposal where `voter`
+    /// presented the credential with `serial`.
+    pub fn cast_vote_with_credential(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+        serial: &[u8; 32],
+    ) -> Instruction {
+        let credential = credential_address(&proposal_address(authority, proposal_id), serial);
+        cast_vote_as(
+            voter,
+            authority,
+            proposal_id,
+            cluster_offset,
+            computation_offset,
+            ballot,
+            voter_token_account,
+            Some(credential),
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// As [`cast_vote`] (or, with `serial`, [`cast_vote_with_credential`])
+    /// on a burn-to-vote proposal: burns a token of `burn_mint`, owned by
+    /// `token_program`, from `voter`'s `burn_token_account`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_burning(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+        serial: Option<&[u8; 32]>,
+        burn_token_account: &Pubkey,
+        burn_mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let proposal = proposal_address(authority, proposal_id);
+        cast_vote_as(
+            voter,
+            authority,
+            proposal_id,
+            cluster_offset,
+            computation_offset,
+            ballot,
+            voter_token_account,
+            serial.map(|serial| credential_address(&proposal, serial)),
+            Some((*burn_token_account, *burn_mint, *token_program)),
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// As [`cast_vote`] (or, with `serial`, [`cast_vote_with_credential`])
+    /// on a proposal with an eligibility oracle: `oracle_accounts` are
+    /// forwarded to the `oracle` program's `can_vote`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_with_oracle(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+        serial: Option<&[u8; 32]>,
+        oracle: &Pubkey,
+        oracle_accounts: &[Pubkey],
+    ) -> Instruction {
+        let proposal = proposal_address(authority, proposal_id);
+        let mut ix = cast_vote_as(
+            voter,
+            authority,
+            proposal_id,
+            cluster_offset,
+            computation_offset,
+            ballot,
+            voter_token_account,
+            serial.map(|serial| credential_address(&proposal, serial)),
+            None,
+            Some(*oracle),
+            None,
+            None,
+        );
+        ix.accounts.extend(
+            oracle_accounts
+                .iter()
+                .map(|account| AccountMeta::new_readonly(*account, false)),
+        );
+        ix
+    }
+
+    /// As [`cast_vote`] (or, with `serial`, [`cast_vote_with_credential`])
+    /// on a token-weighted proposal: `weight_token_account` is `voter`'s
+    /// account of the weight mint.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_token_weighted(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+        serial: Option<&[u8; 32]>,
+        weight_token_account: &Pubkey,
+    ) -> Instruction {
+        let proposal = proposal_address(authority, proposal_id);
+        cast_vote_as(
+            voter,
+            authority,
+            proposal_id,
+            cluster_offset,
+            computation_offset,
+            ballot,
+            voter_token_account,
+            serial.map(|serial| credential_address(&proposal, serial)),
+            None,
+            None,
+            Some(*weight_token_account),
+            None,
+        )
+    }
+
+    /// As [`cast_vote`] on a collection-gated proposal: `nft_token_account`
+    /// is `voter`'s account of the NFT `nft_mint`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_with_nft(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        nft_token_account: &Pubkey,
+        nft_mint: &Pubkey,
+    ) -> Instruction {
+        cast_vote_as(
+            voter,
+            authority,
+            proposal_id,
+            cluster_offset,
+            computation_offset,
+            ballot,
+            Some(*nft_token_account),
+            None,
+            None,
+            None,
+            None,
+            Some(*nft_mint),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn cast_vote_as(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+        credential: Option<Pubkey>,
+        burn: Option<(Pubkey, Pubkey, Pubkey)>,
+        eligibility_oracle: Option<Pubkey>,
+        weight_token_account: Option<Pubkey>,
+        nft_mint: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastVote {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                credential,
+                mix_queue: Some(mix_queue_address(&proposal)),
+                slot_hashes: Some(sysvar::slot_hashes::ID),
+                voter_credits: Some(voter_credits_address(&proposal, voter)),
+                credit_account: Some(credit_account_address(&proposal, voter)),
+                burn_token_account: burn.map(|(account, _, _)| account),
+                burn_mint: burn.map(|(_, mint, _)| mint),
+                token_program: burn.map(|(_, _, program)| program),
+                eligibility_oracle,
+                allowlist: Some(allowlist_address(&proposal)),
+                ballot_commitment: Some(ballot_commitment_address(&proposal, voter)),
+                weight_token_account,
+                nft_metadata: nft_mint.as_ref().map(nft_metadata_address),
+                nft_ballot: nft_mint.map(|mint| nft_ballot_address(&proposal, &mint)),
+            },
+            instruction::CastVote {
+                computation_offset,
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], on a proposal keeping data-less records.
+    pub fn cast_vote_dataless(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastVoteDataless {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastVoteDataless {
+                computation_offset,
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], signed by `session_key` for `wallet`'s ballot;
+    /// `serial` names the wallet's presented credential on a gated proposal.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_session(
+        session_key: &Pubkey,
+        wallet: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+        serial: Option<&[u8; 32]>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastVoteSession {
+                payer: *session_key,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                session: session_address(&proposal, wallet),
+                voter_record: voter_record_address(&proposal, wallet),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                credential: serial.map(|serial| credential_address(&proposal, serial)),
+                mix_queue: Some(mix_queue_address(&proposal)),
+                slot_hashes: Some(sysvar::slot_hashes::ID),
+                voter_credits: Some(voter_credits_address(&proposal, wallet)),
+                credit_account: Some(credit_account_address(&proposal, wallet)),
+            },
+            instruction::CastVoteSession {
+                computation_offset,
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// One [`cast_vote`] per `(proposal, ballot)` in a single instruction;
+    /// the `i`-th is queued at `computation_offset + i`.
+    pub fn cast_votes_batch(
+        voter: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+        votes: &[(Pubkey, EncryptedBallot)],
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_VOTE, computation_offset);
+        let mut ix = build(
+            accounts::CastVotesBatch {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+            },
+            instruction::CastVotesBatch {
+                computation_offset,
+                votes: votes
+                    .iter()
+                    .map(|(_, ballot)| BatchedVote {
+                        ciphertexts: ballot.ciphertexts,
+                        encryption_pubkey: ballot.encryption_pubkey,
+                        nonce: ballot.nonce,
+                    })
+                    .collect(),
+            },
+        );
+        for (i, (proposal, _)) in votes.iter().enumerate() {
+            let computation_account =
+                arcium_pda::computation_acc(cluster_offset, computation_offset.wrapping_add(i as u64));
+            ix.accounts.extend([
+                AccountMeta::new(*proposal, false),
+                AccountMeta::new_readonly(tally_address(proposal), false),
+                AccountMeta::new(voter_record_address(proposal, voter), false),
+                AccountMeta::new(computation_account, false),
+            ]);
+        }
+        ix
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Likert` proposal; the ballot
+    /// carries the four offset scores.
+    pub fn cast_likert(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_LIKERT, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastLikert {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastLikert {
+                computation_offset,
+                _id: proposal_id,
+                score_0: ballot.ciphertexts[0],
+                score_1: ballot.ciphertexts[1],
+                score_2: ballot.ciphertexts[2],
+                score_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::YesNoAbstain` proposal; the
+    /// ballot carries the one-hot choice.
+    pub fn cast_choice(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_CHOICE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastChoice {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastChoice {
+                computation_offset,
+                _id: proposal_id,
+                choice_0: ballot.ciphertexts[0],
+                choice_1: ballot.ciphertexts[1],
+                choice_2: ballot.ciphertexts[2],
+                choice_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_choice`], for a proposal on `on_ballot` once it keeps
+    /// round records.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_round_choice(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        on_ballot: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_ROUND_CHOICE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastRoundChoice {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                round_record: round_record_address(on_ballot, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastRoundChoice {
+                computation_offset,
+                _id: proposal_id,
+                choice_0: ballot.ciphertexts[0],
+                choice_1: ballot.ciphertexts[1],
+                choice_2: ballot.ciphertexts[2],
+                choice_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_choice`], on `proposals` of the same `Ballot` at once,
+    /// in the order of `ballot`'s choices.
+    pub fn cast_ballot_choices(
+        voter: &Pubkey,
+        proposals: &[Pubkey],
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallotChoices,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_BALLOT_CHOICES, computation_offset);
+        let mut ix = build(
+            accounts::CastBallotChoices {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+            },
+            instruction::CastBallotChoices {
+                computation_offset,
+                choices: ballot.ciphertexts,
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        );
+        for proposal in proposals {
+            ix.accounts.extend([
+                AccountMeta::new(*proposal, false),
+                AccountMeta::new(tally_address(proposal), false),
+                AccountMeta::new(voter_record_address(proposal, voter), false),
+            ]);
+        }
+        ix
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Approval` proposal; the
+    /// ballot carries a 0 or 1 per option.
+    pub fn cast_approval(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_APPROVAL, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastApproval {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastApproval {
+                computation_offset,
+                _id: proposal_id,
+                approve_0: ballot.ciphertexts[0],
+                approve_1: ballot.ciphertexts[1],
+                approve_2: ballot.ciphertexts[2],
+                approve_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Borda` proposal; the ballot
+    /// carries the option at each place, best first.
+    pub fn cast_borda(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_BORDA, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastBorda {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastBorda {
+                computation_offset,
+                _id: proposal_id,
+                rank_0: ballot.ciphertexts[0],
+                rank_1: ballot.ciphertexts[1],
+                rank_2: ballot.ciphertexts[2],
+                rank_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Range` proposal; the ballot
+    /// carries a 0..=10 score per option.
+    pub fn cast_range(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_RANGE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastRange {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+            },
+            instruction::CastRange {
+                computation_offset,
+                _id: proposal_id,
+                score_0: ballot.ciphertexts[0],
+                score_1: ballot.ciphertexts[1],
+                score_2: ballot.ciphertexts[2],
+                score_3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// As [`cast_vote`], for a `BallotKind::Pairwise` round.
+    pub fn cast_pairwise(
+        voter: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        ballot: &EncryptedBallot,
+        voter_token_account: Option<Pubkey>,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_CAST_PAIRWISE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CastPairwise {
+                payer: *voter,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                authority: *authority,
+                proposal_acc: proposal,
+                voter_record: voter_record_address(&proposal, voter),
+                global_stats: global_stats_address(),
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                voter_token_account,
+                pairwise_box: pairwise_box_address(&proposal),
+            },
+            instruction::CastPairwise {
+                computation_offset,
+                _id: proposal_id,
+                vote_v0: ballot.ciphertexts[0],
+                vote_v1: ballot.ciphertexts[1],
+                vote_v2: ballot.ciphertexts[2],
+                vote_v3: ballot.ciphertexts[3],
+                vote_encryption_pubkey: ballot.encryption_pubkey,
+                vote_nonce: ballot.nonce,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn reveal_results(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_RESULTS, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealResults {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::RevealResults {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign; the proposal must take
+    /// For/Against/Abstain ballots.
+    pub fn reveal_outcome(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_OUTCOME, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealOutcome {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::RevealOutcome {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// As [`reveal_outcome`], for a `BallotKind::BudgetBox` proposal.
+    pub fn reveal_budget_box(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_BUDGET_BOX, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealBudgetBox {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::RevealBudgetBox {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// As [`reveal_outcome`], for a `BallotKind::Pairwise` round.
+    pub fn reveal_pairwise(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_PAIRWISE, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::RevealPairwise {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                global_stats: global_stats_address(),
+                pairwise_box: pairwise_box_address(&proposal),
+            },
+            instruction::RevealPairwise {
+                computation_offset,
+                id: proposal_id,
+            },
+        )
+    }
+
+    /// Only `payer` signs; `proposal` must be a poll.
+    pub fn reveal_poll(
+        payer: &Pubkey,
+        proposal: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_RESULTS, computation_offset);
+        build(
+            accounts::RevealPoll {
+                payer: *payer,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: *proposal,
+                tally_acc: tally_address(proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::RevealPoll { computation_offset },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn check_quorum_progress(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal_id: u32,
+        cluster_offset: u32,
+        computation_offset: u64,
+        vote_threshold: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_QUORUM_PROGRESS, computation_offset);
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CheckQuorumProgress {
+                payer: *payer,
+                authority: *authority,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::CheckQuorumProgress {
+                computation_offset,
+                _id: proposal_id,
+                vote_threshold,
+            },
+        )
+    }
+
+    /// `payer` and `authority` must sign.
+    pub fn appoint_trustees(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        trustees: Vec<Pubkey>,
+        threshold: u8,
+        grace_period: i64,
+    ) -> Instruction {
+        build(
+            accounts::AppointTrustees {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                trustee_set: trustee_set_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::AppointTrustees {
+                trustees,
+                threshold,
+                grace_period,
+            },
+        )
+    }
+
+    /// `trustee` must sign.
+    pub fn approve_reveal(trustee: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::ApproveReveal {
+                trustee: *trustee,
+                proposal_acc: *proposal,
+                trustee_set: trustee_set_address(proposal),
+            },
+            instruction::ApproveReveal {},
+        )
+    }
+
+    /// `payer` and `trustee` must sign.
+    pub fn force_reveal(
+        payer: &Pubkey,
+        trustee: &Pubkey,
+        proposal: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_RESULTS, computation_offset);
+        build(
+            accounts::ForceReveal {
+                payer: *payer,
+                trustee: *trustee,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: *proposal,
+                tally_acc: tally_address(proposal),
+                global_stats: global_stats_address(),
+                trustee_set: trustee_set_address(proposal),
+            },
+            instruction::ForceReveal { computation_offset },
+        )
+    }
+
+    /// `authority` must sign and receives the rent.
+    pub fn close_trustee_set(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CloseTrusteeSet {
+                authority: *authority,
+                proposal_acc: *proposal,
+                trustee_set: trustee_set_address(proposal),
+            },
+            instruction::CloseTrusteeSet {},
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn set_co_authors(authority: &Pubkey, proposal: &Pubkey, co_authors: Vec<Pubkey>) -> Instruction {
+        build(
+            accounts::SetCoAuthors {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::SetCoAuthors { co_authors },
+        )
+    }
+
+    /// `signer` must be the authority or a co-author.  Pass the proposal's
+    /// `category`, if it has one.
+    pub fn extend_deadline(
+        signer: &Pubkey,
+        proposal: &Pubkey,
+        category: Option<u16>,
+        new_deadline: i64,
+    ) -> Instruction {
+        build(
+            accounts::ExtendDeadline {
+                signer: *signer,
+                proposal_acc: *proposal,
+                category_acc: category.map(category_address),
+                config: config_address(),
+            },
+            instruction::ExtendDeadline { new_deadline },
+        )
+    }
+
+    /// `payer` and `co_author` must sign.
+    pub fn co_author_reveal(
+        payer: &Pubkey,
+        co_author: &Pubkey,
+        proposal: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_RESULTS, computation_offset);
+        build(
+            accounts::CoAuthorReveal {
+                payer: *payer,
+                co_author: *co_author,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: *proposal,
+                tally_acc: tally_address(proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::CoAuthorReveal { computation_offset },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn set_reveal_operator(authority: &Pubkey, proposal: &Pubkey, operator: Pubkey) -> Instruction {
+        build(
+            accounts::SetRevealOperator {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::SetRevealOperator { operator },
+        )
+    }
+
+    /// `payer` and `operator` must sign.
+    pub fn operator_reveal(
+        payer: &Pubkey,
+        operator: &Pubkey,
+        proposal: &Pubkey,
+        cluster_offset: u32,
+        computation_offset: u64,
+    ) -> Instruction {
+        let q = QueueAccounts::new(cluster_offset, COMP_DEF_OFFSET_REVEAL_RESULTS, computation_offset);
+        build(
+            accounts::OperatorReveal {
+                payer: *payer,
+                operator: *operator,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: *proposal,
+                tally_acc: tally_address(proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::OperatorReveal { computation_offset },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn enable_auto_reveal(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::EnableAutoReveal {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::EnableAutoReveal {},
+        )
+    }
+
+    /// Only `payer` signs.  `attempt` is the proposal's `auto_reveals`; the
+    /// first call after the deadline uses 0.
+    pub fn auto_reveal(payer: &Pubkey, proposal: &Pubkey, cluster_offset: u32, attempt: u32) -> Instruction {
+        let q = QueueAccounts::new(
+            cluster_offset,
+            COMP_DEF_OFFSET_REVEAL_RESULTS,
+            crate::auto_reveal_offset(proposal, attempt),
+        );
+        build(
+            accounts::AutoReveal {
+                payer: *payer,
+                sign_pda_account: q.sign_pda_account,
+                mxe_account: q.mxe_account,
+                mempool_account: q.mempool_account,
+                executing_pool: q.executing_pool,
+                computation_account: q.computation_account,
+                comp_def_account: q.comp_def_account,
+                cluster_account: q.cluster_account,
+                pool_account: q.pool_account,
+                clock_account: q.clock_account,
+                system_program: system_program::ID,
+                arcium_program: ARCIUM_PROG_ID,
+                proposal_acc: *proposal,
+                tally_acc: tally_address(proposal),
+                global_stats: global_stats_address(),
+            },
+            instruction::AutoReveal {},
+        )
+    }
+
+    pub fn mark_quorum_failed(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::MarkQuorumFailed {
+                payer: *payer,
+                proposal_acc: *proposal,
+            },
+            instruction::MarkQuorumFailed {},
+        )
+    }
+
+    pub fn expire_reveal(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::ExpireReveal {
+                payer: *payer,
+                proposal_acc: *proposal,
+                config: config_address(),
+            },
+            instruction::ExpireReveal {},
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn abort_reveal(authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::AbortReveal {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::AbortReveal {},
+        )
+    }
+
+    /// `payer` and `authority` must sign; `authority` owns
+    /// `authority_token_account` (of `mint`).
+    pub fn create_reward_pool(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        mint: &Pubkey,
+        authority_token_account: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        build(
+            accounts::CreateRewardPool {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                mint: *mint,
+                authority_token_account: *authority_token_account,
+                reward_pool: reward_pool_address(proposal),
+                reward_vault: reward_vault_address(proposal),
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateRewardPool { amount },
+        )
+    }
+
+    /// Permissionless; the remainder goes to `authority_token_account`,
+    /// which must belong to the proposal authority.
+    pub fn settle_rewards(
+        payer: &Pubkey,
+        proposal: &Pubkey,
+        authority_token_account: &Pubkey,
+    ) -> Instruction {
+        build(
+            accounts::SettleRewards {
+                payer: *payer,
+                proposal_acc: *proposal,
+                reward_pool: reward_pool_address(proposal),
+                reward_vault: reward_vault_address(proposal),
+                authority_token_account: *authority_token_account,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::SettleRewards {},
+        )
+    }
+
+    /// `voter` must sign.
+    pub fn claim_reward(
+        voter: &Pubkey,
+        proposal: &Pubkey,
+        voter_token_account: &Pubkey,
+    ) -> Instruction {
+        build(
+            accounts::ClaimReward {
+                voter: *voter,
+                reward_pool: reward_pool_address(proposal),
+                reward_vault: reward_vault_address(proposal),
+                voter_record: voter_record_address(proposal, voter),
+                voter_token_account: *voter_token_account,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::ClaimReward {},
+        )
+    }
+
+    /// `payer` and `authority` must sign; `authority` owns
+    /// `authority_token_account` (of `mint`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_matching_pool(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        mint: &Pubkey,
+        authority_token_account: &Pubkey,
+        amount: u64,
+        recipients: Vec<Pubkey>,
+    ) -> Instruction {
+        build(
+            accounts::CreateMatchingPool {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                mint: *mint,
+                authority_token_account: *authority_token_account,
+                matching_pool: matching_pool_address(proposal),
+                matching_vault: matching_vault_address(proposal),
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateMatchingPool { amount, recipients },
+        )
+    }
+
+    /// Permissionless; the remainder goes to `authority_token_account`,
+    /// which must belong to the proposal authority.
+    pub fn settle_matching(
+        payer: &Pubkey,
+        proposal: &Pubkey,
+        authority_token_account: &Pubkey,
+    ) -> Instruction {
+        build(
+            accounts::SettleMatching {
+                payer: *payer,
+                proposal_acc: *proposal,
+                matching_pool: matching_pool_address(proposal),
+                matching_vault: matching_vault_address(proposal),
+                authority_token_account: *authority_token_account,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::SettleMatching {},
+        )
+    }
+
+    /// Permissionless; `recipient_token_account` must belong to the
+    /// option's recipient.
+    pub fn pay_match(
+        payer: &Pubkey,
+        proposal: &Pubkey,
+        option: u8,
+        recipient_token_account: &Pubkey,
+    ) -> Instruction {
+        build(
+            accounts::PayMatch {
+                payer: *payer,
+                matching_pool: matching_pool_address(proposal),
+                matching_vault: matching_vault_address(proposal),
+                payout: match_payout_address(proposal, option),
+                recipient_token_account: *recipient_token_account,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::PayMatch { option },
+        )
+    }
+
+    /// Permissionless once the proposal is finalized; `payer` funds the mint.
+    pub fn create_receipt_mint(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CreateReceiptMint {
+                payer: *payer,
+                proposal_acc: *proposal,
+                receipt_mint: receipt_mint_address(proposal),
+                token_program: anchor_spl::token_2022::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateReceiptMint {},
+        )
+    }
+
+    /// `voter_token_account` is the voter's Token-2022 account for
+    /// [`receipt_mint_address`].
+    pub fn claim_receipt(
+        voter: &Pubkey,
+        proposal: &Pubkey,
+        voter_token_account: &Pubkey,
+    ) -> Instruction {
+        build(
+            accounts::ClaimReceipt {
+                voter: *voter,
+                proposal_acc: *proposal,
+                voter_record: voter_record_address(proposal, voter),
+                receipt_mint: receipt_mint_address(proposal),
+                voter_token_account: *voter_token_account,
+                token_program: anchor_spl::token_2022::ID,
+            },
+            instruction::ClaimReceipt {},
+        )
+    }
+
+    /// `admin` must sign — e.g. a DAO's PDA holding the admin role.
+    pub fn update_config(admin: &Pubkey, params: ConfigParams) -> Instruction {
+        build(
+            accounts::UpdateConfig {
+                admin: *admin,
+                config: config_address(),
+            },
+            instruction::UpdateConfig { params },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn fund_sponsorship(authority: &Pubkey, proposal: &Pubkey, amount: u64) -> Instruction {
+        build(
+            accounts::FundSponsorship {
+                authority: *authority,
+                proposal_acc: *proposal,
+                system_program: system_program::ID,
+            },
+            instruction::FundSponsorship { amount },
+        )
+    }
+
+    /// `council` must sign; `treasury` must be `Config.treasury`.
+    pub fn slash_deposit(
+        council: &Pubkey,
+        proposal: &Pubkey,
+        treasury: &Pubkey,
+        reason: SlashReason,
+    ) -> Instruction {
+        build(
+            accounts::SlashDeposit {
+                council: *council,
+                config: config_address(),
+                proposal_acc: *proposal,
+                treasury: *treasury,
+            },
+            instruction::SlashDeposit { reason },
+        )
+    }
+
+    /// `payer` and `admin` must sign; `payer` funds a new registry entry.
+    pub fn set_creator(
+        payer: &Pubkey,
+        admin: &Pubkey,
+        creator: &Pubkey,
+        whitelisted: bool,
+        allowed: bool,
+    ) -> Instruction {
+        build(
+            accounts::SetCreator {
+                payer: *payer,
+                admin: *admin,
+                config: config_address(),
+                creator_record: creator_record_address(creator),
+                system_program: system_program::ID,
+            },
+            instruction::SetCreator {
+                creator: *creator,
+                whitelisted,
+                allowed,
+            },
+        )
+    }
+
+    /// `payer` and `admin` must sign; `payer` funds a new category.
+    pub fn set_category(payer: &Pubkey, admin: &Pubkey, category: u16, params: CategoryParams) -> Instruction {
+        build(
+            accounts::SetCategory {
+                payer: *payer,
+                admin: *admin,
+                config: config_address(),
+                category_acc: category_address(category),
+                system_program: system_program::ID,
+            },
+            instruction::SetCategory { category, params },
+        )
+    }
+
+    pub fn assign_category(authority: &Pubkey, proposal: &Pubkey, category: u16) -> Instruction {
+        build(
+            accounts::AssignCategory {
+                authority: *authority,
+                proposal_acc: *proposal,
+                category_acc: category_address(category),
+            },
+            instruction::AssignCategory { category },
+        )
+    }
+
+    /// `payer` and `authority` must sign.  Send it after the
+    /// `create_proposal`s of `proposals` in the same transaction.
+    pub fn create_ballot(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        id: u32,
+        title: String,
+        proposals: &[Pubkey],
+    ) -> Instruction {
+        let mut ix = build(
+            accounts::CreateBallot {
+                payer: *payer,
+                authority: *authority,
+                ballot_acc: ballot_address(authority, id),
+                system_program: system_program::ID,
+            },
+            instruction::CreateBallot { id, title },
+        );
+        ix.accounts
+            .extend(proposals.iter().map(|proposal| AccountMeta::new(*proposal, false)));
+        ix
+    }
+
+    /// `proposals` are the ballot's, in its order.
+    pub fn open_round_records(authority: &Pubkey, ballot_id: u32, proposals: &[Pubkey]) -> Instruction {
+        let mut ix = build(
+            accounts::OpenRoundRecords {
+                authority: *authority,
+                ballot_acc: ballot_address(authority, ballot_id),
+            },
+            instruction::OpenRoundRecords {},
+        );
+        ix.accounts
+            .extend(proposals.iter().map(|proposal| AccountMeta::new(*proposal, false)));
+        ix
+    }
+
+    /// Permissionless; `payer` funds the `Commitment` on the first call.
+    pub fn commit_tally(payer: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CommitTally {
+                payer: *payer,
+                proposal_acc: *proposal,
+                tally_acc: tally_address(proposal),
+                commitment: commitment_address(proposal),
+                system_program: system_program::ID,
+            },
+            instruction::CommitTally {},
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn open_sqrt_credits(authority: &Pubkey, proposal: &Pubkey, credit_mint: &Pubkey) -> Instruction {
+        build(
+            accounts::OpenSqrtCredits {
+                authority: *authority,
+                proposal_acc: *proposal,
+                credit_mint: *credit_mint,
+            },
+            instruction::OpenSqrtCredits {},
+        )
+    }
+
+    /// `voter` must sign and pays the rent.  For a proposal on a ballot,
+    /// point `voter_credits` and `credit_vault` at the ballot's
+    /// `voter_credits_address` and its vault.
+    pub fn register_credits(
+        voter: &Pubkey,
+        proposal: &Pubkey,
+        voter_token_account: &Pubkey,
+        credit_mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let voter_credits = voter_credits_address(proposal, voter);
+        build(
+            accounts::RegisterCredits {
+                voter: *voter,
+                proposal_acc: *proposal,
+                voter_token_account: *voter_token_account,
+                credit_mint: *credit_mint,
+                voter_credits,
+                credit_vault: credit_vault_address(&voter_credits),
+                token_program: *token_program,
+                system_program: system_program::ID,
+            },
+            instruction::RegisterCredits {},
+        )
+    }
+
+    /// `voter` must sign; `scope` is the proposal, or its ballot.
+    pub fn release_credits(
+        voter: &Pubkey,
+        scope: &Pubkey,
+        voter_token_account: &Pubkey,
+        credit_mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let voter_credits = voter_credits_address(scope, voter);
+        build(
+            accounts::ReleaseCredits {
+                voter: *voter,
+                voter_credits,
+                credit_mint: *credit_mint,
+                credit_vault: credit_vault_address(&voter_credits),
+                voter_token_account: *voter_token_account,
+                token_program: *token_program,
+            },
+            instruction::ReleaseCredits {},
+        )
+    }
+
+    /// `authority` must sign; `payer` pays the rent.
+    pub fn fund_credits(
+        payer: &Pubkey,
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        voter: &Pubkey,
+        credits: u64,
+    ) -> Instruction {
+        build(
+            accounts::FundCredits {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: *proposal,
+                credit_account: credit_account_address(proposal, voter),
+                system_program: system_program::ID,
+            },
+            instruction::FundCredits { voter: *voter, credits },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn open_burn_to_vote(authority: &Pubkey, proposal: &Pubkey, burn_mint: &Pubkey) -> Instruction {
+        build(
+            accounts::OpenBurnToVote {
+                authority: *authority,
+                proposal_acc: *proposal,
+                burn_mint: *burn_mint,
+            },
+            instruction::OpenBurnToVote {},
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn set_eligibility_oracle(authority: &Pubkey, proposal: &Pubkey, oracle: &Pubkey) -> Instruction {
+        build(
+            accounts::SetEligibilityOracle {
+                authority: *authority,
+                proposal_acc: *proposal,
+                oracle: *oracle,
+            },
+            instruction::SetEligibilityOracle {},
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn set_weight_curve(authority: &Pubkey, proposal: &Pubkey, curve: WeightCurve) -> Instruction {
+        build(
+            accounts::SetWeightCurve {
+                authority: *authority,
+                proposal_acc: *proposal,
+            },
+            instruction::SetWeightCurve { curve },
+        )
+    }
+
+    /// `authority` must sign.
+    pub fn open_token_weights(
+        authority: &Pubkey,
+        proposal: &Pubkey,
+        weight_mint: &Pubkey,
+        max_weight: u64,
+    ) -> Instruction {
+        build(
+            accounts::OpenTokenWeights {
+                authority: *authority,
+                proposal_acc: *proposal,
+                weight_mint: *weight_mint,
+            },
+            instruction::OpenTokenWeights { max_weight },
+        )
+    }
+
+    /// `admin` must sign.
+    pub fn withdraw_fees(admin: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+        build(
+            accounts::WithdrawFees {
+                admin: *admin,
+                config: config_address(),
+                fee_vault: fee_vault_address(),
+                recipient: *recipient,
+            },
+            instruction::WithdrawFees { amount },
+        )
+    }
+
+    /// `payer` and `authority` sign; `authority` receives the rent and
+    /// `payer` funds the tombstone.
+    pub fn close_proposal(payer: &Pubkey, authority: &Pubkey, proposal_id: u32) -> Instruction {
+        let proposal = proposal_address(authority, proposal_id);
+        build(
+            accounts::CloseProposal {
+                payer: *payer,
+                authority: *authority,
+                proposal_acc: proposal,
+                tally_acc: tally_address(&proposal),
+                closed_proposal: closed_proposal_address(&proposal),
+                config: config_address(),
+                system_program: system_program::ID,
+            },
+            instruction::CloseProposal { id: proposal_id },
+        )
+    }
+
+    /// The `ProposalStatus` comes back as return data
+    /// (`get_return_data` after the CPI).
+    pub fn get_proposal_status(proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::GetProposalStatus {
+                proposal_acc: *proposal,
+            },
+            instruction::GetProposalStatus {},
+        )
+    }
+}
+
+// ============================================================
+// Account Structs — Computation Definition Initializers
+// ============================================================
+
+#[init_computation_definition_accounts("init_tallies", payer)]
+#[derive(Accounts)]
+pub struct InitTalliesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_vote", payer)]
+#[derive(Accounts)]
+pub struct InitVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_likert", payer)]
+#[derive(Accounts)]
+pub struct InitLikertCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_jury_scores", payer)]
+#[derive(Accounts)]
+pub struct InitJuryCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_choice", payer)]
+#[derive(Accounts)]
+pub struct InitChoiceCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_ballot_choices", payer)]
+#[derive(Accounts)]
+pub struct InitBallotChoicesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_round_choice", payer)]
+#[derive(Accounts)]
+pub struct InitRoundChoiceCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_approval", payer)]
+#[derive(Accounts)]
+pub struct InitApprovalCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_results", payer)]
+#[derive(Accounts)]
+pub struct InitRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_outcome", payer)]
+#[derive(Accounts)]
+pub struct InitOutcomeCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_budget_box", payer)]
+#[derive(Accounts)]
+pub struct InitBudgetBoxCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_pairwise", payer)]
+#[derive(Accounts)]
+pub struct InitPairwiseCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_pairwise", payer)]
+#[derive(Accounts)]
+pub struct InitPairwiseRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_write_ins", payer)]
+#[derive(Accounts)]
+pub struct InitWriteInsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_write_in", payer)]
+#[derive(Accounts)]
+pub struct InitWriteInCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_write_in", payer)]
+#[derive(Accounts)]
+pub struct InitWriteInRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("release_identity", payer)]
+#[derive(Accounts)]
+pub struct InitIdentityCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_quorum_progress", payer)]
+#[derive(Accounts)]
+pub struct InitQuorumProgressCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_spend_histogram", payer)]
+#[derive(Accounts)]
+pub struct InitSpendHistogramCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_vote_histogram", payer)]
+#[derive(Accounts)]
+pub struct InitHistogramVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_spend_histogram", payer)]
+#[derive(Accounts)]
+pub struct InitHistogramRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_council_tallies", payer)]
+#[derive(Accounts)]
+pub struct InitCouncilTalliesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_council_choice", payer)]
+#[derive(Accounts)]
+pub struct InitCouncilChoiceCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_chambers", payer)]
+#[derive(Accounts)]
+pub struct InitChambersRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_rankings", payer)]
+#[derive(Accounts)]
+pub struct InitRankingsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_ranked_vote", payer)]
+#[derive(Accounts)]
+pub struct InitRankedVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_ranked", payer)]
+#[derive(Accounts)]
+pub struct InitRankedRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_preferences", payer)]
+#[derive(Accounts)]
+pub struct InitPreferencesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_condorcet", payer)]
+#[derive(Accounts)]
+pub struct InitCondorcetVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_condorcet", payer)]
+#[derive(Accounts)]
+pub struct InitCondorcetRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_star", payer)]
+#[derive(Accounts)]
+pub struct InitStarVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("reveal_star", payer)]
+#[derive(Accounts)]
+pub struct InitStarRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_borda", payer)]
+#[derive(Accounts)]
+pub struct InitBordaCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_range", payer)]
+#[derive(Accounts)]
+pub struct InitRangeCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_vote_batch", payer)]
+#[derive(Accounts)]
+pub struct InitVoteBatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_credit_bank", payer)]
+#[derive(Accounts)]
+pub struct InitCreditBankCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_vote_banked", payer)]
+#[derive(Accounts)]
+pub struct InitBankedVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("seal_budget", payer)]
+#[derive(Accounts)]
+pub struct InitSealBudgetCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("cast_vote_budgeted", payer)]
+#[derive(Accounts)]
+pub struct InitBudgetedVoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot))]
+    /// CHECK: address_lookup_table, checked by arcium program.
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: lut_program is the Address Lookup Table program.
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Configuration
+// ============================================================
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init, payer = payer,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init, payer = payer,
+        space = 8 + FeeVault::INIT_SPACE,
+        seeds = [b"fee_vault"],
+        bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ ErrorCode::InvalidAuthority,
+    )]
+    pub program: Program<'info, crate::program::PrivateVoting>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ErrorCode::InvalidAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    /// The program's upgrade authority, e.g. a multisig vault signing
+    /// through CPI; `payer` funds the accounts.
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct SetCreator<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + CreatorRecord::INIT_SPACE,
+        seeds = [b"creator", creator.as_ref()],
+        bump,
+    )]
+    pub creator_record: Account<'info, CreatorRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Account<'info, FeeVault>,
+    /// CHECK: any account may receive the withdrawn lamports.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+// ============================================================
+// Account Structs — Proposal
+// ============================================================
+
+#[queue_computation_accounts("init_tallies", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Becomes the proposal authority.  May differ from `payer`, so a
+    /// multisig vault can own proposals while a member pays.
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_TALLIES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init, payer = payer,
+        space = 8 + ProposalAccount::INIT_SPACE,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + std::mem::size_of::<TallyAccount>(),
+        seeds = [b"tally", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats"],
+        bump,
+    )]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + CreatorRecord::INIT_SPACE,
+        seeds = [b"creator", authority.key().as_ref()],
+        bump,
+    )]
+    pub creator_record: Box<Account<'info, CreatorRecord>>,
+    /// CHECK: the `ClosedProposal` `close_proposal` leaves at this address;
+    /// it must not exist.
+    #[account(
+        seeds = [b"closed_proposal", proposal_acc.key().as_ref()],
+        bump,
+        constraint = closed_proposal.data_is_empty() @ ErrorCode::ProposalIdRetired,
+    )]
+    pub closed_proposal: UncheckedAccount<'info>,
+    /// Required for `QuorumKind::SupplyBps`: its supply now is the quorum
+    /// denominator.
+    pub quorum_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
+    /// Gates the proposal to holders of `gate_threshold` of this mint.
+    pub gate_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
+}
+
+#[callback_accounts("init_tallies")]
+#[derive(Accounts)]
+pub struct InitTalliesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_TALLIES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+// ============================================================
+// Account Structs — Categories
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(category: u16)]
+pub struct SetCategory<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + Category::INIT_SPACE,
+        seeds = [b"category", category.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub category_acc: Account<'info, Category>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(category: u16)]
+pub struct AssignCategory<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        seeds = [b"category", category.to_le_bytes().as_ref()],
+        bump = category_acc.bump,
+    )]
+    pub category_acc: Account<'info, Category>,
+}
+
+// ============================================================
+// Account Structs — Ballots
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct CreateBallot<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init, payer = payer,
+        space = 8 + Ballot::INIT_SPACE,
+        seeds = [b"ballot", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub ballot_acc: Account<'info, Ballot>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Tally Commitments
+// ============================================================
+
+#[derive(Accounts)]
+pub struct CommitTally<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + Commitment::INIT_SPACE,
+        seeds = [b"commitment", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub commitment: Account<'info, Commitment>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Burn-to-Vote
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenBurnToVote<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub burn_mint: InterfaceAccount<'info, token_interface::Mint>,
+}
+
+// ============================================================
+// Account Structs — Eligibility Oracle
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SetEligibilityOracle<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    /// CHECK: any executable program; the interface it must implement is
+    /// described at `CAN_VOTE_DISCRIMINATOR`.
+    #[account(executable)]
+    pub oracle: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetWeightCurve<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+// ============================================================
+// Account Structs — Token-Weighted Voting
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenTokenWeights<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub weight_mint: InterfaceAccount<'info, token_interface::Mint>,
+}
+
+// ============================================================
+// Account Structs — Candidate Registration
+// ============================================================
+
+#[derive(Accounts)]
+pub struct RegisterCandidate<'info> {
+    /// Pays the account rent and the deposit.
+    #[account(mut)]
+    pub candidate: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = candidate,
+        space = 8 + Candidate::INIT_SPACE,
+        seeds = [b"candidate", proposal_acc.key().as_ref(), candidate.key().as_ref()],
+        bump,
+    )]
+    pub candidate_acc: Account<'info, Candidate>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCandidate<'info> {
+    #[account(mut)]
+    pub candidate: Signer<'info>,
+    /// CHECK: the candidate's proposal, checked by address; it may already
+    /// be closed, which the handler allows.
+    #[account(address = candidate_acc.proposal)]
+    pub proposal_acc: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = candidate,
+        seeds = [b"candidate", proposal_acc.key().as_ref(), candidate.key().as_ref()],
+        bump = candidate_acc.bump,
+    )]
+    pub candidate_acc: Account<'info, Candidate>,
+}
+
+// ============================================================
+// Account Structs — Option Details
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SetOptionDetails<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority @ ErrorCode::InvalidAuthority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + OptionDetails::INIT_SPACE,
+        seeds = [b"option_details", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub option_details: Account<'info, OptionDetails>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Translations
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SetTranslation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority @ ErrorCode::InvalidAuthority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + ProposalTranslations::INIT_SPACE,
+        seeds = [b"translations", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub translations: Account<'info, ProposalTranslations>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveTranslation<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one = authority @ ErrorCode::InvalidAuthority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"translations", proposal_acc.key().as_ref()],
+        bump = translations.bump,
+    )]
+    pub translations: Account<'info, ProposalTranslations>,
+}
+
+// ============================================================
+// Account Structs — Amendments
+// ============================================================
+
+#[derive(Accounts)]
+pub struct RequireParentOutcome<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmParentOutcome<'info> {
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub parent_acc: Account<'info, ProposalAccount>,
+}
+
+// ============================================================
+// Account Structs — Proposal Chains
+// ============================================================
+
+#[derive(Accounts)]
+pub struct DeclareDependency<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub parent_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + Dependency::INIT_SPACE,
+        seeds = [b"dependency", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub dependency: Account<'info, Dependency>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDependency<'info> {
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub parent_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"dependency", proposal_acc.key().as_ref()],
+        bump = dependency.bump,
+    )]
+    pub dependency: Account<'info, Dependency>,
+}
+
+// ============================================================
+// Account Structs — Sanctions
+// ============================================================
+
+#[derive(Accounts)]
+pub struct DeclareSanction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + Sanction::INIT_SPACE,
+        seeds = [b"sanction", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub sanction: Account<'info, Sanction>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSanction<'info> {
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"sanction", proposal_acc.key().as_ref()],
+        bump = sanction.bump,
+    )]
+    pub sanction: Account<'info, Sanction>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// The proposal whose deposit a `SlashDeposit` sanction takes.
+    #[account(mut)]
+    pub target_acc: Option<Account<'info, ProposalAccount>>,
+    /// CHECK: must be `Config.treasury`; only receives lamports.
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    /// The registry entry a `RemoveCreator` sanction clears.
+    #[account(mut)]
+    pub creator_record: Option<Account<'info, CreatorRecord>>,
+}
+
+// ============================================================
+// Account Structs — Write-ins
+// ============================================================
+
+#[queue_computation_accounts("init_write_ins", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct OpenWriteIns<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_WRITE_INS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + WriteInTally::INIT_SPACE,
+        seeds = [b"write_ins", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub write_in_tally: Account<'info, WriteInTally>,
+}
+
+#[callback_accounts("init_write_ins")]
+#[derive(Accounts)]
+pub struct InitWriteInsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_WRITE_INS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"write_ins", proposal_acc.key().as_ref()],
+        bump = write_in_tally.bump,
+    )]
+    pub write_in_tally: Account<'info, WriteInTally>,
+}
+
+#[queue_computation_accounts("cast_write_in", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastWriteIn<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_WRITE_IN))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        seeds = [b"write_ins", proposal_acc.key().as_ref()],
+        bump = write_in_tally.bump,
+    )]
+    pub write_in_tally: Box<Account<'info, WriteInTally>>,
+}
+
+#[callback_accounts("cast_write_in")]
+#[derive(Accounts)]
+pub struct CastWriteInCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_WRITE_IN))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"write_ins", proposal_acc.key().as_ref()],
+        bump = write_in_tally.bump,
+    )]
+    pub write_in_tally: Account<'info, WriteInTally>,
+}
+
+#[queue_computation_accounts("reveal_write_in", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct RevealWriteIn<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_WRITE_IN))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"write_ins", proposal_acc.key().as_ref()],
+        bump = write_in_tally.bump,
+    )]
+    pub write_in_tally: Account<'info, WriteInTally>,
+}
+
+#[callback_accounts("reveal_write_in")]
+#[derive(Accounts)]
+pub struct RevealWriteInCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_WRITE_IN))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"write_ins", proposal_acc.key().as_ref()],
+        bump = write_in_tally.bump,
+    )]
+    pub write_in_tally: Account<'info, WriteInTally>,
+}
+
+// ============================================================
+// Account Structs — Jury
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(juror: Pubkey)]
+pub struct SeatJuror<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + Juror::INIT_SPACE,
+        seeds = [b"juror", proposal_acc.key().as_ref(), juror.as_ref()],
+        bump,
+    )]
+    pub juror_acc: Account<'info, Juror>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseJuror<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: the juror's proposal, checked by address; it may already be
+    /// closed, which the handler allows.
+    #[account(address = juror_acc.proposal)]
+    pub proposal_acc: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority,
+        seeds = [b"juror", proposal_acc.key().as_ref(), juror_acc.juror.as_ref()],
+        bump = juror_acc.bump,
+    )]
+    pub juror_acc: Account<'info, Juror>,
+}
+
+#[queue_computation_accounts("cast_jury_scores", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastJuryScores<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_JURY_SCORES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    /// The payer's seat on the panel; only seated jurors may score.
+    #[account(
+        seeds = [b"juror", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump = juror_acc.bump,
+    )]
+    pub juror_acc: Box<Account<'info, Juror>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+#[callback_accounts("cast_jury_scores")]
+#[derive(Accounts)]
+pub struct CastJuryScoresCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_JURY_SCORES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+// ============================================================
+// Account Structs — Council chamber
+// ============================================================
+
+#[queue_computation_accounts("init_council_tallies", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct OpenCouncil<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_COUNCIL_TALLIES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + CouncilChamber::INIT_SPACE,
+        seeds = [b"council", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub council_chamber: Box<Account<'info, CouncilChamber>>,
+}
+
+#[callback_accounts("init_council_tallies")]
+#[derive(Accounts)]
+pub struct InitCouncilTalliesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_COUNCIL_TALLIES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"council", proposal_acc.key().as_ref()],
+        bump = council_chamber.bump,
+    )]
+    pub council_chamber: Box<Account<'info, CouncilChamber>>,
+}
+
+#[queue_computation_accounts("cast_council_choice", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastCouncilChoice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_COUNCIL_CHOICE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"council", proposal_acc.key().as_ref()],
+        bump = council_chamber.bump,
+    )]
+    pub council_chamber: Box<Account<'info, CouncilChamber>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"council_vote", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub council_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+}
+
+#[callback_accounts("cast_council_choice")]
+#[derive(Accounts)]
+pub struct CastCouncilChoiceCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_COUNCIL_CHOICE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"council_vote", proposal_acc.key().as_ref(), council_record.voter.as_ref()],
+        bump = council_record.bump,
+    )]
+    pub council_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"council", proposal_acc.key().as_ref()],
+        bump = council_chamber.bump,
+    )]
+    pub council_chamber: Box<Account<'info, CouncilChamber>>,
+}
+
+#[queue_computation_accounts("reveal_chambers", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealChambers<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CHAMBERS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"council", proposal_acc.key().as_ref()],
+        bump = council_chamber.bump,
+    )]
+    pub council_chamber: Box<Account<'info, CouncilChamber>>,
+}
+
+#[callback_accounts("reveal_chambers")]
+#[derive(Accounts)]
+pub struct RevealChambersCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CHAMBERS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"council", proposal_acc.key().as_ref()],
+        bump = council_chamber.bump,
+    )]
+    pub council_chamber: Box<Account<'info, CouncilChamber>>,
+}
+
+// ============================================================
+// Account Structs — Turnout Bias
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SetTurnoutBias<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+// ============================================================
+// Account Structs — Fast Track
+// ============================================================
+
+#[derive(Accounts)]
+pub struct FastTrack<'info> {
+    pub authority: Signer<'info>,
+    pub council: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = council @ ErrorCode::InvalidAuthority,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+// ============================================================
+// Account Structs — Credentials
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenCredentials<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + CredentialRegistry::INIT_SPACE,
+        seeds = [b"credentials", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub credential_registry: Account<'info, CredentialRegistry>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(voter: Pubkey)]
+pub struct IssueCredential<'info> {
+    #[account(mut)]
+    pub registrar: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        has_one = registrar,
+        seeds = [b"credentials", proposal_acc.key().as_ref()],
+        bump = credential_registry.bump,
+    )]
+    pub credential_registry: Account<'info, CredentialRegistry>,
+    #[account(
+        init, payer = registrar,
+        space = 8 + CredentialIssuance::INIT_SPACE,
+        seeds = [b"issuance", proposal_acc.key().as_ref(), voter.as_ref()],
+        bump,
+    )]
+    pub issuance: Account<'info, CredentialIssuance>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(serial: [u8; 32])]
+pub struct RevokeCredential<'info> {
+    #[account(mut)]
+    pub registrar: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        has_one = registrar,
+        seeds = [b"credentials", proposal_acc.key().as_ref()],
+        bump = credential_registry.bump,
+    )]
+    pub credential_registry: Account<'info, CredentialRegistry>,
+    #[account(
+        init_if_needed, payer = registrar,
+        space = 8 + BallotCredential::INIT_SPACE,
+        seeds = [b"credential", proposal_acc.key().as_ref(), serial.as_ref()],
+        bump,
+    )]
+    pub credential: Account<'info, BallotCredential>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(serial: [u8; 32])]
+pub struct PresentCredential<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"credentials", proposal_acc.key().as_ref()],
+        bump = credential_registry.bump,
+    )]
+    pub credential_registry: Account<'info, CredentialRegistry>,
+    #[account(
+        init, payer = payer,
+        space = 8 + BallotCredential::INIT_SPACE,
+        seeds = [b"credential", proposal_acc.key().as_ref(), serial.as_ref()],
+        bump,
+    )]
+    pub credential: Account<'info, BallotCredential>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(serial: [u8; 32])]
+pub struct VerifyCredential<'info> {
+    pub credential_registry: Account<'info, CredentialRegistry>,
+    /// CHECK: the serial's `BallotCredential`, which may not exist yet;
+    /// the handler reads it only if it does.
+    #[account(
+        seeds = [b"credential", credential_registry.proposal.as_ref(), serial.as_ref()],
+        bump,
+    )]
+    pub credential: UncheckedAccount<'info>,
+}
+
+// ============================================================
+// Account Structs — Nullifier Set
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(max_voters: u32)]
+pub struct OpenNullifierSet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"credentials", proposal_acc.key().as_ref()],
+        bump = credential_registry.bump,
+    )]
+    pub credential_registry: Account<'info, CredentialRegistry>,
+    #[account(
+        init, payer = payer,
+        space = NullifierSet::space(max_voters).min(MAX_PERMITTED_DATA_INCREASE),
+        seeds = [b"nullifiers", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub nullifier_set: Account<'info, NullifierSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GrowNullifierSet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"nullifiers", nullifier_set.proposal.as_ref()],
+        bump = nullifier_set.bump,
+    )]
+    pub nullifier_set: Account<'info, NullifierSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("cast_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32, serial: [u8; 32])]
+pub struct CastVoteAnonymous<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    #[account(
+        mut,
+        seeds = [b"credentials", proposal_acc.key().as_ref()],
+        bump = credential_registry.bump,
+    )]
+    pub credential_registry: Box<Account<'info, CredentialRegistry>>,
+    /// CHECK: the serial's `BallotCredential`, which exists only if the
+    /// serial was revoked; the handler requires it empty.
+    #[account(seeds = [b"credential", proposal_acc.key().as_ref(), serial.as_ref()], bump)]
+    pub credential: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"nullifiers", proposal_acc.key().as_ref()],
+        bump = nullifier_set.bump,
+    )]
+    pub nullifier_set: Box<Account<'info, NullifierSet>>,
+    /// CHECK: as for `cast_vote`.
+    #[account(mut, seeds = [b"mix_queue", proposal_acc.key().as_ref()], bump)]
+    pub mix_queue: Option<UncheckedAccount<'info>>,
+    /// CHECK: as for `cast_vote`, the SlotHashes sysvar.
+    #[account(address = ::anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+    /// Required once `open_compliance_roll` ran, with `sealed_identity`.
+    #[account(
+        mut,
+        seeds = [b"compliance", proposal_acc.key().as_ref()],
+        bump = compliance_roll.bump,
+    )]
+    pub compliance_roll: Option<Box<Account<'info, ComplianceRoll>>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + SealedIdentity::INIT_SPACE,
+        seeds = [b"identity", proposal_acc.key().as_ref(), serial.as_ref()],
+        bump,
+    )]
+    pub sealed_identity: Option<Box<Account<'info, SealedIdentity>>>,
+}
+
+#[derive(Accounts)]
+pub struct CheckNullifier<'info> {
+    pub nullifier_set: Account<'info, NullifierSet>,
+}
+
+#[derive(Accounts)]
+pub struct CloseNullifierSet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: the set's proposal, checked by address; it may already be
+    /// closed, which the handler allows.
+    #[account(address = nullifier_set.proposal)]
+    pub proposal_acc: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority,
+        seeds = [b"nullifiers", proposal_acc.key().as_ref()],
+        bump = nullifier_set.bump,
+    )]
+    pub nullifier_set: Account<'info, NullifierSet>,
+}
+
+// ============================================================
+// Account Structs — Compliance Roll
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenComplianceRoll<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"credentials", proposal_acc.key().as_ref()],
+        bump = credential_registry.bump,
+    )]
+    pub credential_registry: Account<'info, CredentialRegistry>,
+    #[account(
+        init, payer = payer,
+        space = 8 + ComplianceRoll::INIT_SPACE,
+        seeds = [b"compliance", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub compliance_roll: Account<'info, ComplianceRoll>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("release_identity", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct ReleaseIdentity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RELEASE_IDENTITY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"compliance", proposal_acc.key().as_ref()],
+        bump = compliance_roll.bump,
+    )]
+    pub compliance_roll: Account<'info, ComplianceRoll>,
+    #[account(constraint = sealed_identity.proposal == proposal_acc.key())]
+    pub sealed_identity: Account<'info, SealedIdentity>,
+}
+
+#[callback_accounts("release_identity")]
+#[derive(Accounts)]
+pub struct ReleaseIdentityCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RELEASE_IDENTITY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"compliance", proposal_acc.key().as_ref()],
+        bump = compliance_roll.bump,
+    )]
+    pub compliance_roll: Account<'info, ComplianceRoll>,
+    #[account(mut, constraint = sealed_identity.proposal == proposal_acc.key())]
+    pub sealed_identity: Account<'info, SealedIdentity>,
+}
+
+#[derive(Accounts)]
+pub struct CloseComplianceRoll<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: the roll's proposal, checked by address; it may already be
+    /// closed, which the handler allows.
+    #[account(address = compliance_roll.proposal)]
+    pub proposal_acc: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority,
+        seeds = [b"compliance", proposal_acc.key().as_ref()],
+        bump = compliance_roll.bump,
+    )]
+    pub compliance_roll: Account<'info, ComplianceRoll>,
+}
+
+// ============================================================
+// Account Structs — History Gating
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenHistoryGate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + HistoryGate::INIT_SPACE,
+        seeds = [b"history_gate", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub history_gate: Account<'info, HistoryGate>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(voter: Pubkey)]
+pub struct AttestHistory<'info> {
+    #[account(mut)]
+    pub attestor: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"history_gate", proposal_acc.key().as_ref()],
+        bump = history_gate.bump,
+    )]
+    pub history_gate: Account<'info, HistoryGate>,
+    #[account(
+        init, payer = attestor,
+        space = 8 + BallotCredential::INIT_SPACE,
+        seeds = [b"credential", proposal_acc.key().as_ref(), voter.as_ref()],
+        bump,
+    )]
+    pub credential: Account<'info, BallotCredential>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProveHistory<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"history_gate", proposal_acc.key().as_ref()],
+        bump = history_gate.bump,
+    )]
+    pub history_gate: Account<'info, HistoryGate>,
+    #[account(
+        init, payer = voter,
+        space = 8 + BallotCredential::INIT_SPACE,
+        seeds = [b"credential", proposal_acc.key().as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub credential: Account<'info, BallotCredential>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Allowlist
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(capacity: u16)]
+pub struct OpenAllowlist<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = Allowlist::space(capacity),
+        seeds = [b"allowlist", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub allowlist: Account<'info, Allowlist>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddVoter<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"allowlist", proposal_acc.key().as_ref()],
+        bump = allowlist.bump,
+    )]
+    pub allowlist: Account<'info, Allowlist>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveVoter<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"allowlist", proposal_acc.key().as_ref()],
+        bump = allowlist.bump,
+    )]
+    pub allowlist: Account<'info, Allowlist>,
+}
+
+// ============================================================
+// Account Structs — NFT Collections
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenCollectionGate<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+}
+
+// ============================================================
+// Account Structs — Session Keys
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenSession<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = wallet,
+        space = 8 + VotingSession::INIT_SPACE,
+        seeds = [b"session", proposal_acc.key().as_ref(), wallet.key().as_ref()],
+        bump,
+    )]
+    pub session: Account<'info, VotingSession>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSession<'info> {
+    /// The wallet or its session key.
+    #[account(
+        constraint = signer.key() == session.wallet || signer.key() == session.session_key
+            @ ErrorCode::InvalidAuthority,
+    )]
+    pub signer: Signer<'info>,
+    /// CHECK: receives the rent; checked against the session.
+    #[account(mut, address = session.wallet)]
+    pub wallet: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"session", session.proposal.as_ref(), session.wallet.as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, VotingSession>,
+}
+
+// ============================================================
+// Account Structs — Commit Phase
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenCommitPhase<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CommitBallot<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = voter,
+        space = 8 + BallotCommitment::INIT_SPACE,
+        seeds = [b"ballot_commitment", proposal_acc.key().as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub ballot_commitment: Account<'info, BallotCommitment>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Spend Histograms
+// ============================================================
+
+#[queue_computation_accounts("init_spend_histogram", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct OpenSpendHistogram<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SPEND_HISTOGRAM))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + SpendHistogram::INIT_SPACE,
+        seeds = [b"spend_histogram", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub spend_histogram: Account<'info, SpendHistogram>,
+}
+
+#[callback_accounts("init_spend_histogram")]
+#[derive(Accounts)]
+pub struct InitSpendHistogramCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SPEND_HISTOGRAM))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"spend_histogram", proposal_acc.key().as_ref()],
+        bump = spend_histogram.bump,
+    )]
+    pub spend_histogram: Account<'info, SpendHistogram>,
+}
+
+#[queue_computation_accounts("cast_vote_histogram", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastVoteHistogram<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_HISTOGRAM))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        seeds = [b"spend_histogram", proposal_acc.key().as_ref()],
+        bump = spend_histogram.bump,
+    )]
+    pub spend_histogram: Box<Account<'info, SpendHistogram>>,
+}
+
+#[callback_accounts("cast_vote_histogram")]
+#[derive(Accounts)]
+pub struct CastVoteHistogramCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE_HISTOGRAM))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"spend_histogram", proposal_acc.key().as_ref()],
+        bump = spend_histogram.bump,
+    )]
+    pub spend_histogram: Account<'info, SpendHistogram>,
+}
+
+#[queue_computation_accounts("reveal_spend_histogram", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct RevealSpendHistogram<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_SPEND_HISTOGRAM))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"spend_histogram", proposal_acc.key().as_ref()],
+        bump = spend_histogram.bump,
+    )]
+    pub spend_histogram: Account<'info, SpendHistogram>,
+}
+
+#[callback_accounts("reveal_spend_histogram")]
+#[derive(Accounts)]
+pub struct RevealSpendHistogramCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_SPEND_HISTOGRAM))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"spend_histogram", proposal_acc.key().as_ref()],
+        bump = spend_histogram.bump,
+    )]
+    pub spend_histogram: Account<'info, SpendHistogram>,
+}
+
+// ============================================================
+// Account Structs — Ranked-Choice Voting
+// ============================================================
+
+#[queue_computation_accounts("init_rankings", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct OpenRankings<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RANKINGS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + RankingTally::INIT_SPACE,
+        seeds = [b"rankings", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub rankings: Account<'info, RankingTally>,
+}
+
+#[callback_accounts("init_rankings")]
+#[derive(Accounts)]
+pub struct InitRankingsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RANKINGS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"rankings", proposal_acc.key().as_ref()],
+        bump = rankings.bump,
+    )]
+    pub rankings: Account<'info, RankingTally>,
+}
+
+#[queue_computation_accounts("cast_ranked_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastRankedVote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_RANKED_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        seeds = [b"rankings", proposal_acc.key().as_ref()],
+        bump = rankings.bump,
+    )]
+    pub rankings: Box<Account<'info, RankingTally>>,
+}
+
+#[callback_accounts("cast_ranked_vote")]
+#[derive(Accounts)]
+pub struct CastRankedVoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_RANKED_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"rankings", proposal_acc.key().as_ref()],
+        bump = rankings.bump,
+    )]
+    pub rankings: Account<'info, RankingTally>,
+}
+
+#[queue_computation_accounts("reveal_ranked", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealRanked<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RANKED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"rankings", proposal_acc.key().as_ref()],
+        bump = rankings.bump,
+    )]
+    pub rankings: Account<'info, RankingTally>,
+}
+
+#[callback_accounts("reveal_ranked")]
+#[derive(Accounts)]
+pub struct RevealRankedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RANKED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+// ============================================================
+// Account Structs — Condorcet Voting
+// ============================================================
+
+#[queue_computation_accounts("init_preferences", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct OpenPreferences<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_PREFERENCES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init, payer = payer,
+        space = 8 + PreferenceMatrix::INIT_SPACE,
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub preferences: Account<'info, PreferenceMatrix>,
+}
+
+#[callback_accounts("init_preferences")]
+#[derive(Accounts)]
+pub struct InitPreferencesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_PREFERENCES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Account<'info, PreferenceMatrix>,
+}
+
+#[queue_computation_accounts("cast_condorcet", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastCondorcet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_CONDORCET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Box<Account<'info, PreferenceMatrix>>,
+}
+
+#[callback_accounts("cast_condorcet")]
+#[derive(Accounts)]
+pub struct CastCondorcetCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_CONDORCET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Account<'info, PreferenceMatrix>,
+}
+
+#[queue_computation_accounts("reveal_condorcet", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealCondorcet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CONDORCET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Account<'info, PreferenceMatrix>,
+}
+
+#[callback_accounts("reveal_condorcet")]
+#[derive(Accounts)]
+pub struct RevealCondorcetCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_CONDORCET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Account<'info, PreferenceMatrix>,
+}
+
+// ============================================================
+// Account Structs — STAR Voting
+// ============================================================
+
+#[queue_computation_accounts("cast_star", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastStar<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_STAR))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Box<Account<'info, PreferenceMatrix>>,
+}
+
+#[callback_accounts("cast_star")]
+#[derive(Accounts)]
+pub struct CastStarCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_STAR))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Account<'info, PreferenceMatrix>,
+}
+
+#[queue_computation_accounts("reveal_star", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealStar<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_STAR))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"preferences", proposal_acc.key().as_ref()],
+        bump = preferences.bump,
+    )]
+    pub preferences: Account<'info, PreferenceMatrix>,
+}
+
+#[callback_accounts("reveal_star")]
+#[derive(Accounts)]
+pub struct RevealStarCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_STAR))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+// ============================================================
+// Account Structs — Voting
+// ============================================================
+
+#[queue_computation_accounts("cast_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`;
+    /// under `GatingMode::Collection`, their account of the NFT.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    /// Required once `open_credentials` or `open_history_gate` gated the
+    /// proposal: the payer's `BallotCredential`.
+    pub credential: Option<Box<Account<'info, BallotCredential>>>,
+    /// CHECK: the proposal's `MixQueue`, which may not exist; required and
+    /// deserialized only inside an open mix window.
+    #[account(mut, seeds = [b"mix_queue", proposal_acc.key().as_ref()], bump)]
+    pub mix_queue: Option<UncheckedAccount<'info>>,
+    /// CHECK: the SlotHashes sysvar, required with `mix_queue`; a held
+    /// ballot folds its latest hash into the queue's release seed.
+    #[account(address = ::anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+    /// CHECK: the payer's `VoterCredits`, which may not exist; required and
+    /// deserialized only once `open_sqrt_credits` ran.
+    #[account(
+        seeds = [b"credits", proposal_acc.registration_scope(proposal_acc.key()).as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_credits: Option<UncheckedAccount<'info>>,
+    /// CHECK: the payer's `CreditAccount`, which may not exist; required and
+    /// deserialized only once `fund_credits` ran.
+    #[account(seeds = [b"credit_account", proposal_acc.key().as_ref(), payer.key().as_ref()], bump)]
+    pub credit_account: Option<UncheckedAccount<'info>>,
+    /// Required once `open_burn_to_vote` ran, with `burn_mint` and
+    /// `token_program`: the payer's account of the burn mint.
+    #[account(mut)]
+    pub burn_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(mut)]
+    pub burn_mint: Option<Box<InterfaceAccount<'info, token_interface::Mint>>>,
+    pub token_program: Option<Interface<'info, token_interface::TokenInterface>>,
+    /// CHECK: required once `set_eligibility_oracle` ran: the oracle
+    /// program, matched against `proposal_acc.eligibility_oracle`.  The
+    /// accounts it reads follow as remaining accounts.
+    #[account(executable)]
+    pub eligibility_oracle: Option<UncheckedAccount<'info>>,
+    /// CHECK: the proposal's `Allowlist`, which may not exist; required and
+    /// deserialized only once `open_allowlist` gated the proposal.
+    #[account(seeds = [b"allowlist", proposal_acc.key().as_ref()], bump)]
+    pub allowlist: Option<UncheckedAccount<'info>>,
+    /// CHECK: the payer's `BallotCommitment`, which may not exist; required
+    /// and deserialized only once `open_commit_phase` ran.
+    #[account(seeds = [b"ballot_commitment", proposal_acc.key().as_ref(), payer.key().as_ref()], bump)]
+    pub ballot_commitment: Option<UncheckedAccount<'info>>,
+    /// Required once `open_token_weights` ran: the payer's account of
+    /// `proposal_acc.weight_mint`, whose balance weighs the ballot.
+    pub weight_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    /// CHECK: required once `open_collection_gate` ran: the Metaplex
+    /// metadata of the NFT in `voter_token_account`, checked by address,
+    /// owner and contents.
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: required once `open_collection_gate` ran: the NFT's
+    /// `NftBallot`, which must not exist yet; its address is checked and
+    /// it is created here.
+    #[account(mut)]
+    pub nft_ballot: Option<UncheckedAccount<'info>>,
+}
+
+#[queue_computation_accounts("cast_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CastVotesBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    /// CHECK: the first ballot's computation account; the handler swaps in
+    /// each ballot's own in turn.
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+}
+
+#[queue_computation_accounts("cast_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastVoteSession<'info> {
+    /// The session key.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        seeds = [b"session", proposal_acc.key().as_ref(), session.wallet.as_ref()],
+        bump = session.bump,
+        constraint = session.session_key == payer.key() @ ErrorCode::InvalidSession,
+    )]
+    pub session: Account<'info, VotingSession>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), session.wallet.as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// As for `cast_vote`, the wallet's account of the quorum or gate mint.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    /// As for `cast_vote`, the wallet's presented `BallotCredential`.
+    pub credential: Option<Box<Account<'info, BallotCredential>>>,
+    /// CHECK: as for `cast_vote`, the proposal's `MixQueue`.
+    #[account(mut, seeds = [b"mix_queue", proposal_acc.key().as_ref()], bump)]
+    pub mix_queue: Option<UncheckedAccount<'info>>,
+    /// CHECK: as for `cast_vote`, the SlotHashes sysvar.
+    #[account(address = ::anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+    /// CHECK: as for `cast_vote`, the wallet's `VoterCredits`.
+    #[account(
+        seeds = [b"credits", proposal_acc.registration_scope(proposal_acc.key()).as_ref(), session.wallet.as_ref()],
+        bump,
+    )]
+    pub voter_credits: Option<UncheckedAccount<'info>>,
+    /// CHECK: as for `cast_vote`, the wallet's `CreditAccount`.
+    #[account(seeds = [b"credit_account", proposal_acc.key().as_ref(), session.wallet.as_ref()], bump)]
+    pub credit_account: Option<UncheckedAccount<'info>>,
+}
+
+#[callback_accounts("cast_vote")]
+#[derive(Accounts)]
+pub struct CastVoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    /// `None` for a `cast_vote_anonymous` or `cast_vote_dataless` ballot.
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Option<Account<'info, VoterRecord>>,
+}
+
+#[queue_computation_accounts("cast_likert", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastLikert<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_LIKERT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+#[callback_accounts("cast_likert")]
+#[derive(Accounts)]
+pub struct CastLikertCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_LIKERT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+#[queue_computation_accounts("cast_choice", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastChoice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_CHOICE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+#[callback_accounts("cast_choice")]
+#[derive(Accounts)]
+pub struct CastChoiceCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_CHOICE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+#[queue_computation_accounts("cast_ballot_choices", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CastBallotChoices<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_BALLOT_CHOICES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+}
+
+#[callback_accounts("cast_ballot_choices")]
+#[derive(Accounts)]
+pub struct CastBallotChoicesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_BALLOT_CHOICES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[queue_computation_accounts("cast_approval", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastApproval<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_APPROVAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+#[callback_accounts("cast_approval")]
+#[derive(Accounts)]
+pub struct CastApprovalCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_APPROVAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+#[queue_computation_accounts("cast_borda", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastBorda<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_BORDA))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+#[callback_accounts("cast_borda")]
+#[derive(Accounts)]
+pub struct CastBordaCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_BORDA))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+#[queue_computation_accounts("cast_range", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastRange<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_RANGE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+#[callback_accounts("cast_range")]
+#[derive(Accounts)]
+pub struct CastRangeCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_RANGE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+#[queue_computation_accounts("cast_pairwise", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastPairwise<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_PAIRWISE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + VoterRecord::INIT_SPACE,
+        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + PairwiseBox::INIT_SPACE,
+        seeds = [b"pairwise_box", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub pairwise_box: Box<Account<'info, PairwiseBox>>,
+}
+
+#[callback_accounts("cast_pairwise")]
+#[derive(Accounts)]
+pub struct CastPairwiseCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_PAIRWISE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter_record.voter.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(
+        mut,
+        seeds = [b"pairwise_box", proposal_acc.key().as_ref()],
+        bump = pairwise_box.bump,
+    )]
+    pub pairwise_box: Box<Account<'info, PairwiseBox>>,
+}
+
+// ============================================================
+// Account Structs — Quorum Progress
+// ============================================================
+
+#[queue_computation_accounts("reveal_quorum_progress", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CheckQuorumProgress<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_QUORUM_PROGRESS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+#[callback_accounts("reveal_quorum_progress")]
+#[derive(Accounts)]
+pub struct RevealQuorumProgressCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_QUORUM_PROGRESS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+// ============================================================
+// Account Structs — Trustees
+// ============================================================
+
+#[derive(Accounts)]
+pub struct AppointTrustees<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + TrusteeSet::INIT_SPACE,
+        seeds = [b"trustees", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub trustee_set: Account<'info, TrusteeSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveReveal<'info> {
+    pub trustee: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"trustees", proposal_acc.key().as_ref()],
+        bump = trustee_set.bump,
+    )]
+    pub trustee_set: Account<'info, TrusteeSet>,
+}
+
+#[queue_computation_accounts("reveal_results", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ForceReveal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub trustee: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"trustees", proposal_acc.key().as_ref()],
+        bump = trustee_set.bump,
+    )]
+    pub trustee_set: Account<'info, TrusteeSet>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTrusteeSet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: the set's proposal, checked by address; it may already be
+    /// closed, which the handler allows.
+    #[account(address = trustee_set.proposal)]
+    pub proposal_acc: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority,
+        seeds = [b"trustees", proposal_acc.key().as_ref()],
+        bump = trustee_set.bump,
+    )]
+    pub trustee_set: Account<'info, TrusteeSet>,
+}
+
+// ============================================================
+// Account Structs — Co-authors
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SetCoAuthors<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendDeadline<'info> {
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    /// The proposal's category, if it has one.
+    pub category_acc: Option<Account<'info, Category>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[queue_computation_accounts("reveal_results", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CoAuthorReveal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub co_author: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+// ============================================================
+// Account Structs — Reveal Operator
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SetRevealOperator<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[queue_computation_accounts("reveal_results", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct OperatorReveal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub operator: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+// ============================================================
+// Account Structs — Reveal
+// ============================================================
+
+#[queue_computation_accounts("reveal_results", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealResults<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+#[queue_computation_accounts("reveal_results", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealPoll<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+#[derive(Accounts)]
+pub struct EnableAutoReveal<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[queue_computation_accounts("reveal_results", payer)]
+#[derive(Accounts)]
+pub struct AutoReveal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(
+            auto_reveal_offset(&proposal_acc.key(), proposal_acc.auto_reveals),
+            mxe_account,
+            ErrorCode::ClusterNotSet
+        )
+    )]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+#[callback_accounts("reveal_results")]
+#[derive(Accounts)]
+pub struct RevealResultsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[queue_computation_accounts("reveal_outcome", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealOutcome<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_OUTCOME))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+#[callback_accounts("reveal_outcome")]
+#[derive(Accounts)]
+pub struct RevealOutcomeCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_OUTCOME))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[queue_computation_accounts("reveal_budget_box", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealBudgetBox<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, ArciumSignerAccount>,
@@ -411,7 +19013,74 @@ pub struct CreateProposal<'info> {
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
     /// CHECK: computation_account
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_TALLIES))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_BUDGET_BOX))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+#[callback_accounts("reveal_budget_box")]
+#[derive(Accounts)]
+pub struct RevealBudgetBoxCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_BUDGET_BOX))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[queue_computation_accounts("reveal_pairwise", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct RevealPairwise<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_PAIRWISE))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
     pub cluster_account: Account<'info, Cluster>,
@@ -421,250 +19090,3085 @@ pub struct CreateProposal<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + PairwiseBox::INIT_SPACE,
+        seeds = [b"pairwise_box", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub pairwise_box: Box<Account<'info, PairwiseBox>>,
+}
+
+#[callback_accounts("reveal_pairwise")]
+#[derive(Accounts)]
+pub struct RevealPairwiseCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_PAIRWISE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct AbortReveal<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+// ============================================================
+// Account Structs — Maintenance
+// ============================================================
+
+#[derive(Accounts)]
+pub struct MarkQuorumFailed<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireReveal<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// ============================================================
+// Account Structs — Fee Sponsorship
+// ============================================================
+
+#[derive(Accounts)]
+pub struct FundSponsorship<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority @ ErrorCode::InvalidAuthority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Council
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SlashDeposit<'info> {
+    pub council: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = council @ ErrorCode::InvalidAuthority,
+        has_one = treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    /// CHECK: must be `Config.treasury`; only receives lamports.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+// ============================================================
+// Account Structs — Participation Rewards
+// ============================================================
+
+#[derive(Accounts)]
+pub struct CreateRewardPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority @ ErrorCode::InvalidAuthority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(
+        init, payer = payer,
+        seeds = [b"reward_vault", proposal_acc.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = reward_pool,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleRewards<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"reward_pool", proposal_acc.key().as_ref()],
+        bump = reward_pool.bump,
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, seeds = [b"reward_vault", proposal_acc.key().as_ref()], bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    /// Receives the remainder; must belong to the proposal authority.
+    #[account(
+        mut,
+        token::mint = reward_pool.mint,
+        token::authority = proposal_acc.authority,
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReward<'info> {
+    pub voter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.proposal.as_ref()],
+        bump = reward_pool.bump,
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, seeds = [b"reward_vault", reward_pool.proposal.as_ref()], bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"voter", reward_pool.proposal.as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, token::mint = reward_pool.mint)]
+    pub voter_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// ============================================================
+// Account Structs — Matching Funds
+// ============================================================
+
+#[derive(Accounts)]
+pub struct CreateMatchingPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority @ ErrorCode::InvalidAuthority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + MatchingPool::INIT_SPACE,
+        seeds = [b"matching_pool", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+    #[account(
+        init, payer = payer,
+        seeds = [b"matching_vault", proposal_acc.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = matching_pool,
+    )]
+    pub matching_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleMatching<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"matching_pool", proposal_acc.key().as_ref()],
+        bump = matching_pool.bump,
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+    #[account(mut, seeds = [b"matching_vault", proposal_acc.key().as_ref()], bump)]
+    pub matching_vault: Account<'info, TokenAccount>,
+    /// Receives the remainder; must belong to the proposal authority.
+    #[account(
+        mut,
+        token::mint = matching_pool.mint,
+        token::authority = proposal_acc.authority,
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(option: u8)]
+pub struct PayMatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"matching_pool", matching_pool.proposal.as_ref()],
+        bump = matching_pool.bump,
+    )]
+    pub matching_pool: Account<'info, MatchingPool>,
+    #[account(mut, seeds = [b"matching_vault", matching_pool.proposal.as_ref()], bump)]
+    pub matching_vault: Account<'info, TokenAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + MatchPayout::INIT_SPACE,
+        seeds = [b"match_payout", matching_pool.proposal.as_ref(), option.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub payout: Account<'info, MatchPayout>,
+    /// Must belong to the option's recipient; checked by the handler.
+    #[account(mut, token::mint = matching_pool.mint)]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Participation Receipts
+// ============================================================
+
+#[derive(Accounts)]
+pub struct CreateReceiptMint<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    /// CHECK: created and initialized as a Token-2022 mint by the handler
+    #[account(mut, seeds = [b"receipt_mint", proposal_acc.key().as_ref()], bump)]
+    pub receipt_mint: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReceipt<'info> {
+    pub voter: Signer<'info>,
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        mut,
+        seeds = [b"voter", proposal_acc.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    #[account(mut, seeds = [b"receipt_mint", proposal_acc.key().as_ref()], bump)]
+    pub receipt_mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = voter,
+        token::token_program = token_program,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// ============================================================
+// Account Structs — Close
+// ============================================================
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct CloseProposal<'info> {
+    /// Funds the tombstone.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Receives the rent, the deposit and any unspent sponsorship.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"proposal", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, close = authority, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
     #[account(
         init, payer = payer,
-        space = 8 + ProposalAccount::INIT_SPACE,
-        seeds = [b"proposal", payer.key().as_ref(), id.to_le_bytes().as_ref()],
+        space = 8 + ClosedProposal::INIT_SPACE,
+        seeds = [b"closed_proposal", proposal_acc.key().as_ref()],
         bump,
     )]
+    pub closed_proposal: Account<'info, ClosedProposal>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================
+// Account Structs — Views
+// ============================================================
+
+#[derive(Accounts)]
+pub struct GetProposalStatus<'info> {
     pub proposal_acc: Account<'info, ProposalAccount>,
 }
 
-#[callback_accounts("init_tallies")]
-#[derive(Accounts)]
-pub struct InitTalliesCallback<'info> {
-    pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_TALLIES))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
-    #[account(mut)]
-    pub proposal_acc: Account<'info, ProposalAccount>,
+// ============================================================
+// State Accounts
+// ============================================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProposalAccount {
+    pub bump: u8,
+    pub id: u32,
+    pub authority: Pubkey,
+    pub kind: ProposalKind,
+    #[max_len(MAX_TITLE_LEN)]
+    pub title: String,
+    #[max_len(4, MAX_OPTION_LEN)]
+    pub options: Vec<String>,
+    pub num_options: u8,
+    pub deadline: i64,
+    /// End of candidate registration, when voting opens; 0 when the
+    /// options were given at creation.
+    pub registration_deadline: i64,
+    pub voice_credits: u64,
+    pub ballot: BallotKind,
+    /// `Credential` once `open_credentials` gated the proposal.
+    pub gating: GatingMode,
+    /// Under `GatingMode::Token`, the mint a voter must hold at least
+    /// `gate_threshold` of; under `GatingMode::Collection`, the collection
+    /// mint a voter's NFT must be a verified member of.
+    pub gate_mint: Pubkey,
+    pub gate_threshold: u64,
+    /// Voter count, or basis points of `electorate`, per `quorum_kind`.
+    pub quorum: u32,
+    pub quorum_kind: QuorumKind,
+    /// Registered voters or snapshot token supply; 0 for `Voters`.  Under
+    /// `RegistrarBps`, credentials issued so far, fixed by the first ballot.
+    pub electorate: u64,
+    /// Token whose supply is `electorate` under `SupplyBps`.
+    pub quorum_mint: Pubkey,
+    /// Slot at creation, when `electorate` was read.
+    pub snapshot_slot: u64,
+    /// Unix time at creation.
+    pub created_at: i64,
+    /// `SupplyBps` turnout: voters' `quorum_mint` balances as of their
+    /// `cast_vote`.
+    pub turnout_weight: u64,
+    pub is_finalized: bool,
+    pub voter_count: u32,
+    /// Ballots whose `cast_vote` callback succeeded.  Unlike `voter_count`
+    /// this excludes rejected computations, and `cast_vote_anonymous`
+    /// ballots.
+    pub counted_voters: u32,
+    /// Set by `mark_quorum_failed` once the deadline passed below quorum.
+    pub quorum_failed: bool,
+    /// When the current reveal was queued; 0 when none is in flight.
+    pub reveal_pending_since: i64,
+    /// Computation account of the latest reveal queued; reveal callbacks
+    /// from any other computation are dropped.  Cleared by `abort_reveal`.
+    pub reveal_computation: Pubkey,
+    /// Revealed tallies (option_0..3, total_votes); zero until finalized.
+    pub results: [u64; 5],
+    pub winner: u8,
+    /// Decided by `reveal_outcome` for a For/Against/Abstain ballot; `None`
+    /// otherwise and until then.
+    pub outcome: Option<VoteOutcome>,
+    /// Options funded by `reveal_budget_box` for a budget box, bit i for
+    /// option i; `None` otherwise and until then.
+    pub funded: Option<u8>,
+    pub result_attestation: ResultAttestation,
+    /// A `RewardPool` was funded for this proposal.
+    pub has_reward_pool: bool,
+    /// `settle_rewards` ran; the proposal may be closed.
+    pub rewards_settled: bool,
+    /// Lamports bonded at creation, held above rent and returned by
+    /// `close_proposal`; zeroed by `slash_deposit`.
+    pub deposit: u64,
+    /// Lamports left from `fund_sponsorship` to reimburse voters' Arcium
+    /// fees, also held on this account and returned by `close_proposal`.
+    pub sponsor_balance: u64,
+    /// Ballots whose fee the sponsorship paid, fully or in part.
+    pub sponsored_votes: u32,
+    /// Seconds before the deadline from which ballots are held in the
+    /// `MixQueue`; 0 unless `open_mix_window` set it.
+    pub mix_window: i64,
+    /// Ballots held in the `MixQueue` and not yet released.
+    pub mix_held: u32,
+    /// End of the commit phase, before which ballots are only committed
+    /// with `commit_ballot`; 0 unless `open_commit_phase` set it.
+    pub commit_deadline: i64,
+    /// Set by `enable_auto_reveal`: anyone may reveal with `auto_reveal`.
+    pub auto_reveal: bool,
+    /// `auto_reveal` calls that queued a reveal; the next one's computation
+    /// offset is `auto_reveal_offset(proposal, auto_reveals)`.
+    pub auto_reveals: u32,
+    /// `total_votes` target fixed by the first `check_quorum_progress`; 0
+    /// until then.
+    pub vote_threshold: u64,
+    /// A progress check saw `total_votes` at or above `vote_threshold`.
+    pub vote_threshold_reached: bool,
+    /// When the last progress check landed; 0 if none has.
+    pub progress_checked_at: i64,
+    /// `approve_reveal` calls a reveal needs, from the proposal's
+    /// `TrusteeSet`; 0 without one.
+    pub trustee_threshold: u8,
+    /// Trustees who have approved the reveal.
+    pub trustee_approvals: u8,
+    /// Set by `open_spend_histogram`: ballots go through
+    /// `cast_vote_histogram`.
+    pub spend_histogram: bool,
+    /// Set by `require_parent_outcome`: the proposal and winner this
+    /// amendment depends on.
+    pub requires_parent_outcome: Option<ParentOutcome>,
+    /// `confirm_parent_outcome` saw the parent finalized with that winner;
+    /// ballots are accepted from then on.
+    pub parent_outcome_met: bool,
+    /// Set by `declare_dependency` and cleared by `resolve_dependency`; no
+    /// ballots are accepted in between.
+    pub awaiting_dependency: bool,
+    /// Slot the first ballot was admitted in; 0 before.  From then on the
+    /// options, deadline and budget can't change.
+    pub first_vote_slot: u64,
+    /// Registry category set by `assign_category`.
+    pub category: Option<u16>,
+    /// Keys set by `set_co_authors` that may also `extend_deadline` and
+    /// queue the reveal.
+    #[max_len(MAX_CO_AUTHORS)]
+    pub co_authors: Vec<Pubkey>,
+    /// Set by `open_sqrt_credits`: each voter's budget is the square root
+    /// of their balance of this mint, recorded in their `VoterCredits`.
+    pub credit_mint: Option<Pubkey>,
+    /// Set by `open_burn_to_vote`: each ballot burns one token of this mint.
+    pub burn_mint: Option<Pubkey>,
+    /// Set by `set_eligibility_oracle`: the program `cast_vote` asks for
+    /// each ballot's weight.
+    pub eligibility_oracle: Option<Pubkey>,
+    /// Moved on each time a reveal is queued.  Ballots carry the
+    /// generation they were cast in, and a callback from an older one is
+    /// dropped.
+    pub tally_generation: u32,
+    /// Computations queued for the proposal whose callback hasn't landed
+    /// yet, aborted or rejected ones included.
+    pub pending_computations: u32,
+    /// When the latest of them was queued.
+    pub last_queued_at: i64,
+    /// The `Ballot` grouping this proposal with others.
+    pub on_ballot: Option<Pubkey>,
+    /// Ballots folded into the `TallyAccount`, anonymous ones included.
+    pub applied_ballots: u32,
+    /// Set by `open_council`: a `CouncilChamber` votes alongside the
+    /// community, and the proposal is revealed with `reveal_chambers`.
+    pub dual_chamber: bool,
+    /// A `MatchingPool` was funded for this funding round.
+    pub has_matching_pool: bool,
+    /// `settle_matching` ran; the proposal may be closed.
+    pub matching_settled: bool,
+    /// Margin `reveal_outcome` adds, in bps, to For's majority per share of
+    /// the electorate that didn't vote; 0 unless `set_turnout_bias` set it.
+    pub turnout_bias_bps: u16,
+    /// For's share of For and Against, in bps, `reveal_outcome` requires
+    /// instead of half; `Config.emergency_supermajority_bps` once
+    /// `fast_track` ran, otherwise 0.
+    pub supermajority_bps: u16,
+    /// This proposal's bit in its ballot's `RoundRecord`s, set by
+    /// `open_round_records`; such a proposal is voted with
+    /// `cast_round_choice` only.
+    pub round_slot: Option<u8>,
+    /// Set by `set_reveal_operator`: a key that may also queue the reveal
+    /// with `operator_reveal`, and nothing else.
+    pub reveal_operator: Option<Pubkey>,
+    /// Set by `set_weight_curve`: how the `cast_vote` circuit bends
+    /// `eligibility_oracle`'s weights, or the `weight_mint` balances.
+    pub weight_curve: WeightCurve,
+    /// Set by `open_token_weights`: each `cast_vote` is weighed by the
+    /// payer's balance of this mint.
+    pub weight_mint: Option<Pubkey>,
+    /// `weight_mint`'s decimals: balances weigh in whole tokens.
+    pub weight_decimals: u8,
+    /// Whole tokens a ballot weighs at most.
+    pub max_token_weight: u64,
+    /// Ballots waiting in the `BallotBuffer` for `process_ballot_batch`.
+    pub buffered: u32,
+    /// Set by the first `fund_credits`: each voter's budget is their
+    /// `CreditAccount`.
+    pub funded_credits: bool,
+    /// Set by `open_credit_bank`: ballots go through `cast_vote_banked`,
+    /// against the voter's `VoterCreditBank` with the authority.
+    pub credit_bank: bool,
+    /// Set by the first `fund_encrypted_budget`: ballots go through
+    /// `cast_vote_budgeted`, against the voter's `EncryptedBudget`.
+    pub encrypted_budgets: bool,
+    /// Set by `open_dataless_records`: ballots go through
+    /// `cast_vote_dataless`, whose `VoterRecord`s hold no data.
+    pub dataless_records: bool,
+}
+
+impl ProposalAccount {
+    /// `key` is the authority or one of its co-authors.
+    pub fn shares_authority(&self, key: &Pubkey) -> bool {
+        *key == self.authority || self.co_authors.contains(key)
+    }
+
+    /// What a voter registers with, given this proposal's own `key`: its
+    /// `Ballot`, so one registration serves every proposal on it, or else
+    /// the proposal.
+    pub fn registration_scope(&self, key: Pubkey) -> Pubkey {
+        self.on_ballot.unwrap_or(key)
+    }
+
+    /// A queued computation may still call back: one is outstanding and the
+    /// latest was queued less than `timeout` seconds before `now`.  Past
+    /// that a callback is presumed lost, as with `expire_reveal`.
+    pub fn has_computations_in_flight(&self, now: i64, timeout: i64) -> bool {
+        self.pending_computations > 0 && now < self.last_queued_at.saturating_add(timeout)
+    }
+
+    /// A ballot has been admitted, so options, deadline and budget are
+    /// fixed.
+    pub fn is_frozen(&self) -> bool {
+        self.first_vote_slot != 0
+    }
+
+    /// An amendment whose parent outcome hasn't been confirmed yet, so
+    /// it takes no ballots.
+    pub fn awaiting_parent_outcome(&self) -> bool {
+        self.requires_parent_outcome.is_some() && !self.parent_outcome_met
+    }
+
+    /// Lifecycle state as of `now` (unix seconds).
+    pub fn state(&self, now: i64) -> ProposalState {
+        if self.is_finalized {
+            ProposalState::Finalized
+        } else if self.quorum_failed {
+            ProposalState::QuorumFailed
+        } else if now < self.registration_deadline {
+            ProposalState::Registration
+        } else if now < self.deadline {
+            ProposalState::Voting
+        } else {
+            ProposalState::VotingClosed
+        }
+    }
+
+    /// Turnout needed to reveal, in the unit of `turnout()`.  Percentages
+    /// round up, so 50% of 3 voters needs 2.
+    pub fn quorum_target(&self) -> u64 {
+        match self.quorum_kind {
+            QuorumKind::Voters => self.quorum as u64,
+            QuorumKind::RegisteredBps | QuorumKind::RegistrarBps | QuorumKind::SupplyBps => {
+                (self.electorate as u128 * self.quorum as u128).div_ceil(10_000) as u64
+            }
+        }
+    }
+
+    /// Ballots cast, or token weight behind them under `SupplyBps`.
+    pub fn turnout(&self) -> u64 {
+        match self.quorum_kind {
+            QuorumKind::Voters | QuorumKind::RegisteredBps | QuorumKind::RegistrarBps => self.voter_count as u64,
+            QuorumKind::SupplyBps => self.turnout_weight,
+        }
+    }
+
+    pub fn quorum_reached(&self) -> bool {
+        self.turnout() >= self.quorum_target()
+    }
+
+    /// Whether enough trustees approved for a reveal to be queued; always
+    /// true without a `TrusteeSet`.
+    pub fn trustees_approved(&self) -> bool {
+        self.trustee_approvals >= self.trustee_threshold
+    }
+
+    /// Multiplier `cast_vote` applies to a ballot cast at `now`.  1 unless
+    /// the ballot is `TimeWeighted`, whose percentage moves linearly from
+    /// `start_pct` when voting opens to `end_pct` at the deadline.
+    pub fn ballot_weight(&self, now: i64) -> u64 {
+        let BallotKind::TimeWeighted { start_pct, end_pct } = self.ballot else {
+            return 1;
+        };
+        let opens = self.created_at.max(self.registration_deadline);
+        let window = (self.deadline - opens).max(1) as i128;
+        let elapsed = (now - opens).clamp(0, window as i64) as i128;
+        let (start, end) = (start_pct as i128, end_pct as i128);
+        (start + (end - start) * elapsed / window) as u64
+    }
+
+    /// The largest budget a voter can hold: `voice_credits`, unless voters
+    /// get square-root credits, at most `sqrt(u64::MAX)`, or funded ones,
+    /// of any size.
+    pub fn budget_ceiling(&self) -> u64 {
+        if self.funded_credits {
+            u64::MAX
+        } else if self.credit_mint.is_some() {
+            integer_sqrt(u64::MAX)
+        } else {
+            self.voice_credits
+        }
+    }
+
+    /// Whether `MAX_VOTERS` ballots within `budget`, each weighed by
+    /// `max_token_weight` tokens at the highest time weight and, with an
+    /// eligibility oracle, `MAX_ORACLE_WEIGHT`, fit the u64 tallies.
+    /// Σ v² ≤ budget over four options caps Σ v, which `total_votes` adds
+    /// up, at 2·√budget.
+    pub fn token_weight_fits(&self, budget: u64) -> bool {
+        let time_weight = match self.ballot {
+            BallotKind::TimeWeighted { start_pct, end_pct } => u64::from(start_pct.max(end_pct)),
+            _ => 1,
+        };
+        let oracle_weight = if self.eligibility_oracle.is_some() { MAX_ORACLE_WEIGHT } else { 1 };
+        [2 * integer_sqrt(budget), time_weight, oracle_weight, u64::from(MAX_VOTERS)]
+            .into_iter()
+            .try_fold(self.max_token_weight, u64::checked_mul)
+            .is_some()
+    }
+
+    /// Whether a ballot cast at `now` is held for release after the deadline.
+    pub fn in_mix_window(&self, now: i64) -> bool {
+        self.mix_window > 0 && now >= self.deadline - self.mix_window
+    }
+
+    /// Net sentiment per option of a revealed Likert proposal.  Its
+    /// `results` hold each option's sum of offset (0..=4) scores and the
+    /// ballot count, so the offset comes back off here.
+    pub fn net_sentiment(&self) -> [i64; 4] {
+        let offset = 2 * self.results[4] as i64;
+        [0, 1, 2, 3].map(|i| self.results[i] as i64 - offset)
+    }
+
+    /// Average score per option of a revealed range proposal, in
+    /// hundredths.  Its `results` hold each option's score total and the
+    /// ballot count, both already public, so the division happens here
+    /// rather than inside MPC.  All zero without counted ballots.
+    pub fn average_scores(&self) -> [u64; 4] {
+        let ballots = self.results[4];
+        if ballots == 0 {
+            return [0; 4];
+        }
+        [0, 1, 2, 3].map(|i| (self.results[i] as u128 * 100 / ballots as u128) as u64)
+    }
+
+    /// Options a revealed budget box funds, in option order, and their
+    /// total cost.  Empty for any other proposal.
+    pub fn funded_options(&self) -> (Vec<u8>, u64) {
+        let (Some(funded), BallotKind::BudgetBox { costs, .. }) = (self.funded, self.ballot) else {
+            return (Vec::new(), 0);
+        };
+        let options: Vec<u8> = (0..4).filter(|i| funded & (1 << i) != 0).collect();
+        let spent = options.iter().map(|i| costs[*i as usize]).sum();
+        (options, spent)
+    }
+
+    /// Applications of a revealed jury proposal from the highest aggregate
+    /// score down, ties in option order.
+    pub fn jury_ranking(&self) -> Vec<u8> {
+        let mut ranking: Vec<u8> = (0..self.num_options).collect();
+        ranking.sort_by_key(|i| std::cmp::Reverse(self.results[*i as usize]));
+        ranking
+    }
+
+    /// Compact status snapshot as of `now` (unix seconds).
+    pub fn status(&self, now: i64) -> ProposalStatus {
+        let target = self.quorum_target();
+        let quorum_progress_bps = if target == 0 {
+            10_000
+        } else {
+            (self.turnout() as u128 * 10_000 / target as u128).min(10_000) as u16
+        };
+
+        ProposalStatus {
+            state: self.state(now),
+            time_remaining: self.deadline.saturating_sub(now).max(0),
+            voter_count: self.voter_count,
+            quorum: self.quorum,
+            quorum_kind: self.quorum_kind,
+            turnout: self.turnout(),
+            quorum_target: target,
+            quorum_reached: self.quorum_reached(),
+            quorum_progress_bps,
+        }
+    }
+}
+
+/// A proposal's encrypted tallies, one PDA per proposal
+/// `[b"tally", proposal]`, kept apart from `ProposalAccount` so the
+/// proposal's metadata stays small and the counters can grow.  Zero-copy:
+/// circuits read `vote_state` in place through `ArgBuilder::account`, at
+/// offset 8, just past the discriminator.
+#[account(zero_copy)]
+#[repr(C)]
+pub struct TallyAccount {
+    /// Encrypted vote tallies: 5 counters (option_0..3 + total_votes) x 32 bytes
+    pub vote_state: [[u8; 32]; 5],
+    /// Nonce `vote_state` was encrypted under, a little-endian `u128`:
+    /// bytes rather than the integer so the zero-copy layout needs no
+    /// padding.
+    pub nonce: [u8; 16],
+    /// The `ProposalAccount` these tallies belong to.
+    pub proposal: Pubkey,
+    pub bump: u8,
+}
+
+impl TallyAccount {
+    pub fn nonce(&self) -> u128 {
+        u128::from_le_bytes(self.nonce)
+    }
+
+    pub fn set_nonce(&mut self, nonce: u128) {
+        self.nonce = nonce.to_le_bytes();
+    }
+}
+
+/// The cluster's BLS signature over the `reveal_results` output, stored so
+/// the results can be verified off-chain long after the callback.  The
+/// signed message is the Borsh-encoded output (five `u64` tallies and the
+/// `u8` winner) followed by `computation_slot` and `slot_counter`, both LE.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct ResultAttestation {
+    /// Arcium cluster account whose BLS key produced `signature`.
+    pub cluster: Pubkey,
+    pub signature: [u8; 64],
+    pub computation_slot: u64,
+    pub slot_counter: u16,
+}
+
+impl Default for ResultAttestation {
+    fn default() -> Self {
+        Self {
+            cluster: Pubkey::default(),
+            signature: [0; 64],
+            computation_slot: 0,
+            slot_counter: 0,
+        }
+    }
+}
+
+/// Protocol-wide settings, one PDA per deployment `[b"config"]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub bump: u8,
+    /// May change these settings through `update_config`.
+    pub admin: Pubkey,
+    /// Lamports charged per `create_proposal`.
+    pub proposal_fee: u64,
+    /// Lamports charged per `cast_vote`.
+    pub vote_fee: u64,
+    /// Seconds a queued reveal may wait for its callback before
+    /// `expire_reveal` clears it.
+    pub reveal_timeout: i64,
+    /// Blocks `create_proposal` and `cast_vote`.  Reveals, maintenance and
+    /// closing keep working so no proposal gets stuck.
+    pub paused: bool,
+    /// Bitmask of `GatingMode::bit`s new proposals may use.
+    pub allowed_gating_modes: u8,
+    /// Lamports bonded per `create_proposal`, refunded on close.
+    pub proposal_deposit: u64,
+    /// May slash proposal deposits through `slash_deposit`.
+    pub council: Pubkey,
+    /// Receives slashed deposits.
+    pub treasury: Pubkey,
+    /// Proposals one authority may create per Solana epoch; 0 for no cap.
+    pub proposals_per_epoch: u32,
+    /// Cap for creators the admin whitelisted through `set_creator`;
+    /// 0 for no cap.
+    pub whitelisted_proposals_per_epoch: u32,
+    /// Only creators the admin allowed through `set_creator` may call
+    /// `create_proposal`.
+    pub permissioned_creation: bool,
+    /// Lamports bonded per `register_candidate`, refunded by
+    /// `close_candidate`.
+    pub candidate_deposit: u64,
+    /// Shortest voting period, in seconds from `fast_track`, a fast-tracked
+    /// proposal may have.
+    pub emergency_min_duration: i64,
+    /// For's share of For and Against, in bps, a fast-tracked proposal must
+    /// be over to pass; 0 disables `fast_track`.
+    pub emergency_supermajority_bps: u16,
+}
+
+impl Config {
+    pub fn allows(&self, mode: GatingMode) -> bool {
+        self.allowed_gating_modes & mode.bit() != 0
+    }
+
+    fn apply(&mut self, params: &ConfigParams) -> Result<()> {
+        require!(params.reveal_timeout > 0, ErrorCode::InvalidConfig);
+        require!(
+            params.emergency_min_duration >= 0
+                && (params.emergency_supermajority_bps == 0
+                    || (5_001..=10_000).contains(&params.emergency_supermajority_bps)),
+            ErrorCode::InvalidConfig
+        );
+        self.admin = params.admin;
+        self.proposal_fee = params.proposal_fee;
+        self.vote_fee = params.vote_fee;
+        self.reveal_timeout = params.reveal_timeout;
+        self.paused = params.paused;
+        self.allowed_gating_modes = params.allowed_gating_modes;
+        self.proposal_deposit = params.proposal_deposit;
+        self.council = params.council;
+        self.treasury = params.treasury;
+        self.proposals_per_epoch = params.proposals_per_epoch;
+        self.whitelisted_proposals_per_epoch = params.whitelisted_proposals_per_epoch;
+        self.permissioned_creation = params.permissioned_creation;
+        self.candidate_deposit = params.candidate_deposit;
+        self.emergency_min_duration = params.emergency_min_duration;
+        self.emergency_supermajority_bps = params.emergency_supermajority_bps;
+        Ok(())
+    }
+}
+
+/// Settings accepted by `initialize_config` and `update_config`; see
+/// `Config` for their meaning.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigParams {
+    pub admin: Pubkey,
+    pub proposal_fee: u64,
+    pub vote_fee: u64,
+    pub reveal_timeout: i64,
+    pub paused: bool,
+    pub allowed_gating_modes: u8,
+    pub proposal_deposit: u64,
+    pub council: Pubkey,
+    pub treasury: Pubkey,
+    pub proposals_per_epoch: u32,
+    pub whitelisted_proposals_per_epoch: u32,
+    pub permissioned_creation: bool,
+    pub candidate_deposit: u64,
+    pub emergency_min_duration: i64,
+    pub emergency_supermajority_bps: u16,
+}
+
+/// Settings accepted by `set_category`; see `Category` for their meaning.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CategoryParams {
+    pub name: String,
+    pub default_quorum: u32,
+    pub min_duration: i64,
+    pub max_duration: i64,
+    pub active: bool,
+}
+
+/// Who may vote on a proposal.  Each mode is one bit of
+/// `Config::allowed_gating_modes`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum GatingMode {
+    /// Any wallet may vote.
+    Open,
+    /// Only wallets that presented a registrar-issued credential, set up
+    /// by `open_credentials`.
+    Credential,
+    /// Only wallets with enough voting history or wallet age, set up by
+    /// `open_history_gate`.
+    History,
+    /// Only wallets the authority listed, set up by `open_allowlist`.
+    Allowlist,
+    /// Only wallets holding at least `gate_threshold` of `gate_mint`, set
+    /// at creation by `create_proposal`.
+    Token,
+    /// Only wallets holding an NFT of the verified collection `gate_mint`,
+    /// one ballot per NFT, set up by `open_collection_gate`.
+    Collection,
+}
+
+impl GatingMode {
+    pub const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Whether a proposal binds its outcome or is only a temperature check.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum ProposalKind {
+    /// Created by `create_proposal`: quorum, deposit, and reveal by the
+    /// authority.
+    Governance,
+    /// Created by `create_poll`: no quorum, no deposit, and revealed by
+    /// anyone through `reveal_poll`.
+    Poll,
+}
+
+/// What a ballot expresses, and so which circuit counts it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum BallotKind {
+    /// Effective votes per option within the voice credit budget; the
+    /// highest tally wins.  Cast with `cast_vote`.
+    Quadratic,
+    /// A -2..+2 score per option, revealed as net sentiment per option
+    /// for prioritization surveys.  Cast with `cast_likert`.  `winner`
+    /// is then simply the option with the highest net sentiment.
+    Likert,
+    /// One choice per voter between options 0 (For), 1 (Against) and 2
+    /// (Abstain), cast with `cast_choice` and revealed with
+    /// `reveal_outcome`.  Against above `veto_bps` basis points of turnout
+    /// vetoes the proposal however many voted For; 0 disables the veto.
+    YesNoAbstain { veto_bps: u16 },
+    /// Quadratic allocations cast with `cast_vote`, each multiplied inside
+    /// MPC by a percentage that moves linearly from `start_pct` when voting
+    /// opens to `end_pct` at the deadline.  Start high to reward early
+    /// commitment, or end low to damp last-minute swings.  Tallies are
+    /// then in hundredths of a vote.
+    TimeWeighted { start_pct: u16, end_pct: u16 },
+    /// A quadratic funding round with a CLR-style pairwise coordination
+    /// discount: each pair of voters' cross-subsidy to an option is scaled
+    /// by `discount / (discount + overlap)`, where overlap is how much the
+    /// pair backs the same options, so a ring voting in lockstep earns
+    /// little more than its members alone.  Cast with `cast_pairwise`,
+    /// revealed with `reveal_pairwise`; 0 disables the discount.
+    Pairwise { discount: u32 },
+    /// A reviewer panel: jurors seated with `seat_juror` score each
+    /// application (option) from 0 to `max_score` on each of `criteria`
+    /// criteria (at most `MAX_CRITERIA`) with `cast_jury_scores`.
+    /// `reveal_results` reveals only each application's sum over jurors
+    /// and criteria, `total_votes` is the juror count, and
+    /// `JuryRankingEvent` carries the ranking.
+    Jury { criteria: u8, max_score: u8 },
+    /// A shortlist: each voter approves up to `max_choices` options with
+    /// one vote each, cast with `cast_approval`.  Ballots approving more
+    /// are discarded inside MPC, and `total_votes` counts ballots.
+    Approval { max_choices: u8 },
+    /// Participatory budgeting: option i costs `costs[i]` out of `budget`.
+    /// Voters approve any number of options with `cast_approval`, and
+    /// `reveal_budget_box` funds the subset with the most approvals that
+    /// fits the budget, recorded in `ProposalAccount::funded`.  Costs past
+    /// the last option are 0.
+    BudgetBox { budget: u64, costs: [u64; 4] },
+    /// Instant runoff: each voter ranks every option with
+    /// `cast_ranked_vote`, and `reveal_ranked` eliminates the weakest
+    /// option round by round inside MPC until one holds a majority.  The
+    /// running tallies count first preferences; whole rankings are kept in
+    /// the proposal's `RankingTally`, opened by `open_rankings`.
+    Ranked,
+    /// Borda count: each voter ranks every option with `cast_borda`, which
+    /// adds n - 1 points for first place down to none for last, and
+    /// `reveal_results` names the option with the most points.
+    Borda,
+    /// Condorcet: each voter ranks every option with `cast_condorcet`,
+    /// which counts every head-to-head preference in the proposal's
+    /// `PreferenceMatrix`, opened by `open_preferences`.  `reveal_condorcet`
+    /// names the option that beats every other one, or flags a cycle.
+    Condorcet,
+    /// Score Then Automatic Runoff: each voter scores every option 0..=5
+    /// with `cast_star`, which adds the scores to the tallies and counts
+    /// head-to-head preferences in the proposal's `PreferenceMatrix`,
+    /// opened by `open_preferences`.  `reveal_star` runs off the two
+    /// highest-scoring options.
+    Star,
+    /// Range voting: each voter scores every option 0..=10 with
+    /// `cast_range`, and `reveal_results` names the option with the
+    /// highest total, the average scores following in
+    /// `RangeAveragesEvent`.
+    Range,
+}
+
+/// The parent an amendment waits on, and the option that must win it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct ParentOutcome {
+    pub parent: Pubkey,
+    pub winner: u8,
+}
+
+/// What one option concretely means, for execution adapters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct OptionDetail {
+    /// Account the option pays or acts on, if any.
+    pub recipient: Option<Pubkey>,
+    /// Lamports or token base units the option moves; 0 when none.
+    pub amount: u64,
+    /// Off-chain description of the option; empty when none.
+    #[max_len(MAX_METADATA_URI_LEN)]
+    pub metadata_uri: String,
+}
+
+/// A proposal's title and option labels in one locale.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub struct Translation {
+    /// BCP 47 language tag, such as `pt-BR`.
+    #[max_len(MAX_LOCALE_LEN)]
+    pub locale: String,
+    #[max_len(MAX_TITLE_LEN)]
+    pub title: String,
+    /// Entry i translates option i; later options have none.
+    #[max_len(4, MAX_OPTION_LEN)]
+    pub options: Vec<String>,
+}
+
+/// How `cast_vote` bends a voter's stake, the eligibility oracle's weight
+/// times their `weight_mint` balance, before multiplying their ballot by it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace)]
+pub enum WeightCurve {
+    /// The stake as is.
+    #[default]
+    Linear,
+    /// The stake, up to `max`.
+    Cap { max: u64 },
+    /// `floor(sqrt(stake))`: a hundred times the stake, ten times the say.
+    Sqrt,
+    /// The stake's bit length, `floor(log2(stake)) + 1`: each doubling
+    /// adds one.
+    Log,
+}
+
+impl WeightCurve {
+    /// The `curve` and `cap` arguments of the `cast_vote` circuit.
+    pub fn circuit_args(&self) -> (u64, u64) {
+        match self {
+            Self::Linear => (0, 0),
+            Self::Cap { max } => (1, *max),
+            Self::Sqrt => (2, 0),
+            Self::Log => (3, 0),
+        }
+    }
+}
+
+/// How a chained proposal's parent must be decided for it to open.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum DependencyCondition {
+    /// The parent's `reveal_outcome` was `Passed`.
+    Passes,
+    /// The parent was rejected, vetoed or failed quorum.
+    Fails,
+}
+
+/// What a `Sanction` does once its proposal passes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum SanctionAction {
+    /// Send `proposal`'s deposit to `Config.treasury`, as `slash_deposit`.
+    SlashDeposit { proposal: Pubkey, reason: SlashReason },
+    /// Clear `creator`'s `whitelisted` and `allowed` registry flags.
+    RemoveCreator { creator: Pubkey },
+}
+
+impl SanctionAction {
+    /// The proposal or creator sanctioned.
+    pub fn target(&self) -> Pubkey {
+        match self {
+            Self::SlashDeposit { proposal, .. } => *proposal,
+            Self::RemoveCreator { creator } => *creator,
+        }
+    }
+}
+
+impl DependencyCondition {
+    /// Whether `parent` has been decided this way; `false` while it is
+    /// still open.
+    pub fn is_met_by(&self, parent: &ProposalAccount) -> bool {
+        match self {
+            Self::Passes => parent.outcome == Some(VoteOutcome::Passed),
+            Self::Fails => {
+                parent.quorum_failed || matches!(parent.outcome, Some(VoteOutcome::Rejected | VoteOutcome::Vetoed))
+            }
+        }
+    }
+}
+
+/// How a For/Against/Abstain proposal was decided, computed by the
+/// `reveal_outcome` circuit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum VoteOutcome {
+    /// For beat Against without a veto.
+    Passed,
+    /// Against matched or beat For.
+    Rejected,
+    /// Against crossed the veto threshold.
+    Vetoed,
+}
+
+impl VoteOutcome {
+    /// The circuit's outcome code: 0 passed, 1 rejected, 2 vetoed.
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0 => Self::Passed,
+            1 => Self::Rejected,
+            _ => Self::Vetoed,
+        }
+    }
+}
+
+/// How `reveal_chambers` combines the community's and the council's
+/// decisions into a dual-chamber proposal's outcome.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum ChamberRule {
+    /// Both chambers must pass it.
+    BothMustPass,
+    /// Either chamber passing it is enough.
+    Either,
+    /// For's share of For and Against, averaged over the chambers with
+    /// `council_bps` basis points of weight on the council's, must be over
+    /// half, and neither chamber may have vetoed.
+    Weighted { council_bps: u16 },
+}
+
+impl ChamberRule {
+    /// The `reveal_chambers` circuit's rule code.
+    pub fn code(&self) -> u64 {
+        match self {
+            Self::BothMustPass => 0,
+            Self::Either => 1,
+            Self::Weighted { .. } => 2,
+        }
+    }
+
+    /// The council's weight under `Weighted`; 0 otherwise.
+    pub fn council_bps(&self) -> u16 {
+        match self {
+            Self::Weighted { council_bps } => *council_bps,
+            _ => 0,
+        }
+    }
+}
+
+/// How a proposal's `quorum` is read.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum QuorumKind {
+    /// At least `quorum` ballots.
+    Voters,
+    /// Ballots from `quorum` basis points of the registered electorate
+    /// given at creation.
+    RegisteredBps,
+    /// Voters holding `quorum` basis points of the mint's supply at
+    /// creation.  Balances are read when each ballot is cast, so pair it
+    /// with a non-transferable or locked governance token.
+    SupplyBps,
+    /// Ballots from `quorum` basis points of the voters the registrar
+    /// issued credentials to before the first ballot, which fixes the
+    /// count.  Ballots need the registrar's credentials.
+    RegistrarBps,
+}
+
+/// Why the council slashed a deposit, recorded in `DepositSlashedEvent`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum SlashReason {
+    Spam,
+    Duplicate,
+    /// Abusive or illegal content.
+    Abusive,
+    /// Misleading options or description.
+    Misleading,
+    Other,
+}
+
+/// Protocol fee revenue, one PDA per deployment `[b"fee_vault"]`, created
+/// with the `Config`.  The lamports it holds above rent are exactly
+/// `balance()`.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    pub bump: u8,
+    /// Every fee ever paid in.
+    pub total_collected: u64,
+    /// Paid back to their payers.
+    pub total_refunded: u64,
+    /// Taken out by the admin through `withdraw_fees`.
+    pub total_withdrawn: u64,
+}
+
+impl FeeVault {
+    /// Lamports available to `withdraw_fees`.
+    pub fn balance(&self) -> u64 {
+        self.total_collected - self.total_refunded - self.total_withdrawn
+    }
+}
+
+/// A candidate standing in an election, one PDA per proposal and candidate
+/// `[b"candidate", proposal, candidate]`.  Holds `deposit` above rent until
+/// `close_candidate`.
+#[account]
+#[derive(InitSpace)]
+pub struct Candidate {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub candidate: Pubkey,
+    /// The option the candidate's name occupies.
+    pub index: u8,
+    #[max_len(32)]
+    pub name: String,
+    pub deposit: u64,
+}
+
+/// A seat on a jury proposal's reviewer panel, one PDA per proposal and
+/// juror `[b"juror", proposal, juror]`.  Only seated jurors may
+/// `cast_jury_scores`; the proposal `authority` reclaims the rent with
+/// `close_juror`.
+#[account]
+#[derive(InitSpace)]
+pub struct Juror {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub juror: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// A credential-gated proposal's registrar, one PDA per proposal
+/// `[b"credentials", proposal]`, created by `open_credentials`.
+#[account]
+#[derive(InitSpace)]
+pub struct CredentialRegistry {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// Signs `issue_credential` and `revoke_credential`.
+    pub registrar: Pubkey,
+    /// Uncompressed alt_bn128 G2 BLS public key credentials verify under.
+    pub registrar_key: [u8; 128],
+    pub issued: u32,
+    pub presented: u32,
+    pub revoked: u32,
+    /// Set by `open_nullifier_set`: credentials are spent by
+    /// `cast_vote_anonymous`, not presented.
+    pub anonymous: bool,
+    /// Set by `open_compliance_roll`: anonymous ballots must seal their
+    /// voter's identity.
+    pub compliance_roll: bool,
+}
+
+/// A registered voter's blind credential, one PDA per proposal and voter
+/// `[b"issuance", proposal, voter]`.  Holds only blinded values, which
+/// can't be matched against the serial the voter later presents.
+#[account]
+#[derive(InitSpace)]
+pub struct CredentialIssuance {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// The voter's hashed serial times their blinding factor, in G1.
+    pub blinded_message: [u8; 64],
+    /// The registrar's signature on `blinded_message`.
+    pub blind_signature: [u8; 64],
+}
+
+/// A spent credential serial, one PDA per proposal and serial
+/// `[b"credential", proposal, serial]`, created by `present_credential`
+/// or `revoke_credential`.  Admits `holder` to `cast_vote` unless revoked.
+#[account]
+#[derive(InitSpace)]
+pub struct BallotCredential {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub serial: [u8; 32],
+    /// The presenting wallet; default for a serial revoked unpresented.
+    pub holder: Pubkey,
+    pub revoked: bool,
+}
+
+/// A history-gated proposal's bar, one PDA per proposal
+/// `[b"history_gate", proposal]`, created by `open_history_gate`.
+#[account]
+#[derive(InitSpace)]
+pub struct HistoryGate {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// Signs `attest_history`; without one only `prove_history` admits.
+    pub attestor: Option<Pubkey>,
+    /// Seconds since a wallet's first transaction, as attested.
+    pub min_wallet_age: i64,
+    /// Counted ballots on other proposals.
+    pub min_prior_ballots: u32,
+    pub admitted: u32,
+}
+
+/// An allowlisted proposal's electorate, one PDA per proposal
+/// `[b"allowlist", proposal]`, created by `open_allowlist` and sized for
+/// `capacity` voters.
+#[account]
+pub struct Allowlist {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub capacity: u16,
+    pub voters: Vec<Pubkey>,
+}
+
+impl Allowlist {
+    /// Account size, discriminator included, for `capacity` voters.
+    pub const fn space(capacity: u16) -> usize {
+        8 + 1 + 32 + 2 + 4 + 32 * capacity as usize
+    }
+}
+
+/// Spent credential nullifiers of an anonymous proposal, one PDA per
+/// proposal `[b"nullifiers", proposal]`, created by `open_nullifier_set`.
+/// The header is followed by `slots` 16-byte slots: an open-addressed hash
+/// table of `nullifier`s, zero where empty.
+#[account]
+#[derive(InitSpace)]
+pub struct NullifierSet {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// The proposal's authority, who gets the rent back.
+    pub authority: Pubkey,
+    pub max_voters: u32,
+    /// Twice `max_voters`, so the table is never more than half full.
+    pub slots: u32,
+    pub count: u32,
+}
+
+impl NullifierSet {
+    /// Bytes before the slots.
+    pub const HEADER_LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Account size of a set for `max_voters` once fully grown.
+    pub fn space(max_voters: u32) -> usize {
+        Self::HEADER_LEN + 2 * max_voters as usize * 16
+    }
+
+    pub fn full_len(&self) -> usize {
+        Self::space(self.max_voters)
+    }
+}
+
+/// An anonymous proposal's compliance key, one PDA per proposal
+/// `[b"compliance", proposal]`, created by `open_compliance_roll`.
+#[account]
+#[derive(InitSpace)]
+pub struct ComplianceRoll {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// The proposal's authority, who gets the rent back.
+    pub authority: Pubkey,
+    /// x25519 public key `release_identity` re-encrypts identities to.
+    pub compliance_key: [u8; 32],
+    /// `SealedIdentity` accounts written by `cast_vote_anonymous`.
+    pub sealed: u32,
+    /// Of those, re-encrypted to `compliance_key`.
+    pub released: u32,
+}
+
+/// A proposal's reveal trustees, one PDA per proposal
+/// `[b"trustees", proposal]`, created by `appoint_trustees`.
+#[account]
+#[derive(InitSpace)]
+pub struct TrusteeSet {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// The proposal's authority, who gets the rent back.
+    pub authority: Pubkey,
+    #[max_len(MAX_TRUSTEES)]
+    pub trustees: Vec<Pubkey>,
+    /// Bit `i` is set once `trustees[i]` approved the reveal.
+    pub approved: u8,
+    /// Seconds after the deadline the authority has to reveal before the
+    /// trustees may `force_reveal`.
+    pub grace_period: i64,
+}
+
+/// Per-option recipient, amount and metadata URI, one PDA per proposal
+/// `[b"option_details", proposal]`, written by `set_option_details`.
+#[account]
+#[derive(InitSpace)]
+pub struct OptionDetails {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// Entry i describes option i.
+    #[max_len(4)]
+    pub details: Vec<OptionDetail>,
+}
+
+/// The proposal's title and options in other languages, one PDA per
+/// proposal `[b"translations", proposal]`, written by `set_translation`.
+/// The canonical labels stay on the proposal.
+#[account]
+#[derive(InitSpace)]
+pub struct ProposalTranslations {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    #[max_len(MAX_TRANSLATIONS)]
+    pub translations: Vec<Translation>,
+}
+
+/// A chained proposal's link to its parent, one PDA per proposal
+/// `[b"dependency", proposal]`, created by `declare_dependency`.
+#[account]
+#[derive(InitSpace)]
+pub struct Dependency {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub parent: Pubkey,
+    pub condition: DependencyCondition,
+    /// `resolve_dependency` saw the parent decided as required.
+    pub resolved: bool,
+}
+
+/// The sanction a For/Against/Abstain proposal votes on, one PDA per
+/// proposal `[b"sanction", proposal]`, created by `declare_sanction`.
+#[account]
+#[derive(InitSpace)]
+pub struct Sanction {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub action: SanctionAction,
+    /// `execute_sanction` carried it out.
+    pub executed: bool,
+}
+
+/// The registered wallet an anonymous voter declared, one PDA per proposal
+/// and credential serial `[b"identity", proposal, serial]`.  The program
+/// can't match it against the registrar's `CredentialIssuance` records;
+/// the compliance key's holder does, after release.
+#[account]
+#[derive(InitSpace)]
+pub struct SealedIdentity {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// The voter's x25519 key, then the compliance key once released.
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    /// The wallet's first and last 16 bytes, each a little-endian u128.
+    pub ciphertexts: [[u8; 32]; 2],
+    pub released: bool,
+}
+
+/// A voter's registered wallet as `cast_vote_anonymous` receives it,
+/// encrypted under the ballot's key with a nonce of its own.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdentityCiphertexts {
+    pub ciphertexts: [[u8; 32]; 2],
+    pub nonce: u128,
+}
+
+/// A wallet's authorization for `session_key` to cast its ballot on one
+/// proposal, one PDA per proposal and wallet `[b"session", proposal,
+/// wallet]`, created by `open_session` and closed by `close_session`.
+#[account]
+#[derive(InitSpace)]
+pub struct VotingSession {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub wallet: Pubkey,
+    pub session_key: Pubkey,
+    /// Unix timestamp after which `cast_vote_session` refuses the key.
+    pub expires_at: i64,
+}
+
+/// Registry entry per proposal authority `[b"creator", authority]`,
+/// created by their first `create_proposal` or by the admin's
+/// `set_creator`.  Counts proposals per Solana epoch for the rate limit.
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorRecord {
+    pub bump: u8,
+    pub authority: Pubkey,
+    /// Subject to `Config.whitelisted_proposals_per_epoch` instead of
+    /// `Config.proposals_per_epoch`.
+    pub whitelisted: bool,
+    /// Epoch `epoch_proposals` refers to.
+    pub epoch: u64,
+    pub epoch_proposals: u32,
+    pub total_proposals: u64,
+    /// May create proposals while `Config.permissioned_creation` is on.
+    pub allowed: bool,
+}
+
+/// Tombstone of a closed proposal, one PDA per proposal address
+/// `[b"closed_proposal", proposal]`, created by `close_proposal`.  Its
+/// existence retires the authority's id: voter records and other accounts
+/// keyed by the old address outlive the proposal and must never be read
+/// as a new one's.
+#[account]
+#[derive(InitSpace)]
+pub struct ClosedProposal {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub id: u32,
+    pub closed_at: i64,
+}
+
+/// An admin-curated proposal category, one PDA per id
+/// `[b"category", id]`, written by `set_category`.
+#[account]
+#[derive(InitSpace)]
+pub struct Category {
+    pub bump: u8,
+    pub id: u16,
+    #[max_len(32)]
+    pub name: String,
+    /// Quorum, in voters, for an assigned proposal whose voter-count quorum
+    /// is 0.
+    pub default_quorum: u32,
+    /// Bounds on an assigned proposal's seconds from creation to deadline;
+    /// `max_duration` 0 for no upper bound.
+    pub min_duration: i64,
+    pub max_duration: i64,
+    /// Only active categories can be assigned.
+    pub active: bool,
+}
+
+/// Related proposals voted on together, such as the races of one
+/// election: one PDA per authority and id `[b"ballot", authority, id]`,
+/// written by `create_ballot`.
+#[account]
+#[derive(InitSpace)]
+pub struct Ballot {
+    pub bump: u8,
+    pub id: u32,
+    pub authority: Pubkey,
+    #[max_len(MAX_TITLE_LEN)]
+    pub title: String,
+    /// Shared by every proposal on the ballot.
+    pub deadline: i64,
+    pub registration_deadline: i64,
+    #[max_len(MAX_BALLOT_PROPOSALS)]
+    pub proposals: Vec<Pubkey>,
+    /// Set by `open_round_records`: votes are recorded in `RoundRecord`s.
+    pub round_records: bool,
+}
+
+/// Running commitment to a proposal's encrypted tally, PDA
+/// `[b"commitment", proposal]`, advanced by `commit_tally`.
+#[account]
+#[derive(InitSpace)]
+pub struct Commitment {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// Every checkpoint's leaf chained by `fold_commitment`, in order.
+    pub root: [u8; 32],
+    /// The latest checkpoint's `tally_leaf`.
+    pub leaf: [u8; 32],
+    /// `ProposalAccount::applied_ballots` at the latest checkpoint.
+    pub applied_ballots: u32,
+    pub checkpoints: u32,
+    pub committed_at: i64,
+}
+
+impl CreatorRecord {
+    /// Count one more proposal in `epoch`, failing past the creator's cap.
+    fn record_proposal(&mut self, epoch: u64, config: &Config) -> Result<()> {
+        if self.epoch != epoch {
+            self.epoch = epoch;
+            self.epoch_proposals = 0;
+        }
+        let cap = if self.whitelisted {
+            config.whitelisted_proposals_per_epoch
+        } else {
+            config.proposals_per_epoch
+        };
+        require!(cap == 0 || self.epoch_proposals < cap, ErrorCode::ProposalRateLimited);
+        self.epoch_proposals = self.epoch_proposals.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+        self.total_proposals = self.total_proposals.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+        Ok(())
+    }
+}
+
+/// Protocol-wide counters, one PDA per deployment `[b"global_stats"]`.
+/// Created by the first `create_proposal`.
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalStats {
+    pub bump: u8,
+    pub total_proposals: u64,
+    pub total_finalized: u64,
+    pub total_ballots: u64,
+    /// Callbacks whose computation output was aborted or failed verification.
+    pub total_failed_computations: u64,
+}
+
+/// A voter's ballot on a proposal, PDA `[b"voter", proposal, voter]`.  Its
+/// existence marks the vote.  `proposal` and `voter` repeat the seeds for
+/// the callbacks and `prove_history`, which find records by voter; the
+/// flags and generation are what the ballot's callback, rewards and
+/// receipts move on.  On a proposal with `dataless_records` the PDA is
+/// created with no data at all, and none of this is kept.
+#[account]
+#[derive(InitSpace)]
+pub struct VoterRecord {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// The `cast_vote` callback succeeded.  A ballot the circuit discards
+    /// as over budget still counts: the program cannot tell them apart.
+    pub counted: bool,
+    pub reward_claimed: bool,
+    pub receipt_claimed: bool,
+    /// `ProposalAccount::tally_generation` when the ballot was cast; its
+    /// callback is dropped if a reveal has moved the generation on since.
+    pub tally_generation: u32,
+    /// On a collection-gated proposal, the NFT the ballot was cast with.
+    pub nft_mint: Option<Pubkey>,
+}
+
+/// An NFT's ballot on a collection-gated proposal, PDA `[b"nft_ballot",
+/// proposal, mint]`, created by `cast_vote`.  It outlives transfers, so
+/// the NFT votes once whoever holds it.
+#[account]
+#[derive(InitSpace)]
+pub struct NftBallot {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    /// The wallet that voted with the NFT.
+    pub voter: Pubkey,
+}
+
+/// The sealed ballots of a `BallotKind::Pairwise` round, one PDA per
+/// proposal `[b"pairwise_box", proposal]`.  Slots are handed out in cast
+/// order and filled by the `cast_pairwise` callback.
+#[account]
+#[derive(InitSpace)]
+pub struct PairwiseBox {
+    pub bump: u8,
+    /// Each slot's allocation, encrypted to the cluster.  Must stay first:
+    /// `reveal_pairwise` reads the slots at fixed offsets.
+    pub ballots: [[[u8; 32]; 4]; MAX_PAIRWISE_BALLOTS],
+    pub nonces: [u128; MAX_PAIRWISE_BALLOTS],
+    /// Who holds each slot, so the callback can find the voter's.
+    pub voters: [Pubkey; MAX_PAIRWISE_BALLOTS],
+    /// Slots handed out.
+    pub count: u8,
+    /// Bit `i` is set once slot `i` holds a sealed ballot.
+    pub filled: u8,
+}
+
+/// A voter's commitment to their ballot, one PDA per proposal and voter
+/// `[b"ballot_commitment", proposal, voter]`, created by `commit_ballot`.
+#[account]
+#[derive(InitSpace)]
+pub struct BallotCommitment {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// `ballot_hash` of the ballot `cast_vote` must receive.
+    pub commitment: [u8; 32],
+    pub committed_at: i64,
+}
+
+/// One ballot of a `cast_votes_batch`, as `cast_vote` takes it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchedVote {
+    pub ciphertexts: [[u8; 32]; 4],
+    pub encryption_pubkey: [u8; 32],
+    pub nonce: u128,
+}
+
+/// A proposal's credit-spend histogram, one PDA per proposal
+/// `[b"spend_histogram", proposal]`, created by `open_spend_histogram`.
+#[account]
+#[derive(InitSpace)]
+pub struct SpendHistogram {
+    pub bump: u8,
+    /// Encrypted counts, three buckets per option.  Must stay first: the
+    /// histogram circuits read it at a fixed offset.
+    pub state: [[u8; 32]; SPEND_HISTOGRAM_COUNTS],
+    pub nonce: u128,
+    pub proposal: Pubkey,
+    /// The counts are initialized; `cast_vote_histogram` accepts ballots.
+    pub ready: bool,
+    /// Set by the `reveal_spend_histogram` callback.
+    pub revealed: bool,
+    /// The noised counts once revealed, option-major: ballots that spent
+    /// 1-9, 16-49 or 64+ credits on each option.
+    pub counts: [u64; SPEND_HISTOGRAM_COUNTS],
+}
+
+/// A ranked-choice proposal's ballots by order, one PDA per proposal
+/// `[b"rankings", proposal]`, created by `open_rankings`.
+#[account]
+#[derive(InitSpace)]
+pub struct RankingTally {
+    pub bump: u8,
+    /// Encrypted count of ballots per order of the four options.  Must
+    /// stay first: the ranked circuits read it at a fixed offset.
+    pub state: [[u8; 32]; RANKING_ORDERS],
+    pub nonce: u128,
+    pub proposal: Pubkey,
+    /// The counts are initialized; `cast_ranked_vote` accepts ballots.
+    pub ready: bool,
+}
+
+/// A Condorcet or STAR proposal's head-to-head counts, one PDA per
+/// proposal `[b"preferences", proposal]`, created by `open_preferences`.
+#[account]
+#[derive(InitSpace)]
+pub struct PreferenceMatrix {
+    pub bump: u8,
+    /// Encrypted count of ballots ranking or scoring option i above option
+    /// j, at `4 * i + j`.  Must stay first: the circuits read it at a fixed
+    /// offset.
+    pub state: [[u8; 32]; PREFERENCE_CELLS],
+    pub nonce: u128,
+    pub proposal: Pubkey,
+    /// The counts are initialized; `cast_condorcet` or `cast_star` accepts
+    /// ballots.
+    pub ready: bool,
+    /// Set by `reveal_condorcet`: no option beats every other one, so the
+    /// proposal's winner only has the most head-to-head wins.
+    pub cycle: bool,
+}
+
+/// A proposal's write-in tallies, one PDA per proposal
+/// `[b"write_ins", proposal]`, created by `open_write_ins`.
+#[account]
+#[derive(InitSpace)]
+pub struct WriteInTally {
+    pub bump: u8,
+    /// Encrypted `MAX_WRITE_INS` candidate hashes then their counts.  Must
+    /// stay first: the write-in circuits read it at a fixed offset.
+    pub state: [[u8; 32]; 2 * MAX_WRITE_INS],
+    pub nonce: u128,
+    pub proposal: Pubkey,
+    /// The table is initialized; `cast_write_in` accepts ballots.
+    pub ready: bool,
+    /// Set by the `reveal_write_in` callback.
+    pub revealed: bool,
+    /// The top write-in's candidate hash once revealed; 0 if nobody wrote
+    /// anyone in.
+    pub top_candidate: u128,
+    pub top_votes: u64,
+}
+
+/// A dual-chamber proposal's second electorate, `[b"council", proposal]`,
+/// created by `open_council`.
+#[account]
+#[derive(InitSpace)]
+pub struct CouncilChamber {
+    pub bump: u8,
+    /// Encrypted council tallies, laid out as `TallyAccount::vote_state`.
+    /// Must stay first: the council circuits read it at a fixed offset.
+    pub vote_state: [[u8; 32]; 5],
+    pub nonce: u128,
+    pub proposal: Pubkey,
+    pub rule: ChamberRule,
+    /// Wallets that may `cast_council_choice`, once each.
+    #[max_len(MAX_COUNCIL_MEMBERS)]
+    pub members: Vec<Pubkey>,
+    /// The tally is initialized; `cast_council_choice` accepts ballots.
+    pub ready: bool,
+    /// Council ballots admitted.
+    pub ballots: u32,
+    /// Revealed council tallies (For, Against, Abstain, 0, ballots); zero
+    /// until `reveal_chambers` lands.
+    pub results: [u64; 5],
+    /// Each chamber's own decision, set by the `reveal_chambers` callback;
+    /// the combined one is the proposal's `outcome`.
+    pub community_outcome: Option<VoteOutcome>,
+    pub council_outcome: Option<VoteOutcome>,
+}
+
+/// Participation reward escrow, one per proposal
+/// `[b"reward_pool", proposal]`.  Its tokens sit in the token account
+/// `[b"reward_vault", proposal]`, owned by this PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    /// Set by `settle_rewards`; claims open afterwards.
+    pub settled: bool,
+    /// Counted voters at settlement.
+    pub eligible_voters: u32,
+    /// Tokens per eligible voter.
+    pub share: u64,
+    pub claimed_voters: u32,
+}
+
+/// Matching fund escrow for a quadratic funding round, one per proposal
+/// `[b"matching_pool", proposal]`.  Its tokens sit in the token account
+/// `[b"matching_vault", proposal]`, owned by this PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct MatchingPool {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    /// Wallet paid each option's match, one per option.
+    #[max_len(4)]
+    pub recipients: Vec<Pubkey>,
+    /// Set by `settle_matching`; payouts open afterwards.
+    pub settled: bool,
+    /// Tokens matched to each option at settlement.
+    pub matches: [u64; 4],
+    /// Options paid by `pay_match`, bit i for option i.
+    pub paid: u8,
+}
+
+/// Receipt of one option's match, `[b"match_payout", proposal, option]`.
+#[account]
+#[derive(InitSpace)]
+pub struct MatchPayout {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub option: u8,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub paid_at: i64,
+}
+
+// ============================================================
+// View Types
+// ============================================================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProposalState {
+    /// Accepting candidates; voting opens at the registration deadline.
+    Registration,
+    /// Accepting ballots (before the deadline).
+    Voting,
+    /// Deadline passed, results not yet revealed.
+    VotingClosed,
+    /// Results revealed.
+    Finalized,
+    /// Deadline passed below quorum; results will never be revealed.
+    QuorumFailed,
+}
+
+/// Returned by `verify_credential`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CredentialStatus {
+    /// Not the registrar's signature on this serial.
+    Invalid,
+    /// Valid and not yet presented.
+    Unspent,
+    /// Valid and already presented.
+    Presented,
+    /// Revoked by the registrar.
+    Revoked,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProposalStatus {
+    pub state: ProposalState,
+    /// Seconds until the deadline (0 once it has passed).
+    pub time_remaining: i64,
+    pub voter_count: u32,
+    pub quorum: u32,
+    pub quorum_kind: QuorumKind,
+    pub turnout: u64,
+    pub quorum_target: u64,
+    pub quorum_reached: bool,
+    /// turnout / quorum_target in basis points, capped at 10_000.
+    pub quorum_progress_bps: u16,
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CallbackStage {
+    InitTallies,
+    CastVote,
+    RevealResults,
+    QuorumProgress,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CallbackRejectReason {
+    /// The cluster reported the computation as failed.
+    ComputationAborted,
+    /// The output signature did not verify against the cluster's BLS key.
+    VerificationFailed,
+}
+
+/// Emitted when a callback's computation output is rejected.  The proposal
+/// state is left untouched.
+#[event]
+pub struct CallbackRejectedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub stage: CallbackStage,
+    pub reason: CallbackRejectReason,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub authority: Pubkey,
+    pub kind: ProposalKind,
+    pub title: String,
+    pub options: Vec<String>,
+    pub deadline: i64,
+    pub voice_credits: u64,
+    pub ballot: BallotKind,
+    pub quorum: u32,
+    pub quorum_kind: QuorumKind,
+    pub quorum_target: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteCastEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub timestamp: i64,
+    pub voter_count: u32,
+}
+
+#[event]
+pub struct QuorumFailedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub voter_count: u32,
+    pub quorum: u32,
+    pub turnout: u64,
+    pub quorum_target: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevealExpiredEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub queued_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevealAbortedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// The aborted computation, whose callback will be dropped.
+    pub computation: Pubkey,
+    pub queued_at: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `initialize_config` and `update_config` with the new settings.
+#[event]
+pub struct ConfigUpdatedEvent {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub proposal_fee: u64,
+    pub vote_fee: u64,
+    pub reveal_timeout: i64,
+    pub paused: bool,
+    pub allowed_gating_modes: u8,
+    pub proposal_deposit: u64,
+    pub council: Pubkey,
+    pub treasury: Pubkey,
+    pub proposals_per_epoch: u32,
+    pub whitelisted_proposals_per_epoch: u32,
+    pub permissioned_creation: bool,
+    pub candidate_deposit: u64,
+    pub emergency_min_duration: i64,
+    pub emergency_supermajority_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreatorUpdatedEvent {
+    pub creator: Pubkey,
+    pub whitelisted: bool,
+    pub allowed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CategoryUpdatedEvent {
+    pub category: u16,
+    pub name: String,
+    pub default_quorum: u32,
+    pub min_duration: i64,
+    pub max_duration: i64,
+    pub active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CategoryAssignedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub category: u16,
+    pub quorum: u32,
+}
+
+#[event]
+pub struct BallotCreatedEvent {
+    pub ballot: Pubkey,
+    pub ballot_id: u32,
+    pub authority: Pubkey,
+    pub title: String,
+    pub proposals: Vec<Pubkey>,
+    pub deadline: i64,
+    pub registration_deadline: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TallyCommittedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// What the leaf commits to, so a replay can recompute it.
+    pub vote_state: [[u8; 32]; 5],
+    pub nonce: u128,
+    pub applied_ballots: u32,
+    pub leaf: [u8; 32],
+    pub root: [u8; 32],
+    /// 1 for the first checkpoint.
+    pub checkpoint: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BurnToVoteOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct EligibilityOracleSetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub oracle: Pubkey,
+}
+
+#[event]
+pub struct WeightCurveSetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub curve: WeightCurve,
+}
+
+#[event]
+pub struct TokenWeightsOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub mint: Pubkey,
+    pub max_weight: u64,
+}
+
+#[event]
+pub struct OptionDetailsSetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub details: Vec<OptionDetail>,
+}
+
+#[event]
+pub struct TranslationSetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub locale: String,
+    pub title: String,
+    pub options: Vec<String>,
+}
+
+#[event]
+pub struct TranslationRemovedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub locale: String,
+}
+
+#[event]
+pub struct CandidateRegisteredEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub candidate: Pubkey,
+    pub index: u8,
+    pub name: String,
+    /// The option set after this registration.
+    pub options: Vec<String>,
+    pub deposit: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ParentOutcomeRequiredEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub parent: Pubkey,
+    pub winner: u8,
+}
+
+#[event]
+pub struct ParentOutcomeMetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub parent: Pubkey,
+    pub winner: u8,
+}
+
+#[event]
+pub struct DependencyDeclaredEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub parent: Pubkey,
+    pub condition: DependencyCondition,
+}
+
+#[event]
+pub struct DependencyResolvedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub parent: Pubkey,
+    pub condition: DependencyCondition,
+}
+
+#[event]
+pub struct SanctionDeclaredEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub action: SanctionAction,
+}
+
+#[event]
+pub struct SanctionExecutedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub action: SanctionAction,
+    /// Lamports slashed; 0 for `RemoveCreator`.
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SponsorshipFundedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub amount: u64,
+    /// Sponsorship left after this top-up.
+    pub balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DepositSlashedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub authority: Pubkey,
+    pub council: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub reason: SlashReason,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardPoolCreatedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsSettledEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub eligible_voters: u32,
+    pub share: u64,
+    /// Returned to the authority.
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardClaimedEvent {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchingPoolCreatedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchingSettledEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Tokens matched to each option.
+    pub matches: [u64; 4],
+    /// Returned to the authority.
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchPaidEvent {
+    pub proposal: Pubkey,
+    pub option: u8,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReceiptClaimedEvent {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawnEvent {
+    pub fee_vault: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    /// `FeeVault::balance` after the withdrawal.
+    pub remaining: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ResultsRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub option_0: u64,
+    pub option_1: u64,
+    pub option_2: u64,
+    pub option_3: u64,
+    pub total_votes: u64,
+    pub winner: u8,
+}
+
+/// Emitted after `ResultsRevealedEvent` for a For/Against/Abstain proposal.
+#[event]
+pub struct OutcomeRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub votes_abstain: u64,
+    pub outcome: VoteOutcome,
+}
+
+#[event]
+pub struct CouncilOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub members: u8,
+    pub rule: ChamberRule,
+}
+
+#[event]
+pub struct TurnoutBiasSetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub bias_bps: u16,
+}
+
+#[event]
+pub struct FastTrackedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub council: Pubkey,
+    pub deadline: i64,
+    pub supermajority_bps: u16,
+}
+
+#[event]
+pub struct ChambersRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub community_outcome: VoteOutcome,
+    pub council_for: u64,
+    pub council_against: u64,
+    pub council_abstain: u64,
+    pub council_outcome: VoteOutcome,
+    /// The proposal's outcome under the chamber rule.
+    pub outcome: VoteOutcome,
+}
+
+#[event]
+pub struct WriteInRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Hash of the top write-in's identifier; 0 if there were none.
+    pub candidate: u128,
+    pub votes: u64,
+}
+
+#[event]
+pub struct CredentialsOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub registrar: Pubkey,
+}
+
+#[event]
+pub struct CredentialIssuedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub voter: Pubkey,
+}
+
+#[event]
+pub struct CredentialRevokedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub serial: [u8; 32],
+}
+
+#[event]
+pub struct NullifierSetOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub max_voters: u32,
+}
+
+#[event]
+pub struct ComplianceRollOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub compliance_key: [u8; 32],
+}
+
+#[event]
+pub struct IdentityReleasedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// The `SealedIdentity`, now readable with the compliance key.
+    pub identity: Pubkey,
+    pub released: u32,
+    pub sealed: u32,
+}
+
+#[event]
+pub struct CommitPhaseOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub commit_deadline: i64,
+}
+
+#[event]
+pub struct BallotCommittedEvent {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub commitment: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SpendHistogramRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Noised counts, as in `SpendHistogram::counts`.
+    pub counts: [u64; SPEND_HISTOGRAM_COUNTS],
+}
+
+#[event]
+pub struct SessionOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub wallet: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct SessionClosedEvent {
+    pub proposal: Pubkey,
+    pub wallet: Pubkey,
+    pub session_key: Pubkey,
+}
+
+#[event]
+pub struct JurorSeatedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub juror: Pubkey,
+}
+
+/// Emitted after `ResultsRevealedEvent` for a jury proposal.
+#[event]
+pub struct JuryRankingEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Jurors whose scores were counted.
+    pub jurors: u64,
+    /// Sum of scores over jurors and criteria per application.
+    pub scores: [u64; 4],
+    /// Applications from the highest score down, ties in option order.
+    pub ranking: Vec<u8>,
+}
+
+/// Emitted after `ResultsRevealedEvent` for a budget box.
+#[event]
+pub struct BudgetBoxFundedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Funded options, in option order.
+    pub funded: Vec<u8>,
+    /// Total cost of the funded options.
+    pub spent: u64,
+}
+
+/// Emitted after `ResultsRevealedEvent` for a Condorcet proposal.
+#[event]
+pub struct CondorcetRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// The Condorcet winner, or with a cycle the option with the most
+    /// head-to-head wins.
+    pub winner: u8,
+    /// No option beats every other one head to head.
+    pub cycle: bool,
 }
 
-// ============================================================
-// Account Structs — Voting
-// ============================================================
+/// Emitted after `ResultsRevealedEvent` for a STAR proposal.
+#[event]
+pub struct StarRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// The runoff winner; the other finalist is `runner_up`.
+    pub winner: u8,
+    pub runner_up: u8,
+    /// Ballots scoring the winner above the runner-up.
+    pub runoff_winner: u64,
+    /// Ballots scoring the runner-up above the winner.
+    pub runoff_runner_up: u64,
+}
 
-#[queue_computation_accounts("cast_vote", payer)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64, _id: u32)]
-pub struct CastVote<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed, space = 9, payer = payer,
-        seeds = [&SIGN_PDA_SEED], bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
-    /// CHECK: Proposal authority pubkey
-    #[account(address = proposal_acc.authority)]
-    pub authority: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
-        bump = proposal_acc.bump,
-        has_one = authority,
-    )]
-    pub proposal_acc: Account<'info, ProposalAccount>,
-    #[account(
-        init, payer = payer,
-        space = 8 + VoterRecord::INIT_SPACE,
-        seeds = [b"voter", proposal_acc.key().as_ref(), payer.key().as_ref()],
-        bump,
-    )]
-    pub voter_record: Account<'info, VoterRecord>,
+/// Emitted after `ResultsRevealedEvent` for a range proposal.
+#[event]
+pub struct RangeAveragesEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Counted ballots.
+    pub ballots: u64,
+    /// Average 0..=10 score per option, in hundredths.
+    pub average_scores: [u64; 4],
 }
 
-#[callback_accounts("cast_vote")]
-#[derive(Accounts)]
-pub struct CastVoteCallback<'info> {
-    pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
-    #[account(mut)]
-    pub proposal_acc: Account<'info, ProposalAccount>,
+/// Emitted after `ResultsRevealedEvent` for a Likert proposal.
+#[event]
+pub struct SentimentRevealedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Counted ballots.
+    pub ballots: u64,
+    /// Sum of -2..+2 scores per option.
+    pub net_sentiment: [i64; 4],
 }
 
-// ============================================================
-// Account Structs — Reveal
-// ============================================================
+#[event]
+pub struct QuorumProgressEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub vote_threshold: u64,
+    /// Whether `total_votes` has reached `vote_threshold`; nothing else
+    /// about the tallies is revealed.
+    pub reached: bool,
+    pub timestamp: i64,
+}
 
-#[queue_computation_accounts("reveal_results", payer)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64, id: u32)]
-pub struct RevealResults<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(
-        init_if_needed, space = 9, payer = payer,
-        seeds = [&SIGN_PDA_SEED], bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
-    #[account(
-        seeds = [b"proposal", payer.key().as_ref(), id.to_le_bytes().as_ref()],
-        bump = proposal_acc.bump,
-    )]
-    pub proposal_acc: Account<'info, ProposalAccount>,
+#[event]
+pub struct TrusteesAppointedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub trustees: Vec<Pubkey>,
+    pub threshold: u8,
+    pub grace_period: i64,
 }
 
-#[callback_accounts("reveal_results")]
-#[derive(Accounts)]
-pub struct RevealResultsCallback<'info> {
-    pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_RESULTS))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
-    #[account(mut)]
-    pub proposal_acc: Account<'info, ProposalAccount>,
+#[event]
+pub struct RevealApprovedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub trustee: Pubkey,
+    /// Approvals so far, out of the `threshold` the reveal needs.
+    pub approvals: u8,
+    pub threshold: u8,
 }
 
-// ============================================================
-// State Accounts
-// ============================================================
+/// Emitted by `force_reveal`, before the reveal's own events.
+#[event]
+pub struct RevealForcedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub trustee: Pubkey,
+    pub timestamp: i64,
+}
 
-#[account]
-#[derive(InitSpace)]
-pub struct ProposalAccount {
-    pub bump: u8,
-    /// Encrypted vote tallies: 5 counters (option_0..3 + total_votes) x 32 bytes
-    pub vote_state: [[u8; 32]; 5],
-    pub id: u32,
+#[event]
+pub struct CoAuthorsSetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub co_authors: Vec<Pubkey>,
+}
+
+#[event]
+pub struct RevealOperatorSetEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
     pub authority: Pubkey,
-    pub nonce: u128,
-    #[max_len(100)]
-    pub title: String,
-    #[max_len(4, 32)]
-    pub options: Vec<String>,
-    pub num_options: u8,
+    pub operator: Pubkey,
+}
+
+#[event]
+pub struct DeadlineExtendedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// The authority or co-author who extended it.
+    pub signer: Pubkey,
     pub deadline: i64,
-    pub voice_credits: u64,
-    pub quorum: u32,
-    pub is_finalized: bool,
-    pub voter_count: u32,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct VoterRecord {
-    pub bump: u8,
+#[event]
+pub struct AutoRevealEnabledEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub deadline: i64,
+    /// Offset of the first `auto_reveal`, for scheduling it.
+    pub computation_offset: u64,
+}
+
+#[event]
+pub struct HistoryGateOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub attestor: Option<Pubkey>,
+    pub min_wallet_age: i64,
+    pub min_prior_ballots: u32,
+}
+
+#[event]
+pub struct HistoryAdmittedEvent {
     pub proposal: Pubkey,
+    pub proposal_id: u32,
     pub voter: Pubkey,
-    pub has_voted: bool,
+    pub prior_ballots: u32,
+    /// By `attest_history` rather than `prove_history`.
+    pub attested: bool,
 }
 
-// ============================================================
-// Events
-// ============================================================
+#[event]
+pub struct AllowlistOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub capacity: u16,
+}
 
 #[event]
-pub struct VoteCastEvent {
+pub struct CollectionGateOpenedEvent {
+    pub proposal: Pubkey,
     pub proposal_id: u32,
-    pub timestamp: i64,
-    pub voter_count: u32,
+    pub collection: Pubkey,
 }
 
 #[event]
-pub struct ResultsRevealedEvent {
+pub struct AllowlistUpdatedEvent {
+    pub proposal: Pubkey,
     pub proposal_id: u32,
-    pub option_0: u64,
-    pub option_1: u64,
-    pub option_2: u64,
-    pub option_3: u64,
-    pub total_votes: u64,
-    pub winner: u8,
+    pub voter: Pubkey,
+    /// Added by `add_voter` rather than removed by `remove_voter`.
+    pub listed: bool,
+    /// Voters listed after the update.
+    pub count: u16,
 }
 
 // ============================================================
 // Errors
 // ============================================================
 
+/// Errors, grouped by `ErrorFamily` into blocks of 100 codes: lifecycle
+/// from 6000, eligibility from 6100, MPC from 6200, config from 6300 and
+/// accounts from 6400.  The first error of each family pins the start of
+/// its block.  A code never changes once released: a new error goes at the
+/// end of its family, and a retired one keeps its place.
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Invalid authority")]
-    InvalidAuthority,
-    #[msg("Computation was aborted")]
-    AbortedComputation,
-    #[msg("Cluster not set")]
-    ClusterNotSet,
+    // Lifecycle: the instruction comes too early or too late in the
+    // proposal's life, or a one-time step was already taken.
     #[msg("Voting period has ended")]
-    VotingPeriodEnded,
+    VotingPeriodEnded = 0,
     #[msg("Voting period has not ended yet")]
     VotingPeriodNotEnded,
-    #[msg("Already voted on this proposal")]
-    AlreadyVoted,
-    #[msg("Invalid option choice")]
-    InvalidChoice,
     #[msg("Proposal already finalized")]
     ProposalAlreadyFinalized,
     #[msg("Quorum not met")]
     QuorumNotMet,
+    #[msg("Proposal not finalized")]
+    ProposalNotFinalized,
+    #[msg("Quorum was reached")]
+    QuorumReached,
+    #[msg("Proposal already marked as quorum failed")]
+    QuorumAlreadyFailed,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Voting has already started")]
+    VotingAlreadyStarted,
+    #[msg("Rewards have not been settled")]
+    RewardsNotSettled,
+    #[msg("Rewards already settled")]
+    RewardsAlreadySettled,
+    #[msg("Reward already claimed")]
+    RewardAlreadyClaimed,
+    #[msg("Receipt already claimed")]
+    ReceiptAlreadyClaimed,
+    #[msg("Candidate registration is not open")]
+    RegistrationClosed,
+    #[msg("Candidate registration is still open")]
+    RegistrationOpen,
+    #[msg("All candidate slots are taken")]
+    CandidateSlotsFull,
+    #[msg("Write-ins are still being initialized")]
+    WriteInsNotReady,
+    #[msg("Top write-in was already revealed")]
+    WriteInAlreadyRevealed,
+    #[msg("No held ballots to release")]
+    NoHeldBallots,
+    #[msg("Held ballots must be released before the reveal")]
+    HeldBallotsPending,
+    #[msg("Identity already released")]
+    IdentityAlreadyReleased,
+    #[msg("Sealed identities are still to be released")]
+    IdentitiesPending,
+    #[msg("Trustee already approved the reveal")]
+    TrusteeAlreadyApproved,
+    #[msg("The proposal's trustees have not approved the reveal")]
+    TrusteeApprovalPending,
+    #[msg("The authority's grace period to reveal has not passed")]
+    RevealGraceNotOver,
+    #[msg("Spend histogram is not initialized yet")]
+    HistogramNotReady,
+    #[msg("Spend histogram already revealed")]
+    HistogramAlreadyRevealed,
+    #[msg("This proposal does not depend on a parent outcome")]
+    NoParentOutcome,
+    #[msg("The parent proposal has not finalized with the required winner")]
+    ParentOutcomeNotMet,
+    #[msg("The parent proposal has not been decided as this proposal depends on")]
+    DependencyNotMet,
+    #[msg("Options, deadline and budget are frozen once the first ballot is cast")]
+    ProposalFrozen,
+    #[msg("The proposal already has a category")]
+    CategoryAlreadySet,
+    #[msg("The proposal's co-authors are already set")]
+    CoAuthorsAlreadySet,
+    #[msg("A new deadline must be later than the current one")]
+    InvalidDeadlineExtension,
+    #[msg("Square-root credits are already open on this proposal")]
+    SqrtCreditsAlreadyOpen,
+    #[msg("This proposal does not assign square-root credits")]
+    SqrtCreditsNotOpen,
+    #[msg("The proposal is already gated")]
+    ProposalAlreadyGated,
+    #[msg("Burn-to-vote is already open on this proposal")]
+    BurnToVoteAlreadyOpen,
+    #[msg("This proposal already has an eligibility oracle")]
+    EligibilityOracleAlreadySet,
+    #[msg("No instruction moves a proposal between these lifecycle states")]
+    InvalidTransition,
+    #[msg("The proposal is already on a ballot")]
+    ProposalAlreadyOnBallot,
+    #[msg("The council's tally is still being initialized")]
+    CouncilNotReady,
+    #[msg("A dual-chamber proposal is revealed with reveal_chambers")]
+    DualChamberReveal,
+    #[msg("The matching pool has not been settled")]
+    MatchingNotSettled,
+    #[msg("The matching pool is already settled")]
+    MatchingAlreadySettled,
+    #[msg("The proposal already has a commit phase")]
+    CommitPhaseAlreadyOpen,
+    #[msg("Ballots are taken once the commit phase ends")]
+    CommitPhaseOpen,
+    #[msg("The commit phase has ended")]
+    CommitPhaseEnded,
+    #[msg("The first ballot fixed the electorate")]
+    ElectorateFixed,
+    #[msg("The sanction's proposal has not passed")]
+    SanctionNotPassed,
+    #[msg("The sanction was already carried out")]
+    SanctionAlreadyExecuted,
+    #[msg("The proposal is already fast-tracked")]
+    AlreadyFastTracked,
+    #[msg("The ballot already keeps round records")]
+    RoundRecordsAlreadyOpen,
+    #[msg("The proposal's ballot doesn't keep round records")]
+    RoundRecordsNotOpen,
+    #[msg("The proposal's reveal operator is already set")]
+    RevealOperatorAlreadySet,
+    #[msg("Ranking tally is not initialized yet")]
+    RankingsNotReady,
+    #[msg("Preference matrix is not initialized yet")]
+    PreferencesNotReady,
+    #[msg("Token weights are already open on this proposal")]
+    TokenWeightsAlreadyOpen,
+    #[msg("No buffered ballots to process")]
+    NoBufferedBallots,
+    #[msg("Buffered ballots must be processed before the reveal")]
+    BufferedBallotsPending,
+    #[msg("Until the deadline, buffered ballots are processed in full batches")]
+    BallotBatchNotFull,
+    #[msg("The proposal does not bank credits")]
+    CreditBankNotOpen,
+    #[msg("The credit bank is still being initialized")]
+    CreditBankNotReady,
+    #[msg("A ballot against this credit bank is still being counted")]
+    CreditBankBusy,
+    #[msg("The voter's encrypted budget is still being sealed")]
+    EncryptedBudgetNotReady,
+    #[msg("This proposal id was closed and can't be reused")]
+    ProposalIdRetired,
+    #[msg("Escrowed credit tokens are released once the deadline passes")]
+    CreditsLocked,
+    #[msg("The proposal already keeps data-less voter records")]
+    DatalessRecordsAlreadyOpen,
+    #[msg("The proposal doesn't keep data-less voter records")]
+    DatalessRecordsNotOpen,
+
+    // Eligibility: the signer may not act, or the ballot is not admitted.
+    #[msg("Invalid authority")]
+    InvalidAuthority = 100,
+    #[msg("Already voted on this proposal")]
+    AlreadyVoted,
+    #[msg("Invalid option choice")]
+    InvalidChoice,
+    #[msg("Vote was not counted")]
+    VoteNotCounted,
+    #[msg("Too many proposals from this authority this epoch")]
+    ProposalRateLimited,
+    #[msg("Proposal creation is restricted to allowed creators")]
+    CreatorNotAllowed,
+    #[msg("Only polls can be revealed by anyone")]
+    NotAPoll,
+    #[msg("Ballot does not match the proposal's ballot kind")]
+    WrongBallotKind,
+    #[msg("Pairwise round already holds its maximum number of ballots")]
+    PairwiseRoundFull,
+    #[msg("Voter holds no slot in the pairwise round")]
+    PairwiseSlotNotFound,
+    #[msg("Proposal requires a presented credential")]
+    CredentialRequired,
+    #[msg("Credential signature or holder is invalid")]
+    InvalidCredential,
+    #[msg("Credential was revoked")]
+    CredentialRevoked,
+    #[msg("Session key or expiry is invalid")]
+    InvalidSession,
+    #[msg("Session has expired")]
+    SessionExpired,
+    #[msg("Nullifier set is full")]
+    NullifierSetFull,
+    #[msg("Credential was already used to vote")]
+    NullifierSpent,
+    #[msg("Credentials on this proposal are spent by cast_vote_anonymous")]
+    AnonymousCredentials,
+    #[msg("This proposal's anonymous ballots must seal the voter's identity")]
+    IdentityRequired,
+    #[msg("Invalid sealed identity")]
+    InvalidIdentity,
+    #[msg("Signer is not one of the proposal's trustees")]
+    NotATrustee,
+    #[msg("This proposal's ballots must be cast with cast_vote_histogram")]
+    HistogramBallotRequired,
+    #[msg("The voter's balance gives no voice credits")]
+    NoVoiceCredits,
+    #[msg("Register square-root credits with register_credits before voting")]
+    CreditsNotRegistered,
+    #[msg("Signer is not the history gate's attestor")]
+    InvalidAttestor,
+    #[msg("The wallet is younger than the gate requires")]
+    WalletTooYoung,
+    #[msg("The wallet has too few prior ballots")]
+    TooFewPriorBallots,
+    #[msg("Wallet age can only be attested")]
+    WalletAgeUnproven,
+    #[msg("History records must be this wallet's counted voter records on other proposals")]
+    InvalidHistoryRecord,
+    #[msg("This proposal's ballots burn a token: pass the payer's account of its burn mint")]
+    MembershipTokenRequired,
+    #[msg("This proposal's eligibility oracle must be passed to cast_vote")]
+    EligibilityOracleRequired,
+    #[msg("The eligibility oracle didn't return a u64 weight")]
+    InvalidOracleResponse,
+    #[msg("The eligibility oracle refused this voter")]
+    NotEligible,
+    #[msg("The voter is already on the allowlist")]
+    VoterAlreadyListed,
+    #[msg("The allowlist is at capacity")]
+    AllowlistFull,
+    #[msg("The voter is not on the allowlist")]
+    NotOnAllowlist,
+    #[msg("This proposal has an allowlist: vote with cast_vote and pass it")]
+    AllowlistRequired,
+    #[msg("The proposal has admitted MAX_VOTERS ballots")]
+    VoterLimitReached,
+    #[msg("Signer is not a member of the proposal's council")]
+    NotACouncilMember,
+    #[msg("The ballot does not match the voter's commitment")]
+    BallotNotCommitted,
+    #[msg("The proposal's ballot keeps round records: vote with cast_round_choice")]
+    RoundRecordRequired,
+    #[msg("The voter holds none of the proposal's weight mint")]
+    NoWeightTokens,
+    #[msg("The proposal's authority has not funded this voter's credits")]
+    CreditsNotFunded,
+    #[msg("This proposal's ballots must be cast with cast_vote_banked")]
+    BankedBallotRequired,
+    #[msg("This proposal's ballots must be cast with cast_vote_budgeted")]
+    BudgetedBallotRequired,
+    #[msg("Token-gated ballots need the voter's own token account of the gate mint")]
+    GateTokenAccountRequired,
+    #[msg("The voter holds less than the proposal's gate threshold")]
+    GateBalanceTooLow,
+    #[msg("Collection-gated ballots need the voter's NFT token account, its metadata and its NftBallot")]
+    NftRequired,
+    #[msg("The NFT is not a verified member of the proposal's collection")]
+    NotInCollection,
+    #[msg("This NFT already voted on this proposal")]
+    NftAlreadyVoted,
+    #[msg("The eligibility oracle answered a weight above MAX_ORACLE_WEIGHT")]
+    OracleWeightTooHigh,
+    #[msg("This proposal keeps data-less voter records: vote with cast_vote_dataless")]
+    DatalessRecordRequired,
+
+    // MPC: queuing computations, their callbacks and reveals.
+    #[msg("Computation was aborted")]
+    AbortedComputation = 200,
+    #[msg("Cluster not set")]
+    ClusterNotSet,
+    #[msg("A reveal is already pending")]
+    RevealAlreadyPending,
+    #[msg("No reveal is pending")]
+    NoPendingReveal,
+    #[msg("Pending reveal has not timed out yet")]
+    RevealNotStale,
+    #[msg("Invalid computation account")]
+    InvalidComputationAccount,
+    /// Retired: the mix queue grows with every held ballot.
+    #[msg("Mix queue is full")]
+    MixQueueFull,
+    #[msg("Auto-reveal is not enabled for this proposal")]
+    AutoRevealDisabled,
+    #[msg("A computation queued for this proposal has not called back yet")]
+    ComputationsInFlight,
+    #[msg("The tally has not changed since the last checkpoint")]
+    TallyUnchanged,
+    #[msg("Ballot buffer is full")]
+    BallotBufferFull,
+
+    // Config: program or proposal parameters are out of range or
+    // don't combine.
+    #[msg("Gating mode not allowed by config")]
+    GatingModeNotAllowed = 300,
+    #[msg("Invalid config parameters")]
+    InvalidConfig,
+    #[msg("Quorum out of range for its kind")]
+    InvalidQuorum,
+    #[msg("Options of a candidate election come from registration")]
+    OptionsSetByCandidates,
+    #[msg("Registration deadline must be in the future and before the voting deadline")]
+    InvalidRegistrationDeadline,
+    #[msg("Candidate name must be 1 to 32 bytes of visible text")]
+    InvalidCandidateName,
+    #[msg("For/Against/Abstain needs exactly three options and a veto threshold up to 10,000 bps")]
+    InvalidVetoBallot,
+    #[msg("Time weights must be 1 to 10,000 percent")]
+    InvalidTimeWeight,
+    #[msg("Jury ballots need 1 to 3 criteria and a maximum score above 0")]
+    InvalidJuryBallot,
+    #[msg("Approval ballots need a limit of 1 to 4 choices")]
+    InvalidApprovalBallot,
+    #[msg("Budget boxes need a budget, fixed options and a nonzero cost for exactly each option")]
+    InvalidBudgetBox,
+    #[msg("Mix window must be positive")]
+    InvalidMixWindow,
+    #[msg("Nullifier set size is out of range, unsupported by the quorum, or already allocated")]
+    InvalidNullifierSet,
+    #[msg("A compliance roll needs anonymous credentials")]
+    ComplianceNeedsAnonymity,
+    #[msg("Vote threshold must be positive and match the proposal's first progress check")]
+    InvalidVoteThreshold,
+    #[msg("Trustee set needs 1 to 5 distinct keys, a threshold up to their number, and a positive grace period")]
+    InvalidTrusteeSet,
+    #[msg("A spend histogram can't be combined with a mix window, commit phase, gating, credits or burn-to-vote")]
+    HistogramUnsupported,
+    #[msg("Parent must be another proposal and the winner an option index")]
+    InvalidParentOutcome,
+    #[msg("A dependency's parent must be another For/Against/Abstain proposal")]
+    InvalidDependency,
+    #[msg("A category needs a name of up to 32 bytes and consistent duration bounds")]
+    InvalidCategory,
+    #[msg("This category is not active")]
+    CategoryInactive,
+    #[msg("The proposal's voting period is outside the category's bounds")]
+    DurationOutsideCategory,
+    #[msg("Co-authors must be 1 to 3 distinct keys other than the authority")]
+    InvalidCoAuthors,
+    #[msg("Square-root credit ballots are cast with cast_vote or cast_vote_session, without a spend histogram")]
+    SqrtCreditsUnsupported,
+    #[msg("A history gate needs a bar, and an attestor to check wallet age")]
+    InvalidHistoryGate,
+    #[msg("A burn mint can have at most 19 decimals")]
+    InvalidBurnMint,
+    #[msg("Burn-to-vote ballots are cast with cast_vote, without a spend histogram")]
+    BurnToVoteUnsupported,
+    #[msg("ArcVote can't be its own eligibility oracle")]
+    InvalidEligibilityOracle,
+    #[msg("Oracle-gated ballots are cast with cast_vote, without a spend histogram")]
+    EligibilityOracleUnsupported,
+    #[msg("An allowlist holds 1 to MAX_ALLOWLIST_VOTERS voters")]
+    InvalidAllowlistCapacity,
+    #[msg("Title must be 1 to 100 bytes of visible text")]
+    InvalidTitle,
+    #[msg("Option labels must be 1 to 32 bytes of visible text")]
+    InvalidOptionLabel,
+    #[msg("Two options have the same label")]
+    DuplicateOption,
+    #[msg("A ballot groups 2 to 8 distinct, writable proposals sharing a deadline and registration deadline")]
+    InvalidBallot,
+    #[msg("A proposal on a ballot keeps the ballot's deadline")]
+    DeadlineSetByBallot,
+    #[msg("A council needs 1 to 16 distinct members and a weight up to 10,000 bps")]
+    InvalidCouncil,
+    #[msg("A matching pool names one recipient per option")]
+    InvalidRecipients,
+    #[msg("Option details cover at most the proposal's options, with URIs up to 200 bytes and no spaces")]
+    InvalidOptionDetails,
+    #[msg("A commit phase must end in the future, after registration and before the deadline")]
+    InvalidCommitPhase,
+    #[msg("Commit-phase ballots are cast with cast_vote")]
+    CommitPhaseUnsupported,
+    #[msg("A turnout bias needs a RegisteredBps quorum and 1 to 5,000 bps")]
+    InvalidTurnoutBias,
+    #[msg("A turnout bias can't be combined with a council chamber")]
+    TurnoutBiasUnsupported,
+    #[msg("A sanction can't target its own proposal or authority")]
+    InvalidSanction,
+    #[msg("Fast-tracking is disabled by config")]
+    FastTrackDisabled,
+    #[msg("A fast-tracked deadline must be after registration, no sooner than the emergency minimum, and not later")]
+    InvalidFastTrack,
+    #[msg("A fast-tracked proposal can't have a council chamber")]
+    FastTrackUnsupported,
+    #[msg("Round records need open For/Against/Abstain proposals without a reward pool")]
+    RoundRecordsUnsupported,
+    #[msg("The reveal operator must be a key other than the authority")]
+    InvalidRevealOperator,
+    #[msg("Weight curves bend an eligibility oracle's weights or token balances and can't share a mix window")]
+    WeightCurveUnsupported,
+    #[msg("A capped weight curve needs a cap above zero")]
+    InvalidWeightCurve,
+    #[msg("A translation needs a BCP 47 locale of up to 16 bytes and at most the proposal's options")]
+    InvalidTranslation,
+    #[msg("A proposal holds at most 8 translations")]
+    TooManyTranslations,
+    #[msg("Token-weighted ballots are cast with cast_vote, without a spend histogram")]
+    TokenWeightsUnsupported,
+    #[msg("A vote batch holds 1 to 4 ballots on distinct proposals, each with its proposal, tally, voter record and computation accounts")]
+    InvalidVoteBatch,
+    #[msg("Ballots in the mix window are cast with cast_vote or cast_vote_session")]
+    MixWindowUnsupported,
+    #[msg("Quadratic ballots need a voice credit budget above 0")]
+    InvalidVoiceCredits,
+    #[msg("Funded-credit ballots are cast with cast_vote, cast_vote_session or submit_ballot, without square-root credits or a spend histogram")]
+    FundedCreditsUnsupported,
+    #[msg("Banked proposals are open to anyone, without a mix window, commit phase, ballot buffer or any other budget or weight mode")]
+    CreditBankUnsupported,
+    #[msg("Encrypted-budget proposals are open to anyone, without a mix window, commit phase, ballot buffer or any other budget or weight mode")]
+    EncryptedBudgetsUnsupported,
+    #[msg("A token gate needs a threshold above 0, and under a supply quorum the quorum mint")]
+    InvalidTokenGate,
+    #[msg("Collection gates take a voter-count or registered-voter quorum")]
+    CollectionGateUnsupported,
+    #[msg("The token weight cap is zero or could overflow the tallies")]
+    InvalidTokenWeightCap,
+    #[msg("A weight mint can have at most 19 decimals")]
+    InvalidWeightMint,
+    #[msg("A square-root-credit proposal keeps the deadline its voters escrowed tokens until")]
+    DeadlineSetByCredits,
+    #[msg("Data-less voter records need an open quadratic proposal without rewards or other ballot modes")]
+    DatalessRecordsUnsupported,
+
+    // Accounts: an account passed in is missing or not the expected
+    // one, or a balance or counter it holds can't take the change.
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount = 400,
+    #[msg("Not enough collected fees in the vault")]
+    InsufficientFees,
+    #[msg("Proposal has no deposit to slash")]
+    NoDeposit,
+    #[msg("Treasury does not match the config")]
+    InvalidTreasury,
+    #[msg("Supply-based quorum needs the quorum mint")]
+    QuorumMintRequired,
+    #[msg("Supply-based quorum needs the voter's quorum mint token account")]
+    QuorumTokenAccountRequired,
+    #[msg("Ballots in the mix window need the proposal's mix queue")]
+    MixQueueRequired,
+    #[msg("Nullifier set must be grown to full size first")]
+    NullifierSetNotAllocated,
+    #[msg("Token account must be the voter's account of the proposal's credit mint")]
+    InvalidCreditAccount,
+    #[msg("A counter would wrap around")]
+    CounterOverflow,
+    #[msg("A lamport or token amount would wrap around")]
+    AmountOverflow,
+    #[msg("Token account must belong to the option's recipient")]
+    InvalidRecipient,
+    #[msg("Sanction target account is missing or not the one voted on")]
+    InvalidSanctionTarget,
+    #[msg("The proposal has no translation into this locale")]
+    TranslationNotFound,
+    #[msg("Token-weighted ballots need the voter's account of the proposal's weight mint")]
+    InvalidWeightAccount,
+    #[msg("Metadata account must be the NFT's Metaplex metadata")]
+    InvalidNftMetadata,
+}
+
+/// The subsystem an error comes from, for clients that map a failed
+/// transaction's custom error code without matching on its name.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorFamily {
+    Lifecycle,
+    Eligibility,
+    Mpc,
+    Config,
+    Accounts,
+}
+
+impl ErrorFamily {
+    pub const ALL: [ErrorFamily; 5] = [
+        ErrorFamily::Lifecycle,
+        ErrorFamily::Eligibility,
+        ErrorFamily::Mpc,
+        ErrorFamily::Config,
+        ErrorFamily::Accounts,
+    ];
+
+    /// Codes this family owns.
+    pub fn codes(self) -> std::ops::Range<u32> {
+        let first = anchor_lang::error::ERROR_CODE_OFFSET + 100 * self as u32;
+        first..first + 100
+    }
+
+    /// The family owning `code`, or `None` for a code outside ArcVote's
+    /// ranges, such as Anchor's own.
+    pub fn of(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|family| family.codes().contains(&code))
+    }
+}
+
+impl ErrorCode {
+    pub fn family(self) -> ErrorFamily {
+        ErrorFamily::of(self.into()).expect("every error code is in a family")
+    }
 }
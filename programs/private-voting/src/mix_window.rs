@@ -0,0 +1,334 @@
+//! The mix window.
+//!
+//! Ballots cast in the last `window` seconds before the deadline wait in
+//! the proposal's `MixQueue` instead of reaching the tallies, and
+//! `release_held_ballot` applies them in random order once voting has
+//! closed.
+
+use crate::*;
+
+pub(crate) fn open_mix_window(ctx: Context<OpenMixWindow>, window: i64) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal_acc;
+    require!(
+        matches!(
+            proposal.ballot,
+            BallotKind::Quadratic | BallotKind::TimeWeighted { .. }
+        ),
+        ErrorCode::WrongBallotKind
+    );
+    require!(proposal.voter_count == 0, ErrorCode::VotingAlreadyStarted);
+    check_stage(proposal.state(Clock::get()?.unix_timestamp), Stage::Setup)?;
+    require!(window > 0, ErrorCode::InvalidMixWindow);
+    require!(!proposal.spend_histogram, ErrorCode::HistogramUnsupported);
+    require!(!proposal.credit_bank, ErrorCode::CreditBankUnsupported);
+    require!(!proposal.encrypted_budgets, ErrorCode::EncryptedBudgetsUnsupported);
+    require!(
+        proposal.weight_curve == WeightCurve::Linear,
+        ErrorCode::WeightCurveUnsupported
+    );
+    proposal.mix_window = window;
+
+    let mix_queue = &mut ctx.accounts.mix_queue;
+    mix_queue.bump = ctx.bumps.mix_queue;
+    mix_queue.proposal = proposal.key();
+
+    emit!(MixWindowOpenedEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        window,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn release_held_ballot(ctx: Context<ReleaseHeldBallot>, computation_offset: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    check_stage(ctx.accounts.proposal_acc.state(clock.unix_timestamp), Stage::Closed)?;
+    let mix_queue = &mut ctx.accounts.mix_queue;
+    require!(mix_queue.count > 0, ErrorCode::NoHeldBallots);
+    // No ballot is held after the deadline, so the seed is fixed by now
+    // and the order is the same whoever releases.
+    let draw = hashv(&[MIX_SEED_DOMAIN, &mix_queue.seed, &mix_queue.count.to_le_bytes()]);
+    let draw = u64::from_le_bytes(draw.to_bytes()[..8].try_into().unwrap());
+    let index = (draw % u64::from(mix_queue.count)) as u32;
+    mix_queue.count -= 1;
+    let (at, last) = (MixQueue::record_offset(index), MixQueue::record_offset(mix_queue.count));
+    let held = {
+        let info = mix_queue.to_account_info();
+        let mut data = info.try_borrow_mut_data()?;
+        let held = HeldBallot::deserialize(&mut &data[at..])
+            .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))?;
+        // The last record fills the gap.
+        data.copy_within(last..last + HeldBallot::INIT_SPACE, at);
+        held
+    };
+    ctx.accounts.proposal_acc.mix_held = ctx
+        .accounts
+        .proposal_acc
+        .mix_held
+        .checked_sub(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+
+    // Same circuit and argument order as `cast_vote`.
+    let args = ArgBuilder::new()
+        .x25519_pubkey(held.encryption_pubkey)
+        .plaintext_u128(held.nonce)
+        .encrypted_u64(held.ciphertexts[0])
+        .encrypted_u64(held.ciphertexts[1])
+        .encrypted_u64(held.ciphertexts[2])
+        .encrypted_u64(held.ciphertexts[3])
+        .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+        .account(
+            ctx.accounts.tally_acc.key(),
+            8, // discriminator
+            32 * 5, // 5 encrypted u64 counters
+        )
+        .plaintext_u64(held.weight)
+        .plaintext_u64(held.budget)
+        // Mix windows refuse weight curves, so the held weight already
+        // includes any oracle weight: a stake of 1, linear.
+        .plaintext_u64(1)
+        .plaintext_u64(0)
+        .plaintext_u64(0)
+        .plaintext_u64(ctx.accounts.proposal_acc.num_options.into())
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    let lamports_before = ctx.accounts.payer.lamports();
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![CastVoteCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: held.voter_record,
+                    // The program id of a `cast_vote_anonymous` ballot.
+                    is_writable: held.voter_record != crate::ID,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+}
+
+// ============================================================
+// Account Structs
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenMixWindow<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(
+        init, payer = payer,
+        space = 8 + MixQueue::INIT_SPACE,
+        seeds = [b"mix_queue", proposal_acc.key().as_ref()],
+        bump,
+    )]
+    pub mix_queue: Account<'info, MixQueue>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("cast_vote", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct ReleaseHeldBallot<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_VOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(
+        mut,
+        seeds = [b"mix_queue", proposal_acc.key().as_ref()],
+        bump = mix_queue.bump,
+    )]
+    pub mix_queue: Account<'info, MixQueue>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+}
+
+// ============================================================
+// State Accounts
+// ============================================================
+
+/// Ballots cast inside a proposal's mix window, one PDA per proposal
+/// `[b"mix_queue", proposal]`, created by `open_mix_window`.  The header
+/// is followed by `count` Borsh `HeldBallot` records; `cast_vote` grows
+/// the account by one record per held ballot and `release_held_ballot`
+/// empties it.
+#[account]
+#[derive(InitSpace)]
+pub struct MixQueue {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    /// Ballots held and not yet released.
+    pub count: u32,
+    /// Folds in the latest slot hash with each held ballot; orders the
+    /// release once the window has closed.
+    pub seed: [u8; 32],
+}
+
+impl MixQueue {
+    /// Bytes before the records.
+    pub const HEADER_LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Where the `index`th held ballot starts.
+    pub const fn record_offset(index: u32) -> usize {
+        Self::HEADER_LEN + index as usize * HeldBallot::INIT_SPACE
+    }
+}
+
+/// A ballot as `cast_vote` (or `submit_ballot`) received it, still
+/// encrypted to the voter's shared key, with the weight it was cast at and
+/// its credit budget.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct HeldBallot {
+    pub voter_record: Pubkey,
+    pub ciphertexts: [[u8; 32]; 4],
+    pub encryption_pubkey: [u8; 32],
+    pub nonce: u128,
+    pub weight: u64,
+    pub budget: u64,
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[event]
+pub struct MixWindowOpenedEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    pub window: i64,
+}
+
+#[event]
+pub struct BallotHeldEvent {
+    pub proposal: Pubkey,
+    pub proposal_id: u32,
+    /// Ballots now waiting for release.
+    pub held: u32,
+}
+
+// ============================================================
+// Helpers
+// ============================================================
+
+/// Park a ballot cast inside the mix window in the proposal's `MixQueue`
+/// rather than queuing it; `release_held_ballot` applies it after the
+/// deadline.  The queue is passed unchecked so clients can always include
+/// it; it must be the initialized one here.  It grows by one record per
+/// ballot, the rent paid by `payer`, and folds the latest slot hash into
+/// its release seed.
+pub(crate) fn hold_ballot<'info>(
+    proposal: &mut Account<'info, ProposalAccount>,
+    mix_queue: Option<&UncheckedAccount<'info>>,
+    slot_hashes: Option<&UncheckedAccount<'info>>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    ballot: HeldBallot,
+) -> Result<()> {
+    let info = mix_queue.ok_or(ErrorCode::MixQueueRequired)?;
+    let slot_hashes = slot_hashes.ok_or(ErrorCode::MixQueueRequired)?;
+    // Its seeds pin the address, so only this program can have written it.
+    let mut mix_queue = MixQueue::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let offset = MixQueue::record_offset(mix_queue.count);
+    let len = offset + HeldBallot::INIT_SPACE;
+    let rent = Rent::get()?.minimum_balance(len).saturating_sub(info.lamports());
+    if rent > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: info.to_account_info(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    if info.data_len() < len {
+        info.resize(len)?;
+    }
+
+    // SlotHashes is a length, then (slot, hash) pairs, newest first.
+    let slot_hash = slot_hashes.try_borrow_data()?.get(16..48).map(<[u8]>::to_vec);
+    let slot_hash = slot_hash.ok_or(ErrorCode::MixQueueRequired)?;
+    mix_queue.seed = hashv(&[MIX_SEED_DOMAIN, &mix_queue.seed, &slot_hash]).to_bytes();
+    mix_queue.count = mix_queue.count.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+    let mut data = info.try_borrow_mut_data()?;
+    mix_queue.try_serialize(&mut &mut data[..])?;
+    ballot
+        .serialize(&mut &mut data[offset..len])
+        .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotSerialize))?;
+    drop(data);
+    proposal.mix_held = proposal.mix_held.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+    emit!(BallotHeldEvent {
+        proposal: proposal.key(),
+        proposal_id: proposal.id,
+        held: proposal.mix_held,
+    });
+
+    Ok(())
+}
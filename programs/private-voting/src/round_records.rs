@@ -0,0 +1,364 @@
+//! Round records.
+//!
+//! `open_round_records` records a ballot's votes per voter instead of per
+//! proposal: one `RoundRecord` `[b"round_voter", ballot, voter]` holds a
+//! bit for each of the ballot's proposals, so a voter pays one rent
+//! deposit for the whole ballot.  Its proposals are then voted with
+//! `cast_round_choice`.
+
+use crate::*;
+
+pub(crate) fn open_round_records(ctx: Context<OpenRoundRecords>) -> Result<()> {
+    let ballot = &mut ctx.accounts.ballot_acc;
+    require!(!ballot.round_records, ErrorCode::RoundRecordsAlreadyOpen);
+    require!(
+        ctx.remaining_accounts.len() == ballot.proposals.len(),
+        ErrorCode::InvalidBallot
+    );
+    let now = Clock::get()?.unix_timestamp;
+
+    for (slot, (info, key)) in ctx.remaining_accounts.iter().zip(&ballot.proposals).enumerate() {
+        require!(
+            info.owner == &crate::ID && info.is_writable && info.key == key,
+            ErrorCode::InvalidBallot
+        );
+        let mut proposal = ProposalAccount::try_deserialize(&mut &info.try_borrow_data()?[..])
+            .map_err(|_| error!(ErrorCode::InvalidBallot))?;
+        require!(!proposal.is_frozen(), ErrorCode::ProposalFrozen);
+        check_stage(proposal.state(now), Stage::Setup)?;
+        require!(
+            matches!(proposal.ballot, BallotKind::YesNoAbstain { .. })
+                && proposal.gating == GatingMode::Open
+                && !proposal.has_reward_pool,
+            ErrorCode::RoundRecordsUnsupported
+        );
+        proposal.round_slot = Some(slot as u8);
+        proposal.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+    ballot.round_records = true;
+
+    emit!(RoundRecordsOpenedEvent {
+        ballot: ballot.key(),
+        ballot_id: ballot.id,
+        authority: ballot.authority,
+        proposals: ballot.proposals.clone(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn cast_round_choice(
+    ctx: Context<CastRoundChoice>,
+    computation_offset: u64,
+    choice_0: [u8; 32],
+    choice_1: [u8; 32],
+    choice_2: [u8; 32],
+    choice_3: [u8; 32],
+    vote_encryption_pubkey: [u8; 32],
+    vote_nonce: u128,
+) -> Result<()> {
+    let round_record_bump = ctx.bumps.round_record;
+    let accounts = &mut *ctx.accounts;
+    admit_round_ballot(
+        &mut accounts.proposal_acc,
+        &mut accounts.round_record,
+        round_record_bump,
+        accounts.payer.key(),
+        accounts.voter_token_account.as_deref(),
+        &mut accounts.global_stats,
+        &accounts.config,
+    )?;
+
+    collect_fee(
+        &ctx.accounts.payer,
+        &mut ctx.accounts.fee_vault,
+        &ctx.accounts.system_program,
+        ctx.accounts.config.vote_fee,
+    )?;
+
+    // cast_round_choice(choice_ctxt: Enc<Shared, Choice>, tallies_ctxt: Enc<Mxe, VoteTallies>)
+    let args = ArgBuilder::new()
+        .x25519_pubkey(vote_encryption_pubkey)
+        .plaintext_u128(vote_nonce)
+        .encrypted_u64(choice_0)
+        .encrypted_u64(choice_1)
+        .encrypted_u64(choice_2)
+        .encrypted_u64(choice_3)
+        .plaintext_u128(ctx.accounts.tally_acc.load()?.nonce())
+        .account(
+            ctx.accounts.tally_acc.key(),
+            8,
+            32 * 5,
+        )
+        .build();
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    let lamports_before = ctx.accounts.payer.lamports();
+    track_computation(&mut ctx.accounts.proposal_acc)?;
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        vec![CastRoundChoiceCallback::callback_ix(
+            computation_offset,
+            &ctx.accounts.mxe_account,
+            &[
+                CallbackAccount {
+                    pubkey: ctx.accounts.proposal_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tally_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.global_stats.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.round_record.key(),
+                    is_writable: true,
+                },
+            ],
+        )?],
+        1,
+        0,
+    )?;
+
+    reimburse_arcium_fee(&mut ctx.accounts.proposal_acc, &ctx.accounts.payer, lamports_before)
+}
+
+pub(crate) fn cast_round_choice_callback(
+    ctx: Context<CastRoundChoiceCallback>,
+    output: SignedComputationOutputs<CastRoundChoiceOutput>,
+) -> Result<()> {
+    settle_computation(&mut ctx.accounts.proposal_acc);
+    let slot = usize::from(ctx.accounts.proposal_acc.round_slot.unwrap_or_default());
+    let generation = ctx.accounts.round_record.tally_generations[slot];
+    if !is_current_generation(&ctx.accounts.proposal_acc, generation) {
+        return Ok(());
+    }
+    let aborted = matches!(output, SignedComputationOutputs::Failure);
+    let o = match output.verify_output(
+        &ctx.accounts.cluster_account,
+        &ctx.accounts.computation_account,
+    ) {
+        Ok(CastRoundChoiceOutput { field_0 }) => field_0,
+        Err(_) => {
+            return reject_callback(
+                &mut ctx.accounts.global_stats,
+                ctx.accounts.proposal_acc.key(),
+                ctx.accounts.proposal_acc.id,
+                CallbackStage::CastVote,
+                aborted,
+            )
+        }
+    };
+
+    count_round_ballot(
+        &mut ctx.accounts.proposal_acc,
+        &ctx.accounts.tally_acc,
+        &mut ctx.accounts.round_record,
+        o.ciphertexts,
+        o.nonce,
+    )
+}
+
+// ============================================================
+// Account Structs
+// ============================================================
+
+#[derive(Accounts)]
+pub struct OpenRoundRecords<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub ballot_acc: Account<'info, Ballot>,
+}
+
+#[queue_computation_accounts("cast_round_choice", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct CastRoundChoice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed, space = 9, payer = payer,
+        seeds = [&SIGN_PDA_SEED], bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_ROUND_CHOICE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: Proposal authority pubkey
+    #[account(address = proposal_acc.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"proposal", authority.key().as_ref(), _id.to_le_bytes().as_ref()],
+        bump = proposal_acc.bump,
+        has_one = authority,
+        constraint = proposal_acc.round_slot.is_some() @ ErrorCode::RoundRecordsNotOpen,
+    )]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    /// Shared by the voter's races on the ballot; the first creates it.
+    #[account(
+        init_if_needed, payer = payer,
+        space = 8 + RoundRecord::INIT_SPACE,
+        seeds = [b"round_voter", proposal_acc.registration_scope(proposal_acc.key()).as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub round_record: Account<'info, RoundRecord>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Box<Account<'info, GlobalStats>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+    #[account(mut, seeds = [b"fee_vault"], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+    /// Required for `QuorumKind::SupplyBps`: the voter's account of
+    /// `proposal_acc.quorum_mint`, whose balance counts toward turnout.
+    /// Under `GatingMode::Token`, the voter's account of `gate_mint`.
+    pub voter_token_account: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
+
+#[callback_accounts("cast_round_choice")]
+#[derive(Accounts)]
+pub struct CastRoundChoiceCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CAST_ROUND_CHOICE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub proposal_acc: Account<'info, ProposalAccount>,
+    #[account(mut, seeds = [b"tally", proposal_acc.key().as_ref()], bump)]
+    pub tally_acc: AccountLoader<'info, TallyAccount>,
+    #[account(mut, seeds = [b"global_stats"], bump = global_stats.bump)]
+    pub global_stats: Account<'info, GlobalStats>,
+    #[account(
+        mut,
+        seeds = [b"round_voter", round_record.ballot.as_ref(), round_record.voter.as_ref()],
+        bump = round_record.bump,
+    )]
+    pub round_record: Account<'info, RoundRecord>,
+}
+
+// ============================================================
+// State Accounts
+// ============================================================
+
+/// A voter's ballots on a `Ballot` keeping round records, PDA
+/// `[b"round_voter", ballot, voter]`, created by their first
+/// `cast_round_choice`.  Bit `ProposalAccount::round_slot` of each mask
+/// stands for one proposal, so the ballot's races share one rent deposit.
+#[account]
+#[derive(InitSpace)]
+pub struct RoundRecord {
+    pub bump: u8,
+    pub ballot: Pubkey,
+    pub voter: Pubkey,
+    /// Proposals voted on; a set bit refuses a second ballot.
+    pub voted: u8,
+    /// Proposals whose `cast_round_choice` callback succeeded.
+    pub counted: u8,
+    /// Per slot, as `VoterRecord::tally_generation`.
+    pub tally_generations: [u32; MAX_BALLOT_PROPOSALS],
+}
+
+// ============================================================
+// Events
+// ============================================================
+
+#[event]
+pub struct RoundRecordsOpenedEvent {
+    pub ballot: Pubkey,
+    pub ballot_id: u32,
+    pub authority: Pubkey,
+    pub proposals: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+// ============================================================
+// Helpers
+// ============================================================
+
+/// As `admit_ballot`, for a proposal whose ballot keeps round records:
+/// sets the proposal's bit in the voter's `RoundRecord` in place of
+/// creating a `VoterRecord`.  `open_round_records` only takes proposals
+/// open to anyone, so there is no credential or allowlist to check.
+fn admit_round_ballot(
+    proposal: &mut Account<ProposalAccount>,
+    round_record: &mut Account<RoundRecord>,
+    round_record_bump: u8,
+    voter: Pubkey,
+    voter_token_account: Option<&InterfaceAccount<token_interface::TokenAccount>>,
+    global_stats: &mut GlobalStats,
+    config: &Config,
+) -> Result<()> {
+    check_ballot_open(proposal, config)?;
+    let slot = proposal.round_slot.ok_or(ErrorCode::RoundRecordsNotOpen)?;
+    let bit = 1u8 << slot;
+    // The record outlives this proposal's ballot: its bit is the guard.
+    require!(round_record.voted & bit == 0, ErrorCode::AlreadyVoted);
+
+    round_record.bump = round_record_bump;
+    round_record.ballot = proposal.registration_scope(proposal.key());
+    round_record.voter = voter;
+    round_record.voted |= bit;
+    round_record.tally_generations[usize::from(slot)] = proposal.tally_generation;
+
+    record_turnout(proposal, global_stats)?;
+    weigh_turnout(proposal, voter, voter_token_account)
+}
+
+/// As `count_ballot`, marking the proposal's bit counted in the voter's
+/// `RoundRecord`.
+fn count_round_ballot(
+    proposal: &mut Account<ProposalAccount>,
+    tally: &AccountLoader<TallyAccount>,
+    round_record: &mut Account<RoundRecord>,
+    vote_state: [[u8; 32]; 5],
+    nonce: u128,
+) -> Result<()> {
+    store_tally(tally, vote_state, nonce)?;
+    proposal.applied_ballots = proposal
+        .applied_ballots
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    proposal.counted_voters = proposal
+        .counted_voters
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    round_record.counted |= 1u8 << proposal.round_slot.unwrap_or_default();
+    emit_vote_cast(proposal.key(), proposal)
+}
@@ -0,0 +1,230 @@
+//! Proposal lifecycle.
+//!
+//! `ProposalAccount::state` places a proposal in a `ProposalState`; the
+//! clock alone moves it from `Registration` to `Voting` to `VotingClosed`.
+//! Only two moves are made by instructions, and `try_transition` is the one
+//! place they are allowed: a reveal finalizes a closed proposal, or it is
+//! marked quorum-failed.  Instructions that act on a proposal without moving
+//! it name the `Stage` they run in and call `check_stage`.
+
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, ProposalState};
+
+/// The moves instructions make, as `(from, to)`.
+pub const TRANSITIONS: [(ProposalState, ProposalState); 2] = [
+    (ProposalState::VotingClosed, ProposalState::Finalized),
+    (ProposalState::VotingClosed, ProposalState::QuorumFailed),
+];
+
+/// Where in its lifecycle a proposal must be for an instruction to act on
+/// it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stage {
+    /// Before the deadline: setting the proposal up, registration included.
+    Setup,
+    /// Before the registration deadline: registering candidates.
+    Registration,
+    /// Between the registration deadline and the deadline: ballots.
+    Voting,
+    /// After the deadline, before the proposal is finalized or fails
+    /// quorum: queuing reveals.
+    Closed,
+    /// Results revealed.
+    Finalized,
+    /// Finalized or quorum-failed: the proposal is done with and its
+    /// accounts can be closed.
+    Settled,
+}
+
+impl Stage {
+    /// Every stage, for exhaustive checks.
+    pub const ALL: [Stage; 6] = [
+        Stage::Setup,
+        Stage::Registration,
+        Stage::Voting,
+        Stage::Closed,
+        Stage::Finalized,
+        Stage::Settled,
+    ];
+
+    /// Whether a proposal in `state` is in this stage.
+    pub fn contains(self, state: ProposalState) -> bool {
+        use ProposalState::*;
+        match self {
+            Stage::Setup => matches!(state, Registration | Voting),
+            Stage::Registration => state == Registration,
+            Stage::Voting => state == Voting,
+            Stage::Closed => state == VotingClosed,
+            Stage::Finalized => state == Finalized,
+            Stage::Settled => matches!(state, Finalized | QuorumFailed),
+        }
+    }
+}
+
+impl ProposalState {
+    /// Every state, for exhaustive checks.
+    pub const ALL: [ProposalState; 5] = [
+        ProposalState::Registration,
+        ProposalState::Voting,
+        ProposalState::VotingClosed,
+        ProposalState::Finalized,
+        ProposalState::QuorumFailed,
+    ];
+}
+
+/// Allow an instruction to move a proposal from `from` to `to`, or fail with
+/// the error that says why it can't.
+pub fn try_transition(from: ProposalState, to: ProposalState) -> Result<()> {
+    if TRANSITIONS.contains(&(from, to)) {
+        return Ok(());
+    }
+    Err(match (from, to) {
+        (_, ProposalState::Finalized | ProposalState::QuorumFailed) => not_closed(from),
+        _ => ErrorCode::InvalidTransition,
+    }
+    .into())
+}
+
+/// Allow an instruction that runs in `stage` to act on a proposal in
+/// `state`, or fail with the error that says why it can't.
+pub fn check_stage(state: ProposalState, stage: Stage) -> Result<()> {
+    if stage.contains(state) {
+        return Ok(());
+    }
+    Err(match stage {
+        Stage::Setup => ErrorCode::VotingPeriodEnded,
+        Stage::Registration => ErrorCode::RegistrationClosed,
+        Stage::Voting if state == ProposalState::Registration => ErrorCode::RegistrationOpen,
+        Stage::Voting => ErrorCode::VotingPeriodEnded,
+        Stage::Closed => not_closed(state),
+        Stage::Finalized | Stage::Settled => ErrorCode::ProposalNotFinalized,
+    }
+    .into())
+}
+
+/// Why a proposal in `state` is not `VotingClosed`.
+fn not_closed(state: ProposalState) -> ErrorCode {
+    match state {
+        ProposalState::Finalized => ErrorCode::ProposalAlreadyFinalized,
+        ProposalState::QuorumFailed => ErrorCode::QuorumAlreadyFailed,
+        _ => ErrorCode::VotingPeriodNotEnded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ProposalState::*;
+
+    fn refusal(result: Result<()>) -> Error {
+        result.expect_err("refused")
+    }
+
+    #[test]
+    fn only_the_table_moves_a_proposal() {
+        for from in ProposalState::ALL {
+            for to in ProposalState::ALL {
+                let allowed = matches!((from, to), (VotingClosed, Finalized) | (VotingClosed, QuorumFailed));
+                assert_eq!(try_transition(from, to).is_ok(), allowed, "{from:?} -> {to:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn refused_moves_say_why() {
+        for to in [Finalized, QuorumFailed] {
+            for from in [Registration, Voting] {
+                assert_eq!(
+                    refusal(try_transition(from, to)),
+                    ErrorCode::VotingPeriodNotEnded.into()
+                );
+            }
+            assert_eq!(
+                refusal(try_transition(Finalized, to)),
+                ErrorCode::ProposalAlreadyFinalized.into()
+            );
+            assert_eq!(
+                refusal(try_transition(QuorumFailed, to)),
+                ErrorCode::QuorumAlreadyFailed.into()
+            );
+        }
+        for from in ProposalState::ALL {
+            for to in [Registration, Voting, VotingClosed] {
+                assert_eq!(refusal(try_transition(from, to)), ErrorCode::InvalidTransition.into());
+            }
+        }
+    }
+
+    #[test]
+    fn stages_cover_their_states() {
+        let expected: [(Stage, &[ProposalState]); 6] = [
+            (Stage::Setup, &[Registration, Voting]),
+            (Stage::Registration, &[Registration]),
+            (Stage::Voting, &[Voting]),
+            (Stage::Closed, &[VotingClosed]),
+            (Stage::Finalized, &[Finalized]),
+            (Stage::Settled, &[Finalized, QuorumFailed]),
+        ];
+        assert_eq!(expected.map(|(stage, _)| stage), Stage::ALL);
+        for (stage, states) in expected {
+            for state in ProposalState::ALL {
+                assert_eq!(
+                    check_stage(state, stage).is_ok(),
+                    states.contains(&state),
+                    "{state:?} in {stage:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn refused_stages_say_why() {
+        let cases = [
+            (Stage::Setup, VotingClosed, ErrorCode::VotingPeriodEnded),
+            (Stage::Setup, Finalized, ErrorCode::VotingPeriodEnded),
+            (Stage::Setup, QuorumFailed, ErrorCode::VotingPeriodEnded),
+            (Stage::Registration, Voting, ErrorCode::RegistrationClosed),
+            (Stage::Registration, VotingClosed, ErrorCode::RegistrationClosed),
+            (Stage::Registration, Finalized, ErrorCode::RegistrationClosed),
+            (Stage::Registration, QuorumFailed, ErrorCode::RegistrationClosed),
+            (Stage::Voting, Registration, ErrorCode::RegistrationOpen),
+            (Stage::Voting, VotingClosed, ErrorCode::VotingPeriodEnded),
+            (Stage::Voting, Finalized, ErrorCode::VotingPeriodEnded),
+            (Stage::Voting, QuorumFailed, ErrorCode::VotingPeriodEnded),
+            (Stage::Closed, Registration, ErrorCode::VotingPeriodNotEnded),
+            (Stage::Closed, Voting, ErrorCode::VotingPeriodNotEnded),
+            (Stage::Closed, Finalized, ErrorCode::ProposalAlreadyFinalized),
+            (Stage::Closed, QuorumFailed, ErrorCode::QuorumAlreadyFailed),
+            (Stage::Finalized, Registration, ErrorCode::ProposalNotFinalized),
+            (Stage::Finalized, Voting, ErrorCode::ProposalNotFinalized),
+            (Stage::Finalized, VotingClosed, ErrorCode::ProposalNotFinalized),
+            (Stage::Finalized, QuorumFailed, ErrorCode::ProposalNotFinalized),
+            (Stage::Settled, Registration, ErrorCode::ProposalNotFinalized),
+            (Stage::Settled, Voting, ErrorCode::ProposalNotFinalized),
+            (Stage::Settled, VotingClosed, ErrorCode::ProposalNotFinalized),
+        ];
+        let refused = Stage::ALL
+            .iter()
+            .flat_map(|stage| ProposalState::ALL.map(|state| (*stage, state)))
+            .filter(|(stage, state)| !stage.contains(*state))
+            .count();
+        assert_eq!(cases.len(), refused);
+        for (stage, state, expected) in cases {
+            assert_eq!(
+                refusal(check_stage(state, stage)),
+                expected.into(),
+                "{state:?} in {stage:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_move_leaves_the_closed_stage_for_a_settled_one() {
+        for (from, to) in TRANSITIONS {
+            assert!(Stage::Closed.contains(from));
+            assert!(Stage::Settled.contains(to));
+            assert!(check_stage(to, Stage::Closed).is_err());
+        }
+    }
+}
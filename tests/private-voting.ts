@@ -30,6 +30,11 @@ import { expect } from "chai";
 
 const ENCRYPTION_KEY_MESSAGE = "arcvote-encryption-key-v1";
 
+// A `MixQueue`'s header (discriminator, bump, proposal, count, seed) and
+// each `HeldBallot` record after it.
+const MIX_QUEUE_HEADER_LEN = 8 + 1 + 32 + 4 + 32;
+const HELD_BALLOT_LEN = 32 + 4 * 32 + 32 + 16 + 8 + 8;
+
 function deriveEncryptionKey(
   wallet: anchor.web3.Keypair,
   message: string
@@ -70,6 +75,87 @@ async function getMXEPublicKeyWithRetry(
   throw new Error(`Failed to get MXE public key after ${maxRetries} attempts`);
 }
 
+const TOKEN_2022_PROGRAM_ID = new PublicKey(
+  "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+);
+const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey(
+  "ATokenGPvbdGVxr1b2hvZbsiqW5xAW25efTNsLJA8knL"
+);
+
+function associatedTokenAddress(owner: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  )[0];
+}
+
+/** Associated token program `CreateIdempotent` for a Token-2022 mint. */
+function createAssociatedTokenAccountIdempotent(
+  payer: PublicKey,
+  owner: PublicKey,
+  mint: PublicKey
+): anchor.web3.TransactionInstruction {
+  return new anchor.web3.TransactionInstruction({
+    programId: ASSOCIATED_TOKEN_PROGRAM_ID,
+    keys: [
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: associatedTokenAddress(owner, mint), isSigner: false, isWritable: true },
+      { pubkey: owner, isSigner: false, isWritable: false },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: anchor.web3.SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: Buffer.from([1]),
+  });
+}
+
+/** Create and initialize a Token-2022 `mint` with no freeze authority. */
+async function createMintInstructions(
+  connection: anchor.web3.Connection,
+  payer: PublicKey,
+  mint: PublicKey,
+  authority: PublicKey,
+  decimals: number
+): Promise<anchor.web3.TransactionInstruction[]> {
+  const MINT_SIZE = 82;
+  return [
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: payer,
+      newAccountPubkey: mint,
+      space: MINT_SIZE,
+      lamports: await connection.getMinimumBalanceForRentExemption(MINT_SIZE),
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    // InitializeMint2: decimals, mint authority, no freeze authority.
+    new anchor.web3.TransactionInstruction({
+      programId: TOKEN_2022_PROGRAM_ID,
+      keys: [{ pubkey: mint, isSigner: false, isWritable: true }],
+      data: Buffer.concat([Buffer.from([20, decimals]), authority.toBuffer(), Buffer.from([0])]),
+    }),
+  ];
+}
+
+/** Token-2022 `MintTo` into `owner`'s associated account. */
+function mintToInstruction(
+  mint: PublicKey,
+  owner: PublicKey,
+  authority: PublicKey,
+  amount: number
+): anchor.web3.TransactionInstruction {
+  const data = Buffer.alloc(9);
+  data.writeUInt8(7, 0);
+  data.writeBigUInt64LE(BigInt(amount), 1);
+  return new anchor.web3.TransactionInstruction({
+    programId: TOKEN_2022_PROGRAM_ID,
+    keys: [
+      { pubkey: mint, isSigner: false, isWritable: true },
+      { pubkey: associatedTokenAddress(owner, mint), isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+    ],
+    data,
+  });
+}
+
 function sleep(ms: number): Promise<void> {
   return new Promise((r) => setTimeout(r, ms));
 }
@@ -96,6 +182,47 @@ describe("ArcVote — Private Quadratic Voting", () => {
   const arciumEnv = getArciumEnv();
   const clusterAccount = getClusterAccAddress(arciumEnv.arciumClusterOffset);
 
+  const [configPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+  const [feeVaultPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("fee_vault")],
+    program.programId
+  );
+  const configParams = (
+    admin: PublicKey,
+    {
+      paused = false,
+      proposalFee = 0,
+      proposalDeposit = 0,
+      treasury = admin,
+      proposalsPerEpoch = 0,
+      whitelistedProposalsPerEpoch = 0,
+      permissionedCreation = false,
+      candidateDeposit = 0,
+      allowedGatingModes = 1, // GatingMode::Open
+      emergencyMinDuration = 0,
+      emergencySupermajorityBps = 0,
+    } = {}
+  ) => ({
+    admin,
+    proposalFee: new anchor.BN(proposalFee),
+    voteFee: new anchor.BN(0),
+    revealTimeout: new anchor.BN(600),
+    paused,
+    allowedGatingModes,
+    proposalDeposit: new anchor.BN(proposalDeposit),
+    council: admin,
+    treasury,
+    proposalsPerEpoch,
+    whitelistedProposalsPerEpoch,
+    permissionedCreation,
+    candidateDeposit: new anchor.BN(candidateDeposit),
+    emergencyMinDuration: new anchor.BN(emergencyMinDuration),
+    emergencySupermajorityBps,
+  });
+
   it("full quadratic voting lifecycle: create, allocate credits, threshold reveal", async () => {
     const PROPOSAL_ID = 1;
     const VOICE_CREDITS = 100;
@@ -112,9 +239,63 @@ describe("ArcVote — Private Quadratic Voting", () => {
     console.log("\n=== Initializing computation definitions ===");
     await initCompDef(program, provider, owner, "init_tallies", "initTalliesCompDef");
     await initCompDef(program, provider, owner, "cast_vote", "initVoteCompDef");
+    await initCompDef(program, provider, owner, "cast_likert", "initLikertCompDef");
+    await initCompDef(program, provider, owner, "cast_choice", "initChoiceCompDef");
+    await initCompDef(program, provider, owner, "cast_ballot_choices", "initBallotChoicesCompDef");
+    await initCompDef(program, provider, owner, "cast_round_choice", "initRoundChoiceCompDef");
     await initCompDef(program, provider, owner, "reveal_results", "initRevealCompDef");
+    await initCompDef(program, provider, owner, "reveal_outcome", "initOutcomeCompDef");
+    await initCompDef(program, provider, owner, "cast_pairwise", "initPairwiseCompDef");
+    await initCompDef(program, provider, owner, "reveal_pairwise", "initPairwiseRevealCompDef");
+    await initCompDef(program, provider, owner, "init_write_ins", "initWriteInsCompDef");
+    await initCompDef(program, provider, owner, "cast_write_in", "initWriteInCompDef");
+    await initCompDef(program, provider, owner, "reveal_write_in", "initWriteInRevealCompDef");
+    await initCompDef(program, provider, owner, "cast_jury_scores", "initJuryCompDef");
+    await initCompDef(program, provider, owner, "cast_approval", "initApprovalCompDef");
+    await initCompDef(program, provider, owner, "reveal_budget_box", "initBudgetBoxCompDef");
+    await initCompDef(program, provider, owner, "release_identity", "initIdentityCompDef");
+    await initCompDef(program, provider, owner, "reveal_quorum_progress", "initQuorumProgressCompDef");
+    await initCompDef(program, provider, owner, "init_spend_histogram", "initSpendHistogramCompDef");
+    await initCompDef(program, provider, owner, "cast_vote_histogram", "initHistogramVoteCompDef");
+    await initCompDef(program, provider, owner, "reveal_spend_histogram", "initHistogramRevealCompDef");
+    await initCompDef(program, provider, owner, "init_council_tallies", "initCouncilTalliesCompDef");
+    await initCompDef(program, provider, owner, "cast_council_choice", "initCouncilChoiceCompDef");
+    await initCompDef(program, provider, owner, "reveal_chambers", "initChambersRevealCompDef");
+    await initCompDef(program, provider, owner, "init_rankings", "initRankingsCompDef");
+    await initCompDef(program, provider, owner, "cast_ranked_vote", "initRankedVoteCompDef");
+    await initCompDef(program, provider, owner, "reveal_ranked", "initRankedRevealCompDef");
+    await initCompDef(program, provider, owner, "cast_borda", "initBordaCompDef");
+    await initCompDef(program, provider, owner, "init_preferences", "initPreferencesCompDef");
+    await initCompDef(program, provider, owner, "cast_condorcet", "initCondorcetVoteCompDef");
+    await initCompDef(program, provider, owner, "reveal_condorcet", "initCondorcetRevealCompDef");
+    await initCompDef(program, provider, owner, "cast_star", "initStarVoteCompDef");
+    await initCompDef(program, provider, owner, "reveal_star", "initStarRevealCompDef");
+    await initCompDef(program, provider, owner, "cast_range", "initRangeCompDef");
+    await initCompDef(program, provider, owner, "cast_vote_batch", "initVoteBatchCompDef");
+    await initCompDef(program, provider, owner, "init_credit_bank", "initCreditBankCompDef");
+    await initCompDef(program, provider, owner, "cast_vote_banked", "initBankedVoteCompDef");
+    await initCompDef(program, provider, owner, "seal_budget", "initSealBudgetCompDef");
+    await initCompDef(program, provider, owner, "cast_vote_budgeted", "initBudgetedVoteCompDef");
     console.log("All comp defs initialized.\n");
 
+    // ---- Initialize protocol config (upgrade authority only) ----
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await program.methods
+      .initializeConfig(configParams(owner.publicKey))
+      .accountsPartial({
+        payer: owner.publicKey,
+        authority: owner.publicKey,
+        config: configPDA,
+        programData,
+      })
+      .rpc({ commitment: "confirmed" });
+    const config = await program.account.config.fetch(configPDA);
+    expect(config.admin.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(config.paused).to.equal(false);
+
     // ---- Create voter keypairs and airdrop SOL ----
     const voters = [
       anchor.web3.Keypair.generate(),
@@ -150,11 +331,18 @@ describe("ArcVote — Private Quadratic Voting", () => {
         ["Solana", "Ethereum", "Avalanche", "Sui"],
         4,
         deadline,
+        new anchor.BN(0),
         new anchor.BN(VOICE_CREDITS),
         QUORUM,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
         new anchor.BN(deserializeLE(proposalNonce).toString())
       )
       .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
         computationAccount: getComputationAccAddress(
           arciumEnv.arciumClusterOffset,
           proposalComputationOffset
@@ -190,6 +378,24 @@ describe("ArcVote — Private Quadratic Voting", () => {
       program.programId
     );
 
+    // The encrypted counters live in the proposal's own tally account.
+    const [tallyPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("tally"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const tally = await program.account.tallyAccount.fetch(tallyPDA);
+    expect(tally.proposal.toBase58()).to.equal(proposalPDA.toBase58());
+    expect(tally.voteState.some((c) => c.some((b) => b !== 0))).to.equal(true);
+
+    // ---- Sponsor the voters' Arcium fees ----
+    const SPONSORSHIP = 100_000_000;
+    await program.methods
+      .fundSponsorship(new anchor.BN(SPONSORSHIP))
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    const sponsored = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(sponsored.sponsorBalance.toNumber()).to.equal(SPONSORSHIP);
+
     // ---- Cast quadratic votes ----
     // Three different strategies to demonstrate QV mechanics:
     //
@@ -264,6 +470,9 @@ describe("ArcVote — Private Quadratic Voting", () => {
           ),
           authority: owner.publicKey,
           proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: null,
         })
         .signers([voter])
         .rpc({ skipPreflight: true, commitment: "confirmed" });
@@ -302,6 +511,7 @@ describe("ArcVote — Private Quadratic Voting", () => {
     const revealSig = await program.methods
       .revealResults(revealComputationOffset, PROPOSAL_ID)
       .accountsPartial({
+        authority: owner.publicKey,
         computationAccount: getComputationAccAddress(
           arciumEnv.arciumClusterOffset,
           revealComputationOffset
@@ -353,8 +563,111 @@ describe("ArcVote — Private Quadratic Voting", () => {
     const proposalAcc = await program.account.proposalAccount.fetch(proposalPDA);
     expect(proposalAcc.isFinalized).to.equal(true);
     expect(proposalAcc.voterCount).to.equal(3);
+    expect(proposalAcc.firstVoteSlot.toNumber()).to.be.greaterThan(0);
+    expect(proposalAcc.sponsorBalance.toNumber()).to.be.at.most(SPONSORSHIP);
+    expect(proposalAcc.sponsoredVotes).to.be.at.most(3);
     expect(proposalAcc.voiceCredits.toString()).to.equal("100");
     expect(proposalAcc.quorum).to.equal(2);
+    expect(proposalAcc.results.map((v) => v.toString())).to.deep.equal([
+      "12",
+      "8",
+      "11",
+      "0",
+      "31",
+    ]);
+    expect(proposalAcc.winner).to.equal(0);
+    expect(proposalAcc.resultAttestation.cluster.toBase58()).to.equal(
+      clusterAccount.toBase58()
+    );
+    expect(proposalAcc.resultAttestation.signature.some((b) => b !== 0)).to.equal(
+      true
+    );
+
+    // Verify the read-only status view
+    const status = await program.methods
+      .getProposalStatus()
+      .accounts({ proposalAcc: proposalPDA })
+      .view();
+    expect(status.state).to.deep.equal({ finalized: {} });
+    expect(status.timeRemaining.toString()).to.equal("0");
+    expect(status.quorumTarget.toNumber()).to.equal(QUORUM);
+    expect(status.quorumReached).to.equal(true);
+    expect(status.quorumProgressBps).to.equal(10000);
+
+    // Verify protocol-wide counters
+    const [globalStatsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global_stats")],
+      program.programId
+    );
+    const stats = await program.account.globalStats.fetch(globalStatsPDA);
+    expect(stats.totalProposals.toNumber()).to.be.at.least(1);
+    expect(stats.totalFinalized.toNumber()).to.be.at.least(1);
+    expect(stats.totalBallots.toNumber()).to.be.at.least(3);
+
+    // Mint a non-transferable "I voted" receipt to the first voter
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .createReceiptMint()
+      .accountsPartial({
+        payer: owner.publicKey,
+        proposalAcc: proposalPDA,
+        receiptMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+    const receiptAccount = associatedTokenAddress(voters[0].publicKey, receiptMint);
+    await program.methods
+      .claimReceipt()
+      .accountsPartial({
+        voter: voters[0].publicKey,
+        proposalAcc: proposalPDA,
+        receiptMint,
+        voterTokenAccount: receiptAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .preInstructions([
+        createAssociatedTokenAccountIdempotent(
+          voters[0].publicKey,
+          voters[0].publicKey,
+          receiptMint
+        ),
+      ])
+      .signers([voters[0]])
+      .rpc({ commitment: "confirmed" });
+    const receiptBalance = await provider.connection.getTokenAccountBalance(
+      receiptAccount
+    );
+    expect(receiptBalance.value.amount).to.equal("1");
+
+    let reclaimed = false;
+    try {
+      await program.methods
+        .claimReceipt()
+        .accountsPartial({
+          voter: voters[0].publicKey,
+          proposalAcc: proposalPDA,
+          receiptMint,
+          voterTokenAccount: receiptAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([voters[0]])
+        .rpc({ commitment: "confirmed" });
+      reclaimed = true;
+    } catch (err) {
+      expect(String(err)).to.include("ReceiptAlreadyClaimed");
+    }
+    expect(reclaimed).to.equal(false);
+
+    // Close the finalized proposal and reclaim rent
+    await program.methods
+      .closeProposal(PROPOSAL_ID)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    const closed = await provider.connection.getAccountInfo(proposalPDA);
+    expect(closed).to.equal(null);
 
     console.log("\n=== QV demonstration ===");
     console.log("Voter 2 went all-in on Avalanche (10 effective votes, cost=100)");
@@ -363,7 +676,11294 @@ describe("ArcVote — Private Quadratic Voting", () => {
     console.log("  This is the power of QV — concentration has diminishing returns.");
     console.log("\nAll assertions passed!");
   });
-});
+
+  it("marks a proposal that missed quorum and closes it", async () => {
+    const PROPOSAL_ID = 2;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 5
+    );
+
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Nobody shows up",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const failedEventPromise = awaitEvent("quorumFailedEvent");
+    await program.methods
+      .markQuorumFailed()
+      .accountsPartial({ payer: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    const failedEvent = await failedEventPromise;
+    expect(failedEvent.voterCount).to.equal(0);
+    expect(failedEvent.quorum).to.equal(1);
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.quorumFailed).to.equal(true);
+    expect(proposal.isFinalized).to.equal(false);
+
+    await program.methods
+      .closeProposal(PROPOSAL_ID)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    const closed = await provider.connection.getAccountInfo(proposalPDA);
+    expect(closed).to.equal(null);
+
+    // The tombstone retires the id, so nothing keyed by the old proposal
+    // can be mistaken for a new one's.
+    const [closedPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("closed_proposal"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const tombstone = await program.account.closedProposal.fetch(closedPDA);
+    expect(tombstone.authority.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(tombstone.id).to.equal(PROPOSAL_ID);
+
+    const reuseOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .createProposal(
+          reuseOffset,
+          PROPOSAL_ID,
+          "Nobody shows up, again",
+          ["Yes", "No"],
+          2,
+          new anchor.BN(deadline.toNumber() + 3600),
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            reuseOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("reusing a closed proposal's id should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("ProposalIdRetired");
+    }
+  });
+
+  it("blocks new proposals while paused and only lets the admin update config", async () => {
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const stranger = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      stranger.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+    try {
+      await program.methods
+        .updateConfig(configParams(stranger.publicKey))
+        .accountsPartial({ admin: stranger.publicKey, config: configPDA })
+        .signers([stranger])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("non-admin config update should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidAuthority");
+    }
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { paused: true }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .createProposal(
+          computationOffset,
+          3,
+          "Paused",
+          ["Yes", "No"],
+          2,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("create_proposal should fail while paused");
+    } catch (e) {
+      expect(e.toString()).to.include("ProtocolPaused");
+    }
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    const config = await program.account.config.fetch(configPDA);
+    expect(config.paused).to.equal(false);
+  });
+
+  it("collects proposal fees and lets the admin withdraw them", async () => {
+    const PROPOSAL_ID = 4;
+    const FEE = 10_000_000;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const treasury = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { proposalFee: FEE }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    const before = await program.account.feeVault.fetch(feeVaultPDA);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Fee paying",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const collected = await program.account.feeVault.fetch(feeVaultPDA);
+    expect(
+      collected.totalCollected.sub(before.totalCollected).toNumber()
+    ).to.equal(FEE);
+
+    const withdrawnEventPromise = awaitEvent("feesWithdrawnEvent");
+    await program.methods
+      .withdrawFees(new anchor.BN(FEE))
+      .accountsPartial({
+        admin: owner.publicKey,
+        config: configPDA,
+        feeVault: feeVaultPDA,
+        recipient: treasury.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    const withdrawnEvent = await withdrawnEventPromise;
+    expect(withdrawnEvent.amount.toNumber()).to.equal(FEE);
+    expect(withdrawnEvent.recipient.toBase58()).to.equal(
+      treasury.publicKey.toBase58()
+    );
+    expect(await provider.connection.getBalance(treasury.publicKey)).to.equal(FEE);
+
+    const after = await program.account.feeVault.fetch(feeVaultPDA);
+    expect(
+      after.totalWithdrawn.sub(before.totalWithdrawn).toNumber()
+    ).to.equal(FEE);
+
+    try {
+      await program.methods
+        .withdrawFees(new anchor.BN(1))
+        .accountsPartial({
+          admin: owner.publicKey,
+          config: configPDA,
+          feeVault: feeVaultPDA,
+          recipient: treasury.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("withdrawing more than was collected should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("InsufficientFees");
+    }
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("lets the council slash a spam proposal's deposit to the treasury", async () => {
+    const PROPOSAL_ID = 5;
+    const DEPOSIT = 50_000_000;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const treasury = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .updateConfig(
+        configParams(owner.publicKey, {
+          proposalDeposit: DEPOSIT,
+          treasury: treasury.publicKey,
+        })
+      )
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Buy my token",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const bonded = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(bonded.deposit.toNumber()).to.equal(DEPOSIT);
+
+    const slashedEventPromise = awaitEvent("depositSlashedEvent");
+    await program.methods
+      .slashDeposit({ spam: {} })
+      .accountsPartial({
+        council: owner.publicKey,
+        config: configPDA,
+        proposalAcc: proposalPDA,
+        treasury: treasury.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    const slashedEvent = await slashedEventPromise;
+    expect(slashedEvent.amount.toNumber()).to.equal(DEPOSIT);
+    expect(slashedEvent.reason).to.deep.equal({ spam: {} });
+    expect(await provider.connection.getBalance(treasury.publicKey)).to.equal(
+      DEPOSIT
+    );
+    const slashed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(slashed.deposit.toNumber()).to.equal(0);
+
+    try {
+      await program.methods
+        .slashDeposit({ spam: {} })
+        .accountsPartial({
+          council: owner.publicKey,
+          config: configPDA,
+          proposalAcc: proposalPDA,
+          treasury: treasury.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("slashing twice should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("NoDeposit");
+    }
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("rate-limits proposals per authority unless whitelisted", async () => {
+    const PROPOSAL_ID = 6;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const [creatorPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const creator = await program.account.creatorRecord.fetch(creatorPDA);
+    expect(creator.totalProposals.toNumber()).to.be.at.least(1);
+
+    // Cap everyone at what the owner already created this epoch.
+    await program.methods
+      .updateConfig(
+        configParams(owner.publicKey, {
+          proposalsPerEpoch: creator.epochProposals,
+          whitelistedProposalsPerEpoch: creator.epochProposals + 1,
+        })
+      )
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const createProposal = async () => {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          PROPOSAL_ID,
+          "One too many",
+          ["Yes", "No"],
+          2,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      return computationOffset;
+    };
+
+    try {
+      await createProposal();
+      expect.fail("create_proposal past the cap should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("ProposalRateLimited");
+    }
+
+    await program.methods
+      .setCreator(owner.publicKey, true, false)
+      .accountsPartial({ payer: owner.publicKey, admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    const computationOffset = await createProposal();
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const whitelisted = await program.account.creatorRecord.fetch(creatorPDA);
+    expect(whitelisted.whitelisted).to.equal(true);
+    expect(whitelisted.epochProposals).to.equal(creator.epochProposals + 1);
+
+    await program.methods
+      .setCreator(owner.publicKey, false, false)
+      .accountsPartial({ payer: owner.publicKey, admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("restricts proposal creation to allowed creators when permissioned", async () => {
+    const PROPOSAL_ID = 7;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { permissionedCreation: true }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const createProposal = async () => {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          PROPOSAL_ID,
+          "Team only",
+          ["Yes", "No"],
+          2,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      return computationOffset;
+    };
+
+    try {
+      await createProposal();
+      expect.fail("create_proposal by a creator not on the allowlist should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("CreatorNotAllowed");
+    }
+
+    await program.methods
+      .setCreator(owner.publicKey, false, true)
+      .accountsPartial({ payer: owner.publicKey, admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    const computationOffset = await createProposal();
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("lets an unfunded authority such as a multisig vault own a proposal", async () => {
+    const PROPOSAL_ID = 8;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    // Holds no SOL: it only signs, while the owner pays.
+    const vault = anchor.web3.Keypair.generate();
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Treasury budget",
+        ["Approve", "Reject"],
+        2,
+        new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        payer: owner.publicKey,
+        authority: vault.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .signers([vault])
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        vault.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.authority.toBase58()).to.equal(vault.publicKey.toBase58());
+    expect(await provider.connection.getBalance(vault.publicKey)).to.equal(0);
+  });
+
+  it("fixes a percentage-of-registered-voters quorum at creation", async () => {
+    const PROPOSAL_ID = 9;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const createProposal = async (quorumBps: number) => {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          PROPOSAL_ID,
+          "Council seat",
+          ["Alice", "Bob"],
+          2,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+          new anchor.BN(0),
+          new anchor.BN(100),
+          quorumBps,
+          { registeredBps: {} },
+          new anchor.BN(5),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      return computationOffset;
+    };
+
+    try {
+      await createProposal(10_001);
+      expect.fail("a quorum above 100% should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidQuorum");
+    }
+
+    // 50% of 5 registered voters rounds up to 3 ballots.
+    const computationOffset = await createProposal(5_000);
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.quorumKind).to.deep.equal({ registeredBps: {} });
+    expect(proposal.electorate.toNumber()).to.equal(5);
+
+    const status = await program.methods
+      .getProposalStatus()
+      .accounts({ proposalAcc: proposalPDA })
+      .view();
+    expect(status.quorumTarget.toNumber()).to.equal(3);
+    expect(status.turnout.toNumber()).to.equal(0);
+    expect(status.quorumReached).to.equal(false);
+  });
+
+  it("counts a registrar quorum's electorate as credentials are issued", async () => {
+    const PROPOSAL_ID = 52;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Council seat",
+        ["Alice", "Bob"],
+        2,
+        new anchor.BN(Math.floor(Date.now() / 1000) + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        5_000,
+        { registrarBps: {} },
+        // Ignored: the registrar's issuances make up the electorate.
+        new anchor.BN(5),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).electorate.toNumber()).to.equal(0);
+
+    // A registrar whose secret key is 1, as in the credential test.
+    const registrar = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      registrar.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const registrarKey = Array.from(
+      Buffer.from(
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2" +
+          "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed" +
+          "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b" +
+          "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        "hex"
+      )
+    );
+    const g1 = (y: number) => {
+      const point = new Array(64).fill(0);
+      point[31] = 1;
+      point[63] = y;
+      return point;
+    };
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b11 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .openCredentials(registrar.publicKey, registrarKey)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    for (let i = 0; i < 3; i++) {
+      await program.methods
+        .issueCredential(anchor.web3.Keypair.generate().publicKey, g1(2), g1(2))
+        .accountsPartial({ registrar: registrar.publicKey, proposalAcc: proposalPDA })
+        .signers([registrar])
+        .rpc({ commitment: "confirmed" });
+    }
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.quorumKind).to.deep.equal({ registrarBps: {} });
+    expect(proposal.electorate.toNumber()).to.equal(3);
+
+    // 50% of 3 registered voters rounds up to 2 ballots.
+    const status = await program.methods
+      .getProposalStatus()
+      .accounts({ proposalAcc: proposalPDA })
+      .view();
+    expect(status.quorumTarget.toNumber()).to.equal(2);
+    expect(status.quorumReached).to.equal(false);
+  });
+
+  it("builds an election's options from registered candidates", async () => {
+    const PROPOSAL_ID = 10;
+    const CANDIDATE_DEPOSIT = 0.01 * anchor.web3.LAMPORTS_PER_SOL;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { candidateDeposit: CANDIDATE_DEPOSIT }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const now = Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Chair election",
+        [],
+        0,
+        new anchor.BN(now + 120),
+        new anchor.BN(now + 60),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const status = await program.methods
+      .getProposalStatus()
+      .accounts({ proposalAcc: proposalPDA })
+      .view();
+    expect(status.state).to.deep.equal({ registration: {} });
+
+    const candidates = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    const names = ["Alice", "Bob"];
+    for (const [i, candidate] of candidates.entries()) {
+      const airdrop = await provider.connection.requestAirdrop(
+        candidate.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      await program.methods
+        .registerCandidate(names[i])
+        .accountsPartial({ candidate: candidate.publicKey, proposalAcc: proposalPDA })
+        .signers([candidate])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    // The Candidate PDA already exists, so its `init` fails.
+    let duplicateRejected = false;
+    try {
+      await program.methods
+        .registerCandidate("Alice again")
+        .accountsPartial({ candidate: candidates[0].publicKey, proposalAcc: proposalPDA })
+        .signers([candidates[0]])
+        .rpc({ commitment: "confirmed" });
+    } catch {
+      duplicateRejected = true;
+    }
+    expect(duplicateRejected).to.equal(true);
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.options).to.deep.equal(names);
+    expect(proposal.numOptions).to.equal(2);
+
+    const [candidatePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("candidate"), proposalPDA.toBuffer(), candidates[1].publicKey.toBuffer()],
+      program.programId
+    );
+    const candidate = await program.account.candidate.fetch(candidatePDA);
+    expect(candidate.index).to.equal(1);
+    expect(candidate.name).to.equal("Bob");
+    expect(candidate.deposit.toNumber()).to.equal(CANDIDATE_DEPOSIT);
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("lets anyone reveal a poll at its deadline, without quorum or deposit", async () => {
+    const PROPOSAL_ID = 11;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    // Governance proposals would bond this; polls post nothing.
+    await program.methods
+      .updateConfig(
+        configParams(owner.publicKey, {
+          proposalDeposit: anchor.web3.LAMPORTS_PER_SOL / 100,
+        })
+      )
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 5
+    );
+    await program.methods
+      .createPoll(
+        computationOffset,
+        PROPOSAL_ID,
+        "Move the community call?",
+        ["Tuesday", "Thursday"],
+        2,
+        deadline,
+        new anchor.BN(100),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const [pollPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const poll = await program.account.proposalAccount.fetch(pollPDA);
+    expect(poll.kind).to.deep.equal({ poll: {} });
+    expect(poll.deposit.toNumber()).to.equal(0);
+    expect(poll.quorum).to.equal(0);
+
+    const stranger = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      stranger.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+    const revealPoll = (proposalAcc: PublicKey) => {
+      const revealOffset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        revealOffset,
+        program.methods
+          .revealPoll(revealOffset)
+          .accountsPartial({
+            payer: stranger.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              revealOffset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+            ),
+            proposalAcc,
+          })
+          .signers([stranger]),
+      ] as const;
+    };
+
+    // Governance proposals keep their reveal authority.
+    const [governancePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([9]).buffer)),
+      ],
+      program.programId
+    );
+    try {
+      await revealPoll(governancePDA)[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_poll on a governance proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("NotAPoll");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    // Nobody voted, yet the poll reveals: it has no quorum to miss.
+    const revealEventPromise = awaitEvent("resultsRevealedEvent");
+    const [revealOffset, reveal] = revealPoll(pollPDA);
+    await reveal.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+    const revealEvent = await revealEventPromise;
+    expect(revealEvent.proposal.toBase58()).to.equal(pollPDA.toBase58());
+    expect(revealEvent.totalVotes.toNumber()).to.equal(0);
+
+    const revealed = await program.account.proposalAccount.fetch(pollPDA);
+    expect(revealed.isFinalized).to.equal(true);
+  });
+
+  it("tallies Likert ballots into net sentiment per option", async () => {
+    const PROPOSAL_ID = 12;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 40
+    );
+    await program.methods
+      .createPoll(
+        computationOffset,
+        PROPOSAL_ID,
+        "How do you feel about each workstream?",
+        ["Fees", "UX", "Docs"],
+        3,
+        deadline,
+        new anchor.BN(100),
+        { likert: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    const cast = (
+      method: "castLikert" | "castVote",
+      voter: anchor.web3.Keypair,
+      scores: number[]
+    ) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      // Scores go in offset by +2 so the circuit sees 0..=4.
+      const ciphertexts = cipher.encrypt(
+        [0, 1, 2, 3].map((i) => BigInt((scores[i] ?? -2) + 2)),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods[method](
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+          .accountsPartial({
+            payer: voter.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(
+                getCompDefAccOffset(method === "castLikert" ? "cast_likert" : "cast_vote")
+              ).readUInt32LE()
+            ),
+            authority: owner.publicKey,
+            proposalAcc: proposalPDA,
+            voterTokenAccount: null,
+            credential: null,
+            mixQueue: null,
+          })
+          .signers([voter]),
+      ] as const;
+    };
+
+    const voters = [];
+    for (let i = 0; i < 2; i++) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      voters.push(voter);
+    }
+
+    // A quadratic ballot has no place on a Likert proposal.
+    try {
+      await cast("castVote", voters[0], [2, 0, 0])[1].rpc({ commitment: "confirmed" });
+      expect.fail("cast_vote on a Likert proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("WrongBallotKind");
+    }
+
+    //   Voter 0: [+2, -1,  0]
+    //   Voter 1: [+1, -2, +1]
+    // Net sentiment: Fees +3, UX -3, Docs +1.
+    const ballots = [
+      [2, -1, 0],
+      [1, -2, 1],
+    ];
+    for (let i = 0; i < voters.length; i++) {
+      const [offset, tx] = cast("castLikert", voters[i], ballots[i]);
+      await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const sentimentPromise = awaitEvent("sentimentRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealPoll(revealOffset)
+      .accountsPartial({
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+        proposalAcc: proposalPDA,
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const sentiment = await sentimentPromise;
+    expect(sentiment.ballots.toNumber()).to.equal(2);
+    expect(sentiment.netSentiment.slice(0, 3).map((n) => n.toNumber())).to.deep.equal([3, -3, 1]);
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.winner).to.equal(0);
+  });
+
+  it("vetoes a For/Against/Abstain proposal that For would have carried", async () => {
+    const PROPOSAL_ID = 13;
+    const VETO_BPS = 3_000;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Ship v2 this quarter?",
+        ["For", "Against", "Abstain"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { yesNoAbstain: { vetoBps: VETO_BPS } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // For 2, Against 1: For wins the count, but Against holds 33% of
+    // turnout, above the 30% veto threshold.
+    const choices = [0, 0, 1];
+    for (const option of choices) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(
+        [0, 1, 2, 3].map((i) => BigInt(i === option ? 1 : 0)),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castChoice(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_choice")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const reveal = (method: "revealResults" | "revealOutcome", circuit: string) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods[method](offset, PROPOSAL_ID).accountsPartial({
+          authority: owner.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+          ),
+        }),
+      ] as const;
+    };
+
+    // The plain reveal has no veto, so it is refused.
+    try {
+      await reveal("revealResults", "reveal_results")[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_results on a For/Against/Abstain proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("WrongBallotKind");
+    }
+
+    const outcomePromise = awaitEvent("outcomeRevealedEvent");
+    const [revealOffset, tx] = reveal("revealOutcome", "reveal_outcome");
+    await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const outcome = await outcomePromise;
+    expect(outcome.votesFor.toNumber()).to.equal(2);
+    expect(outcome.votesAgainst.toNumber()).to.equal(1);
+    expect(outcome.votesAbstain.toNumber()).to.equal(0);
+    expect(outcome.outcome).to.deep.equal({ vetoed: {} });
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.outcome).to.deep.equal({ vetoed: {} });
+    expect(revealed.winner).to.equal(0);
+  });
+
+  it("discounts the matching a colluding pair earns in a pairwise funding round", async () => {
+    const PROPOSAL_ID = 14;
+    const DISCOUNT = 25;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 90
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Q3 grants round",
+        ["Ring", "Commons", "Docs"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { pairwise: { discount: DISCOUNT } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [pairwiseBoxPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pairwise_box"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // Two wallets go all-in on Ring in lockstep; three back Commons more
+    // modestly.  Plain QF would fund Ring, (10+10)² = 400, over Commons,
+    // (6+6+6)² = 324.  The ring's overlap of 100 is beyond
+    // 3 × 25, so its cross term is dropped: Ring = 100 + 100 = 200.  The
+    // Commons voters overlap by 36 each, between 25/3 and 75, so each of
+    // their three pairs keeps half: Commons = 3·36 + 3·36 = 216.  The
+    // last ballot's 9 votes are for a fourth option the round doesn't
+    // have, so it is sealed as all zeros and funds nothing.
+    const allocations = [
+      [10, 0, 0, 0],
+      [10, 0, 0, 0],
+      [0, 6, 0, 0],
+      [0, 6, 0, 0],
+      [0, 6, 0, 0],
+      [0, 0, 0, 9],
+    ];
+    for (const allocation of allocations) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castPairwise(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_pairwise")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    const box = await program.account.pairwiseBox.fetch(pairwiseBoxPDA);
+    expect(box.count).to.equal(6);
+    expect(box.filled).to.equal(0b111111);
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealPairwise(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_pairwise")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(200);
+    expect(results.option1.toNumber()).to.equal(216);
+    expect(results.option2.toNumber()).to.equal(0);
+    expect(results.option3.toNumber()).to.equal(0);
+    expect(results.totalVotes.toNumber()).to.equal(416);
+    expect(results.winner).to.equal(1);
+  });
+
+  it("weights earlier quadratic ballots more on a time-weighted proposal", async () => {
+    const PROPOSAL_ID = 15;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    // 300% at creation, falling linearly to 100% at the deadline.
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Adopt the new fee curve?",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { timeWeighted: { startPct: 300, endPct: 100 } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // The same 4 votes, No first and Yes half a minute later.
+    const allocations = [
+      [0, 4, 0, 0],
+      [4, 0, 0, 0],
+    ];
+    for (const [i, allocation] of allocations.entries()) {
+      if (i > 0) await sleep(30_000);
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // Tallies are in hundredths of a vote: 4 votes at 100%..300% each.
+    const results = await resultsPromise;
+    const [yes, no] = [results.option0.toNumber(), results.option1.toNumber()];
+    expect(yes % 4).to.equal(0);
+    expect(no % 4).to.equal(0);
+    expect(yes).to.be.within(400, 1200);
+    expect(no).to.be.within(400, 1200);
+    expect(no).to.be.greaterThan(yes);
+    expect(results.totalVotes.toNumber()).to.equal(yes + no);
+    expect(results.winner).to.equal(1);
+  });
+
+  it("counts write-ins per candidate hash and reveals only the top one", async () => {
+    const PROPOSAL_ID = 16;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    // Matches `encryption::write_in_hash` in the Rust SDK.
+    const writeInHash = (name: string) =>
+      deserializeLE(createHash("sha256").update(name.trim().toLowerCase()).digest().subarray(0, 16));
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 75
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Treasurer",
+        ["Alice", "Bob"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [writeInTallyPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("write_ins"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    const openOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .openWriteIns(
+        openOffset,
+        PROPOSAL_ID,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          openOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_write_ins")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(provider, openOffset, program.programId, "confirmed");
+    expect((await program.account.writeInTally.fetch(writeInTallyPDA)).ready).to.equal(true);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    // Two spellings of one candidate and one other.
+    for (const name of ["Carol", " carol ", "Dave"]) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const [ciphertext] = cipher.encrypt([writeInHash(name)], nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castWriteIn(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertext),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_write_in")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).countedVoters).to.equal(3);
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const writeInPromise = awaitEvent("writeInRevealedEvent");
+    const writeInOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealWriteIn(writeInOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          writeInOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_write_in")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, writeInOffset, program.programId, "confirmed");
+
+    const event = await writeInPromise;
+    expect(BigInt(event.candidate.toString())).to.equal(writeInHash("Carol"));
+    expect(event.votes.toNumber()).to.equal(2);
+    const tally = await program.account.writeInTally.fetch(writeInTallyPDA);
+    expect(tally.revealed).to.equal(true);
+    expect(tally.topVotes.toNumber()).to.equal(2);
+  });
+
+  it("ranks applications by a seated jury's summed scores without revealing any juror's", async () => {
+    const PROPOSAL_ID = 17;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 90
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Grants Q3",
+        ["Alpha", "Beta", "Gamma"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { jury: { criteria: 2, maxScore: 10 } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    const reviewers = [];
+    for (let i = 0; i < 4; i++) {
+      const reviewer = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        reviewer.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      reviewers.push(reviewer);
+    }
+    // The last reviewer is never seated.
+    for (const juror of reviewers.slice(0, 3)) {
+      await program.methods
+        .seatJuror(juror.publicKey)
+        .accountsPartial({
+          payer: owner.publicKey,
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    // One row of per-application scores per criterion.
+    const score = (juror: anchor.web3.Keypair, rows: number[][]) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(juror, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const plaintexts = [0, 1, 2].flatMap((c) =>
+        [0, 1, 2, 3].map((a) => BigInt(rows[c]?.[a] ?? 0))
+      );
+      const ciphertexts = cipher.encrypt(plaintexts, nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .castJuryScores(
+            offset,
+            PROPOSAL_ID,
+            ciphertexts.map((ct) => Array.from(ct)),
+            Array.from(publicKey),
+            new anchor.BN(deserializeLE(nonce).toString())
+          )
+          .accountsPartial({
+            payer: juror.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("cast_jury_scores")).readUInt32LE()
+            ),
+            authority: owner.publicKey,
+            proposalAcc: proposalPDA,
+            voterTokenAccount: null,
+          })
+          .signers([juror]),
+      ] as const;
+    };
+
+    try {
+      await score(reviewers[3], [[10, 10, 10]])[1].rpc({ commitment: "confirmed" });
+      expect.fail("an unseated reviewer should not be able to score");
+    } catch (e) {
+      expect(e.toString()).to.include("AccountNotInitialized");
+    }
+
+    //   Juror 0: impact [8, 5,  9], feasibility [7, 6, 10] -> [15, 11, 19]
+    //   Juror 1: impact [6, 9,  7], feasibility [5, 8,  6] -> [11, 17, 13]
+    //   Juror 2: scores 11, off the 0..10 scale, so the ballot is dropped.
+    // Totals: Alpha 26, Beta 28, Gamma 32, ranked Gamma, Beta, Alpha.
+    const ballots = [
+      [[8, 5, 9], [7, 6, 10]],
+      [[6, 9, 7], [5, 8, 6]],
+      [[11, 0, 0], [0, 0, 0]],
+    ];
+    for (let i = 0; i < ballots.length; i++) {
+      const [offset, tx] = score(reviewers[i], ballots[i]);
+      await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const rankingPromise = awaitEvent("juryRankingEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const ranking = await rankingPromise;
+    expect(ranking.jurors.toNumber()).to.equal(2);
+    expect(ranking.scores.map((s) => s.toNumber())).to.deep.equal([26, 28, 32, 0]);
+    expect(Array.from(ranking.ranking)).to.deep.equal([2, 1, 0]);
+
+    const [jurorPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("juror"), proposalPDA.toBuffer(), reviewers[0].publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .closeJuror()
+      .accountsPartial({ authority: owner.publicKey, jurorAcc: jurorPDA })
+      .rpc({ commitment: "confirmed" });
+    expect(await provider.connection.getAccountInfo(jurorPDA)).to.equal(null);
+  });
+
+  it("discards approval ballots over the shortlist limit", async () => {
+    const PROPOSAL_ID = 18;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 40
+    );
+    await program.methods
+      .createPoll(
+        computationOffset,
+        PROPOSAL_ID,
+        "Which two grants should go to the final round?",
+        ["Indexer", "Wallet", "Docs", "Audits"],
+        4,
+        deadline,
+        new anchor.BN(100),
+        { approval: { maxChoices: 2 } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    const cast = async (voter: anchor.web3.Keypair, approved: number[]) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(
+        [0, 1, 2, 3].map((i) => (approved.includes(i) ? BigInt(1) : BigInt(0))),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castApproval(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_approval")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    };
+
+    //   Voter 0: Indexer, Wallet
+    //   Voter 1: Wallet, Audits
+    //   Voter 2: Indexer, Wallet, Docs — over the limit, discarded
+    const ballots = [[0, 1], [1, 3], [0, 1, 2]];
+    for (const approved of ballots) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      await cast(voter, approved);
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealPoll(revealOffset)
+      .accountsPartial({
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+        proposalAcc: proposalPDA,
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.results.slice(0, 4).map((n) => n.toNumber())).to.deep.equal([1, 2, 0, 1]);
+    expect(revealed.results[4].toNumber()).to.equal(2);
+    expect(revealed.winner).to.equal(1);
+  });
+
+  it("funds the best-supported set of options that fits a budget box", async () => {
+    const PROPOSAL_ID = 19;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    const create = (costs: number[]) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .createProposal(
+            offset,
+            PROPOSAL_ID,
+            "Community fund, round 1",
+            ["Bridge", "Garden", "Library", "Mural"],
+            4,
+            deadline,
+            new anchor.BN(0),
+            new anchor.BN(0),
+            1,
+            { voters: {} },
+            new anchor.BN(0),
+            new anchor.BN(0),
+            { budgetBox: { budget: new anchor.BN(100), costs: costs.map((c) => new anchor.BN(c)) } },
+            new anchor.BN(deserializeLE(randomBytes(16)).toString())
+          )
+          .accountsPartial({
+            authority: owner.publicKey,
+            quorumMint: null,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+            ),
+          }),
+      ] as const;
+    };
+
+    // Every option needs a cost.
+    try {
+      await create([70, 40, 40, 0])[1].rpc({ commitment: "confirmed" });
+      expect.fail("a budget box with a free option should be refused");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidBudgetBox");
+    }
+
+    const [computationOffset, tx] = create([70, 40, 40, 50]);
+    await tx.rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // Bridge has the most approvals (3) but leaves no room for anything
+    // else; Garden + Library (2 + 2) fit together and win.
+    const ballots = [[0, 1], [0, 2], [1, 2], [0]];
+    for (const approved of ballots) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(
+        [0, 1, 2, 3].map((i) => (approved.includes(i) ? BigInt(1) : BigInt(0))),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castApproval(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_approval")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const reveal = (method: "revealResults" | "revealBudgetBox", circuit: string) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods[method](offset, PROPOSAL_ID).accountsPartial({
+          authority: owner.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+          ),
+        }),
+      ] as const;
+    };
+
+    // The plain reveal picks no funded set, so it is refused.
+    try {
+      await reveal("revealResults", "reveal_results")[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_results on a budget box should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("WrongBallotKind");
+    }
+
+    const fundedPromise = awaitEvent("budgetBoxFundedEvent");
+    const [revealOffset, revealTx] = reveal("revealBudgetBox", "reveal_budget_box");
+    await revealTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const funded = await fundedPromise;
+    expect(Array.from(funded.funded)).to.deep.equal([1, 2]);
+    expect(funded.spent.toNumber()).to.equal(80);
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.results.slice(0, 5).map((n) => n.toNumber())).to.deep.equal([3, 2, 2, 0, 4]);
+    expect(revealed.funded).to.equal(0b0110);
+    expect(revealed.winner).to.equal(0);
+  });
+
+  it("gates a quadratic proposal behind registrar-issued credentials", async () => {
+    const PROPOSAL_ID = 20;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 600
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Council seat",
+        ["Ana", "Ben"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [registryPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("credentials"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    // A registrar whose secret key is 1: its public key is the G2
+    // generator, and it signs a blinded message by returning it unchanged.
+    const registrar = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      registrar.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const registrarKey = Array.from(
+      Buffer.from(
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2" +
+          "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed" +
+          "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b" +
+          "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        "hex"
+      )
+    );
+    const g1 = (y: number) => {
+      const point = new Array(64).fill(0);
+      point[31] = 1;
+      point[63] = y;
+      return point;
+    };
+
+    const open = () =>
+      program.methods
+        .openCredentials(registrar.publicKey, registrarKey)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+
+    // The admin has to allow credential gating first.
+    try {
+      await open();
+      expect.fail("open_credentials should need GatingMode::Credential");
+    } catch (e) {
+      expect(e.toString()).to.include("GatingModeNotAllowed");
+    }
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b11 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    await open();
+
+    // Without a presented credential, cast_vote is refused.
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const voter = anchor.web3.Keypair.generate();
+    const voterAirdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(voterAirdrop, "confirmed");
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const nonce = randomBytes(16);
+    const ciphertexts = cipher.encrypt([BigInt(6), BigInt(8), BigInt(0), BigInt(0)], nonce);
+    const voteOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .castVote(
+          voteOffset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            voteOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("cast_vote without a credential should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("CredentialRequired");
+    }
+
+    // The registrar's blind signature must pair with its public key.
+    const issue = (signature: number[]) =>
+      program.methods
+        .issueCredential(voter.publicKey, g1(2), signature)
+        .accountsPartial({ registrar: registrar.publicKey, proposalAcc: proposalPDA })
+        .signers([registrar])
+        .rpc({ commitment: "confirmed" });
+    try {
+      await issue(g1(3));
+      expect.fail("a blind signature off the curve should be refused");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidCredential");
+    }
+    await issue(g1(2));
+
+    // A serial revoked before use can't be presented: its credential
+    // account already exists.
+    const serial = Array.from(randomBytes(32));
+    await program.methods
+      .revokeCredential(serial)
+      .accountsPartial({ registrar: registrar.publicKey, proposalAcc: proposalPDA })
+      .signers([registrar])
+      .rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .presentCredential(serial, g1(2))
+        .accountsPartial({ payer: voter.publicKey, proposalAcc: proposalPDA })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a revoked serial should not be presentable");
+    } catch (e) {
+      expect(e.toString()).to.include("already in use");
+    }
+    const status = await program.methods
+      .verifyCredential(serial, g1(2))
+      .accountsPartial({ credentialRegistry: registryPDA })
+      .view();
+    expect(status).to.deep.equal({ invalid: {} });
+
+    const registry = await program.account.credentialRegistry.fetch(registryPDA);
+    expect(registry.issued).to.equal(1);
+    expect(registry.revoked).to.equal(1);
+    expect(registry.presented).to.equal(0);
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.gating).to.deep.equal({ credential: {} });
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("casts a wallet's ballot with an expiring session key", async () => {
+    const PROPOSAL_ID = 21;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Adopt the new logo?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // The wallet stays cold; the session key is a throwaway browser key.
+    const wallet = anchor.web3.Keypair.generate();
+    const sessionKey = anchor.web3.Keypair.generate();
+    for (const key of [wallet, sessionKey]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        key.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+    }
+    const [sessionPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("session"), proposalPDA.toBuffer(), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    const [voterRecordPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter"), proposalPDA.toBuffer(), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const openSession = (expiresAt: number) =>
+      program.methods
+        .openSession(sessionKey.publicKey, new anchor.BN(expiresAt))
+        .accountsPartial({ wallet: wallet.publicKey, proposalAcc: proposalPDA })
+        .signers([wallet])
+        .rpc({ commitment: "confirmed" });
+    const castWith = (signer: anchor.web3.Keypair) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(signer, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt([BigInt(7), BigInt(1), BigInt(0), BigInt(0)], nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .castVoteSession(
+            offset,
+            PROPOSAL_ID,
+            Array.from(ciphertexts[0]),
+            Array.from(ciphertexts[1]),
+            Array.from(ciphertexts[2]),
+            Array.from(ciphertexts[3]),
+            Array.from(publicKey),
+            new anchor.BN(deserializeLE(nonce).toString())
+          )
+          .accountsPartial({
+            payer: signer.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+            ),
+            authority: owner.publicKey,
+            proposalAcc: proposalPDA,
+            session: sessionPDA,
+            voterRecord: voterRecordPDA,
+            voterTokenAccount: null,
+            credential: null,
+            mixQueue: null,
+          })
+          .signers([signer]),
+      ] as const;
+    };
+
+    // A session that has expired can't vote; the session key kills it.
+    await openSession(now + 5);
+    await sleep(8000);
+    try {
+      await castWith(sessionKey)[1].rpc({ commitment: "confirmed" });
+      expect.fail("an expired session should be refused");
+    } catch (e) {
+      expect(e.toString()).to.include("SessionExpired");
+    }
+    await program.methods
+      .closeSession()
+      .accountsPartial({ signer: sessionKey.publicKey, wallet: wallet.publicKey, session: sessionPDA })
+      .signers([sessionKey])
+      .rpc({ commitment: "confirmed" });
+    expect(await provider.connection.getAccountInfo(sessionPDA, "confirmed")).to.be.null;
+
+    await openSession(now + 600);
+    const session = await program.account.votingSession.fetch(sessionPDA);
+    expect(session.sessionKey.toBase58()).to.equal(sessionKey.publicKey.toBase58());
+
+    // Only the authorized key may use the session.
+    const stranger = anchor.web3.Keypair.generate();
+    const strangerAirdrop = await provider.connection.requestAirdrop(
+      stranger.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(strangerAirdrop, "confirmed");
+    try {
+      await castWith(stranger)[1].rpc({ commitment: "confirmed" });
+      expect.fail("a key the wallet didn't authorize should be refused");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidSession");
+    }
+
+    const [offset, tx] = castWith(sessionKey);
+    await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+
+    // The ballot is the wallet's.
+    const record = await program.account.voterRecord.fetch(voterRecordPDA);
+    expect(record.voter.toBase58()).to.equal(wallet.publicKey.toBase58());
+    expect(record.counted).to.equal(true);
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.voterCount).to.equal(1);
+
+    await program.methods
+      .closeSession()
+      .accountsPartial({ signer: wallet.publicKey, wallet: wallet.publicKey, session: sessionPDA })
+      .signers([wallet])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("holds ballots cast in the mix window until voting closes", async () => {
+    const PROPOSAL_ID = 22;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const deadline = now + 40;
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Move the meetup?",
+        ["Stay", "Move"],
+        2,
+        new anchor.BN(deadline),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [mixQueuePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mix_queue"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // The window covers all of voting, so every ballot is held.
+    await program.methods
+      .openMixWindow(new anchor.BN(600))
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    //   Voter 0: [9, 4]  (81 + 16 = 97 credits)
+    //   Voter 1: [2, 9]  (4 + 81 = 85 credits)
+    const allocations = [
+      [9, 4, 0, 0],
+      [2, 9, 0, 0],
+    ];
+    for (const allocation of allocations) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: mixQueuePDA,
+          slotHashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    // Admitted and counted toward quorum, but not yet in the tally.
+    let proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.voterCount).to.equal(2);
+    expect(proposal.mixHeld).to.equal(2);
+    const queue = await program.account.mixQueue.fetch(mixQueuePDA);
+    expect(queue.count).to.equal(2);
+    // Each held ballot is a record after the header, its VoterRecord first.
+    const queueData = (await provider.connection.getAccountInfo(mixQueuePDA)).data;
+    for (let i = 0; i < queue.count; i++) {
+      const at = MIX_QUEUE_HEADER_LEN + i * HELD_BALLOT_LEN;
+      const record = await program.account.voterRecord.fetch(new PublicKey(queueData.subarray(at, at + 32)));
+      expect(record.counted).to.equal(false);
+    }
+
+    const release = () => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .releaseHeldBallot(offset, PROPOSAL_ID)
+          .accountsPartial({
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+            ),
+            authority: owner.publicKey,
+            proposalAcc: proposalPDA,
+          }),
+      ] as const;
+    };
+    const reveal = () => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .revealResults(offset, PROPOSAL_ID)
+          .accountsPartial({
+            authority: owner.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+            ),
+          }),
+      ] as const;
+    };
+
+    try {
+      await release()[1].rpc({ commitment: "confirmed" });
+      expect.fail("held ballots can't be released while voting is open");
+    } catch (e) {
+      expect(e.toString()).to.include("VotingPeriodNotEnded");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline) break;
+      await sleep(1000);
+    }
+
+    // The reveal waits for every held ballot.
+    try {
+      await reveal()[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_results should wait for held ballots");
+    } catch (e) {
+      expect(e.toString()).to.include("HeldBallotsPending");
+    }
+
+    for (let i = 0; i < allocations.length; i++) {
+      const [offset, tx] = release();
+      await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+    proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.mixHeld).to.equal(0);
+    try {
+      await release()[1].rpc({ commitment: "confirmed" });
+      expect.fail("an empty queue has nothing to release");
+    } catch (e) {
+      expect(e.toString()).to.include("NoHeldBallots");
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const [revealOffset, revealTx] = reveal();
+    await revealTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(11);
+    expect(results.option1.toNumber()).to.equal(13);
+    expect(results.winner).to.equal(1);
+  });
+
+  it("holds any number of ballots in the mix window", async () => {
+    const PROPOSAL_ID = 79;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = (blockTime || Math.floor(Date.now() / 1000)) + 3600;
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Rename the guild?",
+        ["Keep", "Rename"],
+        2,
+        new anchor.BN(deadline),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        gateMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [mixQueuePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mix_queue"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .openMixWindow(new anchor.BN(7200))
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    // More than the 32 ballots the queue used to stop at.  Holding queues
+    // no computation, so these never reach the cluster.
+    const HELD = 40;
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    let seed: number[] = [];
+    for (let i = 0; i < HELD; i++) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt([1, 1, 0, 0].map((v) => BigInt(v)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, offset),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: mixQueuePDA,
+          slotHashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+
+      // Every held ballot moves the release seed.
+      const queue = await program.account.mixQueue.fetch(mixQueuePDA);
+      expect(queue.seed).to.not.deep.equal(seed);
+      seed = queue.seed;
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.voterCount).to.equal(HELD);
+    expect(proposal.mixHeld).to.equal(HELD);
+    const queue = await program.account.mixQueue.fetch(mixQueuePDA);
+    expect(queue.count).to.equal(HELD);
+    const info = await provider.connection.getAccountInfo(mixQueuePDA);
+    expect(info.data.length).to.equal(MIX_QUEUE_HEADER_LEN + HELD * HELD_BALLOT_LEN);
+  });
+
+  it("spends anonymous credentials against a nullifier set", async () => {
+    const PROPOSAL_ID = 23;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 600
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Council seat",
+        ["Ana", "Ben"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [nullifierSetPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nullifiers"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    // The same sk=1 registrar as the credential test above.
+    const registrar = anchor.web3.Keypair.generate();
+    const registrarKey = Array.from(
+      Buffer.from(
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2" +
+          "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed" +
+          "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b" +
+          "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        "hex"
+      )
+    );
+    const g1 = (y: number) => {
+      const point = new Array(64).fill(0);
+      point[31] = 1;
+      point[63] = y;
+      return point;
+    };
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b11 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .openCredentials(registrar.publicKey, registrarKey)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    // A small set is allocated in full when it's opened.
+    await program.methods
+      .openNullifierSet(100)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .growNullifierSet()
+        .accountsPartial({ proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a fully allocated set should not grow");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidNullifierSet");
+    }
+    const set = await program.account.nullifierSet.fetch(nullifierSetPDA);
+    expect(set.maxVoters).to.equal(100);
+    expect(set.slots).to.equal(200);
+    expect(set.count).to.equal(0);
+
+    // Credentials can no longer be presented from a wallet.
+    const voter = anchor.web3.Keypair.generate();
+    const voterAirdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(voterAirdrop, "confirmed");
+    const serial = Array.from(randomBytes(32));
+    try {
+      await program.methods
+        .presentCredential(serial, g1(2))
+        .accountsPartial({ payer: voter.publicKey, proposalAcc: proposalPDA })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("present_credential should be closed on an anonymous proposal");
+    } catch (e) {
+      expect(e.toString()).to.include("AnonymousCredentials");
+    }
+
+    // An anonymous ballot still needs the registrar's signature on its serial.
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const nonce = randomBytes(16);
+    const ciphertexts = cipher.encrypt([BigInt(6), BigInt(8), BigInt(0), BigInt(0)], nonce);
+    const voteOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .castVoteAnonymous(
+          voteOffset,
+          PROPOSAL_ID,
+          serial,
+          g1(2),
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString()),
+          null
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            voteOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          mixQueue: null,
+          complianceRoll: null,
+          sealedIdentity: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("an unsigned serial should not cast an anonymous ballot");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidCredential");
+    }
+
+    const spent = await program.methods
+      .checkNullifier(serial)
+      .accountsPartial({ nullifierSet: nullifierSetPDA })
+      .view();
+    expect(spent).to.equal(false);
+    const registry = await program.account.credentialRegistry.fetch(
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("credentials"), proposalPDA.toBuffer()],
+        program.programId
+      )[0]
+    );
+    expect(registry.anonymous).to.equal(true);
+    expect(registry.presented).to.equal(0);
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("opens a compliance roll only on an anonymous proposal", async () => {
+    const PROPOSAL_ID = 24;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 600
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Union ballot",
+        ["Strike", "Negotiate"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [complianceRollPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("compliance"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const registryPDA = PublicKey.findProgramAddressSync(
+      [Buffer.from("credentials"), proposalPDA.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b11 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .openCredentials(anchor.web3.Keypair.generate().publicKey, new Array(128).fill(0))
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    // Presented credentials vote from a public wallet; there is nothing to seal.
+    const complianceKey = Array.from(x25519.getPublicKey(randomBytes(32)));
+    try {
+      await program.methods
+        .openComplianceRoll(complianceKey)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a compliance roll should need a nullifier set");
+    } catch (e) {
+      expect(e.toString()).to.include("ComplianceNeedsAnonymity");
+    }
+
+    await program.methods
+      .openNullifierSet(100)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .openComplianceRoll(complianceKey)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const roll = await program.account.complianceRoll.fetch(complianceRollPDA);
+    expect(roll.complianceKey).to.deep.equal(complianceKey);
+    expect(roll.sealed).to.equal(0);
+    expect(roll.released).to.equal(0);
+    const registry = await program.account.credentialRegistry.fetch(registryPDA);
+    expect(registry.complianceRoll).to.equal(true);
+
+    // Nothing is released, or closed, before the proposal is decided.
+    try {
+      await program.methods
+        .closeComplianceRoll()
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a live proposal's compliance roll should not close");
+    } catch (e) {
+      expect(e.toString()).to.include("ProposalNotFinalized");
+    }
+
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("lets anyone fire an auto-reveal at a precomputed offset", async () => {
+    const PROPOSAL_ID = 25;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 5
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Renew the grants committee?",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    // An automation thread holds this instruction from before the deadline:
+    // no argument, and the computation account is known in advance.
+    const attempt = Buffer.alloc(4);
+    attempt.writeUInt32LE(0);
+    const autoRevealOffset = new anchor.BN(
+      createHash("sha256")
+        .update(Buffer.from("arcvote-auto-reveal-v1"))
+        .update(proposalPDA.toBuffer())
+        .update(attempt)
+        .digest()
+        .subarray(0, 8),
+      "le"
+    );
+    const stranger = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      stranger.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const autoReveal = () =>
+      program.methods
+        .autoReveal()
+        .accountsPartial({
+          payer: stranger.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            autoRevealOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+          ),
+          proposalAcc: proposalPDA,
+        })
+        .signers([stranger])
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await autoReveal();
+      expect.fail("auto_reveal should need the authority's opt-in");
+    } catch (e) {
+      expect(e.toString()).to.include("AutoRevealDisabled");
+    }
+
+    await program.methods
+      .enableAutoReveal()
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await autoReveal();
+      expect.fail("auto_reveal should wait for the deadline");
+    } catch (e) {
+      expect(e.toString()).to.include("VotingPeriodNotEnded");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    // Nobody voted, so the same call settles the proposal as quorum-failed.
+    const quorumFailedEventPromise = awaitEvent("quorumFailedEvent");
+    await autoReveal();
+    const quorumFailedEvent = await quorumFailedEventPromise;
+    expect(quorumFailedEvent.proposal.toBase58()).to.equal(proposalPDA.toBase58());
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.autoReveal).to.equal(true);
+    expect(proposal.quorumFailed).to.equal(true);
+    expect(proposal.autoReveals).to.equal(0);
+  });
+
+  it("reveals only whether the vote total reached a fixed threshold", async () => {
+    const PROPOSAL_ID = 26;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 600
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Fund the audit?",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const checkProgress = (threshold: number) => {
+      const checkOffset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        checkOffset,
+        program.methods
+          .checkQuorumProgress(checkOffset, PROPOSAL_ID, new anchor.BN(threshold))
+          .accountsPartial({
+            authority: owner.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              checkOffset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("reveal_quorum_progress")).readUInt32LE()
+            ),
+          }),
+      ] as const;
+    };
+
+    try {
+      await checkProgress(0)[1].rpc({ commitment: "confirmed" });
+      expect.fail("a zero threshold should be refused");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidVoteThreshold");
+    }
+
+    const progressEventPromise = awaitEvent("quorumProgressEvent");
+    const [checkOffset, check] = checkProgress(10);
+    await check.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      checkOffset,
+      program.programId,
+      "confirmed"
+    );
+    const progressEvent = await progressEventPromise;
+    expect(progressEvent.proposal.toBase58()).to.equal(proposalPDA.toBase58());
+    expect(progressEvent.voteThreshold.toNumber()).to.equal(10);
+    expect(progressEvent.reached).to.equal(false);
+
+    // Moving the threshold would bisect the total.
+    try {
+      await checkProgress(5)[1].rpc({ commitment: "confirmed" });
+      expect.fail("the threshold should be fixed by the first check");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidVoteThreshold");
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.voteThreshold.toNumber()).to.equal(10);
+    expect(proposal.voteThresholdReached).to.equal(false);
+    expect(proposal.progressCheckedAt.toNumber()).to.be.greaterThan(0);
+  });
+
+  it("drops the callback of an aborted reveal", async () => {
+    const PROPOSAL_ID = 27;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 5
+    );
+    await program.methods
+      .createPoll(
+        computationOffset,
+        PROPOSAL_ID,
+        "Rename the forum?",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(100),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [pollPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const revealPoll = (revealOffset: anchor.BN) =>
+      program.methods.revealPoll(revealOffset).accountsPartial({
+        payer: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+        proposalAcc: pollPDA,
+      });
+    const abortReveal = () =>
+      program.methods
+        .abortReveal()
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: pollPDA });
+
+    try {
+      await abortReveal().rpc({ commitment: "confirmed" });
+      expect.fail("there is no reveal to abort yet");
+    } catch (e) {
+      expect(e.toString()).to.include("NoPendingReveal");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    // Queue and abort in one transaction, so the callback surely comes later.
+    const abortedOffset = new anchor.BN(randomBytes(8), "hex");
+    const abortedEventPromise = awaitEvent("revealAbortedEvent");
+    await revealPoll(abortedOffset)
+      .postInstructions([await abortReveal().instruction()])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    const abortedEvent = await abortedEventPromise;
+    expect(abortedEvent.computation.toBase58()).to.equal(
+      getComputationAccAddress(arciumEnv.arciumClusterOffset, abortedOffset).toBase58()
+    );
+    await awaitComputationFinalization(
+      provider,
+      abortedOffset,
+      program.programId,
+      "confirmed"
+    );
+    const aborted = await program.account.proposalAccount.fetch(pollPDA);
+    expect(aborted.isFinalized).to.equal(false);
+    expect(aborted.revealPendingSince.toNumber()).to.equal(0);
+    // Queuing the reveal superseded any ballot still in flight.
+    expect(aborted.tallyGeneration).to.equal(1);
+    // The aborted reveal's callback still settled its computation.
+    expect(aborted.pendingComputations).to.equal(0);
+
+    // A fresh reveal still finalizes.
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await revealPoll(revealOffset).rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+    const revealed = await program.account.proposalAccount.fetch(pollPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.tallyGeneration).to.equal(2);
+    expect(revealed.pendingComputations).to.equal(0);
+  });
+
+  it("holds the reveal for trustee approval and lets a trustee force it", async () => {
+    const PROPOSAL_ID = 28;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const trustees = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    const stranger = anchor.web3.Keypair.generate();
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 8
+    );
+    await program.methods
+      .createPoll(
+        computationOffset,
+        PROPOSAL_ID,
+        "Adopt the new logo?",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(100),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [pollPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [trusteeSetPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("trustees"), pollPDA.toBuffer()],
+      program.programId
+    );
+    const appoint = (threshold: number) =>
+      program.methods
+        .appointTrustees(
+          trustees.map((t) => t.publicKey),
+          threshold,
+          new anchor.BN(2)
+        )
+        .accountsPartial({
+          payer: owner.publicKey,
+          authority: owner.publicKey,
+          proposalAcc: pollPDA,
+          trusteeSet: trusteeSetPDA,
+        });
+    const approve = (trustee: anchor.web3.Keypair) =>
+      program.methods
+        .approveReveal()
+        .accountsPartial({
+          trustee: trustee.publicKey,
+          proposalAcc: pollPDA,
+          trusteeSet: trusteeSetPDA,
+        })
+        .signers([trustee]);
+    const queueAccounts = (revealOffset: anchor.BN) => ({
+      computationAccount: getComputationAccAddress(
+        arciumEnv.arciumClusterOffset,
+        revealOffset
+      ),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+      ),
+      proposalAcc: pollPDA,
+    });
+
+    try {
+      await appoint(3).rpc({ commitment: "confirmed" });
+      expect.fail("a threshold above the number of trustees must be rejected");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidTrusteeSet");
+    }
+    await appoint(2).rpc({ commitment: "confirmed" });
+    const set = await program.account.trusteeSet.fetch(trusteeSetPDA);
+    expect(set.trustees.map((t) => t.toBase58())).to.deep.equal(
+      trustees.map((t) => t.publicKey.toBase58())
+    );
+    expect((await program.account.proposalAccount.fetch(pollPDA)).trusteeThreshold).to.equal(2);
+
+    try {
+      await approve(trustees[0]).rpc({ commitment: "confirmed" });
+      expect.fail("approvals open when voting closes");
+    } catch (e) {
+      expect(e.toString()).to.include("VotingPeriodNotEnded");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber() + 2) break;
+      await sleep(1000);
+    }
+
+    try {
+      const revealOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .revealPoll(revealOffset)
+        .accountsPartial({ payer: owner.publicKey, ...queueAccounts(revealOffset) })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("the trustees have not approved yet");
+    } catch (e) {
+      expect(e.toString()).to.include("TrusteeApprovalPending");
+    }
+
+    try {
+      await approve(stranger).rpc({ commitment: "confirmed" });
+      expect.fail("only trustees approve");
+    } catch (e) {
+      expect(e.toString()).to.include("NotATrustee");
+    }
+
+    const approvedEventPromise = awaitEvent("revealApprovedEvent");
+    await approve(trustees[0]).rpc({ commitment: "confirmed" });
+    const approvedEvent = await approvedEventPromise;
+    expect(approvedEvent.approvals).to.equal(1);
+    expect(approvedEvent.threshold).to.equal(2);
+
+    try {
+      await approve(trustees[0]).rpc({ commitment: "confirmed" });
+      expect.fail("a trustee approves once");
+    } catch (e) {
+      expect(e.toString()).to.include("TrusteeAlreadyApproved");
+    }
+    await approve(trustees[1]).rpc({ commitment: "confirmed" });
+
+    const forceOffset = new anchor.BN(randomBytes(8), "hex");
+    const forcedEventPromise = awaitEvent("revealForcedEvent");
+    await program.methods
+      .forceReveal(forceOffset)
+      .accountsPartial({
+        payer: owner.publicKey,
+        trustee: trustees[1].publicKey,
+        ...queueAccounts(forceOffset),
+        trusteeSet: trusteeSetPDA,
+      })
+      .signers([trustees[1]])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    const forcedEvent = await forcedEventPromise;
+    expect(forcedEvent.trustee.toBase58()).to.equal(trustees[1].publicKey.toBase58());
+    await awaitComputationFinalization(
+      provider,
+      forceOffset,
+      program.programId,
+      "confirmed"
+    );
+    const revealed = await program.account.proposalAccount.fetch(pollPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.trusteeApprovals).to.equal(2);
+  });
+
+  it("buckets quadratic spend per option and reveals it only with noise", async () => {
+    const PROPOSAL_ID = 29;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 75
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Grant focus",
+        ["Tooling", "Research"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [histogramPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("spend_histogram"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    const openOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .openSpendHistogram(
+        openOffset,
+        PROPOSAL_ID,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          openOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_spend_histogram")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(provider, openOffset, program.programId, "confirmed");
+    expect((await program.account.spendHistogram.fetch(histogramPDA)).ready).to.equal(true);
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).spendHistogram).to.equal(true);
+
+    // Held ballots would skip the histogram.
+    try {
+      await program.methods
+        .openMixWindow(new anchor.BN(600))
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a mix window on a histogram proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("HistogramUnsupported");
+    }
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const cast = (method: "castVote" | "castVoteHistogram", voter: anchor.web3.Keypair, allocation: number[]) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      const circuit = method === "castVote" ? "cast_vote" : "cast_vote_histogram";
+      const accounts = {
+        payer: voter.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          offset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+        ),
+        authority: owner.publicKey,
+        proposalAcc: proposalPDA,
+        voterTokenAccount: null,
+        ...(method === "castVote" ? { credential: null, mixQueue: null } : {}),
+      };
+      return [
+        offset,
+        program.methods[method](
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+          .accountsPartial(accounts)
+          .signers([voter]),
+      ] as const;
+    };
+
+    //   Voter 0: [9, 4]  (81 + 16 = 97 credits)
+    //   Voter 1: [2, 9]  (4 + 81 = 85 credits)
+    //   Voter 2: [10, 0] (100 credits)
+    //   Voter 3: [3, 0] and 9 for a third option the proposal doesn't
+    //            have, which is dropped before counting and bucketing.
+    const allocations = [
+      [9, 4, 0, 0],
+      [2, 9, 0, 0],
+      [10, 0, 0, 0],
+      [3, 0, 9, 0],
+    ];
+    for (const [i, allocation] of allocations.entries()) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      if (i === 0) {
+        // The plain quadratic path would leave the histogram behind.
+        try {
+          await cast("castVote", voter, allocation)[1].rpc({ commitment: "confirmed" });
+          expect.fail("cast_vote on a histogram proposal should fail");
+        } catch (e) {
+          expect(e.toString()).to.include("HistogramBallotRequired");
+        }
+      }
+
+      const [offset, tx] = cast("castVoteHistogram", voter, allocation);
+      await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).countedVoters).to.equal(4);
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+    const results = (await program.account.proposalAccount.fetch(proposalPDA)).results;
+    expect(results[0].toNumber()).to.equal(24);
+    expect(results[1].toNumber()).to.equal(13);
+    expect(results[2].toNumber()).to.equal(0);
+
+    const revealHistogram = () => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .revealSpendHistogram(offset, PROPOSAL_ID)
+          .accountsPartial({
+            authority: owner.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("reveal_spend_histogram")).readUInt32LE()
+            ),
+          }),
+      ] as const;
+    };
+    const histogramPromise = awaitEvent("spendHistogramRevealedEvent");
+    const [histogramOffset, histogramTx] = revealHistogram();
+    await histogramTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, histogramOffset, program.programId, "confirmed");
+
+    // Exact buckets: option 0 has two 1-3 and two 8-10 ballots, option 1
+    // one 4-7 and one 8-10, option 2 none; the noise moves each by at
+    // most 32.
+    const exact = [2, 0, 2, 0, 1, 1, 0, 0, 0, 0, 0, 0];
+    const event = await histogramPromise;
+    const histogram = await program.account.spendHistogram.fetch(histogramPDA);
+    expect(histogram.revealed).to.equal(true);
+    for (const [i, count] of exact.entries()) {
+      const noised = histogram.counts[i].toNumber();
+      expect(event.counts[i].toNumber()).to.equal(noised);
+      expect(noised).to.be.at.least(Math.max(0, count - 32));
+      expect(noised).to.be.at.most(count + 32);
+    }
+
+    try {
+      await revealHistogram()[1].rpc({ commitment: "confirmed" });
+      expect.fail("a second histogram reveal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("HistogramAlreadyRevealed");
+    }
+  });
+
+  it("opens an amendment only once its parent picks the required option", async () => {
+    const PARENT_ID = 30;
+    const PROPOSAL_ID = 31;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const parentDeadline = new anchor.BN(now + 5);
+    const parentOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createPoll(
+        parentOffset,
+        PARENT_ID,
+        "Adopt the new charter?",
+        ["Yes", "No"],
+        2,
+        parentDeadline,
+        new anchor.BN(100),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          parentOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      parentOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Amend the charter's quorum rule?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const proposalPda = (id: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          owner.publicKey.toBuffer(),
+          Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+        ],
+        program.programId
+      )[0];
+    const parentPDA = proposalPda(PARENT_ID);
+    const proposalPDA = proposalPda(PROPOSAL_ID);
+    const requireParent = (winner: number) =>
+      program.methods
+        .requireParentOutcome(parentPDA, winner)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+    const confirmParent = () =>
+      program.methods
+        .confirmParentOutcome()
+        .accountsPartial({ proposalAcc: proposalPDA, parentAcc: parentPDA })
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await program.methods
+        .requireParentOutcome(proposalPDA, 0)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a proposal can't amend itself");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidParentOutcome");
+    }
+    await requireParent(1);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const cast = () => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt([BigInt(6), BigInt(8), BigInt(0), BigInt(0)], nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .castVote(
+            offset,
+            PROPOSAL_ID,
+            Array.from(ciphertexts[0]),
+            Array.from(ciphertexts[1]),
+            Array.from(ciphertexts[2]),
+            Array.from(ciphertexts[3]),
+            Array.from(publicKey),
+            new anchor.BN(deserializeLE(nonce).toString())
+          )
+          .accountsPartial({
+            payer: voter.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+            ),
+            authority: owner.publicKey,
+            proposalAcc: proposalPDA,
+            voterTokenAccount: null,
+            credential: null,
+            mixQueue: null,
+          })
+          .signers([voter]),
+      ] as const;
+    };
+
+    try {
+      await cast()[1].rpc({ commitment: "confirmed" });
+      expect.fail("the amendment should wait on its parent");
+    } catch (e) {
+      expect(e.toString()).to.include("ParentOutcomeNotMet");
+    }
+    try {
+      await confirmParent();
+      expect.fail("the parent has not been revealed yet");
+    } catch (e) {
+      expect(e.toString()).to.include("ParentOutcomeNotMet");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= parentDeadline.toNumber()) break;
+      await sleep(1000);
+    }
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealPoll(revealOffset)
+      .accountsPartial({
+        payer: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+        proposalAcc: parentPDA,
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // Nobody voted on the parent, so it picks option 0, not the required 1.
+    const parent = await program.account.proposalAccount.fetch(parentPDA);
+    expect(parent.isFinalized).to.equal(true);
+    expect(parent.winner).to.equal(0);
+    try {
+      await confirmParent();
+      expect.fail("the parent picked another option");
+    } catch (e) {
+      expect(e.toString()).to.include("ParentOutcomeNotMet");
+    }
+
+    await requireParent(0);
+    const metEventPromise = awaitEvent("parentOutcomeMetEvent");
+    await confirmParent();
+    const metEvent = await metEventPromise;
+    expect(metEvent.proposal.toBase58()).to.equal(proposalPDA.toBase58());
+    expect(metEvent.parent.toBase58()).to.equal(parentPDA.toBase58());
+    expect(metEvent.winner).to.equal(0);
+
+    const [castOffset, castVote] = cast();
+    await castVote.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, castOffset, program.programId, "confirmed");
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.requiresParentOutcome.parent.toBase58()).to.equal(parentPDA.toBase58());
+    expect(proposal.parentOutcomeMet).to.equal(true);
+    expect(proposal.voterCount).to.equal(1);
+  });
+
+  it("opens one branch of a proposal chain once its parent is decided", async () => {
+    const PARENT_ID = 32;
+    const IF_PASSES_ID = 33;
+    const IF_FAILS_ID = 34;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const parentDeadline = new anchor.BN(now + 5);
+    const createProposal = async (
+      id: number,
+      title: string,
+      options: string[],
+      deadline: anchor.BN,
+      ballot: object
+    ) => {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          id,
+          title,
+          options,
+          options.length,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          ballot,
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+      return PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          owner.publicKey.toBuffer(),
+          Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+        ],
+        program.programId
+      )[0];
+    };
+    const parentPDA = await createProposal(
+      PARENT_ID,
+      "Adopt the 2027 budget?",
+      ["For", "Against", "Abstain"],
+      parentDeadline,
+      { yesNoAbstain: { vetoBps: 0 } }
+    );
+    const ifPassesPDA = await createProposal(
+      IF_PASSES_ID,
+      "Which pilot does the budget fund?",
+      ["Grants", "Audits"],
+      new anchor.BN(now + 600),
+      { quadratic: {} }
+    );
+    const ifFailsPDA = await createProposal(
+      IF_FAILS_ID,
+      "What gets cut without a budget?",
+      ["Events", "Swag"],
+      new anchor.BN(now + 600),
+      { quadratic: {} }
+    );
+
+    const dependencyPda = (proposal: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("dependency"), proposal.toBuffer()],
+        program.programId
+      )[0];
+    const declareDependency = (proposal: PublicKey, parent: PublicKey, condition: object) =>
+      program.methods
+        .declareDependency(condition)
+        .accountsPartial({
+          payer: owner.publicKey,
+          authority: owner.publicKey,
+          proposalAcc: proposal,
+          parentAcc: parent,
+          dependency: dependencyPda(proposal),
+        })
+        .rpc({ commitment: "confirmed" });
+    const resolveDependency = (proposal: PublicKey) =>
+      program.methods
+        .resolveDependency()
+        .accountsPartial({
+          proposalAcc: proposal,
+          parentAcc: parentPDA,
+          dependency: dependencyPda(proposal),
+        })
+        .rpc({ commitment: "confirmed" });
+
+    // Only a For/Against/Abstain decision can pass or fail.
+    try {
+      await declareDependency(ifFailsPDA, ifPassesPDA, { fails: {} });
+      expect.fail("a quadratic parent neither passes nor fails");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidDependency");
+    }
+    await declareDependency(ifPassesPDA, parentPDA, { passes: {} });
+    await declareDependency(ifFailsPDA, parentPDA, { fails: {} });
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const nonce = randomBytes(16);
+    const ciphertexts = cipher.encrypt([BigInt(6), BigInt(8), BigInt(0), BigInt(0)], nonce);
+    const castOffset = new anchor.BN(randomBytes(8), "hex");
+    try {
+      await program.methods
+        .castVote(
+          castOffset,
+          IF_PASSES_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            castOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: ifPassesPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a chained proposal should wait on its parent");
+    } catch (e) {
+      expect(e.toString()).to.include("DependencyNotMet");
+    }
+    try {
+      await resolveDependency(ifFailsPDA);
+      expect.fail("the parent is still open");
+    } catch (e) {
+      expect(e.toString()).to.include("DependencyNotMet");
+    }
+
+    // Nobody votes on the budget, so it fails quorum.
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= parentDeadline.toNumber()) break;
+      await sleep(1000);
+    }
+    await program.methods
+      .markQuorumFailed()
+      .accountsPartial({ payer: owner.publicKey, proposalAcc: parentPDA })
+      .rpc({ commitment: "confirmed" });
+
+    try {
+      await resolveDependency(ifPassesPDA);
+      expect.fail("the parent did not pass");
+    } catch (e) {
+      expect(e.toString()).to.include("DependencyNotMet");
+    }
+    const resolvedEventPromise = awaitEvent("dependencyResolvedEvent");
+    await resolveDependency(ifFailsPDA);
+    const resolvedEvent = await resolvedEventPromise;
+    expect(resolvedEvent.proposal.toBase58()).to.equal(ifFailsPDA.toBase58());
+    expect(resolvedEvent.parent.toBase58()).to.equal(parentPDA.toBase58());
+    expect(resolvedEvent.condition).to.deep.equal({ fails: {} });
+
+    const ifPasses = await program.account.proposalAccount.fetch(ifPassesPDA);
+    expect(ifPasses.awaitingDependency).to.equal(true);
+    const ifFails = await program.account.proposalAccount.fetch(ifFailsPDA);
+    expect(ifFails.awaitingDependency).to.equal(false);
+    const dependency = await program.account.dependency.fetch(dependencyPda(ifFailsPDA));
+    expect(dependency.parent.toBase58()).to.equal(parentPDA.toBase58());
+    expect(dependency.resolved).to.equal(true);
+  });
+
+  it("files proposals under admin-curated categories with their own rules", async () => {
+    const PROPOSAL_ID = 35;
+    const SHORT_PROPOSAL_ID = 36;
+    const TREASURY = 1;
+    const RETIRED = 2;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const stranger = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      stranger.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+    const categoryPda = (category: number) => {
+      const id = Buffer.alloc(2);
+      id.writeUInt16LE(category);
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("category"), id],
+        program.programId
+      )[0];
+    };
+    const setCategory = (category: number, name: string, active: boolean, admin = owner) =>
+      program.methods
+        .setCategory(category, {
+          name,
+          defaultQuorum: 3,
+          minDuration: new anchor.BN(60),
+          maxDuration: new anchor.BN(3600),
+          active,
+        })
+        .accountsPartial({
+          payer: admin.publicKey,
+          admin: admin.publicKey,
+          config: configPDA,
+          categoryAcc: categoryPda(category),
+        })
+        .signers(admin === owner ? [] : [admin])
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await setCategory(TREASURY, "Treasury", true, stranger);
+      expect.fail("only the admin curates categories");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidAuthority");
+    }
+    await setCategory(TREASURY, "Treasury", true);
+    await setCategory(RETIRED, "Retired", false);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const createProposal = async (id: number, deadline: anchor.BN) => {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          id,
+          "Fund the audit?",
+          ["Yes", "No"],
+          2,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          0,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+      return PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          owner.publicKey.toBuffer(),
+          Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+        ],
+        program.programId
+      )[0];
+    };
+    const proposalPDA = await createProposal(PROPOSAL_ID, new anchor.BN(now + 600));
+    const shortPDA = await createProposal(SHORT_PROPOSAL_ID, new anchor.BN(now + 30));
+    const assignCategory = (proposal: PublicKey, category: number) =>
+      program.methods
+        .assignCategory(category)
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposal,
+          categoryAcc: categoryPda(category),
+        })
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await assignCategory(proposalPDA, RETIRED);
+      expect.fail("a retired category can't be assigned");
+    } catch (e) {
+      expect(e.toString()).to.include("CategoryInactive");
+    }
+    try {
+      await assignCategory(shortPDA, TREASURY);
+      expect.fail("treasury votes run at least a minute");
+    } catch (e) {
+      expect(e.toString()).to.include("DurationOutsideCategory");
+    }
+
+    const assignedEventPromise = awaitEvent("categoryAssignedEvent");
+    await assignCategory(proposalPDA, TREASURY);
+    const assignedEvent = await assignedEventPromise;
+    expect(assignedEvent.category).to.equal(TREASURY);
+    expect(assignedEvent.quorum).to.equal(3);
+    try {
+      await assignCategory(proposalPDA, TREASURY);
+      expect.fail("a proposal is filed once");
+    } catch (e) {
+      expect(e.toString()).to.include("CategoryAlreadySet");
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.category).to.equal(TREASURY);
+    expect(proposal.quorum).to.equal(3);
+    const category = await program.account.category.fetch(categoryPda(TREASURY));
+    expect(category.name).to.equal("Treasury");
+    expect(category.active).to.equal(true);
+  });
+
+  it("shares deadline and reveal rights with co-authors", async () => {
+    const PROPOSAL_ID = 37;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const coAuthor = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Elect the steward?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const setCoAuthors = (coAuthors: PublicKey[]) =>
+      program.methods
+        .setCoAuthors(coAuthors)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+    const extendDeadline = (signer: anchor.web3.Keypair, deadline: number) =>
+      program.methods
+        .extendDeadline(new anchor.BN(deadline))
+        .accountsPartial({
+          signer: signer.publicKey,
+          proposalAcc: proposalPDA,
+          categoryAcc: null,
+        })
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await setCoAuthors([owner.publicKey]);
+      expect.fail("the authority can't be its own co-author");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidCoAuthors");
+    }
+    const setEventPromise = awaitEvent("coAuthorsSetEvent");
+    await setCoAuthors([coAuthor.publicKey]);
+    const setEvent = await setEventPromise;
+    expect(setEvent.coAuthors.map((k: PublicKey) => k.toBase58())).to.deep.equal([
+      coAuthor.publicKey.toBase58(),
+    ]);
+    try {
+      await setCoAuthors([stranger.publicKey]);
+      expect.fail("co-authors are fixed once set");
+    } catch (e) {
+      expect(e.toString()).to.include("CoAuthorsAlreadySet");
+    }
+
+    try {
+      await extendDeadline(stranger, now + 1200);
+      expect.fail("only the authority and co-authors extend the deadline");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidAuthority");
+    }
+    try {
+      await extendDeadline(coAuthor, now + 300);
+      expect.fail("a deadline only moves later");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidDeadlineExtension");
+    }
+    const extendedEventPromise = awaitEvent("deadlineExtendedEvent");
+    await extendDeadline(coAuthor, now + 1200);
+    const extendedEvent = await extendedEventPromise;
+    expect(extendedEvent.signer.toBase58()).to.equal(coAuthor.publicKey.toBase58());
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.deadline.toNumber()).to.equal(now + 1200);
+    expect(proposal.coAuthors.length).to.equal(1);
+  });
+
+  it("prices quadratic ballots from square-root-of-balance credits", async () => {
+    const PROPOSAL_ID = 38;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Fund the grants round?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    // Any existing mint will do; reuse the first proposal's receipt mint.
+    const [firstProposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([1]).buffer)),
+      ],
+      program.programId
+    );
+    const [creditMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), firstProposalPDA.toBuffer()],
+      program.programId
+    );
+    const openSqrtCredits = () =>
+      program.methods
+        .openSqrtCredits()
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          creditMint,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    const openedEventPromise = awaitEvent("sqrtCreditsOpenedEvent");
+    await openSqrtCredits();
+    const openedEvent = await openedEventPromise;
+    expect(openedEvent.mint.toBase58()).to.equal(creditMint.toBase58());
+    try {
+      await openSqrtCredits();
+      expect.fail("credits open only once");
+    } catch (e) {
+      expect(e.toString()).to.include("SqrtCreditsAlreadyOpen");
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.creditMint.toBase58()).to.equal(creditMint.toBase58());
+  });
+
+  it("escrows square-root-credit tokens so a balance registers only once", async () => {
+    const PROPOSAL_ID = 80;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const [holder, accomplice] = [0, 1].map(() => anchor.web3.Keypair.generate());
+    for (const voter of [holder, accomplice]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+    }
+
+    // The holder owns 100 tokens, 10 credits; the accomplice none.
+    const mint = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        ...(await createMintInstructions(
+          provider.connection,
+          owner.publicKey,
+          mint.publicKey,
+          owner.publicKey,
+          0
+        )),
+        createAssociatedTokenAccountIdempotent(owner.publicKey, holder.publicKey, mint.publicKey),
+        createAssociatedTokenAccountIdempotent(owner.publicKey, accomplice.publicKey, mint.publicKey),
+        mintToInstruction(mint.publicKey, holder.publicKey, owner.publicKey, 100)
+      ),
+      [mint],
+      { commitment: "confirmed" }
+    );
+    const holderTokens = associatedTokenAddress(holder.publicKey, mint.publicKey);
+    const accompliceTokens = associatedTokenAddress(accomplice.publicKey, mint.publicKey);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Fund the grants round, one registration per token?",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    await program.methods
+      .openSqrtCredits()
+      .accountsPartial({
+        authority: owner.publicKey,
+        proposalAcc: proposalPDA,
+        creditMint: mint.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const voterCreditsAddress = (voter: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("credits"), proposalPDA.toBuffer(), voter.toBuffer()],
+        program.programId
+      )[0];
+    const creditVaultAddress = (voter: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("credit_vault"), voterCreditsAddress(voter).toBuffer()],
+        program.programId
+      )[0];
+    const registerCredits = (voter: anchor.web3.Keypair, voterTokenAccount: PublicKey) =>
+      program.methods
+        .registerCredits()
+        .accountsPartial({
+          voter: voter.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount,
+          creditMint: mint.publicKey,
+          voterCredits: voterCreditsAddress(voter.publicKey),
+          creditVault: creditVaultAddress(voter.publicKey),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+    const releaseCredits = (voter: anchor.web3.Keypair, voterTokenAccount: PublicKey) =>
+      program.methods
+        .releaseCredits()
+        .accountsPartial({
+          voter: voter.publicKey,
+          voterCredits: voterCreditsAddress(voter.publicKey),
+          creditMint: mint.publicKey,
+          creditVault: creditVaultAddress(voter.publicKey),
+          voterTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+    const tokenBalance = async (account: PublicKey) =>
+      (await provider.connection.getTokenAccountBalance(account, "confirmed")).value.amount;
+
+    const registeredPromise = awaitEvent("creditsRegisteredEvent");
+    await registerCredits(holder, holderTokens);
+    const registered = await registeredPromise;
+    expect(registered.balance.toNumber()).to.equal(100);
+    expect(registered.credits.toNumber()).to.equal(10);
+    const record = await program.account.voterCredits.fetch(voterCreditsAddress(holder.publicKey));
+    expect(record.credits.toNumber()).to.equal(10);
+    expect(record.unlocksAt.toNumber()).to.equal(deadline.toNumber());
+    expect(await tokenBalance(creditVaultAddress(holder.publicKey))).to.equal("100");
+    expect(await tokenBalance(holderTokens)).to.equal("0");
+
+    // Token-2022 `TransferChecked` of the registered tokens to the
+    // accomplice: they now sit in the vault, so there is nothing to send.
+    const transfer = Buffer.alloc(10);
+    transfer.writeUInt8(12, 0);
+    transfer.writeBigUInt64LE(BigInt(100), 1);
+    transfer.writeUInt8(0, 9);
+    try {
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          new anchor.web3.TransactionInstruction({
+            programId: TOKEN_2022_PROGRAM_ID,
+            keys: [
+              { pubkey: holderTokens, isSigner: false, isWritable: true },
+              { pubkey: mint.publicKey, isSigner: false, isWritable: false },
+              { pubkey: accompliceTokens, isSigner: false, isWritable: true },
+              { pubkey: holder.publicKey, isSigner: true, isWritable: false },
+            ],
+            data: transfer,
+          })
+        ),
+        [holder],
+        { commitment: "confirmed" }
+      );
+      expect.fail("registered tokens should not leave the vault");
+    } catch (e) {
+      // TokenError::InsufficientFunds
+      expect(e.toString()).to.include("custom program error: 0x1");
+    }
+    expect(await tokenBalance(accompliceTokens)).to.equal("0");
+
+    // So the second wallet has no balance to register, and the first
+    // can't register again.
+    try {
+      await registerCredits(accomplice, accompliceTokens);
+      expect.fail("the same tokens should not register twice");
+    } catch (e) {
+      expect(e.toString()).to.include("NoVoiceCredits");
+    }
+    try {
+      await registerCredits(holder, holderTokens);
+      expect.fail("a voter registers once");
+    } catch (e) {
+      expect(e.toString()).to.include("already in use");
+    }
+
+    try {
+      await releaseCredits(holder, holderTokens);
+      expect.fail("escrowed tokens stay locked until the deadline");
+    } catch (e) {
+      expect(e.toString()).to.include("CreditsLocked");
+    }
+    try {
+      await program.methods
+        .extendDeadline(new anchor.BN(deadline.toNumber() + 600))
+        .accountsPartial({
+          signer: owner.publicKey,
+          proposalAcc: proposalPDA,
+          categoryAcc: null,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("the deadline the tokens are escrowed until should not move");
+    } catch (e) {
+      expect(e.toString()).to.include("DeadlineSetByCredits");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+    const releasedPromise = awaitEvent("creditsReleasedEvent");
+    await releaseCredits(holder, holderTokens);
+    const released = await releasedPromise;
+    expect(released.balance.toNumber()).to.equal(100);
+    expect(await tokenBalance(holderTokens)).to.equal("100");
+    expect(
+      await provider.connection.getAccountInfo(voterCreditsAddress(holder.publicKey), "confirmed")
+    ).to.equal(null);
+  });
+
+  it("admits wallets to a history-gated proposal", async () => {
+    const PROPOSAL_ID = 39;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const voter = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      stranger.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Admit long-standing voters only?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    // GatingMode::Open | GatingMode::History
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b101 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .openHistoryGate(null, new anchor.BN(86400), 0)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("wallet age needs an attestor");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidHistoryGate");
+    }
+    await program.methods
+      .openHistoryGate(owner.publicKey, new anchor.BN(86400), 2)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const attest = (
+      attestor: anchor.web3.Keypair,
+      firstSeen: number,
+      priorBallots: number
+    ) =>
+      program.methods
+        .attestHistory(voter.publicKey, new anchor.BN(firstSeen), priorBallots)
+        .accountsPartial({ attestor: attestor.publicKey, proposalAcc: proposalPDA })
+        .signers([attestor])
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await attest(stranger, now - 2 * 86400, 5);
+      expect.fail("only the attestor admits wallets");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidAttestor");
+    }
+    try {
+      await attest(owner, now - 3600, 5);
+      expect.fail("the wallet is a day short");
+    } catch (e) {
+      expect(e.toString()).to.include("WalletTooYoung");
+    }
+    try {
+      await attest(owner, now - 2 * 86400, 1);
+      expect.fail("the wallet voted once");
+    } catch (e) {
+      expect(e.toString()).to.include("TooFewPriorBallots");
+    }
+    try {
+      await program.methods
+        .proveHistory()
+        .accountsPartial({ voter: stranger.publicKey, proposalAcc: proposalPDA })
+        .signers([stranger])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("records can't show wallet age");
+    } catch (e) {
+      expect(e.toString()).to.include("WalletAgeUnproven");
+    }
+
+    const admittedEventPromise = awaitEvent("historyAdmittedEvent");
+    await attest(owner, now - 2 * 86400, 5);
+    const admittedEvent = await admittedEventPromise;
+    expect(admittedEvent.voter.toBase58()).to.equal(voter.publicKey.toBase58());
+    expect(admittedEvent.attested).to.equal(true);
+
+    const [credentialPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("credential"), proposalPDA.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    const credential = await program.account.ballotCredential.fetch(credentialPDA);
+    expect(credential.holder.toBase58()).to.equal(voter.publicKey.toBase58());
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.gating).to.deep.equal({ history: {} });
+  });
+
+  it("burns a membership token per ballot once burn-to-vote is open", async () => {
+    const PROPOSAL_ID = 40;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Renew the membership charter?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    // The first proposal's receipt mint stands in for a membership mint.
+    const [firstProposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([1]).buffer)),
+      ],
+      program.programId
+    );
+    const [burnMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), firstProposalPDA.toBuffer()],
+      program.programId
+    );
+    const openBurnToVote = () =>
+      program.methods
+        .openBurnToVote()
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          burnMint,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    const openedEventPromise = awaitEvent("burnToVoteOpenedEvent");
+    await openBurnToVote();
+    const openedEvent = await openedEventPromise;
+    expect(openedEvent.mint.toBase58()).to.equal(burnMint.toBase58());
+    try {
+      await openBurnToVote();
+      expect.fail("burn-to-vote opens only once");
+    } catch (e) {
+      expect(e.toString()).to.include("BurnToVoteAlreadyOpen");
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.burnMint.toBase58()).to.equal(burnMint.toBase58());
+  });
+
+  it("hands ballot eligibility to an external oracle program", async () => {
+    const PROPOSAL_ID = 41;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Admit the new working group?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    const setOracle = (oracle: PublicKey) =>
+      program.methods
+        .setEligibilityOracle()
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          oracle,
+        })
+        .rpc({ commitment: "confirmed" });
+    const setWeightCurve = (curve: object) =>
+      program.methods
+        .setWeightCurve(curve as any)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await setWeightCurve({ sqrt: {} });
+      expect.fail("a weight curve bends the oracle's weights");
+    } catch (e) {
+      expect(e.toString()).to.include("WeightCurveUnsupported");
+    }
+    try {
+      await setOracle(program.programId);
+      expect.fail("ArcVote can't vouch for its own voters");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidEligibilityOracle");
+    }
+
+    // Any executable program is accepted; the system program stands in.
+    const oracle = anchor.web3.SystemProgram.programId;
+    const setEventPromise = awaitEvent("eligibilityOracleSetEvent");
+    await setOracle(oracle);
+    const setEvent = await setEventPromise;
+    expect(setEvent.oracle.toBase58()).to.equal(oracle.toBase58());
+    try {
+      await setOracle(oracle);
+      expect.fail("the oracle is set only once");
+    } catch (e) {
+      expect(e.toString()).to.include("EligibilityOracleAlreadySet");
+    }
+
+    try {
+      await setWeightCurve({ cap: { max: new anchor.BN(0) } });
+      expect.fail("a cap of 0 would count no ballot");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidWeightCurve");
+    }
+    const curveEventPromise = awaitEvent("weightCurveSetEvent");
+    await setWeightCurve({ sqrt: {} });
+    const curveEvent = await curveEventPromise;
+    expect(curveEvent.curve).to.have.property("sqrt");
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.eligibilityOracle.toBase58()).to.equal(oracle.toBase58());
+    expect(proposal.weightCurve).to.have.property("sqrt");
+  });
+
+  it("keeps an authority-managed allowlist of voters", async () => {
+    const PROPOSAL_ID = 42;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Ratify the committee charter?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    // GatingMode::Open | GatingMode::Allowlist
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b1001 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    const openAllowlist = (capacity: number) =>
+      program.methods
+        .openAllowlist(capacity)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+    try {
+      await openAllowlist(0);
+      expect.fail("an allowlist needs room for a voter");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidAllowlistCapacity");
+    }
+    const openedEventPromise = awaitEvent("allowlistOpenedEvent");
+    await openAllowlist(2);
+    expect((await openedEventPromise).capacity).to.equal(2);
+
+    const [alice, bob, carol] = [0, 1, 2].map(() => anchor.web3.Keypair.generate().publicKey);
+    const addVoter = (voter: PublicKey) =>
+      program.methods
+        .addVoter(voter)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+    const removeVoter = (voter: PublicKey) =>
+      program.methods
+        .removeVoter(voter)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+
+    await addVoter(alice);
+    const addedEventPromise = awaitEvent("allowlistUpdatedEvent");
+    await addVoter(bob);
+    const addedEvent = await addedEventPromise;
+    expect(addedEvent.voter.toBase58()).to.equal(bob.toBase58());
+    expect(addedEvent.listed).to.equal(true);
+    expect(addedEvent.count).to.equal(2);
+    try {
+      await addVoter(alice);
+      expect.fail("a voter is listed once");
+    } catch (e) {
+      expect(e.toString()).to.include("VoterAlreadyListed");
+    }
+    try {
+      await addVoter(carol);
+      expect.fail("the allowlist holds two voters");
+    } catch (e) {
+      expect(e.toString()).to.include("AllowlistFull");
+    }
+
+    await removeVoter(bob);
+    try {
+      await removeVoter(bob);
+      expect.fail("bob is no longer listed");
+    } catch (e) {
+      expect(e.toString()).to.include("NotOnAllowlist");
+    }
+    await addVoter(carol);
+
+    const [allowlistPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("allowlist"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const allowlist = await program.account.allowlist.fetch(allowlistPDA);
+    expect(allowlist.voters.map((voter) => voter.toBase58())).to.deep.equal([
+      alice.toBase58(),
+      carol.toBase58(),
+    ]);
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.gating).to.deep.equal({ allowlist: {} });
+  });
+
+  it("refuses hidden characters and duplicate option labels", async () => {
+    const PROPOSAL_ID = 43;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const createProposal = (title: string, options: string[]) => {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      return program.methods
+        .createProposal(
+          computationOffset,
+          PROPOSAL_ID,
+          title,
+          options,
+          options.length,
+          new anchor.BN(now + 600),
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" })
+        .then(() => computationOffset);
+    };
+
+    const refusals: [string, string[], string][] = [
+      ["Fund\u200bthe grant?", ["Yes", "No"], "InvalidTitle"],
+      ["   ", ["Yes", "No"], "InvalidTitle"],
+      ["Fund the grant?", ["Yes", "Yes"], "DuplicateOption"],
+      ["Fund the grant?", ["Yes", " Yes "], "DuplicateOption"],
+      ["Fund the grant?", ["Yes", "No\u202e"], "InvalidOptionLabel"],
+      ["Fund the grant?", ["Yes", "No\n"], "InvalidOptionLabel"],
+    ];
+    for (const [title, options, error] of refusals) {
+      try {
+        await createProposal(title, options);
+        expect.fail(`${JSON.stringify([title, options])} is refused`);
+      } catch (e) {
+        expect(e.toString()).to.include(error);
+      }
+    }
+
+    const computationOffset = await createProposal("  Fund the grant?  ", [" Yes", "No "]);
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.title).to.equal("Fund the grant?");
+    expect(proposal.options).to.deep.equal(["Yes", "No"]);
+  });
+
+  it("groups an election's proposals into a ballot", async () => {
+    const BALLOT_ID = 1;
+    const PROPOSAL_IDS = [44, 45];
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const deadline = new anchor.BN(now + 600);
+    const proposalPDAs = PROPOSAL_IDS.map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("proposal"),
+            owner.publicKey.toBuffer(),
+            Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+          ],
+          program.programId
+        )[0]
+    );
+    const [ballotPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("ballot"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([BALLOT_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const createProposal = (id: number, title: string, computationOffset: anchor.BN) =>
+      program.methods
+        .createProposal(
+          computationOffset,
+          id,
+          title,
+          ["Ana", "Ben"],
+          2,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        });
+    const createBallot = (proposals: PublicKey[]) =>
+      program.methods
+        .createBallot(BALLOT_ID, "Board election")
+        .accountsPartial({ authority: owner.publicKey, ballotAcc: ballotPDA })
+        .remainingAccounts(
+          proposals.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        );
+
+    const chairOffset = new anchor.BN(randomBytes(8), "hex");
+    await createProposal(PROPOSAL_IDS[0], "Chair", chairOffset).rpc({ commitment: "confirmed" });
+    try {
+      await createBallot([proposalPDAs[0]]).rpc({ commitment: "confirmed" });
+      expect.fail("a ballot groups at least two proposals");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidBallot");
+    }
+
+    // The second race and the ballot land in one transaction.
+    const treasurerOffset = new anchor.BN(randomBytes(8), "hex");
+    const createdEventPromise = awaitEvent("ballotCreatedEvent");
+    await createProposal(PROPOSAL_IDS[1], "Treasurer", treasurerOffset)
+      .postInstructions([await createBallot(proposalPDAs).instruction()])
+      .rpc({ commitment: "confirmed" });
+    const createdEvent = await createdEventPromise;
+    expect(createdEvent.proposals.map((p) => p.toBase58())).to.deep.equal(
+      proposalPDAs.map((p) => p.toBase58())
+    );
+    for (const offset of [chairOffset, treasurerOffset]) {
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    const ballot = await program.account.ballot.fetch(ballotPDA);
+    expect(ballot.title).to.equal("Board election");
+    expect(ballot.deadline.toNumber()).to.equal(deadline.toNumber());
+    for (const proposalPDA of proposalPDAs) {
+      const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+      expect(proposal.onBallot.toBase58()).to.equal(ballotPDA.toBase58());
+    }
+
+    try {
+      await program.methods
+        .extendDeadline(deadline.addn(600))
+        .accountsPartial({
+          signer: owner.publicKey,
+          proposalAcc: proposalPDAs[0],
+          categoryAcc: null,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("the races close together");
+    } catch (e) {
+      expect(e.toString()).to.include("DeadlineSetByBallot");
+    }
+  });
+
+  it("keeps square-root credits off a proposal until it joins its ballot", async () => {
+    const BALLOT_ID = 2;
+    const PROPOSAL_IDS = [46, 47];
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const deadline = new anchor.BN(now + 600);
+    const proposalPDAs = PROPOSAL_IDS.map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("proposal"),
+            owner.publicKey.toBuffer(),
+            Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+          ],
+          program.programId
+        )[0]
+    );
+    const [ballotPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("ballot"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([BALLOT_ID]).buffer)),
+      ],
+      program.programId
+    );
+    for (const id of PROPOSAL_IDS) {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          id,
+          `Seat ${id}`,
+          ["Ana", "Ben"],
+          2,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+    }
+
+    // Any existing mint will do; reuse the first proposal's receipt mint.
+    const [firstProposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([1]).buffer)),
+      ],
+      program.programId
+    );
+    const [creditMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), firstProposalPDA.toBuffer()],
+      program.programId
+    );
+    const openSqrtCredits = (proposalPDA: PublicKey) =>
+      program.methods
+        .openSqrtCredits()
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          creditMint,
+        })
+        .rpc({ commitment: "confirmed" });
+    const createBallot = () =>
+      program.methods
+        .createBallot(BALLOT_ID, "Council seats")
+        .accountsPartial({ authority: owner.publicKey, ballotAcc: ballotPDA })
+        .remainingAccounts(
+          proposalPDAs.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .rpc({ commitment: "confirmed" });
+
+    // Registrations made under the proposal would not reach the ballot.
+    await openSqrtCredits(proposalPDAs[0]);
+    try {
+      await createBallot();
+      expect.fail("credits must open after the proposal joins its ballot");
+    } catch (e) {
+      expect(e.toString()).to.include("SqrtCreditsAlreadyOpen");
+    }
+  });
+
+  it("casts choices on two races of a ballot in one computation", async () => {
+    const BALLOT_ID = 3;
+    const PROPOSAL_IDS = [56, 57];
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const deadline = new anchor.BN(now + 600);
+    const proposalPDAs = PROPOSAL_IDS.map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("proposal"),
+            owner.publicKey.toBuffer(),
+            Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+          ],
+          program.programId
+        )[0]
+    );
+    const [ballotPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("ballot"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([BALLOT_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const offsets = PROPOSAL_IDS.map(() => new anchor.BN(randomBytes(8), "hex"));
+    const createProposals = PROPOSAL_IDS.map((id, i) =>
+      program.methods
+        .createProposal(
+          offsets[i],
+          id,
+          i === 0 ? "Adopt the budget?" : "Amend the bylaws?",
+          ["For", "Against", "Abstain"],
+          3,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { yesNoAbstain: { vetoBps: 0 } },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offsets[i]
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+    );
+    await createProposals[0].rpc({ commitment: "confirmed" });
+    await createProposals[1]
+      .postInstructions([
+        await program.methods
+          .createBallot(BALLOT_ID, "Annual meeting")
+          .accountsPartial({ authority: owner.publicKey, ballotAcc: ballotPDA })
+          .remainingAccounts(
+            proposalPDAs.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+          )
+          .instruction(),
+      ])
+      .rpc({ commitment: "confirmed" });
+    for (const offset of offsets) {
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const nonce = randomBytes(16);
+    // For on the budget, Against on the bylaws; the unused slots stay zero.
+    const ciphertexts = cipher.encrypt(
+      [0, 1, 0, 0, 0, 0, 0, 0].map((c) => BigInt(c)),
+      nonce
+    );
+    const castBallotChoices = (races: PublicKey[], offset: anchor.BN) =>
+      program.methods
+        .castBallotChoices(
+          offset,
+          ciphertexts.map((c) => Array.from(c)),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_ballot_choices")).readUInt32LE()
+          ),
+        })
+        .remainingAccounts(ballotChoiceAccounts(program.programId, races, voter.publicKey))
+        .signers([voter]);
+
+    try {
+      await castBallotChoices(
+        [proposalPDAs[0], proposalPDAs[0]],
+        new anchor.BN(randomBytes(8), "hex")
+      ).rpc({
+        commitment: "confirmed",
+      });
+      expect.fail("the two races must differ");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidBallot");
+    }
+
+    const castOffset = new anchor.BN(randomBytes(8), "hex");
+    await castBallotChoices(proposalPDAs, castOffset).rpc({
+      skipPreflight: true,
+      commitment: "confirmed",
+    });
+    await awaitComputationFinalization(provider, castOffset, program.programId, "confirmed");
+
+    for (const proposalPDA of proposalPDAs) {
+      const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+      expect(proposal.voterCount).to.equal(1);
+      expect(proposal.appliedBallots).to.equal(1);
+      expect(proposal.pendingComputations).to.equal(0);
+      const [voterRecordPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("voter"), proposalPDA.toBuffer(), voter.publicKey.toBuffer()],
+        program.programId
+      );
+      const record = await program.account.voterRecord.fetch(voterRecordPDA);
+      expect(record.counted).to.equal(true);
+    }
+  });
+
+  it("casts choices on one race and on every race of a full ballot", async () => {
+    const BALLOT_ID = 5;
+    const PROPOSAL_IDS = [84, 85, 86, 87, 88, 89, 90, 91];
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const deadline = new anchor.BN(now + 600);
+    const proposalPDAs = PROPOSAL_IDS.map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("proposal"),
+            owner.publicKey.toBuffer(),
+            Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+          ],
+          program.programId
+        )[0]
+    );
+    const [ballotPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("ballot"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([BALLOT_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const offsets = PROPOSAL_IDS.map(() => new anchor.BN(randomBytes(8), "hex"));
+    for (const [i, id] of PROPOSAL_IDS.entries()) {
+      const createProposal = program.methods
+        .createProposal(
+          offsets[i],
+          id,
+          `Seat ${i + 1}?`,
+          ["For", "Against", "Abstain"],
+          3,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { yesNoAbstain: { vetoBps: 0 } },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offsets[i]
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        });
+      if (i < PROPOSAL_IDS.length - 1) {
+        await createProposal.rpc({ commitment: "confirmed" });
+        continue;
+      }
+      await createProposal
+        .postInstructions([
+          await program.methods
+            .createBallot(BALLOT_ID, "Board seats")
+            .accountsPartial({ authority: owner.publicKey, ballotAcc: ballotPDA })
+            .remainingAccounts(
+              proposalPDAs.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+            )
+            .instruction(),
+        ])
+        .rpc({ commitment: "confirmed" });
+    }
+    for (const offset of offsets) {
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const castBallotChoices = async (
+      voter: anchor.web3.Keypair,
+      races: PublicKey[],
+      choices: number[]
+    ) => {
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const padded = [...choices, ...Array(8 - choices.length).fill(0)];
+      const ciphertexts = cipher.encrypt(
+        padded.map((c) => BigInt(c)),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      const ix = await program.methods
+        .castBallotChoices(
+          offset,
+          ciphertexts.map((c) => Array.from(c)),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_ballot_choices")).readUInt32LE()
+          ),
+        })
+        .remainingAccounts(ballotChoiceAccounts(program.programId, races, voter.publicKey))
+        .instruction();
+      // Eight races overflow a legacy transaction; their accounts go in a lookup table.
+      await sendWithLookupTable(provider, owner, voter, ix);
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    };
+
+    const single = anchor.web3.Keypair.generate();
+    await castBallotChoices(single, [proposalPDAs[3]], [2]);
+    const full = anchor.web3.Keypair.generate();
+    await castBallotChoices(full, proposalPDAs, [0, 1, 2, 0, 1, 2, 0, 1]);
+
+    for (const [i, proposalPDA] of proposalPDAs.entries()) {
+      const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+      expect(proposal.voterCount).to.equal(i === 3 ? 2 : 1);
+      expect(proposal.appliedBallots).to.equal(i === 3 ? 2 : 1);
+      expect(proposal.pendingComputations).to.equal(0);
+      const [voterRecordPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("voter"), proposalPDA.toBuffer(), full.publicKey.toBuffer()],
+        program.programId
+      );
+      const record = await program.account.voterRecord.fetch(voterRecordPDA);
+      expect(record.counted).to.equal(true);
+    }
+    const [singleRecordPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter"), proposalPDAs[3].toBuffer(), single.publicKey.toBuffer()],
+      program.programId
+    );
+    const singleRecord = await program.account.voterRecord.fetch(singleRecordPDA);
+    expect(singleRecord.counted).to.equal(true);
+  });
+
+  it("records a voter's ballots across a round in one account", async () => {
+    const BALLOT_ID = 4;
+    const PROPOSAL_IDS = [58, 59];
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const deadline = new anchor.BN(now + 600);
+    const proposalPDAs = PROPOSAL_IDS.map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("proposal"),
+            owner.publicKey.toBuffer(),
+            Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+          ],
+          program.programId
+        )[0]
+    );
+    const [ballotPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("ballot"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([BALLOT_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const offsets = PROPOSAL_IDS.map(() => new anchor.BN(randomBytes(8), "hex"));
+    const createProposals = PROPOSAL_IDS.map((id, i) =>
+      program.methods
+        .createProposal(
+          offsets[i],
+          id,
+          i === 0 ? "Elect the treasurer?" : "Elect the secretary?",
+          ["For", "Against", "Abstain"],
+          3,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { yesNoAbstain: { vetoBps: 0 } },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offsets[i]
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+    );
+    const remainingAccounts = proposalPDAs.map((pubkey) => ({
+      pubkey,
+      isWritable: true,
+      isSigner: false,
+    }));
+    await createProposals[0].rpc({ commitment: "confirmed" });
+    await createProposals[1]
+      .postInstructions([
+        await program.methods
+          .createBallot(BALLOT_ID, "Officer elections")
+          .accountsPartial({ authority: owner.publicKey, ballotAcc: ballotPDA })
+          .remainingAccounts(remainingAccounts)
+          .instruction(),
+        await program.methods
+          .openRoundRecords()
+          .accountsPartial({ authority: owner.publicKey, ballotAcc: ballotPDA })
+          .remainingAccounts(remainingAccounts)
+          .instruction(),
+      ])
+      .rpc({ commitment: "confirmed" });
+    for (const offset of offsets) {
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+    for (const [slot, proposalPDA] of proposalPDAs.entries()) {
+      const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+      expect(proposal.roundSlot).to.equal(slot);
+    }
+
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const [roundRecordPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("round_voter"), ballotPDA.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const nonce = randomBytes(16);
+    const ciphertexts = cipher.encrypt([1, 0, 0, 0].map((c) => BigInt(c)), nonce);
+    const cast = (method: "castChoice" | "castRoundChoice", slot: number, offset: anchor.BN) =>
+      program.methods[method](
+        offset,
+        PROPOSAL_IDS[slot],
+        Array.from(ciphertexts[0]),
+        Array.from(ciphertexts[1]),
+        Array.from(ciphertexts[2]),
+        Array.from(ciphertexts[3]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString())
+      )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(
+              getCompDefAccOffset(method === "castChoice" ? "cast_choice" : "cast_round_choice")
+            ).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDAs[slot],
+          ...(method === "castRoundChoice" ? { roundRecord: roundRecordPDA } : {}),
+          voterTokenAccount: null,
+        })
+        .signers([voter]);
+
+    try {
+      await cast("castChoice", 0, new anchor.BN(randomBytes(8), "hex")).rpc({
+        commitment: "confirmed",
+      });
+      expect.fail("a round proposal takes cast_round_choice");
+    } catch (e) {
+      expect(e.toString()).to.include("RoundRecordRequired");
+    }
+
+    for (const slot of [0, 1]) {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await cast("castRoundChoice", slot, offset).rpc({
+        skipPreflight: true,
+        commitment: "confirmed",
+      });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    try {
+      await cast("castRoundChoice", 0, new anchor.BN(randomBytes(8), "hex")).rpc({
+        commitment: "confirmed",
+      });
+      expect.fail("one ballot per race");
+    } catch (e) {
+      expect(e.toString()).to.include("AlreadyVoted");
+    }
+
+    const record = await program.account.roundRecord.fetch(roundRecordPDA);
+    expect(record.ballot.toBase58()).to.equal(ballotPDA.toBase58());
+    expect(record.voted).to.equal(0b11);
+    expect(record.counted).to.equal(0b11);
+    for (const proposalPDA of proposalPDAs) {
+      const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+      expect(proposal.voterCount).to.equal(1);
+      expect(proposal.countedVoters).to.equal(1);
+    }
+  });
+
+  it("names a reveal operator once, before the first ballot", async () => {
+    const PROPOSAL_ID = 60;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+    const operator = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Renew the keeper contract?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const setRevealOperator = (key: PublicKey) =>
+      program.methods
+        .setRevealOperator(key)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await setRevealOperator(owner.publicKey);
+      expect.fail("the authority can't be its own reveal operator");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidRevealOperator");
+    }
+    const setEventPromise = awaitEvent("revealOperatorSetEvent");
+    await setRevealOperator(operator.publicKey);
+    const setEvent = await setEventPromise;
+    expect(setEvent.operator.toBase58()).to.equal(operator.publicKey.toBase58());
+    try {
+      await setRevealOperator(stranger.publicKey);
+      expect.fail("the reveal operator is fixed once set");
+    } catch (e) {
+      expect(e.toString()).to.include("RevealOperatorAlreadySet");
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.revealOperator.toBase58()).to.equal(operator.publicKey.toBase58());
+  });
+
+  it("keeps translations beside the canonical labels", async () => {
+    const PROPOSAL_ID = 61;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Fund the translation grant?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [translationsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("translations"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const setTranslation = (locale: string, title: string, options: string[]) =>
+      program.methods
+        .setTranslation(locale, title, options)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+    const removeTranslation = (locale: string) =>
+      program.methods
+        .removeTranslation(locale)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+
+    const refusals: [string, string[]][] = [
+      ["p", ["Sim", "Não"]],
+      ["pt_BR", ["Sim", "Não"]],
+      ["pt-", ["Sim", "Não"]],
+      ["pt-BR", ["Sim", "Não", "Talvez"]],
+    ];
+    for (const [locale, options] of refusals) {
+      try {
+        await setTranslation(locale, "Financiar a bolsa?", options);
+        expect.fail("invalid translations are refused");
+      } catch (e) {
+        expect(e.toString()).to.include("InvalidTranslation");
+      }
+    }
+
+    const setEventPromise = awaitEvent("translationSetEvent");
+    await setTranslation("pt-BR", "Financiar a bolsa?", ["Sim", "Nao"]);
+    const setEvent = await setEventPromise;
+    expect(setEvent.locale).to.equal("pt-BR");
+    await setTranslation("pt-BR", "Financiar a bolsa?", ["Sim", "Não"]);
+    await setTranslation("de", "Das Stipendium finanzieren?", ["Ja"]);
+
+    let translations = await program.account.proposalTranslations.fetch(translationsPDA);
+    expect(translations.translations.map((t: any) => t.locale)).to.deep.equal(["pt-BR", "de"]);
+    expect(translations.translations[0].options).to.deep.equal(["Sim", "Não"]);
+
+    try {
+      await removeTranslation("fr");
+      expect.fail("only existing translations can be removed");
+    } catch (e) {
+      expect(e.toString()).to.include("TranslationNotFound");
+    }
+    const removedEventPromise = awaitEvent("translationRemovedEvent");
+    await removeTranslation("de");
+    const removedEvent = await removedEventPromise;
+    expect(removedEvent.locale).to.equal("de");
+
+    translations = await program.account.proposalTranslations.fetch(translationsPDA);
+    expect(translations.translations.length).to.equal(1);
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.options).to.deep.equal(["Yes", "No"]);
+  });
+
+  it("elects a ranked-choice winner by instant runoff inside MPC", async () => {
+    const PROPOSAL_ID = 62;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 75
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Working group chair",
+        ["Ana", "Ben", "Cy"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(0),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { ranked: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [rankingsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("rankings"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    const openOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .openRankings(
+        openOffset,
+        PROPOSAL_ID,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          openOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_rankings")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(provider, openOffset, program.programId, "confirmed");
+    expect((await program.account.rankingTally.fetch(rankingsPDA)).ready).to.equal(true);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // Ana and Ben lead the first round 2-2; Cy's ballot then moves to Ben,
+    // who wins 3-2 although plurality would have tied.  The last ballot
+    // ranks Ana twice and is discarded.
+    const rankings = [
+      [0, 1, 2, 3],
+      [0, 2, 1, 3],
+      [1, 2, 0, 3],
+      [1, 0, 2, 3],
+      [2, 1, 0, 3],
+      [0, 0, 1, 3],
+    ];
+    for (const ranking of rankings) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(ranking.map((option) => BigInt(option)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castRankedVote(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_ranked_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const reveal = (method: "revealResults" | "revealRanked", circuit: string) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods[method](offset, PROPOSAL_ID).accountsPartial({
+          authority: owner.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+          ),
+        }),
+      ] as const;
+    };
+
+    // The plain reveal would hand the first-preference tie to Ana.
+    try {
+      await reveal("revealResults", "reveal_results")[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_results on a ranked-choice proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("WrongBallotKind");
+    }
+
+    const [revealOffset, revealTx] = reveal("revealRanked", "reveal_ranked");
+    await revealTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.results.slice(0, 5).map((n) => n.toNumber())).to.deep.equal([2, 3, 0, 0, 5]);
+    expect(revealed.winner).to.equal(1);
+  });
+
+  it("scores ranked ballots by Borda count and reveals the most points", async () => {
+    const PROPOSAL_ID = 63;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 75
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Q3 priorities",
+        ["Docs", "SDK", "Audit"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(0),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { borda: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // Docs leads on first preferences, but SDK is everyone's first or
+    // second choice: Docs 2+2 = 4, SDK 1+1+2+1 = 5, Audit 2+1 = 3 points.
+    // The last ballot ranks SDK twice and is discarded.
+    const rankings = [
+      [0, 1, 2, 3],
+      [0, 1, 2, 3],
+      [1, 2, 0, 3],
+      [2, 1, 0, 3],
+      [1, 1, 2, 3],
+    ];
+    for (const ranking of rankings) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(ranking.map((option) => BigInt(option)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castBorda(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_borda")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.results.slice(0, 5).map((n) => n.toNumber())).to.deep.equal([4, 5, 3, 0, 4]);
+    expect(revealed.winner).to.equal(1);
+  });
+
+  it("finds the Condorcet winner from an encrypted preference matrix", async () => {
+    const PROPOSAL_ID = 64;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 75
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Offsite venue",
+        ["Lisbon", "Berlin", "Austin"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(0),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { condorcet: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [preferencesPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("preferences"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    const openOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .openPreferences(
+        openOffset,
+        PROPOSAL_ID,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          openOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_preferences")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(provider, openOffset, program.programId, "confirmed");
+    expect((await program.account.preferenceMatrix.fetch(preferencesPDA)).ready).to.equal(true);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // Lisbon and Berlin tie 2-2 on first preferences, but Berlin beats
+    // Lisbon 3-2 and Austin 3-2 head to head, and Lisbon beats Austin
+    // 3-2.  The last ballot ranks Lisbon twice and is discarded.
+    const rankings = [
+      [0, 1, 2, 3],
+      [0, 2, 1, 3],
+      [1, 2, 0, 3],
+      [2, 1, 0, 3],
+      [1, 0, 2, 3],
+      [0, 0, 1, 3],
+    ];
+    for (const ranking of rankings) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(ranking.map((option) => BigInt(option)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castCondorcet(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_condorcet")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const reveal = (method: "revealResults" | "revealCondorcet", circuit: string) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods[method](offset, PROPOSAL_ID).accountsPartial({
+          authority: owner.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+          ),
+        }),
+      ] as const;
+    };
+
+    // The plain reveal would hand the first-preference tie to Lisbon.
+    try {
+      await reveal("revealResults", "reveal_results")[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_results on a Condorcet proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("WrongBallotKind");
+    }
+
+    const [revealOffset, revealTx] = reveal("revealCondorcet", "reveal_condorcet");
+    await revealTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.results.slice(0, 5).map((n) => n.toNumber())).to.deep.equal([1, 2, 0, 0, 5]);
+    expect(revealed.winner).to.equal(1);
+    expect((await program.account.preferenceMatrix.fetch(preferencesPDA)).cycle).to.equal(false);
+  });
+
+  it("scores STAR ballots and runs off the top two inside MPC", async () => {
+    const PROPOSAL_ID = 65;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 75
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Logo",
+        ["Wave", "Arc", "Dot"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(0),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { star: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [preferencesPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("preferences"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    const openOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .openPreferences(
+        openOffset,
+        PROPOSAL_ID,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          openOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_preferences")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(provider, openOffset, program.programId, "confirmed");
+    expect((await program.account.preferenceMatrix.fetch(preferencesPDA)).ready).to.equal(true);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // Arc has the highest score total, 18 to Wave's 15, but three of the
+    // five counted ballots score Wave above Arc, so Wave wins the runoff.
+    // The last ballot scores Wave 6 and is discarded.
+    const ballots = [
+      [5, 4, 0, 0],
+      [5, 4, 0, 0],
+      [0, 5, 1, 0],
+      [0, 5, 2, 0],
+      [5, 0, 0, 0],
+      [6, 0, 0, 0],
+    ];    for (const scores of ballots) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(scores.map((score) => BigInt(score)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castStar(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_star")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const reveal = (method: "revealResults" | "revealStar", circuit: string) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods[method](offset, PROPOSAL_ID).accountsPartial({
+          authority: owner.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+          ),
+        }),
+      ] as const;
+    };
+
+    // The plain reveal would hand the proposal to Arc on score alone.
+    try {
+      await reveal("revealResults", "reveal_results")[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_results on a STAR proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("WrongBallotKind");
+    }
+
+    const [revealOffset, revealTx] = reveal("revealStar", "reveal_star");
+    await revealTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.results.slice(0, 5).map((n) => n.toNumber())).to.deep.equal([15, 18, 3, 0, 5]);
+    expect(revealed.winner).to.equal(0);
+  });
+
+  it("weighs ballots by the voter's token balance once token weights are open", async () => {
+    const PROPOSAL_ID = 66;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Adopt the token-weighted treasury policy?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    // The first proposal's receipt mint stands in for a governance mint.
+    const [firstProposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([1]).buffer)),
+      ],
+      program.programId
+    );
+    const [weightMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), firstProposalPDA.toBuffer()],
+      program.programId
+    );
+    const openTokenWeights = (maxWeight: anchor.BN) =>
+      program.methods
+        .openTokenWeights(maxWeight)
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          weightMint,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    // 100 credits put at most 2·10 votes on a ballot, so MAX_VOTERS
+    // (1,000,000) ballots fit the u64 tallies up to u64::MAX / (20 · 10⁶)
+    // tokens each, and not one more.
+    const limit = new anchor.BN("922337203685");
+    for (const maxWeight of [new anchor.BN(0), limit.addn(1)]) {
+      try {
+        await openTokenWeights(maxWeight);
+        expect.fail(`a cap of ${maxWeight.toString()} tokens should fail`);
+      } catch (e) {
+        expect(e.toString()).to.include("InvalidTokenWeightCap");
+      }
+    }
+
+    const openedEventPromise = awaitEvent("tokenWeightsOpenedEvent");
+    await openTokenWeights(limit);
+    const openedEvent = await openedEventPromise;
+    expect(openedEvent.mint.toBase58()).to.equal(weightMint.toBase58());
+    expect(openedEvent.maxWeight.toString()).to.equal(limit.toString());
+    try {
+      await openTokenWeights(limit);
+      expect.fail("token weights open only once");
+    } catch (e) {
+      expect(e.toString()).to.include("TokenWeightsAlreadyOpen");
+    }
+
+    // Balances can be bent like an oracle's weights.
+    await program.methods
+      .setWeightCurve({ sqrt: {} } as any)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.weightMint.toBase58()).to.equal(weightMint.toBase58());
+    expect(proposal.weightDecimals).to.equal(0);
+    expect(proposal.maxTokenWeight.toString()).to.equal(limit.toString());
+    expect(proposal.weightCurve).to.have.property("sqrt");
+  });
+
+  it("leaves room in the token weight cap for the oracle's largest weight", async () => {
+    const PROPOSAL_ID = 81;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const createProposal = async (id: number) => {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          id,
+          "Weigh the grants vote by holding and standing?",
+          ["Yes", "No"],
+          2,
+          new anchor.BN(now + 600),
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+      return PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          owner.publicKey.toBuffer(),
+          Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+        ],
+        program.programId
+      )[0];
+    };
+
+    const [firstProposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([1]).buffer)),
+      ],
+      program.programId
+    );
+    const [weightMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), firstProposalPDA.toBuffer()],
+      program.programId
+    );
+    const openTokenWeights = (proposalPDA: PublicKey, maxWeight: anchor.BN) =>
+      program.methods
+        .openTokenWeights(maxWeight)
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          weightMint,
+        })
+        .rpc({ commitment: "confirmed" });
+    // Any executable program is accepted; the system program stands in.
+    const setOracle = (proposalPDA: PublicKey) =>
+      program.methods
+        .setEligibilityOracle()
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          oracle: anchor.web3.SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    // An oracle may answer up to MAX_ORACLE_WEIGHT (10,000), so with 100
+    // credits the cap drops from 922,337,203,685 tokens to
+    // u64::MAX / (20 · 10⁶ · 10⁴).
+    const withoutOracle = new anchor.BN("922337203685");
+    const withOracle = new anchor.BN("92233720");
+
+    const oracleFirst = await createProposal(PROPOSAL_ID);
+    await setOracle(oracleFirst);
+    for (const maxWeight of [withoutOracle, withOracle.addn(1)]) {
+      try {
+        await openTokenWeights(oracleFirst, maxWeight);
+        expect.fail(`a cap of ${maxWeight.toString()} tokens should fail beside an oracle`);
+      } catch (e) {
+        expect(e.toString()).to.include("InvalidTokenWeightCap");
+      }
+    }
+    await openTokenWeights(oracleFirst, withOracle);
+
+    // The other way round, the oracle is refused while the cap would let
+    // its largest weight overflow the tallies.
+    const weightsFirst = await createProposal(PROPOSAL_ID + 1);
+    await openTokenWeights(weightsFirst, withoutOracle);
+    try {
+      await setOracle(weightsFirst);
+      expect.fail("an oracle should not multiply a cap that only fits without it");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidTokenWeightCap");
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(oracleFirst);
+    expect(proposal.maxTokenWeight.toString()).to.equal(withOracle.toString());
+    expect(proposal.eligibilityOracle.toBase58()).to.equal(
+      anchor.web3.SystemProgram.programId.toBase58()
+    );
+  });
+
+  it("averages range scores after the reveal", async () => {
+    const PROPOSAL_ID = 67;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 75
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Mascot",
+        ["Owl", "Fox", "Elk"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(0),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { range: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    // Owl 10+4+7 = 21, Fox 6+9+7 = 22, Elk 0+2+7 = 9 over three counted
+    // ballots.  The fourth scores Elk off the scale and the fifth scores
+    // an option the proposal doesn't have; both are discarded.
+    const ballots = [
+      [10, 6, 0, 0],
+      [4, 9, 2, 0],
+      [7, 7, 7, 0],
+      [3, 0, 11, 0],
+      [0, 0, 0, 5],
+    ];
+    for (const scores of ballots) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(scores.map((score) => BigInt(score)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castRange(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_range")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const averagesPromise = awaitEvent("rangeAveragesEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const averages = await averagesPromise;
+    expect(averages.ballots.toNumber()).to.equal(3);
+    expect(averages.averageScores.map((n) => n.toNumber())).to.deep.equal([700, 733, 300, 0]);
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.results.slice(0, 5).map((n) => n.toNumber())).to.deep.equal([21, 22, 9, 0, 3]);
+    expect(revealed.winner).to.equal(1);
+  });
+
+  it("checkpoints the encrypted tally into a commitment", async () => {
+    const PROPOSAL_ID = 48;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Audit the tally?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [commitmentPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commitment"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const commitTally = () =>
+      program.methods
+        .commitTally()
+        .accountsPartial({ payer: owner.publicKey, proposalAcc: proposalPDA, commitment: commitmentPDA })
+        .rpc({ commitment: "confirmed" });
+
+    const committedEventPromise = awaitEvent("tallyCommittedEvent");
+    await commitTally();
+    const committedEvent = await committedEventPromise;
+    expect(committedEvent.checkpoint).to.equal(1);
+
+    // The leaf and root follow from the tally alone.
+    const domain = Buffer.from("arcvote-tally-commitment-v1");
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    const [tallyPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("tally"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const tally = await program.account.tallyAccount.fetch(tallyPDA);
+    const leaf = createHash("sha256")
+      .update(domain)
+      .update(proposalPDA.toBuffer())
+      .update(Buffer.concat(tally.voteState.map((c) => Buffer.from(c))))
+      .update(Buffer.from(tally.nonce))
+      .update(Buffer.from(new Uint8Array(new Uint32Array([proposal.appliedBallots]).buffer)))
+      .digest();
+    const root = createHash("sha256").update(domain).update(Buffer.alloc(32)).update(leaf).digest();
+    const commitment = await program.account.commitment.fetch(commitmentPDA);
+    expect(Buffer.from(commitment.leaf).equals(leaf)).to.equal(true);
+    expect(Buffer.from(commitment.root).equals(root)).to.equal(true);
+    expect(Buffer.from(committedEvent.root).equals(root)).to.equal(true);
+
+    try {
+      await commitTally();
+      expect.fail("an unchanged tally is not checkpointed again");
+    } catch (e) {
+      expect(e.toString()).to.include("TallyUnchanged");
+    }
+  });
+
+  it("rejects a dual-chamber proposal the community passed but the council did not", async () => {
+    const PROPOSAL_ID = 49;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 90
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Raise the treasury cap?",
+        ["For", "Against", "Abstain"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { yesNoAbstain: { vetoBps: 0 } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [councilPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("council"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    const fund = async () => {
+      const wallet = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      return wallet;
+    };
+    const members = [await fund(), await fund()];
+
+    const openOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .openCouncil(
+        openOffset,
+        PROPOSAL_ID,
+        new anchor.BN(deserializeLE(randomBytes(16)).toString()),
+        members.map((m) => m.publicKey),
+        { bothMustPass: {} }
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          openOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_council_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(provider, openOffset, program.programId, "confirmed");
+    expect((await program.account.councilChamber.fetch(councilPDA)).ready).to.equal(true);
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).dualChamber).to.equal(true);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const cast = (method: "castChoice" | "castCouncilChoice", voter: anchor.web3.Keypair, option: number) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(
+        [0, 1, 2, 3].map((i) => BigInt(i === option ? 1 : 0)),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      const circuit = method === "castChoice" ? "cast_choice" : "cast_council_choice";
+      return [
+        offset,
+        program.methods[method](
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+          .accountsPartial({
+            payer: voter.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+            ),
+            authority: owner.publicKey,
+            proposalAcc: proposalPDA,
+            ...(method === "castChoice" ? { voterTokenAccount: null } : {}),
+          })
+          .signers([voter]),
+      ] as const;
+    };
+
+    // Community: For 2, Against 1 — passes on its own.
+    for (const option of [0, 0, 1]) {
+      const [offset, tx] = cast("castChoice", await fund(), option);
+      await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    // Only seated members reach the council tally.
+    try {
+      await cast("castCouncilChoice", await fund(), 0)[1].rpc({ commitment: "confirmed" });
+      expect.fail("a non-member's council ballot should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("NotACouncilMember");
+    }
+
+    // Council: Against 1, Abstain 1 — rejects.
+    for (const [member, option] of [[members[0], 1], [members[1], 2]] as const) {
+      const [offset, tx] = cast("castCouncilChoice", member, option);
+      await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+    const chamber = await program.account.councilChamber.fetch(councilPDA);
+    expect(chamber.ballots).to.equal(2);
+    // Council ballots stay out of the community's turnout.
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).voterCount).to.equal(3);
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const reveal = (method: "revealOutcome" | "revealChambers", circuit: string) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods[method](offset, PROPOSAL_ID).accountsPartial({
+          authority: owner.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+          ),
+        }),
+      ] as const;
+    };
+
+    // Revealing the community alone would skip the council.
+    try {
+      await reveal("revealOutcome", "reveal_outcome")[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_outcome on a dual-chamber proposal should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("DualChamberReveal");
+    }
+
+    const chambersPromise = awaitEvent("chambersRevealedEvent");
+    const [revealOffset, tx] = reveal("revealChambers", "reveal_chambers");
+    await tx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const event = await chambersPromise;
+    expect(event.communityOutcome).to.deep.equal({ passed: {} });
+    expect(event.councilFor.toNumber()).to.equal(0);
+    expect(event.councilAgainst.toNumber()).to.equal(1);
+    expect(event.councilAbstain.toNumber()).to.equal(1);
+    expect(event.councilOutcome).to.deep.equal({ rejected: {} });
+    expect(event.outcome).to.deep.equal({ rejected: {} });
+
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.isFinalized).to.equal(true);
+    expect(revealed.outcome).to.deep.equal({ rejected: {} });
+    expect(revealed.results[0].toNumber()).to.equal(2);
+    expect(revealed.results[1].toNumber()).to.equal(1);
+    const council = await program.account.councilChamber.fetch(councilPDA);
+    expect(council.results.map((n) => n.toNumber())).to.deep.equal([0, 1, 1, 0, 2]);
+    expect(council.communityOutcome).to.deep.equal({ passed: {} });
+    expect(council.councilOutcome).to.deep.equal({ rejected: {} });
+  });
+
+  it("holds a low-turnout majority to a turnout-biased margin", async () => {
+    const PROPOSAL_ID = 53;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    // Quorum: 10% of an electorate of 10.
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Adopt the new charter?",
+        ["For", "Against", "Abstain"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1_000,
+        { registeredBps: {} },
+        new anchor.BN(10),
+        new anchor.BN(0),
+        { yesNoAbstain: { vetoBps: 0 } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    for (const biasBps of [0, 5_001]) {
+      try {
+        await program.methods
+          .setTurnoutBias(biasBps)
+          .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+          .rpc({ commitment: "confirmed" });
+        expect.fail(`a bias of ${biasBps} bps should be refused`);
+      } catch (e) {
+        expect(e.toString()).to.include("InvalidTurnoutBias");
+      }
+    }
+    await program.methods
+      .setTurnoutBias(5_000)
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).turnoutBiasBps).to.equal(5_000);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    // For 2, Against 1: a simple majority, but 3 of 10 voting needs For
+    // above 1/2 + 1/2 · 7/10 = 85% of For and Against.
+    for (const option of [0, 0, 1]) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(
+        [0, 1, 2, 3].map((i) => BigInt(i === option ? 1 : 0)),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castChoice(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_choice")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    // The bias is fixed once ballots are in.
+    try {
+      await program.methods
+        .setTurnoutBias(1)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("setting a bias after the first ballot should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("ProposalFrozen");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const outcomePromise = awaitEvent("outcomeRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealOutcome(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_outcome")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    expect((await outcomePromise).outcome).to.deep.equal({ rejected: {} });
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.outcome).to.deep.equal({ rejected: {} });
+    expect(revealed.results[0].toNumber()).to.equal(2);
+    expect(revealed.results[1].toNumber()).to.equal(1);
+  });
+
+  it("strips a creator of its registry flags once a sanction passes", async () => {
+    const PROPOSAL_ID = 54;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const creator = anchor.web3.Keypair.generate().publicKey;
+    const [creatorPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator"), creator.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .setCreator(creator, true, true)
+      .accountsPartial({ payer: owner.publicKey, admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Remove the spam creator?",
+        ["For", "Against", "Abstain"],
+        3,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { yesNoAbstain: { vetoBps: 0 } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [sanctionPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("sanction"), proposalPDA.toBuffer()],
+      program.programId
+    );
+
+    // The proposal's own authority is out of reach.
+    try {
+      await program.methods
+        .declareSanction({ removeCreator: { creator: owner.publicKey } })
+        .accountsPartial({ payer: owner.publicKey, authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a sanction against the proposal's authority should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidSanction");
+    }
+    await program.methods
+      .declareSanction({ removeCreator: { creator } })
+      .accountsPartial({ payer: owner.publicKey, authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    const execute = () =>
+      program.methods
+        .executeSanction()
+        .accountsPartial({
+          proposalAcc: proposalPDA,
+          config: configPDA,
+          targetAcc: null,
+          treasury: null,
+          creatorRecord: creatorPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const nonce = randomBytes(16);
+    const ciphertexts = cipher.encrypt([BigInt(1), BigInt(0), BigInt(0), BigInt(0)], nonce);
+    const castOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .castChoice(
+        castOffset,
+        PROPOSAL_ID,
+        Array.from(ciphertexts[0]),
+        Array.from(ciphertexts[1]),
+        Array.from(ciphertexts[2]),
+        Array.from(ciphertexts[3]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString())
+      )
+      .accountsPartial({
+        payer: voter.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          castOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("cast_choice")).readUInt32LE()
+        ),
+        authority: owner.publicKey,
+        proposalAcc: proposalPDA,
+        voterTokenAccount: null,
+      })
+      .signers([voter])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, castOffset, program.programId, "confirmed");
+
+    // Nothing happens before the proposal is decided.
+    try {
+      await execute();
+      expect.fail("executing an undecided sanction should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("SanctionNotPassed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealOutcome(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_outcome")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+    expect((await program.account.proposalAccount.fetch(proposalPDA)).outcome).to.deep.equal({ passed: {} });
+
+    const executedPromise = awaitEvent("sanctionExecutedEvent");
+    await execute();
+    const event = await executedPromise;
+    expect(event.action).to.deep.equal({ removeCreator: { creator } });
+    expect(event.amount.toNumber()).to.equal(0);
+
+    const record = await program.account.creatorRecord.fetch(creatorPDA);
+    expect(record.whitelisted).to.equal(false);
+    expect(record.allowed).to.equal(false);
+    expect((await program.account.sanction.fetch(sanctionPDA)).executed).to.equal(true);
+
+    try {
+      await execute();
+      expect.fail("a sanction should only execute once");
+    } catch (e) {
+      expect(e.toString()).to.include("SanctionAlreadyExecuted");
+    }
+  });
+
+  it("fast-tracks an emergency proposal under a council-approved supermajority", async () => {
+    const PROPOSAL_ID = 55;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Pause the bridge?",
+        ["For", "Against", "Abstain"],
+        3,
+        new anchor.BN(now + 86_400),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { yesNoAbstain: { vetoBps: 0 } },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    // The council is the admin in these tests.
+    const fastTrack = (deadline: number) =>
+      program.methods
+        .fastTrack(new anchor.BN(deadline))
+        .accountsPartial({
+          authority: owner.publicKey,
+          council: owner.publicKey,
+          config: configPDA,
+          proposalAcc: proposalPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await fastTrack(now + 60);
+      expect.fail("fast-tracking should be off by default");
+    } catch (e) {
+      expect(e.toString()).to.include("FastTrackDisabled");
+    }
+
+    await program.methods
+      .updateConfig(
+        configParams(owner.publicKey, { emergencyMinDuration: 45, emergencySupermajorityBps: 7_500 })
+      )
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await fastTrack(now + 5);
+      expect.fail("a deadline sooner than the emergency minimum should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidFastTrack");
+    }
+    const deadline = Math.floor(Date.now() / 1000) + 60;
+    await fastTrack(deadline);
+    const tracked = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(tracked.deadline.toNumber()).to.equal(deadline);
+    expect(tracked.supermajorityBps).to.equal(7_500);
+
+    // For 2, Against 1: a majority, but short of 75%.
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    for (const option of [0, 0, 1]) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(
+        [0, 1, 2, 3].map((i) => BigInt(i === option ? 1 : 0)),
+        nonce
+      );
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castChoice(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_choice")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+    await program.methods
+      .updateConfig(configParams(owner.publicKey))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline) break;
+      await sleep(1000);
+    }
+
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealOutcome(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_outcome")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const revealed = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(revealed.outcome).to.deep.equal({ rejected: {} });
+    expect(revealed.results[0].toNumber()).to.equal(2);
+    expect(revealed.results[1].toNumber()).to.equal(1);
+  });
+
+  it("describes what each option pays until the first ballot", async () => {
+    const PROPOSAL_ID = 50;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Which grant do we fund?",
+        ["Indexer", "Docs"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [optionDetailsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("option_details"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const setOptionDetails = (details: any[]) =>
+      program.methods
+        .setOptionDetails(details)
+        .accountsPartial({
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    const indexer = anchor.web3.Keypair.generate().publicKey;
+    const refusals: any[][] = [
+      // More entries than options.
+      [
+        { recipient: null, amount: new anchor.BN(0), metadataUri: "" },
+        { recipient: null, amount: new anchor.BN(0), metadataUri: "" },
+        { recipient: null, amount: new anchor.BN(0), metadataUri: "" },
+      ],
+      [{ recipient: null, amount: new anchor.BN(0), metadataUri: "https://x.org/a b" }],
+      [{ recipient: null, amount: new anchor.BN(0), metadataUri: "https://x.org/" + "a".repeat(200) }],
+    ];
+    for (const details of refusals) {
+      try {
+        await setOptionDetails(details);
+        expect.fail("invalid option details are refused");
+      } catch (e) {
+        expect(e.toString()).to.include("InvalidOptionDetails");
+      }
+    }
+
+    await setOptionDetails([
+      { recipient: indexer, amount: new anchor.BN(1_000), metadataUri: "ipfs://indexer" },
+    ]);
+    // A second call replaces the first.
+    await setOptionDetails([
+      { recipient: indexer, amount: new anchor.BN(5_000), metadataUri: "ipfs://indexer-v2" },
+      { recipient: null, amount: new anchor.BN(0), metadataUri: "ipfs://docs" },
+    ]);
+    const { details } = await program.account.optionDetails.fetch(optionDetailsPDA);
+    expect(details.length).to.equal(2);
+    expect(details[0].recipient.toBase58()).to.equal(indexer.toBase58());
+    expect(details[0].amount.toNumber()).to.equal(5_000);
+    expect(details[0].metadataUri).to.equal("ipfs://indexer-v2");
+    expect(details[1].recipient).to.equal(null);
+    expect(details[1].metadataUri).to.equal("ipfs://docs");
+  });
+
+  it("takes only the committed ballot after a commit phase", async () => {
+    const PROPOSAL_ID = 51;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const now = Math.floor(Date.now() / 1000);
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Adopt the new charter?",
+        ["Yes", "No"],
+        2,
+        new anchor.BN(now + 600),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const openCommitPhase = (commitDeadline: number) =>
+      program.methods
+        .openCommitPhase(new anchor.BN(commitDeadline))
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+
+    // The commit phase must end before voting does.
+    try {
+      await openCommitPhase(now + 900);
+      expect.fail("a commit phase past the deadline is refused");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidCommitPhase");
+    }
+    const commitDeadline = Math.floor(Date.now() / 1000) + 20;
+    await openCommitPhase(commitDeadline);
+    try {
+      await openCommitPhase(commitDeadline + 10);
+      expect.fail("the commit phase is opened once");
+    } catch (e) {
+      expect(e.toString()).to.include("CommitPhaseAlreadyOpen");
+    }
+
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const encrypt = (nonce: Uint8Array) => cipher.encrypt([5n, 5n, 0n, 0n], nonce);
+    const castVote = (ciphertexts: number[][], nonce: Uint8Array) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" })
+        .then((sig) => ({ sig, offset }));
+    };
+
+    const nonce = randomBytes(16);
+    const ciphertexts = encrypt(nonce);
+    // The nonce goes in as the little-endian bytes it was read from.
+    const commitment = createHash("sha256")
+      .update("arcvote-ballot-commitment-v1")
+      .update(proposalPDA.toBuffer())
+      .update(voter.publicKey.toBuffer())
+      .update(Buffer.concat(ciphertexts.map((ct) => Buffer.from(ct))))
+      .update(Buffer.from(publicKey))
+      .update(nonce)
+      .digest();
+    try {
+      await castVote(ciphertexts, nonce);
+      expect.fail("no ballot is taken during the commit phase");
+    } catch (e) {
+      expect(e.toString()).to.include("CommitPhaseOpen");
+    }
+    await program.methods
+      .commitBallot(Array.from(commitment))
+      .accountsPartial({ voter: voter.publicKey, proposalAcc: proposalPDA })
+      .signers([voter])
+      .rpc({ commitment: "confirmed" });
+    const [commitmentPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ballot_commitment"), proposalPDA.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    const record = await program.account.ballotCommitment.fetch(commitmentPDA);
+    expect(Buffer.from(record.commitment).equals(commitment)).to.equal(true);
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= commitDeadline) break;
+      await sleep(1000);
+    }
+    // The same allocation under another nonce isn't the ballot committed to.
+    const otherNonce = randomBytes(16);
+    try {
+      await castVote(encrypt(otherNonce), otherNonce);
+      expect.fail("an uncommitted ballot is refused");
+    } catch (e) {
+      expect(e.toString()).to.include("BallotNotCommitted");
+    }
+    const { offset } = await castVote(ciphertexts, nonce);
+    await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.voterCount).to.equal(1);
+    expect(proposal.commitDeadline.toNumber()).to.equal(commitDeadline);
+  });
+
+  it("drops votes for options the proposal doesn't have", async () => {
+    const PROPOSAL_ID = 68;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 30
+    );
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Renew the audit contract?",
+        ["Yes", "No"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    // 3 votes for Yes and 9 for a third option that doesn't exist: within
+    // budget, but only the 3 may reach the tallies.
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const nonce = randomBytes(16);
+    const ciphertexts = cipher.encrypt([3n, 0n, 9n, 0n], nonce);
+    const offset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .castVote(
+        offset,
+        PROPOSAL_ID,
+        Array.from(ciphertexts[0]),
+        Array.from(ciphertexts[1]),
+        Array.from(ciphertexts[2]),
+        Array.from(ciphertexts[3]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString())
+      )
+      .accountsPartial({
+        payer: voter.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          offset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+        ),
+        authority: owner.publicKey,
+        proposalAcc: proposalPDA,
+        voterTokenAccount: null,
+        credential: null,
+        mixQueue: null,
+      })
+      .signers([voter])
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(3);
+    expect(results.option1.toNumber()).to.equal(0);
+    expect(results.option2.toNumber()).to.equal(0);
+    expect(results.totalVotes.toNumber()).to.equal(3);
+    expect(results.winner).to.equal(0);
+  });
+
+  it("casts votes on several proposals in one transaction", async () => {
+    const PROPOSAL_IDS = [69, 70];
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    const proposals: PublicKey[] = [];
+    for (const id of PROPOSAL_IDS) {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          id,
+          `Race ${id}`,
+          ["A", "B"],
+          2,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(100),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+      const [proposalPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          owner.publicKey.toBuffer(),
+          Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+        ],
+        program.programId
+      );
+      proposals.push(proposalPDA);
+    }
+
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const votes = [
+      [5n, 5n, 0n, 0n],
+      [0n, 10n, 0n, 0n],
+    ].map((allocation) => {
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation, nonce);
+      return {
+        ciphertexts: ciphertexts.map((c) => Array.from(c)),
+        encryptionPubkey: Array.from(publicKey),
+        nonce: new anchor.BN(deserializeLE(nonce).toString()),
+      };
+    });
+    // Kept below 2^56 so `offset + i` can't wrap.
+    const offset = new anchor.BN(randomBytes(7), "hex");
+    const voterRecords = proposals.map(
+      (proposal) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("voter"), proposal.toBuffer(), voter.publicKey.toBuffer()],
+          program.programId
+        )[0]
+    );
+    const castBatch = () =>
+      program.methods
+        .castVotesBatch(offset, votes)
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+        })
+        .remainingAccounts(
+          proposals.flatMap((proposal, i) => [
+            { pubkey: proposal, isSigner: false, isWritable: true },
+            {
+              pubkey: PublicKey.findProgramAddressSync(
+                [Buffer.from("tally"), proposal.toBuffer()],
+                program.programId
+              )[0],
+              isSigner: false,
+              isWritable: false,
+            },
+            { pubkey: voterRecords[i], isSigner: false, isWritable: true },
+            {
+              pubkey: getComputationAccAddress(
+                arciumEnv.arciumClusterOffset,
+                offset.addn(i)
+              ),
+              isSigner: false,
+              isWritable: true,
+            },
+          ])
+        )
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+
+    await castBatch();
+    for (let i = 0; i < proposals.length; i++) {
+      await awaitComputationFinalization(
+        provider,
+        offset.addn(i),
+        program.programId,
+        "confirmed"
+      );
+    }
+
+    for (let i = 0; i < proposals.length; i++) {
+      const record = await program.account.voterRecord.fetch(voterRecords[i]);
+      expect(record.proposal.toBase58()).to.equal(proposals[i].toBase58());
+      expect(record.counted).to.equal(true);
+      const proposal = await program.account.proposalAccount.fetch(proposals[i]);
+      expect(proposal.voterCount).to.equal(1);
+      expect(proposal.appliedBallots).to.equal(1);
+    }
+
+    // The records now exist, so the same batch again is a double vote.
+    let doubleVoted = false;
+    try {
+      await castBatch();
+      doubleVoted = true;
+    } catch (err) {
+      expect(String(err)).to.include("AlreadyVoted");
+    }
+    expect(doubleVoted).to.equal(false);
+  });
+
+  it("folds buffered ballots into the tally a batch at a time", async () => {
+    const PROPOSAL_ID = 71;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const now = blockTime || Math.floor(Date.now() / 1000);
+    const deadline = now + 40;
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Repaint the hall?",
+        ["Keep", "Repaint"],
+        2,
+        new anchor.BN(deadline),
+        new anchor.BN(0),
+        new anchor.BN(100),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const [ballotBufferPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ballot_buffer"), proposalPDA.toBuffer()],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+
+    await program.methods
+      .openBallotBuffer()
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+
+    //   Voter 0: [9, 4]   (81 + 16 = 97 credits)
+    //   Voter 1: [2, 9]   (4 + 81 = 85 credits)
+    //   Voter 2: [10, 1]  (100 + 1 = 101 credits, over budget: discarded)
+    const allocations = [
+      [9, 4, 0, 0],
+      [2, 9, 0, 0],
+      [10, 1, 0, 0],
+    ];
+    for (const allocation of allocations) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      await program.methods
+        .submitBallot(
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    // Admitted and counted toward quorum, but not yet in the tally.
+    let proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.voterCount).to.equal(3);
+    expect(proposal.buffered).to.equal(3);
+    const buffer = await program.account.ballotBuffer.fetch(ballotBufferPDA);
+    expect(buffer.ballots.length).to.equal(3);
+    const voterRecords = buffer.ballots.map((ballot) => ballot.voterRecord);
+
+    const processBatch = () => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .processBallotBatch(offset, PROPOSAL_ID)
+          .accountsPartial({
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("cast_vote_batch")).readUInt32LE()
+            ),
+            authority: owner.publicKey,
+            proposalAcc: proposalPDA,
+          }),
+      ] as const;
+    };
+    const reveal = () => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .revealResults(offset, PROPOSAL_ID)
+          .accountsPartial({
+            authority: owner.publicKey,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+            ),
+          }),
+      ] as const;
+    };
+
+    try {
+      await processBatch()[1].rpc({ commitment: "confirmed" });
+      expect.fail("a partial batch waits for the deadline");
+    } catch (e) {
+      expect(e.toString()).to.include("BallotBatchNotFull");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline) break;
+      await sleep(1000);
+    }
+
+    // The reveal waits for every buffered ballot.
+    try {
+      await reveal()[1].rpc({ commitment: "confirmed" });
+      expect.fail("reveal_results should wait for buffered ballots");
+    } catch (e) {
+      expect(e.toString()).to.include("BufferedBallotsPending");
+    }
+
+    const [batchOffset, batchTx] = processBatch();
+    await batchTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, batchOffset, program.programId, "confirmed");
+
+    proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.buffered).to.equal(0);
+    expect(proposal.appliedBallots).to.equal(3);
+    for (const voterRecord of voterRecords) {
+      const record = await program.account.voterRecord.fetch(voterRecord);
+      expect(record.counted).to.equal(true);
+    }
+    try {
+      await processBatch()[1].rpc({ commitment: "confirmed" });
+      expect.fail("an empty buffer has nothing to process");
+    } catch (e) {
+      expect(e.toString()).to.include("NoBufferedBallots");
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const [revealOffset, revealTx] = reveal();
+    await revealTx.rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(provider, revealOffset, program.programId, "confirmed");
+
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(11);
+    expect(results.option1.toNumber()).to.equal(13);
+    expect(results.winner).to.equal(1);
+  });
+
+  it("checks quadratic ballots against the proposal's own voice credits", async () => {
+    const PROPOSAL_ID = 72;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 30
+    );
+    const create = (voiceCredits: number) => {
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      return [
+        offset,
+        program.methods
+          .createProposal(
+            offset,
+            PROPOSAL_ID,
+            "Extend the grants program?",
+            ["Extend", "End"],
+            2,
+            deadline,
+            new anchor.BN(0),
+            new anchor.BN(voiceCredits),
+            1,
+            { voters: {} },
+            new anchor.BN(0),
+            new anchor.BN(0),
+            { quadratic: {} },
+            new anchor.BN(deserializeLE(randomBytes(16)).toString())
+          )
+          .accountsPartial({
+            authority: owner.publicKey,
+            quorumMint: null,
+            computationAccount: getComputationAccAddress(
+              arciumEnv.arciumClusterOffset,
+              offset
+            ),
+            clusterAccount,
+            mxeAccount: getMXEAccAddress(program.programId),
+            mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+            executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+            compDefAccount: getCompDefAccAddress(
+              program.programId,
+              Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+            ),
+          }),
+      ] as const;
+    };
+
+    try {
+      await create(0)[1].rpc({ commitment: "confirmed" });
+      expect.fail("a quadratic proposal without voice credits should fail");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidVoiceCredits");
+    }
+
+    const [computationOffset, createTx] = create(50);
+    await createTx.rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    //   Voter 0: [6, 3]  (36 + 9 = 45 credits, within 50)
+    //   Voter 1: [3, 7]  (9 + 49 = 58 credits, over 50: discarded)
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    for (const allocation of [
+      [6, 3, 0, 0],
+      [3, 7, 0, 0],
+    ]) {
+      const voter = anchor.web3.Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // With 100 credits the second ballot would have counted and tipped it to End.
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(6);
+    expect(results.option1.toNumber()).to.equal(3);
+    expect(results.winner).to.equal(0);
+  });
+
+  it("marks ballots with data-less voter records once the proposal opens them", async () => {
+    const PROPOSAL_ID = 83;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 30
+    );
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Fund the community call?",
+        ["Fund", "Skip"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(50),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    const openedPromise = awaitEvent("datalessRecordsOpenedEvent");
+    await program.methods
+      .openDatalessRecords()
+      .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+      .rpc({ commitment: "confirmed" });
+    const opened = await openedPromise;
+    expect(opened.proposalId).to.equal(PROPOSAL_ID);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+    const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+    const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+    const cast = (method: "castVote" | "castVoteDataless", offset: anchor.BN) => {
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt([BigInt(6), BigInt(3), BigInt(0), BigInt(0)], nonce);
+      return program.methods[method](
+        offset,
+        PROPOSAL_ID,
+        Array.from(ciphertexts[0]),
+        Array.from(ciphertexts[1]),
+        Array.from(ciphertexts[2]),
+        Array.from(ciphertexts[3]),
+        Array.from(publicKey),
+        new anchor.BN(deserializeLE(nonce).toString())
+      )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          ...(method === "castVote" ? { credential: null, mixQueue: null } : {}),
+        })
+        .signers([voter]);
+    };
+
+    try {
+      await cast("castVote", new anchor.BN(randomBytes(8), "hex")).rpc({
+        commitment: "confirmed",
+      });
+      expect.fail("a data-less proposal takes cast_vote_dataless");
+    } catch (e) {
+      expect(e.toString()).to.include("DatalessRecordRequired");
+    }
+
+    const offset = new anchor.BN(randomBytes(8), "hex");
+    await cast("castVoteDataless", offset).rpc({
+      skipPreflight: true,
+      commitment: "confirmed",
+    });
+    await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+
+    // The record is there, and empty: its existence is the vote.
+    const [voterRecordPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter"), proposalPDA.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    const record = await provider.connection.getAccountInfo(voterRecordPDA, "confirmed");
+    expect(record.owner.toBase58()).to.equal(program.programId.toBase58());
+    expect(record.data.length).to.equal(0);
+
+    try {
+      await cast("castVoteDataless", new anchor.BN(randomBytes(8), "hex")).rpc({
+        commitment: "confirmed",
+      });
+      expect.fail("a second ballot should find the record");
+    } catch (e) {
+      expect(e.toString()).to.include("AlreadyVoted");
+    }
+
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA, "confirmed");
+    expect(proposal.voterCount).to.equal(1);
+    expect(proposal.countedVoters).to.equal(1);
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(6);
+    expect(results.option1.toNumber()).to.equal(3);
+  });
+
+  it("checks ballots against credits the authority funded per voter", async () => {
+    const PROPOSAL_ID = 73;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 40
+    );
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Renew the maintainer stipend?",
+        ["Renew", "Lapse"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(10),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    const voters = [0, 1, 2].map(() => anchor.web3.Keypair.generate());
+    for (const voter of voters) {
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+    }
+
+    // The contributor gets 200 credits, the newcomer 25; the third voter none.
+    for (const [voter, credits] of [
+      [voters[0], 200],
+      [voters[1], 25],
+    ] as const) {
+      await program.methods
+        .fundCredits(voter.publicKey, new anchor.BN(credits))
+        .accountsPartial({
+          payer: owner.publicKey,
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+    const [creditAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("credit_account"), proposalPDA.toBuffer(), voters[0].publicKey.toBuffer()],
+      program.programId
+    );
+    const funded = await program.account.creditAccount.fetch(creditAccount);
+    expect(funded.credits.toNumber()).to.equal(200);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const castVote = async (voter: anchor.web3.Keypair, allocation: number[]) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          Array.from(ciphertexts[0]),
+          Array.from(ciphertexts[1]),
+          Array.from(ciphertexts[2]),
+          Array.from(ciphertexts[3]),
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: null,
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      return offset;
+    };
+
+    try {
+      await castVote(voters[2], [1, 1, 0, 0]);
+      expect.fail("a voter nobody funded should not be able to vote");
+    } catch (e) {
+      expect(e.toString()).to.include("CreditsNotFunded");
+    }
+
+    //   Voter 0: [10, 10]  (100 + 100 = 200 credits, all of theirs)
+    //   Voter 1: [3, 4]    (9 + 16 = 25 credits, all of theirs)
+    for (const [voter, allocation] of [
+      [voters[0], [10, 10, 0, 0]],
+      [voters[1], [3, 4, 0, 0]],
+    ] as const) {
+      const offset = await castVote(voter, [...allocation]);
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // Against the flat 10 credits both ballots would have been discarded.
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(13);
+    expect(results.option1.toNumber()).to.equal(14);
+    expect(results.winner).to.equal(1);
+  });
+
+  it("carries unspent voice credits over between a DAO's banked proposals", async () => {
+    const PROPOSAL_IDS = [74, 75];
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 60
+    );
+    const proposalPDAs: PublicKey[] = [];
+    for (const id of PROPOSAL_IDS) {
+      const computationOffset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .createProposal(
+          computationOffset,
+          id,
+          `Fund grant round ${id - 73}?`,
+          ["Fund", "Defer"],
+          2,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(10),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(0),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(
+        provider,
+        computationOffset,
+        program.programId,
+        "confirmed"
+      );
+      const [proposalPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          owner.publicKey.toBuffer(),
+          Buffer.from(new Uint8Array(new Int32Array([id]).buffer)),
+        ],
+        program.programId
+      );
+      await program.methods
+        .openCreditBank()
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA })
+        .rpc({ commitment: "confirmed" });
+      proposalPDAs.push(proposalPDA);
+    }
+
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+    const bankOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createCreditBank(
+        bankOffset,
+        PROPOSAL_IDS[0],
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        payer: voter.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          bankOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_credit_bank")).readUInt32LE()
+        ),
+        authority: owner.publicKey,
+        proposalAcc: proposalPDAs[0],
+      })
+      .signers([voter])
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(provider, bankOffset, program.programId, "confirmed");
+    const [creditBank] = PublicKey.findProgramAddressSync(
+      [Buffer.from("credit_bank"), owner.publicKey.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    const bank = await program.account.voterCreditBank.fetch(creditBank);
+    expect(bank.ready).to.equal(true);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const encryptVote = (allocation: number[]) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      return {
+        votes: ciphertexts.map((c) => Array.from(c)),
+        publicKey: Array.from(publicKey),
+        nonce: new anchor.BN(deserializeLE(nonce).toString()),
+      };
+    };
+    const queueAccounts = (offset: anchor.BN, circuit: string) => ({
+      payer: voter.publicKey,
+      computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, offset),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+      authority: owner.publicKey,
+      voterTokenAccount: null,
+    });
+
+    // A banked proposal only takes ballots that go through the bank.
+    try {
+      const { votes, publicKey, nonce } = encryptVote([1, 1, 0, 0]);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(offset, PROPOSAL_IDS[0], votes[0], votes[1], votes[2], votes[3], publicKey, nonce)
+        .accountsPartial({
+          ...queueAccounts(offset, "cast_vote"),
+          proposalAcc: proposalPDAs[0],
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a plain cast_vote should not be accepted on a banked proposal");
+    } catch (e) {
+      expect(e.toString()).to.include("BankedBallotRequired");
+    }
+
+    //   Round 1: [1, 1]  (2 of 10 credits; 8 carry over)
+    //   Round 2: [4, 1]  (17 credits: the new 10 plus the 8 banked)
+    for (const [i, allocation] of [
+      [0, [1, 1, 0, 0]],
+      [1, [4, 1, 0, 0]],
+    ] as const) {
+      const { votes, publicKey, nonce } = encryptVote([...allocation]);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVoteBanked(offset, PROPOSAL_IDS[i], votes[0], votes[1], votes[2], votes[3], publicKey, nonce)
+        .accountsPartial({
+          ...queueAccounts(offset, "cast_vote_banked"),
+          proposalAcc: proposalPDAs[i],
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_IDS[1])
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // Against round 2's own 10 credits the ballot would have been discarded.
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(4);
+    expect(results.option1.toNumber()).to.equal(1);
+    expect(results.winner).to.equal(0);
+  });
+
+  it("checks ballots against budgets nobody but the authority can read", async () => {
+    const PROPOSAL_ID = 76;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 50
+    );
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Fund the security audit?",
+        ["Fund", "Defer"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(10),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    const voters = [0, 1, 2].map(() => anchor.web3.Keypair.generate());
+    for (const voter of voters) {
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+    }
+
+    // The authority seals 30 credits for the first voter and 5 for the
+    // second; only it ever sees the amounts.
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const authorityKey = deriveEncryptionKey(owner, ENCRYPTION_KEY_MESSAGE);
+    const authorityCipher = new RescueCipher(
+      x25519.getSharedSecret(authorityKey.privateKey, mxePublicKey)
+    );
+    for (const [voter, credits] of [
+      [voters[0], 30],
+      [voters[1], 5],
+    ] as const) {
+      const budgetNonce = randomBytes(16);
+      const [budget] = authorityCipher.encrypt([BigInt(credits)], budgetNonce);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      const fundedPromise = awaitEvent("encryptedBudgetFundedEvent");
+      await program.methods
+        .fundEncryptedBudget(
+          offset,
+          voter.publicKey,
+          Array.from(budget),
+          Array.from(authorityKey.publicKey),
+          new anchor.BN(deserializeLE(budgetNonce).toString()),
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          payer: owner.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            offset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("seal_budget")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+      const funded = await fundedPromise;
+      expect(funded.voter.toBase58()).to.equal(voter.publicKey.toBase58());
+    }
+
+    const encryptVote = (voter: anchor.web3.Keypair, allocation: number[]) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const ciphertexts = cipher.encrypt(allocation.map((v) => BigInt(v)), nonce);
+      return {
+        votes: ciphertexts.map((c) => Array.from(c)),
+        publicKey: Array.from(publicKey),
+        nonce: new anchor.BN(deserializeLE(nonce).toString()),
+      };
+    };
+    const queueAccounts = (voter: anchor.web3.Keypair, offset: anchor.BN, circuit: string) => ({
+      payer: voter.publicKey,
+      computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, offset),
+      clusterAccount,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+      executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset(circuit)).readUInt32LE()
+      ),
+      authority: owner.publicKey,
+      proposalAcc: proposalPDA,
+      voterTokenAccount: null,
+    });
+    const castVoteBudgeted = async (voter: anchor.web3.Keypair, allocation: number[]) => {
+      const { votes, publicKey, nonce } = encryptVote(voter, allocation);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVoteBudgeted(offset, PROPOSAL_ID, votes[0], votes[1], votes[2], votes[3], publicKey, nonce)
+        .accountsPartial(queueAccounts(voter, offset, "cast_vote_budgeted"))
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      return offset;
+    };
+
+    try {
+      const { votes, publicKey, nonce } = encryptVote(voters[0], [1, 1, 0, 0]);
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(offset, PROPOSAL_ID, votes[0], votes[1], votes[2], votes[3], publicKey, nonce)
+        .accountsPartial({
+          ...queueAccounts(voters[0], offset, "cast_vote"),
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voters[0]])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("a plain cast_vote should not be accepted with encrypted budgets");
+    } catch (e) {
+      expect(e.toString()).to.include("BudgetedBallotRequired");
+    }
+
+    try {
+      await castVoteBudgeted(voters[2], [1, 1, 0, 0]);
+      expect.fail("a voter the authority never funded should not be able to vote");
+    } catch (e) {
+      expect(e.toString()).to.include("AccountNotInitialized");
+    }
+
+    //   Voter 0: [5, 2]  (25 + 4 = 29 credits, within their 30)
+    //   Voter 1: [2, 2]  (4 + 4 = 8 credits, over their 5: discarded)
+    for (const [voter, allocation] of [
+      [voters[0], [5, 2, 0, 0]],
+      [voters[1], [2, 2, 0, 0]],
+    ] as const) {
+      const offset = await castVoteBudgeted(voter, [...allocation]);
+      await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    }
+
+    while (true) {
+      const currentSlot = await provider.connection.getSlot("confirmed");
+      const currentTime = await provider.connection.getBlockTime(currentSlot);
+      if (currentTime && currentTime >= deadline.toNumber()) break;
+      await sleep(1000);
+    }
+
+    const resultsPromise = awaitEvent("resultsRevealedEvent");
+    const revealOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .revealResults(revealOffset, PROPOSAL_ID)
+      .accountsPartial({
+        authority: owner.publicKey,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          revealOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("reveal_results")).readUInt32LE()
+        ),
+      })
+      .rpc({ skipPreflight: true, commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      revealOffset,
+      program.programId,
+      "confirmed"
+    );
+
+    // Against the flat 10 credits the first ballot would have been
+    // discarded and the second counted.
+    const results = await resultsPromise;
+    expect(results.option0.toNumber()).to.equal(5);
+    expect(results.option1.toNumber()).to.equal(2);
+    expect(results.winner).to.equal(0);
+  });
+
+  it("admits only wallets holding enough of the gate mint", async () => {
+    const PROPOSAL_ID = 77;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const voters = [0, 1, 2].map(() => anchor.web3.Keypair.generate());
+    for (const voter of voters) {
+      const airdrop = await provider.connection.requestAirdrop(
+        voter.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop, "confirmed");
+    }
+
+    // The first voter holds 150, above the threshold of 100; the second
+    // holds 50 and the third none.
+    const mint = anchor.web3.Keypair.generate();
+    const setup = new anchor.web3.Transaction().add(
+      ...(await createMintInstructions(
+        provider.connection,
+        owner.publicKey,
+        mint.publicKey,
+        owner.publicKey,
+        0
+      ))
+    );
+    for (const [voter, amount] of [
+      [voters[0], 150],
+      [voters[1], 50],
+    ] as const) {
+      setup.add(
+        createAssociatedTokenAccountIdempotent(owner.publicKey, voter.publicKey, mint.publicKey),
+        mintToInstruction(mint.publicKey, voter.publicKey, owner.publicKey, amount)
+      );
+    }
+    await provider.sendAndConfirm(setup, [mint], { commitment: "confirmed" });
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 300
+    );
+    const createProposal = (computationOffset: anchor.BN) =>
+      program.methods
+        .createProposal(
+          computationOffset,
+          PROPOSAL_ID,
+          "Members-only budget vote",
+          ["Approve", "Reject"],
+          2,
+          deadline,
+          new anchor.BN(0),
+          new anchor.BN(10),
+          1,
+          { voters: {} },
+          new anchor.BN(0),
+          new anchor.BN(100),
+          { quadratic: {} },
+          new anchor.BN(deserializeLE(randomBytes(16)).toString())
+        )
+        .accountsPartial({
+          authority: owner.publicKey,
+          quorumMint: null,
+          gateMint: mint.publicKey,
+          computationAccount: getComputationAccAddress(
+            arciumEnv.arciumClusterOffset,
+            computationOffset
+          ),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+          ),
+        })
+        .rpc({ commitment: "confirmed" });
+
+    try {
+      await createProposal(new anchor.BN(randomBytes(8), "hex"));
+      expect.fail("token gating should need the admin's go-ahead");
+    } catch (e) {
+      expect(e.toString()).to.include("GatingModeNotAllowed");
+    }
+
+    // GatingMode::Open | GatingMode::Token
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b10001 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await createProposal(computationOffset);
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.gating).to.deep.equal({ token: {} });
+    expect(proposal.gateMint.toBase58()).to.equal(mint.publicKey.toBase58());
+    expect(proposal.gateThreshold.toNumber()).to.equal(100);
+
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const castVote = async (voter: anchor.web3.Keypair, voterTokenAccount: PublicKey | null) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const votes = cipher.encrypt([1, 1, 0, 0].map((v) => BigInt(v)), nonce).map((c) => Array.from(c));
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          votes[0],
+          votes[1],
+          votes[2],
+          votes[3],
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, offset),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount,
+          credential: null,
+          mixQueue: null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+      return offset;
+    };
+
+    try {
+      await castVote(voters[1], associatedTokenAddress(voters[1].publicKey, mint.publicKey));
+      expect.fail("a holder below the threshold should not be able to vote");
+    } catch (e) {
+      expect(e.toString()).to.include("GateBalanceTooLow");
+    }
+    for (const voterTokenAccount of [
+      null,
+      associatedTokenAddress(voters[0].publicKey, mint.publicKey),
+    ]) {
+      try {
+        await castVote(voters[2], voterTokenAccount);
+        expect.fail("a wallet should not vote without, or on another's, gate tokens");
+      } catch (e) {
+        expect(e.toString()).to.include("GateTokenAccountRequired");
+      }
+    }
+
+    const offset = await castVote(
+      voters[0],
+      associatedTokenAddress(voters[0].publicKey, mint.publicKey)
+    );
+    await awaitComputationFinalization(provider, offset, program.programId, "confirmed");
+    const voted = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(voted.voterCount).to.equal(1);
+  });
+
+  it("admits only NFTs of the gate collection", async () => {
+    const PROPOSAL_ID = 78;
+    const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
+
+    const voter = anchor.web3.Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      voter.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop, "confirmed");
+
+    // A one-of-one mint the voter holds; localnet has no Token Metadata
+    // program, so it has no metadata account.
+    const nft = anchor.web3.Keypair.generate();
+    const collection = anchor.web3.Keypair.generate().publicKey;
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        ...(await createMintInstructions(
+          provider.connection,
+          owner.publicKey,
+          nft.publicKey,
+          owner.publicKey,
+          0
+        )),
+        createAssociatedTokenAccountIdempotent(owner.publicKey, voter.publicKey, nft.publicKey),
+        mintToInstruction(nft.publicKey, voter.publicKey, owner.publicKey, 1)
+      ),
+      [nft],
+      { commitment: "confirmed" }
+    );
+
+    const slot = await provider.connection.getSlot("confirmed");
+    const blockTime = await provider.connection.getBlockTime(slot);
+    const deadline = new anchor.BN(
+      (blockTime || Math.floor(Date.now() / 1000)) + 300
+    );
+    const computationOffset = new anchor.BN(randomBytes(8), "hex");
+    await program.methods
+      .createProposal(
+        computationOffset,
+        PROPOSAL_ID,
+        "Holders' treasury vote",
+        ["Approve", "Reject"],
+        2,
+        deadline,
+        new anchor.BN(0),
+        new anchor.BN(10),
+        1,
+        { voters: {} },
+        new anchor.BN(0),
+        new anchor.BN(0),
+        { quadratic: {} },
+        new anchor.BN(deserializeLE(randomBytes(16)).toString())
+      )
+      .accountsPartial({
+        authority: owner.publicKey,
+        quorumMint: null,
+        gateMint: null,
+        computationAccount: getComputationAccAddress(
+          arciumEnv.arciumClusterOffset,
+          computationOffset
+        ),
+        clusterAccount,
+        mxeAccount: getMXEAccAddress(program.programId),
+        mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+        executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+        compDefAccount: getCompDefAccAddress(
+          program.programId,
+          Buffer.from(getCompDefAccOffset("init_tallies")).readUInt32LE()
+        ),
+      })
+      .rpc({ commitment: "confirmed" });
+    await awaitComputationFinalization(
+      provider,
+      computationOffset,
+      program.programId,
+      "confirmed"
+    );
+    const [proposalPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        owner.publicKey.toBuffer(),
+        Buffer.from(new Uint8Array(new Int32Array([PROPOSAL_ID]).buffer)),
+      ],
+      program.programId
+    );
+
+    const openCollectionGate = () =>
+      program.methods
+        .openCollectionGate(collection)
+        .accountsPartial({ authority: owner.publicKey, proposalAcc: proposalPDA, config: configPDA })
+        .rpc({ commitment: "confirmed" });
+    try {
+      await openCollectionGate();
+      expect.fail("collection gating should need the admin's go-ahead");
+    } catch (e) {
+      expect(e.toString()).to.include("GatingModeNotAllowed");
+    }
+
+    // GatingMode::Open | GatingMode::Collection
+    await program.methods
+      .updateConfig(configParams(owner.publicKey, { allowedGatingModes: 0b100001 }))
+      .accountsPartial({ admin: owner.publicKey, config: configPDA })
+      .rpc({ commitment: "confirmed" });
+    await openCollectionGate();
+    const proposal = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(proposal.gating).to.deep.equal({ collection: {} });
+    expect(proposal.gateMint.toBase58()).to.equal(collection.toBase58());
+    try {
+      await openCollectionGate();
+      expect.fail("a proposal should take one gate");
+    } catch (e) {
+      expect(e.toString()).to.include("ProposalAlreadyGated");
+    }
+
+    const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+    const [metadataPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), nft.publicKey.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    const [nftBallotPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nft_ballot"), proposalPDA.toBuffer(), nft.publicKey.toBuffer()],
+      program.programId
+    );
+    const mxePublicKey = await getMXEPublicKeyWithRetry(provider, program.programId);
+    const castVote = async (withNft: boolean) => {
+      const { privateKey, publicKey } = deriveEncryptionKey(voter, ENCRYPTION_KEY_MESSAGE);
+      const cipher = new RescueCipher(x25519.getSharedSecret(privateKey, mxePublicKey));
+      const nonce = randomBytes(16);
+      const votes = cipher.encrypt([1, 1, 0, 0].map((v) => BigInt(v)), nonce).map((c) => Array.from(c));
+      const offset = new anchor.BN(randomBytes(8), "hex");
+      await program.methods
+        .castVote(
+          offset,
+          PROPOSAL_ID,
+          votes[0],
+          votes[1],
+          votes[2],
+          votes[3],
+          Array.from(publicKey),
+          new anchor.BN(deserializeLE(nonce).toString())
+        )
+        .accountsPartial({
+          payer: voter.publicKey,
+          computationAccount: getComputationAccAddress(arciumEnv.arciumClusterOffset, offset),
+          clusterAccount,
+          mxeAccount: getMXEAccAddress(program.programId),
+          mempoolAccount: getMempoolAccAddress(arciumEnv.arciumClusterOffset),
+          executingPool: getExecutingPoolAccAddress(arciumEnv.arciumClusterOffset),
+          compDefAccount: getCompDefAccAddress(
+            program.programId,
+            Buffer.from(getCompDefAccOffset("cast_vote")).readUInt32LE()
+          ),
+          authority: owner.publicKey,
+          proposalAcc: proposalPDA,
+          voterTokenAccount: withNft ? associatedTokenAddress(voter.publicKey, nft.publicKey) : null,
+          credential: null,
+          mixQueue: null,
+          nftMetadata: withNft ? metadataPDA : null,
+          nftBallot: withNft ? nftBallotPDA : null,
+        })
+        .signers([voter])
+        .rpc({ commitment: "confirmed" });
+    };
+
+    try {
+      await castVote(false);
+      expect.fail("a wallet should not vote without an NFT");
+    } catch (e) {
+      expect(e.toString()).to.include("NftRequired");
+    }
+    // Without Token Metadata on localnet, no metadata account can prove
+    // membership.
+    try {
+      await castVote(true);
+      expect.fail("an NFT without verified metadata should not vote");
+    } catch (e) {
+      expect(e.toString()).to.include("InvalidNftMetadata");
+    }
+    const unvoted = await program.account.proposalAccount.fetch(proposalPDA);
+    expect(unvoted.voterCount).to.equal(0);
+  });
+
+  it("numbers errors in a range per subsystem", () => {
+    const code = (name: string) =>
+      program.idl.errors.find((error) => error.name.toLowerCase() === name.toLowerCase()).code;
+    const families: [number, string, string][] = [
+      [6000, "VotingPeriodEnded", "InvalidTransition"],
+      [6100, "InvalidAuthority", "VoterLimitReached"],
+      [6200, "AbortedComputation", "ComputationsInFlight"],
+      [6300, "GatingModeNotAllowed", "DuplicateOption"],
+      [6400, "InvalidAmount", "AmountOverflow"],
+    ];
+    for (const [first, head, tail] of families) {
+      expect(code(head)).to.equal(first);
+      expect(code(tail)).to.be.above(first).and.below(first + 100);
+    }
+    const codes = program.idl.errors.map((error) => error.code);
+    expect(new Set(codes).size).to.equal(codes.length);
+    expect(codes.every((c) => c >= 6000 && c < 6500)).to.equal(true);
+  });
+
+  it("matches the published ciphertext test vectors", () => {
+    const vectors = JSON.parse(
+      fs.readFileSync("crates/arcvote-vectors/vectors.json").toString()
+    );
+    const mxePublicKey = Buffer.from(vectors.mxe_public_key, "hex");
+
+    for (const ballot of vectors.cast_vote) {
+      const privateKey = Buffer.from(ballot.voter_secret_key, "hex");
+      expect(
+        Buffer.from(x25519.getPublicKey(privateKey)).toString("hex")
+      ).to.equal(ballot.voter_public_key);
+
+      const sharedSecret = x25519.getSharedSecret(privateKey, mxePublicKey);
+      expect(Buffer.from(sharedSecret).toString("hex")).to.equal(
+        ballot.shared_secret
+      );
+
+      const cipher = new RescueCipher(sharedSecret);
+      const ciphertexts = cipher.encrypt(
+        ballot.allocation.map((v: number) => BigInt(v)),
+        Buffer.from(ballot.nonce, "hex")
+      );
+      expect(ciphertexts.map((c) => Buffer.from(c).toString("hex"))).to.deep.equal(
+        ballot.ciphertexts
+      );
+    }
+  });
+});
+
+// ---- Helper: remaining accounts for cast_ballot_choices ----
+function ballotChoiceAccounts(
+  programId: PublicKey,
+  races: PublicKey[],
+  voter: PublicKey
+): anchor.web3.AccountMeta[] {
+  return races.flatMap((proposal) => [
+    { pubkey: proposal, isWritable: true, isSigner: false },
+    {
+      pubkey: PublicKey.findProgramAddressSync(
+        [Buffer.from("tally"), proposal.toBuffer()],
+        programId
+      )[0],
+      isWritable: true,
+      isSigner: false,
+    },
+    {
+      pubkey: PublicKey.findProgramAddressSync(
+        [Buffer.from("voter"), proposal.toBuffer(), voter.toBuffer()],
+        programId
+      )[0],
+      isWritable: true,
+      isSigner: false,
+    },
+  ]);
+}
+
+// ---- Helper: send one instruction as a v0 transaction through a fresh lookup table ----
+async function sendWithLookupTable(
+  provider: anchor.AnchorProvider,
+  authority: anchor.web3.Keypair,
+  payer: anchor.web3.Keypair,
+  ix: anchor.web3.TransactionInstruction
+): Promise<string> {
+  const connection = provider.connection;
+  const addresses = [
+    ...new Map(
+      ix.keys
+        .filter((meta) => !meta.isSigner)
+        .map((meta) => [meta.pubkey.toBase58(), meta.pubkey])
+    ).values(),
+    ix.programId,
+  ];
+  const [createIx, lookupTable] = anchor.web3.AddressLookupTableProgram.createLookupTable({
+    authority: authority.publicKey,
+    payer: authority.publicKey,
+    recentSlot: await connection.getSlot("finalized"),
+  });
+  await provider.sendAndConfirm(new anchor.web3.Transaction().add(createIx), [authority], {
+    commitment: "confirmed",
+  });
+  for (let i = 0; i < addresses.length; i += 20) {
+    const extendIx = anchor.web3.AddressLookupTableProgram.extendLookupTable({
+      lookupTable,
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      addresses: addresses.slice(i, i + 20),
+    });
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(extendIx), [authority], {
+      commitment: "confirmed",
+    });
+  }
+  // A table only serves lookups from the slot after its last extension.
+  const extendedAt = await connection.getSlot("confirmed");
+  while ((await connection.getSlot("confirmed")) <= extendedAt) {
+    await new Promise((resolve) => setTimeout(resolve, 200));
+  }
+
+  const table = (await connection.getAddressLookupTable(lookupTable)).value;
+  const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash("confirmed");
+  const message = new anchor.web3.TransactionMessage({
+    payerKey: payer.publicKey,
+    recentBlockhash: blockhash,
+    instructions: [
+      anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+      ix,
+    ],
+  }).compileToV0Message([table]);
+  const tx = new anchor.web3.VersionedTransaction(message);
+  tx.sign([payer]);
+  const sig = await connection.sendTransaction(tx, { skipPreflight: true });
+  await connection.confirmTransaction(
+    { signature: sig, blockhash, lastValidBlockHeight },
+    "confirmed"
+  );
+  const status = await connection.getTransaction(sig, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  if (status?.meta?.err) {
+    throw new Error(`transaction ${sig} failed: ${JSON.stringify(status.meta.err)}`);
+  }
+  return sig;
+}
 
 // ---- Helper: initialize a computation definition + upload circuit ----
 async function initCompDef(